@@ -1,5 +1,22 @@
+use std::{env, path::PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("proto/user.proto")?;
-    tonic_build::compile_protos("proto/item.proto")?;
+    // サーバーリフレクション用に全サービスのディスクリプタを書き出す
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("rust_webapi_descriptor.bin"))
+        .compile_protos(
+            &[
+                "proto/user.proto",
+                "proto/item.proto",
+                "proto/product.proto",
+                "proto/category.proto",
+                "proto/change_feed.proto",
+                "proto/google/rpc/status.proto",
+                "proto/google/rpc/error_details.proto",
+            ],
+            &["proto"],
+        )?;
     Ok(())
 }
@@ -15,12 +15,14 @@ CREATE TABLE users (
 CREATE TABLE categories (
     id VARCHAR(255) PRIMARY KEY,
     name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL,
     description TEXT,
     parent_id VARCHAR(255),
     sort_order INTEGER NOT NULL DEFAULT 0,
     is_active BOOLEAN NOT NULL DEFAULT true,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    version BIGINT NOT NULL DEFAULT 1,
     FOREIGN KEY (parent_id) REFERENCES categories(id) ON DELETE CASCADE,
     CONSTRAINT check_sort_order_non_negative CHECK (sort_order >= 0),
     CONSTRAINT check_name_not_empty CHECK (LENGTH(TRIM(name)) > 0),
@@ -32,6 +34,69 @@ CREATE INDEX idx_categories_parent_id ON categories(parent_id);
 CREATE INDEX idx_categories_sort_order ON categories(sort_order);
 CREATE INDEX idx_categories_is_active ON categories(is_active);
 CREATE INDEX idx_categories_parent_sort ON categories(parent_id, sort_order);
+-- Slugs are unique among siblings (root categories share the '' parent)
+CREATE UNIQUE INDEX idx_categories_parent_slug ON categories ((COALESCE(parent_id, '')), slug);
+
+-- Previous slugs kept so that old category URLs can be redirected.
+-- parent_id is the parent at the time and is not a foreign key because it may have been deleted.
+CREATE TABLE category_slug_redirects (
+    parent_id VARCHAR(255),
+    slug VARCHAR(100) NOT NULL,
+    category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    -- Set when the slug belonged to a category merged into category_id; the merged
+    -- category's renamed children keep their old slugs under this id
+    merged_from VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_category_slug_redirects_parent_slug
+    ON category_slug_redirects ((COALESCE(parent_id, '')), slug);
+
+-- Closure table holding every ancestor/descendant pair (including each category itself at depth 0)
+CREATE TABLE category_closure (
+    ancestor_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    descendant_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    depth INTEGER NOT NULL CHECK (depth >= 0),
+    PRIMARY KEY (ancestor_id, descendant_id)
+);
+
+CREATE INDEX idx_category_closure_descendant ON category_closure(descendant_id, depth);
+
+-- Backfill from parent_id for categories created before the closure table existed
+INSERT INTO category_closure (ancestor_id, descendant_id, depth)
+WITH RECURSIVE paths AS (
+    SELECT id AS ancestor_id, id AS descendant_id, 0 AS depth FROM categories
+    UNION ALL
+    SELECT p.ancestor_id, c.id, p.depth + 1
+    FROM paths p
+    JOIN categories c ON c.parent_id = p.descendant_id
+)
+SELECT ancestor_id, descendant_id, depth FROM paths
+ON CONFLICT DO NOTHING;
+
+-- Product attribute definitions declared by a category and inherited by its descendants
+-- (a closer category's definition overrides an ancestor's one with the same name)
+CREATE TABLE category_attribute_definitions (
+    category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    attribute_type VARCHAR(20) NOT NULL,
+    allowed_values TEXT[] NOT NULL DEFAULT '{}',
+    required BOOLEAN NOT NULL DEFAULT false,
+    unit VARCHAR(50),
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (category_id, name),
+    CONSTRAINT check_attribute_type CHECK (attribute_type IN ('string', 'number', 'enum', 'boolean'))
+);
+
+-- Category name/description per locale (the categories row holds the default locale)
+CREATE TABLE category_translations (
+    category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    locale VARCHAR(35) NOT NULL,
+    name VARCHAR(100),
+    description TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (category_id, locale)
+);
 
 -- Trigger to automatically update updated_at timestamp
 CREATE OR REPLACE FUNCTION update_updated_at_column()
@@ -52,6 +117,7 @@ CREATE TRIGGER update_categories_updated_at
 CREATE TABLE products (
     id VARCHAR(255) PRIMARY KEY,
     name VARCHAR(200) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE,
     description TEXT,
     sku VARCHAR(50) NOT NULL UNIQUE,
     brand VARCHAR(100),
@@ -66,6 +132,7 @@ CREATE TABLE products (
     shipping_fee DECIMAL(10,2) DEFAULT 0,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    version BIGINT NOT NULL DEFAULT 1,
     FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
     CONSTRAINT check_name_not_empty CHECK (LENGTH(TRIM(name)) > 0),
     CONSTRAINT check_sku_not_empty CHECK (LENGTH(TRIM(sku)) > 0),
@@ -79,6 +146,15 @@ CREATE TABLE products (
     CONSTRAINT check_shipping_fee_non_negative CHECK (shipping_fee >= 0)
 );
 
+-- Previous product slugs kept so that old product URLs can be redirected
+CREATE TABLE product_slug_redirects (
+    slug VARCHAR(100) PRIMARY KEY,
+    product_id VARCHAR(255) NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_product_slug_redirects_product_id ON product_slug_redirects(product_id);
+
 CREATE TABLE product_prices (
     id BIGSERIAL PRIMARY KEY,
     product_id VARCHAR(255) NOT NULL,
@@ -155,6 +231,28 @@ CREATE TABLE product_attributes (
     UNIQUE(product_id, attribute_name)
 );
 
+-- Product name/description per locale (the products row holds the default locale)
+CREATE TABLE product_translations (
+    product_id VARCHAR(255) NOT NULL,
+    locale VARCHAR(35) NOT NULL,
+    name VARCHAR(200),
+    description TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (product_id, locale),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+-- Image alt text per locale, removed together with the product translation or the image
+CREATE TABLE product_image_translations (
+    image_id VARCHAR(255) NOT NULL,
+    product_id VARCHAR(255) NOT NULL,
+    locale VARCHAR(35) NOT NULL,
+    alt_text TEXT NOT NULL,
+    PRIMARY KEY (image_id, locale),
+    FOREIGN KEY (image_id) REFERENCES product_images(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id, locale) REFERENCES product_translations(product_id, locale) ON DELETE CASCADE
+);
+
 CREATE TABLE product_history (
     id BIGSERIAL PRIMARY KEY,
     product_id VARCHAR(255) NOT NULL,
@@ -167,6 +265,167 @@ CREATE TABLE product_history (
     FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
 );
 
+CREATE TABLE product_options (
+    id BIGSERIAL PRIMARY KEY,
+    product_id VARCHAR(255) NOT NULL,
+    option_name VARCHAR(100) NOT NULL,
+    option_values TEXT[] NOT NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    CONSTRAINT check_option_name_not_empty CHECK (LENGTH(TRIM(option_name)) > 0),
+    UNIQUE(product_id, option_name)
+);
+
+CREATE TABLE product_variants (
+    id VARCHAR(255) PRIMARY KEY,
+    product_id VARCHAR(255) NOT NULL,
+    sku VARCHAR(50) NOT NULL UNIQUE,
+    combination_key TEXT NOT NULL,
+    selling_price DECIMAL(12,2) NOT NULL,
+    list_price DECIMAL(12,2),
+    discount_price DECIMAL(12,2),
+    currency VARCHAR(3) NOT NULL DEFAULT 'JPY',
+    tax_included BOOLEAN NOT NULL DEFAULT true,
+    effective_from TIMESTAMP WITH TIME ZONE,
+    effective_until TIMESTAMP WITH TIME ZONE,
+    quantity INTEGER NOT NULL DEFAULT 0,
+    reserved_quantity INTEGER NOT NULL DEFAULT 0,
+    alert_threshold INTEGER,
+    track_inventory BOOLEAN NOT NULL DEFAULT true,
+    allow_backorder BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    CONSTRAINT check_variant_selling_price_positive CHECK (selling_price > 0),
+    CONSTRAINT check_variant_quantity_non_negative CHECK (quantity >= 0),
+    CONSTRAINT check_variant_reserved_not_exceeds_quantity CHECK (reserved_quantity <= quantity),
+    CONSTRAINT product_variants_combination_key UNIQUE(product_id, combination_key)
+);
+
+CREATE TABLE product_variant_options (
+    id BIGSERIAL PRIMARY KEY,
+    variant_id VARCHAR(255) NOT NULL,
+    option_name VARCHAR(100) NOT NULL,
+    option_value TEXT NOT NULL,
+    FOREIGN KEY (variant_id) REFERENCES product_variants(id) ON DELETE CASCADE,
+    UNIQUE(variant_id, option_name)
+);
+
+CREATE TABLE product_bundles (
+    product_id VARCHAR(255) PRIMARY KEY,
+    pricing_type VARCHAR(20) NOT NULL DEFAULT 'fixed',
+    discount_rate DECIMAL(5,4),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    CONSTRAINT check_bundle_pricing_type CHECK (pricing_type IN ('fixed', 'discounted_sum')),
+    CONSTRAINT check_bundle_discount_rate CHECK (
+        discount_rate IS NULL OR (discount_rate >= 0 AND discount_rate < 1)
+    )
+);
+
+CREATE TABLE product_bundle_components (
+    id BIGSERIAL PRIMARY KEY,
+    bundle_id VARCHAR(255) NOT NULL,
+    component_id VARCHAR(255) NOT NULL,
+    quantity INTEGER NOT NULL,
+    FOREIGN KEY (bundle_id) REFERENCES product_bundles(product_id) ON DELETE CASCADE,
+    -- Components referenced by a bundle cannot be physically deleted
+    CONSTRAINT product_bundle_components_component_id_fkey
+        FOREIGN KEY (component_id) REFERENCES products(id) ON DELETE RESTRICT,
+    CONSTRAINT check_bundle_component_quantity_positive CHECK (quantity > 0),
+    CONSTRAINT check_bundle_component_not_self CHECK (bundle_id != component_id),
+    UNIQUE(bundle_id, component_id)
+);
+
+CREATE TABLE product_status_schedules (
+    id VARCHAR(255) PRIMARY KEY,
+    product_id VARCHAR(255) NOT NULL,
+    target_status VARCHAR(50) NOT NULL,
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    state VARCHAR(20) NOT NULL DEFAULT 'pending',
+    reason TEXT,
+    created_by VARCHAR(255),
+    -- Set when an executor claims the schedule; stale claims are retried
+    claimed_at TIMESTAMP WITH TIME ZONE,
+    executed_at TIMESTAMP WITH TIME ZONE,
+    failure_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    CONSTRAINT check_schedule_target_status CHECK (target_status IN ('Active', 'Inactive', 'Discontinued')),
+    CONSTRAINT check_schedule_state CHECK (state IN ('pending', 'processing', 'applied', 'cancelled', 'failed'))
+);
+
+-- 呼び出し元（scope）と Idempotency-Key ごとのリクエスト指紋と保存済みレスポンス（status_code が NULL の間は処理中）
+CREATE TABLE idempotency_keys (
+    scope VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL,
+    request_path TEXT NOT NULL,
+    status_code INTEGER,
+    response_headers TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (scope, idempotency_key)
+);
+
+-- ドメインイベントの outbox（業務データと同じトランザクションで書き込み、ディスパッチャーが配信する）
+CREATE TABLE outbox_events (
+    id BIGSERIAL PRIMARY KEY,
+    aggregate_type VARCHAR(50) NOT NULL,
+    aggregate_id VARCHAR(255) NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Set while a dispatcher delivers the event; stale claims are retried
+    claimed_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    published_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_outbox_events_pending ON outbox_events(aggregate_type, aggregate_id, id) WHERE published_at IS NULL;
+CREATE INDEX idx_outbox_events_published_at ON outbox_events(published_at) WHERE published_at IS NOT NULL;
+
+-- Webhook subscriptions receiving domain events from the outbox dispatcher
+CREATE TABLE webhook_subscriptions (
+    id VARCHAR(255) PRIMARY KEY,
+    url TEXT NOT NULL,
+    -- Empty means every event type
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    secret VARCHAR(255) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    disabled_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id VARCHAR(255) NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    status_code INTEGER,
+    latency_ms BIGINT,
+    last_error TEXT,
+    succeeded BOOLEAN NOT NULL DEFAULT FALSE,
+    next_attempt_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (subscription_id, event_id)
+);
+
+CREATE INDEX idx_webhook_deliveries_subscription ON webhook_deliveries(subscription_id, last_attempt_at DESC);
+CREATE INDEX idx_webhook_deliveries_retry ON webhook_deliveries(next_attempt_at) WHERE NOT succeeded;
+
 -- Indexes for better performance
 CREATE INDEX idx_products_sku ON products(sku);
 CREATE INDEX idx_products_category_id ON products(category_id);
@@ -194,6 +453,22 @@ CREATE INDEX idx_product_history_product_id ON product_history(product_id);
 CREATE INDEX idx_product_history_changed_at ON product_history(changed_at);
 CREATE INDEX idx_product_history_field_name ON product_history(field_name);
 
+CREATE INDEX idx_product_options_product_id ON product_options(product_id);
+
+CREATE INDEX idx_product_variants_product_id ON product_variants(product_id);
+CREATE INDEX idx_product_variants_selling_price ON product_variants(selling_price);
+
+CREATE INDEX idx_product_variant_options_variant_id ON product_variant_options(variant_id);
+CREATE INDEX idx_product_variant_options_name_value ON product_variant_options(option_name, option_value);
+
+CREATE INDEX idx_product_bundle_components_bundle_id ON product_bundle_components(bundle_id);
+CREATE INDEX idx_product_bundle_components_component_id ON product_bundle_components(component_id);
+
+CREATE INDEX idx_product_status_schedules_product_id ON product_status_schedules(product_id);
+CREATE INDEX idx_product_status_schedules_due ON product_status_schedules(state, scheduled_at);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
+
 -- Additional triggers for updated_at columns
 CREATE TRIGGER update_products_updated_at 
     BEFORE UPDATE ON products 
@@ -220,6 +495,21 @@ CREATE TRIGGER update_product_attributes_updated_at
     FOR EACH ROW 
     EXECUTE FUNCTION update_updated_at_column();
 
+CREATE TRIGGER update_product_variants_updated_at 
+    BEFORE UPDATE ON product_variants 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_product_bundles_updated_at 
+    BEFORE UPDATE ON product_bundles 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_product_status_schedules_updated_at 
+    BEFORE UPDATE ON product_status_schedules 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
 -- Constraint to ensure only one main image per product
 CREATE UNIQUE INDEX idx_product_images_main_unique 
     ON product_images(product_id) 
@@ -134,7 +134,7 @@ export default function (data) {
                 // Validate deletion
                 group('Validate Deletion', () => {
                     const response = http.get(
-                        `${API_BASE_URL}/api/products/${testItemId}/deletion-check`,
+                        `${API_BASE_URL}/api/items/${testItemId}/deletion-check`,
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'validate_deletion' })
                     );
                     
@@ -146,7 +146,7 @@ export default function (data) {
                 // Logical delete
                 group('Logical Delete', () => {
                     const response = http.del(
-                        `${API_BASE_URL}/api/products/${testItemId}`,
+                        `${API_BASE_URL}/api/items/${testItemId}/logical`,
                         null,
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'logical_delete' })
                     );
@@ -159,7 +159,7 @@ export default function (data) {
                 // Get deleted items
                 group('Get Deleted Items', () => {
                     const response = http.get(
-                        `${API_BASE_URL}/api/products/deleted`,
+                        `${API_BASE_URL}/api/items/deleted`,
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'get_deleted' })
                     );
                     
@@ -171,7 +171,7 @@ export default function (data) {
                 // Restore item
                 group('Restore Item', () => {
                     const response = http.post(
-                        `${API_BASE_URL}/api/products/${testItemId}/restore`,
+                        `${API_BASE_URL}/api/items/${testItemId}/restore`,
                         null,
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'restore' })
                     );
@@ -184,7 +184,7 @@ export default function (data) {
                 // Physical delete
                 group('Physical Delete', () => {
                     const response = http.del(
-                        `${API_BASE_URL}/api/products/${testItemId}/permanent`,
+                        `${API_BASE_URL}/api/items/${testItemId}/permanent`,
                         null,
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'physical_delete' })
                     );
@@ -222,7 +222,7 @@ export default function (data) {
                     };
                     
                     const response = http.del(
-                        `${API_BASE_URL}/api/products/batch`,
+                        `${API_BASE_URL}/api/items/batch`,
                         JSON.stringify(batchRequest),
                         getAuthHeadersWithTags(token, { endpoint: 'items', operation: 'batch_delete' })
                     );
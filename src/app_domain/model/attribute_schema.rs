@@ -0,0 +1,562 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use super::product_filter::{AttributeCondition, AttributeFilter};
+
+/// 属性名の最大長（product_attributes.attribute_name に合わせる）
+pub const MAX_ATTRIBUTE_NAME_LENGTH: usize = 100;
+
+/// カテゴリで定義する商品属性の型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Number,
+    Enum,
+    Boolean,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Enum => "enum",
+            AttributeType::Boolean => "boolean",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            AttributeType::String,
+            AttributeType::Number,
+            AttributeType::Enum,
+            AttributeType::Boolean,
+        ]
+        .into_iter()
+        .find(|candidate| candidate.as_str() == value)
+    }
+}
+
+impl std::fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// カテゴリが商品に求める属性の定義
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub name: String,
+    pub attribute_type: AttributeType,
+    /// enum 型で選べる値（他の型では空）
+    pub allowed_values: Vec<String>,
+    pub required: bool,
+    /// number 型の値の単位（"cm"、"inch" など）
+    pub unit: Option<String>,
+}
+
+impl AttributeDefinition {
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_ATTRIBUTE_NAME_LENGTH {
+            return Err(format!(
+                "属性名は1〜{}文字で指定してください",
+                MAX_ATTRIBUTE_NAME_LENGTH
+            ));
+        }
+        if name.contains([',', ':', '<', '>', '|']) {
+            return Err(format!("属性名「{}」に , : < > | は使用できません", name));
+        }
+
+        match self.attribute_type {
+            AttributeType::Enum => {
+                if self.allowed_values.is_empty() {
+                    return Err(format!(
+                        "enum 型の属性「{}」には allowed_values が必要です",
+                        name
+                    ));
+                }
+                let mut seen = HashSet::new();
+                for value in &self.allowed_values {
+                    if value.trim().is_empty() || !seen.insert(value.trim().to_lowercase()) {
+                        return Err(format!(
+                            "属性「{}」の allowed_values に空または重複した値があります",
+                            name
+                        ));
+                    }
+                }
+            }
+            _ if !self.allowed_values.is_empty() => {
+                return Err(format!(
+                    "allowed_values は enum 型の属性にのみ指定できます（「{}」）",
+                    name
+                ));
+            }
+            _ => (),
+        }
+
+        if self.unit.is_some() && self.attribute_type != AttributeType::Number {
+            return Err(format!(
+                "unit は number 型の属性にのみ指定できます（「{}」）",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    /// カテゴリ1件分の定義を検証し、前後の空白を除いた定義を返す（属性名は大文字小文字を区別せず一意）
+    pub fn validate_all(definitions: Vec<AttributeDefinition>) -> Result<Vec<Self>, String> {
+        let mut seen = HashSet::new();
+        definitions
+            .into_iter()
+            .map(|definition| {
+                let definition = AttributeDefinition {
+                    name: definition.name.trim().to_string(),
+                    allowed_values: definition
+                        .allowed_values
+                        .iter()
+                        .map(|value| value.trim().to_string())
+                        .collect(),
+                    unit: definition
+                        .unit
+                        .map(|unit| unit.trim().to_string())
+                        .filter(|unit| !unit.is_empty()),
+                    ..definition
+                };
+                definition.validate()?;
+                if !seen.insert(definition.name.to_lowercase()) {
+                    return Err(format!("属性「{}」が重複しています", definition.name));
+                }
+                Ok(definition)
+            })
+            .collect()
+    }
+
+    /// 値を型に合わせて正規化する
+    ///
+    /// number は末尾の単位を除いて 10 進数に、boolean は "true"/"false" に、
+    /// enum は大文字小文字を区別せずに一致した選択肢の表記にそろえる。
+    pub fn normalize_value(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self.attribute_type {
+            AttributeType::String => Ok(value.to_string()),
+            AttributeType::Number => {
+                let number = self
+                    .unit
+                    .as_deref()
+                    .and_then(|unit| value.strip_suffix(unit))
+                    .unwrap_or(value)
+                    .trim();
+                Decimal::from_str(number)
+                    .map(|number| number.normalize().to_string())
+                    .map_err(|_| format!("'{}' is not a number", value))
+            }
+            AttributeType::Enum => self
+                .allowed_values
+                .iter()
+                .find(|allowed| allowed.eq_ignore_ascii_case(value))
+                .cloned()
+                .ok_or_else(|| {
+                    format!(
+                        "'{}' is not one of {}",
+                        value,
+                        self.allowed_values.join(", ")
+                    )
+                }),
+            AttributeType::Boolean => match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok("true".to_string()),
+                "false" | "no" | "0" => Ok("false".to_string()),
+                _ => Err(format!("'{}' is not a boolean", value)),
+            },
+        }
+    }
+}
+
+/// 属性値が定義に合わない理由
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeViolation {
+    pub attribute: String,
+    pub reason: String,
+}
+
+impl AttributeViolation {
+    fn new(attribute: &str, reason: impl Into<String>) -> Self {
+        Self {
+            attribute: attribute.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for AttributeViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.attribute, self.reason)
+    }
+}
+
+/// 商品のカテゴリに適用される属性定義（祖先カテゴリから継承したものを含む）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttributeSchema {
+    /// 定義したカテゴリの ID と定義
+    attributes: Vec<(String, AttributeDefinition)>,
+}
+
+impl AttributeSchema {
+    /// ルートに近いカテゴリから順に並んだ定義を継承する
+    ///
+    /// 同じ名前（大文字小文字を区別しない）の定義は近いカテゴリのもので上書きし、
+    /// 並び順は最初に定義した祖先の位置を保つ。
+    pub fn inherit(definitions: impl IntoIterator<Item = (String, AttributeDefinition)>) -> Self {
+        let mut attributes: Vec<(String, AttributeDefinition)> = Vec::new();
+        for (category_id, definition) in definitions {
+            match attributes
+                .iter_mut()
+                .find(|(_, existing)| existing.name.eq_ignore_ascii_case(&definition.name))
+            {
+                Some(existing) => *existing = (category_id, definition),
+                None => attributes.push((category_id, definition)),
+            }
+        }
+        Self { attributes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    /// 定義したカテゴリの ID と定義を順に返す
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &AttributeDefinition)> {
+        self.attributes
+            .iter()
+            .map(|(category_id, definition)| (category_id.as_str(), definition))
+    }
+
+    /// 大文字小文字を区別せずに定義を探す
+    pub fn find(&self, name: &str) -> Option<&AttributeDefinition> {
+        let name = name.trim();
+        self.attributes
+            .iter()
+            .map(|(_, definition)| definition)
+            .find(|definition| definition.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 商品の属性を検証し、属性名を定義の表記に、値を型に合わせて正規化する
+    ///
+    /// 定義がなければ従来どおり自由な属性を受け付ける。定義がある場合は未定義の属性・
+    /// 型に合わない値・必須属性の欠落をまとめて返す。任意の属性の空の値は削除として扱う。
+    pub fn validate(
+        &self,
+        attributes: HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, Vec<AttributeViolation>> {
+        if self.is_empty() {
+            return Ok(attributes);
+        }
+
+        let mut entries: Vec<(String, String)> = attributes.into_iter().collect();
+        entries.sort();
+
+        let mut normalized = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut violations = Vec::new();
+        for (name, value) in entries {
+            let Some(definition) = self.find(&name) else {
+                violations.push(AttributeViolation::new(
+                    name.trim(),
+                    "is not defined for this category",
+                ));
+                continue;
+            };
+            if !seen.insert(definition.name.as_str()) {
+                violations.push(AttributeViolation::new(
+                    &definition.name,
+                    "is specified more than once",
+                ));
+                continue;
+            }
+            if value.trim().is_empty() {
+                continue;
+            }
+            match definition.normalize_value(&value) {
+                Ok(value) => {
+                    normalized.insert(definition.name.clone(), value);
+                }
+                Err(reason) => violations.push(AttributeViolation::new(&definition.name, reason)),
+            }
+        }
+
+        for (_, definition) in &self.attributes {
+            let reported = violations
+                .iter()
+                .any(|violation| violation.attribute == definition.name);
+            if definition.required && !normalized.contains_key(&definition.name) && !reported {
+                violations.push(AttributeViolation::new(&definition.name, "is required"));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(normalized)
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// 検索の属性条件を定義に合わせる（属性名と値を正規化し、範囲指定は number 型に限る）
+    ///
+    /// 定義がなければ条件をそのまま返す。
+    pub fn resolve_filters(
+        &self,
+        filters: Vec<AttributeFilter>,
+    ) -> Result<Vec<AttributeFilter>, String> {
+        if self.is_empty() {
+            return Ok(filters);
+        }
+
+        filters
+            .into_iter()
+            .map(|filter| {
+                let definition = self.find(&filter.name).ok_or_else(|| {
+                    format!(
+                        "attribute '{}' is not defined for this category",
+                        filter.name
+                    )
+                })?;
+                let condition = match filter.condition {
+                    AttributeCondition::OneOf(values) => AttributeCondition::OneOf(
+                        values
+                            .iter()
+                            .map(|value| {
+                                definition
+                                    .normalize_value(value)
+                                    .map_err(|reason| format!("{}: {}", definition.name, reason))
+                            })
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    range @ AttributeCondition::Range { .. } => {
+                        if definition.attribute_type != AttributeType::Number {
+                            return Err(format!(
+                                "attribute '{}' is not a number and cannot be filtered by range",
+                                definition.name
+                            ));
+                        }
+                        range
+                    }
+                };
+                Ok(AttributeFilter {
+                    name: definition.name.clone(),
+                    condition,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(name: &str, attribute_type: AttributeType) -> AttributeDefinition {
+        AttributeDefinition {
+            name: name.to_string(),
+            attribute_type,
+            allowed_values: Vec::new(),
+            required: false,
+            unit: None,
+        }
+    }
+
+    fn schema() -> AttributeSchema {
+        AttributeSchema::inherit([
+            (
+                "cat_root".to_string(),
+                AttributeDefinition {
+                    allowed_values: vec!["Red".to_string(), "Blue".to_string()],
+                    required: true,
+                    ..definition("color", AttributeType::Enum)
+                },
+            ),
+            (
+                "cat_root".to_string(),
+                definition("material", AttributeType::String),
+            ),
+            (
+                "cat_child".to_string(),
+                AttributeDefinition {
+                    unit: Some("inch".to_string()),
+                    ..definition("screen_size", AttributeType::Number)
+                },
+            ),
+            (
+                "cat_child".to_string(),
+                definition("wireless", AttributeType::Boolean),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_attribute_definition_validation() {
+        assert!(AttributeDefinition::validate_all(vec![
+            definition("color", AttributeType::String),
+            definition("Color", AttributeType::String),
+        ])
+        .is_err());
+        assert!(definition("color", AttributeType::Enum).validate().is_err());
+        assert!(AttributeDefinition {
+            allowed_values: vec!["red".to_string()],
+            ..definition("color", AttributeType::String)
+        }
+        .validate()
+        .is_err());
+        assert!(AttributeDefinition {
+            unit: Some("cm".to_string()),
+            ..definition("color", AttributeType::String)
+        }
+        .validate()
+        .is_err());
+        assert!(definition("a:b", AttributeType::String).validate().is_err());
+
+        let trimmed = AttributeDefinition::validate_all(vec![AttributeDefinition {
+            unit: Some(" ".to_string()),
+            ..definition(" width ", AttributeType::Number)
+        }])
+        .unwrap();
+        assert_eq!(trimmed[0].name, "width");
+        assert_eq!(trimmed[0].unit, None);
+    }
+
+    #[test]
+    fn test_schema_inheritance_overrides_by_name() {
+        let schema = AttributeSchema::inherit([
+            (
+                "cat_root".to_string(),
+                definition("color", AttributeType::String),
+            ),
+            (
+                "cat_root".to_string(),
+                definition("weight", AttributeType::Number),
+            ),
+            (
+                "cat_child".to_string(),
+                AttributeDefinition {
+                    allowed_values: vec!["red".to_string()],
+                    ..definition("Color", AttributeType::Enum)
+                },
+            ),
+        ]);
+
+        let attributes: Vec<(&str, &str)> = schema
+            .iter()
+            .map(|(category_id, definition)| (category_id, definition.name.as_str()))
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![("cat_child", "Color"), ("cat_root", "weight")]
+        );
+        assert_eq!(
+            schema.find("COLOR").unwrap().attribute_type,
+            AttributeType::Enum
+        );
+    }
+
+    #[test]
+    fn test_schema_normalizes_names_and_values() {
+        let attributes = HashMap::from([
+            ("Colour".to_string(), "red".to_string()),
+            ("COLOR".to_string(), "red".to_string()),
+            ("Screen_Size".to_string(), "13.30 inch".to_string()),
+            ("wireless".to_string(), "Yes".to_string()),
+            ("material".to_string(), " ".to_string()),
+        ]);
+        let violations = schema().validate(attributes.clone()).unwrap_err();
+        assert_eq!(
+            violations,
+            vec![AttributeViolation::new(
+                "Colour",
+                "is not defined for this category"
+            )]
+        );
+
+        let mut attributes = attributes;
+        attributes.remove("Colour");
+        let normalized = schema().validate(attributes).unwrap();
+        assert_eq!(
+            normalized,
+            HashMap::from([
+                ("color".to_string(), "Red".to_string()),
+                ("screen_size".to_string(), "13.3".to_string()),
+                ("wireless".to_string(), "true".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_schema_reports_type_errors_and_missing_required() {
+        let violations = schema()
+            .validate(HashMap::from([
+                ("screen_size".to_string(), "large".to_string()),
+                ("wireless".to_string(), "maybe".to_string()),
+            ]))
+            .unwrap_err();
+        let attributes: Vec<&str> = violations.iter().map(|v| v.attribute.as_str()).collect();
+        assert_eq!(attributes, vec!["screen_size", "wireless", "color"]);
+        assert_eq!(violations[2].reason, "is required");
+
+        let violations = schema()
+            .validate(HashMap::from([
+                ("color".to_string(), "green".to_string()),
+                ("Color".to_string(), "red".to_string()),
+            ]))
+            .unwrap_err();
+        assert_eq!(
+            violations,
+            vec![AttributeViolation::new(
+                "color",
+                "is specified more than once"
+            )]
+        );
+
+        // 定義がなければ自由な属性を受け付ける
+        let free = HashMap::from([("anything".to_string(), "goes".to_string())]);
+        assert_eq!(AttributeSchema::default().validate(free.clone()), Ok(free));
+    }
+
+    #[test]
+    fn test_schema_resolves_search_filters() {
+        let filters = vec![
+            AttributeFilter {
+                name: "COLOR".to_string(),
+                condition: AttributeCondition::OneOf(vec!["blue".to_string()]),
+            },
+            AttributeFilter {
+                name: "screen_size".to_string(),
+                condition: AttributeCondition::Range {
+                    min: Some(Decimal::from(13)),
+                    max: None,
+                },
+            },
+        ];
+        let resolved = schema().resolve_filters(filters).unwrap();
+        assert_eq!(resolved[0].name, "color");
+        assert_eq!(
+            resolved[0].condition,
+            AttributeCondition::OneOf(vec!["Blue".to_string()])
+        );
+
+        let range_on_enum = vec![AttributeFilter {
+            name: "color".to_string(),
+            condition: AttributeCondition::Range {
+                min: None,
+                max: Some(Decimal::from(1)),
+            },
+        }];
+        assert!(schema().resolve_filters(range_on_enum).is_err());
+
+        let unknown = vec![AttributeFilter {
+            name: "colour".to_string(),
+            condition: AttributeCondition::OneOf(vec!["red".to_string()]),
+        }];
+        assert!(schema().resolve_filters(unknown).is_err());
+    }
+}
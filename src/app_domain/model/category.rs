@@ -1,16 +1,22 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::slug;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Category {
     pub id: String,
     pub name: String,
+    /// URL 用のスラッグ（同じ親の下で一意）
+    pub slug: String,
     pub description: Option<String>,
     pub parent_id: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 楽観的排他制御用のバージョン（更新のたびに加算）
+    pub version: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,21 +35,51 @@ impl CategoryPath {
     //     self.depth <= 5
     // }
 
+    #[allow(dead_code)]
     pub fn contains(&self, category_id: &str) -> bool {
         self.path.contains(&category_id.to_string())
     }
 }
 
+/// 旧スラッグから見つかったカテゴリ
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryRedirect {
+    pub category: Category,
+    /// 統合元の旧スラッグだった場合の統合元 ID。統合時に連番を付けた子の旧スラッグは
+    /// この ID を親として残っている
+    pub merged_from: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CategoryTree {
     pub id: String,
     pub name: String,
+    pub slug: String,
     pub description: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
+    pub product_count: ProductCount,
     pub children: Vec<CategoryTree>,
 }
 
+/// カテゴリに紐づく商品数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductCount {
+    /// カテゴリ直下の商品数
+    pub direct: i64,
+    /// 子孫カテゴリ（非アクティブを含む）の商品も含めた商品数
+    pub total: i64,
+}
+
+/// 商品が紐づくカテゴリを削除するときの商品の扱い
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProductDisposition {
+    /// 指定したカテゴリへ付け替える
+    Reassign(String),
+    /// 非アクティブにしてカテゴリから外す（販売終了の商品はステータスを変えない）
+    Deactivate,
+}
+
 impl Category {
     pub fn new(
         id: String,
@@ -53,15 +89,19 @@ impl Category {
         sort_order: i32,
     ) -> Self {
         let now = Utc::now();
+        // 名前から生成できない場合（漢字を含むなど）は空のままにし、作成時にスラッグの指定を求める
+        let slug = slug::slugify(&name).unwrap_or_default();
         Self {
             id,
             name,
+            slug,
             description,
             parent_id,
             sort_order,
             is_active: true,
             created_at: now,
             updated_at: now,
+            version: 1,
         }
     }
 
@@ -90,6 +130,7 @@ impl Category {
 
     pub fn validate(&self) -> Result<(), CategoryError> {
         self.validate_name()?;
+        Self::validate_slug(&self.slug)?;
         self.validate_sort_order()?;
         Ok(())
     }
@@ -110,6 +151,23 @@ impl Category {
         Ok(())
     }
 
+    pub fn update_slug(&mut self, slug: String) -> Result<(), CategoryError> {
+        Self::validate_slug(&slug)?;
+        self.slug = slug;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn validate_slug(slug: &str) -> Result<(), CategoryError> {
+        if !slug::is_valid_slug(slug) {
+            return Err(CategoryError::InvalidSlug(format!(
+                "スラッグは英小文字・数字をハイフンで区切った{}文字以下の文字列である必要があります",
+                slug::MAX_SLUG_LENGTH
+            )));
+        }
+        Ok(())
+    }
+
     pub fn update_description(&mut self, description: Option<String>) {
         self.description = description;
         self.updated_at = Utc::now();
@@ -135,6 +193,19 @@ impl Category {
         self.is_active = true;
         self.updated_at = Utc::now();
     }
+
+    /// 呼び出し側が把握しているバージョンと現在のバージョンを照合する
+    pub fn check_version(&self, expected: Option<i64>) -> Result<(), CategoryError> {
+        match expected {
+            Some(expected) if expected != self.version => {
+                Err(CategoryError::VersionMismatch(format!(
+                    "期待されたバージョン {} に対し現在のバージョンは {} です",
+                    expected, self.version
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -146,7 +217,12 @@ pub enum CategoryError {
     CircularReference(String),
     MaxDepthExceeded(String),
     HasChildren(String),
-    // HasProducts(String),
+    HasProducts(String),
+    VersionMismatch(String),
+    InvalidSlug(String),
+    SlugDuplicate(String),
+    InvalidAttributeDefinition(String),
+    InvalidTranslation(String),
 }
 
 impl std::fmt::Display for CategoryError {
@@ -161,7 +237,14 @@ impl std::fmt::Display for CategoryError {
             }
             CategoryError::MaxDepthExceeded(msg) => write!(f, "Maximum depth exceeded: {}", msg),
             CategoryError::HasChildren(msg) => write!(f, "Category has children: {}", msg),
-            // CategoryError::HasProducts(msg) => write!(f, "Category has products: {}", msg),
+            CategoryError::HasProducts(msg) => write!(f, "Category has products: {}", msg),
+            CategoryError::VersionMismatch(msg) => write!(f, "Version mismatch: {}", msg),
+            CategoryError::InvalidSlug(msg) => write!(f, "Invalid category slug: {}", msg),
+            CategoryError::SlugDuplicate(msg) => write!(f, "Category slug duplicate: {}", msg),
+            CategoryError::InvalidAttributeDefinition(msg) => {
+                write!(f, "Invalid attribute definition: {}", msg)
+            }
+            CategoryError::InvalidTranslation(msg) => write!(f, "Invalid translation: {}", msg),
         }
     }
 }
@@ -187,9 +270,29 @@ mod tests {
         assert_eq!(category.description, Some("Electronic devices".to_string()));
         assert_eq!(category.parent_id, None);
         assert_eq!(category.sort_order, 1);
+        assert_eq!(category.slug, "electronics");
         assert!(category.is_active);
     }
 
+    #[test]
+    fn test_category_update_slug() {
+        let mut category = Category::new("cat_123".to_string(), "家電".to_string(), None, None, 1);
+        assert_eq!(category.slug, "");
+        assert!(matches!(
+            category.validate(),
+            Err(CategoryError::InvalidSlug(_))
+        ));
+
+        assert!(category.update_slug("kaden".to_string()).is_ok());
+        assert_eq!(category.slug, "kaden");
+
+        match category.update_slug("Kaden Goods".to_string()) {
+            Err(CategoryError::InvalidSlug(_)) => (),
+            _ => panic!("Expected InvalidSlug error"),
+        }
+        assert_eq!(category.slug, "kaden");
+    }
+
     #[test]
     fn test_category_validation_valid() {
         let category = Category::new(
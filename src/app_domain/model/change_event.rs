@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app_domain::model::category::Category;
+use crate::app_domain::model::product::{Product, ProductStatus};
+use domain::model::item::Item;
+
+/// 変更フィードで通知するエンティティの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityType {
+    Item,
+    Product,
+    Category,
+}
+
+impl EntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::Item => "item",
+            EntityType::Product => "product",
+            EntityType::Category => "category",
+        }
+    }
+}
+
+impl FromStr for EntityType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "item" | "items" => Ok(EntityType::Item),
+            "product" | "products" => Ok(EntityType::Product),
+            "category" | "categories" => Ok(EntityType::Category),
+            other => Err(format!("不明なエンティティ種別です: {}", other)),
+        }
+    }
+}
+
+/// 変更の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemSnapshot {
+    pub id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductSnapshot {
+    pub id: String,
+    pub sku: String,
+    pub name: String,
+    pub status: ProductStatus,
+    pub category_id: Option<String>,
+    pub version: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategorySnapshot {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub sort_order: i32,
+    pub is_active: bool,
+    pub version: i64,
+}
+
+/// 変更後のエンティティの状態
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "entity_type", rename_all = "snake_case")]
+pub enum ChangePayload {
+    Item(ItemSnapshot),
+    Product(ProductSnapshot),
+    Category(CategorySnapshot),
+}
+
+impl ChangePayload {
+    pub fn entity_type(&self) -> EntityType {
+        match self {
+            ChangePayload::Item(_) => EntityType::Item,
+            ChangePayload::Product(_) => EntityType::Product,
+            ChangePayload::Category(_) => EntityType::Category,
+        }
+    }
+
+    pub fn entity_id(&self) -> String {
+        match self {
+            ChangePayload::Item(item) => item.id.to_string(),
+            ChangePayload::Product(product) => product.id.clone(),
+            ChangePayload::Category(category) => category.id.clone(),
+        }
+    }
+}
+
+impl From<&Item> for ChangePayload {
+    fn from(item: &Item) -> Self {
+        ChangePayload::Item(ItemSnapshot {
+            id: item.id,
+            name: item.name.clone(),
+            description: item.description.clone(),
+            deleted: item.deleted,
+        })
+    }
+}
+
+impl From<&Product> for ChangePayload {
+    fn from(product: &Product) -> Self {
+        ChangePayload::Product(ProductSnapshot {
+            id: product.id.clone(),
+            sku: product.sku.clone(),
+            name: product.name.clone(),
+            status: product.status.clone(),
+            category_id: product.category_id.clone(),
+            version: product.version,
+            updated_at: product.updated_at,
+        })
+    }
+}
+
+impl From<&Category> for ChangePayload {
+    fn from(category: &Category) -> Self {
+        ChangePayload::Category(CategorySnapshot {
+            id: category.id.clone(),
+            name: category.name.clone(),
+            parent_id: category.parent_id.clone(),
+            sort_order: category.sort_order,
+            is_active: category.is_active,
+            version: category.version,
+        })
+    }
+}
+
+/// 変更フィードの再開位置
+///
+/// `epoch` はフィードを保持するプロセスごとに異なり、別のプロセス（再起動前や
+/// 別のレプリカ）で得たカーソルを見分けるために使う。文字列では `<epoch>-<sequence>`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeCursor {
+    pub epoch: u64,
+    pub sequence: u64,
+}
+
+impl fmt::Display for ChangeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.epoch, self.sequence)
+    }
+}
+
+impl FromStr for ChangeCursor {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .trim()
+            .split_once('-')
+            .and_then(|(epoch, sequence)| {
+                Some(ChangeCursor {
+                    epoch: epoch.parse().ok()?,
+                    sequence: sequence.parse().ok()?,
+                })
+            })
+            .ok_or_else(|| {
+                format!(
+                    "カーソルは <epoch>-<sequence> の形式で指定してください: {}",
+                    value
+                )
+            })
+    }
+}
+
+/// 変更フィードのイベント
+///
+/// `sequence` はフィード内で単調増加し、`epoch` と組み合わせて再接続時の再開位置
+/// （カーソル）になる。削除・復元イベントは変更後の状態を持たないため `payload` が `None` になる。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub epoch: u64,
+    pub sequence: u64,
+    pub entity_type: EntityType,
+    pub entity_id: String,
+    pub kind: ChangeKind,
+    /// 変更された項目のグループ（例: `price`, `inventory`）。空の場合は特定しない
+    pub changed_fields: Vec<String>,
+    pub payload: Option<ChangePayload>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ChangeEvent {
+    pub fn cursor(&self) -> ChangeCursor {
+        ChangeCursor {
+            epoch: self.epoch,
+            sequence: self.sequence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_type_parses_singular_and_plural() {
+        assert_eq!("products".parse::<EntityType>(), Ok(EntityType::Product));
+        assert_eq!(" Category ".parse::<EntityType>(), Ok(EntityType::Category));
+        assert!("order".parse::<EntityType>().is_err());
+    }
+
+    #[test]
+    fn test_cursor_round_trips_through_string() {
+        let cursor = ChangeCursor {
+            epoch: 81_234,
+            sequence: 42,
+        };
+        assert_eq!(cursor.to_string(), "81234-42");
+        assert_eq!("81234-42".parse::<ChangeCursor>(), Ok(cursor));
+        assert!("42".parse::<ChangeCursor>().is_err());
+        assert!("a-1".parse::<ChangeCursor>().is_err());
+    }
+}
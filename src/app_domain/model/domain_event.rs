@@ -0,0 +1,319 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app_domain::model::category::Category;
+use crate::app_domain::model::change_event::EntityType;
+use crate::app_domain::model::product::{FieldChange, Inventory, Price, Product, ProductStatus};
+
+/// outbox に書き込むドメインイベントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DomainEventType {
+    ProductCreated,
+    ProductUpdated,
+    ProductStatusChanged,
+    ProductDeleted,
+    PriceChanged,
+    InventoryAdjusted,
+    CategoryCreated,
+    CategoryUpdated,
+    CategoryMoved,
+    CategoryDeleted,
+    ItemDeleted,
+    ItemRestored,
+}
+
+impl DomainEventType {
+    pub const ALL: [DomainEventType; 12] = [
+        DomainEventType::ProductCreated,
+        DomainEventType::ProductUpdated,
+        DomainEventType::ProductStatusChanged,
+        DomainEventType::ProductDeleted,
+        DomainEventType::PriceChanged,
+        DomainEventType::InventoryAdjusted,
+        DomainEventType::CategoryCreated,
+        DomainEventType::CategoryUpdated,
+        DomainEventType::CategoryMoved,
+        DomainEventType::CategoryDeleted,
+        DomainEventType::ItemDeleted,
+        DomainEventType::ItemRestored,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainEventType::ProductCreated => "ProductCreated",
+            DomainEventType::ProductUpdated => "ProductUpdated",
+            DomainEventType::ProductStatusChanged => "ProductStatusChanged",
+            DomainEventType::ProductDeleted => "ProductDeleted",
+            DomainEventType::PriceChanged => "PriceChanged",
+            DomainEventType::InventoryAdjusted => "InventoryAdjusted",
+            DomainEventType::CategoryCreated => "CategoryCreated",
+            DomainEventType::CategoryUpdated => "CategoryUpdated",
+            DomainEventType::CategoryMoved => "CategoryMoved",
+            DomainEventType::CategoryDeleted => "CategoryDeleted",
+            DomainEventType::ItemDeleted => "ItemDeleted",
+            DomainEventType::ItemRestored => "ItemRestored",
+        }
+    }
+}
+
+impl fmt::Display for DomainEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DomainEventType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        DomainEventType::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == value.trim())
+            .ok_or_else(|| format!("不明なイベント種別です: {}", value))
+    }
+}
+
+/// 在庫数と引当数（InventoryAdjusted の変更前後）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockLevel {
+    pub quantity: i32,
+    pub reserved_quantity: i32,
+}
+
+impl From<&Inventory> for StockLevel {
+    fn from(inventory: &Inventory) -> Self {
+        Self {
+            quantity: inventory.quantity,
+            reserved_quantity: inventory.reserved_quantity,
+        }
+    }
+}
+
+/// 集約（商品・カテゴリ・アイテム）に起きた変更
+///
+/// 業務データと同じトランザクションで outbox に書き込まれ、コミット後に
+/// ディスパッチャーが各シンクへ配信する。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainEvent {
+    pub event_type: DomainEventType,
+    pub aggregate_type: EntityType,
+    pub aggregate_id: String,
+    pub payload: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl DomainEvent {
+    pub fn new(
+        event_type: DomainEventType,
+        aggregate_type: EntityType,
+        aggregate_id: impl ToString,
+        payload: Value,
+    ) -> Self {
+        Self {
+            event_type,
+            aggregate_type,
+            aggregate_id: aggregate_id.to_string(),
+            payload,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn product_created(product: &Product) -> Self {
+        Self::new(
+            DomainEventType::ProductCreated,
+            EntityType::Product,
+            &product.id,
+            json!({
+                "sku": product.sku,
+                "name": product.name,
+                "status": product.status,
+                "category_id": product.category_id,
+            }),
+        )
+    }
+
+    /// 変更された項目と変更前後の値（product_history と同じ粒度）
+    pub fn product_updated(product_id: &str, changes: &[FieldChange]) -> Self {
+        let changes: Vec<Value> = changes
+            .iter()
+            .map(|change| {
+                json!({
+                    "field": change.field_name,
+                    "old_value": change.old_value,
+                    "new_value": change.new_value,
+                })
+            })
+            .collect();
+        Self::new(
+            DomainEventType::ProductUpdated,
+            EntityType::Product,
+            product_id,
+            json!({ "changes": changes }),
+        )
+    }
+
+    pub fn product_status_changed(
+        product_id: &str,
+        from: &ProductStatus,
+        to: &ProductStatus,
+    ) -> Self {
+        Self::new(
+            DomainEventType::ProductStatusChanged,
+            EntityType::Product,
+            product_id,
+            json!({ "from": from, "to": to }),
+        )
+    }
+
+    pub fn product_deleted(product_id: &str) -> Self {
+        Self::new(
+            DomainEventType::ProductDeleted,
+            EntityType::Product,
+            product_id,
+            json!({}),
+        )
+    }
+
+    pub fn price_changed(product_id: &str, previous: Option<&Price>, price: &Price) -> Self {
+        Self::new(
+            DomainEventType::PriceChanged,
+            EntityType::Product,
+            product_id,
+            json!({
+                "previous_selling_price": previous.map(|p| p.selling_price),
+                "selling_price": price.selling_price,
+                "list_price": price.list_price,
+                "discount_price": price.discount_price,
+                "currency": price.currency,
+                "tax_included": price.tax_included,
+            }),
+        )
+    }
+
+    pub fn inventory_adjusted(product_id: &str, previous: StockLevel, current: StockLevel) -> Self {
+        Self::new(
+            DomainEventType::InventoryAdjusted,
+            EntityType::Product,
+            product_id,
+            json!({
+                "previous_quantity": previous.quantity,
+                "quantity": current.quantity,
+                "previous_reserved_quantity": previous.reserved_quantity,
+                "reserved_quantity": current.reserved_quantity,
+            }),
+        )
+    }
+
+    pub fn category_created(category: &Category) -> Self {
+        Self::new(
+            DomainEventType::CategoryCreated,
+            EntityType::Category,
+            &category.id,
+            Self::category_payload(category),
+        )
+    }
+
+    pub fn category_updated(category: &Category) -> Self {
+        Self::new(
+            DomainEventType::CategoryUpdated,
+            EntityType::Category,
+            &category.id,
+            Self::category_payload(category),
+        )
+    }
+
+    pub fn category_moved(category: &Category, previous_parent_id: Option<&str>) -> Self {
+        let mut payload = Self::category_payload(category);
+        payload["previous_parent_id"] = json!(previous_parent_id);
+        Self::new(
+            DomainEventType::CategoryMoved,
+            EntityType::Category,
+            &category.id,
+            payload,
+        )
+    }
+
+    pub fn category_deleted(category_id: &str) -> Self {
+        Self::new(
+            DomainEventType::CategoryDeleted,
+            EntityType::Category,
+            category_id,
+            json!({}),
+        )
+    }
+
+    pub fn item_deleted(item_id: u64, physical: bool) -> Self {
+        Self::new(
+            DomainEventType::ItemDeleted,
+            EntityType::Item,
+            item_id,
+            json!({ "physical": physical }),
+        )
+    }
+
+    pub fn item_restored(item_id: u64) -> Self {
+        Self::new(
+            DomainEventType::ItemRestored,
+            EntityType::Item,
+            item_id,
+            json!({}),
+        )
+    }
+
+    fn category_payload(category: &Category) -> Value {
+        json!({
+            "name": category.name,
+            "parent_id": category.parent_id,
+            "sort_order": category.sort_order,
+            "is_active": category.is_active,
+            "version": category.version,
+        })
+    }
+}
+
+/// outbox に保存されたイベント
+///
+/// `id` は書き込み順に採番され、同じ集約のイベントはこの順に配信される。
+/// 配信は at-least-once のため、受信側は `id` で重複を除外する。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutboxEvent {
+    pub id: i64,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+    /// これまでの配信試行回数（今回の試行を含む）
+    #[serde(skip)]
+    pub attempts: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_round_trip() {
+        for event_type in DomainEventType::ALL {
+            assert_eq!(event_type.as_str().parse(), Ok(event_type));
+        }
+        assert!("OrderPlaced".parse::<DomainEventType>().is_err());
+    }
+
+    #[test]
+    fn test_outbox_event_serializes_flat() {
+        let event = OutboxEvent {
+            id: 7,
+            event: DomainEvent::item_deleted(3, false),
+            attempts: 1,
+        };
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["id"], 7);
+        assert_eq!(value["event_type"], "ItemDeleted");
+        assert_eq!(value["aggregate_type"], "item");
+        assert_eq!(value["aggregate_id"], "3");
+        assert_eq!(value["payload"]["physical"], false);
+        assert!(value.get("attempts").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+
+/// 冪等性キーの最大長
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// 呼び出し元ごとに区切った冪等性キー
+///
+/// `scope` には認証済みの subject など呼び出し元を識別する値を入れる。
+/// 別の呼び出し元が同じキーを送っても、互いの記録に触れることはない。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey {
+    pub scope: String,
+    pub key: String,
+}
+
+impl IdempotencyKey {
+    pub fn new(scope: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            scope: scope.into(),
+            key: key.into(),
+        }
+    }
+}
+
+/// 冪等性キーに紐づけて保存された処理結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// 冪等性キーの記録（`response` が `None` の間は処理中）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    pub key: IdempotencyKey,
+    pub fingerprint: String,
+    pub request_path: String,
+    pub response: Option<StoredResponse>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// キー確保の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reservation {
+    /// 新規に確保できた（呼び出し側が処理を実行する）
+    Reserved,
+    /// 有効期限内の記録が既に存在する
+    Existing(IdempotencyRecord),
+}
+
+/// 冪等性キー付きリクエストの扱い
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyOutcome {
+    Proceed,
+    Replay(StoredResponse),
+    InProgress,
+    FingerprintMismatch,
+}
+
+impl IdempotencyRecord {
+    /// 同じキーで再送されたリクエストをどう扱うかを判定する
+    pub fn outcome_for(&self, fingerprint: &str) -> IdempotencyOutcome {
+        if self.fingerprint != fingerprint {
+            return IdempotencyOutcome::FingerprintMismatch;
+        }
+
+        match &self.response {
+            Some(response) => IdempotencyOutcome::Replay(response.clone()),
+            None => IdempotencyOutcome::InProgress,
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// キーは 1〜255 文字の表示可能な ASCII 文字列とする
+pub fn is_valid_idempotency_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH
+        && key.chars().all(|c| c.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn record(response: Option<StoredResponse>) -> IdempotencyRecord {
+        let now = Utc::now();
+        IdempotencyRecord {
+            key: IdempotencyKey::new("sub:user-1", "key-1"),
+            fingerprint: "abc".to_string(),
+            request_path: "POST /api/items".to_string(),
+            response,
+            created_at: now,
+            expires_at: now + Duration::hours(1),
+        }
+    }
+
+    #[test]
+    fn test_outcome_for() {
+        let stored = StoredResponse {
+            status_code: 201,
+            headers: vec![],
+            body: b"{}".to_vec(),
+        };
+
+        assert_eq!(
+            record(Some(stored.clone())).outcome_for("abc"),
+            IdempotencyOutcome::Replay(stored)
+        );
+        assert_eq!(
+            record(None).outcome_for("abc"),
+            IdempotencyOutcome::InProgress
+        );
+        assert_eq!(
+            record(None).outcome_for("other"),
+            IdempotencyOutcome::FingerprintMismatch
+        );
+    }
+
+    #[test]
+    fn test_is_valid_idempotency_key() {
+        assert!(is_valid_idempotency_key(
+            "8e03978e-40d5-43e8-bc93-6894a57f9324"
+        ));
+        assert!(!is_valid_idempotency_key(""));
+        assert!(!is_valid_idempotency_key("has space"));
+        assert!(!is_valid_idempotency_key(&"a".repeat(256)));
+    }
+}
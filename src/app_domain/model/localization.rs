@@ -0,0 +1,385 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::category::Category;
+use super::product::{Product, ProductImage};
+
+/// 既定ロケール（商品・カテゴリ本体の名前や説明はこのロケールの内容として扱う）
+pub const DEFAULT_LOCALE: &str = "ja";
+
+/// 翻訳した名前の最大長（products.name / categories.name に合わせる）
+const MAX_PRODUCT_NAME_LENGTH: usize = 200;
+const MAX_CATEGORY_NAME_LENGTH: usize = 100;
+
+/// サポートするロケールと既定ロケール
+///
+/// ロケールは小文字・ハイフン区切り（`en`、`en-us` など）に正規化して扱う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locales {
+    default_locale: String,
+    supported: Vec<String>,
+}
+
+impl Default for Locales {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE, ["ja", "en"])
+    }
+}
+
+impl Locales {
+    /// 既定ロケールは `supported` に含まれていなくても先頭に加える
+    pub fn new<I, S>(default_locale: &str, supported: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let default_locale = normalize_tag(default_locale);
+        let mut locales = vec![default_locale.clone()];
+        for locale in supported {
+            let locale = normalize_tag(locale.as_ref());
+            if !locale.is_empty() && !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+        Self {
+            default_locale,
+            supported: locales,
+        }
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    pub fn is_default(&self, locale: &str) -> bool {
+        self.default_locale == locale
+    }
+
+    /// 翻訳を登録できるロケール（既定ロケール以外）
+    pub fn translatable(&self) -> impl Iterator<Item = &str> {
+        self.supported.iter().skip(1).map(String::as_str)
+    }
+
+    /// 言語タグに一致するロケールを返す（完全一致がなければ `en-US` は `en` にも一致する）
+    pub fn resolve(&self, tag: &str) -> Option<&str> {
+        let tag = normalize_tag(tag);
+        let primary = tag.split('-').next().unwrap_or_default();
+        if primary.is_empty() {
+            return None;
+        }
+        self.supported
+            .iter()
+            .find(|locale| **locale == tag)
+            .or_else(|| {
+                self.supported
+                    .iter()
+                    .find(|locale| locale.split('-').next() == Some(primary))
+            })
+            .map(String::as_str)
+    }
+
+    /// 翻訳を登録できるロケールか検証し、正規化したロケールを返す
+    pub fn translation_locale(&self, locale: &str) -> Result<String, String> {
+        let normalized = normalize_tag(locale);
+        if !self.supported.contains(&normalized) {
+            return Err(format!(
+                "サポートされていないロケールです: {}（{}）",
+                locale,
+                self.supported.join(", ")
+            ));
+        }
+        if self.is_default(&normalized) {
+            return Err(format!(
+                "既定ロケール {} の内容は商品・カテゴリ本体で更新してください",
+                normalized
+            ));
+        }
+        Ok(normalized)
+    }
+
+    /// 表示するロケールを決める
+    ///
+    /// `requested`（クエリパラメーター）を `Accept-Language` より優先し、どちらも
+    /// サポートするロケールに一致しなければ既定ロケールを返す。
+    pub fn negotiate(&self, requested: Option<&str>, accept_language: Option<&str>) -> String {
+        requested
+            .and_then(|tag| self.resolve(tag))
+            .or_else(|| accept_language.and_then(|header| self.match_accept_language(header)))
+            .unwrap_or(&self.default_locale)
+            .to_string()
+    }
+
+    fn match_accept_language(&self, header: &str) -> Option<&str> {
+        let mut ranges: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(1.0, |q| q.trim().parse().unwrap_or(0.0));
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // 品質値の高い順（同じ値ならヘッダーに書かれた順）
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.into_iter().find_map(|(tag, _)| match tag {
+            "*" => Some(self.default_locale.as_str()),
+            _ => self.resolve(tag),
+        })
+    }
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// 前後の空白を除き、空になった項目は未翻訳として扱う
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn has_text(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.trim().is_empty())
+}
+
+/// 商品のロケールごとの翻訳（未翻訳の項目は既定ロケールの内容で表示する）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProductTranslation {
+    pub locale: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 画像 ID ごとの代替テキスト
+    pub image_alt_texts: BTreeMap<String, String>,
+}
+
+impl ProductTranslation {
+    pub fn normalize(mut self) -> Result<Self, String> {
+        self.name = non_empty(self.name);
+        self.description = non_empty(self.description);
+        self.image_alt_texts = self
+            .image_alt_texts
+            .into_iter()
+            .filter_map(|(image_id, alt_text)| non_empty(Some(alt_text)).map(|alt| (image_id, alt)))
+            .collect();
+
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.chars().count() > MAX_PRODUCT_NAME_LENGTH)
+        {
+            return Err(format!(
+                "商品名は{}文字以下である必要があります",
+                MAX_PRODUCT_NAME_LENGTH
+            ));
+        }
+        if self.name.is_none() && self.description.is_none() && self.image_alt_texts.is_empty() {
+            return Err(
+                "翻訳する項目がありません（削除する場合は DELETE を使用してください）".to_string(),
+            );
+        }
+        Ok(self)
+    }
+
+    /// 既定ロケールの内容があるのに翻訳されていない項目
+    /// （`name`、`description`、`images.{画像ID}.alt_text`）
+    pub fn missing_fields(
+        product: &Product,
+        images: &[ProductImage],
+        translation: Option<&Self>,
+    ) -> Vec<String> {
+        let mut missing = Vec::new();
+        if translation.and_then(|t| t.name.as_ref()).is_none() {
+            missing.push("name".to_string());
+        }
+        if has_text(product.description.as_deref())
+            && translation.and_then(|t| t.description.as_ref()).is_none()
+        {
+            missing.push("description".to_string());
+        }
+        for image in images {
+            if has_text(image.alt_text.as_deref())
+                && !translation.is_some_and(|t| t.image_alt_texts.contains_key(&image.id))
+            {
+                missing.push(format!("images.{}.alt_text", image.id));
+            }
+        }
+        missing
+    }
+}
+
+/// カテゴリのロケールごとの翻訳（未翻訳の項目は既定ロケールの内容で表示する）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryTranslation {
+    pub locale: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl CategoryTranslation {
+    pub fn normalize(mut self) -> Result<Self, String> {
+        self.name = non_empty(self.name);
+        self.description = non_empty(self.description);
+
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.chars().count() > MAX_CATEGORY_NAME_LENGTH)
+        {
+            return Err(format!(
+                "カテゴリ名は{}文字以下である必要があります",
+                MAX_CATEGORY_NAME_LENGTH
+            ));
+        }
+        if self.name.is_none() && self.description.is_none() {
+            return Err(
+                "翻訳する項目がありません（削除する場合は DELETE を使用してください）".to_string(),
+            );
+        }
+        Ok(self)
+    }
+
+    /// 翻訳のある名前と説明で置き換える
+    pub fn apply(&self, name: &mut String, description: &mut Option<String>) {
+        if let Some(translated) = &self.name {
+            *name = translated.clone();
+        }
+        if let Some(translated) = &self.description {
+            *description = Some(translated.clone());
+        }
+    }
+
+    /// 既定ロケールの内容があるのに翻訳されていない項目（`name`、`description`）
+    pub fn missing_fields(category: &Category, translation: Option<&Self>) -> Vec<String> {
+        let mut missing = Vec::new();
+        if translation.and_then(|t| t.name.as_ref()).is_none() {
+            missing.push("name".to_string());
+        }
+        if has_text(category.description.as_deref())
+            && translation.and_then(|t| t.description.as_ref()).is_none()
+        {
+            missing.push("description".to_string());
+        }
+        missing
+    }
+}
+
+/// 翻訳が不足している商品・カテゴリ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingTranslation {
+    pub id: String,
+    /// 既定ロケールの名前
+    pub name: String,
+    pub locale: String,
+    pub fields: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::product::ProductStatus;
+
+    #[test]
+    fn test_negotiate_locale() {
+        let locales = Locales::new("ja", ["en", "zh-TW"]);
+
+        assert_eq!(locales.negotiate(None, None), "ja");
+        assert_eq!(locales.negotiate(Some("EN"), Some("ja")), "en");
+        // サポートしていない指定は無視して Accept-Language を見る
+        assert_eq!(locales.negotiate(Some("fr"), Some("en-US,ja;q=0.5")), "en");
+        assert_eq!(
+            locales.negotiate(None, Some("fr, zh_tw;q=0.8, en;q=0.9")),
+            "en"
+        );
+        assert_eq!(locales.negotiate(None, Some("zh-Hant-TW")), "zh-tw");
+        assert_eq!(locales.negotiate(None, Some("en;q=0, fr")), "ja");
+        assert_eq!(locales.negotiate(None, Some("fr, *;q=0.1")), "ja");
+        assert_eq!(locales.negotiate(None, Some(";;,")), "ja");
+    }
+
+    #[test]
+    fn test_translation_locale() {
+        let locales = Locales::default();
+
+        assert_eq!(locales.translation_locale(" EN ").unwrap(), "en");
+        assert!(locales.translation_locale("ja").is_err());
+        assert!(locales.translation_locale("fr").is_err());
+        assert_eq!(locales.translatable().collect::<Vec<_>>(), ["en"]);
+    }
+
+    #[test]
+    fn test_normalize_translation() {
+        let translation = ProductTranslation {
+            locale: "en".to_string(),
+            name: Some("  Notebook ".to_string()),
+            description: Some("   ".to_string()),
+            image_alt_texts: BTreeMap::from([
+                ("img_1".to_string(), "Cover".to_string()),
+                ("img_2".to_string(), "".to_string()),
+            ]),
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(translation.name.as_deref(), Some("Notebook"));
+        assert_eq!(translation.description, None);
+        assert_eq!(translation.image_alt_texts.len(), 1);
+
+        let empty = CategoryTranslation {
+            locale: "en".to_string(),
+            name: Some(" ".to_string()),
+            description: None,
+        };
+        assert!(empty.normalize().is_err());
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let mut product = Product::new(
+            "prod_1".to_string(),
+            "ノート".to_string(),
+            "NB-1".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        product.description = Some("A4サイズ".to_string());
+        let images = vec![
+            ProductImage {
+                id: "img_1".to_string(),
+                url: "https://example.com/1.jpg".to_string(),
+                alt_text: Some("表紙".to_string()),
+                sort_order: 0,
+                is_main: true,
+            },
+            ProductImage {
+                id: "img_2".to_string(),
+                url: "https://example.com/2.jpg".to_string(),
+                alt_text: None,
+                sort_order: 1,
+                is_main: false,
+            },
+        ];
+
+        assert_eq!(
+            ProductTranslation::missing_fields(&product, &images, None),
+            ["name", "description", "images.img_1.alt_text"]
+        );
+        let translation = ProductTranslation {
+            locale: "en".to_string(),
+            name: Some("Notebook".to_string()),
+            image_alt_texts: BTreeMap::from([("img_1".to_string(), "Cover".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProductTranslation::missing_fields(&product, &images, Some(&translation)),
+            ["description"]
+        );
+
+        let category = Category::new("cat_1".to_string(), "文具".to_string(), None, None, 0);
+        assert_eq!(
+            CategoryTranslation::missing_fields(&category, None),
+            ["name"]
+        );
+    }
+}
@@ -1,3 +1,14 @@
+pub mod attribute_schema;
 pub mod category;
+pub mod change_event;
+pub mod domain_event;
+pub mod idempotency;
 pub mod item;
+pub mod localization;
 pub mod product;
+pub mod product_bulk;
+pub mod product_export;
+pub mod product_filter;
+pub mod product_import;
+pub mod slug;
+pub mod webhook;
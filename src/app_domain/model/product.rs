@@ -1,11 +1,17 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::attribute_schema::AttributeViolation;
+use super::slug;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Product {
     pub id: String,
     pub name: String,
+    /// URL 用のスラッグ（全商品で一意）
+    pub slug: String,
     pub description: Option<String>,
     pub sku: String,
     pub brand: Option<String>,
@@ -16,6 +22,8 @@ pub struct Product {
     pub shipping_info: ShippingInfo,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 楽観的排他制御用のバージョン（変更が記録されるたびに加算）
+    pub version: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -92,20 +100,182 @@ pub struct ProductHistory {
     pub changed_at: DateTime<Utc>,
 }
 
+impl ProductStatus {
+    /// 許可された状態遷移かどうかを判定
+    ///
+    /// Draft → Active、Active ⇄ Inactive、任意の状態 → Discontinued のみ許可する。
+    /// 同一状態への遷移は変更なしとして許可する。
+    pub fn can_transition_to(&self, next: &ProductStatus) -> bool {
+        matches!(
+            (self, next),
+            (ProductStatus::Draft, ProductStatus::Active)
+                | (ProductStatus::Active, ProductStatus::Inactive)
+                | (ProductStatus::Inactive, ProductStatus::Active)
+                | (_, ProductStatus::Discontinued)
+        ) || self == next
+    }
+}
+
+/// 変更履歴に記録する操作者と変更理由
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeContext {
+    pub changed_by: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// 商品本体・価格・タグ・属性への変更を1トランザクションでまとめて書き込む単位
+///
+/// `None` の項目は変更しない。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductRevision {
+    pub product: Option<Product>,
+    pub price: Option<Price>,
+    pub tags: Option<Vec<String>>,
+    pub attributes: Option<HashMap<String, String>>,
+}
+
+/// 履歴に記録するフィールド単位の変更（変更前後の値）
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field_name: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductOption {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductVariant {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub option_values: BTreeMap<String, String>,
+    pub price: Price,
+    pub inventory: Inventory,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceRange {
+    pub min: Decimal,
+    pub max: Decimal,
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleComponent {
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BundlePricing {
+    /// セット商品自身に設定された価格を使用
+    Fixed,
+    /// 構成商品の販売価格の合計から割引率を適用（0.1 = 10%引き）
+    DiscountedSum { discount_rate: Decimal },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductBundle {
+    pub product_id: String,
+    pub components: Vec<BundleComponent>,
+    pub pricing: BundlePricing,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleState {
+    Pending,
+    /// 実行中（レプリカ間の二重実行を防ぐため claim 済み）
+    Processing,
+    Applied,
+    Cancelled,
+    Failed,
+}
+
+impl std::fmt::Display for ScheduleState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleState::Pending => write!(f, "pending"),
+            ScheduleState::Processing => write!(f, "processing"),
+            ScheduleState::Applied => write!(f, "applied"),
+            ScheduleState::Cancelled => write!(f, "cancelled"),
+            ScheduleState::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// 指定時刻に商品ステータスを変更する予約
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductStatusSchedule {
+    pub id: String,
+    pub product_id: String,
+    pub target_status: ProductStatus,
+    pub scheduled_at: DateTime<Utc>,
+    pub state: ScheduleState,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProductError {
     InvalidName,
     InvalidSku,
     SkuAlreadyExists,
+    InvalidSlug,
+    SlugRequired,
+    SlugAlreadyExists,
     InvalidPrice,
     InvalidPriceRelationship,
     InvalidInventoryQuantity,
     InvalidDimensions,
     InvalidWeight,
     InvalidShippingFee,
+    InvalidAttributes(Vec<AttributeViolation>),
     TooManyImages,
     ImageNotFound,
     InvalidImageOrder,
+    InvalidVariantOptions,
+    VariantCombinationDuplicate,
+    VariantNotFound,
+    InvalidBundle,
+    BundleNotFound,
+    InsufficientInventory,
+    UsedInBundle(Vec<String>),
+    InvalidStatusTransition {
+        from: ProductStatus,
+        to: ProductStatus,
+    },
+    ActivationRequirementsNotMet(Vec<String>),
+    InvalidSchedule,
+    ScheduleNotFound,
+    InvalidRollbackTarget(String),
+    HistoryNotFound,
+    VersionMismatch {
+        expected: i64,
+        actual: i64,
+    },
+    InvalidImport(String),
+    ImportJobNotFound,
+    InvalidExport(String),
+    InvalidFilter(String),
+    InvalidBulkUpdate(String),
+    BulkCountMismatch {
+        expected: i64,
+        actual: i64,
+    },
+    InvalidTranslation(String),
+    TranslationNotFound,
     // CategoryNotFound,
     ProductNotFound,
     // InsufficientPermissions,
@@ -118,15 +288,80 @@ impl std::fmt::Display for ProductError {
             ProductError::InvalidName => write!(f, "Product name is invalid"),
             ProductError::InvalidSku => write!(f, "Product SKU is invalid"),
             ProductError::SkuAlreadyExists => write!(f, "SKU already exists"),
+            ProductError::InvalidSlug => write!(f, "Product slug is invalid"),
+            ProductError::SlugRequired => {
+                write!(
+                    f,
+                    "Slug is required because it cannot be generated from the name"
+                )
+            }
+            ProductError::SlugAlreadyExists => write!(f, "Slug already exists"),
             ProductError::InvalidPrice => write!(f, "Price is invalid"),
             ProductError::InvalidPriceRelationship => write!(f, "Price relationship is invalid"),
             ProductError::InvalidInventoryQuantity => write!(f, "Inventory quantity is invalid"),
             ProductError::InvalidDimensions => write!(f, "Dimensions are invalid"),
             ProductError::InvalidWeight => write!(f, "Weight is invalid"),
             ProductError::InvalidShippingFee => write!(f, "Shipping fee is invalid"),
+            ProductError::InvalidAttributes(violations) => write!(
+                f,
+                "Product attributes are invalid: {}",
+                violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
             ProductError::TooManyImages => write!(f, "Too many images"),
             ProductError::ImageNotFound => write!(f, "Image not found"),
             ProductError::InvalidImageOrder => write!(f, "Invalid image order"),
+            ProductError::InvalidVariantOptions => write!(f, "Variant options are invalid"),
+            ProductError::VariantCombinationDuplicate => {
+                write!(f, "Variant option combination already exists")
+            }
+            ProductError::VariantNotFound => write!(f, "Variant not found"),
+            ProductError::InvalidBundle => write!(f, "Bundle definition is invalid"),
+            ProductError::BundleNotFound => write!(f, "Bundle not found"),
+            ProductError::InsufficientInventory => write!(f, "Insufficient inventory"),
+            ProductError::UsedInBundle(bundle_ids) => write!(
+                f,
+                "Product is a component of bundles: {}",
+                bundle_ids.join(", ")
+            ),
+            ProductError::InvalidStatusTransition { from, to } => {
+                write!(
+                    f,
+                    "Status transition from {} to {} is not allowed",
+                    from, to
+                )
+            }
+            ProductError::ActivationRequirementsNotMet(missing) => {
+                write!(f, "Activation requirements not met: {}", missing.join(", "))
+            }
+            ProductError::InvalidSchedule => write!(f, "Status schedule is invalid"),
+            ProductError::ScheduleNotFound => write!(f, "Status schedule not found"),
+            ProductError::InvalidRollbackTarget(reason) => {
+                write!(f, "Invalid rollback target: {}", reason)
+            }
+            ProductError::HistoryNotFound => write!(f, "History entry not found"),
+            ProductError::VersionMismatch { expected, actual } => write!(
+                f,
+                "Version mismatch: expected {}, current {}",
+                expected, actual
+            ),
+            ProductError::InvalidImport(reason) => write!(f, "Invalid import file: {}", reason),
+            ProductError::ImportJobNotFound => write!(f, "Import job not found"),
+            ProductError::InvalidExport(reason) => write!(f, "Invalid export request: {}", reason),
+            ProductError::InvalidFilter(reason) => write!(f, "Invalid product filter: {}", reason),
+            ProductError::InvalidBulkUpdate(reason) => write!(f, "Invalid bulk update: {}", reason),
+            ProductError::BulkCountMismatch { expected, actual } => write!(
+                f,
+                "Bulk update expected {} matching products but found {}",
+                expected, actual
+            ),
+            ProductError::InvalidTranslation(reason) => {
+                write!(f, "Invalid translation: {}", reason)
+            }
+            ProductError::TranslationNotFound => write!(f, "Translation not found"),
             // ProductError::CategoryNotFound => write!(f, "Category not found"),
             ProductError::ProductNotFound => write!(f, "Product not found"),
             // ProductError::InsufficientPermissions => write!(f, "Insufficient permissions"),
@@ -146,10 +381,13 @@ impl Product {
     ) -> Result<Self, ProductError> {
         Self::validate_name(&name)?;
         Self::validate_sku(&sku)?;
+        // 名前から生成できない場合（漢字を含むなど）は空のままにし、作成時にスラッグの指定を求める
+        let slug = slug::slugify(&name).unwrap_or_default();
 
         Ok(Product {
             id,
             name,
+            slug,
             description: None,
             sku,
             brand: None,
@@ -160,9 +398,21 @@ impl Product {
             shipping_info: ShippingInfo::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         })
     }
 
+    /// 呼び出し側が把握しているバージョンと現在のバージョンを照合する
+    pub fn check_version(&self, expected: Option<i64>) -> Result<(), ProductError> {
+        match expected {
+            Some(expected) if expected != self.version => Err(ProductError::VersionMismatch {
+                expected,
+                actual: self.version,
+            }),
+            _ => Ok(()),
+        }
+    }
+
     pub fn validate_name(name: &str) -> Result<(), ProductError> {
         if name.trim().is_empty() || name.len() > 200 {
             return Err(ProductError::InvalidName);
@@ -192,6 +442,20 @@ impl Product {
         Ok(())
     }
 
+    pub fn validate_slug(slug: &str) -> Result<(), ProductError> {
+        if !slug::is_valid_slug(slug) {
+            return Err(ProductError::InvalidSlug);
+        }
+        Ok(())
+    }
+
+    pub fn update_slug(&mut self, slug: String) -> Result<(), ProductError> {
+        Self::validate_slug(&slug)?;
+        self.slug = slug;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     pub fn update_sku(&mut self, sku: String) -> Result<(), ProductError> {
         Self::validate_sku(&sku)?;
         self.sku = sku;
@@ -209,9 +473,55 @@ impl Product {
         self.updated_at = Utc::now();
     }
 
-    pub fn update_status(&mut self, status: ProductStatus) {
-        self.status = status;
-        self.updated_at = Utc::now();
+    pub fn update_status(&mut self, status: ProductStatus) -> Result<(), ProductError> {
+        if !self.status.can_transition_to(&status) {
+            return Err(ProductError::InvalidStatusTransition {
+                from: self.status.clone(),
+                to: status,
+            });
+        }
+
+        if self.status != status {
+            self.status = status;
+            self.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    /// 削除状態（Discontinued）からの復元
+    ///
+    /// 遷移グラフの例外として Inactive に戻す。再公開には publish が必要。
+    pub fn restore(&mut self) {
+        if self.status == ProductStatus::Discontinued {
+            self.status = ProductStatus::Inactive;
+            self.updated_at = Utc::now();
+        }
+    }
+
+    /// 公開（Active への遷移）の前提条件を検証
+    ///
+    /// 価格・1枚以上の画像・カテゴリが必要。不足している項目名を返す。
+    pub fn validate_activation(
+        &self,
+        has_price: bool,
+        image_count: usize,
+    ) -> Result<(), ProductError> {
+        let mut missing = Vec::new();
+        if !has_price {
+            missing.push("price".to_string());
+        }
+        if image_count == 0 {
+            missing.push("images".to_string());
+        }
+        if self.category_id.is_none() {
+            missing.push("category_id".to_string());
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ProductError::ActivationRequirementsNotMet(missing))
+        }
     }
 
     pub fn update_category(&mut self, category_id: Option<String>) {
@@ -334,6 +644,14 @@ impl Inventory {
         Ok(())
     }
 
+    /// 在庫数が販売可能数・引当の上限になるか
+    ///
+    /// 在庫管理対象外またはバックオーダー可の場合は在庫数に制約されない。
+    /// 販売可能数の算出と在庫引当はこの判定を共有する。
+    pub fn limits_availability(&self) -> bool {
+        self.track_inventory && !self.allow_backorder
+    }
+
     pub fn update_quantity(&mut self, quantity: i32) -> Result<(), ProductError> {
         if quantity < 0 {
             return Err(ProductError::InvalidInventoryQuantity);
@@ -442,6 +760,691 @@ impl ProductImage {
     }
 }
 
+impl ProductOption {
+    pub fn validate(&self) -> Result<(), ProductError> {
+        if self.name.trim().is_empty() || self.name.len() > 100 || self.values.is_empty() {
+            return Err(ProductError::InvalidVariantOptions);
+        }
+
+        let mut seen = HashSet::new();
+        for value in &self.values {
+            if value.trim().is_empty() || !seen.insert(value.as_str()) {
+                return Err(ProductError::InvalidVariantOptions);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// オプション定義一覧を検証（オプション名の重複も不可）
+    pub fn validate_all(options: &[ProductOption]) -> Result<(), ProductError> {
+        let mut names = HashSet::new();
+        for option in options {
+            option.validate()?;
+            if !names.insert(option.name.as_str()) {
+                return Err(ProductError::InvalidVariantOptions);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ProductVariant {
+    pub fn new(
+        id: String,
+        product_id: String,
+        sku: String,
+        option_values: BTreeMap<String, String>,
+        price: Price,
+        inventory: Inventory,
+    ) -> Result<Self, ProductError> {
+        Product::validate_sku(&sku)?;
+        price.validate()?;
+        inventory.validate()?;
+
+        Ok(ProductVariant {
+            id,
+            product_id,
+            sku,
+            option_values,
+            price,
+            inventory,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// 親商品のオプション定義に対してオプション値を検証
+    ///
+    /// すべてのオプションに値が指定され、その値が許可された値であること、
+    /// 定義されていないオプションが含まれないことを確認する
+    pub fn validate_options(&self, options: &[ProductOption]) -> Result<(), ProductError> {
+        if options.is_empty() || self.option_values.len() != options.len() {
+            return Err(ProductError::InvalidVariantOptions);
+        }
+
+        for option in options {
+            match self.option_values.get(&option.name) {
+                Some(value) if option.values.contains(value) => {}
+                _ => return Err(ProductError::InvalidVariantOptions),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// オプション値の組み合わせを一意に表すキー（例: "color=Navy|size=M"）
+    pub fn combination_key(&self) -> String {
+        self.option_values
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// バリエーション間でオプション値の組み合わせが重複していないことを検証
+    pub fn validate_unique_combinations(variants: &[ProductVariant]) -> Result<(), ProductError> {
+        let mut seen = HashSet::new();
+        for variant in variants {
+            if !seen.insert(variant.combination_key()) {
+                return Err(ProductError::VariantCombinationDuplicate);
+            }
+        }
+        Ok(())
+    }
+
+    /// バリエーションの販売価格から価格帯を算出
+    ///
+    /// 通貨の異なる価格は比較できないため、通貨が揃っていない場合は `None` を返す。
+    pub fn price_range(variants: &[ProductVariant]) -> Option<PriceRange> {
+        let first = variants.first()?;
+        let mut range = PriceRange {
+            min: first.price.selling_price,
+            max: first.price.selling_price,
+            currency: first.price.currency.clone(),
+        };
+
+        for variant in &variants[1..] {
+            if variant.price.currency != range.currency {
+                return None;
+            }
+            range.min = range.min.min(variant.price.selling_price);
+            range.max = range.max.max(variant.price.selling_price);
+        }
+
+        Some(range)
+    }
+
+    pub fn update_sku(&mut self, sku: String) -> Result<(), ProductError> {
+        Product::validate_sku(&sku)?;
+        self.sku = sku;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn update_price(&mut self, price: Price) -> Result<(), ProductError> {
+        price.validate()?;
+        self.price = price;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn update_inventory(&mut self, inventory: Inventory) -> Result<(), ProductError> {
+        inventory.validate()?;
+        self.inventory = inventory;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+impl ProductBundle {
+    pub fn new(
+        product_id: String,
+        components: Vec<BundleComponent>,
+        pricing: BundlePricing,
+    ) -> Result<Self, ProductError> {
+        let bundle = ProductBundle {
+            product_id,
+            components,
+            pricing,
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    pub fn validate(&self) -> Result<(), ProductError> {
+        if self.components.is_empty() {
+            return Err(ProductError::InvalidBundle);
+        }
+
+        let mut seen = HashSet::new();
+        for component in &self.components {
+            if component.quantity <= 0
+                || component.product_id == self.product_id
+                || !seen.insert(component.product_id.as_str())
+            {
+                return Err(ProductError::InvalidBundle);
+            }
+        }
+
+        if let BundlePricing::DiscountedSum { discount_rate } = self.pricing {
+            if discount_rate < Decimal::ZERO || discount_rate >= Decimal::ONE {
+                return Err(ProductError::InvalidBundle);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 構成商品の在庫から販売可能なセット数を算出
+    ///
+    /// 在庫管理対象外またはバックオーダー可の構成商品は制約にならない。
+    /// すべての構成商品が制約にならない場合は `None`（無制限）を返す。
+    pub fn available_quantity(&self, inventories: &HashMap<String, Inventory>) -> Option<i32> {
+        self.components
+            .iter()
+            .filter_map(|component| match inventories.get(&component.product_id) {
+                Some(inventory) if !inventory.limits_availability() => None,
+                Some(inventory) => Some(
+                    (inventory.quantity - inventory.reserved_quantity).max(0) / component.quantity,
+                ),
+                None => Some(0),
+            })
+            .min()
+    }
+
+    /// セット商品を `quantity` 個引き当てる際の構成商品ごとの引当数
+    pub fn component_reservations(
+        &self,
+        quantity: i32,
+    ) -> Result<Vec<(String, i32)>, ProductError> {
+        if quantity <= 0 {
+            return Err(ProductError::InvalidInventoryQuantity);
+        }
+
+        self.components
+            .iter()
+            .map(|component| {
+                component
+                    .quantity
+                    .checked_mul(quantity)
+                    .map(|total| (component.product_id.clone(), total))
+                    .ok_or(ProductError::InvalidInventoryQuantity)
+            })
+            .collect()
+    }
+
+    /// 構成商品の価格の合計に割引率を適用した価格を算出
+    ///
+    /// 固定価格の場合、または構成商品の価格が揃っていない場合は `None` を返す。
+    pub fn discounted_price(&self, component_prices: &HashMap<String, Price>) -> Option<Price> {
+        let discount_rate = match self.pricing {
+            BundlePricing::Fixed => return None,
+            BundlePricing::DiscountedSum { discount_rate } => discount_rate,
+        };
+
+        let mut total = Decimal::ZERO;
+        let mut currency = None;
+        let mut tax_included = true;
+        for component in &self.components {
+            let price = component_prices.get(&component.product_id)?;
+            match currency {
+                None => currency = Some(price.currency.clone()),
+                Some(ref c) if c != &price.currency => return None,
+                Some(_) => {}
+            }
+            tax_included &= price.tax_included;
+            total += price.selling_price * Decimal::from(component.quantity);
+        }
+
+        let currency = currency?;
+        Some(Price {
+            selling_price: Price::round_to_minor_unit(
+                total * (Decimal::ONE - discount_rate),
+                &currency,
+            ),
+            list_price: Some(total),
+            discount_price: None,
+            currency,
+            tax_included,
+            effective_from: None,
+            effective_until: None,
+        })
+    }
+}
+
+impl ProductStatusSchedule {
+    pub fn new(
+        id: String,
+        product_id: String,
+        target_status: ProductStatus,
+        scheduled_at: DateTime<Utc>,
+        reason: Option<String>,
+        created_by: Option<String>,
+    ) -> Result<Self, ProductError> {
+        // Draft へは遷移できないため予約対象外
+        if target_status == ProductStatus::Draft || scheduled_at <= Utc::now() {
+            return Err(ProductError::InvalidSchedule);
+        }
+
+        Ok(ProductStatusSchedule {
+            id,
+            product_id,
+            target_status,
+            scheduled_at,
+            state: ScheduleState::Pending,
+            reason,
+            created_by,
+            executed_at: None,
+            failure_reason: None,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+impl ChangeContext {
+    pub fn new(changed_by: Option<String>, reason: Option<String>) -> Self {
+        Self { changed_by, reason }
+    }
+}
+
+impl FieldChange {
+    /// 値が変わっている場合のみ変更を返す
+    pub fn diff(
+        field_name: impl Into<String>,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Option<Self> {
+        if old_value == new_value {
+            return None;
+        }
+
+        Some(FieldChange {
+            field_name: field_name.into(),
+            old_value,
+            new_value,
+        })
+    }
+
+    /// タグ集合の変更（順序は無視して比較）
+    pub fn tags(old_tags: &[String], new_tags: &[String]) -> Option<Self> {
+        let join = |tags: &[String]| {
+            let mut sorted = tags.to_vec();
+            sorted.sort();
+            sorted.dedup();
+            (!sorted.is_empty()).then(|| sorted.join(","))
+        };
+
+        Self::diff("tags", join(old_tags), join(new_tags))
+    }
+
+    /// 属性ごとの変更（`attributes.<name>`）
+    pub fn attributes(
+        old_attributes: &HashMap<String, String>,
+        new_attributes: &HashMap<String, String>,
+    ) -> Vec<Self> {
+        let names: std::collections::BTreeSet<&String> =
+            old_attributes.keys().chain(new_attributes.keys()).collect();
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                Self::diff(
+                    format!("attributes.{}", name),
+                    old_attributes.get(name).cloned(),
+                    new_attributes.get(name).cloned(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn display_opt<T: ToString>(value: Option<&T>) -> Option<String> {
+    value.map(ToString::to_string)
+}
+
+// 履歴に記録された文字列値を元の型に戻す
+fn parse_history_value<T: std::str::FromStr>(
+    field_name: &str,
+    value: &str,
+) -> Result<T, ProductError> {
+    value.parse().map_err(|_| {
+        ProductError::InvalidRollbackTarget(format!("cannot restore {} from history", field_name))
+    })
+}
+
+fn parse_optional_history_value<T: std::str::FromStr>(
+    field_name: &str,
+    value: Option<&str>,
+) -> Result<Option<T>, ProductError> {
+    value
+        .map(|value| parse_history_value(field_name, value))
+        .transpose()
+}
+
+fn required_history_value<'a>(
+    field_name: &str,
+    value: Option<&'a str>,
+) -> Result<&'a str, ProductError> {
+    value.ok_or_else(|| {
+        ProductError::InvalidRollbackTarget(format!("{} has no value at that point", field_name))
+    })
+}
+
+impl Product {
+    /// 変更前（作成時は `None`）と変更後の商品の差分
+    pub fn field_changes(previous: Option<&Product>, current: &Product) -> Vec<FieldChange> {
+        let dimensions = |p: &Product| {
+            p.dimensions
+                .as_ref()
+                .map(|d| format!("{}x{}x{}", d.width, d.height, d.depth))
+        };
+
+        [
+            FieldChange::diff(
+                "name",
+                previous.map(|p| p.name.clone()),
+                Some(current.name.clone()),
+            ),
+            FieldChange::diff(
+                "slug",
+                previous.map(|p| p.slug.clone()),
+                Some(current.slug.clone()),
+            ),
+            FieldChange::diff(
+                "description",
+                previous.and_then(|p| p.description.clone()),
+                current.description.clone(),
+            ),
+            FieldChange::diff(
+                "sku",
+                previous.map(|p| p.sku.clone()),
+                Some(current.sku.clone()),
+            ),
+            FieldChange::diff(
+                "brand",
+                previous.and_then(|p| p.brand.clone()),
+                current.brand.clone(),
+            ),
+            FieldChange::diff(
+                "status",
+                previous.map(|p| p.status.to_string()),
+                Some(current.status.to_string()),
+            ),
+            FieldChange::diff(
+                "category_id",
+                previous.and_then(|p| p.category_id.clone()),
+                current.category_id.clone(),
+            ),
+            FieldChange::diff(
+                "dimensions",
+                previous.and_then(dimensions),
+                dimensions(current),
+            ),
+            FieldChange::diff(
+                "weight",
+                previous.and_then(|p| display_opt(p.weight.as_ref())),
+                display_opt(current.weight.as_ref()),
+            ),
+            FieldChange::diff(
+                "shipping_info.shipping_class",
+                previous.map(|p| p.shipping_info.shipping_class.clone()),
+                Some(current.shipping_info.shipping_class.clone()),
+            ),
+            FieldChange::diff(
+                "shipping_info.free_shipping",
+                previous.map(|p| p.shipping_info.free_shipping.to_string()),
+                Some(current.shipping_info.free_shipping.to_string()),
+            ),
+            FieldChange::diff(
+                "shipping_info.shipping_fee",
+                previous.map(|p| p.shipping_info.shipping_fee.to_string()),
+                Some(current.shipping_info.shipping_fee.to_string()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// 履歴の値をフィールドに戻す（`field_changes` の逆操作）
+    ///
+    /// ロールバック対象外のフィールド（ステータスなど）は `false` を返す。
+    pub fn restore_field(
+        &mut self,
+        field_name: &str,
+        value: Option<&str>,
+    ) -> Result<bool, ProductError> {
+        match field_name {
+            "name" => self.update_name(required_history_value(field_name, value)?.to_string())?,
+            "slug" => self.update_slug(required_history_value(field_name, value)?.to_string())?,
+            "description" => self.update_description(value.map(str::to_string)),
+            "sku" => self.update_sku(required_history_value(field_name, value)?.to_string())?,
+            "brand" => self.update_brand(value.map(str::to_string)),
+            "category_id" => self.update_category(value.map(str::to_string)),
+            "dimensions" => {
+                let dimensions = match value {
+                    Some(value) => {
+                        let parts: Vec<Decimal> = value
+                            .split('x')
+                            .map(|part| parse_history_value(field_name, part))
+                            .collect::<Result<_, _>>()?;
+                        match parts.as_slice() {
+                            [width, height, depth] => {
+                                Some(Dimensions::new(*width, *height, *depth)?)
+                            }
+                            _ => {
+                                return Err(ProductError::InvalidRollbackTarget(format!(
+                                    "cannot restore {} from history",
+                                    field_name
+                                )))
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                self.update_dimensions(dimensions)?;
+            }
+            "weight" => {
+                self.update_weight(parse_optional_history_value(field_name, value)?)?;
+            }
+            "shipping_info.shipping_class" => {
+                let mut shipping_info = self.shipping_info.clone();
+                shipping_info.shipping_class =
+                    required_history_value(field_name, value)?.to_string();
+                self.update_shipping_info(shipping_info)?;
+            }
+            "shipping_info.free_shipping" => {
+                let mut shipping_info = self.shipping_info.clone();
+                shipping_info.free_shipping =
+                    parse_history_value(field_name, required_history_value(field_name, value)?)?;
+                self.update_shipping_info(shipping_info)?;
+            }
+            "shipping_info.shipping_fee" => {
+                let mut shipping_info = self.shipping_info.clone();
+                shipping_info.shipping_fee =
+                    parse_history_value(field_name, required_history_value(field_name, value)?)?;
+                self.update_shipping_info(shipping_info)?;
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+impl Price {
+    /// 通貨の補助単位の桁数（ISO 4217、未知の通貨は 2 桁）
+    pub fn minor_unit_digits(currency: &str) -> u32 {
+        match currency.to_ascii_uppercase().as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    /// 金額を通貨の補助単位に丸める
+    pub fn round_to_minor_unit(amount: Decimal, currency: &str) -> Decimal {
+        amount.round_dp(Self::minor_unit_digits(currency))
+    }
+
+    /// 価格の差分（`price.<field>`）
+    pub fn field_changes(previous: Option<&Price>, current: &Price) -> Vec<FieldChange> {
+        [
+            FieldChange::diff(
+                "price.selling_price",
+                previous.map(|p| p.selling_price.to_string()),
+                Some(current.selling_price.to_string()),
+            ),
+            FieldChange::diff(
+                "price.list_price",
+                previous.and_then(|p| display_opt(p.list_price.as_ref())),
+                display_opt(current.list_price.as_ref()),
+            ),
+            FieldChange::diff(
+                "price.discount_price",
+                previous.and_then(|p| display_opt(p.discount_price.as_ref())),
+                display_opt(current.discount_price.as_ref()),
+            ),
+            FieldChange::diff(
+                "price.currency",
+                previous.map(|p| p.currency.clone()),
+                Some(current.currency.clone()),
+            ),
+            FieldChange::diff(
+                "price.tax_included",
+                previous.map(|p| p.tax_included.to_string()),
+                Some(current.tax_included.to_string()),
+            ),
+            FieldChange::diff(
+                "price.effective_from",
+                previous.and_then(|p| p.effective_from.map(|d| d.to_rfc3339())),
+                current.effective_from.map(|d| d.to_rfc3339()),
+            ),
+            FieldChange::diff(
+                "price.effective_until",
+                previous.and_then(|p| p.effective_until.map(|d| d.to_rfc3339())),
+                current.effective_until.map(|d| d.to_rfc3339()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// 履歴の値を価格のフィールドに戻す（`price.<field>` 以外は `false`）
+    pub fn restore_field(
+        &mut self,
+        field_name: &str,
+        value: Option<&str>,
+    ) -> Result<bool, ProductError> {
+        match field_name {
+            "price.selling_price" => {
+                self.selling_price =
+                    parse_history_value(field_name, required_history_value(field_name, value)?)?
+            }
+            "price.list_price" => {
+                self.list_price = parse_optional_history_value(field_name, value)?
+            }
+            "price.discount_price" => {
+                self.discount_price = parse_optional_history_value(field_name, value)?
+            }
+            "price.currency" => {
+                self.currency = required_history_value(field_name, value)?.to_string()
+            }
+            "price.tax_included" => {
+                self.tax_included =
+                    parse_history_value(field_name, required_history_value(field_name, value)?)?
+            }
+            "price.effective_from" => {
+                self.effective_from = parse_optional_history_value(field_name, value)?
+            }
+            "price.effective_until" => {
+                self.effective_until = parse_optional_history_value(field_name, value)?
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+impl Inventory {
+    /// 在庫の差分（`inventory.<field>`）
+    pub fn field_changes(previous: Option<&Inventory>, current: &Inventory) -> Vec<FieldChange> {
+        [
+            FieldChange::diff(
+                "inventory.quantity",
+                previous.map(|i| i.quantity.to_string()),
+                Some(current.quantity.to_string()),
+            ),
+            FieldChange::diff(
+                "inventory.reserved_quantity",
+                previous.map(|i| i.reserved_quantity.to_string()),
+                Some(current.reserved_quantity.to_string()),
+            ),
+            FieldChange::diff(
+                "inventory.alert_threshold",
+                previous.and_then(|i| display_opt(i.alert_threshold.as_ref())),
+                display_opt(current.alert_threshold.as_ref()),
+            ),
+            FieldChange::diff(
+                "inventory.track_inventory",
+                previous.map(|i| i.track_inventory.to_string()),
+                Some(current.track_inventory.to_string()),
+            ),
+            FieldChange::diff(
+                "inventory.allow_backorder",
+                previous.map(|i| i.allow_backorder.to_string()),
+                Some(current.allow_backorder.to_string()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+impl ProductImage {
+    /// 画像の差分（`images.<id>.<field>`）。追加時は `previous`、削除時は `current` が `None`
+    pub fn field_changes(
+        previous: Option<&ProductImage>,
+        current: Option<&ProductImage>,
+    ) -> Vec<FieldChange> {
+        let id = match current.or(previous) {
+            Some(image) => image.id.clone(),
+            None => return vec![],
+        };
+
+        [
+            FieldChange::diff(
+                format!("images.{}.url", id),
+                previous.map(|i| i.url.clone()),
+                current.map(|i| i.url.clone()),
+            ),
+            FieldChange::diff(
+                format!("images.{}.alt_text", id),
+                previous.and_then(|i| i.alt_text.clone()),
+                current.and_then(|i| i.alt_text.clone()),
+            ),
+            FieldChange::diff(
+                format!("images.{}.sort_order", id),
+                previous.map(|i| i.sort_order.to_string()),
+                current.map(|i| i.sort_order.to_string()),
+            ),
+            FieldChange::diff(
+                format!("images.{}.is_main", id),
+                previous.map(|i| i.is_main.to_string()),
+                current.map(|i| i.is_main.to_string()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -460,6 +1463,28 @@ mod tests {
         assert_eq!(product.name, "Test Product");
         assert_eq!(product.sku, "TEST-SKU-123");
         assert_eq!(product.status, ProductStatus::Draft);
+        assert_eq!(product.version, 1);
+    }
+
+    #[test]
+    fn test_check_version() {
+        let product = Product::new(
+            "prod_123".to_string(),
+            "Test Product".to_string(),
+            "TEST-SKU-123".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+
+        assert!(product.check_version(None).is_ok());
+        assert!(product.check_version(Some(1)).is_ok());
+        assert_eq!(
+            product.check_version(Some(3)),
+            Err(ProductError::VersionMismatch {
+                expected: 3,
+                actual: 1
+            })
+        );
     }
 
     #[test]
@@ -486,6 +1511,35 @@ mod tests {
         assert!(matches!(result, Err(ProductError::InvalidSku)));
     }
 
+    #[test]
+    fn test_product_slug() {
+        let mut product = Product::new(
+            "prod_123".to_string(),
+            "Test Product".to_string(),
+            "TEST-SKU-123".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        assert_eq!(product.slug, "test-product");
+
+        // 名前から生成できない場合は空のまま
+        let kanji = Product::new(
+            "prod_456".to_string(),
+            "腕時計".to_string(),
+            "TEST-SKU-456".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        assert_eq!(kanji.slug, "");
+
+        assert_eq!(
+            product.update_slug("Test Product".to_string()),
+            Err(ProductError::InvalidSlug)
+        );
+        product.update_slug("test-product-2".to_string()).unwrap();
+        assert_eq!(product.slug, "test-product-2");
+    }
+
     #[test]
     fn test_price_validation() {
         // let price = Price::new(
@@ -526,4 +1580,432 @@ mod tests {
             Err(ProductError::InvalidDimensions)
         ));
     }
+
+    fn apparel_options() -> Vec<ProductOption> {
+        vec![
+            ProductOption {
+                name: "size".to_string(),
+                values: vec!["S".to_string(), "M".to_string(), "L".to_string()],
+            },
+            ProductOption {
+                name: "color".to_string(),
+                values: vec!["Navy".to_string(), "White".to_string()],
+            },
+        ]
+    }
+
+    fn variant(sku: &str, size: &str, color: &str, selling_price: i64) -> ProductVariant {
+        let option_values = BTreeMap::from([
+            ("size".to_string(), size.to_string()),
+            ("color".to_string(), color.to_string()),
+        ]);
+        ProductVariant::new(
+            format!("var_{}", sku),
+            "prod_123".to_string(),
+            sku.to_string(),
+            option_values,
+            Price {
+                selling_price: Decimal::from(selling_price),
+                list_price: None,
+                discount_price: None,
+                currency: "JPY".to_string(),
+                tax_included: true,
+                effective_from: None,
+                effective_until: None,
+            },
+            Inventory {
+                quantity: 5,
+                reserved_quantity: 0,
+                alert_threshold: None,
+                track_inventory: true,
+                allow_backorder: false,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_option_validation() {
+        assert!(ProductOption::validate_all(&apparel_options()).is_ok());
+
+        let mut duplicated = apparel_options();
+        duplicated[1].name = "size".to_string();
+        assert!(matches!(
+            ProductOption::validate_all(&duplicated),
+            Err(ProductError::InvalidVariantOptions)
+        ));
+
+        let empty_values = ProductOption {
+            name: "size".to_string(),
+            values: vec![],
+        };
+        assert!(matches!(
+            empty_values.validate(),
+            Err(ProductError::InvalidVariantOptions)
+        ));
+    }
+
+    #[test]
+    fn test_variant_options_validation() {
+        let options = apparel_options();
+        assert!(variant("TEE-M-NAVY", "M", "Navy", 3000)
+            .validate_options(&options)
+            .is_ok());
+
+        let unknown_value = variant("TEE-XL-NAVY", "XL", "Navy", 3000);
+        assert!(matches!(
+            unknown_value.validate_options(&options),
+            Err(ProductError::InvalidVariantOptions)
+        ));
+
+        let mut missing_option = variant("TEE-M", "M", "Navy", 3000);
+        missing_option.option_values.remove("color");
+        assert!(matches!(
+            missing_option.validate_options(&options),
+            Err(ProductError::InvalidVariantOptions)
+        ));
+    }
+
+    #[test]
+    fn test_variant_combination_uniqueness() {
+        let variants = vec![
+            variant("TEE-M-NAVY", "M", "Navy", 3000),
+            variant("TEE-L-NAVY", "L", "Navy", 3200),
+        ];
+        assert!(ProductVariant::validate_unique_combinations(&variants).is_ok());
+
+        let duplicated = vec![
+            variant("TEE-M-NAVY", "M", "Navy", 3000),
+            variant("TEE-M-NAVY-2", "M", "Navy", 3000),
+        ];
+        assert!(matches!(
+            ProductVariant::validate_unique_combinations(&duplicated),
+            Err(ProductError::VariantCombinationDuplicate)
+        ));
+    }
+
+    #[test]
+    fn test_variant_price_range() {
+        assert!(ProductVariant::price_range(&[]).is_none());
+
+        let variants = vec![
+            variant("TEE-S-WHITE", "S", "White", 2800),
+            variant("TEE-M-NAVY", "M", "Navy", 3000),
+            variant("TEE-L-NAVY", "L", "Navy", 3200),
+        ];
+        let range = ProductVariant::price_range(&variants).unwrap();
+        assert_eq!(range.min, Decimal::from(2800));
+        assert_eq!(range.max, Decimal::from(3200));
+        assert_eq!(range.currency, "JPY");
+
+        let mut mixed = variants;
+        mixed[1].price.currency = "USD".to_string();
+        assert!(ProductVariant::price_range(&mixed).is_none());
+    }
+
+    fn stock(quantity: i32, reserved_quantity: i32) -> Inventory {
+        Inventory {
+            quantity,
+            reserved_quantity,
+            alert_threshold: None,
+            track_inventory: true,
+            allow_backorder: false,
+        }
+    }
+
+    fn gift_set(pricing: BundlePricing) -> ProductBundle {
+        ProductBundle::new(
+            "gift_set".to_string(),
+            vec![
+                BundleComponent {
+                    product_id: "mug".to_string(),
+                    quantity: 2,
+                },
+                BundleComponent {
+                    product_id: "coffee".to_string(),
+                    quantity: 1,
+                },
+            ],
+            pricing,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_validation() {
+        let self_reference = ProductBundle::new(
+            "gift_set".to_string(),
+            vec![BundleComponent {
+                product_id: "gift_set".to_string(),
+                quantity: 1,
+            }],
+            BundlePricing::Fixed,
+        );
+        assert!(matches!(self_reference, Err(ProductError::InvalidBundle)));
+
+        let zero_quantity = ProductBundle::new(
+            "gift_set".to_string(),
+            vec![BundleComponent {
+                product_id: "mug".to_string(),
+                quantity: 0,
+            }],
+            BundlePricing::Fixed,
+        );
+        assert!(matches!(zero_quantity, Err(ProductError::InvalidBundle)));
+
+        let invalid_rate = ProductBundle::new(
+            "gift_set".to_string(),
+            vec![BundleComponent {
+                product_id: "mug".to_string(),
+                quantity: 1,
+            }],
+            BundlePricing::DiscountedSum {
+                discount_rate: Decimal::ONE,
+            },
+        );
+        assert!(matches!(invalid_rate, Err(ProductError::InvalidBundle)));
+    }
+
+    #[test]
+    fn test_bundle_available_quantity() {
+        let bundle = gift_set(BundlePricing::Fixed);
+
+        let inventories = HashMap::from([
+            ("mug".to_string(), stock(9, 2)),
+            ("coffee".to_string(), stock(10, 0)),
+        ]);
+        assert_eq!(bundle.available_quantity(&inventories), Some(3));
+
+        let missing_component = HashMap::from([("mug".to_string(), stock(9, 0))]);
+        assert_eq!(bundle.available_quantity(&missing_component), Some(0));
+
+        let mut untracked = stock(0, 0);
+        untracked.track_inventory = false;
+        let unlimited = HashMap::from([
+            ("mug".to_string(), untracked.clone()),
+            ("coffee".to_string(), untracked),
+        ]);
+        assert_eq!(bundle.available_quantity(&unlimited), None);
+
+        let mut backorder = stock(0, 0);
+        backorder.allow_backorder = true;
+        assert!(!backorder.limits_availability());
+        let backordered = HashMap::from([
+            ("mug".to_string(), backorder),
+            ("coffee".to_string(), stock(4, 0)),
+        ]);
+        assert_eq!(bundle.available_quantity(&backordered), Some(4));
+    }
+
+    #[test]
+    fn test_bundle_component_reservations() {
+        let bundle = gift_set(BundlePricing::Fixed);
+
+        let reservations = bundle.component_reservations(3).unwrap();
+        assert_eq!(
+            reservations,
+            vec![("mug".to_string(), 6), ("coffee".to_string(), 3)]
+        );
+        assert!(matches!(
+            bundle.component_reservations(0),
+            Err(ProductError::InvalidInventoryQuantity)
+        ));
+    }
+
+    #[test]
+    fn test_bundle_discounted_price() {
+        let price = |selling_price: i64| Price {
+            selling_price: Decimal::from(selling_price),
+            list_price: None,
+            discount_price: None,
+            currency: "JPY".to_string(),
+            tax_included: true,
+            effective_from: None,
+            effective_until: None,
+        };
+        let prices = HashMap::from([
+            ("mug".to_string(), price(1500)),
+            ("coffee".to_string(), price(1000)),
+        ]);
+
+        let bundle = gift_set(BundlePricing::DiscountedSum {
+            discount_rate: Decimal::new(1, 1),
+        });
+        let derived = bundle.discounted_price(&prices).unwrap();
+        assert_eq!(derived.list_price, Some(Decimal::from(4000)));
+        assert_eq!(derived.selling_price, Decimal::from(3600));
+
+        let bundle = gift_set(BundlePricing::DiscountedSum {
+            discount_rate: Decimal::new(1234, 4),
+        });
+        assert_eq!(
+            bundle.discounted_price(&prices).unwrap().selling_price,
+            Decimal::from(3506)
+        );
+
+        assert!(gift_set(BundlePricing::Fixed)
+            .discounted_price(&prices)
+            .is_none());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let mut product = Product::new(
+            "1".to_string(),
+            "Test Product".to_string(),
+            "TEST-001".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            product.update_status(ProductStatus::Inactive),
+            Err(ProductError::InvalidStatusTransition { .. })
+        ));
+        assert!(product.update_status(ProductStatus::Active).is_ok());
+        assert!(product.update_status(ProductStatus::Inactive).is_ok());
+        assert!(product.update_status(ProductStatus::Active).is_ok());
+        assert!(product.update_status(ProductStatus::Discontinued).is_ok());
+        assert!(matches!(
+            product.update_status(ProductStatus::Draft),
+            Err(ProductError::InvalidStatusTransition { .. })
+        ));
+
+        product.restore();
+        assert_eq!(product.status, ProductStatus::Inactive);
+    }
+
+    #[test]
+    fn test_validate_activation_reports_missing_fields() {
+        let mut product = Product::new(
+            "1".to_string(),
+            "Test Product".to_string(),
+            "TEST-001".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+
+        match product.validate_activation(false, 0) {
+            Err(ProductError::ActivationRequirementsNotMet(missing)) => {
+                assert_eq!(missing, vec!["price", "images", "category_id"]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        product.update_category(Some("cat-1".to_string()));
+        assert!(product.validate_activation(true, 1).is_ok());
+    }
+
+    #[test]
+    fn test_status_schedule_validation() {
+        let future = Utc::now() + chrono::Duration::hours(1);
+        let past = Utc::now() - chrono::Duration::hours(1);
+
+        let schedule = ProductStatusSchedule::new(
+            "s1".to_string(),
+            "p1".to_string(),
+            ProductStatus::Active,
+            future,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(schedule.state, ScheduleState::Pending);
+        assert_eq!(schedule.scheduled_at, future);
+
+        assert!(matches!(
+            ProductStatusSchedule::new(
+                "s2".to_string(),
+                "p1".to_string(),
+                ProductStatus::Active,
+                past,
+                None,
+                None,
+            ),
+            Err(ProductError::InvalidSchedule)
+        ));
+        assert!(matches!(
+            ProductStatusSchedule::new(
+                "s3".to_string(),
+                "p1".to_string(),
+                ProductStatus::Draft,
+                future,
+                None,
+                None,
+            ),
+            Err(ProductError::InvalidSchedule)
+        ));
+    }
+
+    #[test]
+    fn test_field_changes_only_report_differences() {
+        let before = Product::new(
+            "1".to_string(),
+            "Old Name".to_string(),
+            "TEST-001".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        let mut after = before.clone();
+        after.update_name("New Name".to_string()).unwrap();
+
+        let changes = Product::field_changes(Some(&before), &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field_name, "name");
+        assert_eq!(changes[0].old_value.as_deref(), Some("Old Name"));
+        assert_eq!(changes[0].new_value.as_deref(), Some("New Name"));
+
+        let created = Product::field_changes(None, &after);
+        assert!(created.iter().all(|c| c.old_value.is_none()));
+        assert!(created.iter().any(|c| c.field_name == "sku"));
+
+        assert!(FieldChange::tags(
+            &["b".to_string(), "a".to_string()],
+            &["a".to_string(), "b".to_string()]
+        )
+        .is_none());
+
+        let old_attributes = HashMap::from([("color".to_string(), "red".to_string())]);
+        let new_attributes = HashMap::from([("size".to_string(), "M".to_string())]);
+        let attribute_changes = FieldChange::attributes(&old_attributes, &new_attributes);
+        assert_eq!(attribute_changes.len(), 2);
+        assert_eq!(attribute_changes[0].field_name, "attributes.color");
+        assert_eq!(attribute_changes[0].new_value, None);
+    }
+
+    #[test]
+    fn test_restore_field_reverses_field_changes() {
+        let mut before = Product::new(
+            "1".to_string(),
+            "Name".to_string(),
+            "TEST-001".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        before.dimensions = Some(
+            Dimensions::new(Decimal::new(10, 0), Decimal::new(20, 0), Decimal::new(5, 0)).unwrap(),
+        );
+        before.weight = Some(Decimal::new(150, 1));
+
+        let mut restored = Product::new(
+            "1".to_string(),
+            "Other".to_string(),
+            "TEST-002".to_string(),
+            ProductStatus::Draft,
+        )
+        .unwrap();
+        for change in Product::field_changes(Some(&restored.clone()), &before) {
+            assert!(restored
+                .restore_field(&change.field_name, change.new_value.as_deref())
+                .unwrap());
+        }
+        assert!(Product::field_changes(Some(&before), &restored).is_empty());
+
+        // ステータスはロールバック対象外
+        assert!(!restored.restore_field("status", Some("Active")).unwrap());
+        assert!(matches!(
+            restored.restore_field("weight", Some("heavy")),
+            Err(ProductError::InvalidRollbackTarget(_))
+        ));
+    }
 }
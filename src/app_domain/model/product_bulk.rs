@@ -0,0 +1,245 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::product::{FieldChange, Price, Product, ProductError, ProductStatus};
+
+/// 条件指定の一括更新で各商品に適用する変更
+///
+/// 指定したフィールドだけを変更する。`discount_rate` を指定すると
+/// 販売価格からの割引率（0.1 = 10%引き）で割引価格を設定する。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProductBulkPatch {
+    pub status: Option<ProductStatus>,
+    pub category_id: Option<String>,
+    pub brand: Option<String>,
+    pub selling_price: Option<Decimal>,
+    pub list_price: Option<Decimal>,
+    pub discount_price: Option<Decimal>,
+    pub discount_rate: Option<Decimal>,
+}
+
+/// 1商品に一括更新を適用した結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkChange {
+    /// 変更後の商品（基本情報が変わらない場合は `None`）
+    pub product: Option<Product>,
+    /// 変更後の価格（価格が変わらない場合は `None`）
+    pub price: Option<Price>,
+    pub changes: Vec<FieldChange>,
+}
+
+impl BulkChange {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl ProductBulkPatch {
+    pub fn validate(&self) -> Result<(), ProductError> {
+        if self.status.is_none()
+            && self.category_id.is_none()
+            && self.brand.is_none()
+            && !self.touches_price()
+        {
+            return Err(ProductError::InvalidBulkUpdate(
+                "patch must change at least one field".to_string(),
+            ));
+        }
+
+        if self.discount_price.is_some() && self.discount_rate.is_some() {
+            return Err(ProductError::InvalidBulkUpdate(
+                "discount_price and discount_rate cannot be combined".to_string(),
+            ));
+        }
+
+        if let Some(rate) = self.discount_rate {
+            if rate <= Decimal::ZERO || rate >= Decimal::ONE {
+                return Err(ProductError::InvalidBulkUpdate(
+                    "discount_rate must be greater than 0 and less than 1".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn touches_price(&self) -> bool {
+        self.selling_price.is_some()
+            || self.list_price.is_some()
+            || self.discount_price.is_some()
+            || self.discount_rate.is_some()
+    }
+
+    /// 商品と現在の価格に変更を適用し、変更内容を返す
+    ///
+    /// 状態遷移と価格の整合性は通常の更新と同じ規則で検証する。
+    pub fn apply(
+        &self,
+        product: &Product,
+        price: Option<&Price>,
+    ) -> Result<BulkChange, ProductError> {
+        let mut updated = product.clone();
+        if let Some(status) = &self.status {
+            updated.update_status(status.clone())?;
+        }
+        if let Some(category_id) = &self.category_id {
+            if product.category_id.as_ref() != Some(category_id) {
+                updated.update_category(Some(category_id.clone()));
+            }
+        }
+        if let Some(brand) = &self.brand {
+            if product.brand.as_ref() != Some(brand) {
+                updated.update_brand(Some(brand.clone()));
+            }
+        }
+
+        let mut changes = Product::field_changes(Some(product), &updated);
+        let product = (!changes.is_empty()).then_some(updated);
+
+        let price = match price {
+            _ if !self.touches_price() => None,
+            None => {
+                return Err(ProductError::InvalidBulkUpdate(
+                    "product has no current price".to_string(),
+                ))
+            }
+            Some(current) => {
+                let mut new_price = current.clone();
+                if let Some(selling_price) = self.selling_price {
+                    new_price.selling_price = selling_price;
+                }
+                if let Some(list_price) = self.list_price {
+                    new_price.list_price = Some(list_price);
+                }
+                if let Some(discount_price) = self.discount_price {
+                    new_price.discount_price = Some(discount_price);
+                }
+                if let Some(rate) = self.discount_rate {
+                    new_price.discount_price = Some(Price::round_to_minor_unit(
+                        new_price.selling_price * (Decimal::ONE - rate),
+                        &new_price.currency,
+                    ));
+                }
+                new_price.validate()?;
+
+                let price_changes = Price::field_changes(Some(current), &new_price);
+                (!price_changes.is_empty()).then(|| {
+                    changes.extend(price_changes);
+                    new_price
+                })
+            }
+        };
+
+        Ok(BulkChange {
+            product,
+            price,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product() -> Product {
+        Product::new(
+            "p1".to_string(),
+            "Beach Towel".to_string(),
+            "BT-001".to_string(),
+            ProductStatus::Active,
+        )
+        .unwrap()
+    }
+
+    fn price() -> Price {
+        Price {
+            selling_price: Decimal::new(1980, 0),
+            list_price: None,
+            discount_price: None,
+            currency: "JPY".to_string(),
+            tax_included: true,
+            effective_from: None,
+            effective_until: None,
+        }
+    }
+
+    #[test]
+    fn test_discount_rate_sets_discount_price() {
+        let patch = ProductBulkPatch {
+            discount_rate: Some(Decimal::new(1, 1)),
+            ..Default::default()
+        };
+        patch.validate().unwrap();
+
+        let change = patch.apply(&product(), Some(&price())).unwrap();
+        assert!(change.product.is_none());
+        assert_eq!(
+            change.price.unwrap().discount_price,
+            Some(Decimal::new(1782, 0))
+        );
+        assert_eq!(change.changes.len(), 1);
+        assert_eq!(change.changes[0].field_name, "price.discount_price");
+    }
+
+    #[test]
+    fn test_discount_rate_rounds_to_currency_minor_unit() {
+        let patch = ProductBulkPatch {
+            discount_rate: Some(Decimal::new(13, 2)),
+            ..Default::default()
+        };
+
+        let change = patch.apply(&product(), Some(&price())).unwrap();
+        assert_eq!(
+            change.price.unwrap().discount_price,
+            Some(Decimal::new(1723, 0))
+        );
+
+        let usd = Price {
+            selling_price: Decimal::new(1999, 2),
+            currency: "USD".to_string(),
+            ..price()
+        };
+        let change = patch.apply(&product(), Some(&usd)).unwrap();
+        assert_eq!(
+            change.price.unwrap().discount_price,
+            Some(Decimal::new(1739, 2))
+        );
+    }
+
+    #[test]
+    fn test_unchanged_fields_produce_no_change() {
+        let mut current = product();
+        current.brand = Some("Acme".to_string());
+        let patch = ProductBulkPatch {
+            status: Some(ProductStatus::Active),
+            brand: Some("Acme".to_string()),
+            ..Default::default()
+        };
+
+        let change = patch.apply(&current, None).unwrap();
+        assert!(change.is_empty());
+        assert!(change.product.is_none());
+    }
+
+    #[test]
+    fn test_invalid_patches_are_rejected() {
+        assert!(ProductBulkPatch::default().validate().is_err());
+        assert!(ProductBulkPatch {
+            discount_price: Some(Decimal::new(100, 0)),
+            discount_rate: Some(Decimal::new(1, 1)),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+
+        let draft_only = ProductBulkPatch {
+            status: Some(ProductStatus::Draft),
+            ..Default::default()
+        };
+        assert!(matches!(
+            draft_only.apply(&product(), None),
+            Err(ProductError::InvalidStatusTransition { .. })
+        ));
+    }
+}
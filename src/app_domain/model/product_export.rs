@@ -0,0 +1,388 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use super::product::{Inventory, Price, Product, ProductError};
+use super::product_import::{ATTRIBUTE_COLUMN_PREFIX, TAG_SEPARATOR};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// エクスポートの列
+///
+/// 宣言順が出力順になる。指定順に関わらずこの順で出力し、
+/// `attr:<name>` 列は固定列の後に属性名順で並ぶ。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExportColumn {
+    Id,
+    Sku,
+    Name,
+    Description,
+    Brand,
+    Status,
+    CategoryId,
+    SellingPrice,
+    ListPrice,
+    DiscountPrice,
+    Currency,
+    TaxIncluded,
+    Quantity,
+    ReservedQuantity,
+    AvailableQuantity,
+    AlertThreshold,
+    TrackInventory,
+    AllowBackorder,
+    Tags,
+    MainImageUrl,
+    Attributes,
+    CreatedAt,
+    UpdatedAt,
+    Attribute(String),
+}
+
+impl ExportColumn {
+    /// 列の指定がない場合に出力する列
+    pub const DEFAULT: [ExportColumn; 23] = [
+        ExportColumn::Id,
+        ExportColumn::Sku,
+        ExportColumn::Name,
+        ExportColumn::Description,
+        ExportColumn::Brand,
+        ExportColumn::Status,
+        ExportColumn::CategoryId,
+        ExportColumn::SellingPrice,
+        ExportColumn::ListPrice,
+        ExportColumn::DiscountPrice,
+        ExportColumn::Currency,
+        ExportColumn::TaxIncluded,
+        ExportColumn::Quantity,
+        ExportColumn::ReservedQuantity,
+        ExportColumn::AvailableQuantity,
+        ExportColumn::AlertThreshold,
+        ExportColumn::TrackInventory,
+        ExportColumn::AllowBackorder,
+        ExportColumn::Tags,
+        ExportColumn::MainImageUrl,
+        ExportColumn::Attributes,
+        ExportColumn::CreatedAt,
+        ExportColumn::UpdatedAt,
+    ];
+
+    fn parse(name: &str) -> Option<Self> {
+        if let Some(attribute) = name.strip_prefix(ATTRIBUTE_COLUMN_PREFIX) {
+            let attribute = attribute.trim();
+            return (!attribute.is_empty()).then(|| ExportColumn::Attribute(attribute.to_string()));
+        }
+
+        Self::DEFAULT
+            .into_iter()
+            .find(|column| column.name() == name)
+    }
+
+    /// カンマ区切りの列指定を解釈し、重複を除いて出力順に並べる
+    pub fn parse_list(columns: Option<&str>) -> Result<Vec<Self>, ProductError> {
+        let Some(columns) = columns.filter(|c| !c.trim().is_empty()) else {
+            return Ok(Self::DEFAULT.to_vec());
+        };
+
+        let mut parsed = columns
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                Self::parse(name).ok_or_else(|| {
+                    ProductError::InvalidExport(format!("unknown column '{}'", name))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        parsed.dedup();
+        Ok(parsed)
+    }
+
+    pub fn name(&self) -> String {
+        let name = match self {
+            ExportColumn::Id => "id",
+            ExportColumn::Sku => "sku",
+            ExportColumn::Name => "name",
+            ExportColumn::Description => "description",
+            ExportColumn::Brand => "brand",
+            ExportColumn::Status => "status",
+            ExportColumn::CategoryId => "category_id",
+            ExportColumn::SellingPrice => "selling_price",
+            ExportColumn::ListPrice => "list_price",
+            ExportColumn::DiscountPrice => "discount_price",
+            ExportColumn::Currency => "currency",
+            ExportColumn::TaxIncluded => "tax_included",
+            ExportColumn::Quantity => "quantity",
+            ExportColumn::ReservedQuantity => "reserved_quantity",
+            ExportColumn::AvailableQuantity => "available_quantity",
+            ExportColumn::AlertThreshold => "alert_threshold",
+            ExportColumn::TrackInventory => "track_inventory",
+            ExportColumn::AllowBackorder => "allow_backorder",
+            ExportColumn::Tags => "tags",
+            ExportColumn::MainImageUrl => "main_image_url",
+            ExportColumn::Attributes => "attributes",
+            ExportColumn::CreatedAt => "created_at",
+            ExportColumn::UpdatedAt => "updated_at",
+            ExportColumn::Attribute(name) => return format!("{}{}", ATTRIBUTE_COLUMN_PREFIX, name),
+        };
+        name.to_string()
+    }
+}
+
+/// エクスポートする1商品分のデータ
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductExportRow {
+    pub product: Product,
+    pub price: Option<Price>,
+    pub inventory: Option<Inventory>,
+    pub tags: Vec<String>,
+    pub attributes: BTreeMap<String, String>,
+    pub main_image_url: Option<String>,
+}
+
+impl ProductExportRow {
+    fn value(&self, column: &ExportColumn) -> Value {
+        let product = &self.product;
+        let price = self.price.as_ref();
+        let inventory = self.inventory.as_ref();
+        let text = |value: Option<&String>| value.map_or(Value::Null, |v| Value::from(v.as_str()));
+        let decimal =
+            |value: Option<Decimal>| value.map_or(Value::Null, |v| Value::from(v.to_string()));
+
+        match column {
+            ExportColumn::Id => Value::from(product.id.as_str()),
+            ExportColumn::Sku => Value::from(product.sku.as_str()),
+            ExportColumn::Name => Value::from(product.name.as_str()),
+            ExportColumn::Description => text(product.description.as_ref()),
+            ExportColumn::Brand => text(product.brand.as_ref()),
+            ExportColumn::Status => Value::from(product.status.to_string()),
+            ExportColumn::CategoryId => text(product.category_id.as_ref()),
+            ExportColumn::SellingPrice => decimal(price.map(|p| p.selling_price)),
+            ExportColumn::ListPrice => decimal(price.and_then(|p| p.list_price)),
+            ExportColumn::DiscountPrice => decimal(price.and_then(|p| p.discount_price)),
+            ExportColumn::Currency => text(price.map(|p| &p.currency)),
+            ExportColumn::TaxIncluded => price.map_or(Value::Null, |p| Value::from(p.tax_included)),
+            ExportColumn::Quantity => inventory.map_or(Value::Null, |i| Value::from(i.quantity)),
+            ExportColumn::ReservedQuantity => {
+                inventory.map_or(Value::Null, |i| Value::from(i.reserved_quantity))
+            }
+            ExportColumn::AvailableQuantity => inventory.map_or(Value::Null, |i| {
+                Value::from(i.quantity - i.reserved_quantity)
+            }),
+            ExportColumn::AlertThreshold => inventory
+                .and_then(|i| i.alert_threshold)
+                .map_or(Value::Null, Value::from),
+            ExportColumn::TrackInventory => {
+                inventory.map_or(Value::Null, |i| Value::from(i.track_inventory))
+            }
+            ExportColumn::AllowBackorder => {
+                inventory.map_or(Value::Null, |i| Value::from(i.allow_backorder))
+            }
+            ExportColumn::Tags => Value::from(self.tags.clone()),
+            ExportColumn::MainImageUrl => text(self.main_image_url.as_ref()),
+            ExportColumn::Attributes => Value::Object(
+                self.attributes
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Value::from(value.as_str())))
+                    .collect::<Map<_, _>>(),
+            ),
+            ExportColumn::CreatedAt => Value::from(product.created_at.to_rfc3339()),
+            ExportColumn::UpdatedAt => Value::from(product.updated_at.to_rfc3339()),
+            ExportColumn::Attribute(name) => text(self.attributes.get(name)),
+        }
+    }
+}
+
+/// 選択された列を指定形式の1行に書き出す
+#[derive(Debug, Clone)]
+pub struct ExportWriter {
+    format: ExportFormat,
+    columns: Vec<ExportColumn>,
+}
+
+impl ExportWriter {
+    pub fn new(format: ExportFormat, columns: Vec<ExportColumn>) -> Self {
+        Self { format, columns }
+    }
+
+    /// CSV のヘッダー行（JSON Lines にはヘッダーがない）
+    pub fn header(&self) -> Option<String> {
+        match self.format {
+            ExportFormat::Csv => {
+                let names: Vec<String> =
+                    self.columns.iter().map(|c| csv_field(&c.name())).collect();
+                Some(format!("{}\n", names.join(",")))
+            }
+            ExportFormat::Jsonl => None,
+        }
+    }
+
+    pub fn write_row(&self, row: &ProductExportRow, out: &mut String) {
+        match self.format {
+            ExportFormat::Csv => {
+                let cells: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|column| csv_field(&csv_cell(row.value(column))))
+                    .collect();
+                out.push_str(&cells.join(","));
+            }
+            ExportFormat::Jsonl => {
+                // 列の順序を保つためにキーを順に書き出す
+                out.push('{');
+                for (index, column) in self.columns.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::from(column.name()).to_string());
+                    out.push(':');
+                    out.push_str(&row.value(column).to_string());
+                }
+                out.push('}');
+            }
+        }
+        out.push('\n');
+    }
+}
+
+/// CSV のセルの文字列表現（タグは `|` 区切り、属性は JSON オブジェクト）
+fn csv_cell(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s,
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(&TAG_SEPARATOR.to_string()),
+        other => other.to_string(),
+    }
+}
+
+/// RFC 4180 に従い必要な場合だけ引用符で囲む
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::product::ProductStatus;
+
+    fn row() -> ProductExportRow {
+        let mut product = Product::new(
+            "p1".to_string(),
+            "Notebook, A5".to_string(),
+            "NB-001".to_string(),
+            ProductStatus::Active,
+        )
+        .unwrap();
+        product.description = Some("Say \"hi\"".to_string());
+
+        ProductExportRow {
+            product,
+            price: Some(Price {
+                selling_price: Decimal::new(500, 0),
+                list_price: None,
+                discount_price: None,
+                currency: "JPY".to_string(),
+                tax_included: true,
+                effective_from: None,
+                effective_until: None,
+            }),
+            inventory: Some(Inventory {
+                quantity: 10,
+                reserved_quantity: 4,
+                alert_threshold: None,
+                track_inventory: true,
+                allow_backorder: false,
+            }),
+            tags: vec!["paper".to_string(), "sale".to_string()],
+            attributes: BTreeMap::from([("color".to_string(), "red".to_string())]),
+            main_image_url: None,
+        }
+    }
+
+    #[test]
+    fn test_column_list_uses_stable_order() {
+        let columns =
+            ExportColumn::parse_list(Some("attr:size, selling_price,sku,attr:color,sku")).unwrap();
+        let names: Vec<String> = columns.iter().map(ExportColumn::name).collect();
+        assert_eq!(
+            names,
+            vec!["sku", "selling_price", "attr:color", "attr:size"]
+        );
+
+        assert_eq!(
+            ExportColumn::parse_list(None).unwrap(),
+            ExportColumn::DEFAULT.to_vec()
+        );
+        assert!(matches!(
+            ExportColumn::parse_list(Some("sku,price")),
+            Err(ProductError::InvalidExport(_))
+        ));
+    }
+
+    #[test]
+    fn test_csv_row_escapes_fields() {
+        let columns = ExportColumn::parse_list(Some(
+            "sku,name,description,selling_price,list_price,available_quantity,tags,attr:color",
+        ))
+        .unwrap();
+        let writer = ExportWriter::new(ExportFormat::Csv, columns);
+
+        let mut out = String::new();
+        writer.write_row(&row(), &mut out);
+
+        assert_eq!(
+            writer.header().unwrap(),
+            "sku,name,description,selling_price,list_price,available_quantity,tags,attr:color\n"
+        );
+        assert_eq!(
+            out,
+            "NB-001,\"Notebook, A5\",\"Say \"\"hi\"\"\",500,,6,paper|sale,red\n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_row_keeps_column_order() {
+        let columns = ExportColumn::parse_list(Some("tags,sku,attributes,quantity")).unwrap();
+        let writer = ExportWriter::new(ExportFormat::Jsonl, columns);
+
+        let mut out = String::new();
+        writer.write_row(&row(), &mut out);
+
+        assert!(writer.header().is_none());
+        assert_eq!(
+            out,
+            "{\"sku\":\"NB-001\",\"quantity\":10,\"tags\":[\"paper\",\"sale\"],\"attributes\":{\"color\":\"red\"}}\n"
+        );
+    }
+}
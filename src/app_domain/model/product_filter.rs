@@ -0,0 +1,157 @@
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::product::{ProductError, ProductStatus};
+
+/// 商品の絞り込み条件
+///
+/// 商品検索の条件にブランドとステータスを加えたもので、
+/// エクスポートや条件指定の一括更新の対象を表す。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductFilter {
+    pub query: String,
+    pub category_id: Option<String>,
+    pub brand: Option<String>,
+    pub status: Option<ProductStatus>,
+    pub tags: Vec<String>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub in_stock_only: bool,
+    /// 属性の条件（すべてを満たす商品に絞る）
+    pub attributes: Vec<AttributeFilter>,
+}
+
+/// 商品属性の絞り込み条件（属性名は大文字小文字を区別しない）
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeFilter {
+    pub name: String,
+    pub condition: AttributeCondition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeCondition {
+    /// いずれかの値に一致する（大文字小文字を区別しない）
+    OneOf(Vec<String>),
+    /// 数値として範囲内にある
+    Range {
+        min: Option<Decimal>,
+        max: Option<Decimal>,
+    },
+}
+
+impl ProductFilter {
+    /// ステータスを大文字小文字を区別せずに解釈する
+    pub fn parse_status(status: &str) -> Result<ProductStatus, ProductError> {
+        [
+            ProductStatus::Active,
+            ProductStatus::Inactive,
+            ProductStatus::Draft,
+            ProductStatus::Discontinued,
+        ]
+        .into_iter()
+        .find(|candidate| candidate.to_string().eq_ignore_ascii_case(status.trim()))
+        .ok_or_else(|| ProductError::InvalidFilter(format!("unknown status '{}'", status)))
+    }
+
+    /// カンマ区切りのタグ指定を解釈する
+    pub fn parse_tags(tags: &str) -> Vec<String> {
+        tags.split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    /// カンマ区切りの属性条件を解釈する
+    ///
+    /// `color:red|blue` は値のいずれかに一致、`screen_size>=13`・`screen_size<=16` は数値の範囲を表す。
+    pub fn parse_attributes(attributes: &str) -> Result<Vec<AttributeFilter>, ProductError> {
+        attributes
+            .split(',')
+            .map(str::trim)
+            .filter(|condition| !condition.is_empty())
+            .map(|condition| {
+                let invalid = || {
+                    ProductError::InvalidFilter(format!(
+                        "invalid attribute condition '{}'",
+                        condition
+                    ))
+                };
+                let number = |value: &str| Decimal::from_str(value.trim()).map_err(|_| invalid());
+
+                let (name, condition) = if let Some((name, min)) = condition.split_once(">=") {
+                    let min = Some(number(min)?);
+                    (name, AttributeCondition::Range { min, max: None })
+                } else if let Some((name, max)) = condition.split_once("<=") {
+                    let max = Some(number(max)?);
+                    (name, AttributeCondition::Range { min: None, max })
+                } else if let Some((name, values)) = condition.split_once(':') {
+                    let values: Vec<String> = values
+                        .split('|')
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .collect();
+                    if values.is_empty() {
+                        return Err(invalid());
+                    }
+                    (name, AttributeCondition::OneOf(values))
+                } else {
+                    return Err(invalid());
+                };
+
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(AttributeFilter {
+                    name: name.to_string(),
+                    condition,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attributes() {
+        let filters =
+            ProductFilter::parse_attributes("color:red| Blue , screen_size>=13,screen_size<=16.5")
+                .unwrap();
+        assert_eq!(
+            filters,
+            vec![
+                AttributeFilter {
+                    name: "color".to_string(),
+                    condition: AttributeCondition::OneOf(vec![
+                        "red".to_string(),
+                        "Blue".to_string()
+                    ]),
+                },
+                AttributeFilter {
+                    name: "screen_size".to_string(),
+                    condition: AttributeCondition::Range {
+                        min: Some(Decimal::from(13)),
+                        max: None,
+                    },
+                },
+                AttributeFilter {
+                    name: "screen_size".to_string(),
+                    condition: AttributeCondition::Range {
+                        min: None,
+                        max: Some(Decimal::from_str("16.5").unwrap()),
+                    },
+                },
+            ]
+        );
+
+        for invalid in ["color", "color:", ":red", "size>=large"] {
+            assert!(matches!(
+                ProductFilter::parse_attributes(invalid),
+                Err(ProductError::InvalidFilter(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,775 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use super::product::{Inventory, Price, Product, ProductError, ProductStatus};
+
+/// 属性を表す列名の接頭辞（`attr:color` → 属性 `color`）
+pub const ATTRIBUTE_COLUMN_PREFIX: &str = "attr:";
+/// タグ列で複数のタグを区切る文字
+pub const TAG_SEPARATOR: char = '|';
+/// 新規作成時に通貨が指定されなかった場合の通貨
+pub const DEFAULT_IMPORT_CURRENCY: &str = "JPY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+}
+
+impl ImportFormat {
+    pub fn delimiter(&self) -> u8 {
+        match self {
+            ImportFormat::Csv => b',',
+            ImportFormat::Tsv => b'\t',
+        }
+    }
+}
+
+/// インポートファイルの列
+#[derive(Debug, Clone, PartialEq)]
+enum ImportColumn {
+    Sku,
+    Name,
+    Slug,
+    Description,
+    Brand,
+    CategoryId,
+    SellingPrice,
+    ListPrice,
+    DiscountPrice,
+    Currency,
+    TaxIncluded,
+    Quantity,
+    AlertThreshold,
+    TrackInventory,
+    AllowBackorder,
+    Tags,
+    Attribute(String),
+}
+
+impl ImportColumn {
+    fn parse(name: &str) -> Option<Self> {
+        if let Some(attribute) = name.strip_prefix(ATTRIBUTE_COLUMN_PREFIX) {
+            let attribute = attribute.trim();
+            return (!attribute.is_empty()).then(|| ImportColumn::Attribute(attribute.to_string()));
+        }
+
+        let column = match name {
+            "sku" => ImportColumn::Sku,
+            "name" => ImportColumn::Name,
+            "slug" => ImportColumn::Slug,
+            "description" => ImportColumn::Description,
+            "brand" => ImportColumn::Brand,
+            "category_id" => ImportColumn::CategoryId,
+            "selling_price" => ImportColumn::SellingPrice,
+            "list_price" => ImportColumn::ListPrice,
+            "discount_price" => ImportColumn::DiscountPrice,
+            "currency" => ImportColumn::Currency,
+            "tax_included" => ImportColumn::TaxIncluded,
+            "quantity" => ImportColumn::Quantity,
+            "alert_threshold" => ImportColumn::AlertThreshold,
+            "track_inventory" => ImportColumn::TrackInventory,
+            "allow_backorder" => ImportColumn::AllowBackorder,
+            "tags" => ImportColumn::Tags,
+            _ => return None,
+        };
+        Some(column)
+    }
+
+    fn name(&self) -> String {
+        match self {
+            ImportColumn::Sku => "sku".to_string(),
+            ImportColumn::Name => "name".to_string(),
+            ImportColumn::Slug => "slug".to_string(),
+            ImportColumn::Description => "description".to_string(),
+            ImportColumn::Brand => "brand".to_string(),
+            ImportColumn::CategoryId => "category_id".to_string(),
+            ImportColumn::SellingPrice => "selling_price".to_string(),
+            ImportColumn::ListPrice => "list_price".to_string(),
+            ImportColumn::DiscountPrice => "discount_price".to_string(),
+            ImportColumn::Currency => "currency".to_string(),
+            ImportColumn::TaxIncluded => "tax_included".to_string(),
+            ImportColumn::Quantity => "quantity".to_string(),
+            ImportColumn::AlertThreshold => "alert_threshold".to_string(),
+            ImportColumn::TrackInventory => "track_inventory".to_string(),
+            ImportColumn::AllowBackorder => "allow_backorder".to_string(),
+            ImportColumn::Tags => "tags".to_string(),
+            ImportColumn::Attribute(name) => format!("{}{}", ATTRIBUTE_COLUMN_PREFIX, name),
+        }
+    }
+}
+
+/// 1行目のヘッダーから解釈した列構成
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportHeader {
+    columns: Vec<ImportColumn>,
+}
+
+impl ImportHeader {
+    /// 未知の列・重複した列・`sku` 列の欠落はファイル全体のエラーとする
+    pub fn parse(fields: &[String]) -> Result<Self, ProductError> {
+        let mut columns = Vec::with_capacity(fields.len());
+        for field in fields {
+            let name = field.trim().trim_start_matches('\u{feff}');
+            let column = ImportColumn::parse(name)
+                .ok_or_else(|| ProductError::InvalidImport(format!("unknown column '{}'", name)))?;
+            if columns.contains(&column) {
+                return Err(ProductError::InvalidImport(format!(
+                    "duplicate column '{}'",
+                    name
+                )));
+            }
+            columns.push(column);
+        }
+
+        if !columns.contains(&ImportColumn::Sku) {
+            return Err(ProductError::InvalidImport(
+                "missing required column 'sku'".to_string(),
+            ));
+        }
+
+        Ok(Self { columns })
+    }
+
+    /// データ行を解釈する。値の形式が不正な列はすべてエラーとして返す
+    pub fn record(
+        &self,
+        line: usize,
+        fields: Vec<String>,
+    ) -> Result<ProductImportRecord, Vec<ImportRowError>> {
+        let sku = self
+            .columns
+            .iter()
+            .position(|c| *c == ImportColumn::Sku)
+            .and_then(|i| fields.get(i))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        if fields.len() != self.columns.len() {
+            return Err(vec![ImportRowError::new(
+                line,
+                sku,
+                None,
+                format!(
+                    "expected {} columns, found {}",
+                    self.columns.len(),
+                    fields.len()
+                ),
+            )]);
+        }
+
+        let mut record = ProductImportRecord {
+            line,
+            sku: sku.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        let mut errors = Vec::new();
+
+        for (column, value) in self.columns.iter().zip(fields) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let result = match column {
+                ImportColumn::Sku => Product::validate_sku(value).map_err(|e| e.to_string()),
+                ImportColumn::Name => {
+                    record.name = Some(value.to_string());
+                    Ok(())
+                }
+                ImportColumn::Slug => Product::validate_slug(value)
+                    .map(|_| record.slug = Some(value.to_string()))
+                    .map_err(|e| e.to_string()),
+                ImportColumn::Description => {
+                    record.description = Some(value.to_string());
+                    Ok(())
+                }
+                ImportColumn::Brand => {
+                    record.brand = Some(value.to_string());
+                    Ok(())
+                }
+                ImportColumn::CategoryId => {
+                    record.category_id = Some(value.to_string());
+                    Ok(())
+                }
+                ImportColumn::SellingPrice => {
+                    parse_value(value).map(|v| record.selling_price = Some(v))
+                }
+                ImportColumn::ListPrice => parse_value(value).map(|v| record.list_price = Some(v)),
+                ImportColumn::DiscountPrice => {
+                    parse_value(value).map(|v| record.discount_price = Some(v))
+                }
+                ImportColumn::Currency => {
+                    record.currency = Some(value.to_string());
+                    Ok(())
+                }
+                ImportColumn::TaxIncluded => {
+                    parse_bool(value).map(|v| record.tax_included = Some(v))
+                }
+                ImportColumn::Quantity => parse_value(value).map(|v| record.quantity = Some(v)),
+                ImportColumn::AlertThreshold => {
+                    parse_value(value).map(|v| record.alert_threshold = Some(v))
+                }
+                ImportColumn::TrackInventory => {
+                    parse_bool(value).map(|v| record.track_inventory = Some(v))
+                }
+                ImportColumn::AllowBackorder => {
+                    parse_bool(value).map(|v| record.allow_backorder = Some(v))
+                }
+                ImportColumn::Tags => {
+                    record.tags = Some(split_tags(value));
+                    Ok(())
+                }
+                ImportColumn::Attribute(name) => {
+                    record.attributes.insert(name.clone(), value.to_string());
+                    Ok(())
+                }
+            };
+
+            if let Err(message) = result {
+                errors.push(ImportRowError::new(
+                    line,
+                    sku.clone(),
+                    Some(column.name()),
+                    message,
+                ));
+            }
+        }
+
+        if sku.is_none() {
+            errors.push(ImportRowError::new(
+                line,
+                None,
+                Some("sku".to_string()),
+                ProductError::InvalidSku.to_string(),
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(record)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number", value))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(format!("'{}' is not a valid boolean", value)),
+    }
+}
+
+fn split_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = value
+        .split(TAG_SEPARATOR)
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// インポートの1行。空欄の列は `None`（既存商品では変更しない）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductImportRecord {
+    pub line: usize,
+    pub sku: String,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    pub brand: Option<String>,
+    pub category_id: Option<String>,
+    pub selling_price: Option<Decimal>,
+    pub list_price: Option<Decimal>,
+    pub discount_price: Option<Decimal>,
+    pub currency: Option<String>,
+    pub tax_included: Option<bool>,
+    pub quantity: Option<i32>,
+    pub alert_threshold: Option<i32>,
+    pub track_inventory: Option<bool>,
+    pub allow_backorder: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// 取り込み先の既存商品の現在の状態
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductSnapshot {
+    pub product: Product,
+    pub price: Option<Price>,
+    pub inventory: Option<Inventory>,
+    pub attributes: HashMap<String, String>,
+}
+
+/// 行を現在の状態に適用した結果。`None` の項目は書き込まない
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedImport {
+    pub product: Product,
+    pub is_new: bool,
+    pub product_changed: bool,
+    pub price: Option<Price>,
+    pub inventory: Option<Inventory>,
+    pub tags: Option<Vec<String>>,
+    pub attributes: Option<HashMap<String, String>>,
+}
+
+impl ProductImportRecord {
+    fn has_price_columns(&self) -> bool {
+        self.selling_price.is_some()
+            || self.list_price.is_some()
+            || self.discount_price.is_some()
+            || self.currency.is_some()
+            || self.tax_included.is_some()
+    }
+
+    fn has_inventory_columns(&self) -> bool {
+        self.quantity.is_some()
+            || self.alert_threshold.is_some()
+            || self.track_inventory.is_some()
+            || self.allow_backorder.is_some()
+    }
+
+    fn error(&self, field: &str, error: ProductError) -> ImportRowError {
+        ImportRowError::new(
+            self.line,
+            Some(self.sku.clone()),
+            Some(field.to_string()),
+            error.to_string(),
+        )
+    }
+
+    /// 行を既存商品（無ければ新規商品）に適用し、`Product::validate_*` などの
+    /// 既存の検証規則で検証する。新規商品は Draft として作成し、商品名と販売価格を必須とする
+    pub fn resolve(
+        &self,
+        current: Option<&ProductSnapshot>,
+        new_id: impl FnOnce() -> String,
+    ) -> Result<ResolvedImport, ImportRowError> {
+        let (mut product, is_new) = match current {
+            Some(snapshot) => (snapshot.product.clone(), false),
+            None => {
+                let name = self.name.clone().ok_or_else(|| {
+                    ImportRowError::new(
+                        self.line,
+                        Some(self.sku.clone()),
+                        Some("name".to_string()),
+                        "name is required for new products".to_string(),
+                    )
+                })?;
+                let mut product =
+                    Product::new(new_id(), name, self.sku.clone(), ProductStatus::Draft)
+                        .map_err(|e| self.error("name", e))?;
+                match &self.slug {
+                    Some(slug) => product.slug = slug.clone(),
+                    None if product.slug.is_empty() => {
+                        return Err(self.error("slug", ProductError::SlugRequired));
+                    }
+                    None => (),
+                }
+                (product, true)
+            }
+        };
+
+        // スラッグの変更は旧スラッグのリダイレクトを伴うため、取り込みでは新規商品にだけ設定する
+        if self.slug.as_ref().is_some_and(|slug| *slug != product.slug) {
+            return Err(ImportRowError::new(
+                self.line,
+                Some(self.sku.clone()),
+                Some("slug".to_string()),
+                format!(
+                    "slug of an existing product cannot be changed by import (current: {})",
+                    product.slug
+                ),
+            ));
+        }
+        if let Some(name) = &self.name {
+            product
+                .update_name(name.clone())
+                .map_err(|e| self.error("name", e))?;
+        }
+        if self.description.is_some() {
+            product.update_description(self.description.clone());
+        }
+        if self.brand.is_some() {
+            product.update_brand(self.brand.clone());
+        }
+        if self.category_id.is_some() {
+            product.update_category(self.category_id.clone());
+        }
+        let product_changed = match current {
+            Some(snapshot) => !Product::field_changes(Some(&snapshot.product), &product).is_empty(),
+            None => true,
+        };
+
+        let current_price = current.and_then(|s| s.price.clone());
+        let price = if self.has_price_columns() || current_price.is_none() {
+            let mut price = match (current_price, self.selling_price) {
+                (Some(price), _) => price,
+                (None, Some(selling_price)) => Price {
+                    selling_price,
+                    list_price: None,
+                    discount_price: None,
+                    currency: DEFAULT_IMPORT_CURRENCY.to_string(),
+                    tax_included: true,
+                    effective_from: None,
+                    effective_until: None,
+                },
+                // 価格が未設定の既存商品は、価格列が無ければそのままにする
+                (None, None) if !is_new && !self.has_price_columns() => {
+                    return self.finish(product, is_new, product_changed, None, current);
+                }
+                (None, None) => {
+                    return Err(ImportRowError::new(
+                        self.line,
+                        Some(self.sku.clone()),
+                        Some("selling_price".to_string()),
+                        "selling_price is required for products without a price".to_string(),
+                    ));
+                }
+            };
+            if let Some(selling_price) = self.selling_price {
+                price.selling_price = selling_price;
+            }
+            if self.list_price.is_some() {
+                price.list_price = self.list_price;
+            }
+            if self.discount_price.is_some() {
+                price.discount_price = self.discount_price;
+            }
+            if let Some(currency) = &self.currency {
+                price.currency = currency.clone();
+            }
+            if let Some(tax_included) = self.tax_included {
+                price.tax_included = tax_included;
+            }
+            price.validate().map_err(|e| self.error("price", e))?;
+            Some(price)
+        } else {
+            None
+        };
+
+        self.finish(product, is_new, product_changed, price, current)
+    }
+
+    fn finish(
+        &self,
+        product: Product,
+        is_new: bool,
+        product_changed: bool,
+        price: Option<Price>,
+        current: Option<&ProductSnapshot>,
+    ) -> Result<ResolvedImport, ImportRowError> {
+        let inventory = if self.has_inventory_columns() || is_new {
+            let mut inventory = current
+                .and_then(|s| s.inventory.clone())
+                .unwrap_or(Inventory {
+                    quantity: 0,
+                    reserved_quantity: 0,
+                    alert_threshold: None,
+                    track_inventory: true,
+                    allow_backorder: false,
+                });
+            if let Some(quantity) = self.quantity {
+                inventory
+                    .update_quantity(quantity)
+                    .map_err(|e| self.error("quantity", e))?;
+            }
+            if self.alert_threshold.is_some() {
+                inventory.alert_threshold = self.alert_threshold;
+            }
+            if let Some(track_inventory) = self.track_inventory {
+                inventory.track_inventory = track_inventory;
+            }
+            if let Some(allow_backorder) = self.allow_backorder {
+                inventory.allow_backorder = allow_backorder;
+            }
+            inventory
+                .validate()
+                .map_err(|e| self.error("inventory", e))?;
+            Some(inventory)
+        } else {
+            None
+        };
+
+        // 指定された属性だけを上書きし、それ以外の既存の属性は残す（属性名は大文字小文字を区別しない）
+        let attributes = (!self.attributes.is_empty()).then(|| {
+            let mut attributes = current.map(|s| s.attributes.clone()).unwrap_or_default();
+            attributes.retain(|name, _| {
+                !self
+                    .attributes
+                    .keys()
+                    .any(|imported| imported.eq_ignore_ascii_case(name))
+            });
+            attributes.extend(self.attributes.clone());
+            attributes
+        });
+
+        Ok(ResolvedImport {
+            product,
+            is_new,
+            product_changed,
+            price,
+            inventory,
+            tags: self.tags.clone(),
+            attributes,
+        })
+    }
+}
+
+/// 行単位の検証・取り込みエラー（`line` は 1 始まりでヘッダー行を含む）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRowError {
+    pub line: usize,
+    pub sku: Option<String>,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ImportRowError {
+    pub fn new(line: usize, sku: Option<String>, field: Option<String>, message: String) -> Self {
+        Self {
+            line,
+            sku,
+            field,
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportRowOutcome {
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportJobState {
+    Queued,
+    Running,
+    Completed,
+}
+
+/// 非同期で実行されるインポートジョブの進捗
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductImportJob {
+    pub id: String,
+    pub state: ImportJobState,
+    pub total_rows: usize,
+    pub processed_rows: usize,
+    pub created_count: usize,
+    pub updated_count: usize,
+    pub errors: Vec<ImportRowError>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ProductImportJob {
+    pub fn new(
+        id: String,
+        total_rows: usize,
+        errors: Vec<ImportRowError>,
+        created_by: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            state: ImportJobState::Queued,
+            total_rows,
+            processed_rows: 0,
+            created_count: 0,
+            updated_count: 0,
+            errors,
+            created_by,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    /// 取り込みに失敗した行数（解析時のエラーを含む）
+    pub fn failed_count(&self) -> usize {
+        self.errors
+            .iter()
+            .map(|e| e.line)
+            .collect::<std::collections::BTreeSet<_>>()
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(columns: &[&str]) -> ImportHeader {
+        let fields: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+        ImportHeader::parse(&fields).unwrap()
+    }
+
+    fn fields(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn snapshot() -> ProductSnapshot {
+        ProductSnapshot {
+            product: Product::new(
+                "p1".to_string(),
+                "Notebook".to_string(),
+                "NB-001".to_string(),
+                ProductStatus::Active,
+            )
+            .unwrap(),
+            price: Some(Price {
+                selling_price: Decimal::new(500, 0),
+                list_price: Some(Decimal::new(600, 0)),
+                discount_price: None,
+                currency: "JPY".to_string(),
+                tax_included: true,
+                effective_from: None,
+                effective_until: None,
+            }),
+            inventory: Some(Inventory {
+                quantity: 10,
+                reserved_quantity: 4,
+                alert_threshold: None,
+                track_inventory: true,
+                allow_backorder: false,
+            }),
+            attributes: HashMap::from([("color".to_string(), "red".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_header_rejects_unknown_and_missing_columns() {
+        assert!(matches!(
+            ImportHeader::parse(&fields(&["sku", "colour"])),
+            Err(ProductError::InvalidImport(_))
+        ));
+        assert!(matches!(
+            ImportHeader::parse(&fields(&["name"])),
+            Err(ProductError::InvalidImport(_))
+        ));
+        assert!(ImportHeader::parse(&fields(&["\u{feff}sku", "attr:size"])).is_ok());
+    }
+
+    #[test]
+    fn test_record_reports_every_invalid_column() {
+        let header = header(&["sku", "selling_price", "quantity", "tax_included"]);
+        let errors = header
+            .record(2, fields(&["NB-001", "abc", "1.5", "maybe"]))
+            .unwrap_err();
+        let columns: Vec<_> = errors.iter().filter_map(|e| e.field.clone()).collect();
+        assert_eq!(columns, vec!["selling_price", "quantity", "tax_included"]);
+        assert!(errors.iter().all(|e| e.line == 2));
+
+        let errors = header.record(3, fields(&["NB-001", "1"])).unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_new_product_requires_name_and_price() {
+        let header = header(&["sku", "name", "selling_price", "tags"]);
+        let record = header
+            .record(2, fields(&["NB-002", "", "100", "b|a|a"]))
+            .unwrap();
+        let error = record.resolve(None, || "new".to_string()).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("name"));
+
+        let record = header
+            .record(2, fields(&["NB-002", "Pen", "", ""]))
+            .unwrap();
+        let error = record.resolve(None, || "new".to_string()).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("selling_price"));
+
+        let record = header
+            .record(2, fields(&["NB-002", "Pen", "100", "b|a|a"]))
+            .unwrap();
+        let resolved = record.resolve(None, || "new".to_string()).unwrap();
+        assert!(resolved.is_new);
+        assert_eq!(resolved.product.id, "new");
+        assert_eq!(resolved.product.status, ProductStatus::Draft);
+        assert_eq!(resolved.price.unwrap().currency, DEFAULT_IMPORT_CURRENCY);
+        assert_eq!(resolved.inventory.unwrap().quantity, 0);
+        assert_eq!(resolved.tags, Some(vec!["a".to_string(), "b".to_string()]));
+    }
+
+    #[test]
+    fn test_resolve_requires_slug_when_name_has_no_reading() {
+        let header = header(&["sku", "name", "slug", "selling_price"]);
+        let record = header
+            .record(2, fields(&["NB-002", "万年筆", "", "100"]))
+            .unwrap();
+        let error = record.resolve(None, || "new".to_string()).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("slug"));
+
+        let record = header
+            .record(2, fields(&["NB-002", "万年筆", "mannenhitsu", "100"]))
+            .unwrap();
+        let resolved = record.resolve(None, || "new".to_string()).unwrap();
+        assert_eq!(resolved.product.slug, "mannenhitsu");
+
+        let errors = header
+            .record(3, fields(&["NB-003", "Pen", "Fountain Pen", "100"]))
+            .unwrap_err();
+        assert_eq!(errors[0].field.as_deref(), Some("slug"));
+
+        // 既存商品のスラッグは取り込みでは変更しない
+        let record = header
+            .record(4, fields(&["NB-001", "", "other", ""]))
+            .unwrap();
+        let error = record
+            .resolve(Some(&snapshot()), || unreachable!())
+            .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("slug"));
+    }
+
+    #[test]
+    fn test_resolve_merges_into_existing_product() {
+        let header = header(&["sku", "name", "discount_price", "quantity", "attr:size"]);
+        let record = header
+            .record(2, fields(&["NB-001", "", "450", "", "A4"]))
+            .unwrap();
+        let resolved = record
+            .resolve(Some(&snapshot()), || unreachable!())
+            .unwrap();
+
+        assert!(!resolved.is_new);
+        assert!(!resolved.product_changed);
+        let price = resolved.price.unwrap();
+        assert_eq!(price.selling_price, Decimal::new(500, 0));
+        assert_eq!(price.discount_price, Some(Decimal::new(450, 0)));
+        assert!(resolved.inventory.is_none());
+        assert_eq!(resolved.attributes.unwrap().len(), 2);
+
+        // 引当済みの数量を下回る在庫は既存の検証規則で拒否される
+        let record = header
+            .record(3, fields(&["NB-001", "", "", "3", ""]))
+            .unwrap();
+        let error = record
+            .resolve(Some(&snapshot()), || unreachable!())
+            .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("quantity"));
+
+        let record = header
+            .record(4, fields(&["NB-001", "", "700", "", ""]))
+            .unwrap();
+        let error = record
+            .resolve(Some(&snapshot()), || unreachable!())
+            .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("price"));
+    }
+}
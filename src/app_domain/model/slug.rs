@@ -0,0 +1,323 @@
+/// スラッグの最大長
+pub const MAX_SLUG_LENGTH: usize = 100;
+
+/// 名前から URL 用のスラッグ（英小文字・数字・ハイフン）を生成する
+///
+/// かなはヘボン式のローマ字に、全角英数字は半角に変換する（半角カナは全角にそろえてから読む）。
+/// 漢字など読みを決められない文字を含む場合や、変換できる文字がない場合は `None` を返す。
+/// 読みの一部を落とした不完全なスラッグを作らないよう、呼び出し元はスラッグの指定を求める。
+pub fn slugify(text: &str) -> Option<String> {
+    let chars: Vec<char> = to_fullwidth_kana(text)
+        .into_iter()
+        .map(to_halfwidth)
+        .collect();
+    let mut slug = String::new();
+    let mut sokuon = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(romaji) = kana_romaji(c) {
+            let mut romaji = romaji.to_string();
+            // 拗音（きゃ）や小さい母音（ふぁ）は前のかなとまとめて読む
+            if let Some(combined) = chars.get(i + 1).and_then(|&next| combine(&romaji, next)) {
+                romaji = combined;
+                i += 1;
+            }
+            // 促音は次の子音を重ねる（っち は tchi）
+            if std::mem::take(&mut sokuon) {
+                match romaji.chars().next() {
+                    Some(_) if romaji.starts_with("ch") => slug.push('t'),
+                    Some(first) if !"aiueon".contains(first) => slug.push(first),
+                    _ => (),
+                }
+            }
+            slug.push_str(&romaji);
+        } else if c == 'っ' || c == 'ッ' {
+            sokuon = true;
+        } else if c == 'ー' || c == '\'' || c == '’' {
+            // 長音とアポストロフィは読みに含めない
+        } else if c.is_ascii_alphanumeric() {
+            sokuon = false;
+            slug.push(c.to_ascii_lowercase());
+        } else if let Some(folded) = fold_latin(c) {
+            sokuon = false;
+            slug.push_str(folded);
+        } else if c.is_alphanumeric() {
+            return None;
+        } else {
+            sokuon = false;
+            if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        i += 1;
+    }
+
+    slug.truncate(MAX_SLUG_LENGTH);
+    let slug = slug.trim_end_matches('-');
+    (!slug.is_empty()).then(|| slug.to_string())
+}
+
+/// 英小文字・数字をハイフンで区切った形式か（先頭・末尾・連続するハイフンは不可）
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= MAX_SLUG_LENGTH
+        && slug.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        })
+}
+
+/// 重複を避けるため `n` 番目の候補（2 番目以降は `-2` などの連番付き）を返す
+pub fn with_suffix(base: &str, n: usize) -> String {
+    if n <= 1 {
+        return base.to_string();
+    }
+    let suffix = format!("-{}", n);
+    let mut base = base[..base.len().min(MAX_SLUG_LENGTH - suffix.len())].to_string();
+    while base.ends_with('-') {
+        base.pop();
+    }
+    base + &suffix
+}
+
+/// 全角英数字を半角に、カタカナをひらがなにそろえる
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 半角カナ（U+FF66〜U+FF9D）の全角カタカナ
+const HALFWIDTH_KANA: &str =
+    "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+/// 半角カナを全角カタカナに変換し、続く濁点（ﾞ）・半濁点（ﾟ）を前のかなと合成する
+fn to_fullwidth_kana(text: &str) -> Vec<char> {
+    let mut chars: Vec<char> = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{FF66}'..='\u{FF9D}' => {
+                chars.extend(HALFWIDTH_KANA.chars().nth(c as usize - 0xFF66))
+            }
+            '\u{FF9E}' | '\u{FF9F}' => {
+                let voiced = chars.last().and_then(|&prev| match c {
+                    '\u{FF9E}' if prev == 'ウ' => Some('ヴ'),
+                    '\u{FF9E}' if "カキクケコサシスセソタチツテトハヒフヘホ".contains(prev) => {
+                        char::from_u32(prev as u32 + 1)
+                    }
+                    '\u{FF9F}' if "ハヒフヘホ".contains(prev) => {
+                        char::from_u32(prev as u32 + 2)
+                    }
+                    _ => None,
+                });
+                // 合成できない濁点・半濁点は読みに含めない
+                if let Some(voiced) = voiced {
+                    chars.pop();
+                    chars.push(voiced);
+                }
+            }
+            _ => chars.push(c),
+        }
+    }
+    chars
+}
+
+fn kana_romaji(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ゔ' => "vu",
+        _ => return None,
+    };
+    Some(romaji)
+}
+
+/// 前のかなの読みと小書きのかなを合わせた読み（合わせられない場合は None）
+fn combine(romaji: &str, small: char) -> Option<String> {
+    let vowel = match small {
+        'ゃ' => "ya",
+        'ゅ' => "yu",
+        'ょ' => "yo",
+        'ぁ' => "a",
+        'ぃ' => "i",
+        'ぅ' => "u",
+        'ぇ' => "e",
+        'ぉ' => "o",
+        _ => return None,
+    };
+    if romaji.len() < 2 {
+        // うぃ・うぇ・うぉ は wi・we・wo
+        return (romaji == "u" && vowel.len() == 1).then(|| format!("w{}", vowel));
+    }
+
+    let stem = &romaji[..romaji.len() - 1];
+    match vowel.strip_prefix('y') {
+        // しゃ・ちゃ・じゃ は y を挟まない
+        Some(vowel) if romaji.ends_with('i') => match stem {
+            "sh" | "ch" | "j" => Some(format!("{}{}", stem, vowel)),
+            _ => Some(format!("{}y{}", stem, vowel)),
+        },
+        Some(_) => None,
+        None => Some(format!("{}{}", stem, vowel)),
+    }
+}
+
+/// アクセント付きのラテン文字を ASCII に寄せる
+fn fold_latin(c: char) -> Option<&'static str> {
+    let folded = match c.to_lowercase().next().unwrap_or(c) {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è' | 'é' | 'ê' | 'ë' => "e",
+        'ì' | 'í' | 'î' | 'ï' => "i",
+        'ñ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'œ' => "oe",
+        'ß' => "ss",
+        'ù' | 'ú' | 'û' | 'ü' => "u",
+        'ý' | 'ÿ' => "y",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slug(text: &str) -> String {
+        slugify(text).unwrap()
+    }
+
+    #[test]
+    fn test_slugify_ascii() {
+        assert_eq!(slug("Audio & Headphones"), "audio-headphones");
+        assert_eq!(slug("  Men's Shoes!  "), "mens-shoes");
+        assert_eq!(slug("Ｔシャツ　ＸＬ"), "tshatsu-xl");
+        assert_eq!(slug("Café Crème"), "cafe-creme");
+    }
+
+    #[test]
+    fn test_slugify_transliterates_kana() {
+        assert_eq!(slug("ヘッドホン"), "heddohon");
+        assert_eq!(slug("きっちん"), "kitchin");
+        assert_eq!(slug("しゃしん・きょうざい"), "shashin-kyouzai");
+        assert_eq!(slug("ファッション"), "fasshon");
+        assert_eq!(slug("ウォッチ"), "wotchi");
+        assert_eq!(slug("コーヒー"), "kohi");
+    }
+
+    #[test]
+    fn test_slugify_normalizes_halfwidth_kana() {
+        assert_eq!(slug("ﾍｯﾄﾞﾎﾝ"), "heddohon");
+        assert_eq!(slug("ｺｰﾋｰ ﾎﾟｯﾄ"), "kohi-potto");
+        assert_eq!(slug("ｳﾞｨﾝﾃｰｼﾞ"), slug("ヴィンテージ"));
+    }
+
+    #[test]
+    fn test_slugify_rejects_names_without_reading() {
+        assert_eq!(slugify("家電"), None);
+        assert_eq!(slugify("オーディオ機器"), None);
+        assert_eq!(slugify("Смартфон"), None);
+        assert_eq!(slugify("!!!"), None);
+    }
+
+    #[test]
+    fn test_slugify_truncates() {
+        let slug = slug(&"ab ".repeat(60));
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert!(is_valid_slug(&slug));
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("audio-2"));
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Audio"));
+        assert!(!is_valid_slug("audio--2"));
+        assert!(!is_valid_slug("-audio"));
+        assert!(!is_valid_slug("ヘッドホン"));
+    }
+
+    #[test]
+    fn test_with_suffix() {
+        assert_eq!(with_suffix("audio", 1), "audio");
+        assert_eq!(with_suffix("audio", 3), "audio-3");
+        assert_eq!(
+            with_suffix(&"a".repeat(MAX_SLUG_LENGTH), 2).len(),
+            MAX_SLUG_LENGTH
+        );
+    }
+}
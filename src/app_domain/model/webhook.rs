@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_domain::model::domain_event::DomainEventType;
+
+/// ドメインイベントを受け取る Webhook の購読
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    /// 受け取るイベント種別（空の場合はすべて）
+    pub event_types: Vec<DomainEventType>,
+    /// 署名（`X-Webhook-Signature`）に使う共有シークレット
+    pub secret: String,
+    pub is_active: bool,
+    /// 連続して配信に失敗した回数（成功するとリセットされる）
+    pub consecutive_failures: i32,
+    /// 連続失敗で自動的に無効化された日時
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    pub fn new(url: String, event_types: Vec<DomainEventType>, secret: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            url,
+            event_types,
+            secret,
+            is_active: true,
+            consecutive_failures: 0,
+            disabled_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// このイベント種別を配信対象とするか
+    pub fn accepts(&self, event_type: DomainEventType) -> bool {
+        self.event_types.is_empty() || self.event_types.contains(&event_type)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let host = self
+            .url
+            .strip_prefix("https://")
+            .or_else(|| self.url.strip_prefix("http://"))
+            .ok_or_else(|| "URL は http:// または https:// で始まる必要があります".to_string())?;
+        if host.is_empty() || host.starts_with('/') {
+            return Err("URL にホスト名がありません".to_string());
+        }
+        if self.secret.trim().is_empty() {
+            return Err("シークレットは空にできません".to_string());
+        }
+        Ok(())
+    }
+
+    /// 有効・無効を切り替える（有効に戻した場合は連続失敗回数もリセットする）
+    pub fn set_active(&mut self, is_active: bool) {
+        if is_active && !self.is_active {
+            self.consecutive_failures = 0;
+            self.disabled_at = None;
+        }
+        self.is_active = is_active;
+    }
+}
+
+/// 1 回の配信試行の結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryAttempt {
+    /// 応答がなかった場合（接続エラー・タイムアウト）は `None`
+    pub status_code: Option<i32>,
+    pub latency_ms: i64,
+    /// 失敗した場合の理由
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// 購読ごと・イベントごとの配信記録
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub subscription_id: String,
+    pub event_id: i64,
+    pub event_type: DomainEventType,
+    /// 送信したリクエストボディ（再送にも使う）
+    pub payload: Value,
+    pub attempts: i32,
+    pub status_code: Option<i32>,
+    pub latency_ms: Option<i64>,
+    pub last_error: Option<String>,
+    pub succeeded: bool,
+    /// 失敗した配信を自動で再試行する日時（配信済みの場合は `None`）
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    /// 配信順序を保つ単位となる集約（ペイロードの `aggregate_type` と `aggregate_id`）
+    pub fn aggregate(&self) -> (&str, &str) {
+        let field = |name: &str| self.payload[name].as_str().unwrap_or_default();
+        (field("aggregate_type"), field("aggregate_id"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(url: &str) -> WebhookSubscription {
+        WebhookSubscription::new(url.to_string(), Vec::new(), "secret".to_string())
+    }
+
+    #[test]
+    fn test_accepts_filters_event_types() {
+        let mut subscription = subscription("https://example.com/hooks");
+        assert!(subscription.accepts(DomainEventType::ItemDeleted));
+
+        subscription.event_types = vec![DomainEventType::PriceChanged];
+        assert!(subscription.accepts(DomainEventType::PriceChanged));
+        assert!(!subscription.accepts(DomainEventType::ItemDeleted));
+    }
+
+    #[test]
+    fn test_validate_requires_http_url() {
+        assert!(subscription("https://example.com/hooks").validate().is_ok());
+        assert!(subscription("http://127.0.0.1:8080").validate().is_ok());
+        assert!(subscription("ftp://example.com").validate().is_err());
+        assert!(subscription("https:///path").validate().is_err());
+    }
+
+    #[test]
+    fn test_reactivation_resets_failures() {
+        let mut subscription = subscription("https://example.com/hooks");
+        subscription.is_active = false;
+        subscription.consecutive_failures = 5;
+        subscription.disabled_at = Some(Utc::now());
+
+        subscription.set_active(true);
+        assert!(subscription.is_active);
+        assert_eq!(subscription.consecutive_failures, 0);
+        assert!(subscription.disabled_at.is_none());
+    }
+}
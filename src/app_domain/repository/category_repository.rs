@@ -1,6 +1,13 @@
-use crate::app_domain::model::category::{Category, CategoryError, CategoryPath, CategoryTree};
+use crate::app_domain::model::attribute_schema::{AttributeDefinition, AttributeSchema};
+use crate::app_domain::model::category::{
+    Category, CategoryError, CategoryPath, CategoryRedirect, CategoryTree, ProductCount,
+    ProductDisposition,
+};
+use crate::app_domain::model::localization::CategoryTranslation;
+use crate::app_domain::model::product::ChangeContext;
 use async_trait::async_trait;
 use mockall::automock;
+use std::collections::HashMap;
 
 #[cfg(test)]
 pub use mockall::predicate;
@@ -15,8 +22,21 @@ pub trait CategoryRepository: Send + Sync {
         parent_id: Option<String>,
         include_inactive: bool,
     ) -> Vec<Category>;
+    /// 親の下（`None` ならルート）で現在のスラッグが一致するカテゴリを返す
+    async fn find_by_slug(&self, parent_id: Option<String>, slug: &str) -> Option<Category>;
+    /// 変更・移動・統合前のスラッグが一致するカテゴリを返す（旧 URL の転送用）
+    async fn find_by_old_slug(
+        &self,
+        parent_id: Option<String>,
+        slug: &str,
+    ) -> Option<CategoryRedirect>;
     // async fn find_children(&self, id: &str, include_inactive: bool) -> Vec<Category>;
+    #[allow(dead_code)]
     async fn find_path(&self, id: &str) -> Result<CategoryPath, CategoryError>;
+    /// ルートから自分自身までのカテゴリを順に返す（存在しない場合は空）
+    async fn find_ancestors(&self, id: &str) -> Result<Vec<Category>, CategoryError>;
+    /// 自分自身を除く子孫カテゴリを浅い順に返す
+    async fn find_descendants(&self, id: &str, include_inactive: bool) -> Vec<Category>;
     async fn find_tree(&self, include_inactive: bool) -> Vec<CategoryTree>;
     async fn exists_by_name_and_parent(
         &self,
@@ -26,16 +46,102 @@ pub trait CategoryRepository: Send + Sync {
     ) -> bool;
     async fn create(&self, category: Category) -> Result<Category, CategoryError>;
     async fn update(&self, category: Category) -> Result<Category, CategoryError>;
-    async fn delete(&self, id: &str) -> Result<bool, CategoryError>;
+    /// 子カテゴリまたは商品が紐づく場合は削除しない
+    ///
+    /// `expected_version` を指定した場合、現在のバージョンと一致しなければ
+    /// `CategoryError::VersionMismatch` を返す（照合と削除は同じトランザクションで行う）。
+    async fn delete(
+        &self,
+        id: &str,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<bool, CategoryError>;
+    /// 紐づく商品を付け替える、または非アクティブにしてから同じトランザクションで削除する
+    ///
+    /// 商品の変更履歴には `ctx` の操作者と変更理由を記録する。
+    async fn delete_with_products(
+        &self,
+        id: &str,
+        disposition: ProductDisposition,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<bool, CategoryError>;
+    /// サブツリーごと移動し、移動元と移動先の兄弟の並び順を連番に振り直す
+    ///
+    /// `expected_version` を指定した場合、ロックしたカテゴリのバージョンと一致しなければ
+    /// `CategoryError::VersionMismatch` を返す。
     async fn move_category(
         &self,
         id: &str,
         new_parent_id: Option<String>,
         new_sort_order: i32,
+        expected_version: Option<i64>,
+    ) -> Result<Category, CategoryError>;
+    /// サブツリーを新しい ID で複製し、複製したルートを返す（商品は複製しない）
+    async fn copy_subtree(
+        &self,
+        id: &str,
+        new_parent_id: Option<String>,
+        new_name: Option<String>,
+        new_sort_order: i32,
+    ) -> Result<Category, CategoryError>;
+    /// 統合元の子カテゴリと商品を統合先へ付け替えてから統合元を削除し、統合先を返す
+    ///
+    /// 商品の変更履歴には `ctx` の操作者と変更理由を記録する。
+    async fn merge_category(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        ctx: &ChangeContext,
     ) -> Result<Category, CategoryError>;
+    /// 親の下の子カテゴリ（`None` ならルート）を指定された ID の順に並べ替え、並べ替え後の一覧を返す
+    ///
+    /// ID の一覧は現在の子カテゴリとちょうど一致しなければならない。
+    async fn reorder_children(
+        &self,
+        parent_id: Option<String>,
+        child_ids: Vec<String>,
+    ) -> Result<Vec<Category>, CategoryError>;
+    /// カテゴリ自身で定義した商品属性を並び順に返す
+    async fn find_attribute_definitions(
+        &self,
+        id: &str,
+    ) -> Result<Vec<AttributeDefinition>, CategoryError>;
+    /// 祖先カテゴリから継承したものを含む商品属性の定義を返す
+    async fn find_attribute_schema(&self, id: &str) -> Result<AttributeSchema, CategoryError>;
+    /// カテゴリ自身の商品属性の定義を指定された順に置き換える（子孫カテゴリはこれを継承する）
+    async fn replace_attribute_definitions(
+        &self,
+        id: &str,
+        definitions: Vec<AttributeDefinition>,
+    ) -> Result<Vec<AttributeDefinition>, CategoryError>;
+    /// カテゴリのロケールごとの翻訳をロケール順に返す
+    async fn find_translations(&self, id: &str) -> Result<Vec<CategoryTranslation>, CategoryError>;
+    /// ロケールの翻訳をカテゴリ ID ごとに返す（表示用のため、取得に失敗した場合は空）
+    async fn find_translations_by_locale(
+        &self,
+        locale: &str,
+    ) -> HashMap<String, CategoryTranslation>;
+    /// 指定したカテゴリのロケールの翻訳をカテゴリ ID ごとに返す（表示用のため、取得に失敗した場合は空）
+    async fn find_translations_for(
+        &self,
+        ids: &[String],
+        locale: &str,
+    ) -> HashMap<String, CategoryTranslation>;
+    /// ロケールの翻訳を置き換える
+    async fn upsert_translation(
+        &self,
+        id: &str,
+        translation: CategoryTranslation,
+    ) -> Result<(), CategoryError>;
+    /// 翻訳を削除する（削除した場合は `true`）
+    async fn delete_translation(&self, id: &str, locale: &str) -> Result<bool, CategoryError>;
     async fn count_children(&self, id: &str) -> i64;
-    // async fn count_products(&self, id: &str) -> i64;
+    async fn count_products(&self, id: &str) -> ProductCount;
+    /// 作成・移動は同じ検証をトランザクション内で行うため、ここは事前確認したい呼び出し元向け
+    #[allow(dead_code)]
     async fn validate_depth(&self, parent_id: Option<String>) -> Result<(), CategoryError>;
+    #[allow(dead_code)]
     async fn validate_circular_reference(
         &self,
         id: &str,
@@ -55,12 +161,14 @@ mod tests {
         let category = Category {
             id: "cat_123".to_string(),
             name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         mock_repo
@@ -84,22 +192,26 @@ mod tests {
             Category {
                 id: "cat_1".to_string(),
                 name: "Category 1".to_string(),
+                slug: "category-1".to_string(),
                 description: None,
                 parent_id: None,
                 sort_order: 1,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
+                version: 1,
             },
             Category {
                 id: "cat_2".to_string(),
                 name: "Category 2".to_string(),
+                slug: "category-2".to_string(),
                 description: None,
                 parent_id: None,
                 sort_order: 2,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
+                version: 1,
             },
         ];
 
@@ -121,12 +233,14 @@ mod tests {
         let new_category = Category {
             id: "cat_new".to_string(),
             name: "New Category".to_string(),
+            slug: "new-category".to_string(),
             description: None,
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         let expected_category = new_category.clone();
@@ -0,0 +1,25 @@
+use crate::app_domain::model::idempotency::{IdempotencyKey, Reservation, StoredResponse};
+use crate::infrastructure::error::AppResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+
+#[automock]
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// 未使用または期限切れのキーを処理中として確保する。
+    /// 有効期限内の記録が既にあればそれを返す。
+    async fn reserve(
+        &self,
+        key: &IdempotencyKey,
+        fingerprint: &str,
+        request_path: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<Reservation>;
+    /// 処理結果を保存し、以降の再送で再生できるようにする
+    async fn complete(&self, key: &IdempotencyKey, response: StoredResponse) -> AppResult<()>;
+    /// 処理が失敗した場合にキーを解放し、再試行できるようにする
+    async fn release(&self, key: &IdempotencyKey) -> AppResult<()>;
+    /// `now` の時点で期限切れの記録を削除し、削除した件数を返す
+    async fn purge_expired(&self, now: DateTime<Utc>) -> AppResult<u64>;
+}
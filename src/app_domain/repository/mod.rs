@@ -1,3 +1,6 @@
 pub mod category_repository;
+pub mod idempotency_repository;
 pub mod item_repository;
+pub mod outbox_repository;
 pub mod product_repository;
+pub mod webhook_repository;
@@ -0,0 +1,28 @@
+use crate::app_domain::model::domain_event::OutboxEvent;
+use crate::infrastructure::error::AppResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+
+/// outbox に書き込まれたイベントを配信するためのリポジトリ
+///
+/// イベントの書き込みは業務データと同じトランザクションで各リポジトリが行う。
+#[automock]
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// 集約ごとに最も古い未配信イベントを配信中として確保する。
+    /// 同じ集約の後続イベントは先頭が配信済みになるまで取得されない。
+    /// `claim_timeout_seconds` を過ぎた確保は停止したディスパッチャーのものとみなして再取得する。
+    async fn claim_pending(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        claim_timeout_seconds: i64,
+    ) -> AppResult<Vec<OutboxEvent>>;
+    /// 配信済みにする
+    async fn mark_published(&self, id: i64) -> AppResult<()>;
+    /// 確保を解除し、`retry_at` 以降に再配信する
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: DateTime<Utc>) -> AppResult<()>;
+    /// `before` より前に配信済みになったイベントを削除し、削除件数を返す
+    async fn purge_published(&self, before: DateTime<Utc>) -> AppResult<u64>;
+}
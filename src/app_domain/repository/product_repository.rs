@@ -1,8 +1,18 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
+use crate::app_domain::model::attribute_schema::AttributeSchema;
+use crate::app_domain::model::localization::{MissingTranslation, ProductTranslation};
 use crate::app_domain::model::product::{
-    Inventory, Price, Product, ProductError, ProductHistory, ProductImage,
+    ChangeContext, Inventory, Price, Product, ProductBundle, ProductError, ProductHistory,
+    ProductImage, ProductOption, ProductRevision, ProductStatus, ProductStatusSchedule,
+    ProductVariant, ScheduleState,
+};
+use crate::app_domain::model::product_export::ProductExportRow;
+use crate::app_domain::model::product_filter::{AttributeFilter, ProductFilter};
+use crate::app_domain::model::product_import::{
+    ImportRowError, ImportRowOutcome, ProductImportRecord,
 };
 
 #[async_trait]
@@ -10,21 +20,44 @@ pub trait ProductRepository: Send + Sync {
     // Basic CRUD operations
     async fn find_by_id(&self, id: &str) -> Option<Product>;
     async fn find_by_sku(&self, sku: &str) -> Option<Product>;
+    async fn find_by_slug(&self, slug: &str) -> Option<Product>;
+    /// 変更前のスラッグが一致する商品を返す（旧 URL の転送用）
+    async fn find_by_old_slug(&self, slug: &str) -> Option<Product>;
     // async fn find_all(&self,
     //     category_id: Option<&str>,
     //     status: Option<&str>,
     //     limit: Option<i64>,
     //     offset: Option<i64>,
     // ) -> Vec<Product>;
-    async fn create(&self, product: Product) -> Result<Product, ProductError>;
-    async fn update(&self, product: Product) -> Result<Product, ProductError>;
-    async fn delete(&self, id: &str) -> Result<(), ProductError>;
+    // 変更系の操作は `ctx` の操作者・理由とともに変更差分を同一トランザクションで履歴に記録する
+    async fn create(&self, product: Product, ctx: &ChangeContext) -> Result<Product, ProductError>;
+    async fn update(&self, product: Product, ctx: &ChangeContext) -> Result<Product, ProductError>;
+    /// `expected_version` を指定した場合、商品をロックしてバージョンが一致する場合だけ削除する
+    async fn delete(&self, id: &str, expected_version: Option<i64>) -> Result<(), ProductError>;
+    /// 商品をロックしてバージョンが `expected_version` と一致する場合だけ、
+    /// 商品本体・価格・タグ・属性の変更を1トランザクションで書き込む
+    ///
+    /// SKU を変更する場合は同じトランザクション内で他の商品・バリアントとの重複を確認する。
+    async fn apply_revision(
+        &self,
+        product_id: &str,
+        expected_version: i64,
+        revision: ProductRevision,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
     async fn exists_by_sku(&self, sku: &str, exclude_id: Option<&str>) -> bool;
+    /// 他の商品の現在または旧スラッグとして使われているか
+    async fn exists_by_slug(&self, slug: &str, exclude_id: Option<&str>) -> bool;
 
     // Price operations
     async fn get_current_price(&self, product_id: &str) -> Option<Price>;
     // async fn get_price_history(&self, product_id: &str, limit: Option<i64>) -> Vec<Price>;
-    async fn update_price(&self, product_id: &str, price: Price) -> Result<Price, ProductError>;
+    async fn update_price(
+        &self,
+        product_id: &str,
+        price: Price,
+        ctx: &ChangeContext,
+    ) -> Result<Price, ProductError>;
 
     // Inventory operations
     async fn get_inventory(&self, product_id: &str) -> Option<Inventory>;
@@ -32,9 +65,13 @@ pub trait ProductRepository: Send + Sync {
         &self,
         product_id: &str,
         inventory: Inventory,
+        ctx: &ChangeContext,
     ) -> Result<Inventory, ProductError>;
-    // async fn reserve_inventory(&self, product_id: &str, quantity: i32) -> Result<(), ProductError>;
-    // async fn release_inventory(&self, product_id: &str, quantity: i32) -> Result<(), ProductError>;
+    /// 複数商品の在庫を1トランザクションで引き当てる（いずれかが不足すれば全体を取り消す）
+    async fn reserve_inventory(&self, reservations: Vec<(String, i32)>)
+        -> Result<(), ProductError>;
+    async fn release_inventory(&self, reservations: Vec<(String, i32)>)
+        -> Result<(), ProductError>;
 
     // Image operations
     async fn get_images(&self, product_id: &str) -> Vec<ProductImage>;
@@ -42,25 +79,55 @@ pub trait ProductRepository: Send + Sync {
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError>;
     async fn update_image(
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError>;
-    async fn delete_image(&self, product_id: &str, image_id: &str) -> Result<(), ProductError>;
+    async fn delete_image(
+        &self,
+        product_id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
     async fn reorder_images(
         &self,
         product_id: &str,
         image_orders: Vec<(String, i32)>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
+    /// 画像 ID を表示順に並べた一覧で並び順を振り直す（一覧は商品の画像すべてと一致しなければならない）
+    async fn set_image_order(
+        &self,
+        product_id: &str,
+        image_ids: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
+    async fn set_main_image(
+        &self,
+        product_id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError>;
-    async fn set_main_image(&self, product_id: &str, image_id: &str) -> Result<(), ProductError>;
 
     // Tag operations
     async fn get_tags(&self, product_id: &str) -> Vec<String>;
-    async fn add_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError>;
+    async fn add_tags(
+        &self,
+        product_id: &str,
+        tags: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
     // async fn remove_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError>;
-    async fn replace_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError>;
+    async fn replace_tags(
+        &self,
+        product_id: &str,
+        tags: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
 
     // Attribute operations
     async fn get_attributes(&self, product_id: &str) -> HashMap<String, String>;
@@ -68,10 +135,64 @@ pub trait ProductRepository: Send + Sync {
         &self,
         product_id: &str,
         attributes: HashMap<String, String>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError>;
+    /// カテゴリ（祖先カテゴリから継承したものを含む）の属性定義
+    async fn find_attribute_schema(
+        &self,
+        category_id: &str,
+    ) -> Result<AttributeSchema, ProductError>;
     // async fn set_attribute(&self, product_id: &str, name: &str, value: &str) -> Result<(), ProductError>;
     // async fn remove_attribute(&self, product_id: &str, name: &str) -> Result<(), ProductError>;
 
+    // Variant operations
+    async fn get_options(&self, product_id: &str) -> Vec<ProductOption>;
+    async fn set_options(
+        &self,
+        product_id: &str,
+        options: Vec<ProductOption>,
+    ) -> Result<(), ProductError>;
+    async fn get_variants(&self, product_id: &str) -> Vec<ProductVariant>;
+    /// 複数の商品のバリエーションを商品 ID ごとに返す（バリエーションの無い商品は含まない）
+    async fn get_variants_for(
+        &self,
+        product_ids: &[String],
+    ) -> HashMap<String, Vec<ProductVariant>>;
+    async fn create_variant(&self, variant: ProductVariant)
+        -> Result<ProductVariant, ProductError>;
+    async fn update_variant(&self, variant: ProductVariant)
+        -> Result<ProductVariant, ProductError>;
+    async fn delete_variant(&self, product_id: &str, variant_id: &str) -> Result<(), ProductError>;
+
+    // Bundle operations
+    async fn get_bundle(&self, product_id: &str) -> Option<ProductBundle>;
+    async fn set_bundle(&self, bundle: ProductBundle) -> Result<(), ProductError>;
+    async fn delete_bundle(&self, product_id: &str) -> Result<(), ProductError>;
+    async fn find_bundles_by_component(&self, component_id: &str) -> Vec<String>;
+
+    // Translation operations
+    async fn get_translations(&self, product_id: &str) -> Vec<ProductTranslation>;
+    /// 複数の商品のロケールの翻訳を商品 ID ごとに返す（表示用のため、取得に失敗した場合は空）
+    async fn get_translations_for(
+        &self,
+        product_ids: &[String],
+        locale: &str,
+    ) -> HashMap<String, ProductTranslation>;
+    /// ロケールの翻訳（名前・説明・画像の代替テキスト）をまとめて置き換える
+    async fn set_translation(
+        &self,
+        product_id: &str,
+        translation: ProductTranslation,
+    ) -> Result<(), ProductError>;
+    async fn delete_translation(&self, product_id: &str, locale: &str) -> Result<(), ProductError>;
+    /// ロケールの翻訳が不足している商品を ID 順に返す
+    async fn find_missing_translations(
+        &self,
+        locale: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Vec<MissingTranslation>;
+
     // History operations
     async fn get_history(
         &self,
@@ -89,6 +210,51 @@ pub trait ProductRepository: Send + Sync {
     //     reason: Option<&str>,
     // ) -> Result<(), ProductError>;
 
+    /// ステータスを `from` から `to` に変更し、同一トランザクションで履歴を記録する
+    ///
+    /// 現在のステータスが `from` と異なる場合は `InvalidStatusTransition` を返す。
+    async fn change_status(
+        &self,
+        product_id: &str,
+        from: ProductStatus,
+        to: ProductStatus,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError>;
+
+    // Schedule operations
+    async fn get_schedules(&self, product_id: &str) -> Vec<ProductStatusSchedule>;
+    async fn create_schedule(
+        &self,
+        schedule: ProductStatusSchedule,
+    ) -> Result<ProductStatusSchedule, ProductError>;
+    async fn cancel_schedule(
+        &self,
+        product_id: &str,
+        schedule_id: &str,
+    ) -> Result<(), ProductError>;
+    /// 実行時刻を過ぎた予約を排他的に取得して processing にする
+    async fn claim_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<ProductStatusSchedule>, ProductError>;
+    async fn complete_schedule(
+        &self,
+        schedule_id: &str,
+        state: ScheduleState,
+        failure_reason: Option<String>,
+    ) -> Result<(), ProductError>;
+
+    // Import operations
+    /// インポート行を SKU ごとに作成・更新する。全行を1トランザクションで処理し、
+    /// いずれかの行が失敗した場合は全体を取り消してその行のエラーを返す
+    async fn import_products(
+        &self,
+        records: Vec<ProductImportRecord>,
+        ctx: &ChangeContext,
+    ) -> Result<Vec<ImportRowOutcome>, ImportRowError>;
+
     // Batch operations
     // async fn update_batch(&self, updates: Vec<(String, Product)>) -> Result<Vec<Product>, ProductError>;
     // async fn update_prices_batch(&self, updates: Vec<(String, Price)>) -> Result<Vec<Price>, ProductError>;
@@ -104,10 +270,25 @@ pub trait ProductRepository: Send + Sync {
         min_price: Option<rust_decimal::Decimal>,
         max_price: Option<rust_decimal::Decimal>,
         in_stock_only: bool,
+        attributes: &[AttributeFilter],
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Vec<Product>;
 
+    async fn find_by_filter(
+        &self,
+        filter: &ProductFilter,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductError>;
+    async fn count_by_filter(&self, filter: &ProductFilter) -> Result<i64, ProductError>;
+    async fn export_products(
+        &self,
+        filter: &ProductFilter,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ProductExportRow>, ProductError>;
+
     // async fn find_by_category_recursive(&self, category_id: &str) -> Vec<Product>;
     async fn find_low_stock_products(&self, threshold: Option<i32>) -> Vec<(Product, Inventory)>;
     async fn find_out_of_stock_products(&self) -> Vec<Product>;
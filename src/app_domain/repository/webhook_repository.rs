@@ -0,0 +1,76 @@
+use crate::app_domain::model::domain_event::DomainEventType;
+use crate::app_domain::model::webhook::{DeliveryAttempt, WebhookDelivery, WebhookSubscription};
+use crate::infrastructure::error::AppResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+use serde_json::Value;
+
+/// Webhook の購読と配信記録を管理するリポジトリ
+#[automock]
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription>;
+    async fn find_all(&self) -> AppResult<Vec<WebhookSubscription>>;
+    async fn find_by_id(&self, id: &str) -> AppResult<Option<WebhookSubscription>>;
+    /// 有効な購読のみ取得する
+    async fn find_active(&self) -> AppResult<Vec<WebhookSubscription>>;
+    async fn update(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription>;
+    /// 購読と配信記録を削除する
+    async fn delete(&self, id: &str) -> AppResult<bool>;
+
+    /// 購読・イベントごとの配信記録を取得する
+    async fn find_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+    ) -> AppResult<Option<WebhookDelivery>>;
+    async fn find_delivery_by_id(&self, id: i64) -> AppResult<Option<WebhookDelivery>>;
+    /// 新しい順に配信記録を取得する
+    async fn find_deliveries(
+        &self,
+        subscription_id: &str,
+        limit: i64,
+    ) -> AppResult<Vec<WebhookDelivery>>;
+    /// 同じ購読・同じ集約で `before_event_id` より前のイベントに未配信の記録があるか
+    async fn has_pending_delivery(
+        &self,
+        subscription_id: &str,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        before_event_id: i64,
+    ) -> AppResult<bool>;
+    /// 送信せずに配信記録を作り、先行する配信が終わった後の再試行で送る
+    async fn queue_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+    ) -> AppResult<WebhookDelivery>;
+
+    /// 配信試行を記録し、購読の連続失敗回数を更新する。
+    /// 連続失敗が `max_failures` に達した購読は無効化する。
+    /// `retry_at` は失敗した配信を次に自動で再試行する日時。
+    #[allow(clippy::too_many_arguments)]
+    async fn record_attempt(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+        attempt: &DeliveryAttempt,
+        max_failures: i32,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> AppResult<WebhookDelivery>;
+
+    /// 再試行の時刻を過ぎた有効な購読の未配信記録を確保する。
+    /// 同じ購読・同じ集約でより前のイベントが未配信の記録は、順序を保つため確保しない。
+    /// 確保した記録は `lease_seconds` 後まで他の確保の対象にならない。
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> AppResult<Vec<WebhookDelivery>>;
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use std::fmt::Debug;
 
+use crate::app_domain::model::product::ChangeContext;
 use crate::app_domain::repository::category_repository::CategoryRepository;
 use crate::app_domain::repository::item_repository::ItemRepository;
 use crate::app_domain::repository::product_repository::ProductRepository;
@@ -25,6 +26,9 @@ pub enum DeletionError {
     /// 削除前バリデーションに失敗した場合
     #[error("Deletion validation failed: {0}")]
     Validation(String),
+    /// 呼び出し側が把握しているバージョンが現在のバージョンと一致しない場合
+    #[error("Version mismatch: {0}")]
+    VersionMismatch(String),
     /// 上記以外のその他のエラー
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -40,6 +44,25 @@ pub trait DeletionStrategy: Send + Sync {
 
     /// 指定された `id` に対して削除処理を実行する
     async fn delete(&self, id: Self::Id, kind: DeleteKind) -> Result<(), DeletionError>;
+
+    /// `expected_version` が現在のバージョンと一致する場合のみ削除処理を実行する
+    ///
+    /// `None` の場合は照合しない。バージョンを持たないエンティティは照合できないため、
+    /// 既定の実装はバージョンの指定を `Validation` として拒否する。
+    async fn delete_with_version(
+        &self,
+        id: Self::Id,
+        kind: DeleteKind,
+        expected_version: Option<i64>,
+    ) -> Result<(), DeletionError> {
+        match expected_version {
+            None => self.delete(id, kind).await,
+            Some(_) => Err(DeletionError::Validation(format!(
+                "{:?} does not support version checks",
+                id
+            ))),
+        }
+    }
 }
 
 /// Item エンティティ用の DeletionStrategy 実装
@@ -113,38 +136,61 @@ where
     type Id = String;
 
     async fn delete(&self, id: Self::Id, kind: DeleteKind) -> Result<(), DeletionError> {
+        self.delete_with_version(id, kind, None).await
+    }
+
+    async fn delete_with_version(
+        &self,
+        id: Self::Id,
+        kind: DeleteKind,
+        expected_version: Option<i64>,
+    ) -> Result<(), DeletionError> {
         use crate::app_domain::model::category::CategoryError;
 
         let res = match kind {
             DeleteKind::Logical => {
                 // Categoryは論理削除として非アクティブ化
                 match self.repository.find_by_id(&id).await {
-                    Some(mut category) => {
-                        category.deactivate();
-                        self.repository
-                            .update(category)
-                            .await
-                            .map(|_| true)
-                            .map_err(|e| CategoryError::NotFound(format!("Update failed: {}", e)))
-                    }
+                    Some(mut category) => match category.check_version(expected_version) {
+                        Ok(()) => {
+                            category.deactivate();
+                            self.repository
+                                .update(category)
+                                .await
+                                .map(|_| true)
+                                .map_err(|e| {
+                                    CategoryError::NotFound(format!("Update failed: {}", e))
+                                })
+                        }
+                        Err(e) => Err(e),
+                    },
                     None => Err(CategoryError::NotFound(format!(
                         "Category {} not found",
                         id
                     ))),
                 }
             }
-            DeleteKind::Physical => self.repository.delete(&id).await,
+            DeleteKind::Physical => {
+                self.repository
+                    .delete(&id, expected_version, &ChangeContext::default())
+                    .await
+            }
             DeleteKind::Restore => {
                 // Categoryの復元として再アクティブ化
                 match self.repository.find_by_id(&id).await {
-                    Some(mut category) => {
-                        category.activate();
-                        self.repository
-                            .update(category)
-                            .await
-                            .map(|_| true)
-                            .map_err(|e| CategoryError::NotFound(format!("Update failed: {}", e)))
-                    }
+                    Some(mut category) => match category.check_version(expected_version) {
+                        Ok(()) => {
+                            category.activate();
+                            self.repository
+                                .update(category)
+                                .await
+                                .map(|_| true)
+                                .map_err(|e| {
+                                    CategoryError::NotFound(format!("Update failed: {}", e))
+                                })
+                        }
+                        Err(e) => Err(e),
+                    },
                     None => Err(CategoryError::NotFound(format!(
                         "Category {} not found",
                         id
@@ -158,7 +204,10 @@ where
             Ok(_) => Ok(()),
             Err(err) => match err {
                 CategoryError::NotFound(msg) => Err(DeletionError::NotFound(msg)),
-                CategoryError::HasChildren(msg) => Err(DeletionError::Validation(msg)),
+                CategoryError::HasChildren(msg) | CategoryError::HasProducts(msg) => {
+                    Err(DeletionError::Validation(msg))
+                }
+                CategoryError::VersionMismatch(msg) => Err(DeletionError::VersionMismatch(msg)),
                 _ => Err(DeletionError::Other(anyhow::Error::new(err))),
             },
         }
@@ -191,35 +240,65 @@ where
     type Id = String;
 
     async fn delete(&self, id: Self::Id, kind: DeleteKind) -> Result<(), DeletionError> {
-        use crate::app_domain::model::product::{ProductError, ProductStatus};
+        self.delete_with_version(id, kind, None).await
+    }
+
+    async fn delete_with_version(
+        &self,
+        id: Self::Id,
+        kind: DeleteKind,
+        expected_version: Option<i64>,
+    ) -> Result<(), DeletionError> {
+        use crate::app_domain::model::product::{ChangeContext, ProductError, ProductStatus};
 
         let res = match kind {
             DeleteKind::Logical => {
+                // セット商品の構成品は論理削除を許可するが、警告を残す
+                let bundle_ids = self.repository.find_bundles_by_component(&id).await;
+                if !bundle_ids.is_empty() {
+                    tracing::warn!(
+                        "Product {} is discontinued while used in bundles: {}",
+                        id,
+                        bundle_ids.join(", ")
+                    );
+                }
+
                 // Productは論理削除としてステータスを非アクティブに変更
                 match self.repository.find_by_id(&id).await {
-                    Some(mut product) => {
-                        product.update_status(ProductStatus::Discontinued);
-                        self.repository
-                            .update(product)
-                            .await
-                            .map(|_| ())
-                            .map_err(|_e| ProductError::ProductNotFound)
-                    }
+                    Some(mut product) => match product.check_version(expected_version) {
+                        Ok(()) => {
+                            // Discontinued への遷移はどの状態からも許可される
+                            let _ = product.update_status(ProductStatus::Discontinued);
+                            let ctx =
+                                ChangeContext::new(None, Some("logical deletion".to_string()));
+                            // update は読み込んだバージョンをロックした行と照合する
+                            self.repository.update(product, &ctx).await.map(|_| ())
+                        }
+                        Err(e) => Err(e),
+                    },
                     None => Err(ProductError::ProductNotFound),
                 }
             }
-            DeleteKind::Physical => self.repository.delete(&id).await,
+            DeleteKind::Physical => {
+                // セット商品の構成品は物理削除できない
+                let bundle_ids = self.repository.find_bundles_by_component(&id).await;
+                if bundle_ids.is_empty() {
+                    self.repository.delete(&id, expected_version).await
+                } else {
+                    Err(ProductError::UsedInBundle(bundle_ids))
+                }
+            }
             DeleteKind::Restore => {
-                // Productの復元としてステータスをアクティブに変更
+                // Productの復元としてステータスを非アクティブに戻す（再公開は publish で行う）
                 match self.repository.find_by_id(&id).await {
-                    Some(mut product) => {
-                        product.update_status(ProductStatus::Active);
-                        self.repository
-                            .update(product)
-                            .await
-                            .map(|_| ())
-                            .map_err(|_e| ProductError::ProductNotFound)
-                    }
+                    Some(mut product) => match product.check_version(expected_version) {
+                        Ok(()) => {
+                            product.restore();
+                            let ctx = ChangeContext::new(None, Some("restore".to_string()));
+                            self.repository.update(product, &ctx).await.map(|_| ())
+                        }
+                        Err(e) => Err(e),
+                    },
                     None => Err(ProductError::ProductNotFound),
                 }
             }
@@ -232,6 +311,10 @@ where
                 ProductError::ProductNotFound => {
                     Err(DeletionError::NotFound(format!("Product {} not found", id)))
                 }
+                ProductError::UsedInBundle(_) => Err(DeletionError::Validation(err.to_string())),
+                ProductError::VersionMismatch { .. } => {
+                    Err(DeletionError::VersionMismatch(err.to_string()))
+                }
                 _ => Err(DeletionError::Other(anyhow::Error::new(err))),
             },
         }
@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::app_domain::model::attribute_schema::{AttributeDefinition, AttributeType};
+use crate::app_domain::model::localization::CategoryTranslation;
 
 #[derive(Deserialize)]
 pub struct CreateCategoryRequest {
     pub name: String,
+    /// 省略時は名前から生成する（同じ親の下で重なる場合は連番を付ける）
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub parent_id: Option<String>,
     pub sort_order: i32,
@@ -12,6 +18,8 @@ pub struct CreateCategoryRequest {
 #[derive(Deserialize)]
 pub struct UpdateCategoryRequest {
     pub name: Option<String>,
+    /// 変更前のスラッグは旧 URL として転送される（名前を変えてもスラッグは変わらない）
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub sort_order: Option<i32>,
     pub is_active: Option<bool>,
@@ -23,27 +31,77 @@ pub struct MoveCategoryRequest {
     pub new_sort_order: Option<i32>,
 }
 
+#[derive(Deserialize)]
+pub struct CopyCategoryRequest {
+    pub new_parent_id: Option<String>,
+    /// 複製したルートカテゴリの名前（省略時は複製元と同じ）
+    pub name: Option<String>,
+    /// 省略時は兄弟カテゴリの末尾に置く
+    pub new_sort_order: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct MergeCategoryRequest {
+    pub target_id: String,
+}
+
+/// 子カテゴリの表示順（現在の子カテゴリすべての ID を表示したい順に並べる）
+#[derive(Deserialize)]
+pub struct ReorderCategoriesRequest {
+    pub child_ids: Vec<String>,
+}
+
+/// 商品属性の定義（`allowed_values` は enum 型、`unit` は number 型のみ）
+#[derive(Deserialize)]
+pub struct AttributeDefinitionRequest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub unit: Option<String>,
+}
+
+/// カテゴリ自身の商品属性の定義（指定した順に並び、既存の定義はすべて置き換わる）
+#[derive(Deserialize)]
+pub struct ReplaceAttributeDefinitionsRequest {
+    pub attributes: Vec<AttributeDefinitionRequest>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CategoryResponse {
     pub id: String,
     pub name: String,
+    pub slug: String,
     pub description: Option<String>,
     pub parent_id: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// カテゴリ直下の商品数（取得時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_count: Option<i64>,
+    /// 子孫カテゴリを含めた商品数（取得時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_product_count: Option<i64>,
 }
 
 #[derive(Serialize)]
 pub struct CategoryListResponse {
     pub id: String,
     pub name: String,
+    pub slug: String,
     pub description: Option<String>,
     pub parent_id: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
     pub children_count: i64,
+    pub product_count: i64,
+    pub total_product_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -52,16 +110,44 @@ pub struct CategoryListResponse {
 pub struct CategoryTreeResponse {
     pub id: String,
     pub name: String,
+    pub slug: String,
     pub description: Option<String>,
     pub sort_order: i32,
     pub is_active: bool,
+    pub product_count: i64,
+    pub total_product_count: i64,
     pub children: Vec<CategoryTreeResponse>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct AttributeDefinitionResponse {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// 祖先カテゴリから継承した定義の場合、その定義を持つカテゴリの ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryAttributesResponse {
+    pub category_id: String,
+    /// カテゴリ自身の定義
+    pub attributes: Vec<AttributeDefinitionResponse>,
+    /// 祖先カテゴリから継承したものを含め、商品に適用される定義
+    pub effective_attributes: Vec<AttributeDefinitionResponse>,
+}
+
 #[derive(Serialize)]
 pub struct CategoryPathItem {
     pub id: String,
     pub name: String,
+    pub slug: String,
 }
 
 #[derive(Serialize)]
@@ -85,9 +171,41 @@ pub struct CategoryTreesResponse {
 pub struct CategoryQueryParams {
     pub parent_id: Option<String>,
     pub include_inactive: Option<bool>,
+    /// 子カテゴリ一覧で孫以下の子孫も含める
+    pub recursive: Option<bool>,
     // pub sort: Option<String>,
 }
 
+/// 商品が紐づくカテゴリを削除するときの指定（どちらか一方のみ）
+#[derive(Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// 商品をこのカテゴリへ付け替えてから削除する
+    pub reassign_to: Option<String>,
+    /// 商品を非アクティブにしてから削除する
+    pub deactivate_products: Option<bool>,
+}
+
+impl DeleteCategoryQuery {
+    pub fn disposition(
+        &self,
+        category_id: &str,
+    ) -> Result<Option<crate::app_domain::model::category::ProductDisposition>, String> {
+        use crate::app_domain::model::category::ProductDisposition;
+
+        match (&self.reassign_to, self.deactivate_products.unwrap_or(false)) {
+            (Some(_), true) => {
+                Err("reassign_to と deactivate_products は同時に指定できません".to_string())
+            }
+            (Some(target), false) if target == category_id => {
+                Err("削除するカテゴリ自身を付け替え先にすることはできません".to_string())
+            }
+            (Some(target), false) => Ok(Some(ProductDisposition::Reassign(target.clone()))),
+            (None, true) => Ok(Some(ProductDisposition::Deactivate)),
+            (None, false) => Ok(None),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct CategoryErrorResponse {
     pub code: String,
@@ -107,12 +225,16 @@ impl From<crate::app_domain::model::category::Category> for CategoryResponse {
         Self {
             id: category.id,
             name: category.name,
+            slug: category.slug,
             description: category.description,
             parent_id: category.parent_id,
             sort_order: category.sort_order,
             is_active: category.is_active,
             created_at: category.created_at,
             updated_at: category.updated_at,
+            version: category.version,
+            product_count: None,
+            total_product_count: None,
         }
     }
 }
@@ -122,9 +244,12 @@ impl From<crate::app_domain::model::category::CategoryTree> for CategoryTreeResp
         Self {
             id: tree.id,
             name: tree.name,
+            slug: tree.slug,
             description: tree.description,
             sort_order: tree.sort_order,
             is_active: tree.is_active,
+            product_count: tree.product_count.direct,
+            total_product_count: tree.product_count.total,
             children: tree.children.into_iter().map(|c| c.into()).collect(),
         }
     }
@@ -138,7 +263,9 @@ impl From<crate::app_domain::model::category::CategoryPath> for CategoryPathResp
                 .into_iter()
                 .map(|id| CategoryPathItem {
                     id: id.clone(),
-                    name: id, // This would need to be enriched with actual names from the repository
+                    // These would need to be enriched with actual names and slugs from the repository
+                    name: id.clone(),
+                    slug: id,
                 })
                 .collect(),
             depth: path.depth,
@@ -146,6 +273,110 @@ impl From<crate::app_domain::model::category::CategoryPath> for CategoryPathResp
     }
 }
 
+/// カテゴリ ID ごとの翻訳で名前と説明を置き換えられるレスポンス
+pub trait LocalizeCategories {
+    /// 翻訳を読み込む対象のカテゴリ ID
+    fn category_ids(&self) -> Vec<String>;
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>);
+}
+
+impl LocalizeCategories for CategoryResponse {
+    fn category_ids(&self) -> Vec<String> {
+        vec![self.id.clone()]
+    }
+
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>) {
+        if let Some(translation) = translations.get(&self.id) {
+            translation.apply(&mut self.name, &mut self.description);
+        }
+    }
+}
+
+impl LocalizeCategories for CategoriesResponse {
+    fn category_ids(&self) -> Vec<String> {
+        self.categories.iter().map(|c| c.id.clone()).collect()
+    }
+
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>) {
+        for category in &mut self.categories {
+            if let Some(translation) = translations.get(&category.id) {
+                translation.apply(&mut category.name, &mut category.description);
+            }
+        }
+    }
+}
+
+impl LocalizeCategories for CategoryTreeResponse {
+    fn category_ids(&self) -> Vec<String> {
+        let mut ids = vec![self.id.clone()];
+        ids.extend(self.children.iter().flat_map(|child| child.category_ids()));
+        ids
+    }
+
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>) {
+        if let Some(translation) = translations.get(&self.id) {
+            translation.apply(&mut self.name, &mut self.description);
+        }
+        for child in &mut self.children {
+            child.localize(translations);
+        }
+    }
+}
+
+impl LocalizeCategories for CategoryTreesResponse {
+    fn category_ids(&self) -> Vec<String> {
+        self.tree
+            .iter()
+            .flat_map(|tree| tree.category_ids())
+            .collect()
+    }
+
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>) {
+        for tree in &mut self.tree {
+            tree.localize(translations);
+        }
+    }
+}
+
+impl LocalizeCategories for CategoryPathResponse {
+    fn category_ids(&self) -> Vec<String> {
+        self.path.iter().map(|item| item.id.clone()).collect()
+    }
+
+    fn localize(&mut self, translations: &HashMap<String, CategoryTranslation>) {
+        for item in &mut self.path {
+            if let Some(name) = translations.get(&item.id).and_then(|t| t.name.as_ref()) {
+                item.name = name.clone();
+            }
+        }
+    }
+}
+
+impl From<AttributeDefinitionRequest> for AttributeDefinition {
+    fn from(request: AttributeDefinitionRequest) -> Self {
+        Self {
+            name: request.name,
+            attribute_type: request.attribute_type,
+            allowed_values: request.allowed_values,
+            required: request.required,
+            unit: request.unit,
+        }
+    }
+}
+
+impl From<AttributeDefinition> for AttributeDefinitionResponse {
+    fn from(definition: AttributeDefinition) -> Self {
+        Self {
+            name: definition.name,
+            attribute_type: definition.attribute_type,
+            allowed_values: definition.allowed_values,
+            required: definition.required,
+            unit: definition.unit,
+            inherited_from: None,
+        }
+    }
+}
+
 impl From<crate::app_domain::model::category::CategoryError> for CategoryErrorResponse {
     fn from(error: crate::app_domain::model::category::CategoryError) -> Self {
         use crate::app_domain::model::category::CategoryError;
@@ -198,11 +429,52 @@ impl From<crate::app_domain::model::category::CategoryError> for CategoryErrorRe
                 message: error.to_string(),
                 details: None,
             },
-            // CategoryError::HasProducts(_) => Self {
-            //     code: "CATEGORY_HAS_PRODUCTS".to_string(),
-            //     message: error.to_string(),
-            //     details: None,
-            // },
+            CategoryError::HasProducts(_) => Self {
+                code: "CATEGORY_HAS_PRODUCTS".to_string(),
+                message: error.to_string(),
+                details: None,
+            },
+            CategoryError::VersionMismatch(_) => Self {
+                code: "CATEGORY_VERSION_MISMATCH".to_string(),
+                message: error.to_string(),
+                details: Some(serde_json::json!({
+                    "field": "version",
+                    "value": null,
+                    "parent_id": null,
+                })),
+            },
+            CategoryError::InvalidSlug(_) => Self {
+                code: "CATEGORY_INVALID_SLUG".to_string(),
+                message: error.to_string(),
+                details: Some(serde_json::json!({
+                    "field": "slug",
+                    "value": null,
+                    "parent_id": null,
+                })),
+            },
+            CategoryError::SlugDuplicate(_) => Self {
+                code: "CATEGORY_SLUG_DUPLICATE".to_string(),
+                message: error.to_string(),
+                details: Some(serde_json::json!({
+                    "field": "slug",
+                    "value": null,
+                    "parent_id": null,
+                })),
+            },
+            CategoryError::InvalidAttributeDefinition(_) => Self {
+                code: "CATEGORY_INVALID_ATTRIBUTE_DEFINITION".to_string(),
+                message: error.to_string(),
+                details: Some(serde_json::json!({
+                    "field": "attributes",
+                    "value": null,
+                    "parent_id": null,
+                })),
+            },
+            CategoryError::InvalidTranslation(_) => Self {
+                code: "CATEGORY_INVALID_TRANSLATION".to_string(),
+                message: error.to_string(),
+                details: None,
+            },
         }
     }
 }
@@ -309,6 +581,31 @@ mod tests {
         assert_eq!(request.new_sort_order, Some(3));
     }
 
+    #[test]
+    fn test_delete_category_query_disposition() {
+        use crate::app_domain::model::category::ProductDisposition;
+
+        let query = |json: &str| serde_json::from_str::<DeleteCategoryQuery>(json).unwrap();
+
+        assert_eq!(query("{}").disposition("cat_1"), Ok(None));
+        assert_eq!(
+            query(r#"{"reassign_to": "cat_2"}"#).disposition("cat_1"),
+            Ok(Some(ProductDisposition::Reassign("cat_2".to_string())))
+        );
+        assert_eq!(
+            query(r#"{"deactivate_products": true}"#).disposition("cat_1"),
+            Ok(Some(ProductDisposition::Deactivate))
+        );
+        assert!(query(r#"{"reassign_to": "cat_1"}"#)
+            .disposition("cat_1")
+            .is_err());
+        assert!(
+            query(r#"{"reassign_to": "cat_2", "deactivate_products": true}"#)
+                .disposition("cat_1")
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_category_query_params_deserialization() {
         let json = r#"{
@@ -1,4 +1,7 @@
 pub mod category_dto;
 pub mod item_dto;
 pub mod product_dto;
+pub mod slug_dto;
+pub mod translation_dto;
 pub mod user_dto;
+pub mod webhook_dto;
@@ -1,11 +1,19 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use crate::app_domain::model::localization::ProductTranslation;
 use crate::app_domain::model::product::{
-    Dimensions, Inventory, Price, Product, ProductError, ProductHistory, ProductImage,
-    ProductStatus, ShippingInfo,
+    BundleComponent, BundlePricing, Dimensions, FieldChange, Inventory, Price, PriceRange, Product,
+    ProductError, ProductHistory, ProductImage, ProductOption, ProductStatus,
+    ProductStatusSchedule, ProductVariant, ScheduleState, ShippingInfo,
+};
+use crate::app_domain::model::product_bulk::ProductBulkPatch;
+use crate::app_domain::model::product_export::ExportFormat;
+use crate::app_domain::model::product_filter::ProductFilter;
+use crate::app_domain::model::product_import::{
+    ImportFormat, ImportJobState, ImportRowError, ProductImportJob,
 };
 
 // Request DTOs
@@ -24,6 +32,8 @@ pub struct CreateProductRequest {
     pub dimensions: Option<DimensionsRequest>,
     pub weight: Option<Decimal>,
     pub shipping_info: Option<ShippingInfoRequest>,
+    /// 省略時は名前から生成し、重複する場合は連番を付ける
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +51,8 @@ pub struct UpdateProductRequest {
     pub dimensions: Option<DimensionsRequest>,
     pub weight: Option<Decimal>,
     pub shipping_info: Option<ShippingInfoRequest>,
+    /// 変更前のスラッグは旧 URL として転送される
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -122,6 +134,12 @@ pub struct ImageOrderItem {
     pub sort_order: i32,
 }
 
+/// 商品の画像すべての ID を表示したい順に並べた一覧
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageOrderRequest {
+    pub image_ids: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BatchUpdateRequest {
     pub updates: Vec<BatchUpdateItem>,
@@ -134,6 +152,104 @@ pub struct BatchUpdateItem {
     pub price: Option<PricePatchRequest>,
     pub inventory: Option<InventoryPatchRequest>,
     pub status: Option<ProductStatus>,
+    /// 指定時は現在のバージョンと一致する場合のみ更新する
+    pub expected_version: Option<i64>,
+}
+
+/// 絞り込み条件に一致するすべての商品に同じ変更を適用する
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateRequest {
+    #[serde(default)]
+    pub filter: ProductFilterRequest,
+    pub patch: ProductBulkPatch,
+    /// 指定時は対象商品数が一致する場合のみ実行する（プレビュー結果の確認用）
+    pub expected_count: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateQuery {
+    /// true の場合は対象件数と変更内容のサンプルのみを返し、商品は変更しない
+    #[serde(default)]
+    pub preview: bool,
+    pub sample_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductOptionRequest {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetProductOptionsRequest {
+    pub options: Vec<ProductOptionRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateVariantRequest {
+    pub sku: String,
+    pub option_values: BTreeMap<String, String>,
+    pub price: PriceRequest,
+    pub inventory: InventoryRequest,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateVariantRequest {
+    pub sku: Option<String>,
+    pub option_values: Option<BTreeMap<String, String>>,
+    pub price: Option<PriceRequest>,
+    pub inventory: Option<InventoryRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleComponentRequest {
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetBundleRequest {
+    pub components: Vec<BundleComponentRequest>,
+    pub pricing: BundlePricing,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InventoryReservationRequest {
+    pub quantity: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatusTransitionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeleteProductQuery {
+    /// 物理削除せず販売終了にする（セット商品の構成品でも削除できる）
+    pub logical: Option<bool>,
+}
+
+/// 論理削除の結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductDeletionResponse {
+    pub id: String,
+    /// この商品を構成品に含むセット商品（空でなければ警告として扱う）
+    pub used_in_bundles: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateStatusScheduleRequest {
+    pub target_status: ProductStatus,
+    pub scheduled_at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+/// ロールバック先（履歴エントリIDまたは日時のどちらか一方を指定）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    pub history_id: Option<i64>,
+    pub at: Option<DateTime<Utc>>,
 }
 
 // Response DTOs
@@ -141,6 +257,7 @@ pub struct BatchUpdateItem {
 pub struct ProductResponse {
     pub id: String,
     pub name: String,
+    pub slug: String,
     pub description: Option<String>,
     pub sku: String,
     pub brand: Option<String>,
@@ -151,11 +268,16 @@ pub struct ProductResponse {
     pub tags: Vec<String>,
     pub attributes: HashMap<String, String>,
     pub images: Vec<ProductImageResponse>,
+    pub options: Vec<ProductOptionResponse>,
+    pub variants: Vec<ProductVariantResponse>,
+    pub price_range: Option<PriceRangeResponse>,
+    pub bundle: Option<BundleResponse>,
     pub dimensions: Option<DimensionsResponse>,
     pub weight: Option<Decimal>,
     pub shipping_info: ShippingInfoResponse,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -194,6 +316,82 @@ pub struct ProductImageResponse {
     pub is_main: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductOptionResponse {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductVariantResponse {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub option_values: BTreeMap<String, String>,
+    pub price: PriceResponse,
+    pub inventory: InventoryResponse,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductVariantListResponse {
+    pub options: Vec<ProductOptionResponse>,
+    pub variants: Vec<ProductVariantResponse>,
+    pub price_range: Option<PriceRangeResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceRangeResponse {
+    pub min: Decimal,
+    pub max: Decimal,
+    pub currency: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleResponse {
+    pub product_id: String,
+    pub pricing: BundlePricing,
+    pub components: Vec<BundleComponentResponse>,
+    pub available_quantity: Option<i32>,
+    pub price: Option<PriceResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleComponentResponse {
+    pub product_id: String,
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    pub quantity: i32,
+    pub available_quantity: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InventoryReservationResponse {
+    pub product_id: String,
+    pub reservations: Vec<InventoryReservationItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InventoryReservationItem {
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusScheduleResponse {
+    pub id: String,
+    pub product_id: String,
+    pub target_status: ProductStatus,
+    pub scheduled_at: DateTime<Utc>,
+    pub state: ScheduleState,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DimensionsResponse {
     pub width: Decimal,
@@ -227,6 +425,20 @@ pub struct ProductHistoryItem {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackPreviewResponse {
+    pub product_id: String,
+    pub changes: Vec<RollbackFieldChange>,
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackFieldChange {
+    pub field: String,
+    pub current_value: Option<String>,
+    pub restored_value: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BatchUpdateResponse {
     pub results: Vec<BatchUpdateResult>,
@@ -242,6 +454,119 @@ pub struct BatchUpdateResult {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdatePreviewResponse {
+    pub matched_count: i64,
+    pub sample: Vec<BulkUpdateSample>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateSample {
+    pub id: String,
+    pub sku: String,
+    pub name: String,
+    pub changes: Vec<BulkFieldChange>,
+    /// この商品に適用できない場合の理由
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkFieldChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateResponse {
+    /// 履歴の変更理由に記録される一括更新の ID
+    pub bulk_id: String,
+    pub matched_count: i64,
+    pub updated_count: i64,
+    pub unchanged_count: i64,
+    /// 対象を確定した後に他の操作で変更・削除されたため、適用しなかった商品
+    pub conflicted_count: i64,
+    pub conflicts: Vec<BulkUpdateFailure>,
+    pub failed_count: i64,
+    pub failures: Vec<BulkUpdateFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateFailure {
+    pub id: String,
+    pub sku: String,
+    pub error: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProductImportQuery {
+    /// 省略時は Content-Type から判定する（`text/tab-separated-values` なら TSV）
+    pub format: Option<ImportFormat>,
+    /// true の場合は検証結果のみを返し、商品は変更しない
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// 商品エクスポートの形式・列と、商品検索と同じ絞り込み条件
+#[derive(Debug, Default, Deserialize)]
+pub struct ProductExportQuery {
+    /// 省略時は CSV
+    pub format: Option<ExportFormat>,
+    /// カンマ区切りの列名（`attr:<name>` で個別の属性）。省略時は全列
+    pub columns: Option<String>,
+    pub q: Option<String>,
+    pub category_id: Option<String>,
+    pub brand: Option<String>,
+    pub status: Option<String>,
+    pub tags: Option<String>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub in_stock_only: Option<bool>,
+    pub attributes: Option<String>,
+}
+
+/// 商品の絞り込み条件（`tags` はカンマ区切り、`status` は大文字小文字を区別しない）
+///
+/// `attributes` は `color:red|blue,screen_size>=13` の形式で属性を絞り込む。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProductFilterRequest {
+    pub q: Option<String>,
+    pub category_id: Option<String>,
+    pub brand: Option<String>,
+    pub status: Option<String>,
+    pub tags: Option<String>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub in_stock_only: Option<bool>,
+    pub attributes: Option<String>,
+}
+
+/// ドライランの検証結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductImportReport {
+    pub total_rows: usize,
+    pub create_count: usize,
+    pub update_count: usize,
+    pub error_count: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductImportJobResponse {
+    pub id: String,
+    pub state: ImportJobState,
+    pub total_rows: usize,
+    pub processed_rows: usize,
+    pub created_count: usize,
+    pub updated_count: usize,
+    pub failed_count: usize,
+    pub errors: Vec<ImportRowError>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
 // Query DTOs
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProductSearchQuery {
@@ -252,6 +577,8 @@ pub struct ProductSearchQuery {
     pub min_price: Option<Decimal>,
     pub max_price: Option<Decimal>,
     pub in_stock_only: Option<bool>,
+    /// 属性の条件（`color:red|blue,screen_size>=13`）。カテゴリを指定すると属性定義の型で解釈する
+    pub attributes: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -285,6 +612,7 @@ impl From<Product> for ProductResponse {
         ProductResponse {
             id: product.id,
             name: product.name,
+            slug: product.slug,
             description: product.description,
             sku: product.sku,
             brand: product.brand,
@@ -295,11 +623,33 @@ impl From<Product> for ProductResponse {
             tags: Vec::new(),           // Will be populated separately
             attributes: HashMap::new(), // Will be populated separately
             images: Vec::new(),         // Will be populated separately
+            options: Vec::new(),        // Will be populated separately
+            variants: Vec::new(),       // Will be populated separately
+            price_range: None,          // Will be populated separately
+            bundle: None,               // Will be populated separately
             dimensions: product.dimensions.map(Into::into),
             weight: product.weight,
             shipping_info: product.shipping_info.into(),
             created_at: product.created_at,
             updated_at: product.updated_at,
+            version: product.version,
+        }
+    }
+}
+
+impl ProductResponse {
+    /// 翻訳のある名前・説明・画像の代替テキストで置き換える
+    pub fn localize(&mut self, translation: &ProductTranslation) {
+        if let Some(name) = &translation.name {
+            self.name = name.clone();
+        }
+        if let Some(description) = &translation.description {
+            self.description = Some(description.clone());
+        }
+        for image in &mut self.images {
+            if let Some(alt_text) = translation.image_alt_texts.get(&image.id) {
+                image.alt_text = Some(alt_text.clone());
+            }
         }
     }
 }
@@ -342,6 +692,77 @@ impl From<ProductImage> for ProductImageResponse {
     }
 }
 
+impl From<ProductOption> for ProductOptionResponse {
+    fn from(option: ProductOption) -> Self {
+        ProductOptionResponse {
+            name: option.name,
+            values: option.values,
+        }
+    }
+}
+
+impl From<ProductVariant> for ProductVariantResponse {
+    fn from(variant: ProductVariant) -> Self {
+        ProductVariantResponse {
+            id: variant.id,
+            product_id: variant.product_id,
+            sku: variant.sku,
+            option_values: variant.option_values,
+            price: variant.price.into(),
+            inventory: variant.inventory.into(),
+            created_at: variant.created_at,
+            updated_at: variant.updated_at,
+        }
+    }
+}
+
+impl From<PriceRange> for PriceRangeResponse {
+    fn from(range: PriceRange) -> Self {
+        PriceRangeResponse {
+            min: range.min,
+            max: range.max,
+            currency: range.currency,
+        }
+    }
+}
+
+impl From<ProductStatusSchedule> for StatusScheduleResponse {
+    fn from(schedule: ProductStatusSchedule) -> Self {
+        StatusScheduleResponse {
+            id: schedule.id,
+            product_id: schedule.product_id,
+            target_status: schedule.target_status,
+            scheduled_at: schedule.scheduled_at,
+            state: schedule.state,
+            reason: schedule.reason,
+            created_by: schedule.created_by,
+            executed_at: schedule.executed_at,
+            failure_reason: schedule.failure_reason,
+            created_at: schedule.created_at,
+        }
+    }
+}
+
+impl From<FieldChange> for RollbackFieldChange {
+    fn from(change: FieldChange) -> Self {
+        Self {
+            field: change.field_name,
+            current_value: change.old_value,
+            restored_value: change.new_value,
+        }
+    }
+}
+
+impl From<FieldChange> for BulkFieldChange {
+    fn from(change: FieldChange) -> Self {
+        Self {
+            field: change.field_name,
+            old_value: change.old_value,
+            new_value: change.new_value,
+        }
+    }
+}
+
 impl From<Dimensions> for DimensionsResponse {
     fn from(dimensions: Dimensions) -> Self {
         DimensionsResponse {
@@ -423,6 +844,24 @@ impl From<ShippingInfoRequest> for ShippingInfo {
     }
 }
 
+impl From<ProductOptionRequest> for ProductOption {
+    fn from(request: ProductOptionRequest) -> Self {
+        ProductOption {
+            name: request.name,
+            values: request.values,
+        }
+    }
+}
+
+impl From<BundleComponentRequest> for BundleComponent {
+    fn from(request: BundleComponentRequest) -> Self {
+        BundleComponent {
+            product_id: request.product_id,
+            quantity: request.quantity,
+        }
+    }
+}
+
 impl From<ProductImageRequest> for ProductImage {
     fn from(request: ProductImageRequest) -> Self {
         ProductImage {
@@ -435,6 +874,65 @@ impl From<ProductImageRequest> for ProductImage {
     }
 }
 
+impl TryFrom<ProductFilterRequest> for ProductFilter {
+    type Error = ProductError;
+
+    fn try_from(request: ProductFilterRequest) -> Result<Self, Self::Error> {
+        if let (Some(min), Some(max)) = (request.min_price, request.max_price) {
+            if min > max {
+                return Err(ProductError::InvalidFilter(
+                    "min_price must not exceed max_price".to_string(),
+                ));
+            }
+        }
+
+        Ok(ProductFilter {
+            query: request.q.unwrap_or_default(),
+            category_id: request.category_id,
+            brand: request.brand,
+            status: request
+                .status
+                .as_deref()
+                .map(ProductFilter::parse_status)
+                .transpose()?,
+            tags: request
+                .tags
+                .as_deref()
+                .map(ProductFilter::parse_tags)
+                .unwrap_or_default(),
+            min_price: request.min_price,
+            max_price: request.max_price,
+            in_stock_only: request.in_stock_only.unwrap_or(false),
+            attributes: request
+                .attributes
+                .as_deref()
+                .map(ProductFilter::parse_attributes)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl From<ProductImportJob> for ProductImportJobResponse {
+    fn from(job: ProductImportJob) -> Self {
+        let failed_count = job.failed_count();
+        ProductImportJobResponse {
+            id: job.id,
+            state: job.state,
+            total_rows: job.total_rows,
+            processed_rows: job.processed_rows,
+            created_count: job.created_count,
+            updated_count: job.updated_count,
+            failed_count,
+            errors: job.errors,
+            created_by: job.created_by,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
+
 impl From<ProductError> for ProductErrorResponse {
     fn from(error: ProductError) -> Self {
         let (code, message, details) = match error {
@@ -463,6 +961,33 @@ impl From<ProductError> for ProductErrorResponse {
                 "SKUが重複しています".to_string(),
                 None,
             ),
+            ProductError::InvalidSlug => (
+                "PRODUCT_INVALID_SLUG".to_string(),
+                "スラッグが無効です".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("slug".to_string()),
+                    value: None,
+                    constraint: Some(
+                        "英小文字・数字をハイフンで区切った100文字以下".to_string(),
+                    ),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::SlugRequired => (
+                "PRODUCT_SLUG_REQUIRED".to_string(),
+                "商品名からスラッグを生成できないため、スラッグを指定してください".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("slug".to_string()),
+                    value: None,
+                    constraint: Some("商品名が漢字などローマ字に変換できない文字を含む場合は必須".to_string()),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::SlugAlreadyExists => (
+                "PRODUCT_SLUG_DUPLICATE".to_string(),
+                "スラッグが重複しています".to_string(),
+                None,
+            ),
             ProductError::InvalidPrice => (
                 "INVALID_PRICE_RANGE".to_string(),
                 "価格の範囲が不正です".to_string(),
@@ -488,6 +1013,24 @@ impl From<ProductError> for ProductErrorResponse {
                     additional_info: None,
                 }),
             ),
+            ProductError::InvalidAttributes(violations) => (
+                "PRODUCT_INVALID_ATTRIBUTES".to_string(),
+                "商品属性がカテゴリの属性定義に合っていません".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("attributes".to_string()),
+                    value: None,
+                    constraint: Some(
+                        "カテゴリ（祖先カテゴリを含む）で定義された属性・型・選択肢に従う必要があります"
+                            .to_string(),
+                    ),
+                    additional_info: Some(
+                        violations
+                            .into_iter()
+                            .map(|violation| (violation.attribute, violation.reason))
+                            .collect(),
+                    ),
+                }),
+            ),
             ProductError::TooManyImages => (
                 "MAX_IMAGES_EXCEEDED".to_string(),
                 "最大画像数を超過しています".to_string(),
@@ -503,6 +1046,215 @@ impl From<ProductError> for ProductErrorResponse {
                 "商品が見つかりません".to_string(),
                 None,
             ),
+            ProductError::InvalidVariantOptions => (
+                "INVALID_VARIANT_OPTIONS".to_string(),
+                "バリエーションのオプションが不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("option_values".to_string()),
+                    value: None,
+                    constraint: Some(
+                        "定義済みのすべてのオプションに許可された値を指定する必要があります"
+                            .to_string(),
+                    ),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::VariantCombinationDuplicate => (
+                "VARIANT_COMBINATION_DUPLICATE".to_string(),
+                "同じオプションの組み合わせのバリエーションが既に存在します".to_string(),
+                None,
+            ),
+            ProductError::VariantNotFound => (
+                "VARIANT_NOT_FOUND".to_string(),
+                "バリエーションが見つかりません".to_string(),
+                None,
+            ),
+            ProductError::InvalidBundle => (
+                "INVALID_BUNDLE".to_string(),
+                "セット商品の構成が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("components".to_string()),
+                    value: None,
+                    constraint: Some(
+                        "構成品は1件以上・数量は1以上・自身や重複を含まず、割引率は0以上1未満"
+                            .to_string(),
+                    ),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::BundleNotFound => (
+                "BUNDLE_NOT_FOUND".to_string(),
+                "セット商品の定義が見つかりません".to_string(),
+                None,
+            ),
+            ProductError::InsufficientInventory => (
+                "INSUFFICIENT_INVENTORY".to_string(),
+                "在庫が不足しています".to_string(),
+                None,
+            ),
+            ProductError::UsedInBundle(bundle_ids) => (
+                "PRODUCT_USED_IN_BUNDLE".to_string(),
+                "セット商品の構成品として使用されています".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([(
+                        "bundle_ids".to_string(),
+                        bundle_ids.join(","),
+                    )])),
+                }),
+            ),
+            ProductError::InvalidStatusTransition { from, to } => (
+                "INVALID_STATUS_TRANSITION".to_string(),
+                format!(
+                    "ステータスを {} から {} に変更することはできません",
+                    from, to
+                ),
+                Some(ProductErrorDetails {
+                    field: Some("status".to_string()),
+                    value: Some(to.to_string()),
+                    constraint: Some(
+                        "Draft→Active、Active⇄Inactive、任意→Discontinued のみ許可されています"
+                            .to_string(),
+                    ),
+                    additional_info: Some(HashMap::from([
+                        ("from".to_string(), from.to_string()),
+                        ("to".to_string(), to.to_string()),
+                    ])),
+                }),
+            ),
+            ProductError::ActivationRequirementsNotMet(missing) => (
+                "ACTIVATION_REQUIREMENTS_NOT_MET".to_string(),
+                "公開に必要な情報が不足しています".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some(missing.join(",")),
+                    value: None,
+                    constraint: Some("価格・1枚以上の画像・カテゴリが必要です".to_string()),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::InvalidSchedule => (
+                "INVALID_SCHEDULE".to_string(),
+                "ステータス変更の予約内容が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("scheduled_at".to_string()),
+                    value: None,
+                    constraint: Some(
+                        "実行日時は未来、変更先は Active/Inactive/Discontinued である必要があります"
+                            .to_string(),
+                    ),
+                    additional_info: None,
+                }),
+            ),
+            ProductError::ScheduleNotFound => (
+                "SCHEDULE_NOT_FOUND".to_string(),
+                "取り消し可能な予約が見つかりません".to_string(),
+                None,
+            ),
+            ProductError::InvalidRollbackTarget(reason) => (
+                "INVALID_ROLLBACK_TARGET".to_string(),
+                "ロールバック先が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: Some(
+                        "history_id または at のどちらか一方を指定してください".to_string(),
+                    ),
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::HistoryNotFound => (
+                "HISTORY_NOT_FOUND".to_string(),
+                "指定された履歴が見つかりません".to_string(),
+                None,
+            ),
+            ProductError::VersionMismatch { expected, actual } => (
+                "VERSION_MISMATCH".to_string(),
+                "商品は他の操作により更新されています".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("version".to_string()),
+                    value: Some(expected.to_string()),
+                    constraint: Some("最新の内容を取得してから再度更新してください".to_string()),
+                    additional_info: Some(HashMap::from([(
+                        "current_version".to_string(),
+                        actual.to_string(),
+                    )])),
+                }),
+            ),
+            ProductError::InvalidImport(reason) => (
+                "INVALID_IMPORT_FILE".to_string(),
+                "インポートファイルの形式が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::ImportJobNotFound => (
+                "IMPORT_JOB_NOT_FOUND".to_string(),
+                "インポートジョブが見つかりません".to_string(),
+                None,
+            ),
+            ProductError::InvalidExport(reason) => (
+                "INVALID_EXPORT_REQUEST".to_string(),
+                "エクスポートの指定が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::InvalidFilter(reason) => (
+                "INVALID_PRODUCT_FILTER".to_string(),
+                "絞り込み条件が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::InvalidBulkUpdate(reason) => (
+                "INVALID_BULK_UPDATE".to_string(),
+                "一括更新の内容が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::BulkCountMismatch { expected, actual } => (
+                "BULK_UPDATE_COUNT_MISMATCH".to_string(),
+                "対象商品数がプレビュー時から変わっています".to_string(),
+                Some(ProductErrorDetails {
+                    field: Some("expected_count".to_string()),
+                    value: Some(expected.to_string()),
+                    constraint: Some("プレビューで対象を確認してから再度実行してください".to_string()),
+                    additional_info: Some(HashMap::from([(
+                        "current_count".to_string(),
+                        actual.to_string(),
+                    )])),
+                }),
+            ),
+            ProductError::InvalidTranslation(reason) => (
+                "PRODUCT_INVALID_TRANSLATION".to_string(),
+                "翻訳の内容が不正です".to_string(),
+                Some(ProductErrorDetails {
+                    field: None,
+                    value: None,
+                    constraint: None,
+                    additional_info: Some(HashMap::from([("reason".to_string(), reason)])),
+                }),
+            ),
+            ProductError::TranslationNotFound => (
+                "PRODUCT_TRANSLATION_NOT_FOUND".to_string(),
+                "指定されたロケールの翻訳が見つかりません".to_string(),
+                None,
+            ),
             // ProductError::CategoryNotFound => (
             //     "CATEGORY_NOT_FOUND".to_string(),
             //     "指定されたカテゴリが存在しません".to_string(),
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::application::dto::category_dto::{CategoryPathItem, CategoryResponse};
+use crate::application::dto::product_dto::ProductResponse;
+
+// Request DTOs
+#[derive(Deserialize)]
+pub struct ResolveSlugQuery {
+    /// カテゴリのスラッグを `/` でつないだパス（末尾は商品のスラッグでもよい）
+    pub path: String,
+}
+
+// Response DTOs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugResourceType {
+    Category,
+    Product,
+}
+
+#[derive(Serialize)]
+pub struct SlugResolutionResponse {
+    pub resource_type: SlugResourceType,
+    /// 現在のスラッグによる正規のパス
+    pub canonical_path: String,
+    /// 旧スラッグや移動前のパスで指定されたため、正規のパスへ転送すべきか
+    pub redirect: bool,
+    /// ルートから対象（商品の場合は所属カテゴリ）までのカテゴリ
+    pub breadcrumb: Vec<CategoryPathItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<CategoryResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<ProductResponse>,
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_domain::model::localization::{
+    CategoryTranslation, MissingTranslation, ProductTranslation,
+};
+
+/// 読み取り API でロケールを指定するクエリ（Accept-Language より優先）
+#[derive(Debug, Default, Deserialize)]
+pub struct LocaleQuery {
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageAltTextRequest {
+    pub image_id: String,
+    pub alt_text: String,
+}
+
+/// ロケールの翻訳をまとめて置き換える（省略・空の項目は既定ロケールの内容で表示する）
+#[derive(Debug, Deserialize)]
+pub struct ProductTranslationRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub images: Vec<ImageAltTextRequest>,
+}
+
+impl ProductTranslationRequest {
+    pub fn into_translation(self, locale: String) -> ProductTranslation {
+        ProductTranslation {
+            locale,
+            name: self.name,
+            description: self.description,
+            image_alt_texts: self
+                .images
+                .into_iter()
+                .map(|image| (image.image_id, image.alt_text))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryTranslationRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl CategoryTranslationRequest {
+    pub fn into_translation(self, locale: String) -> CategoryTranslation {
+        CategoryTranslation {
+            locale,
+            name: self.name,
+            description: self.description,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageAltTextResponse {
+    pub image_id: String,
+    pub alt_text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductTranslationResponse {
+    pub locale: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub images: Vec<ImageAltTextResponse>,
+    /// 既定ロケールの内容があるのに翻訳されていない項目
+    pub missing_fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductTranslationsResponse {
+    pub product_id: String,
+    pub default_locale: String,
+    /// 既定ロケール以外のサポート対象ロケールごとの翻訳
+    pub translations: Vec<ProductTranslationResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryTranslationResponse {
+    pub locale: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 既定ロケールの内容があるのに翻訳されていない項目
+    pub missing_fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryTranslationsResponse {
+    pub category_id: String,
+    pub default_locale: String,
+    /// 既定ロケール以外のサポート対象ロケールごとの翻訳
+    pub translations: Vec<CategoryTranslationResponse>,
+}
+
+/// 翻訳の不足レポートの対象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslatableEntity {
+    Product,
+    Category,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MissingTranslationsQuery {
+    /// 省略時は既定ロケール以外のすべてのサポート対象ロケール
+    pub locale: Option<String>,
+    /// 省略時は商品とカテゴリの両方
+    pub entity_type: Option<TranslatableEntity>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingTranslationResponse {
+    pub id: String,
+    /// 既定ロケールの名前
+    pub name: String,
+    pub missing_fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocaleMissingTranslations {
+    pub locale: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub products: Option<Vec<MissingTranslationResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<MissingTranslationResponse>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingTranslationsResponse {
+    pub default_locale: String,
+    pub locales: Vec<LocaleMissingTranslations>,
+}
+
+impl From<MissingTranslation> for MissingTranslationResponse {
+    fn from(missing: MissingTranslation) -> Self {
+        MissingTranslationResponse {
+            id: missing.id,
+            name: missing.name,
+            missing_fields: missing.fields,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app_domain::model::domain_event::DomainEventType;
+use crate::app_domain::model::webhook::{WebhookDelivery, WebhookSubscription};
+
+// Request DTOs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// 受け取るイベント種別（省略・空の場合はすべて）
+    pub event_types: Option<Vec<DomainEventType>>,
+    /// 省略した場合はサーバー側で生成する
+    pub secret: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<DomainEventType>>,
+    pub secret: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    pub limit: Option<i64>,
+}
+
+// Response DTOs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookSubscriptionResponse {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    /// 作成時のみ返す
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub is_active: bool,
+    pub consecutive_failures: i32,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: i64,
+    pub subscription_id: String,
+    pub event_id: i64,
+    pub event_type: DomainEventType,
+    pub attempts: i32,
+    pub status_code: Option<i32>,
+    pub latency_ms: Option<i64>,
+    pub last_error: Option<String>,
+    pub succeeded: bool,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: DateTime<Utc>,
+}
+
+impl From<WebhookSubscription> for WebhookSubscriptionResponse {
+    fn from(subscription: WebhookSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            url: subscription.url,
+            event_types: subscription.event_types,
+            secret: None,
+            is_active: subscription.is_active,
+            consecutive_failures: subscription.consecutive_failures,
+            disabled_at: subscription.disabled_at,
+            created_at: subscription.created_at,
+            updated_at: subscription.updated_at,
+        }
+    }
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            subscription_id: delivery.subscription_id,
+            event_id: delivery.event_id,
+            event_type: delivery.event_type,
+            attempts: delivery.attempts,
+            status_code: delivery.status_code,
+            latency_ms: delivery.latency_ms,
+            last_error: delivery.last_error,
+            succeeded: delivery.succeeded,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            last_attempt_at: delivery.last_attempt_at,
+        }
+    }
+}
@@ -3,15 +3,25 @@ use std::sync::Arc;
 use tracing::{error, info};
 use uuid::Uuid;
 
-#[cfg(test)]
-use crate::app_domain::model::category::CategoryPath;
-use crate::app_domain::model::category::{Category, CategoryError};
+use crate::app_domain::model::attribute_schema::AttributeDefinition;
+use crate::app_domain::model::category::{Category, CategoryError, ProductDisposition};
+use crate::app_domain::model::change_event::{ChangeKind, EntityType};
+use crate::app_domain::model::localization::{CategoryTranslation, Locales};
+use crate::app_domain::model::product::ChangeContext;
+use crate::app_domain::model::slug;
 use crate::app_domain::repository::category_repository::CategoryRepository;
 use crate::application::dto::category_dto::{
-    CategoriesResponse, CategoryListResponse, CategoryPathItem, CategoryPathResponse,
-    CategoryResponse, CategoryTreesResponse, CreateCategoryRequest, MoveCategoryRequest,
-    UpdateCategoryRequest,
+    AttributeDefinitionResponse, CategoriesResponse, CategoryAttributesResponse,
+    CategoryListResponse, CategoryPathItem, CategoryPathResponse, CategoryResponse,
+    CategoryTreesResponse, CopyCategoryRequest, CreateCategoryRequest, LocalizeCategories,
+    MergeCategoryRequest, MoveCategoryRequest, ReorderCategoriesRequest,
+    ReplaceAttributeDefinitionsRequest, UpdateCategoryRequest,
+};
+use crate::application::dto::translation_dto::{
+    CategoryTranslationRequest, CategoryTranslationResponse, CategoryTranslationsResponse,
+    MissingTranslationResponse,
 };
+use crate::application::service::change_feed::ChangeFeed;
 use crate::infrastructure::metrics::Metrics;
 
 /// カテゴリ関連のユースケースを提供するサービス層。
@@ -20,6 +30,8 @@ use crate::infrastructure::metrics::Metrics;
 /// 監査・メトリクス・ロギングなどクロスカットな処理を担う。
 pub struct CategoryService {
     repository: Arc<dyn CategoryRepository>,
+    changes: Option<Arc<ChangeFeed>>,
+    locales: Locales,
 }
 
 impl CategoryService {
@@ -28,7 +40,33 @@ impl CategoryService {
     /// # 引数
     /// * `repository` - `CategoryRepository` の実装をラップした `Arc`。
     pub fn new(repository: Arc<dyn CategoryRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            changes: None,
+            locales: Locales::default(),
+        }
+    }
+
+    /// 翻訳を扱うロケールを設定します。
+    pub fn with_locales(mut self, locales: Locales) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    pub fn locales(&self) -> &Locales {
+        &self.locales
+    }
+
+    /// 作成・更新・移動を変更フィードに通知するようにします。
+    pub fn with_change_feed(mut self, changes: Arc<ChangeFeed>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    fn notify(&self, kind: ChangeKind, category: &Category, changed_fields: &[&str]) {
+        if let Some(changes) = &self.changes {
+            changes.publish(kind, category.into(), changed_fields);
+        }
     }
 
     /// 全カテゴリを取得します。
@@ -56,7 +94,7 @@ impl CategoryService {
 
     /// 複数の `Category` を `CategoryListResponse` に変換します。
     ///
-    /// 子カテゴリ数の取得を *並列* に行い、N+1 問題を軽減します。
+    /// 子カテゴリ数・商品数の取得を *並列* に行い、N+1 問題を軽減します（結果は入力の順序を保ちます）。
     /// 同時実行数を制限してDB接続プールの枯渇を防ぎます。
     async fn build_category_list_response(
         &self,
@@ -68,31 +106,39 @@ impl CategoryService {
                 let repo = Arc::clone(&self.repository);
                 async move {
                     let children_count = repo.count_children(&category_clone.id).await;
+                    let product_count = repo.count_products(&category_clone.id).await;
                     CategoryListResponse {
                         id: category_clone.id,
                         name: category_clone.name,
+                        slug: category_clone.slug,
                         description: category_clone.description,
                         parent_id: category_clone.parent_id,
                         sort_order: category_clone.sort_order,
                         is_active: category_clone.is_active,
                         children_count,
+                        product_count: product_count.direct,
+                        total_product_count: product_count.total,
                         created_at: category_clone.created_at,
                         updated_at: category_clone.updated_at,
                     }
                 }
             })
-            .buffer_unordered(8);
+            .buffered(8);
 
         tasks_stream.collect::<Vec<_>>().await
     }
 
-    /// ID でカテゴリを取得します。
+    /// ID でカテゴリを取得します（直下および子孫を含む商品数付き）。
     pub async fn find_by_id(&self, id: &str) -> Result<CategoryResponse, CategoryError> {
         Metrics::with_metrics("category", "find_by_id", async {
             match self.repository.find_by_id(id).await {
                 Some(category) => {
                     info!("Fetched category {}", id);
-                    Ok(category.into())
+                    let product_count = self.repository.count_products(id).await;
+                    let mut response = CategoryResponse::from(category);
+                    response.product_count = Some(product_count.direct);
+                    response.total_product_count = Some(product_count.total);
+                    Ok(response)
                 }
                 None => {
                     error!("Category {} not found", id);
@@ -146,29 +192,56 @@ impl CategoryService {
             .await
     }
 
+    /// 指定カテゴリの子孫カテゴリ一覧を浅い階層から順に取得します（自分自身は含みません）。
+    pub async fn find_descendants(
+        &self,
+        id: &str,
+        include_inactive: bool,
+    ) -> Result<CategoriesResponse, CategoryError> {
+        Metrics::with_metrics("category", "find_descendants", async {
+            let categories = self.repository.find_descendants(id, include_inactive).await;
+
+            let category_list = self.build_category_list_response(&categories).await;
+
+            info!(
+                "Fetched {} descendants for category {}",
+                categories.len(),
+                id
+            );
+
+            let total = category_list.len();
+            Ok(CategoriesResponse {
+                categories: category_list,
+                total,
+            })
+        })
+        .await
+    }
+
     /// 指定カテゴリからルートまでのパス情報を取得します。
     ///
     /// 戻り値の `depth` はルートからの階層深さを示します。
     pub async fn find_path(&self, id: &str) -> Result<CategoryPathResponse, CategoryError> {
         Metrics::with_metrics("category", "find_path", async {
-            let path = self.repository.find_path(id).await?;
-
-            // Enrich path with category names
-            let mut path_items = Vec::new();
-            for category_id in &path.path {
-                if let Some(category) = self.repository.find_by_id(category_id).await {
-                    path_items.push(CategoryPathItem {
-                        id: category.id,
-                        name: category.name,
-                    });
-                }
-            }
-
-            info!("Fetched path for category {}, depth: {}", id, path.depth);
+            // 祖先は閉包テーブルから1回のクエリで取得する
+            let path_items: Vec<CategoryPathItem> = self
+                .repository
+                .find_ancestors(id)
+                .await?
+                .into_iter()
+                .map(|category| CategoryPathItem {
+                    id: category.id,
+                    name: category.name,
+                    slug: category.slug,
+                })
+                .collect();
+            let depth = path_items.len();
+
+            info!("Fetched path for category {}, depth: {}", id, depth);
 
             Ok(CategoryPathResponse {
                 path: path_items,
-                depth: path.depth,
+                depth,
             })
         })
         .await
@@ -203,12 +276,29 @@ impl CategoryService {
             // 一意な ID を UUID v4 で生成
             let id = format!("cat_{}", Uuid::new_v4());
 
-            let category =
+            let mut category =
                 Category::new(id, req.name, req.description, req.parent_id, req.sort_order);
+            // 名前が空の場合もスラッグを生成できないため、名前の検証を先に行う
+            category.validate_name()?;
+            match req.slug {
+                Some(slug) => category.update_slug(slug)?,
+                None if category.slug.is_empty() => {
+                    return Err(CategoryError::InvalidSlug(
+                        "カテゴリ名からスラッグを生成できないため、スラッグを指定してください"
+                            .to_string(),
+                    ))
+                }
+                None => {
+                    category.slug = self
+                        .available_slug(category.parent_id.clone(), &category.slug)
+                        .await
+                }
+            }
 
             match self.repository.create(category).await {
                 Ok(created_category) => {
                     info!("Created category with id {}", created_category.id);
+                    self.notify(ChangeKind::Created, &created_category, &[]);
                     Ok(created_category.into())
                 }
                 Err(e) => {
@@ -220,23 +310,111 @@ impl CategoryService {
         .await
     }
 
+    /// 名前から生成したスラッグが兄弟と重なる場合は `-2` などの連番を付けます。
+    async fn available_slug(&self, parent_id: Option<String>, base: &str) -> String {
+        let mut n = 1;
+        loop {
+            let candidate = slug::with_suffix(base, n);
+            if self
+                .repository
+                .find_by_slug(parent_id.clone(), &candidate)
+                .await
+                .is_none()
+            {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    /// 子カテゴリや商品が残っていて削除できない場合にエラーを返します（削除前の検証用）。
+    pub async fn check_deletable(&self, id: &str) -> Result<(), CategoryError> {
+        if self.repository.find_by_id(id).await.is_none() {
+            return Err(CategoryError::NotFound(
+                "カテゴリが見つかりません".to_string(),
+            ));
+        }
+        if self.repository.count_children(id).await > 0 {
+            return Err(CategoryError::HasChildren(
+                "子カテゴリが存在するため削除できません".to_string(),
+            ));
+        }
+        let product_count = self.repository.count_products(id).await;
+        if product_count.direct > 0 {
+            return Err(CategoryError::HasProducts(format!(
+                "商品が{}件紐づいているため削除できません",
+                product_count.direct
+            )));
+        }
+        Ok(())
+    }
+
+    /// カテゴリを削除します。`disposition` を指定した場合は、紐づく商品を付け替える、
+    /// または非アクティブにしてから削除します。
+    ///
+    /// `expected_version` を指定した場合、バージョンの照合は削除と同じトランザクションで行います。
+    /// 商品を処理した場合は `ctx` の操作者と変更理由を商品の変更履歴に記録します。
+    pub async fn delete(
+        &self,
+        id: &str,
+        disposition: Option<ProductDisposition>,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<(), CategoryError> {
+        let operation = if disposition.is_some() {
+            "delete_with_products"
+        } else {
+            "delete"
+        };
+        Metrics::with_metrics("category", operation, async {
+            let deleted = match disposition.clone() {
+                Some(disposition) => {
+                    self.repository
+                        .delete_with_products(id, disposition, expected_version, ctx)
+                        .await?
+                }
+                None => self.repository.delete(id, expected_version, ctx).await?,
+            };
+            if !deleted {
+                return Err(CategoryError::NotFound(
+                    "カテゴリが見つかりません".to_string(),
+                ));
+            }
+            info!("Deleted category {} with products {:?}", id, disposition);
+            if let Some(changes) = &self.changes {
+                changes.publish_id(EntityType::Category, id, ChangeKind::Deleted);
+            }
+            Ok(())
+        })
+        .await
+    }
+
     /// 既存カテゴリを更新します。
+    ///
+    /// `expected_version` を指定した場合、現在のバージョンと一致しなければ
+    /// `CategoryError::VersionMismatch` を返します。
     pub async fn update(
         &self,
         id: &str,
         req: UpdateCategoryRequest,
+        expected_version: Option<i64>,
     ) -> Result<CategoryResponse, CategoryError> {
         Metrics::with_metrics("category", "update", async {
             let mut category =
                 self.repository.find_by_id(id).await.ok_or_else(|| {
                     CategoryError::NotFound("カテゴリが見つかりません".to_string())
                 })?;
+            category.check_version(expected_version)?;
 
             // Update fields if provided
             if let Some(name) = req.name {
                 category.update_name(name)?;
             }
 
+            if let Some(slug) = req.slug {
+                category.update_slug(slug)?;
+            }
+
             if let Some(description) = req.description {
                 category.update_description(Some(description));
             }
@@ -256,6 +434,7 @@ impl CategoryService {
             match self.repository.update(category).await {
                 Ok(updated_category) => {
                     info!("Updated category {}", id);
+                    self.notify(ChangeKind::Updated, &updated_category, &[]);
                     Ok(updated_category.into())
                 }
                 Err(e) => {
@@ -272,13 +451,14 @@ impl CategoryService {
         &self,
         id: &str,
         req: MoveCategoryRequest,
+        expected_version: Option<i64>,
     ) -> Result<CategoryResponse, CategoryError> {
         Metrics::with_metrics("category", "move", async {
             let parent_id = req.new_parent_id.clone();
             let sort_order = req.new_sort_order.unwrap_or(0);
             match self
                 .repository
-                .move_category(id, req.new_parent_id, sort_order)
+                .move_category(id, req.new_parent_id, sort_order, expected_version)
                 .await
             {
                 Ok(moved_category) => {
@@ -286,6 +466,11 @@ impl CategoryService {
                         "Moved category {} to parent {:?} with sort order {}",
                         id, parent_id, sort_order
                     );
+                    self.notify(
+                        ChangeKind::Updated,
+                        &moved_category,
+                        &["parent_id", "sort_order"],
+                    );
                     Ok(moved_category.into())
                 }
                 Err(e) => {
@@ -296,14 +481,361 @@ impl CategoryService {
         })
         .await
     }
+
+    /// 子カテゴリ（`parent_id` が `None` の場合はルートカテゴリ）の並び順を一括で変更します。
+    ///
+    /// # 失敗時
+    /// * `CategoryError::InvalidSortOrder` - ID の一覧が現在の子カテゴリと一致しない場合
+    pub async fn reorder_children(
+        &self,
+        parent_id: Option<String>,
+        req: ReorderCategoriesRequest,
+    ) -> Result<CategoriesResponse, CategoryError> {
+        Metrics::with_metrics("category", "reorder_children", async {
+            match self
+                .repository
+                .reorder_children(parent_id.clone(), req.child_ids)
+                .await
+            {
+                Ok(children) => {
+                    info!(
+                        "Reordered {} categories under parent {:?}",
+                        children.len(),
+                        parent_id
+                    );
+                    for child in &children {
+                        self.notify(ChangeKind::Updated, child, &["sort_order"]);
+                    }
+
+                    let category_list = self.build_category_list_response(&children).await;
+                    let total = category_list.len();
+                    Ok(CategoriesResponse {
+                        categories: category_list,
+                        total,
+                    })
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to reorder categories under parent {:?}: {}",
+                        parent_id, e
+                    );
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    /// カテゴリ自身の商品属性の定義と、祖先カテゴリから継承したものを含む定義を取得します。
+    pub async fn find_attributes(
+        &self,
+        id: &str,
+    ) -> Result<CategoryAttributesResponse, CategoryError> {
+        Metrics::with_metrics("category", "find_attributes", async {
+            if self.repository.find_by_id(id).await.is_none() {
+                return Err(CategoryError::NotFound(
+                    "カテゴリが見つかりません".to_string(),
+                ));
+            }
+            let attributes = self.repository.find_attribute_definitions(id).await?;
+            self.attributes_response(id, attributes).await
+        })
+        .await
+    }
+
+    /// カテゴリ自身の商品属性の定義を置き換えます（子孫カテゴリはこれを継承します）。
+    ///
+    /// 定義の変更は既存の商品には遡って適用せず、次回の作成・更新時に検証されます。
+    pub async fn replace_attributes(
+        &self,
+        id: &str,
+        req: ReplaceAttributeDefinitionsRequest,
+    ) -> Result<CategoryAttributesResponse, CategoryError> {
+        Metrics::with_metrics("category", "replace_attributes", async {
+            let definitions = AttributeDefinition::validate_all(
+                req.attributes.into_iter().map(Into::into).collect(),
+            )
+            .map_err(CategoryError::InvalidAttributeDefinition)?;
+
+            match self
+                .repository
+                .replace_attribute_definitions(id, definitions)
+                .await
+            {
+                Ok(attributes) => {
+                    info!(
+                        "Replaced {} attribute definitions of category {}",
+                        attributes.len(),
+                        id
+                    );
+                    if let Some(category) = self.repository.find_by_id(id).await {
+                        self.notify(ChangeKind::Updated, &category, &["attributes"]);
+                    }
+                    self.attributes_response(id, attributes).await
+                }
+                Err(e) => {
+                    error!("Failed to replace attributes of category {}: {}", id, e);
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    async fn attributes_response(
+        &self,
+        id: &str,
+        attributes: Vec<AttributeDefinition>,
+    ) -> Result<CategoryAttributesResponse, CategoryError> {
+        let schema = self.repository.find_attribute_schema(id).await?;
+        let effective_attributes = schema
+            .iter()
+            .map(|(category_id, definition)| AttributeDefinitionResponse {
+                inherited_from: (category_id != id).then(|| category_id.to_string()),
+                ..definition.clone().into()
+            })
+            .collect();
+        Ok(CategoryAttributesResponse {
+            category_id: id.to_string(),
+            attributes: attributes.into_iter().map(Into::into).collect(),
+            effective_attributes,
+        })
+    }
+
+    /// 既定ロケール以外が指定された場合、翻訳のある名前と説明をそのロケールの内容で置き換えます。
+    pub async fn localize<T: LocalizeCategories>(&self, response: &mut T, locale: &str) {
+        if self.locales.is_default(locale) {
+            return;
+        }
+        let translations = self
+            .repository
+            .find_translations_for(&response.category_ids(), locale)
+            .await;
+        if !translations.is_empty() {
+            response.localize(&translations);
+        }
+    }
+
+    /// 既定ロケール以外のサポート対象ロケールごとの翻訳と、不足している項目を取得します。
+    pub async fn find_translations(
+        &self,
+        id: &str,
+    ) -> Result<CategoryTranslationsResponse, CategoryError> {
+        Metrics::with_metrics("category", "find_translations", async {
+            let category =
+                self.repository.find_by_id(id).await.ok_or_else(|| {
+                    CategoryError::NotFound("カテゴリが見つかりません".to_string())
+                })?;
+            let stored = self.repository.find_translations(id).await?;
+
+            let translations = self
+                .locales
+                .translatable()
+                .map(|locale| {
+                    let translation = stored
+                        .iter()
+                        .find(|t| t.locale == locale)
+                        .cloned()
+                        .unwrap_or_else(|| CategoryTranslation {
+                            locale: locale.to_string(),
+                            ..Default::default()
+                        });
+                    Self::translation_response(&category, translation)
+                })
+                .collect();
+
+            Ok(CategoryTranslationsResponse {
+                category_id: category.id,
+                default_locale: self.locales.default_locale().to_string(),
+                translations,
+            })
+        })
+        .await
+    }
+
+    /// ロケールの名前と説明の翻訳を置き換えます。
+    pub async fn set_translation(
+        &self,
+        id: &str,
+        locale: &str,
+        req: CategoryTranslationRequest,
+    ) -> Result<CategoryTranslationResponse, CategoryError> {
+        Metrics::with_metrics("category", "set_translation", async {
+            let locale = self
+                .locales
+                .translation_locale(locale)
+                .map_err(CategoryError::InvalidTranslation)?;
+            let translation = req
+                .into_translation(locale)
+                .normalize()
+                .map_err(CategoryError::InvalidTranslation)?;
+
+            match self
+                .repository
+                .upsert_translation(id, translation.clone())
+                .await
+            {
+                Ok(()) => {
+                    info!("Set {} translation of category {}", translation.locale, id);
+                    let category = self.repository.find_by_id(id).await.ok_or_else(|| {
+                        CategoryError::NotFound("カテゴリが見つかりません".to_string())
+                    })?;
+                    self.notify(ChangeKind::Updated, &category, &["translations"]);
+                    Ok(Self::translation_response(&category, translation))
+                }
+                Err(e) => {
+                    error!("Failed to set translation of category {}: {}", id, e);
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    /// ロケールの翻訳を削除します（既定ロケールの内容で表示されるようになります）。
+    pub async fn delete_translation(&self, id: &str, locale: &str) -> Result<(), CategoryError> {
+        Metrics::with_metrics("category", "delete_translation", async {
+            let locale = self
+                .locales
+                .translation_locale(locale)
+                .map_err(CategoryError::InvalidTranslation)?;
+            if !self.repository.delete_translation(id, &locale).await? {
+                return Err(CategoryError::NotFound("翻訳が見つかりません".to_string()));
+            }
+            info!("Deleted {} translation of category {}", locale, id);
+            if let Some(category) = self.repository.find_by_id(id).await {
+                self.notify(ChangeKind::Updated, &category, &["translations"]);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// ロケールの翻訳が不足しているカテゴリを ID 順に取得します（非アクティブなカテゴリを含む）。
+    pub async fn find_missing_translations(
+        &self,
+        locale: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<MissingTranslationResponse>, CategoryError> {
+        Metrics::with_metrics("category", "find_missing_translations", async {
+            let locale = self
+                .locales
+                .translation_locale(locale)
+                .map_err(CategoryError::InvalidTranslation)?;
+            let translations = self.repository.find_translations_by_locale(&locale).await;
+            let mut categories = self.repository.find_all(true).await;
+            categories.sort_by(|a, b| a.id.cmp(&b.id));
+
+            Ok(categories
+                .into_iter()
+                .filter_map(|category| {
+                    let fields = CategoryTranslation::missing_fields(
+                        &category,
+                        translations.get(&category.id),
+                    );
+                    (!fields.is_empty()).then_some(MissingTranslationResponse {
+                        id: category.id,
+                        name: category.name,
+                        missing_fields: fields,
+                    })
+                })
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect())
+        })
+        .await
+    }
+
+    fn translation_response(
+        category: &Category,
+        translation: CategoryTranslation,
+    ) -> CategoryTranslationResponse {
+        let missing_fields = CategoryTranslation::missing_fields(category, Some(&translation));
+        CategoryTranslationResponse {
+            locale: translation.locale,
+            name: translation.name,
+            description: translation.description,
+            missing_fields,
+        }
+    }
+
+    /// サブツリーを新しい ID で複製します（商品は複製しません）。
+    pub async fn copy(
+        &self,
+        id: &str,
+        req: CopyCategoryRequest,
+    ) -> Result<CategoryResponse, CategoryError> {
+        Metrics::with_metrics("category", "copy", async {
+            let sort_order = req.new_sort_order.unwrap_or(i32::MAX);
+            match self
+                .repository
+                .copy_subtree(id, req.new_parent_id, req.name, sort_order)
+                .await
+            {
+                Ok(copied_category) => {
+                    info!("Copied category {} as {}", id, copied_category.id);
+                    self.notify(ChangeKind::Created, &copied_category, &[]);
+                    Ok(copied_category.into())
+                }
+                Err(e) => {
+                    error!("Failed to copy category {}: {}", id, e);
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    /// 子カテゴリと商品を統合先へ付け替えてから、カテゴリを削除します。
+    ///
+    /// 戻り値は統合後の統合先カテゴリです（商品数付き）。
+    pub async fn merge(
+        &self,
+        id: &str,
+        req: MergeCategoryRequest,
+        ctx: &ChangeContext,
+    ) -> Result<CategoryResponse, CategoryError> {
+        Metrics::with_metrics("category", "merge", async {
+            match self
+                .repository
+                .merge_category(id, &req.target_id, ctx)
+                .await
+            {
+                Ok(target) => {
+                    info!("Merged category {} into {}", id, target.id);
+                    if let Some(changes) = &self.changes {
+                        changes.publish_id(EntityType::Category, id, ChangeKind::Deleted);
+                    }
+                    self.notify(ChangeKind::Updated, &target, &[]);
+
+                    let product_count = self.repository.count_products(&target.id).await;
+                    let mut response = CategoryResponse::from(target);
+                    response.product_count = Some(product_count.direct);
+                    response.total_product_count = Some(product_count.total);
+                    Ok(response)
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to merge category {} into {}: {}",
+                        id, req.target_id, e
+                    );
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app_domain::model::category::ProductCount;
     use crate::app_domain::repository::category_repository::MockCategoryRepository;
     use chrono::Utc;
     use mockall::predicate::*;
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn test_find_by_id_success() {
@@ -312,12 +844,14 @@ mod tests {
         let category = Category {
             id: "cat_123".to_string(),
             name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         mock_repo
@@ -325,6 +859,14 @@ mod tests {
             .with(eq("cat_123"))
             .return_once(move |_| Some(category.clone()));
 
+        mock_repo
+            .expect_count_products()
+            .with(eq("cat_123"))
+            .return_once(|_| ProductCount {
+                direct: 2,
+                total: 5,
+            });
+
         let service = CategoryService::new(Arc::new(mock_repo));
         let result = service.find_by_id("cat_123").await;
 
@@ -332,6 +874,8 @@ mod tests {
         let response = result.unwrap();
         assert_eq!(response.id, "cat_123");
         assert_eq!(response.name, "Electronics");
+        assert_eq!(response.product_count, Some(2));
+        assert_eq!(response.total_product_count, Some(5));
     }
 
     #[tokio::test]
@@ -359,6 +903,7 @@ mod tests {
 
         let request = CreateCategoryRequest {
             name: "Electronics".to_string(),
+            slug: None,
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
@@ -367,17 +912,23 @@ mod tests {
         let expected_category = Category {
             id: "cat_123".to_string(),
             name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
+        mock_repo
+            .expect_find_by_slug()
+            .with(eq(None::<String>), eq("electronics"))
+            .return_once(|_, _| None);
         mock_repo
             .expect_create()
-            .withf(|cat| cat.name == "Electronics")
+            .withf(|cat| cat.name == "Electronics" && cat.slug == "electronics")
             .return_once(move |_| Ok(expected_category));
 
         let service = CategoryService::new(Arc::new(mock_repo));
@@ -395,11 +946,13 @@ mod tests {
 
         let request = CreateCategoryRequest {
             name: "".to_string(), // Invalid empty name
+            slug: None,
             description: None,
             parent_id: None,
             sort_order: 1,
         };
 
+        mock_repo.expect_find_by_slug().returning(|_, _| None);
         mock_repo.expect_create().return_once(|_| {
             Err(CategoryError::InvalidName(
                 "カテゴリ名は必須です".to_string(),
@@ -416,6 +969,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_requires_slug_when_name_has_no_reading() {
+        let mut mock_repo = MockCategoryRepository::new();
+        mock_repo.expect_create().never();
+
+        let request = CreateCategoryRequest {
+            name: "家電".to_string(),
+            slug: None,
+            description: None,
+            parent_id: None,
+            sort_order: 1,
+        };
+
+        let service = CategoryService::new(Arc::new(mock_repo));
+        match service.create(request).await {
+            Err(CategoryError::InvalidSlug(_)) => (),
+            _ => panic!("Expected InvalidSlug error"),
+        }
+    }
+
     #[tokio::test]
     async fn test_update_success() {
         let mut mock_repo = MockCategoryRepository::new();
@@ -423,23 +996,27 @@ mod tests {
         let existing_category = Category {
             id: "cat_123".to_string(),
             name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         let updated_category = Category {
             id: "cat_123".to_string(),
             name: "Updated Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         mock_repo
@@ -449,24 +1026,59 @@ mod tests {
 
         mock_repo
             .expect_update()
-            .withf(|cat| cat.name == "Updated Electronics")
+            // 名前を変えてもスラッグは変わらない
+            .withf(|cat| cat.name == "Updated Electronics" && cat.slug == "electronics")
             .return_once(move |_| Ok(updated_category));
 
         let request = UpdateCategoryRequest {
             name: Some("Updated Electronics".to_string()),
+            slug: None,
             description: None,
             sort_order: None,
             is_active: None,
         };
 
         let service = CategoryService::new(Arc::new(mock_repo));
-        let result = service.update("cat_123", request).await;
+        let result = service.update("cat_123", request, None).await;
 
         assert!(result.is_ok());
         let response = result.unwrap();
         assert_eq!(response.name, "Updated Electronics");
     }
 
+    #[tokio::test]
+    async fn test_update_version_mismatch() {
+        let mut mock_repo = MockCategoryRepository::new();
+
+        let mut existing_category = Category::new(
+            "cat_123".to_string(),
+            "Electronics".to_string(),
+            None,
+            None,
+            1,
+        );
+        existing_category.version = 3;
+
+        mock_repo
+            .expect_find_by_id()
+            .with(eq("cat_123"))
+            .return_once(move |_| Some(existing_category));
+        mock_repo.expect_update().never();
+
+        let request = UpdateCategoryRequest {
+            name: Some("Updated Electronics".to_string()),
+            slug: None,
+            description: None,
+            sort_order: None,
+            is_active: None,
+        };
+
+        let service = CategoryService::new(Arc::new(mock_repo));
+        let result = service.update("cat_123", request, Some(2)).await;
+
+        assert!(matches!(result, Err(CategoryError::VersionMismatch(_))));
+    }
+
     #[tokio::test]
     async fn test_move_category_success() {
         let mut mock_repo = MockCategoryRepository::new();
@@ -474,18 +1086,25 @@ mod tests {
         let moved_category = Category {
             id: "cat_123".to_string(),
             name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
             description: Some("Electronic devices".to_string()),
             parent_id: Some("cat_parent".to_string()),
             sort_order: 2,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         mock_repo
             .expect_move_category()
-            .with(eq("cat_123"), eq(Some("cat_parent".to_string())), eq(2))
-            .return_once(move |_, _, _| Ok(moved_category));
+            .with(
+                eq("cat_123"),
+                eq(Some("cat_parent".to_string())),
+                eq(2),
+                eq(None::<i64>),
+            )
+            .return_once(move |_, _, _, _| Ok(moved_category));
 
         let request = MoveCategoryRequest {
             new_parent_id: Some("cat_parent".to_string()),
@@ -493,7 +1112,7 @@ mod tests {
         };
 
         let service = CategoryService::new(Arc::new(mock_repo));
-        let result = service.move_category("cat_123", request).await;
+        let result = service.move_category("cat_123", request, None).await;
 
         assert!(result.is_ok());
         let response = result.unwrap();
@@ -505,66 +1124,51 @@ mod tests {
     async fn test_find_path_success() {
         let mut mock_repo = MockCategoryRepository::new();
 
-        let path = CategoryPath::new(vec![
-            "cat_root".to_string(),
-            "cat_child".to_string(),
-            "cat_grandchild".to_string(),
-        ]);
-
-        // Mock the path finding
-        mock_repo
-            .expect_find_path()
-            .with(eq("cat_grandchild"))
-            .return_once(move |_| Ok(path));
-
-        // Mock the category finding for enriching path
         let root_category = Category {
             id: "cat_root".to_string(),
             name: "Root".to_string(),
+            slug: "root".to_string(),
             description: None,
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         let child_category = Category {
             id: "cat_child".to_string(),
             name: "Child".to_string(),
+            slug: "child".to_string(),
             description: None,
             parent_id: Some("cat_root".to_string()),
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         let grandchild_category = Category {
             id: "cat_grandchild".to_string(),
             name: "Grandchild".to_string(),
+            slug: "grandchild".to_string(),
             description: None,
             parent_id: Some("cat_child".to_string()),
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
+        // Ancestors are fetched root first in a single call
         mock_repo
-            .expect_find_by_id()
-            .with(eq("cat_root"))
-            .return_once(move |_| Some(root_category));
-
-        mock_repo
-            .expect_find_by_id()
-            .with(eq("cat_child"))
-            .return_once(move |_| Some(child_category));
-
-        mock_repo
-            .expect_find_by_id()
+            .expect_find_ancestors()
             .with(eq("cat_grandchild"))
-            .return_once(move |_| Some(grandchild_category));
+            .times(1)
+            .return_once(move |_| Ok(vec![root_category, child_category, grandchild_category]));
 
         let service = CategoryService::new(Arc::new(mock_repo));
         let result = service.find_path("cat_grandchild").await;
@@ -586,23 +1190,27 @@ mod tests {
         let category1 = Category {
             id: "cat_1".to_string(),
             name: "Category 1".to_string(),
+            slug: "category-1".to_string(),
             description: Some("Description 1".to_string()),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         let category2 = Category {
             id: "cat_2".to_string(),
             name: "Category 2".to_string(),
+            slug: "category-2".to_string(),
             description: Some("Description 2".to_string()),
             parent_id: None,
             sort_order: 2,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         };
 
         // Mock count_children calls - verify parallel execution works
@@ -616,6 +1224,22 @@ mod tests {
             .with(eq("cat_2"))
             .return_once(|_| 0);
 
+        mock_repo
+            .expect_count_products()
+            .with(eq("cat_1"))
+            .return_once(|_| ProductCount {
+                direct: 0,
+                total: 3,
+            });
+
+        mock_repo
+            .expect_count_products()
+            .with(eq("cat_2"))
+            .return_once(|_| ProductCount {
+                direct: 4,
+                total: 4,
+            });
+
         let service = CategoryService::new(Arc::new(mock_repo));
         let categories = vec![category1.clone(), category2.clone()];
 
@@ -627,11 +1251,14 @@ mod tests {
         let cat1_response = result.iter().find(|r| r.id == "cat_1").unwrap();
         assert_eq!(cat1_response.name, "Category 1");
         assert_eq!(cat1_response.children_count, 2);
+        assert_eq!(cat1_response.product_count, 0);
+        assert_eq!(cat1_response.total_product_count, 3);
 
         // Verify second category
         let cat2_response = result.iter().find(|r| r.id == "cat_2").unwrap();
         assert_eq!(cat2_response.name, "Category 2");
         assert_eq!(cat2_response.children_count, 0);
+        assert_eq!(cat2_response.product_count, 4);
     }
 
     #[tokio::test]
@@ -652,22 +1279,26 @@ mod tests {
             Category {
                 id: "cat_1".to_string(),
                 name: "Category 1".to_string(),
+                slug: "category-1".to_string(),
                 description: None,
                 parent_id: None,
                 sort_order: 1,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
+                version: 1,
             },
             Category {
                 id: "cat_2".to_string(),
                 name: "Category 2".to_string(),
+                slug: "category-2".to_string(),
                 description: None,
                 parent_id: None,
                 sort_order: 2,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
+                version: 1,
             },
         ];
 
@@ -686,6 +1317,11 @@ mod tests {
             .with(eq("cat_2"))
             .return_once(|_| 0);
 
+        mock_repo
+            .expect_count_products()
+            .times(2)
+            .returning(|_| ProductCount::default());
+
         let service = CategoryService::new(Arc::new(mock_repo));
         let result = service.find_all(true).await;
 
@@ -695,4 +1331,88 @@ mod tests {
         assert_eq!(response.total, 2);
         assert_eq!(response.categories.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_localize_and_find_missing_translations() {
+        let mut mock_repo = MockCategoryRepository::new();
+
+        let category = |id: &str, name: &str, description: Option<&str>| Category {
+            id: id.to_string(),
+            name: name.to_string(),
+            slug: id.to_string(),
+            description: description.map(str::to_string),
+            parent_id: None,
+            sort_order: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        };
+        let categories = vec![
+            category("cat_2", "本", Some("書籍")),
+            category("cat_1", "家電", Some("電化製品")),
+        ];
+
+        let electronics = || {
+            HashMap::from([(
+                "cat_1".to_string(),
+                CategoryTranslation {
+                    locale: "en".to_string(),
+                    name: Some("Electronics".to_string()),
+                    description: None,
+                },
+            )])
+        };
+        mock_repo
+            .expect_find_translations_for()
+            .withf(|ids, locale| ids == ["cat_1".to_string()] && locale == "en")
+            .times(1)
+            .returning(move |_, _| electronics());
+        mock_repo
+            .expect_find_translations_by_locale()
+            .with(eq("en"))
+            .times(1)
+            .returning(move |_| electronics());
+        mock_repo
+            .expect_find_all()
+            .with(eq(true))
+            .return_once(move |_| categories);
+
+        let service = CategoryService::new(Arc::new(mock_repo));
+
+        let mut response: CategoryResponse = category("cat_1", "家電", Some("電化製品")).into();
+        service.localize(&mut response, "ja").await;
+        assert_eq!(response.name, "家電");
+        service.localize(&mut response, "en").await;
+        assert_eq!(response.name, "Electronics");
+        assert_eq!(response.description.as_deref(), Some("電化製品"));
+
+        let missing = service
+            .find_missing_translations("en", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(missing.len(), 2);
+        assert_eq!(missing[0].id, "cat_1");
+        assert_eq!(missing[0].missing_fields, vec!["description"]);
+        assert_eq!(missing[1].missing_fields, vec!["name", "description"]);
+    }
+
+    #[tokio::test]
+    async fn test_set_translation_rejects_default_locale() {
+        let mock_repo = MockCategoryRepository::new();
+        let service = CategoryService::new(Arc::new(mock_repo));
+
+        let result = service
+            .set_translation(
+                "cat_1",
+                "ja",
+                CategoryTranslationRequest {
+                    name: Some("家電".to_string()),
+                    description: None,
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(CategoryError::InvalidTranslation(_))));
+    }
 }
@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::app_domain::model::change_event::{
+    ChangeCursor, ChangeEvent, ChangeKind, ChangePayload, EntityType,
+};
+
+/// 購読者ごとに溜められる未配信イベント数（超えた場合は履歴から補う）
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeFeedError {
+    /// 指定されたカーソル以降のイベントが履歴に残っていない、またはカーソルが
+    /// 別のプロセスのもの（全件の再取得が必要）
+    CursorExpired {
+        cursor: ChangeCursor,
+        oldest: ChangeCursor,
+    },
+    /// フィードが停止した
+    Closed,
+}
+
+impl fmt::Display for ChangeFeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeFeedError::CursorExpired { cursor, oldest } => write!(
+                f,
+                "カーソル {} から再開できません（保持している最古のイベントは {}）",
+                cursor, oldest
+            ),
+            ChangeFeedError::Closed => write!(f, "変更フィードが停止しました"),
+        }
+    }
+}
+
+/// 購読者に送るメッセージ
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeFeedMessage {
+    Event(ChangeEvent),
+    /// 接続維持用。`cursor` はフィルタで除外した分も含めた処理済みの位置
+    Heartbeat {
+        cursor: ChangeCursor,
+        sent_at: DateTime<Utc>,
+    },
+}
+
+struct FeedState {
+    last_sequence: u64,
+    history: VecDeque<ChangeEvent>,
+}
+
+/// アイテム・商品・カテゴリの変更を通知するフィード
+///
+/// 直近 `history_size` 件のイベントをメモリに保持し、切断後はカーソルの次のイベントから
+/// 再開できる。シーケンスはプロセスの起動ごとに 1 から振り直されるため、カーソルには
+/// プロセスごとの `epoch` を含め、再起動前や別のレプリカのカーソルは `CursorExpired` にする。
+/// 各プロセスが通知するのは自身で行った変更のみ。
+pub struct ChangeFeed {
+    epoch: u64,
+    state: Mutex<FeedState>,
+    sender: broadcast::Sender<ChangeEvent>,
+    history_size: usize,
+}
+
+impl ChangeFeed {
+    pub fn new(history_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            // JSON の数値として扱えるよう 53 ビットに収める
+            epoch: (Uuid::new_v4().as_u64_pair().0 >> 11).max(1),
+            state: Mutex::new(FeedState {
+                last_sequence: 0,
+                history: VecDeque::with_capacity(history_size),
+            }),
+            sender,
+            history_size,
+        }
+    }
+
+    /// このプロセスのフィードを表す値。カーソルの `epoch` と比較する
+    #[allow(dead_code)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// 変更後の状態を添えて通知する
+    pub fn publish(&self, kind: ChangeKind, payload: ChangePayload, changed_fields: &[&str]) {
+        self.push(
+            payload.entity_type(),
+            payload.entity_id(),
+            kind,
+            changed_fields,
+            Some(payload),
+        );
+    }
+
+    /// 状態を持たない変更（削除・復元）を通知する
+    pub fn publish_id(&self, entity_type: EntityType, entity_id: impl ToString, kind: ChangeKind) {
+        self.push(entity_type, entity_id.to_string(), kind, &[], None);
+    }
+
+    fn push(
+        &self,
+        entity_type: EntityType,
+        entity_id: String,
+        kind: ChangeKind,
+        changed_fields: &[&str],
+        payload: Option<ChangePayload>,
+    ) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.last_sequence += 1;
+        let event = ChangeEvent {
+            epoch: self.epoch,
+            sequence: state.last_sequence,
+            entity_type,
+            entity_id,
+            kind,
+            changed_fields: changed_fields.iter().map(|f| f.to_string()).collect(),
+            payload,
+            occurred_at: Utc::now(),
+        };
+
+        if state.history.len() == self.history_size {
+            state.history.pop_front();
+        }
+        state.history.push_back(event.clone());
+        // ロック中に送信してシーケンス順を保つ（購読者がいない場合の失敗は無視）
+        let _ = self.sender.send(event);
+    }
+
+    /// 変更を購読する
+    ///
+    /// `after` を指定した場合はそのカーソルより後のイベントを履歴から送ってから
+    /// 新しいイベントに切り替える。`None` の場合は購読開始以降のイベントのみ。
+    /// `entity_types` が空の場合はすべての種類を受け取る。
+    pub fn subscribe(
+        self: &Arc<Self>,
+        after: Option<ChangeCursor>,
+        entity_types: Vec<EntityType>,
+    ) -> Result<ChangeSubscription, ChangeFeedError> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let cursor = match after {
+            Some(after) if after.epoch != self.epoch => {
+                return Err(ChangeFeedError::CursorExpired {
+                    cursor: after,
+                    oldest: self.cursor(Self::oldest(&state)),
+                })
+            }
+            Some(after) => after.sequence,
+            None => state.last_sequence,
+        };
+        let backlog = self.backlog(&state, cursor)?;
+        // 履歴の取得と受信開始を同じロック内で行い、取りこぼしと重複を防ぐ
+        let receiver = self.sender.subscribe();
+
+        Ok(ChangeSubscription {
+            feed: self.clone(),
+            receiver,
+            backlog,
+            cursor,
+            entity_types,
+        })
+    }
+
+    fn cursor(&self, sequence: u64) -> ChangeCursor {
+        ChangeCursor {
+            epoch: self.epoch,
+            sequence,
+        }
+    }
+
+    fn oldest(state: &FeedState) -> u64 {
+        state
+            .history
+            .front()
+            .map(|event| event.sequence)
+            .unwrap_or(state.last_sequence + 1)
+    }
+
+    fn backlog(
+        &self,
+        state: &FeedState,
+        cursor: u64,
+    ) -> Result<VecDeque<ChangeEvent>, ChangeFeedError> {
+        let oldest = Self::oldest(state);
+        // まだ振っていない位置や、既に履歴から消えた位置からは再開できない
+        if cursor > state.last_sequence || cursor + 1 < oldest {
+            return Err(ChangeFeedError::CursorExpired {
+                cursor: self.cursor(cursor),
+                oldest: self.cursor(oldest),
+            });
+        }
+
+        Ok(state
+            .history
+            .iter()
+            .filter(|event| event.sequence > cursor)
+            .cloned()
+            .collect())
+    }
+
+    fn backlog_after(&self, cursor: u64) -> Result<VecDeque<ChangeEvent>, ChangeFeedError> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.backlog(&state, cursor)
+    }
+}
+
+/// 1 件の購読。`next` はキャンセルしても取りこぼしが起きない
+pub struct ChangeSubscription {
+    feed: Arc<ChangeFeed>,
+    receiver: broadcast::Receiver<ChangeEvent>,
+    backlog: VecDeque<ChangeEvent>,
+    cursor: u64,
+    entity_types: Vec<EntityType>,
+}
+
+impl ChangeSubscription {
+    /// 処理済みの位置。フィルタで除外したイベントも含むため、再開時はこの値を使う
+    pub fn cursor(&self) -> ChangeCursor {
+        self.feed.cursor(self.cursor)
+    }
+
+    /// 次のイベントを待ち、`idle` の間に何もなければハートビートを返す
+    pub async fn next_or_heartbeat(
+        &mut self,
+        idle: Duration,
+    ) -> Result<ChangeFeedMessage, ChangeFeedError> {
+        match tokio::time::timeout(idle, self.next()).await {
+            Ok(event) => event.map(ChangeFeedMessage::Event),
+            Err(_) => Ok(ChangeFeedMessage::Heartbeat {
+                cursor: self.cursor(),
+                sent_at: Utc::now(),
+            }),
+        }
+    }
+
+    /// 次のイベントを待つ
+    pub async fn next(&mut self) -> Result<ChangeEvent, ChangeFeedError> {
+        loop {
+            let event = match self.backlog.pop_front() {
+                Some(event) => event,
+                None => match self.receiver.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        // 受信が追いつかなかった分は履歴から補う
+                        self.backlog = self.feed.backlog_after(self.cursor)?;
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(ChangeFeedError::Closed)
+                    }
+                },
+            };
+
+            if event.sequence <= self.cursor {
+                continue;
+            }
+            self.cursor = event.sequence;
+
+            if self.entity_types.is_empty() || self.entity_types.contains(&event.entity_type) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::change_event::CategorySnapshot;
+
+    fn category(id: &str) -> ChangePayload {
+        ChangePayload::Category(CategorySnapshot {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_id: None,
+            sort_order: 0,
+            is_active: true,
+            version: 1,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_cursor_replays_history_then_live_events() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        feed.publish(ChangeKind::Created, category("a"), &[]);
+        feed.publish_id(EntityType::Item, 7, ChangeKind::Deleted);
+        feed.publish(ChangeKind::Updated, category("a"), &["name"]);
+
+        let mut subscription = feed.subscribe(Some(feed.cursor(1)), Vec::new()).unwrap();
+        feed.publish(ChangeKind::Created, category("b"), &[]);
+
+        let sequences = [
+            subscription.next().await.unwrap(),
+            subscription.next().await.unwrap(),
+            subscription.next().await.unwrap(),
+        ]
+        .map(|event| event.sequence);
+        assert_eq!(sequences, [2, 3, 4]);
+        assert_eq!(subscription.cursor(), feed.cursor(4));
+    }
+
+    #[tokio::test]
+    async fn test_filter_skips_other_entities_but_advances_cursor() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        let mut subscription = feed.subscribe(None, vec![EntityType::Item]).unwrap();
+        feed.publish(ChangeKind::Created, category("a"), &[]);
+        feed.publish_id(EntityType::Item, 3, ChangeKind::Restored);
+
+        let event = subscription.next().await.unwrap();
+        assert_eq!(event.entity_type, EntityType::Item);
+        assert_eq!(event.entity_id, "3");
+        assert_eq!(event.kind, ChangeKind::Restored);
+        assert_eq!(subscription.cursor(), feed.cursor(2));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_carries_cursor_when_idle() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        let mut subscription = feed.subscribe(None, vec![EntityType::Product]).unwrap();
+        feed.publish(ChangeKind::Created, category("a"), &[]);
+
+        let message = subscription
+            .next_or_heartbeat(Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert!(matches!(
+            message,
+            ChangeFeedMessage::Heartbeat { cursor, .. } if cursor == feed.cursor(1)
+        ));
+    }
+
+    #[test]
+    fn test_expired_or_unknown_cursor_is_rejected() {
+        let feed = Arc::new(ChangeFeed::new(2));
+        for id in ["a", "b", "c"] {
+            feed.publish(ChangeKind::Created, category(id), &[]);
+        }
+
+        // 履歴は 2, 3 のみ。カーソル 1 なら 2 から再開できる
+        assert!(feed.subscribe(Some(feed.cursor(1)), Vec::new()).is_ok());
+        assert_eq!(
+            feed.subscribe(Some(feed.cursor(0)), Vec::new()).err(),
+            Some(ChangeFeedError::CursorExpired {
+                cursor: feed.cursor(0),
+                oldest: feed.cursor(2)
+            })
+        );
+        // まだ振っていないシーケンス
+        assert!(matches!(
+            feed.subscribe(Some(feed.cursor(10)), Vec::new()),
+            Err(ChangeFeedError::CursorExpired { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cursor_from_another_process_is_rejected() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        let other = Arc::new(ChangeFeed::new(10));
+        for id in ["a", "b"] {
+            feed.publish(ChangeKind::Created, category(id), &[]);
+            other.publish(ChangeKind::Created, category(id), &[]);
+        }
+        assert_ne!(feed.epoch(), other.epoch());
+
+        // シーケンスが履歴の範囲内でも、別のレプリカや再起動前のカーソルからは再開しない
+        assert_eq!(
+            feed.subscribe(Some(other.cursor(1)), Vec::new()).err(),
+            Some(ChangeFeedError::CursorExpired {
+                cursor: other.cursor(1),
+                oldest: feed.cursor(1)
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::app_domain::model::change_event::{ChangeKind, EntityType};
 use crate::app_domain::repository::category_repository::CategoryRepository;
 use crate::app_domain::repository::item_repository::ItemRepository;
 use crate::app_domain::repository::product_repository::ProductRepository;
@@ -7,6 +8,7 @@ use crate::app_domain::service::deletion_service::{
     CategoryDeletionStrategy, DeleteKind, DeletionError, DeletionStrategy, ItemDeletionStrategy,
     ProductDeletionStrategy,
 };
+use crate::application::service::change_feed::ChangeFeed;
 use crate::infrastructure::error::{AppError, AppResult};
 use crate::infrastructure::metrics::Metrics;
 
@@ -17,30 +19,60 @@ use crate::infrastructure::metrics::Metrics;
 /// AppError 型に変換された結果を取得することでプレゼンテーション層との依存を切り離す。
 pub struct DeletionFacade {
     item_strategy: Arc<dyn DeletionStrategy<Id = u64> + Send + Sync>,
-    category_strategy: Arc<dyn DeletionStrategy<Id = String> + Send + Sync>,
-    product_strategy: Arc<dyn DeletionStrategy<Id = String> + Send + Sync>,
+    category_strategy: Option<Arc<dyn DeletionStrategy<Id = String> + Send + Sync>>,
+    product_strategy: Option<Arc<dyn DeletionStrategy<Id = String> + Send + Sync>>,
+    changes: Option<Arc<ChangeFeed>>,
 }
 
 impl DeletionFacade {
     /// Factory メソッド。DI コンテナから呼び出されることを想定。
-    pub fn new<IR, CR, PR>(
-        item_repository: Arc<IR>,
-        category_repository: Arc<CR>,
-        product_repository: Arc<PR>,
-    ) -> Self
+    ///
+    /// Category / Product の削除は `with_category_repository` / `with_product_repository` で有効にする。
+    pub fn new<IR>(item_repository: Arc<IR>) -> Self
     where
         IR: ItemRepository + Send + Sync + 'static + ?Sized,
-        CR: CategoryRepository + Send + Sync + 'static + ?Sized,
-        PR: ProductRepository + Send + Sync + 'static + ?Sized,
     {
         let item_strategy = ItemDeletionStrategy::new(item_repository);
-        let category_strategy = CategoryDeletionStrategy::new(category_repository);
-        let product_strategy = ProductDeletionStrategy::new(product_repository);
 
         Self {
             item_strategy: Arc::new(item_strategy),
-            category_strategy: Arc::new(category_strategy),
-            product_strategy: Arc::new(product_strategy),
+            category_strategy: None,
+            product_strategy: None,
+            changes: None,
+        }
+    }
+
+    /// Category の削除に使うリポジトリを設定する
+    pub fn with_category_repository<CR>(mut self, category_repository: Arc<CR>) -> Self
+    where
+        CR: CategoryRepository + Send + Sync + 'static + ?Sized,
+    {
+        self.category_strategy = Some(Arc::new(CategoryDeletionStrategy::new(category_repository)));
+        self
+    }
+
+    /// Product の削除に使うリポジトリを設定する
+    pub fn with_product_repository<PR>(mut self, product_repository: Arc<PR>) -> Self
+    where
+        PR: ProductRepository + Send + Sync + 'static + ?Sized,
+    {
+        self.product_strategy = Some(Arc::new(ProductDeletionStrategy::new(product_repository)));
+        self
+    }
+
+    /// 削除・復元を変更フィードに通知する
+    pub fn with_change_feed(mut self, changes: Arc<ChangeFeed>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    fn notify(&self, entity_type: EntityType, id: impl ToString, kind: DeleteKind) {
+        if let Some(changes) = &self.changes {
+            let kind = match kind {
+                DeleteKind::Logical | DeleteKind::Physical => ChangeKind::Deleted,
+                DeleteKind::Restore => ChangeKind::Restored,
+            };
+            changes.publish_id(entity_type, id, kind);
         }
     }
 
@@ -53,13 +85,23 @@ impl DeletionFacade {
         };
 
         Metrics::with_metrics("deletion_facade", operation, async {
-            self.map_error(self.item_strategy.delete(id, kind).await)
+            self.map_error(self.item_strategy.delete(id, kind).await)?;
+            self.notify(EntityType::Item, id, kind);
+            Ok(())
         })
         .await
     }
 
     /// Category を削除する共通メソッド
-    pub async fn delete_category(&self, id: String, kind: DeleteKind) -> AppResult<()> {
+    ///
+    /// `expected_version` を指定した場合、現在のバージョンと一致しなければ `Conflict` を返す。
+    #[allow(dead_code)]
+    pub async fn delete_category(
+        &self,
+        id: String,
+        kind: DeleteKind,
+        expected_version: Option<i64>,
+    ) -> AppResult<()> {
         let operation = match kind {
             DeleteKind::Logical => "delete_category_logical",
             DeleteKind::Physical => "delete_category_physical",
@@ -67,13 +109,28 @@ impl DeletionFacade {
         };
 
         Metrics::with_metrics("deletion_facade", operation, async {
-            self.map_error(self.category_strategy.delete(id, kind).await)
+            let strategy = Self::configured(&self.category_strategy, "Category")?;
+            self.map_error(
+                strategy
+                    .delete_with_version(id.clone(), kind, expected_version)
+                    .await,
+            )?;
+            self.notify(EntityType::Category, id, kind);
+            Ok(())
         })
         .await
     }
 
     /// Product を削除する共通メソッド
-    pub async fn delete_product(&self, id: String, kind: DeleteKind) -> AppResult<()> {
+    ///
+    /// `expected_version` を指定した場合、現在のバージョンと一致しなければ `Conflict` を返す。
+    #[allow(dead_code)]
+    pub async fn delete_product(
+        &self,
+        id: String,
+        kind: DeleteKind,
+        expected_version: Option<i64>,
+    ) -> AppResult<()> {
         let operation = match kind {
             DeleteKind::Logical => "delete_product_logical",
             DeleteKind::Physical => "delete_product_physical",
@@ -81,16 +138,35 @@ impl DeletionFacade {
         };
 
         Metrics::with_metrics("deletion_facade", operation, async {
-            self.map_error(self.product_strategy.delete(id, kind).await)
+            let strategy = Self::configured(&self.product_strategy, "Product")?;
+            self.map_error(
+                strategy
+                    .delete_with_version(id.clone(), kind, expected_version)
+                    .await,
+            )?;
+            self.notify(EntityType::Product, id, kind);
+            Ok(())
         })
         .await
     }
 
+    #[allow(dead_code)]
+    fn configured<'a>(
+        strategy: &'a Option<Arc<dyn DeletionStrategy<Id = String> + Send + Sync>>,
+        entity: &str,
+    ) -> AppResult<&'a Arc<dyn DeletionStrategy<Id = String> + Send + Sync>> {
+        strategy.as_ref().ok_or_else(|| {
+            AppError::ConfigurationError(format!("{} deletion is not configured", entity))
+        })
+    }
+
     /// Domain エラーをアプリケーション層の AppError にマッピング
     fn map_error<T>(&self, result: Result<T, DeletionError>) -> AppResult<T> {
         result.map_err(|e| match e {
             DeletionError::NotFound(msg) => AppError::NotFound(msg),
-            DeletionError::Validation(msg) => AppError::InternalServerError(msg),
+            DeletionError::Validation(msg) | DeletionError::VersionMismatch(msg) => {
+                AppError::Conflict(msg)
+            }
             DeletionError::Other(err) => {
                 AppError::InternalServerError(format!("Deletion error: {}", err))
             }
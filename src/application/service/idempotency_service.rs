@@ -0,0 +1,258 @@
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::app_domain::model::idempotency::{
+    is_valid_idempotency_key, IdempotencyKey, IdempotencyOutcome, Reservation, StoredResponse,
+    MAX_IDEMPOTENCY_KEY_LENGTH,
+};
+use crate::app_domain::repository::idempotency_repository::IdempotencyRepository;
+use crate::infrastructure::error::{AppError, AppResult};
+use crate::infrastructure::metrics::Metrics;
+
+/// 冪等性キーによる再送検知と処理結果の再生を担うサービス。
+///
+/// HTTP と gRPC の両方から利用され、呼び出し元とキーの組ごとにリクエストの
+/// フィンガープリントと処理結果を `ttl` の間保持する。
+pub struct IdempotencyService {
+    repository: Arc<dyn IdempotencyRepository>,
+    ttl: Duration,
+}
+
+impl IdempotencyService {
+    pub fn new(repository: Arc<dyn IdempotencyRepository>, ttl_seconds: i64) -> Self {
+        Self {
+            repository,
+            ttl: Duration::seconds(ttl_seconds),
+        }
+    }
+
+    /// リクエストの構成要素から SHA-256 のフィンガープリントを計算する。
+    /// 要素の境界がずれて同じ値にならないよう、各要素の長さも含める。
+    pub fn fingerprint(parts: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// キーを確保し、処理を実行すべきか・保存済みの結果を返すべきかを判定する
+    pub async fn begin(
+        &self,
+        key: &IdempotencyKey,
+        fingerprint: &str,
+        request_path: &str,
+    ) -> AppResult<IdempotencyOutcome> {
+        if !is_valid_idempotency_key(&key.key) {
+            return Err(AppError::ValidationError(format!(
+                "冪等性キーは1〜{}文字の英数字記号で指定してください",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            )));
+        }
+
+        let expires_at = Utc::now() + self.ttl;
+        let outcome = match self
+            .repository
+            .reserve(key, fingerprint, request_path, expires_at)
+            .await?
+        {
+            Reservation::Reserved => IdempotencyOutcome::Proceed,
+            Reservation::Existing(record) => record.outcome_for(fingerprint),
+        };
+
+        match &outcome {
+            IdempotencyOutcome::Proceed => {}
+            IdempotencyOutcome::Replay(_) => {
+                Metrics::record_success("idempotency", "replay");
+                info!("Replaying stored response for {}", request_path);
+            }
+            IdempotencyOutcome::InProgress | IdempotencyOutcome::FingerprintMismatch => {
+                Metrics::record_error("idempotency", "begin");
+                warn!(
+                    "Idempotency key rejected for {}: {:?}",
+                    request_path, outcome
+                );
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// 処理結果を保存する
+    pub async fn complete(&self, key: &IdempotencyKey, response: StoredResponse) -> AppResult<()> {
+        self.repository.complete(key, response).await
+    }
+
+    /// 処理が完了しなかったキーを解放する
+    pub async fn release(&self, key: &IdempotencyKey) -> AppResult<()> {
+        self.repository.release(key).await
+    }
+
+    /// 期限切れの記録を削除する
+    pub async fn purge_expired(&self) -> AppResult<u64> {
+        self.repository.purge_expired(Utc::now()).await
+    }
+
+    /// 一定間隔で期限切れの記録を削除するタスクを起動する
+    pub fn spawn_purge(self: Arc<Self>, interval_seconds: u64) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                match self.purge_expired().await {
+                    Ok(purged) if purged > 0 => {
+                        info!("Purged {} expired idempotency keys", purged)
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        Metrics::record_error("idempotency", "purge");
+                        error!("Failed to purge expired idempotency keys: {}", e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repository::idempotency_repository::InMemoryIdempotencyRepository;
+
+    fn key(key: &str) -> IdempotencyKey {
+        IdempotencyKey::new("sub:user-1", key)
+    }
+
+    fn service(ttl_seconds: i64) -> IdempotencyService {
+        IdempotencyService::new(Arc::new(InMemoryIdempotencyRepository::new()), ttl_seconds)
+    }
+
+    #[test]
+    fn test_fingerprint_separates_parts() {
+        let a = IdempotencyService::fingerprint(&[b"ab", b"c"]);
+        let b = IdempotencyService::fingerprint(&[b"a", b"bc"]);
+        assert_ne!(a, b);
+        assert_eq!(a, IdempotencyService::fingerprint(&[b"ab", b"c"]));
+        assert_eq!(a.len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_begin_replays_completed_response() {
+        let service = service(60);
+        let stored = StoredResponse {
+            status_code: 201,
+            headers: vec![],
+            body: b"{\"id\":1}".to_vec(),
+        };
+
+        assert_eq!(
+            service
+                .begin(&key("k1"), "fp", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::Proceed
+        );
+        assert_eq!(
+            service
+                .begin(&key("k1"), "fp", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::InProgress
+        );
+
+        service.complete(&key("k1"), stored.clone()).await.unwrap();
+
+        assert_eq!(
+            service
+                .begin(&key("k1"), "fp", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::Replay(stored)
+        );
+        assert_eq!(
+            service
+                .begin(&key("k1"), "other", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::FingerprintMismatch
+        );
+    }
+
+    #[tokio::test]
+    async fn test_released_and_expired_keys_can_be_reused() {
+        let service = service(60);
+        service
+            .begin(&key("k1"), "fp", "POST /api/items")
+            .await
+            .unwrap();
+        service.release(&key("k1")).await.unwrap();
+        assert_eq!(
+            service
+                .begin(&key("k1"), "fp", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::Proceed
+        );
+
+        let expired = self::service(0);
+        expired
+            .begin(&key("k2"), "fp", "POST /api/items")
+            .await
+            .unwrap();
+        assert_eq!(
+            expired
+                .begin(&key("k2"), "other", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::Proceed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_scoped_by_caller() {
+        let service = service(60);
+        let other = IdempotencyKey::new("sub:user-2", "k1");
+        service
+            .begin(&key("k1"), "fp", "POST /api/items")
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .begin(&other, "other", "POST /api/items")
+                .await
+                .unwrap(),
+            IdempotencyOutcome::Proceed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_removes_only_expired_keys() {
+        let expired = service(0);
+        expired
+            .begin(&key("k1"), "fp", "POST /api/items")
+            .await
+            .unwrap();
+        assert_eq!(expired.purge_expired().await.unwrap(), 1);
+
+        let active = service(60);
+        active
+            .begin(&key("k1"), "fp", "POST /api/items")
+            .await
+            .unwrap();
+        assert_eq!(active.purge_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_begin_rejects_invalid_key() {
+        let service = service(60);
+        assert!(matches!(
+            service.begin(&key(""), "fp", "POST /api/items").await,
+            Err(AppError::ValidationError(_))
+        ));
+    }
+}
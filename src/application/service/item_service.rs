@@ -1,17 +1,20 @@
+use crate::app_domain::model::change_event::{ChangeKind, EntityType};
 use crate::application::dto::item_dto::{
     BatchDeleteRequest, BatchDeleteResponse, CreateItemRequest, DeletionLogResponse,
     DeletionValidationResponse, ItemResponse, UpdateItemRequest,
 };
+use crate::application::service::change_feed::ChangeFeed;
 use crate::infrastructure::error::{AppError, AppResult};
 use crate::infrastructure::metrics::Metrics;
 use domain::model::item::{DeletionType, Item};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 pub struct ItemService {
     repository: std::sync::Arc<
         dyn crate::app_domain::repository::item_repository::ItemRepository + Send + Sync,
     >,
     counter: Mutex<u64>,
+    changes: Option<Arc<ChangeFeed>>,
 }
 
 impl ItemService {
@@ -23,6 +26,19 @@ impl ItemService {
         Self {
             repository,
             counter: Mutex::new(0),
+            changes: None,
+        }
+    }
+
+    /// 作成・更新・一括削除を変更フィードに通知する
+    pub fn with_change_feed(mut self, changes: Arc<ChangeFeed>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    fn notify(&self, kind: ChangeKind, item: &Item, changed_fields: &[&str]) {
+        if let Some(changes) = &self.changes {
+            changes.publish(kind, item.into(), changed_fields);
         }
     }
 
@@ -69,6 +85,7 @@ impl ItemService {
             };
 
             let created_item = self.repository.create(item).await?;
+            self.notify(ChangeKind::Created, &created_item, &[]);
             Ok(self.to_response(created_item))
         })
         .await
@@ -86,6 +103,7 @@ impl ItemService {
                         item.description = Some(description);
                     }
                     let updated = self.repository.update(item).await?;
+                    self.notify(ChangeKind::Updated, &updated, &[]);
                     Ok(self.to_response(updated))
                 }
                 None => Err(AppError::not_found("Item", id)),
@@ -138,6 +156,12 @@ impl ItemService {
                 .filter(|id| !successful_ids.contains(id))
                 .collect();
 
+            if let Some(changes) = &self.changes {
+                for id in &successful_ids {
+                    changes.publish_id(EntityType::Item, id, ChangeKind::Deleted);
+                }
+            }
+
             // 個別に成功/失敗をメトリクスに記録
             if !successful_ids.is_empty() {
                 Metrics::record_success("item", "batch_delete");
@@ -1,5 +1,13 @@
 pub mod category_service;
+pub mod change_feed;
 pub mod deletion_facade;
+pub mod idempotency_service;
 pub mod item_service;
+pub mod outbox_dispatcher;
+pub mod product_export_service;
+pub mod product_import_service;
+pub mod product_schedule_executor;
 pub mod product_service;
+pub mod slug_service;
 pub mod user_service;
+pub mod webhook_service;
@@ -0,0 +1,341 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::app_domain::model::domain_event::OutboxEvent;
+use crate::app_domain::repository::outbox_repository::OutboxRepository;
+use crate::infrastructure::config::OutboxConfig;
+
+/// outbox のイベントの配信先
+///
+/// 配信は at-least-once のため、同じイベントが複数回届いても問題ないように実装する。
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String>;
+}
+
+/// イベントをログに出力するシンク
+pub struct LogSink;
+
+#[async_trait]
+impl EventSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        info!(
+            "Domain event {} {} {}:{} {}",
+            event.id,
+            event.event.event_type,
+            event.event.aggregate_type.as_str(),
+            event.event.aggregate_id,
+            event.event.payload
+        );
+        Ok(())
+    }
+}
+
+/// プロセス内の購読者にイベントを配るシンク
+///
+/// 購読者がいない場合や購読者の受信が遅れた場合でも配信済みとして扱う。
+pub struct ChannelSink {
+    sender: broadcast::Sender<OutboxEvent>,
+}
+
+impl ChannelSink {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    #[allow(dead_code)]
+    pub fn subscribe(&self) -> broadcast::Receiver<OutboxEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[async_trait]
+impl EventSink for ChannelSink {
+    fn name(&self) -> &str {
+        "channel"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        let _ = self.sender.send(event.clone());
+        Ok(())
+    }
+}
+
+/// outbox に書き込まれたドメインイベントを各シンクへ配信するバックグラウンド実行器
+///
+/// 集約ごとに先頭のイベントだけを取得するため、同じ集約のイベントは書き込み順に配信され、
+/// 配信に失敗したイベントより後のイベントは再試行が成功するまで待たされる。
+/// 配信後に記録する前にプロセスが停止した場合は、確保のタイムアウト後に再配信される。
+pub struct OutboxDispatcher {
+    repository: Arc<dyn OutboxRepository>,
+    sinks: Vec<Arc<dyn EventSink>>,
+    config: OutboxConfig,
+}
+
+impl OutboxDispatcher {
+    pub fn new(
+        repository: Arc<dyn OutboxRepository>,
+        sinks: Vec<Arc<dyn EventSink>>,
+        config: OutboxConfig,
+    ) -> Self {
+        Self {
+            repository,
+            sinks,
+            config,
+        }
+    }
+
+    /// 配信可能なイベントを1回分処理し、配信済みにした件数を返す
+    pub async fn run_once(&self) -> usize {
+        let events = match self
+            .repository
+            .claim_pending(
+                Utc::now(),
+                self.config.batch_size,
+                self.config.claim_timeout_seconds,
+            )
+            .await
+        {
+            Ok(events) => events,
+            Err(e) => {
+                error!("Failed to claim outbox events: {}", e);
+                return 0;
+            }
+        };
+
+        let mut published = 0;
+        for event in events {
+            let result = match self.publish(&event).await {
+                Ok(()) => self.repository.mark_published(event.id).await.map(|_| 1),
+                Err(reason) => {
+                    warn!(
+                        "Failed to publish outbox event {} (attempt {}): {}",
+                        event.id, event.attempts, reason
+                    );
+                    let retry_at = Utc::now() + self.backoff(event.attempts);
+                    self.repository
+                        .mark_failed(event.id, &reason, retry_at)
+                        .await
+                        .map(|_| 0)
+                }
+            };
+            match result {
+                Ok(count) => published += count,
+                Err(e) => error!("Failed to record outbox event {}: {}", event.id, e),
+            }
+        }
+        published
+    }
+
+    /// 配信済みで保持期間を過ぎたイベントを削除する
+    pub async fn purge(&self) -> usize {
+        let before = Utc::now() - ChronoDuration::hours(self.config.retention_hours);
+        match self.repository.purge_published(before).await {
+            Ok(count) => count as usize,
+            Err(e) => {
+                error!("Failed to purge outbox events: {}", e);
+                0
+            }
+        }
+    }
+
+    /// 一定間隔で outbox を処理するタスクを起動する
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                "Outbox dispatcher started (interval: {}s, sinks: {})",
+                self.config.interval_seconds,
+                self.sinks
+                    .iter()
+                    .map(|sink| sink.name())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(self.config.interval_seconds));
+            let mut purge_interval = tokio::time::interval(Duration::from_secs(3600));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        // 取り残しがなくなるまで続けて処理する
+                        while self.run_once().await > 0 {}
+                    }
+                    _ = purge_interval.tick() => {
+                        let purged = self.purge().await;
+                        if purged > 0 {
+                            info!("Purged {} published outbox events", purged);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// すべてのシンクに順に配信する（1つでも失敗したらイベント全体を再試行する）
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        for sink in &self.sinks {
+            sink.publish(event)
+                .await
+                .map_err(|e| format!("{}: {}", sink.name(), e))?;
+        }
+        Ok(())
+    }
+
+    /// 試行回数に応じた指数バックオフ（上限あり）
+    fn backoff(&self, attempts: i32) -> ChronoDuration {
+        let exponent = attempts.clamp(1, 16) as u32 - 1;
+        let seconds = 2i64
+            .saturating_pow(exponent)
+            .min(self.config.max_backoff_seconds);
+        ChronoDuration::seconds(seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::domain_event::DomainEvent;
+    use crate::infrastructure::repository::outbox_repository::InMemoryOutboxRepository;
+    use std::sync::Mutex;
+
+    fn config() -> OutboxConfig {
+        OutboxConfig {
+            enabled: true,
+            interval_seconds: 1,
+            batch_size: 10,
+            claim_timeout_seconds: 60,
+            max_backoff_seconds: 300,
+            retention_hours: 1,
+            log_events: false,
+            webhook_url: None,
+            webhook_timeout_seconds: 1,
+        }
+    }
+
+    /// 受け取ったイベント ID を記録し、指定した ID の配信を失敗させるシンク
+    #[derive(Default)]
+    struct RecordingSink {
+        received: Mutex<Vec<i64>>,
+        failing: Mutex<Vec<i64>>,
+    }
+
+    #[async_trait]
+    impl EventSink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+            if self.failing.lock().unwrap().contains(&event.id) {
+                return Err("connection refused".to_string());
+            }
+            self.received.lock().unwrap().push(event.id);
+            Ok(())
+        }
+    }
+
+    fn dispatcher(
+        repository: Arc<InMemoryOutboxRepository>,
+        sink: Arc<RecordingSink>,
+    ) -> OutboxDispatcher {
+        OutboxDispatcher::new(repository, vec![sink], config())
+    }
+
+    #[tokio::test]
+    async fn test_events_are_delivered_in_order_per_aggregate() {
+        let repository = Arc::new(InMemoryOutboxRepository::new());
+        let a1 = repository.append(DomainEvent::item_deleted(1, false));
+        let b1 = repository.append(DomainEvent::item_deleted(2, false));
+        let a2 = repository.append(DomainEvent::item_restored(1));
+        let sink = Arc::new(RecordingSink::default());
+        let dispatcher = dispatcher(repository.clone(), sink.clone());
+
+        // 1 回目は集約ごとの先頭だけ、2 回目で後続のイベントが配信される
+        assert_eq!(dispatcher.run_once().await, 2);
+        assert_eq!(*sink.received.lock().unwrap(), vec![a1, b1]);
+        assert_eq!(dispatcher.run_once().await, 1);
+        assert_eq!(*sink.received.lock().unwrap(), vec![a1, b1, a2]);
+        assert!(repository.pending_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_event_blocks_later_events_of_same_aggregate() {
+        let repository = Arc::new(InMemoryOutboxRepository::new());
+        let a1 = repository.append(DomainEvent::item_deleted(1, false));
+        let a2 = repository.append(DomainEvent::item_restored(1));
+        let b1 = repository.append(DomainEvent::item_deleted(2, true));
+        let sink = Arc::new(RecordingSink::default());
+        sink.failing.lock().unwrap().push(a1);
+        let dispatcher = dispatcher(repository.clone(), sink.clone());
+
+        assert_eq!(dispatcher.run_once().await, 1);
+        assert_eq!(*sink.received.lock().unwrap(), vec![b1]);
+        assert_eq!(repository.pending_ids(), vec![a1, a2]);
+        assert_eq!(
+            repository.last_error(a1).as_deref(),
+            Some("recording: connection refused")
+        );
+
+        // バックオフ中は再試行されず、後続のイベントも配信されない
+        sink.failing.lock().unwrap().clear();
+        assert_eq!(dispatcher.run_once().await, 0);
+        assert_eq!(repository.pending_ids(), vec![a1, a2]);
+    }
+
+    #[tokio::test]
+    async fn test_stale_claim_is_redelivered() {
+        let repository = Arc::new(InMemoryOutboxRepository::new());
+        let id = repository.append(DomainEvent::item_deleted(1, false));
+
+        // 配信中にプロセスが停止した状態を再現する
+        let claimed = repository.claim_pending(Utc::now(), 10, 60).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert!(repository
+            .claim_pending(Utc::now(), 10, 60)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let later = Utc::now() + ChronoDuration::seconds(61);
+        let reclaimed = repository.claim_pending(later, 10, 60).await.unwrap();
+        assert_eq!(reclaimed[0].id, id);
+        assert_eq!(reclaimed[0].attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_ids_are_not_reused_after_purge() {
+        let repository = Arc::new(InMemoryOutboxRepository::new());
+        let first = repository.append(DomainEvent::item_deleted(1, false));
+        let second = repository.append(DomainEvent::item_deleted(2, false));
+        repository.mark_published(first).await.unwrap();
+        let later = Utc::now() + ChronoDuration::seconds(1);
+        assert_eq!(repository.purge_published(later).await.unwrap(), 1);
+
+        let third = repository.append(DomainEvent::item_restored(1));
+        assert!(third > second);
+        repository.mark_published(second).await.unwrap();
+        assert_eq!(repository.pending_ids(), vec![third]);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let sink = Arc::new(RecordingSink::default());
+        let dispatcher = dispatcher(Arc::new(InMemoryOutboxRepository::new()), sink);
+
+        assert_eq!(dispatcher.backoff(1), ChronoDuration::seconds(1));
+        assert_eq!(dispatcher.backoff(4), ChronoDuration::seconds(8));
+        assert_eq!(dispatcher.backoff(30), ChronoDuration::seconds(300));
+    }
+}
@@ -0,0 +1,121 @@
+use futures::stream::{self, BoxStream, StreamExt};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::app_domain::model::product::ProductError;
+use crate::app_domain::model::product_export::{ExportColumn, ExportFormat, ExportWriter};
+use crate::app_domain::model::product_filter::ProductFilter;
+use crate::app_domain::repository::product_repository::ProductRepository;
+use crate::application::dto::product_dto::{ProductExportQuery, ProductFilterRequest};
+use crate::infrastructure::metrics::Metrics;
+
+/// 商品カタログのエクスポート
+///
+/// 商品 ID 順に `batch_size` 件ずつ読み込んでは書き出すため、
+/// カタログの件数に関わらず保持するのは1ページ分のみ。
+pub struct ProductExportService {
+    repository: Arc<dyn ProductRepository>,
+    batch_size: i64,
+}
+
+/// エクスポートの出力形式と本文のストリーム
+pub struct ProductExport {
+    pub format: ExportFormat,
+    pub body: BoxStream<'static, Result<String, ProductError>>,
+}
+
+struct ExportState {
+    repository: Arc<dyn ProductRepository>,
+    filter: ProductFilter,
+    writer: ExportWriter,
+    batch_size: i64,
+    after_id: Option<String>,
+    exported: usize,
+    done: bool,
+}
+
+impl ProductExportService {
+    pub fn new(repository: Arc<dyn ProductRepository>, batch_size: i64) -> Self {
+        Self {
+            repository,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// 指定を検証し、エクスポート本文のストリームを返す
+    ///
+    /// 指定が不正な場合は書き出しを始める前にエラーを返す。
+    pub fn export(&self, query: ProductExportQuery) -> Result<ProductExport, ProductError> {
+        let format = query.format.unwrap_or(ExportFormat::Csv);
+        let columns = ExportColumn::parse_list(query.columns.as_deref())?;
+        let filter = ProductFilter::try_from(ProductFilterRequest {
+            q: query.q,
+            category_id: query.category_id,
+            brand: query.brand,
+            status: query.status,
+            tags: query.tags,
+            min_price: query.min_price,
+            max_price: query.max_price,
+            in_stock_only: query.in_stock_only,
+            attributes: query.attributes,
+        })?;
+
+        let writer = ExportWriter::new(format, columns);
+        let header = stream::iter(writer.header().map(Ok));
+        let state = ExportState {
+            repository: self.repository.clone(),
+            filter,
+            writer,
+            batch_size: self.batch_size,
+            after_id: None,
+            exported: 0,
+            done: false,
+        };
+
+        info!("Starting product export as {:?}", format);
+
+        Ok(ProductExport {
+            format,
+            body: header.chain(stream::unfold(state, next_page)).boxed(),
+        })
+    }
+}
+
+/// 次のページを読み込み、書き出した文字列を返す
+async fn next_page(mut state: ExportState) -> Option<(Result<String, ProductError>, ExportState)> {
+    if state.done {
+        return None;
+    }
+
+    let page = match state
+        .repository
+        .export_products(&state.filter, state.after_id.as_deref(), state.batch_size)
+        .await
+    {
+        Ok(page) => page,
+        Err(e) => {
+            error!("Product export failed after {} rows: {}", state.exported, e);
+            Metrics::record_error("product", "export");
+            state.done = true;
+            return Some((Err(e), state));
+        }
+    };
+
+    if (page.len() as i64) < state.batch_size {
+        state.done = true;
+        Metrics::record_success("product", "export");
+        info!("Exported {} products", state.exported + page.len());
+    }
+    if page.is_empty() {
+        return None;
+    }
+
+    let mut chunk = String::new();
+    for row in &page {
+        state.writer.write_row(row, &mut chunk);
+    }
+    state.exported += page.len();
+    state.after_id = page.last().map(|row| row.product.id.clone());
+
+    Some((Ok(chunk), state))
+}
@@ -0,0 +1,500 @@
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::app_domain::model::change_event::ChangeKind;
+use crate::app_domain::model::product::{ChangeContext, ProductError};
+use crate::app_domain::model::product_import::{
+    ImportFormat, ImportHeader, ImportJobState, ImportRowError, ImportRowOutcome, ProductImportJob,
+    ProductImportRecord, ProductSnapshot,
+};
+use crate::app_domain::repository::product_repository::ProductRepository;
+use crate::application::dto::product_dto::{ProductImportJobResponse, ProductImportReport};
+use crate::application::service::change_feed::ChangeFeed;
+use crate::infrastructure::metrics::Metrics;
+
+/// 完了したジョブの進捗を保持する時間
+const JOB_RETENTION_HOURS: i64 = 24;
+
+/// CSV / TSV による商品の一括取り込み
+///
+/// アップロードされたファイルは `ImportParser` で逐次解析し、実行時は
+/// `chunk_size` 行ごとに1トランザクションで SKU 単位に作成・更新する。
+/// ジョブの進捗はこのインスタンスのメモリ上に保持される。
+pub struct ProductImportService {
+    repository: Arc<dyn ProductRepository>,
+    jobs: Arc<Mutex<HashMap<String, ProductImportJob>>>,
+    chunk_size: usize,
+    max_rows: usize,
+    changes: Option<Arc<ChangeFeed>>,
+}
+
+/// 解析済みのインポートファイル
+#[derive(Debug)]
+pub struct ParsedImport {
+    pub records: Vec<ProductImportRecord>,
+    pub errors: Vec<ImportRowError>,
+    pub total_rows: usize,
+}
+
+impl ProductImportService {
+    pub fn new(repository: Arc<dyn ProductRepository>, chunk_size: usize, max_rows: usize) -> Self {
+        Self {
+            repository,
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            chunk_size: chunk_size.max(1),
+            max_rows,
+            changes: None,
+        }
+    }
+
+    /// 取り込んだ商品を変更フィードに通知する
+    pub fn with_change_feed(mut self, changes: Arc<ChangeFeed>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    pub fn parser(&self, format: ImportFormat) -> ImportParser {
+        ImportParser::new(format, self.max_rows)
+    }
+
+    /// 商品を変更せずに各行を検証し、作成・更新される件数と行ごとのエラーを返す
+    pub async fn dry_run(&self, parsed: ParsedImport) -> ProductImportReport {
+        let mut errors = parsed.errors;
+        let mut create_count = 0;
+        let mut update_count = 0;
+
+        for record in &parsed.records {
+            let current = self.snapshot(&record.sku).await;
+            match record.resolve(current.as_ref(), String::new) {
+                Ok(resolved) if resolved.is_new => create_count += 1,
+                Ok(_) => update_count += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+        errors.sort_by_key(|e| e.line);
+
+        Metrics::record_success("product", "import_dry_run");
+        info!(
+            "Import dry run: {} rows, {} create, {} update, {} errors",
+            parsed.total_rows,
+            create_count,
+            update_count,
+            errors.len()
+        );
+
+        ProductImportReport {
+            total_rows: parsed.total_rows,
+            create_count,
+            update_count,
+            error_count: errors.len(),
+            errors,
+        }
+    }
+
+    /// 取り込みジョブを登録してバックグラウンドで実行する
+    pub fn start(&self, parsed: ParsedImport, ctx: ChangeContext) -> ProductImportJobResponse {
+        let job_id = Uuid::new_v4().to_string();
+        let mut job = ProductImportJob::new(
+            job_id.clone(),
+            parsed.total_rows,
+            parsed.errors,
+            ctx.changed_by.clone(),
+        );
+        // 解析時点で不正だった行は処理済みとして数える
+        job.processed_rows = parsed.total_rows - parsed.records.len();
+
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            let cutoff = Utc::now() - Duration::hours(JOB_RETENTION_HOURS);
+            jobs.retain(|_, job| job.finished_at.is_none_or(|finished| finished > cutoff));
+            jobs.insert(job_id.clone(), job.clone());
+        }
+
+        let repository = self.repository.clone();
+        let jobs = self.jobs.clone();
+        let chunk_size = self.chunk_size;
+        let changes = self.changes.clone();
+        tokio::spawn(async move {
+            run_job(
+                repository,
+                jobs,
+                job_id,
+                parsed.records,
+                chunk_size,
+                ctx,
+                changes,
+            )
+            .await;
+        });
+
+        job.into()
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Result<ProductImportJobResponse, ProductError> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .cloned()
+            .map(Into::into)
+            .ok_or(ProductError::ImportJobNotFound)
+    }
+
+    async fn snapshot(&self, sku: &str) -> Option<ProductSnapshot> {
+        let product = self.repository.find_by_sku(sku).await?;
+        Some(ProductSnapshot {
+            price: self.repository.get_current_price(&product.id).await,
+            inventory: self.repository.get_inventory(&product.id).await,
+            attributes: self.repository.get_attributes(&product.id).await,
+            product,
+        })
+    }
+}
+
+async fn run_job(
+    repository: Arc<dyn ProductRepository>,
+    jobs: Arc<Mutex<HashMap<String, ProductImportJob>>>,
+    job_id: String,
+    records: Vec<ProductImportRecord>,
+    chunk_size: usize,
+    ctx: ChangeContext,
+    changes: Option<Arc<ChangeFeed>>,
+) {
+    let update_job = |f: &dyn Fn(&mut ProductImportJob)| {
+        if let Some(job) = jobs.lock().unwrap().get_mut(&job_id) {
+            f(job);
+        }
+    };
+
+    update_job(&|job| {
+        job.state = ImportJobState::Running;
+        job.started_at = Some(Utc::now());
+    });
+    info!("Import job {} started ({} rows)", job_id, records.len());
+
+    for chunk in records.chunks(chunk_size) {
+        match repository.import_products(chunk.to_vec(), &ctx).await {
+            Ok(outcomes) => {
+                update_job(&|job| {
+                    for outcome in &outcomes {
+                        match outcome {
+                            ImportRowOutcome::Created => job.created_count += 1,
+                            ImportRowOutcome::Updated => job.updated_count += 1,
+                        }
+                    }
+                    job.processed_rows += chunk.len();
+                });
+
+                if let Some(changes) = &changes {
+                    for (record, outcome) in chunk.iter().zip(&outcomes) {
+                        let kind = match outcome {
+                            ImportRowOutcome::Created => ChangeKind::Created,
+                            ImportRowOutcome::Updated => ChangeKind::Updated,
+                        };
+                        if let Some(product) = repository.find_by_sku(&record.sku).await {
+                            changes.publish(kind, (&product).into(), &[]);
+                        }
+                    }
+                }
+            }
+            Err(failure) => {
+                error!(
+                    "Import job {} rolled back chunk at line {}: {}",
+                    job_id, failure.line, failure.message
+                );
+                Metrics::record_error("product", "import");
+                update_job(&|job| {
+                    // 同じチャンクの他の行も取り消されたことを記録する
+                    for record in chunk {
+                        if record.line == failure.line {
+                            job.errors.push(failure.clone());
+                        } else {
+                            job.errors.push(ImportRowError::new(
+                                record.line,
+                                Some(record.sku.clone()),
+                                None,
+                                format!("rolled back because line {} failed", failure.line),
+                            ));
+                        }
+                    }
+                    job.processed_rows += chunk.len();
+                });
+            }
+        }
+    }
+
+    update_job(&|job| {
+        job.errors.sort_by_key(|e| e.line);
+        job.state = ImportJobState::Completed;
+        job.finished_at = Some(Utc::now());
+    });
+    Metrics::record_success("product", "import");
+    info!("Import job {} completed", job_id);
+}
+
+/// アップロードされたファイルを受信したそばから解析する
+///
+/// 1行目をヘッダーとして解釈し、以降の行を `ProductImportRecord` に変換する。
+/// 形式が不正な行や、ファイル内で重複した SKU の行は行エラーとして記録する。
+pub struct ImportParser {
+    reader: DelimitedReader,
+    header: Option<ImportHeader>,
+    records: Vec<ProductImportRecord>,
+    errors: Vec<ImportRowError>,
+    skus: HashMap<String, usize>,
+    total_rows: usize,
+    max_rows: usize,
+}
+
+impl ImportParser {
+    fn new(format: ImportFormat, max_rows: usize) -> Self {
+        Self {
+            reader: DelimitedReader::new(format.delimiter()),
+            header: None,
+            records: Vec::new(),
+            errors: Vec::new(),
+            skus: HashMap::new(),
+            total_rows: 0,
+            max_rows,
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), ProductError> {
+        let mut rows = Vec::new();
+        self.reader.push(bytes, &mut rows);
+        self.accept(rows)
+    }
+
+    pub fn finish(mut self) -> Result<ParsedImport, ProductError> {
+        let mut rows = Vec::new();
+        self.reader.finish(&mut rows);
+        self.accept(rows)?;
+
+        if self.header.is_none() {
+            return Err(ProductError::InvalidImport("file is empty".to_string()));
+        }
+
+        Ok(ParsedImport {
+            records: self.records,
+            errors: self.errors,
+            total_rows: self.total_rows,
+        })
+    }
+
+    fn accept(&mut self, rows: Vec<(usize, Vec<Vec<u8>>)>) -> Result<(), ProductError> {
+        for (line, raw_fields) in rows {
+            let fields = raw_fields
+                .into_iter()
+                .map(String::from_utf8)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| {
+                    ProductError::InvalidImport(format!("line {} is not valid UTF-8", line))
+                })?;
+
+            let header = match &self.header {
+                Some(header) => header,
+                None => {
+                    self.header = Some(ImportHeader::parse(&fields)?);
+                    continue;
+                }
+            };
+
+            self.total_rows += 1;
+            if self.total_rows > self.max_rows {
+                return Err(ProductError::InvalidImport(format!(
+                    "too many rows (maximum {})",
+                    self.max_rows
+                )));
+            }
+
+            match header.record(line, fields) {
+                Ok(record) => {
+                    if let Some(first_line) = self.skus.get(&record.sku) {
+                        self.errors.push(ImportRowError::new(
+                            line,
+                            Some(record.sku.clone()),
+                            Some("sku".to_string()),
+                            format!("duplicate SKU (first seen on line {})", first_line),
+                        ));
+                    } else {
+                        self.skus.insert(record.sku.clone(), line);
+                        self.records.push(record);
+                    }
+                }
+                Err(errors) => self.errors.extend(errors),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 区切り文字形式（RFC 4180）の逐次リーダー
+///
+/// 入力の区切りに関係なくレコードを組み立て、各レコードの開始行番号とともに返す。
+/// ダブルクォートで囲まれたフィールドは区切り文字・改行・`""` を含められる。
+struct DelimitedReader {
+    delimiter: u8,
+    field: Vec<u8>,
+    fields: Vec<Vec<u8>>,
+    in_quotes: bool,
+    quote_pending: bool,
+    quoted: bool,
+    line: usize,
+    record_line: usize,
+}
+
+impl DelimitedReader {
+    fn new(delimiter: u8) -> Self {
+        Self {
+            delimiter,
+            field: Vec::new(),
+            fields: Vec::new(),
+            in_quotes: false,
+            quote_pending: false,
+            quoted: false,
+            line: 1,
+            record_line: 1,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8], out: &mut Vec<(usize, Vec<Vec<u8>>)>) {
+        for &b in bytes {
+            if self.in_quotes {
+                if self.quote_pending {
+                    self.quote_pending = false;
+                    if b == b'"' {
+                        self.field.push(b'"');
+                        continue;
+                    }
+                    // 閉じクォートの後は通常の文字として扱う
+                    self.in_quotes = false;
+                } else {
+                    if b == b'"' {
+                        self.quote_pending = true;
+                    } else {
+                        if b == b'\n' {
+                            self.line += 1;
+                        }
+                        self.field.push(b);
+                    }
+                    continue;
+                }
+            }
+
+            match b {
+                b'"' if self.field.is_empty() && !self.quoted => {
+                    self.in_quotes = true;
+                    self.quoted = true;
+                }
+                b'\r' => {}
+                b'\n' => {
+                    self.end_record(out);
+                    self.line += 1;
+                    self.record_line = self.line;
+                }
+                b if b == self.delimiter => self.end_field(),
+                b => self.field.push(b),
+            }
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<(usize, Vec<Vec<u8>>)>) {
+        self.in_quotes = false;
+        self.quote_pending = false;
+        self.end_record(out);
+    }
+
+    fn end_field(&mut self) {
+        self.fields.push(std::mem::take(&mut self.field));
+        self.quoted = false;
+    }
+
+    fn end_record(&mut self, out: &mut Vec<(usize, Vec<Vec<u8>>)>) {
+        self.end_field();
+        let fields = std::mem::take(&mut self.fields);
+        // 空行は読み飛ばす
+        if fields.len() == 1 && fields[0].is_empty() {
+            return;
+        }
+        out.push((self.record_line, fields));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(delimiter: u8, chunks: &[&str]) -> Vec<(usize, Vec<String>)> {
+        let mut reader = DelimitedReader::new(delimiter);
+        let mut rows = Vec::new();
+        for chunk in chunks {
+            reader.push(chunk.as_bytes(), &mut rows);
+        }
+        reader.finish(&mut rows);
+        rows.into_iter()
+            .map(|(line, fields)| {
+                (
+                    line,
+                    fields
+                        .into_iter()
+                        .map(|f| String::from_utf8(f).unwrap())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reader_handles_quotes_across_chunks() {
+        let rows = read(
+            b',',
+            &[
+                "sku,name\r\nA-1,\"Note",
+                "book, \"\"A4\"\"\"\r\n\r\nA-2,\"two\nlines\"\nA-3,x",
+            ],
+        );
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["sku".to_string(), "name".to_string()]),
+                (2, vec!["A-1".to_string(), "Notebook, \"A4\"".to_string()]),
+                (4, vec!["A-2".to_string(), "two\nlines".to_string()]),
+                (6, vec!["A-3".to_string(), "x".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reader_supports_tabs() {
+        let rows = read(b'\t', &["sku\tname\nA-1\tPen, blue\n"]);
+        assert_eq!(rows[1].1, vec!["A-1".to_string(), "Pen, blue".to_string()]);
+    }
+
+    #[test]
+    fn test_parser_rejects_duplicates_and_limits_rows() {
+        let mut parser = ImportParser::new(ImportFormat::Csv, 3);
+        parser
+            .feed(b"sku,name\nA-1,Pen\nA-1,Pencil\nA-2,\n")
+            .unwrap();
+        let parsed = parser.finish().unwrap();
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line, 3);
+
+        let mut parser = ImportParser::new(ImportFormat::Csv, 1);
+        assert!(matches!(
+            parser.feed(b"sku\nA-1\nA-2\n"),
+            Err(ProductError::InvalidImport(_))
+        ));
+
+        let parser = ImportParser::new(ImportFormat::Csv, 1);
+        assert!(matches!(
+            parser.finish(),
+            Err(ProductError::InvalidImport(_))
+        ));
+    }
+}
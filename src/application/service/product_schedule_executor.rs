@@ -0,0 +1,61 @@
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::application::service::product_service::ProductService;
+use crate::infrastructure::config::SchedulerConfig;
+
+/// 商品ステータス変更の予約を定期的に適用するバックグラウンド実行器
+///
+/// 予約の取得はリポジトリ側で排他制御されるため、複数レプリカで同時に動作させても
+/// 同じ予約が二重に適用されることはない。
+pub struct ProductScheduleExecutor {
+    service: Arc<ProductService>,
+    config: SchedulerConfig,
+}
+
+impl ProductScheduleExecutor {
+    pub fn new(service: Arc<ProductService>, config: SchedulerConfig) -> Self {
+        Self { service, config }
+    }
+
+    /// 実行時刻を過ぎた予約を1回分処理する
+    pub async fn run_once(&self) -> usize {
+        match self
+            .service
+            .run_due_schedules(
+                Utc::now(),
+                self.config.batch_size,
+                self.config.lease_seconds,
+            )
+            .await
+        {
+            Ok(count) => count,
+            Err(e) => {
+                error!("Failed to run product schedules: {}", e);
+                0
+            }
+        }
+    }
+
+    /// 一定間隔で予約を処理するタスクを起動する
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                "Product schedule executor started (interval: {}s)",
+                self.config.interval_seconds
+            );
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(self.config.interval_seconds));
+            loop {
+                interval.tick().await;
+                let applied = self.run_once().await;
+                if applied > 0 {
+                    info!("Processed {} product schedules", applied);
+                }
+            }
+        })
+    }
+}
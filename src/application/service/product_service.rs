@@ -1,27 +1,96 @@
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::app_domain::model::attribute_schema::AttributeSchema;
+use crate::app_domain::model::change_event::{ChangeKind, EntityType};
+use crate::app_domain::model::localization::{Locales, ProductTranslation};
 use crate::app_domain::model::product::{
-    Dimensions, Inventory, Price, Product, ProductError, ProductImage, ShippingInfo,
+    BundleComponent, BundlePricing, ChangeContext, Dimensions, FieldChange, Inventory, Price,
+    Product, ProductBundle, ProductError, ProductImage, ProductOption, ProductRevision,
+    ProductStatus, ProductStatusSchedule, ProductVariant, ScheduleState, ShippingInfo,
 };
+use crate::app_domain::model::product_bulk::{BulkChange, ProductBulkPatch};
+use crate::app_domain::model::product_filter::{AttributeFilter, ProductFilter};
+use crate::app_domain::model::slug;
 use crate::app_domain::repository::product_repository::ProductRepository;
 use crate::application::dto::product_dto::{
-    BatchUpdateRequest, BatchUpdateResponse, BatchUpdateResult, CreateProductRequest,
-    ImageReorderRequest, InventoryRequest, InventoryResponse, PatchProductRequest, PriceRequest,
-    PriceResponse, ProductHistoryQuery, ProductHistoryResponse, ProductImageRequest,
-    ProductImageResponse, ProductListResponse, ProductResponse, ProductSearchQuery,
-    UpdateProductRequest,
+    BatchUpdateRequest, BatchUpdateResponse, BatchUpdateResult, BulkUpdateFailure,
+    BulkUpdatePreviewResponse, BulkUpdateRequest, BulkUpdateResponse, BulkUpdateSample,
+    BundleComponentResponse, BundleResponse, CreateProductRequest, CreateStatusScheduleRequest,
+    CreateVariantRequest, ImageOrderRequest, ImageReorderRequest, InventoryRequest,
+    InventoryReservationItem, InventoryReservationResponse, InventoryResponse, PatchProductRequest,
+    PriceRequest, PriceResponse, ProductHistoryQuery, ProductHistoryResponse, ProductImageRequest,
+    ProductImageResponse, ProductListResponse, ProductOptionResponse, ProductResponse,
+    ProductSearchQuery, ProductVariantListResponse, ProductVariantResponse,
+    RollbackPreviewResponse, RollbackRequest, SetBundleRequest, SetProductOptionsRequest,
+    StatusScheduleResponse, UpdateProductRequest, UpdateVariantRequest,
 };
+use crate::application::dto::translation_dto::{
+    ImageAltTextResponse, MissingTranslationResponse, ProductTranslationRequest,
+    ProductTranslationResponse, ProductTranslationsResponse,
+};
+use crate::application::service::change_feed::ChangeFeed;
 use crate::infrastructure::metrics::Metrics;
 
+/// 条件指定の一括更新で1回に読み込む商品数
+const BULK_UPDATE_BATCH_SIZE: i64 = 100;
+/// 一括更新のプレビューで返すサンプルの既定件数と上限
+const BULK_PREVIEW_SAMPLE_SIZE: usize = 10;
+const BULK_PREVIEW_MAX_SAMPLE_SIZE: usize = 100;
+
 pub struct ProductService {
     repository: Arc<dyn ProductRepository>,
+    changes: Option<Arc<ChangeFeed>>,
+    locales: Locales,
+}
+
+/// ロールバック後の状態と、現在の状態からの差分
+struct RollbackPlan {
+    label: String,
+    version: i64,
+    product: Option<Product>,
+    price: Option<Price>,
+    tags: Option<Vec<String>>,
+    attributes: Option<HashMap<String, String>>,
+    changes: Vec<FieldChange>,
+    conflicts: Vec<String>,
 }
 
 impl ProductService {
     pub fn new(repository: Arc<dyn ProductRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            changes: None,
+            locales: Locales::default(),
+        }
+    }
+
+    /// 翻訳を扱うロケールを設定する
+    pub fn with_locales(mut self, locales: Locales) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    pub fn locales(&self) -> &Locales {
+        &self.locales
+    }
+
+    /// 商品の変更を変更フィードに通知する（復元は DeletionFacade が通知する）
+    pub fn with_change_feed(mut self, changes: Arc<ChangeFeed>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    /// 変更後の商品を読み直して変更フィードに通知する
+    async fn notify(&self, kind: ChangeKind, id: &str, changed_fields: &[&str]) {
+        if let Some(changes) = &self.changes {
+            if let Some(product) = self.repository.find_by_id(id).await {
+                changes.publish(kind, (&product).into(), changed_fields);
+            }
+        }
     }
 
     pub async fn find_by_id(&self, id: &str) -> Result<ProductResponse, ProductError> {
@@ -45,6 +114,17 @@ impl ProductService {
                     response.tags = self.repository.get_tags(id).await;
                     response.attributes = self.repository.get_attributes(id).await;
 
+                    let options = self.repository.get_options(id).await;
+                    response.options = options.into_iter().map(Into::into).collect();
+
+                    let variants = self.repository.get_variants(id).await;
+                    response.price_range = ProductVariant::price_range(&variants).map(Into::into);
+                    response.variants = variants.into_iter().map(Into::into).collect();
+
+                    if let Some(bundle) = self.repository.get_bundle(id).await {
+                        response.bundle = Some(self.build_bundle_response(bundle).await);
+                    }
+
                     info!("Fetched product {}", id);
                     Ok(response)
                 }
@@ -79,6 +159,17 @@ impl ProductService {
                     response.tags = self.repository.get_tags(&id).await;
                     response.attributes = self.repository.get_attributes(&id).await;
 
+                    let options = self.repository.get_options(&id).await;
+                    response.options = options.into_iter().map(Into::into).collect();
+
+                    let variants = self.repository.get_variants(&id).await;
+                    response.price_range = ProductVariant::price_range(&variants).map(Into::into);
+                    response.variants = variants.into_iter().map(Into::into).collect();
+
+                    if let Some(bundle) = self.repository.get_bundle(&id).await {
+                        response.bundle = Some(self.build_bundle_response(bundle).await);
+                    }
+
                     info!("Fetched product by SKU {}", sku);
                     Ok(response)
                 }
@@ -91,11 +182,40 @@ impl ProductService {
         .await
     }
 
-    pub async fn search(&self, query: ProductSearchQuery) -> ProductListResponse {
+    /// 現在のスラッグ、見つからなければ変更前のスラッグで商品を取得する
+    pub async fn find_by_slug(&self, slug: &str) -> Result<ProductResponse, ProductError> {
+        Metrics::with_metrics("product", "find_by_slug", async {
+            let product = match self.repository.find_by_slug(slug).await {
+                Some(product) => product,
+                None => self
+                    .repository
+                    .find_by_old_slug(slug)
+                    .await
+                    .ok_or(ProductError::ProductNotFound)?,
+            };
+            self.find_by_id(&product.id).await
+        })
+        .await
+    }
+
+    pub async fn search(
+        &self,
+        query: ProductSearchQuery,
+    ) -> Result<ProductListResponse, ProductError> {
         let tag_vec: Option<Vec<&str>> = query
             .tags
             .as_ref()
             .map(|tags| tags.split(',').map(|s| s.trim()).collect());
+        let attribute_filters = match query.attributes.as_deref() {
+            Some(attributes) => {
+                let filters = ProductFilter::parse_attributes(attributes).inspect_err(|_| {
+                    Metrics::record_error("product", "search");
+                })?;
+                self.resolve_attribute_filters(query.category_id.as_deref(), filters)
+                    .await?
+            }
+            None => Vec::new(),
+        };
 
         let products = self
             .repository
@@ -106,11 +226,16 @@ impl ProductService {
                 query.min_price,
                 query.max_price,
                 query.in_stock_only.unwrap_or(false),
+                &attribute_filters,
                 query.limit,
                 query.offset,
             )
             .await;
 
+        // Variants roll up to the parent as a price range (loaded for the whole page at once)
+        let product_ids: Vec<String> = products.iter().map(|p| p.id.clone()).collect();
+        let mut variants = self.repository.get_variants_for(&product_ids).await;
+
         let mut product_responses = Vec::new();
         for product in &products {
             let mut response = ProductResponse::from(product.clone());
@@ -126,6 +251,9 @@ impl ProductService {
 
             response.tags = self.repository.get_tags(&product.id).await;
 
+            let variants = variants.remove(&product.id).unwrap_or_default();
+            response.price_range = ProductVariant::price_range(&variants).map(Into::into);
+
             product_responses.push(response);
         }
 
@@ -135,16 +263,60 @@ impl ProductService {
         Metrics::record_success("product", "search");
         info!("Found {} products", products.len());
 
-        ProductListResponse {
+        Ok(ProductListResponse {
             products: product_responses,
             total,
             has_more,
+        })
+    }
+
+    /// カテゴリ（祖先を含む）の属性定義を取得する。カテゴリがなければ定義なしとして扱う
+    async fn attribute_schema(
+        &self,
+        category_id: Option<&str>,
+    ) -> Result<AttributeSchema, ProductError> {
+        match category_id {
+            Some(category_id) => self.repository.find_attribute_schema(category_id).await,
+            None => Ok(AttributeSchema::default()),
         }
     }
 
+    /// 属性をカテゴリの属性定義で検証し、属性名と値を正規化する
+    async fn validate_attributes(
+        &self,
+        category_id: Option<&str>,
+        attributes: HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, ProductError> {
+        self.attribute_schema(category_id)
+            .await?
+            .validate(attributes)
+            .map_err(ProductError::InvalidAttributes)
+    }
+
+    /// カテゴリを変更する商品の既存の属性が、変更先の属性定義に合うことを確かめる
+    async fn ensure_attributes_fit(&self, product: &Product) -> Result<(), ProductError> {
+        let attributes = self.repository.get_attributes(&product.id).await;
+        self.validate_attributes(product.category_id.as_deref(), attributes)
+            .await
+            .map(|_| ())
+    }
+
+    /// 検索の属性条件をカテゴリの属性定義に合わせる（カテゴリの指定がなければそのまま）
+    async fn resolve_attribute_filters(
+        &self,
+        category_id: Option<&str>,
+        filters: Vec<AttributeFilter>,
+    ) -> Result<Vec<AttributeFilter>, ProductError> {
+        self.attribute_schema(category_id)
+            .await?
+            .resolve_filters(filters)
+            .map_err(ProductError::InvalidFilter)
+    }
+
     pub async fn create(
         &self,
         request: CreateProductRequest,
+        ctx: &ChangeContext,
     ) -> Result<ProductResponse, ProductError> {
         // Check if SKU already exists
         if self.repository.exists_by_sku(&request.sku, None).await {
@@ -182,61 +354,167 @@ impl ProductService {
         product.weight = request.weight;
         product.shipping_info = shipping_info;
 
+        // 新規商品は Draft からの遷移として扱い、公開の前提条件も保存前に検証する
+        // （作成時には画像を登録できないため、Active での作成は画像不足で拒否される）
+        if !ProductStatus::Draft.can_transition_to(&product.status) {
+            Metrics::record_error("product", "create");
+            return Err(ProductError::InvalidStatusTransition {
+                from: ProductStatus::Draft,
+                to: product.status.clone(),
+            });
+        }
+        if product.status == ProductStatus::Active {
+            product
+                .validate_activation(true, 0)
+                .inspect_err(|_| Metrics::record_error("product", "create"))?;
+        }
+
+        // 指定されたスラッグは重複をエラーにし、名前から生成したものは連番で重複を避ける
+        match request.slug {
+            Some(slug) => {
+                product.update_slug(slug)?;
+                if self.repository.exists_by_slug(&product.slug, None).await {
+                    Metrics::record_error("product", "create");
+                    return Err(ProductError::SlugAlreadyExists);
+                }
+            }
+            None if product.slug.is_empty() => {
+                Metrics::record_error("product", "create");
+                return Err(ProductError::SlugRequired);
+            }
+            None => product.slug = self.available_slug(&product.slug).await,
+        }
+
         // Validate weight if provided
         if let Some(weight) = product.weight {
             product.update_weight(Some(weight))?;
         }
 
+        // 属性はカテゴリの属性定義に合わせて正規化する（必須属性の欠落もここで検出する）
+        let attributes = self
+            .validate_attributes(
+                product.category_id.as_deref(),
+                request.attributes.unwrap_or_default(),
+            )
+            .await
+            .inspect_err(|_| Metrics::record_error("product", "create"))?;
+
         // Create the product
-        let _created_product = self.repository.create(product).await?;
+        let _created_product = self.repository.create(product, ctx).await?;
 
         // Set initial price
         let price = Price::from(request.price);
         price.validate()?;
         self.repository
-            .update_price(&product_id, price.clone())
+            .update_price(&product_id, price.clone(), ctx)
             .await?;
 
         // Set initial inventory
         let inventory = Inventory::from(request.inventory);
         inventory.validate()?;
         self.repository
-            .update_inventory(&product_id, inventory.clone())
+            .update_inventory(&product_id, inventory.clone(), ctx)
             .await?;
 
         // Add tags if provided
         if let Some(tags) = request.tags {
             if !tags.is_empty() {
-                self.repository.add_tags(&product_id, tags).await?;
+                self.repository.add_tags(&product_id, tags, ctx).await?;
             }
         }
 
         // Add attributes if provided
-        if let Some(attributes) = request.attributes {
-            if !attributes.is_empty() {
-                self.repository
-                    .set_attributes(&product_id, attributes)
-                    .await?;
-            }
+        if !attributes.is_empty() {
+            self.repository
+                .set_attributes(&product_id, attributes, ctx)
+                .await?;
         }
 
         Metrics::record_success("product", "create");
+        self.notify(ChangeKind::Created, &product_id, &[]).await;
         info!("Created product {}", product_id);
 
         // Return full response
         self.find_by_id(&product_id).await
     }
 
+    /// 使われていなければ `base` を、使われていれば連番を付けた空きスラッグを返す
+    async fn available_slug(&self, base: &str) -> String {
+        let mut n = 1;
+        loop {
+            let candidate = slug::with_suffix(base, n);
+            if !self.repository.exists_by_slug(&candidate, None).await {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    /// 商品を削除する。`logical` の場合は物理削除せず、ステータスを Discontinued に変更する
+    ///
+    /// `expected_version` の照合は削除と同じトランザクションで商品をロックして行う。
+    /// 論理削除の場合は、商品を構成品として使用しているセット商品の ID を返す。
+    pub async fn delete(
+        &self,
+        id: &str,
+        logical: bool,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<Vec<String>, ProductError> {
+        let operation = if logical {
+            "delete_logical"
+        } else {
+            "delete_physical"
+        };
+        let used_in_bundles = self.repository.find_bundles_by_component(id).await;
+
+        let result = if logical {
+            match self.repository.find_by_id(id).await {
+                Some(mut product) => match product.check_version(expected_version) {
+                    Ok(()) => {
+                        // Discontinued への遷移はどの状態からも許可される
+                        let _ = product.update_status(ProductStatus::Discontinued);
+                        // update は読み込んだバージョンをロックした行と照合する
+                        self.repository.update(product, ctx).await.map(|_| ())
+                    }
+                    Err(e) => Err(e),
+                },
+                None => Err(ProductError::ProductNotFound),
+            }
+        } else if !used_in_bundles.is_empty() {
+            // セット商品の構成品は物理削除できない
+            Err(ProductError::UsedInBundle(used_in_bundles.clone()))
+        } else {
+            self.repository.delete(id, expected_version).await
+        };
+
+        if let Err(e) = result {
+            Metrics::record_error("product", operation);
+            return Err(e);
+        }
+
+        Metrics::record_success("product", operation);
+        info!("Deleted product {} (logical: {})", id, logical);
+        if let Some(changes) = &self.changes {
+            changes.publish_id(EntityType::Product, id, ChangeKind::Deleted);
+        }
+
+        Ok(if logical { used_in_bundles } else { Vec::new() })
+    }
+
     pub async fn update(
         &self,
         id: &str,
         request: UpdateProductRequest,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
     ) -> Result<ProductResponse, ProductError> {
         let mut product = self
             .repository
             .find_by_id(id)
             .await
             .ok_or(ProductError::ProductNotFound)?;
+        product.check_version(expected_version)?;
 
         // Check SKU uniqueness if being updated
         if let Some(ref new_sku) = request.sku {
@@ -246,6 +524,8 @@ impl ProductService {
             }
         }
 
+        let previous_status = product.status.clone();
+
         // Update fields
         if let Some(name) = request.name {
             product.update_name(name)?;
@@ -259,18 +539,50 @@ impl ProductService {
             product.update_sku(sku)?;
         }
 
+        if let Some(slug) = request.slug {
+            if slug != product.slug {
+                product.update_slug(slug)?;
+                if self
+                    .repository
+                    .exists_by_slug(&product.slug, Some(id))
+                    .await
+                {
+                    Metrics::record_error("product", "update");
+                    return Err(ProductError::SlugAlreadyExists);
+                }
+            }
+        }
+
         if let Some(brand) = request.brand {
             product.update_brand(Some(brand));
         }
 
         if let Some(status) = request.status {
-            product.update_status(status);
+            product.update_status(status)?;
         }
 
+        let category_changed = request
+            .category_id
+            .as_ref()
+            .is_some_and(|category_id| product.category_id.as_ref() != Some(category_id));
         if let Some(category_id) = request.category_id {
             product.update_category(Some(category_id));
         }
 
+        // 属性を指定しない場合も、カテゴリを変えるなら既存の属性が変更先の定義に合う必要がある
+        let attributes = match request.attributes {
+            Some(attributes) => Some(
+                self.validate_attributes(product.category_id.as_deref(), attributes)
+                    .await?,
+            ),
+            None => {
+                if category_changed {
+                    self.ensure_attributes_fit(&product).await?;
+                }
+                None
+            }
+        };
+
         if let Some(dimensions_req) = request.dimensions {
             let dimensions = Some(Dimensions::new(
                 dimensions_req.width,
@@ -289,34 +601,38 @@ impl ProductService {
             product.update_shipping_info(shipping_info)?;
         }
 
+        self.ensure_activation_ready(&product, &previous_status, request.price.is_some())
+            .await?;
+
         // Update the product
-        self.repository.update(product).await?;
+        self.repository.update(product, ctx).await?;
 
         // Update price if provided
         if let Some(price_req) = request.price {
             let price = Price::from(price_req);
             price.validate()?;
-            self.repository.update_price(id, price).await?;
+            self.repository.update_price(id, price, ctx).await?;
         }
 
         // Update inventory if provided
         if let Some(inventory_req) = request.inventory {
             let inventory = Inventory::from(inventory_req);
             inventory.validate()?;
-            self.repository.update_inventory(id, inventory).await?;
+            self.repository.update_inventory(id, inventory, ctx).await?;
         }
 
         // Update tags if provided
         if let Some(tags) = request.tags {
-            self.repository.replace_tags(id, tags).await?;
+            self.repository.replace_tags(id, tags, ctx).await?;
         }
 
         // Update attributes if provided
-        if let Some(attributes) = request.attributes {
-            self.repository.set_attributes(id, attributes).await?;
+        if let Some(attributes) = attributes {
+            self.repository.set_attributes(id, attributes, ctx).await?;
         }
 
         Metrics::record_success("product", "update");
+        self.notify(ChangeKind::Updated, id, &[]).await;
         info!("Updated product {}", id);
 
         self.find_by_id(id).await
@@ -326,12 +642,17 @@ impl ProductService {
         &self,
         id: &str,
         request: PatchProductRequest,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
     ) -> Result<ProductResponse, ProductError> {
         let mut product = self
             .repository
             .find_by_id(id)
             .await
             .ok_or(ProductError::ProductNotFound)?;
+        product.check_version(expected_version)?;
+
+        let previous_status = product.status.clone();
 
         // Update basic fields
         if let Some(name) = request.name {
@@ -343,15 +664,22 @@ impl ProductService {
         }
 
         if let Some(status) = request.status {
-            product.update_status(status);
+            product.update_status(status)?;
         }
 
         if let Some(category_id) = request.category_id {
+            let category_changed = product.category_id.as_ref() != Some(&category_id);
             product.update_category(Some(category_id));
+            if category_changed {
+                self.ensure_attributes_fit(&product).await?;
+            }
         }
 
+        self.ensure_activation_ready(&product, &previous_status, false)
+            .await?;
+
         // Update the product
-        self.repository.update(product).await?;
+        self.repository.update(product, ctx).await?;
 
         // Update price if provided
         if let Some(price_patch) = request.price {
@@ -371,7 +699,7 @@ impl ProductService {
                 }
 
                 new_price.validate()?;
-                self.repository.update_price(id, new_price).await?;
+                self.repository.update_price(id, new_price, ctx).await?;
             }
         }
 
@@ -400,12 +728,13 @@ impl ProductService {
 
                 current_inventory.validate()?;
                 self.repository
-                    .update_inventory(id, current_inventory)
+                    .update_inventory(id, current_inventory, ctx)
                     .await?;
             }
         }
 
         Metrics::record_success("product", "patch");
+        self.notify(ChangeKind::Updated, id, &[]).await;
         info!("Patched product {}", id);
 
         self.find_by_id(id).await
@@ -415,6 +744,7 @@ impl ProductService {
         &self,
         id: &str,
         request: PriceRequest,
+        ctx: &ChangeContext,
     ) -> Result<PriceResponse, ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
@@ -425,9 +755,10 @@ impl ProductService {
         let price = Price::from(request);
         price.validate()?;
 
-        let updated_price = self.repository.update_price(id, price).await?;
+        let updated_price = self.repository.update_price(id, price, ctx).await?;
 
         Metrics::record_success("product", "update_price");
+        self.notify(ChangeKind::Updated, id, &["price"]).await;
         info!("Updated price for product {}", id);
 
         Ok(updated_price.into())
@@ -437,6 +768,7 @@ impl ProductService {
         &self,
         id: &str,
         request: InventoryRequest,
+        ctx: &ChangeContext,
     ) -> Result<InventoryResponse, ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
@@ -447,9 +779,10 @@ impl ProductService {
         let inventory = Inventory::from(request);
         inventory.validate()?;
 
-        let updated_inventory = self.repository.update_inventory(id, inventory).await?;
+        let updated_inventory = self.repository.update_inventory(id, inventory, ctx).await?;
 
         Metrics::record_success("product", "update_inventory");
+        self.notify(ChangeKind::Updated, id, &["inventory"]).await;
         info!("Updated inventory for product {}", id);
 
         Ok(updated_inventory.into())
@@ -459,6 +792,7 @@ impl ProductService {
         &self,
         id: &str,
         request: ProductImageRequest,
+        ctx: &ChangeContext,
     ) -> Result<ProductImageResponse, ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
@@ -467,9 +801,10 @@ impl ProductService {
         }
 
         let image = ProductImage::from(request);
-        let added_image = self.repository.add_image(id, image).await?;
+        let added_image = self.repository.add_image(id, image, ctx).await?;
 
         Metrics::record_success("product", "add_image");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
         info!("Added image {} to product {}", added_image.id, id);
 
         Ok(added_image.into())
@@ -480,6 +815,7 @@ impl ProductService {
         id: &str,
         image_id: &str,
         request: ProductImageRequest,
+        ctx: &ChangeContext,
     ) -> Result<ProductImageResponse, ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
@@ -490,18 +826,25 @@ impl ProductService {
         let mut image = ProductImage::from(request);
         image.id = image_id.to_string();
 
-        let updated_image = self.repository.update_image(id, image).await?;
+        let updated_image = self.repository.update_image(id, image, ctx).await?;
 
         Metrics::record_success("product", "update_image");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
         info!("Updated image {} for product {}", image_id, id);
 
         Ok(updated_image.into())
     }
 
-    pub async fn delete_image(&self, id: &str, image_id: &str) -> Result<(), ProductError> {
-        self.repository.delete_image(id, image_id).await?;
+    pub async fn delete_image(
+        &self,
+        id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        self.repository.delete_image(id, image_id, ctx).await?;
 
         Metrics::record_success("product", "delete_image");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
         info!("Deleted image {} from product {}", image_id, id);
 
         Ok(())
@@ -511,6 +854,7 @@ impl ProductService {
         &self,
         id: &str,
         request: ImageReorderRequest,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
@@ -524,166 +868,1137 @@ impl ProductService {
             .map(|item| (item.image_id, item.sort_order))
             .collect();
 
-        self.repository.reorder_images(id, image_orders).await?;
+        self.repository
+            .reorder_images(id, image_orders, ctx)
+            .await?;
 
         Metrics::record_success("product", "reorder_images");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
         info!("Reordered images for product {}", id);
 
         Ok(())
     }
 
-    pub async fn set_main_image(&self, id: &str, image_id: &str) -> Result<(), ProductError> {
-        self.repository.set_main_image(id, image_id).await?;
+    /// 画像 ID の一覧の順に並び順を振り直す（一覧が画像すべてと一致しなければ `InvalidImageOrder`）
+    pub async fn set_image_order(
+        &self,
+        id: &str,
+        request: ImageOrderRequest,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "set_image_order");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        self.repository
+            .set_image_order(id, request.image_ids, ctx)
+            .await?;
+
+        Metrics::record_success("product", "set_image_order");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
+        info!("Set image order for product {}", id);
+
+        Ok(())
+    }
+
+    pub async fn set_main_image(
+        &self,
+        id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        self.repository.set_main_image(id, image_id, ctx).await?;
 
         Metrics::record_success("product", "set_main_image");
+        self.notify(ChangeKind::Updated, id, &["images"]).await;
         info!("Set main image {} for product {}", image_id, id);
 
         Ok(())
     }
 
-    pub async fn get_history(
+    pub async fn get_options(&self, id: &str) -> Result<Vec<ProductOptionResponse>, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "get_options");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let options = self.repository.get_options(id).await;
+
+        Metrics::record_success("product", "get_options");
+        Ok(options.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn set_options(
         &self,
         id: &str,
-        query: ProductHistoryQuery,
-    ) -> Result<ProductHistoryResponse, ProductError> {
+        request: SetProductOptionsRequest,
+    ) -> Result<Vec<ProductOptionResponse>, ProductError> {
         // Verify product exists
         if self.repository.find_by_id(id).await.is_none() {
-            Metrics::record_error("product", "get_history");
+            Metrics::record_error("product", "set_options");
             return Err(ProductError::ProductNotFound);
         }
 
-        let history_items = self
-            .repository
-            .get_history(id, query.field.as_deref(), query.limit, query.offset)
-            .await;
+        let options: Vec<ProductOption> = request.options.into_iter().map(Into::into).collect();
+        ProductOption::validate_all(&options)?;
 
-        let total = history_items.len() as i64;
-        let has_more = query.limit.is_some_and(|limit| total >= limit);
+        // Existing variants must remain valid under the new option definitions
+        let variants = self.repository.get_variants(id).await;
+        for variant in &variants {
+            variant.validate_options(&options)?;
+        }
 
-        let history_response = history_items.into_iter().map(Into::into).collect();
+        self.repository.set_options(id, options.clone()).await?;
 
-        Metrics::record_success("product", "get_history");
-        info!("Fetched {} history items for product {}", total, id);
+        Metrics::record_success("product", "set_options");
+        self.notify(ChangeKind::Updated, id, &["options"]).await;
+        info!("Set {} options for product {}", options.len(), id);
 
-        Ok(ProductHistoryResponse {
-            history: history_response,
-            total,
-            has_more,
-        })
+        Ok(options.into_iter().map(Into::into).collect())
     }
 
-    pub async fn batch_update(
+    pub async fn list_variants(
         &self,
-        request: BatchUpdateRequest,
-    ) -> Result<BatchUpdateResponse, ProductError> {
-        let mut results = Vec::new();
-        let mut success_count = 0;
-        let mut error_count = 0;
-
-        for update_item in request.updates {
-            let result = match self.apply_batch_update(&update_item).await {
-                Ok(product) => {
-                    success_count += 1;
-                    BatchUpdateResult {
-                        id: update_item.id.clone(),
-                        success: true,
-                        product: Some(product),
-                        error: None,
-                    }
-                }
-                Err(error) => {
-                    error_count += 1;
-                    BatchUpdateResult {
-                        id: update_item.id.clone(),
-                        success: false,
-                        product: None,
-                        error: Some(error.to_string()),
-                    }
-                }
-            };
-            results.push(result);
+        id: &str,
+    ) -> Result<ProductVariantListResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "list_variants");
+            return Err(ProductError::ProductNotFound);
         }
 
-        Metrics::record_success("product", "batch_update");
-        info!(
-            "Batch update completed: {} success, {} errors",
-            success_count, error_count
-        );
+        let options = self.repository.get_options(id).await;
+        let variants = self.repository.get_variants(id).await;
+        let price_range = ProductVariant::price_range(&variants).map(Into::into);
 
-        Ok(BatchUpdateResponse {
-            results,
-            success_count,
-            error_count,
+        Metrics::record_success("product", "list_variants");
+        info!("Fetched {} variants for product {}", variants.len(), id);
+
+        Ok(ProductVariantListResponse {
+            options: options.into_iter().map(Into::into).collect(),
+            variants: variants.into_iter().map(Into::into).collect(),
+            price_range,
         })
     }
 
-    async fn apply_batch_update(
+    pub async fn create_variant(
         &self,
-        update_item: &crate::application::dto::product_dto::BatchUpdateItem,
-    ) -> Result<ProductResponse, ProductError> {
-        let mut product = self
-            .repository
-            .find_by_id(&update_item.id)
-            .await
-            .ok_or(ProductError::ProductNotFound)?;
-
-        // Update name if provided
-        if let Some(ref name) = update_item.name {
-            product.update_name(name.clone())?;
+        id: &str,
+        request: CreateVariantRequest,
+    ) -> Result<ProductVariantResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "create_variant");
+            return Err(ProductError::ProductNotFound);
         }
 
-        // Update status if provided
-        if let Some(ref status) = update_item.status {
-            product.update_status(status.clone());
+        if self.repository.exists_by_sku(&request.sku, None).await {
+            Metrics::record_error("product", "create_variant");
+            return Err(ProductError::SkuAlreadyExists);
         }
 
-        // Update the product
-        self.repository.update(product).await?;
+        let variant = ProductVariant::new(
+            Uuid::new_v4().to_string(),
+            id.to_string(),
+            request.sku,
+            request.option_values,
+            Price::from(request.price),
+            Inventory::from(request.inventory),
+        )?;
 
-        // Update price if provided
-        if let Some(ref price_patch) = update_item.price {
-            if let Some(current_price) = self.repository.get_current_price(&update_item.id).await {
-                let mut new_price = current_price;
+        let options = self.repository.get_options(id).await;
+        variant.validate_options(&options)?;
 
-                if let Some(selling_price) = price_patch.selling_price {
-                    new_price.selling_price = selling_price;
-                }
+        let mut variants = self.repository.get_variants(id).await;
+        variants.push(variant.clone());
+        ProductVariant::validate_unique_combinations(&variants)?;
 
-                if let Some(list_price) = price_patch.list_price {
-                    new_price.list_price = Some(list_price);
-                }
+        let created_variant = self.repository.create_variant(variant).await?;
 
-                if let Some(discount_price) = price_patch.discount_price {
-                    new_price.discount_price = Some(discount_price);
-                }
+        Metrics::record_success("product", "create_variant");
+        self.notify(ChangeKind::Updated, id, &["variants"]).await;
+        info!("Created variant {} for product {}", created_variant.id, id);
 
-                new_price.validate()?;
-                self.repository
-                    .update_price(&update_item.id, new_price)
-                    .await?;
-            }
+        Ok(created_variant.into())
+    }
+
+    pub async fn update_variant(
+        &self,
+        id: &str,
+        variant_id: &str,
+        request: UpdateVariantRequest,
+    ) -> Result<ProductVariantResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "update_variant");
+            return Err(ProductError::ProductNotFound);
         }
 
-        // Update inventory if provided
-        if let Some(ref inventory_patch) = update_item.inventory {
-            if let Some(mut current_inventory) =
-                self.repository.get_inventory(&update_item.id).await
-            {
-                if let Some(quantity) = inventory_patch.quantity {
-                    current_inventory.update_quantity(quantity)?;
-                }
+        let mut variants = self.repository.get_variants(id).await;
+        let position = variants
+            .iter()
+            .position(|variant| variant.id == variant_id)
+            .ok_or(ProductError::VariantNotFound)?;
 
-                if let Some(reserved_quantity) = inventory_patch.reserved_quantity {
-                    current_inventory.reserved_quantity = reserved_quantity;
-                }
+        {
+            let variant = &mut variants[position];
 
-                if let Some(alert_threshold) = inventory_patch.alert_threshold {
-                    current_inventory.alert_threshold = Some(alert_threshold);
+            if let Some(sku) = request.sku {
+                if sku != variant.sku && self.repository.exists_by_sku(&sku, Some(variant_id)).await
+                {
+                    Metrics::record_error("product", "update_variant");
+                    return Err(ProductError::SkuAlreadyExists);
                 }
+                variant.update_sku(sku)?;
+            }
 
-                if let Some(track_inventory) = inventory_patch.track_inventory {
-                    current_inventory.track_inventory = track_inventory;
-                }
+            if let Some(option_values) = request.option_values {
+                variant.option_values = option_values;
+            }
+
+            if let Some(price_req) = request.price {
+                variant.update_price(Price::from(price_req))?;
+            }
+
+            if let Some(inventory_req) = request.inventory {
+                variant.update_inventory(Inventory::from(inventory_req))?;
+            }
+
+            let options = self.repository.get_options(id).await;
+            variant.validate_options(&options)?;
+        }
+
+        ProductVariant::validate_unique_combinations(&variants)?;
+
+        let updated_variant = self
+            .repository
+            .update_variant(variants.swap_remove(position))
+            .await?;
+
+        Metrics::record_success("product", "update_variant");
+        self.notify(ChangeKind::Updated, id, &["variants"]).await;
+        info!("Updated variant {} for product {}", variant_id, id);
+
+        Ok(updated_variant.into())
+    }
+
+    pub async fn delete_variant(&self, id: &str, variant_id: &str) -> Result<(), ProductError> {
+        self.repository.delete_variant(id, variant_id).await?;
+
+        Metrics::record_success("product", "delete_variant");
+        self.notify(ChangeKind::Updated, id, &["variants"]).await;
+        info!("Deleted variant {} from product {}", variant_id, id);
+
+        Ok(())
+    }
+
+    pub async fn get_bundle(&self, id: &str) -> Result<BundleResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "get_bundle");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let bundle = self
+            .repository
+            .get_bundle(id)
+            .await
+            .ok_or(ProductError::BundleNotFound)?;
+
+        Metrics::record_success("product", "get_bundle");
+        Ok(self.build_bundle_response(bundle).await)
+    }
+
+    pub async fn set_bundle(
+        &self,
+        id: &str,
+        request: SetBundleRequest,
+    ) -> Result<BundleResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "set_bundle");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let components: Vec<BundleComponent> =
+            request.components.into_iter().map(Into::into).collect();
+        let bundle = ProductBundle::new(id.to_string(), components, request.pricing)?;
+
+        // Components must exist and must not be bundles themselves
+        for component in &bundle.components {
+            if self
+                .repository
+                .find_by_id(&component.product_id)
+                .await
+                .is_none()
+            {
+                Metrics::record_error("product", "set_bundle");
+                return Err(ProductError::ProductNotFound);
+            }
+            if self
+                .repository
+                .get_bundle(&component.product_id)
+                .await
+                .is_some()
+            {
+                Metrics::record_error("product", "set_bundle");
+                return Err(ProductError::InvalidBundle);
+            }
+        }
+
+        // A product used as a component cannot become a bundle
+        if !self
+            .repository
+            .find_bundles_by_component(id)
+            .await
+            .is_empty()
+        {
+            Metrics::record_error("product", "set_bundle");
+            return Err(ProductError::InvalidBundle);
+        }
+
+        self.repository.set_bundle(bundle.clone()).await?;
+
+        Metrics::record_success("product", "set_bundle");
+        self.notify(ChangeKind::Updated, id, &["bundle"]).await;
+        info!(
+            "Set bundle for product {} with {} components",
+            id,
+            bundle.components.len()
+        );
+
+        Ok(self.build_bundle_response(bundle).await)
+    }
+
+    pub async fn remove_bundle(&self, id: &str) -> Result<(), ProductError> {
+        self.repository.delete_bundle(id).await?;
+
+        Metrics::record_success("product", "remove_bundle");
+        self.notify(ChangeKind::Updated, id, &["bundle"]).await;
+        info!("Removed bundle definition from product {}", id);
+
+        Ok(())
+    }
+
+    /// 既定ロケール以外が指定された場合、翻訳のある項目をそのロケールの内容で置き換える
+    pub async fn localize(&self, products: &mut [ProductResponse], locale: &str) {
+        if self.locales.is_default(locale) {
+            return;
+        }
+        let ids: Vec<String> = products.iter().map(|product| product.id.clone()).collect();
+        let translations = self.repository.get_translations_for(&ids, locale).await;
+        for product in products {
+            if let Some(translation) = translations.get(&product.id) {
+                product.localize(translation);
+            }
+        }
+    }
+
+    pub async fn get_translations(
+        &self,
+        id: &str,
+    ) -> Result<ProductTranslationsResponse, ProductError> {
+        let Some(product) = self.repository.find_by_id(id).await else {
+            Metrics::record_error("product", "get_translations");
+            return Err(ProductError::ProductNotFound);
+        };
+        let images = self.repository.get_images(id).await;
+        let mut stored = self.repository.get_translations(id).await;
+
+        let translations = self
+            .locales
+            .translatable()
+            .map(|locale| {
+                let translation = stored
+                    .iter()
+                    .position(|t| t.locale == locale)
+                    .map(|index| stored.swap_remove(index))
+                    .unwrap_or_else(|| ProductTranslation {
+                        locale: locale.to_string(),
+                        ..Default::default()
+                    });
+                Self::translation_response(&product, &images, translation)
+            })
+            .collect();
+
+        Metrics::record_success("product", "get_translations");
+        Ok(ProductTranslationsResponse {
+            product_id: product.id,
+            default_locale: self.locales.default_locale().to_string(),
+            translations,
+        })
+    }
+
+    /// ロケールの翻訳をまとめて置き換える（リクエストにない画像の代替テキストは削除される）
+    pub async fn set_translation(
+        &self,
+        id: &str,
+        locale: &str,
+        request: ProductTranslationRequest,
+    ) -> Result<ProductTranslationResponse, ProductError> {
+        let locale = self.locales.translation_locale(locale).map_err(|e| {
+            Metrics::record_error("product", "set_translation");
+            ProductError::InvalidTranslation(e)
+        })?;
+        let Some(product) = self.repository.find_by_id(id).await else {
+            Metrics::record_error("product", "set_translation");
+            return Err(ProductError::ProductNotFound);
+        };
+        let translation = request.into_translation(locale).normalize().map_err(|e| {
+            Metrics::record_error("product", "set_translation");
+            ProductError::InvalidTranslation(e)
+        })?;
+
+        let images = self.repository.get_images(id).await;
+        if let Some(image_id) = translation
+            .image_alt_texts
+            .keys()
+            .find(|image_id| !images.iter().any(|image| &image.id == *image_id))
+        {
+            Metrics::record_error("product", "set_translation");
+            return Err(ProductError::InvalidTranslation(format!(
+                "商品の画像ではありません: {}",
+                image_id
+            )));
+        }
+
+        self.repository
+            .set_translation(id, translation.clone())
+            .await
+            .inspect_err(|_| Metrics::record_error("product", "set_translation"))?;
+
+        Metrics::record_success("product", "set_translation");
+        self.notify(ChangeKind::Updated, id, &["translations"])
+            .await;
+        info!("Set {} translation of product {}", translation.locale, id);
+
+        Ok(Self::translation_response(&product, &images, translation))
+    }
+
+    pub async fn delete_translation(&self, id: &str, locale: &str) -> Result<(), ProductError> {
+        let locale = self
+            .locales
+            .translation_locale(locale)
+            .map_err(ProductError::InvalidTranslation)?;
+        self.repository.delete_translation(id, &locale).await?;
+
+        Metrics::record_success("product", "delete_translation");
+        self.notify(ChangeKind::Updated, id, &["translations"])
+            .await;
+        info!("Deleted {} translation of product {}", locale, id);
+
+        Ok(())
+    }
+
+    /// ロケールの翻訳が不足している商品（ID 順）
+    pub async fn find_missing_translations(
+        &self,
+        locale: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<MissingTranslationResponse>, ProductError> {
+        let locale = self
+            .locales
+            .translation_locale(locale)
+            .map_err(ProductError::InvalidTranslation)?;
+        let missing = self
+            .repository
+            .find_missing_translations(&locale, limit, offset)
+            .await;
+
+        Metrics::record_success("product", "find_missing_translations");
+        Ok(missing.into_iter().map(Into::into).collect())
+    }
+
+    fn translation_response(
+        product: &Product,
+        images: &[ProductImage],
+        translation: ProductTranslation,
+    ) -> ProductTranslationResponse {
+        let missing_fields =
+            ProductTranslation::missing_fields(product, images, Some(&translation));
+        ProductTranslationResponse {
+            locale: translation.locale,
+            name: translation.name,
+            description: translation.description,
+            images: translation
+                .image_alt_texts
+                .into_iter()
+                .map(|(image_id, alt_text)| ImageAltTextResponse { image_id, alt_text })
+                .collect(),
+            missing_fields,
+        }
+    }
+
+    pub async fn reserve_inventory(
+        &self,
+        id: &str,
+        quantity: i32,
+    ) -> Result<InventoryReservationResponse, ProductError> {
+        let reservations = self.resolve_reservations(id, quantity).await?;
+
+        self.repository
+            .reserve_inventory(reservations.clone())
+            .await?;
+
+        Metrics::record_success("product", "reserve_inventory");
+        self.notify_reservations(id, &reservations).await;
+        info!("Reserved {} units of product {}", quantity, id);
+
+        Ok(Self::reservation_response(id, reservations))
+    }
+
+    pub async fn release_inventory(
+        &self,
+        id: &str,
+        quantity: i32,
+    ) -> Result<InventoryReservationResponse, ProductError> {
+        let reservations = self.resolve_reservations(id, quantity).await?;
+
+        self.repository
+            .release_inventory(reservations.clone())
+            .await?;
+
+        Metrics::record_success("product", "release_inventory");
+        self.notify_reservations(id, &reservations).await;
+        info!("Released {} units of product {}", quantity, id);
+
+        Ok(Self::reservation_response(id, reservations))
+    }
+
+    /// 引当・解放した商品を通知する（セット商品の場合は在庫が変わった構成品ごとにも通知する）
+    async fn notify_reservations(&self, id: &str, reservations: &[(String, i32)]) {
+        self.notify(ChangeKind::Updated, id, &["inventory"]).await;
+        for (product_id, _) in reservations
+            .iter()
+            .filter(|(product_id, _)| product_id != id)
+        {
+            self.notify(ChangeKind::Updated, product_id, &["inventory"])
+                .await;
+        }
+    }
+
+    // セット商品の場合は構成商品の在庫を引き当てる
+    async fn resolve_reservations(
+        &self,
+        id: &str,
+        quantity: i32,
+    ) -> Result<Vec<(String, i32)>, ProductError> {
+        if self.repository.find_by_id(id).await.is_none() {
+            return Err(ProductError::ProductNotFound);
+        }
+
+        if quantity <= 0 {
+            return Err(ProductError::InvalidInventoryQuantity);
+        }
+
+        match self.repository.get_bundle(id).await {
+            Some(bundle) => bundle.component_reservations(quantity),
+            None => Ok(vec![(id.to_string(), quantity)]),
+        }
+    }
+
+    fn reservation_response(
+        id: &str,
+        reservations: Vec<(String, i32)>,
+    ) -> InventoryReservationResponse {
+        InventoryReservationResponse {
+            product_id: id.to_string(),
+            reservations: reservations
+                .into_iter()
+                .map(|(product_id, quantity)| InventoryReservationItem {
+                    product_id,
+                    quantity,
+                })
+                .collect(),
+        }
+    }
+
+    async fn build_bundle_response(&self, bundle: ProductBundle) -> BundleResponse {
+        let mut inventories = HashMap::new();
+        let mut prices = HashMap::new();
+        let mut components = Vec::with_capacity(bundle.components.len());
+
+        for component in &bundle.components {
+            let product = self.repository.find_by_id(&component.product_id).await;
+            let inventory = self.repository.get_inventory(&component.product_id).await;
+            let available_quantity = inventory.as_ref().and_then(|inventory| {
+                inventory
+                    .limits_availability()
+                    .then(|| (inventory.quantity - inventory.reserved_quantity).max(0))
+            });
+
+            if let Some(inventory) = inventory {
+                inventories.insert(component.product_id.clone(), inventory);
+            }
+            if let Some(price) = self
+                .repository
+                .get_current_price(&component.product_id)
+                .await
+            {
+                prices.insert(component.product_id.clone(), price);
+            }
+
+            components.push(BundleComponentResponse {
+                product_id: component.product_id.clone(),
+                name: product.as_ref().map(|p| p.name.clone()),
+                sku: product.map(|p| p.sku),
+                quantity: component.quantity,
+                available_quantity,
+            });
+        }
+
+        // 構成品の価格が揃わない（未設定・通貨の混在）場合は、セット商品自身の価格で
+        // 代用せずに価格なしとして返す
+        let price = match bundle.pricing {
+            BundlePricing::Fixed => self.repository.get_current_price(&bundle.product_id).await,
+            BundlePricing::DiscountedSum { .. } => bundle.discounted_price(&prices),
+        };
+
+        BundleResponse {
+            available_quantity: bundle.available_quantity(&inventories),
+            product_id: bundle.product_id,
+            pricing: bundle.pricing,
+            components,
+            price: price.map(Into::into),
+        }
+    }
+
+    pub async fn publish(
+        &self,
+        id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<ProductResponse, ProductError> {
+        self.transition_status(id, ProductStatus::Active, ctx)
+            .await?;
+
+        Metrics::record_success("product", "publish");
+        info!("Published product {}", id);
+
+        self.find_by_id(id).await
+    }
+
+    pub async fn discontinue(
+        &self,
+        id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<ProductResponse, ProductError> {
+        self.transition_status(id, ProductStatus::Discontinued, ctx)
+            .await?;
+
+        Metrics::record_success("product", "discontinue");
+        info!("Discontinued product {}", id);
+
+        self.find_by_id(id).await
+    }
+
+    pub async fn list_schedules(
+        &self,
+        id: &str,
+    ) -> Result<Vec<StatusScheduleResponse>, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "list_schedules");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let schedules = self.repository.get_schedules(id).await;
+
+        Metrics::record_success("product", "list_schedules");
+        Ok(schedules.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn create_schedule(
+        &self,
+        id: &str,
+        request: CreateStatusScheduleRequest,
+        created_by: Option<&str>,
+    ) -> Result<StatusScheduleResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "create_schedule");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let schedule = ProductStatusSchedule::new(
+            Uuid::new_v4().to_string(),
+            id.to_string(),
+            request.target_status,
+            request.scheduled_at,
+            request.reason,
+            created_by.map(str::to_string),
+        )?;
+
+        let created = self.repository.create_schedule(schedule).await?;
+
+        Metrics::record_success("product", "create_schedule");
+        info!(
+            "Scheduled product {} to become {} at {}",
+            id, created.target_status, created.scheduled_at
+        );
+
+        Ok(created.into())
+    }
+
+    pub async fn cancel_schedule(&self, id: &str, schedule_id: &str) -> Result<(), ProductError> {
+        self.repository.cancel_schedule(id, schedule_id).await?;
+
+        Metrics::record_success("product", "cancel_schedule");
+        info!("Cancelled schedule {} for product {}", schedule_id, id);
+
+        Ok(())
+    }
+
+    /// 実行時刻を過ぎた予約を適用し、処理した件数を返す
+    ///
+    /// 既に目的のステータスになっている商品は変更せず適用済みとする。
+    /// 1件の適用や結果の記録に失敗しても、残りの予約の処理は続ける。
+    pub async fn run_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<usize, ProductError> {
+        let schedules = self
+            .repository
+            .claim_due_schedules(now, limit, lease_seconds)
+            .await?;
+
+        for schedule in &schedules {
+            let reason = match schedule.reason {
+                Some(ref reason) => format!("schedule {}: {}", schedule.id, reason),
+                None => format!("schedule {}", schedule.id),
+            };
+            let ctx = ChangeContext::new(
+                Some(
+                    schedule
+                        .created_by
+                        .clone()
+                        .unwrap_or_else(|| "scheduler".to_string()),
+                ),
+                Some(reason),
+            );
+
+            let (state, failure_reason) = match self
+                .transition_status(&schedule.product_id, schedule.target_status.clone(), &ctx)
+                .await
+            {
+                Ok(_) => {
+                    Metrics::record_success("product", "apply_schedule");
+                    info!(
+                        "Applied schedule {}: product {} is now {}",
+                        schedule.id, schedule.product_id, schedule.target_status
+                    );
+                    (ScheduleState::Applied, None)
+                }
+                Err(e) => {
+                    Metrics::record_error("product", "apply_schedule");
+                    error!("Failed to apply schedule {}: {}", schedule.id, e);
+                    (ScheduleState::Failed, Some(e.to_string()))
+                }
+            };
+
+            // 結果を記録できなくても残りの予約は処理する（未記録の予約はリース切れ後に再取得される）
+            if let Err(e) = self
+                .repository
+                .complete_schedule(&schedule.id, state, failure_reason)
+                .await
+            {
+                Metrics::record_error("product", "complete_schedule");
+                error!("Failed to record result of schedule {}: {}", schedule.id, e);
+            }
+        }
+
+        Ok(schedules.len())
+    }
+
+    async fn transition_status(
+        &self,
+        id: &str,
+        status: ProductStatus,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let mut product = self
+            .repository
+            .find_by_id(id)
+            .await
+            .ok_or(ProductError::ProductNotFound)?;
+
+        let previous_status = product.status.clone();
+        if previous_status == status {
+            return Ok(());
+        }
+
+        product.update_status(status.clone())?;
+        self.ensure_activation_ready(&product, &previous_status, false)
+            .await?;
+
+        self.repository
+            .change_status(id, previous_status, status, ctx)
+            .await?;
+        self.notify(ChangeKind::Updated, id, &["status"]).await;
+        Ok(())
+    }
+
+    // Active への遷移時のみ、価格・画像・カテゴリが揃っていることを確認する
+    async fn ensure_activation_ready(
+        &self,
+        product: &Product,
+        previous_status: &ProductStatus,
+        price_provided: bool,
+    ) -> Result<(), ProductError> {
+        if product.status != ProductStatus::Active || previous_status == &ProductStatus::Active {
+            return Ok(());
+        }
+
+        let has_price = price_provided
+            || self
+                .repository
+                .get_current_price(&product.id)
+                .await
+                .is_some();
+        let image_count = self.repository.get_images(&product.id).await.len();
+
+        product.validate_activation(has_price, image_count)
+    }
+
+    pub async fn get_history(
+        &self,
+        id: &str,
+        query: ProductHistoryQuery,
+    ) -> Result<ProductHistoryResponse, ProductError> {
+        // Verify product exists
+        if self.repository.find_by_id(id).await.is_none() {
+            Metrics::record_error("product", "get_history");
+            return Err(ProductError::ProductNotFound);
+        }
+
+        let history_items = self
+            .repository
+            .get_history(id, query.field.as_deref(), query.limit, query.offset)
+            .await;
+
+        let total = history_items.len() as i64;
+        let has_more = query.limit.is_some_and(|limit| total >= limit);
+
+        let history_response = history_items.into_iter().map(Into::into).collect();
+
+        Metrics::record_success("product", "get_history");
+        info!("Fetched {} history items for product {}", total, id);
+
+        Ok(ProductHistoryResponse {
+            history: history_response,
+            total,
+            has_more,
+        })
+    }
+
+    /// ロールバックした場合の差分を返す（変更は行わない）
+    pub async fn preview_rollback(
+        &self,
+        id: &str,
+        request: RollbackRequest,
+    ) -> Result<RollbackPreviewResponse, ProductError> {
+        let plan = self.plan_rollback(id, &request).await?;
+
+        Metrics::record_success("product", "preview_rollback");
+
+        Ok(RollbackPreviewResponse {
+            product_id: id.to_string(),
+            changes: plan.changes.into_iter().map(Into::into).collect(),
+            conflicts: plan.conflicts,
+        })
+    }
+
+    /// 指定時点の状態を新しい変更として適用する（履歴は書き換えない）
+    pub async fn rollback(
+        &self,
+        id: &str,
+        request: RollbackRequest,
+        ctx: &ChangeContext,
+    ) -> Result<ProductResponse, ProductError> {
+        let plan = self.plan_rollback(id, &request).await?;
+
+        // 当時の SKU が既に他の商品・バリアントで使われている場合は戻せない
+        if plan.conflicts.iter().any(|field| field == "sku") {
+            Metrics::record_error("product", "rollback");
+            return Err(ProductError::SkuAlreadyExists);
+        }
+
+        let ctx = ChangeContext {
+            changed_by: ctx.changed_by.clone(),
+            reason: ctx.reason.clone().or(Some(plan.label)),
+        };
+
+        // 計画を立てた時点から商品が変更されていれば、途中まで戻すことなく全体を失敗させる
+        let revision = ProductRevision {
+            product: plan.product,
+            price: plan.price,
+            tags: plan.tags,
+            attributes: plan.attributes,
+        };
+        self.repository
+            .apply_revision(id, plan.version, revision, &ctx)
+            .await
+            .inspect_err(|_| Metrics::record_error("product", "rollback"))?;
+
+        Metrics::record_success("product", "rollback");
+        self.notify(ChangeKind::Updated, id, &[]).await;
+        info!(
+            "Rolled back product {} ({} fields changed)",
+            id,
+            plan.changes.len()
+        );
+
+        self.find_by_id(id).await
+    }
+
+    // 対象時点より後の履歴を打ち消して当時の状態を組み立てる
+    //
+    // ステータス・在庫・画像はロールバックの対象外。
+    async fn plan_rollback(
+        &self,
+        id: &str,
+        request: &RollbackRequest,
+    ) -> Result<RollbackPlan, ProductError> {
+        let current = self
+            .repository
+            .find_by_id(id)
+            .await
+            .ok_or(ProductError::ProductNotFound)?;
+
+        let history = self.repository.get_history(id, None, None, None).await;
+
+        let (label, mut undone): (String, Vec<_>) = match (request.history_id, request.at) {
+            (Some(history_id), None) => {
+                if !history.iter().any(|entry| entry.id == history_id) {
+                    return Err(ProductError::HistoryNotFound);
+                }
+                (
+                    format!("rollback to history {}", history_id),
+                    history
+                        .into_iter()
+                        .filter(|entry| entry.id > history_id)
+                        .collect(),
+                )
+            }
+            (None, Some(at)) => {
+                if at < current.created_at {
+                    return Err(ProductError::InvalidRollbackTarget(
+                        "product did not exist at that point".to_string(),
+                    ));
+                }
+                (
+                    format!("rollback to {}", at.to_rfc3339()),
+                    history
+                        .into_iter()
+                        .filter(|entry| entry.changed_at > at)
+                        .collect(),
+                )
+            }
+            _ => {
+                return Err(ProductError::InvalidRollbackTarget(
+                    "specify either history_id or at".to_string(),
+                ))
+            }
+        };
+        undone.sort_by_key(|entry| entry.id);
+
+        // 各フィールドの当時の値は、対象時点より後の最初の変更の変更前の値
+        let mut restored: BTreeMap<String, Option<String>> = BTreeMap::new();
+        for entry in undone {
+            restored.entry(entry.field_name).or_insert(entry.old_value);
+        }
+
+        let mut product = current.clone();
+        let current_price = self.repository.get_current_price(id).await;
+        let mut price = current_price.clone();
+        let current_tags = self.repository.get_tags(id).await;
+        let mut tags = current_tags.clone();
+        let current_attributes = self.repository.get_attributes(id).await;
+        let mut attributes = current_attributes.clone();
+
+        // 当時価格が未設定だった場合、価格は現在のまま残す
+        let price_existed = restored
+            .get("price.selling_price")
+            .is_none_or(|value| value.is_some());
+
+        for (field_name, value) in &restored {
+            let value = value.as_deref();
+
+            if product.restore_field(field_name, value)? {
+                continue;
+            }
+
+            if field_name == "tags" {
+                tags = value
+                    .map(|value| value.split(',').map(str::to_string).collect())
+                    .unwrap_or_default();
+            } else if let Some(name) = field_name.strip_prefix("attributes.") {
+                match value {
+                    Some(value) => attributes.insert(name.to_string(), value.to_string()),
+                    None => attributes.remove(name),
+                };
+            } else if price_existed {
+                if let Some(ref mut price) = price {
+                    price.restore_field(field_name, value)?;
+                }
+            }
+        }
+
+        let product_changes = Product::field_changes(Some(&current), &product);
+        let price_changes = match (current_price.as_ref(), price.as_ref()) {
+            (Some(current_price), Some(price)) => Price::field_changes(Some(current_price), price),
+            _ => vec![],
+        };
+        let tag_change = FieldChange::tags(&current_tags, &tags);
+        let attribute_changes = FieldChange::attributes(&current_attributes, &attributes);
+
+        let mut conflicts = Vec::new();
+        if product.sku != current.sku && self.repository.exists_by_sku(&product.sku, Some(id)).await
+        {
+            conflicts.push("sku".to_string());
+        }
+
+        let mut changes = product_changes.clone();
+        changes.extend(price_changes.iter().cloned());
+        changes.extend(tag_change.clone());
+        changes.extend(attribute_changes.iter().cloned());
+
+        Ok(RollbackPlan {
+            label,
+            version: current.version,
+            product: (!product_changes.is_empty()).then_some(product),
+            price: price.filter(|_| !price_changes.is_empty()),
+            tags: tag_change.map(|_| tags),
+            attributes: (!attribute_changes.is_empty()).then_some(attributes),
+            changes,
+            conflicts,
+        })
+    }
+
+    pub async fn batch_update(
+        &self,
+        request: BatchUpdateRequest,
+        ctx: &ChangeContext,
+    ) -> Result<BatchUpdateResponse, ProductError> {
+        let mut results = Vec::new();
+        let mut success_count = 0;
+        let mut error_count = 0;
+
+        for update_item in request.updates {
+            let result = match self.apply_batch_update(&update_item, ctx).await {
+                Ok(product) => {
+                    success_count += 1;
+                    self.notify(ChangeKind::Updated, &update_item.id, &[]).await;
+                    BatchUpdateResult {
+                        id: update_item.id.clone(),
+                        success: true,
+                        product: Some(product),
+                        error: None,
+                    }
+                }
+                Err(error) => {
+                    error_count += 1;
+                    BatchUpdateResult {
+                        id: update_item.id.clone(),
+                        success: false,
+                        product: None,
+                        error: Some(error.to_string()),
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        Metrics::record_success("product", "batch_update");
+        info!(
+            "Batch update completed: {} success, {} errors",
+            success_count, error_count
+        );
+
+        Ok(BatchUpdateResponse {
+            results,
+            success_count,
+            error_count,
+        })
+    }
+
+    async fn apply_batch_update(
+        &self,
+        update_item: &crate::application::dto::product_dto::BatchUpdateItem,
+        ctx: &ChangeContext,
+    ) -> Result<ProductResponse, ProductError> {
+        let mut product = self
+            .repository
+            .find_by_id(&update_item.id)
+            .await
+            .ok_or(ProductError::ProductNotFound)?;
+        product.check_version(update_item.expected_version)?;
+
+        // Update name if provided
+        if let Some(ref name) = update_item.name {
+            product.update_name(name.clone())?;
+        }
+
+        // Update status if provided
+        if let Some(ref status) = update_item.status {
+            let previous_status = product.status.clone();
+            product.update_status(status.clone())?;
+            self.ensure_activation_ready(&product, &previous_status, false)
+                .await?;
+        }
+
+        // Update the product
+        self.repository.update(product, ctx).await?;
+
+        // Update price if provided
+        if let Some(ref price_patch) = update_item.price {
+            if let Some(current_price) = self.repository.get_current_price(&update_item.id).await {
+                let mut new_price = current_price;
+
+                if let Some(selling_price) = price_patch.selling_price {
+                    new_price.selling_price = selling_price;
+                }
+
+                if let Some(list_price) = price_patch.list_price {
+                    new_price.list_price = Some(list_price);
+                }
+
+                if let Some(discount_price) = price_patch.discount_price {
+                    new_price.discount_price = Some(discount_price);
+                }
+
+                new_price.validate()?;
+                self.repository
+                    .update_price(&update_item.id, new_price, ctx)
+                    .await?;
+            }
+        }
+
+        // Update inventory if provided
+        if let Some(ref inventory_patch) = update_item.inventory {
+            if let Some(mut current_inventory) =
+                self.repository.get_inventory(&update_item.id).await
+            {
+                if let Some(quantity) = inventory_patch.quantity {
+                    current_inventory.update_quantity(quantity)?;
+                }
+
+                if let Some(reserved_quantity) = inventory_patch.reserved_quantity {
+                    current_inventory.reserved_quantity = reserved_quantity;
+                }
+
+                if let Some(alert_threshold) = inventory_patch.alert_threshold {
+                    current_inventory.alert_threshold = Some(alert_threshold);
+                }
+
+                if let Some(track_inventory) = inventory_patch.track_inventory {
+                    current_inventory.track_inventory = track_inventory;
+                }
 
                 if let Some(allow_backorder) = inventory_patch.allow_backorder {
                     current_inventory.allow_backorder = allow_backorder;
@@ -691,7 +2006,7 @@ impl ProductService {
 
                 current_inventory.validate()?;
                 self.repository
-                    .update_inventory(&update_item.id, current_inventory)
+                    .update_inventory(&update_item.id, current_inventory, ctx)
                     .await?;
             }
         }
@@ -699,6 +2014,209 @@ impl ProductService {
         self.find_by_id(&update_item.id).await
     }
 
+    /// 一括更新の対象件数と、先頭からのサンプルに適用される変更を返す
+    pub async fn preview_bulk_update(
+        &self,
+        request: BulkUpdateRequest,
+        sample_size: Option<usize>,
+    ) -> Result<BulkUpdatePreviewResponse, ProductError> {
+        request.patch.validate()?;
+        let mut filter = ProductFilter::try_from(request.filter)?;
+        filter.attributes = self
+            .resolve_attribute_filters(filter.category_id.as_deref(), filter.attributes)
+            .await?;
+
+        let matched_count = self.repository.count_by_filter(&filter).await?;
+        let sample_size = sample_size
+            .unwrap_or(BULK_PREVIEW_SAMPLE_SIZE)
+            .min(BULK_PREVIEW_MAX_SAMPLE_SIZE);
+        let products = self
+            .repository
+            .find_by_filter(&filter, None, sample_size as i64)
+            .await?;
+
+        let mut sample = Vec::new();
+        for product in products {
+            let (changes, error) = match self.plan_bulk_change(&request.patch, &product).await {
+                Ok(change) => (change.changes.into_iter().map(Into::into).collect(), None),
+                Err(e) => (vec![], Some(e.to_string())),
+            };
+            sample.push(BulkUpdateSample {
+                id: product.id,
+                sku: product.sku,
+                name: product.name,
+                changes,
+                error,
+            });
+        }
+
+        Metrics::record_success("product", "bulk_update_preview");
+        info!("Bulk update preview matched {} products", matched_count);
+
+        Ok(BulkUpdatePreviewResponse {
+            matched_count,
+            sample,
+        })
+    }
+
+    /// 絞り込み条件に一致する商品に変更を適用する
+    ///
+    /// 商品 ID 順に `BULK_UPDATE_BATCH_SIZE` 件ずつ処理し、適用できない商品は
+    /// スキップして失敗として返す。履歴の変更理由には一括更新の ID を記録する。
+    pub async fn bulk_update(
+        &self,
+        request: BulkUpdateRequest,
+        ctx: &ChangeContext,
+    ) -> Result<BulkUpdateResponse, ProductError> {
+        request.patch.validate()?;
+        let mut filter = ProductFilter::try_from(request.filter)?;
+        filter.attributes = self
+            .resolve_attribute_filters(filter.category_id.as_deref(), filter.attributes)
+            .await?;
+
+        // 対象の商品とその時点のバージョンを先に確定し、件数の確認と適用を同じ対象に対して行う
+        let mut targets = Vec::new();
+        let mut after_id: Option<String> = None;
+        loop {
+            let page = self
+                .repository
+                .find_by_filter(&filter, after_id.as_deref(), BULK_UPDATE_BATCH_SIZE)
+                .await?;
+            targets.extend(
+                page.iter()
+                    .map(|product| (product.id.clone(), product.sku.clone(), product.version)),
+            );
+            if (page.len() as i64) < BULK_UPDATE_BATCH_SIZE {
+                break;
+            }
+            after_id = page.last().map(|product| product.id.clone());
+        }
+
+        let matched_count = targets.len() as i64;
+        if let Some(expected) = request.expected_count {
+            if expected != matched_count {
+                Metrics::record_error("product", "bulk_update");
+                return Err(ProductError::BulkCountMismatch {
+                    expected,
+                    actual: matched_count,
+                });
+            }
+        }
+
+        let bulk_id = Uuid::new_v4().to_string();
+        let ctx = ChangeContext {
+            changed_by: ctx.changed_by.clone(),
+            reason: Some(match &ctx.reason {
+                Some(reason) => format!("{} (bulk update {})", reason, bulk_id),
+                None => format!("bulk update {}", bulk_id),
+            }),
+        };
+
+        let mut updated_count = 0;
+        let mut unchanged_count = 0;
+        let mut conflicts = Vec::new();
+        let mut failures = Vec::new();
+
+        for (id, sku, version) in targets {
+            match self
+                .apply_bulk_change(&request.patch, &id, version, &ctx)
+                .await
+            {
+                Ok(true) => {
+                    updated_count += 1;
+                    self.notify(ChangeKind::Updated, &id, &[]).await;
+                }
+                Ok(false) => unchanged_count += 1,
+                // 対象を確定した後に他の操作で変更・削除された商品は上書きしない
+                Err(e @ (ProductError::VersionMismatch { .. } | ProductError::ProductNotFound)) => {
+                    conflicts.push(BulkUpdateFailure {
+                        id,
+                        sku,
+                        error: e.to_string(),
+                    })
+                }
+                Err(e) => failures.push(BulkUpdateFailure {
+                    id,
+                    sku,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        Metrics::record_success("product", "bulk_update");
+        info!(
+            "Bulk update {} completed: {} matched, {} updated, {} unchanged, {} conflicted, {} failed",
+            bulk_id,
+            matched_count,
+            updated_count,
+            unchanged_count,
+            conflicts.len(),
+            failures.len()
+        );
+
+        Ok(BulkUpdateResponse {
+            bulk_id,
+            matched_count,
+            updated_count,
+            unchanged_count,
+            conflicted_count: conflicts.len() as i64,
+            conflicts,
+            failed_count: failures.len() as i64,
+            failures,
+        })
+    }
+
+    async fn plan_bulk_change(
+        &self,
+        patch: &ProductBulkPatch,
+        product: &Product,
+    ) -> Result<BulkChange, ProductError> {
+        let price = self.repository.get_current_price(&product.id).await;
+        let change = patch.apply(product, price.as_ref())?;
+        if let Some(updated) = &change.product {
+            self.ensure_activation_ready(updated, &product.status, false)
+                .await?;
+            if updated.category_id != product.category_id {
+                self.ensure_attributes_fit(updated).await?;
+            }
+        }
+        Ok(change)
+    }
+
+    /// 1商品に一括更新を適用し、変更があったかどうかを返す
+    ///
+    /// 商品本体と価格は1トランザクションで書き込み、対象を確定したときの `version` から
+    /// 変更されていれば `VersionMismatch` を返す。
+    async fn apply_bulk_change(
+        &self,
+        patch: &ProductBulkPatch,
+        id: &str,
+        version: i64,
+        ctx: &ChangeContext,
+    ) -> Result<bool, ProductError> {
+        let product = self
+            .repository
+            .find_by_id(id)
+            .await
+            .ok_or(ProductError::ProductNotFound)?;
+        product.check_version(Some(version))?;
+
+        let change = self.plan_bulk_change(patch, &product).await?;
+        if change.is_empty() {
+            return Ok(false);
+        }
+
+        let revision = ProductRevision {
+            product: change.product,
+            price: change.price,
+            ..Default::default()
+        };
+        self.repository
+            .apply_revision(id, version, revision, ctx)
+            .await?;
+        Ok(true)
+    }
+
     pub async fn find_low_stock_products(
         &self,
         threshold: Option<i32>,
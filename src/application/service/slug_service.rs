@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use tracing::info;
+
+use crate::app_domain::model::category::Category;
+use crate::app_domain::model::product::ProductError;
+use crate::app_domain::repository::category_repository::CategoryRepository;
+use crate::application::dto::category_dto::{CategoryPathItem, CategoryResponse};
+use crate::application::dto::slug_dto::{SlugResolutionResponse, SlugResourceType};
+use crate::application::service::product_service::ProductService;
+use crate::infrastructure::error::{AppError, AppResult};
+use crate::infrastructure::metrics::Metrics;
+
+/// スラッグのパス（`/audio/headphones/wh-1000` など）をカテゴリまたは商品に解決するサービス
+///
+/// 各階層は現在のスラッグを優先し、見つからなければ変更・移動・統合前のスラッグで探す。
+/// 最後の階層がカテゴリでなければ商品のスラッグとして扱う。指定されたパスが正規の
+/// パスと異なる場合（旧スラッグ、商品のカテゴリ変更など）は転送が必要であることを返す。
+pub struct SlugService {
+    category_repository: Arc<dyn CategoryRepository>,
+    product_service: Arc<ProductService>,
+}
+
+impl SlugService {
+    pub fn new(
+        category_repository: Arc<dyn CategoryRepository>,
+        product_service: Arc<ProductService>,
+    ) -> Self {
+        Self {
+            category_repository,
+            product_service,
+        }
+    }
+
+    pub async fn resolve(&self, path: &str) -> AppResult<SlugResolutionResponse> {
+        Metrics::with_metrics("slug", "resolve", async {
+            let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+            let Some((last, parents)) = segments.split_last() else {
+                return Err(AppError::BadRequest("path は必須です".to_string()));
+            };
+            let not_found =
+                || AppError::NotFound(format!("パス「{}」に一致するページが見つかりません", path));
+
+            let mut parent: Option<Category> = None;
+            let mut merged_from: Option<String> = None;
+            for segment in parents {
+                let (category, from) = self
+                    .find_category(parent.as_ref(), merged_from.as_deref(), segment)
+                    .await
+                    .ok_or_else(not_found)?;
+                parent = Some(category);
+                merged_from = from;
+            }
+
+            let resolution = match self
+                .find_category(parent.as_ref(), merged_from.as_deref(), last)
+                .await
+            {
+                Some((category, _)) => self.category_resolution(category).await?,
+                None => {
+                    let product = match self.product_service.find_by_slug(last).await {
+                        Ok(product) => product,
+                        Err(ProductError::ProductNotFound) => return Err(not_found()),
+                        Err(e) => return Err(AppError::InternalServerError(e.to_string())),
+                    };
+                    let breadcrumb = match &product.category_id {
+                        Some(category_id) => self.breadcrumb(category_id).await?,
+                        None => Vec::new(),
+                    };
+                    let canonical_path = join_path(
+                        breadcrumb
+                            .iter()
+                            .map(|item| item.slug.as_str())
+                            .chain([product.slug.as_str()]),
+                    );
+                    SlugResolutionResponse {
+                        resource_type: SlugResourceType::Product,
+                        canonical_path,
+                        redirect: false,
+                        breadcrumb,
+                        category: None,
+                        product: Some(product),
+                    }
+                }
+            };
+
+            let redirect = resolution.canonical_path != join_path(segments.iter().copied());
+            info!(
+                "Resolved slug path {} to {} (redirect: {})",
+                path, resolution.canonical_path, redirect
+            );
+            Ok(SlugResolutionResponse {
+                redirect,
+                ..resolution
+            })
+        })
+        .await
+    }
+
+    /// 親の下（`None` ならルート）のカテゴリを現在のスラッグ、次に旧スラッグで探す
+    ///
+    /// 親を統合元の旧スラッグでたどった場合（`merged_from` あり）は、統合時に連番を付けた
+    /// 統合元の子が統合先の同じスラッグの子に隠れないよう、統合元の下の旧スラッグを先に探す。
+    /// 見つかったカテゴリと、統合元の旧スラッグだった場合はその統合元 ID を返す。
+    async fn find_category(
+        &self,
+        parent: Option<&Category>,
+        merged_from: Option<&str>,
+        slug: &str,
+    ) -> Option<(Category, Option<String>)> {
+        if let Some(merged_from) = merged_from {
+            if let Some(redirect) = self
+                .category_repository
+                .find_by_old_slug(Some(merged_from.to_string()), slug)
+                .await
+            {
+                return Some((redirect.category, redirect.merged_from));
+            }
+        }
+
+        let parent_id = parent.map(|category| category.id.clone());
+        match self
+            .category_repository
+            .find_by_slug(parent_id.clone(), slug)
+            .await
+        {
+            Some(category) => Some((category, None)),
+            None => self
+                .category_repository
+                .find_by_old_slug(parent_id, slug)
+                .await
+                .map(|redirect| (redirect.category, redirect.merged_from)),
+        }
+    }
+
+    async fn category_resolution(&self, category: Category) -> AppResult<SlugResolutionResponse> {
+        let breadcrumb = self.breadcrumb(&category.id).await?;
+        Ok(SlugResolutionResponse {
+            resource_type: SlugResourceType::Category,
+            canonical_path: join_path(breadcrumb.iter().map(|item| item.slug.as_str())),
+            redirect: false,
+            breadcrumb,
+            category: Some(CategoryResponse::from(category)),
+            product: None,
+        })
+    }
+
+    async fn breadcrumb(&self, category_id: &str) -> AppResult<Vec<CategoryPathItem>> {
+        let ancestors = self
+            .category_repository
+            .find_ancestors(category_id)
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        Ok(ancestors
+            .into_iter()
+            .map(|category| CategoryPathItem {
+                id: category.id,
+                name: category.name,
+                slug: category.slug,
+            })
+            .collect())
+    }
+}
+
+fn join_path<'a>(segments: impl Iterator<Item = &'a str>) -> String {
+    segments.fold(String::new(), |path, segment| path + "/" + segment)
+}
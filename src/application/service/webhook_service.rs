@@ -0,0 +1,537 @@
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::app_domain::model::domain_event::{DomainEventType, OutboxEvent};
+use crate::app_domain::model::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::app_domain::repository::webhook_repository::WebhookRepository;
+use crate::application::dto::webhook_dto::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveryResponse,
+    WebhookSubscriptionResponse,
+};
+use crate::application::service::outbox_dispatcher::EventSink;
+use crate::infrastructure::error::{AppError, AppResult};
+use crate::infrastructure::metrics::Metrics;
+use crate::infrastructure::webhook::{WebhookClient, WebhookRequest};
+
+/// 配信記録の取得件数の上限
+const MAX_DELIVERY_LIMIT: i64 = 500;
+/// 1 回の再試行で確保する配信記録の件数
+const RETRY_BATCH_SIZE: i64 = 100;
+/// 確保した配信記録を他の再試行の対象から外しておく秒数
+const RETRY_LEASE_SECONDS: i64 = 300;
+
+/// Webhook 購読の管理と、購読ごとのイベント配信を行うサービス
+///
+/// outbox ディスパッチャーのシンクとして登録され、有効な購読のうちイベント種別が
+/// 一致するものに署名付きで POST する。配信に失敗した購読は配信記録に再試行の日時を
+/// 残して購読ごとに再試行するため、失敗した購読が outbox の後続イベントを止めることはない。
+/// 同じ集約のイベントは購読ごとに outbox の順序で届け、先に失敗した配信が残っている間は
+/// 後続のイベントを送らずに記録だけ残し、先行する配信が成功してから再試行で送る。
+pub struct WebhookService {
+    repository: Arc<dyn WebhookRepository>,
+    client: WebhookClient,
+    max_failures: i32,
+    max_backoff_seconds: i64,
+}
+
+impl WebhookService {
+    pub fn new(
+        repository: Arc<dyn WebhookRepository>,
+        client: WebhookClient,
+        max_failures: i32,
+        max_backoff_seconds: i64,
+    ) -> Self {
+        Self {
+            repository,
+            client,
+            max_failures,
+            max_backoff_seconds,
+        }
+    }
+
+    pub async fn find_all(&self) -> AppResult<Vec<WebhookSubscriptionResponse>> {
+        Metrics::with_metrics("webhook", "find_all", async {
+            let subscriptions = self.repository.find_all().await?;
+            Ok(subscriptions.into_iter().map(Into::into).collect())
+        })
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> AppResult<WebhookSubscriptionResponse> {
+        Metrics::with_metrics("webhook", "find_by_id", async {
+            Ok(self.subscription(id).await?.into())
+        })
+        .await
+    }
+
+    /// 購読を登録する（作成時のレスポンスにのみシークレットを含める）
+    pub async fn create(
+        &self,
+        req: CreateWebhookRequest,
+    ) -> AppResult<WebhookSubscriptionResponse> {
+        Metrics::with_metrics("webhook", "create", async {
+            let secret = req
+                .secret
+                .unwrap_or_else(|| format!("whsec_{}", Uuid::new_v4().simple()));
+            let mut subscription =
+                WebhookSubscription::new(req.url, req.event_types.unwrap_or_default(), secret);
+            subscription.is_active = req.is_active.unwrap_or(true);
+            subscription.validate().map_err(AppError::ValidationError)?;
+            self.client
+                .check_target(&subscription.url)
+                .await
+                .map_err(AppError::ValidationError)?;
+
+            let created = self.repository.create(subscription).await?;
+            info!("Created webhook subscription {}", created.id);
+
+            let secret = created.secret.clone();
+            let mut response = WebhookSubscriptionResponse::from(created);
+            response.secret = Some(secret);
+            Ok(response)
+        })
+        .await
+    }
+
+    pub async fn update(
+        &self,
+        id: &str,
+        req: UpdateWebhookRequest,
+    ) -> AppResult<WebhookSubscriptionResponse> {
+        Metrics::with_metrics("webhook", "update", async {
+            let mut subscription = self.subscription(id).await?;
+            if let Some(url) = req.url {
+                subscription.url = url;
+            }
+            if let Some(event_types) = req.event_types {
+                subscription.event_types = event_types;
+            }
+            if let Some(secret) = req.secret {
+                subscription.secret = secret;
+            }
+            if let Some(is_active) = req.is_active {
+                subscription.set_active(is_active);
+            }
+            subscription.validate().map_err(AppError::ValidationError)?;
+            self.client
+                .check_target(&subscription.url)
+                .await
+                .map_err(AppError::ValidationError)?;
+
+            let updated = self.repository.update(subscription).await?;
+            info!("Updated webhook subscription {}", id);
+            Ok(updated.into())
+        })
+        .await
+    }
+
+    pub async fn delete(&self, id: &str) -> AppResult<()> {
+        Metrics::with_metrics("webhook", "delete", async {
+            if !self.repository.delete(id).await? {
+                return Err(AppError::not_found("WebhookSubscription", id));
+            }
+            info!("Deleted webhook subscription {}", id);
+            Ok(())
+        })
+        .await
+    }
+
+    /// 配信記録を新しい順に取得する
+    pub async fn find_deliveries(
+        &self,
+        id: &str,
+        limit: Option<i64>,
+    ) -> AppResult<Vec<WebhookDeliveryResponse>> {
+        Metrics::with_metrics("webhook", "find_deliveries", async {
+            self.subscription(id).await?;
+            let limit = limit.unwrap_or(50).clamp(1, MAX_DELIVERY_LIMIT);
+            let deliveries = self.repository.find_deliveries(id, limit).await?;
+            Ok(deliveries.into_iter().map(Into::into).collect())
+        })
+        .await
+    }
+
+    /// 記録されたリクエストボディを再送する（無効化された購読にも送る）
+    pub async fn redeliver(
+        &self,
+        id: &str,
+        delivery_id: i64,
+    ) -> AppResult<WebhookDeliveryResponse> {
+        Metrics::with_metrics("webhook", "redeliver", async {
+            let subscription = self.subscription(id).await?;
+            let delivery = self
+                .repository
+                .find_delivery_by_id(delivery_id)
+                .await?
+                .filter(|delivery| delivery.subscription_id == subscription.id)
+                .ok_or_else(|| AppError::not_found("WebhookDelivery", delivery_id))?;
+
+            let delivery = self.deliver(&subscription, &delivery).await?;
+            info!(
+                "Redelivered event {} to webhook {} (succeeded: {})",
+                delivery.event_id, id, delivery.succeeded
+            );
+            Ok(delivery.into())
+        })
+        .await
+    }
+
+    /// 再試行の時刻を過ぎた失敗済みの配信を再送し、配信できた件数を返す
+    pub async fn retry_due(&self) -> usize {
+        let deliveries = match self
+            .repository
+            .claim_due_deliveries(Utc::now(), RETRY_BATCH_SIZE, RETRY_LEASE_SECONDS)
+            .await
+        {
+            Ok(deliveries) => deliveries,
+            Err(e) => {
+                error!("Failed to claim webhook deliveries: {}", e);
+                return 0;
+            }
+        };
+
+        let mut delivered = 0;
+        for delivery in deliveries {
+            let result = match self.subscription(&delivery.subscription_id).await {
+                Ok(subscription) => self.deliver(&subscription, &delivery).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(retried) if retried.succeeded => delivered += 1,
+                Ok(retried) => warn!(
+                    "Retry of event {} to webhook {} failed (attempt {}): {}",
+                    retried.event_id,
+                    retried.subscription_id,
+                    retried.attempts,
+                    retried.last_error.unwrap_or_default()
+                ),
+                Err(e) => error!("Failed to retry webhook delivery {}: {}", delivery.id, e),
+            }
+        }
+        delivered
+    }
+
+    /// 一定間隔で失敗した配信を再試行するタスクを起動する
+    pub fn spawn_retry(self: Arc<Self>, interval_seconds: u64) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                let delivered = self.retry_due().await;
+                if delivered > 0 {
+                    info!("Redelivered {} failed webhook deliveries", delivered);
+                }
+            }
+        })
+    }
+
+    async fn subscription(&self, id: &str) -> AppResult<WebhookSubscription> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::not_found("WebhookSubscription", id))
+    }
+
+    /// 配信記録の内容を再送する
+    async fn deliver(
+        &self,
+        subscription: &WebhookSubscription,
+        delivery: &WebhookDelivery,
+    ) -> AppResult<WebhookDelivery> {
+        self.send(
+            subscription,
+            delivery.event_id,
+            delivery.event_type,
+            &delivery.payload,
+            delivery.attempts,
+        )
+        .await
+    }
+
+    /// 送信結果を記録する（失敗した場合は試行回数に応じた再試行の日時も記録する）
+    async fn send(
+        &self,
+        subscription: &WebhookSubscription,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &serde_json::Value,
+        previous_attempts: i32,
+    ) -> AppResult<WebhookDelivery> {
+        let attempt = self
+            .client
+            .send(WebhookRequest {
+                url: &subscription.url,
+                secret: Some(&subscription.secret),
+                event_type: event_type.as_str(),
+                event_id,
+                body: payload,
+            })
+            .await;
+
+        let retry_at = Utc::now() + self.backoff(previous_attempts + 1);
+        let delivery = self
+            .repository
+            .record_attempt(
+                &subscription.id,
+                event_id,
+                event_type,
+                payload,
+                &attempt,
+                self.max_failures,
+                Some(retry_at),
+            )
+            .await?;
+        if !attempt.succeeded()
+            && subscription.is_active
+            && subscription.consecutive_failures + 1 >= self.max_failures
+        {
+            warn!(
+                "Webhook subscription {} disabled after {} consecutive failures",
+                subscription.id, self.max_failures
+            );
+        }
+        Ok(delivery)
+    }
+
+    /// 試行回数に応じた指数バックオフ（上限あり）
+    fn backoff(&self, attempts: i32) -> ChronoDuration {
+        let exponent = attempts.clamp(1, 16) as u32 - 1;
+        let seconds = 2i64.saturating_pow(exponent).min(self.max_backoff_seconds);
+        ChronoDuration::seconds(seconds)
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookService {
+    fn name(&self) -> &str {
+        "subscriptions"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        let event_type = event.event.event_type;
+        let subscriptions = self
+            .repository
+            .find_active()
+            .await
+            .map_err(|e| e.to_string())?;
+        let payload = serde_json::to_value(event).map_err(|e| e.to_string())?;
+
+        for subscription in subscriptions.iter().filter(|s| s.accepts(event_type)) {
+            // outbox からの再配信では、配信済みまたは再試行待ちの購読を飛ばす
+            let recorded = self
+                .repository
+                .find_delivery(&subscription.id, event.id)
+                .await
+                .map_err(|e| e.to_string())?
+                .is_some();
+            if recorded {
+                continue;
+            }
+
+            // 同じ集約の先行するイベントが再試行待ちなら、追い越さないよう順番を待つ
+            let aggregate_type = event.event.aggregate_type.as_str();
+            let waiting = self
+                .repository
+                .has_pending_delivery(
+                    &subscription.id,
+                    aggregate_type,
+                    &event.event.aggregate_id,
+                    event.id,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            if waiting {
+                self.repository
+                    .queue_delivery(&subscription.id, event.id, event_type, &payload)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                info!(
+                    "Queued event {} to webhook {} behind an earlier delivery for {} {}",
+                    event.id, subscription.id, aggregate_type, event.event.aggregate_id
+                );
+                continue;
+            }
+
+            // 配信の失敗は再試行の日時とともに記録されるので、イベントは配信済みとして進める
+            let delivery = self
+                .send(subscription, event.id, event_type, &payload, 0)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(error) = delivery.last_error {
+                warn!(
+                    "Failed to deliver event {} to webhook {} (retry scheduled): {}",
+                    event.id, subscription.id, error
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::domain_event::DomainEvent;
+    use crate::app_domain::model::webhook::DeliveryAttempt;
+    use crate::infrastructure::repository::webhook_repository::InMemoryWebhookRepository;
+
+    fn service() -> WebhookService {
+        WebhookService::new(
+            Arc::new(InMemoryWebhookRepository::new()),
+            WebhookClient::new(Duration::from_millis(500)).allow_private_targets(true),
+            2,
+            60,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_create_validates_and_returns_secret_once() {
+        let service = service();
+
+        let error = service
+            .create(CreateWebhookRequest {
+                url: "ftp://example.com".to_string(),
+                event_types: None,
+                secret: None,
+                is_active: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::ValidationError(_)));
+
+        let created = service
+            .create(CreateWebhookRequest {
+                url: "https://example.com/hooks".to_string(),
+                event_types: Some(vec![DomainEventType::PriceChanged]),
+                secret: None,
+                is_active: None,
+            })
+            .await
+            .unwrap();
+        assert!(created.secret.unwrap().starts_with("whsec_"));
+        assert!(created.is_active);
+
+        let fetched = service.find_by_id(&created.id).await.unwrap();
+        assert!(fetched.secret.is_none());
+        assert_eq!(fetched.event_types, vec![DomainEventType::PriceChanged]);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_is_disabled() {
+        let service = service();
+        // 接続できないポート
+        let created = service
+            .create(CreateWebhookRequest {
+                url: "http://127.0.0.1:1/hooks".to_string(),
+                event_types: None,
+                secret: Some("secret".to_string()),
+                is_active: None,
+            })
+            .await
+            .unwrap();
+
+        for id in 1..=2 {
+            let event = OutboxEvent {
+                id,
+                event: DomainEvent::item_deleted(id as u64, false),
+                attempts: 1,
+            };
+            // 失敗は配信記録に残り、outbox のイベントは配信済みとして進む
+            assert!(service.publish(&event).await.is_ok());
+        }
+
+        let subscription = service.find_by_id(&created.id).await.unwrap();
+        assert!(!subscription.is_active);
+        assert!(subscription.disabled_at.is_some());
+
+        // 無効化された購読には配信しない
+        let event = OutboxEvent {
+            id: 3,
+            event: DomainEvent::item_deleted(1, false),
+            attempts: 1,
+        };
+        assert!(service.publish(&event).await.is_ok());
+
+        let deliveries = service.find_deliveries(&created.id, None).await.unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert!(deliveries
+            .iter()
+            .all(|d| d.status_code.is_none() && !d.succeeded));
+        assert!(deliveries.iter().all(|d| d.next_attempt_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_later_events_for_an_aggregate_wait_for_earlier_deliveries() {
+        let repository = Arc::new(InMemoryWebhookRepository::new());
+        let service = WebhookService::new(
+            repository.clone(),
+            WebhookClient::new(Duration::from_millis(500)).allow_private_targets(true),
+            10,
+            60,
+        );
+        let created = service
+            .create(CreateWebhookRequest {
+                url: "http://127.0.0.1:1/hooks".to_string(),
+                event_types: None,
+                secret: Some("secret".to_string()),
+                is_active: None,
+            })
+            .await
+            .unwrap();
+
+        // イベント 1 の配信に失敗した後、同じアイテムのイベント 2 は送らずに待たせる
+        for (id, item_id) in [(1, 7), (2, 7), (3, 8)] {
+            let event = OutboxEvent {
+                id,
+                event: DomainEvent::item_deleted(item_id, false),
+                attempts: 1,
+            };
+            assert!(service.publish(&event).await.is_ok());
+        }
+        let attempts = |deliveries: Vec<WebhookDelivery>| {
+            let mut attempts: Vec<(i64, i32)> = deliveries
+                .iter()
+                .map(|d| (d.event_id, d.attempts))
+                .collect();
+            attempts.sort();
+            attempts
+        };
+        let deliveries = repository.find_deliveries(&created.id, 10).await.unwrap();
+        assert_eq!(attempts(deliveries), [(1, 1), (2, 0), (3, 1)]);
+
+        // 再試行でも先行するイベント 1 が配信されるまでイベント 2 は対象にならない
+        let later = Utc::now() + ChronoDuration::seconds(5);
+        let claimed = repository.claim_due_deliveries(later, 10, 0).await.unwrap();
+        assert_eq!(attempts(claimed), [(1, 1), (3, 1)]);
+
+        let delivered = DeliveryAttempt {
+            status_code: Some(204),
+            latency_ms: 3,
+            error: None,
+        };
+        let first = repository
+            .find_delivery(&created.id, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        repository
+            .record_attempt(
+                &created.id,
+                1,
+                first.event_type,
+                &first.payload,
+                &delivered,
+                10,
+                None,
+            )
+            .await
+            .unwrap();
+        let claimed = repository.claim_due_deliveries(later, 10, 0).await.unwrap();
+        assert_eq!(attempts(claimed), [(2, 0), (3, 1)]);
+    }
+}
@@ -64,6 +64,48 @@ pub struct KeycloakClaims {
     pub email: String,
 }
 
+impl KeycloakClaims {
+    /// レルムロールまたはクライアント（account）ロールとして `role` を持つか
+    pub fn has_role(&self, role: &str) -> bool {
+        self.realm_access.roles.iter().any(|r| r == role)
+            || self.resource_access.account.roles.iter().any(|r| r == role)
+    }
+
+    /// テストで使う固定のクレーム（`admin` ロールを持つ）
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn dummy() -> Self {
+        Self {
+            exp: 9999999999,
+            iat: 0,
+            auth_time: 0,
+            jti: "dummy-jti".to_string(),
+            iss: "dummy-iss".to_string(),
+            aud: "dummy-aud".to_string(),
+            sub: "dummy-sub".to_string(),
+            typ: "Bearer".to_string(),
+            azp: "dummy-azp".to_string(),
+            session_state: "dummy-session".to_string(),
+            acr: "dummy-acr".to_string(),
+            realm_access: RealmAccess {
+                roles: vec!["user".to_string(), "admin".to_string()],
+            },
+            resource_access: ResourceAccess {
+                account: Account {
+                    roles: vec!["user".to_string()],
+                },
+            },
+            scope: "openid profile email".to_string(),
+            sid: "dummy-sid".to_string(),
+            email_verified: true,
+            name: "Test User".to_string(),
+            preferred_username: "testuser".to_string(),
+            given_name: "Test".to_string(),
+            family_name: "User".to_string(),
+            email: "test@example.com".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RealmAccess {
     pub roles: Vec<String>,
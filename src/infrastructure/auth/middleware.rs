@@ -4,14 +4,26 @@ use std::future::Future;
 use std::pin::Pin;
 
 use super::keycloak::KeycloakClaims;
+use crate::infrastructure::error::AppError;
 
-#[cfg(feature = "test-support")]
-use crate::infrastructure::auth::keycloak::{RealmAccess, ResourceAccess, Account};
+/// 管理 API（`/api/admin/*`）の呼び出しに必要なロール
+pub const ADMIN_ROLE: &str = "admin";
 
 pub struct KeycloakUser {
     pub claims: KeycloakClaims,
 }
 
+impl KeycloakUser {
+    /// レルムロールまたはクライアントロールに `role` が無ければ 403 を返す
+    pub fn require_role(&self, role: &str) -> Result<(), AppError> {
+        if self.claims.has_role(role) {
+            Ok(())
+        } else {
+            Err(AppError::forbidden(format!("{} ロールが必要です", role)))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AuthError {
     #[allow(dead_code)]
@@ -87,30 +99,28 @@ impl FromRequest for KeycloakUser {
     fn from_request(_req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         Box::pin(async move {
             Ok(KeycloakUser {
-                claims: KeycloakClaims {
-                    exp: 9999999999,
-                    iat: 0,
-                    auth_time: 0,
-                    jti: "dummy-jti".to_string(),
-                    iss: "dummy-iss".to_string(),
-                    aud: "dummy-aud".to_string(),
-                    sub: "dummy-sub".to_string(),
-                    typ: "Bearer".to_string(),
-                    azp: "dummy-azp".to_string(),
-                    session_state: "dummy-session".to_string(),
-                    acr: "dummy-acr".to_string(),
-                    realm_access: RealmAccess { roles: vec!["user".to_string()] },
-                    resource_access: ResourceAccess { account: Account { roles: vec!["user".to_string()] } },
-                    scope: "openid profile email".to_string(),
-                    sid: "dummy-sid".to_string(),
-                    email_verified: true,
-                    name: "Test User".to_string(),
-                    preferred_username: "testuser".to_string(),
-                    given_name: "Test".to_string(),
-                    family_name: "User".to_string(),
-                    email: "test@example.com".to_string(),
-                }
+                claims: KeycloakClaims::dummy(),
             })
         })
     }
 }
+
+#[cfg(all(test, feature = "test-support"))]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[actix_web::test]
+    async fn test_require_role_checks_realm_and_client_roles() {
+        let req = TestRequest::default().to_http_request();
+        let mut user = KeycloakUser::extract(&req).await.unwrap();
+        assert!(user.require_role(ADMIN_ROLE).is_ok());
+
+        user.claims.realm_access.roles = vec!["user".to_string()];
+        let error = user.require_role(ADMIN_ROLE).unwrap_err();
+        assert!(matches!(error, AppError::Forbidden(_)));
+
+        user.claims.resource_access.account.roles = vec![ADMIN_ROLE.to_string()];
+        assert!(user.require_role(ADMIN_ROLE).is_ok());
+    }
+}
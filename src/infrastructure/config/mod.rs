@@ -7,6 +7,15 @@ pub struct AppConfig {
     pub database: DatabaseConfig,
     pub server: ServerConfig,
     pub auth: AuthConfig,
+    pub scheduler: SchedulerConfig,
+    pub idempotency: IdempotencyConfig,
+    pub import: ImportConfig,
+    pub export: ExportConfig,
+    pub body_limits: BodyLimitConfig,
+    pub change_feed: ChangeFeedConfig,
+    pub outbox: OutboxConfig,
+    pub webhook: WebhookConfig,
+    pub localization: LocalizationConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -34,6 +43,73 @@ pub struct AuthConfig {
     pub keycloak_client_id: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+    pub lease_seconds: i64, // processing のまま放置された予約を再実行するまでの秒数
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdempotencyConfig {
+    pub ttl_seconds: i64,            // 冪等性キーと処理結果を保持する秒数
+    pub purge_interval_seconds: u64, // 期限切れのキーを削除する間隔
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportConfig {
+    pub chunk_size: usize, // 1トランザクションで取り込む行数
+    pub max_rows: usize,   // 1ファイルで受け付ける最大行数
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportConfig {
+    pub batch_size: i64, // 1回のクエリで読み込む商品数
+}
+
+/// ルートグループごとのリクエストボディの上限（バイト）
+#[derive(Debug, Clone, Deserialize)]
+pub struct BodyLimitConfig {
+    pub default_bytes: usize, // 下記以外の JSON ボディ
+    pub batch_bytes: usize,   // 一括更新・一括削除
+    pub import_bytes: usize,  // CSV / TSV インポート
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeFeedConfig {
+    pub history_size: usize,    // 再接続時の再開用に保持するイベント数
+    pub heartbeat_seconds: u64, // イベントがないときにハートビートを送る間隔
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutboxConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+    pub claim_timeout_seconds: i64, // 配信中のまま放置されたイベントを再配信するまでの秒数
+    pub max_backoff_seconds: i64,   // 配信失敗時の再試行間隔の上限
+    pub retention_hours: i64,       // 配信済みイベントを保持する時間
+    pub log_events: bool,           // 配信したイベントをログにも出力する
+    pub webhook_url: Option<String>,
+    pub webhook_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    pub timeout_seconds: u64,
+    pub max_consecutive_failures: i32, // この回数連続で失敗した購読を無効化する
+    pub retry_interval_seconds: u64,   // 失敗した配信の再試行を確認する間隔
+    pub max_backoff_seconds: i64,      // 失敗した配信の再試行間隔の上限
+    pub allow_private_targets: bool,   // ループバック・プライベートアドレスへの配信を許可する
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalizationConfig {
+    pub default_locale: String,         // 商品・カテゴリ本体の内容のロケール
+    pub supported_locales: Vec<String>, // 翻訳を登録・表示できるロケール（既定ロケールを含む）
+}
+
 impl AppConfig {
     /// 環境変数から設定を読み込む
     pub fn from_env() -> StartupResult<Self> {
@@ -41,6 +117,15 @@ impl AppConfig {
             database: DatabaseConfig::from_env()?,
             server: ServerConfig::from_env()?,
             auth: AuthConfig::from_env()?,
+            scheduler: SchedulerConfig::from_env()?,
+            idempotency: IdempotencyConfig::from_env()?,
+            import: ImportConfig::from_env()?,
+            export: ExportConfig::from_env()?,
+            body_limits: BodyLimitConfig::from_env()?,
+            change_feed: ChangeFeedConfig::from_env()?,
+            outbox: OutboxConfig::from_env()?,
+            webhook: WebhookConfig::from_env()?,
+            localization: LocalizationConfig::from_env(),
         })
     }
 
@@ -85,6 +170,106 @@ impl AppConfig {
             ));
         }
 
+        // スケジューラ設定の検証
+        if self.scheduler.enabled
+            && (self.scheduler.interval_seconds == 0 || self.scheduler.batch_size <= 0)
+        {
+            return Err(StartupError::Configuration(
+                "Scheduler interval and batch size must be greater than 0".to_string(),
+            ));
+        }
+
+        // 冪等性キー設定の検証
+        if self.idempotency.ttl_seconds <= 0 {
+            return Err(StartupError::Configuration(
+                "Idempotency key TTL must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.idempotency.purge_interval_seconds == 0 {
+            return Err(StartupError::Configuration(
+                "Idempotency key purge interval must be greater than 0".to_string(),
+            ));
+        }
+
+        // インポート設定の検証
+        if self.import.chunk_size == 0 || self.import.max_rows == 0 {
+            return Err(StartupError::Configuration(
+                "Import chunk size and max rows must be greater than 0".to_string(),
+            ));
+        }
+
+        // エクスポート設定の検証
+        if self.export.batch_size <= 0 {
+            return Err(StartupError::Configuration(
+                "Export batch size must be greater than 0".to_string(),
+            ));
+        }
+
+        // リクエストボディ上限の検証
+        if self.body_limits.default_bytes == 0
+            || self.body_limits.batch_bytes == 0
+            || self.body_limits.import_bytes == 0
+        {
+            return Err(StartupError::Configuration(
+                "Body size limits must be greater than 0".to_string(),
+            ));
+        }
+
+        // 変更フィード設定の検証
+        if self.change_feed.history_size == 0 || self.change_feed.heartbeat_seconds == 0 {
+            return Err(StartupError::Configuration(
+                "Change feed history size and heartbeat interval must be greater than 0"
+                    .to_string(),
+            ));
+        }
+
+        // outbox 設定の検証
+        if self.outbox.enabled
+            && (self.outbox.interval_seconds == 0
+                || self.outbox.batch_size <= 0
+                || self.outbox.claim_timeout_seconds <= 0
+                || self.outbox.max_backoff_seconds <= 0
+                || self.outbox.retention_hours <= 0
+                || self.outbox.webhook_timeout_seconds == 0)
+        {
+            return Err(StartupError::Configuration(
+                "Outbox interval, batch size, timeouts and retention must be greater than 0"
+                    .to_string(),
+            ));
+        }
+
+        // Webhook 設定の検証
+        if self.webhook.timeout_seconds == 0 || self.webhook.max_consecutive_failures <= 0 {
+            return Err(StartupError::Configuration(
+                "Webhook timeout and max consecutive failures must be greater than 0".to_string(),
+            ));
+        }
+        if self.webhook.retry_interval_seconds == 0 || self.webhook.max_backoff_seconds <= 0 {
+            return Err(StartupError::Configuration(
+                "Webhook retry interval and max backoff must be greater than 0".to_string(),
+            ));
+        }
+
+        // ロケール設定の検証
+        let is_locale_tag = |tag: &String| {
+            !tag.is_empty()
+                && tag
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        if !is_locale_tag(&self.localization.default_locale)
+            || !self
+                .localization
+                .supported_locales
+                .iter()
+                .all(is_locale_tag)
+        {
+            return Err(StartupError::Configuration(
+                "Locales must be non-empty language tags such as \"en\" or \"en-US\"".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -154,6 +339,248 @@ impl AuthConfig {
     }
 }
 
+impl SchedulerConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            enabled: env::var("PRODUCT_SCHEDULER_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_SCHEDULER_ENABLED".to_string())
+                })?,
+            interval_seconds: env::var("PRODUCT_SCHEDULER_INTERVAL")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_SCHEDULER_INTERVAL".to_string())
+                })?,
+            batch_size: env::var("PRODUCT_SCHEDULER_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_SCHEDULER_BATCH_SIZE".to_string())
+                })?,
+            lease_seconds: env::var("PRODUCT_SCHEDULER_LEASE")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_SCHEDULER_LEASE".to_string())
+                })?,
+        })
+    }
+}
+
+impl IdempotencyConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            ttl_seconds: env::var("IDEMPOTENCY_TTL_SECONDS")
+                .unwrap_or_else(|_| "86400".to_string()) // 24 hours
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid IDEMPOTENCY_TTL_SECONDS".to_string())
+                })?,
+            purge_interval_seconds: env::var("IDEMPOTENCY_PURGE_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string()) // 1 hour
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration(
+                        "Invalid IDEMPOTENCY_PURGE_INTERVAL_SECONDS".to_string(),
+                    )
+                })?,
+        })
+    }
+}
+
+impl ImportConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            chunk_size: env::var("PRODUCT_IMPORT_CHUNK_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_IMPORT_CHUNK_SIZE".to_string())
+                })?,
+            max_rows: env::var("PRODUCT_IMPORT_MAX_ROWS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_IMPORT_MAX_ROWS".to_string())
+                })?,
+        })
+    }
+}
+
+impl ExportConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            batch_size: env::var("PRODUCT_EXPORT_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid PRODUCT_EXPORT_BATCH_SIZE".to_string())
+                })?,
+        })
+    }
+}
+
+impl BodyLimitConfig {
+    /// JSON ボディの上限のうち最大のもの（ストリームで受信するインポートは含まない）
+    pub fn max_json_bytes(&self) -> usize {
+        self.default_bytes.max(self.batch_bytes)
+    }
+
+    fn from_env() -> StartupResult<Self> {
+        let limit = |name: &str, default: &str| -> StartupResult<usize> {
+            env::var(name)
+                .unwrap_or_else(|_| default.to_string())
+                .parse()
+                .map_err(|_| StartupError::Configuration(format!("Invalid {}", name)))
+        };
+
+        Ok(Self {
+            default_bytes: limit("HTTP_BODY_LIMIT_DEFAULT", "65536")?,
+            batch_bytes: limit("HTTP_BODY_LIMIT_BATCH", "1048576")?,
+            import_bytes: limit("HTTP_BODY_LIMIT_IMPORT", "52428800")?,
+        })
+    }
+}
+
+impl ChangeFeedConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            history_size: env::var("CHANGE_FEED_HISTORY_SIZE")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid CHANGE_FEED_HISTORY_SIZE".to_string())
+                })?,
+            heartbeat_seconds: env::var("CHANGE_FEED_HEARTBEAT_SECONDS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid CHANGE_FEED_HEARTBEAT_SECONDS".to_string())
+                })?,
+        })
+    }
+}
+
+impl OutboxConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            enabled: env::var("OUTBOX_DISPATCHER_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_DISPATCHER_ENABLED".to_string())
+                })?,
+            interval_seconds: env::var("OUTBOX_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_INTERVAL_SECONDS".to_string())
+                })?,
+            batch_size: env::var("OUTBOX_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_BATCH_SIZE".to_string())
+                })?,
+            claim_timeout_seconds: env::var("OUTBOX_CLAIM_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_CLAIM_TIMEOUT_SECONDS".to_string())
+                })?,
+            max_backoff_seconds: env::var("OUTBOX_MAX_BACKOFF_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_MAX_BACKOFF_SECONDS".to_string())
+                })?,
+            retention_hours: env::var("OUTBOX_RETENTION_HOURS")
+                .unwrap_or_else(|_| "168".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_RETENTION_HOURS".to_string())
+                })?,
+            log_events: env::var("OUTBOX_LOG_EVENTS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid OUTBOX_LOG_EVENTS".to_string())
+                })?,
+            webhook_url: env::var("OUTBOX_WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            webhook_timeout_seconds: env::var("OUTBOX_WEBHOOK_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration(
+                        "Invalid OUTBOX_WEBHOOK_TIMEOUT_SECONDS".to_string(),
+                    )
+                })?,
+        })
+    }
+}
+
+impl WebhookConfig {
+    fn from_env() -> StartupResult<Self> {
+        Ok(Self {
+            timeout_seconds: env::var("WEBHOOK_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid WEBHOOK_TIMEOUT_SECONDS".to_string())
+                })?,
+            max_consecutive_failures: env::var("WEBHOOK_MAX_CONSECUTIVE_FAILURES")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration(
+                        "Invalid WEBHOOK_MAX_CONSECUTIVE_FAILURES".to_string(),
+                    )
+                })?,
+            retry_interval_seconds: env::var("WEBHOOK_RETRY_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration(
+                        "Invalid WEBHOOK_RETRY_INTERVAL_SECONDS".to_string(),
+                    )
+                })?,
+            max_backoff_seconds: env::var("WEBHOOK_MAX_BACKOFF_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid WEBHOOK_MAX_BACKOFF_SECONDS".to_string())
+                })?,
+            allow_private_targets: env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| {
+                    StartupError::Configuration("Invalid WEBHOOK_ALLOW_PRIVATE_TARGETS".to_string())
+                })?,
+        })
+    }
+}
+
+impl LocalizationConfig {
+    fn from_env() -> Self {
+        Self {
+            default_locale: env::var("DEFAULT_LOCALE")
+                .map(|locale| locale.trim().to_string())
+                .unwrap_or_else(|_| "ja".to_string()),
+            supported_locales: env::var("SUPPORTED_LOCALES")
+                .unwrap_or_else(|_| "ja,en".to_string())
+                .split(',')
+                .map(|locale| locale.trim().to_string())
+                .filter(|locale| !locale.is_empty())
+                .collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +628,52 @@ mod tests {
                 keycloak_auth_server_url: "http://localhost:8080".to_string(),
                 keycloak_client_id: "test-client".to_string(),
             },
+            scheduler: SchedulerConfig {
+                enabled: true,
+                interval_seconds: 30,
+                batch_size: 100,
+                lease_seconds: 300,
+            },
+            idempotency: IdempotencyConfig {
+                ttl_seconds: 86400,
+                purge_interval_seconds: 3600,
+            },
+            import: ImportConfig {
+                chunk_size: 100,
+                max_rows: 10000,
+            },
+            export: ExportConfig { batch_size: 500 },
+            body_limits: BodyLimitConfig {
+                default_bytes: 65536,
+                batch_bytes: 1048576,
+                import_bytes: 52428800,
+            },
+            change_feed: ChangeFeedConfig {
+                history_size: 10000,
+                heartbeat_seconds: 15,
+            },
+            outbox: OutboxConfig {
+                enabled: true,
+                interval_seconds: 1,
+                batch_size: 100,
+                claim_timeout_seconds: 60,
+                max_backoff_seconds: 300,
+                retention_hours: 168,
+                log_events: false,
+                webhook_url: None,
+                webhook_timeout_seconds: 10,
+            },
+            webhook: WebhookConfig {
+                timeout_seconds: 10,
+                max_consecutive_failures: 10,
+                retry_interval_seconds: 10,
+                max_backoff_seconds: 3600,
+                allow_private_targets: false,
+            },
+            localization: LocalizationConfig {
+                default_locale: "ja".to_string(),
+                supported_locales: vec!["ja".to_string(), "en".to_string()],
+            },
         };
 
         assert!(config.validate().is_err());
@@ -1,28 +1,53 @@
 use actix_web::web;
 use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::app_domain::model::localization::Locales;
 use crate::app_domain::repository::{
-    category_repository::CategoryRepository, item_repository::ItemRepository,
-    product_repository::ProductRepository,
+    category_repository::CategoryRepository, idempotency_repository::IdempotencyRepository,
+    item_repository::ItemRepository, outbox_repository::OutboxRepository,
+    product_repository::ProductRepository, webhook_repository::WebhookRepository,
 };
 use crate::application::service::{
-    category_service::CategoryService, deletion_facade::DeletionFacade, item_service::ItemService,
-    product_service::ProductService, user_service::UserService,
+    category_service::CategoryService,
+    change_feed::ChangeFeed,
+    deletion_facade::DeletionFacade,
+    idempotency_service::IdempotencyService,
+    item_service::ItemService,
+    outbox_dispatcher::{ChannelSink, EventSink, LogSink, OutboxDispatcher},
+    product_export_service::ProductExportService,
+    product_import_service::ProductImportService,
+    product_schedule_executor::ProductScheduleExecutor,
+    product_service::ProductService,
+    slug_service::SlugService,
+    user_service::UserService,
+    webhook_service::WebhookService,
 };
 use crate::infrastructure::auth::keycloak::{KeycloakAuth, KeycloakConfig};
-use crate::infrastructure::config::AppConfig;
+use crate::infrastructure::config::{AppConfig, BodyLimitConfig, OutboxConfig, SchedulerConfig};
 use crate::infrastructure::repository::{
-    category_repository::PostgresCategoryRepository, item_repository::PostgresItemRepository,
-    product_repository::PostgresProductRepository, user_repository::PostgresUserRepository,
+    category_repository::PostgresCategoryRepository,
+    idempotency_repository::PostgresIdempotencyRepository, item_repository::PostgresItemRepository,
+    outbox_repository::PostgresOutboxRepository, product_repository::PostgresProductRepository,
+    user_repository::PostgresUserRepository, webhook_repository::PostgresWebhookRepository,
 };
+use crate::infrastructure::startup_error::StartupError;
+use crate::infrastructure::webhook::{WebhookClient, WebhookSink};
 use crate::presentation::api::{
-    category_handler::CategoryHandler, item_handler::ItemHandler, product_handler::ProductHandler,
-    user_handler::UserHandler,
+    category_handler::CategoryHandler, change_handler::ChangeHandler, item_handler::ItemHandler,
+    product_handler::ProductHandler, slug_handler::SlugHandler,
+    translation_handler::TranslationHandler, user_handler::UserHandler,
+    webhook_handler::WebhookHandler,
 };
 use crate::presentation::grpc::{
+    auth::GrpcAuth,
+    category_service::{CategoryServiceImpl, CategoryServiceServer},
+    change_feed_service::{ChangeFeedServiceImpl, ChangeFeedServiceServer},
     item_service::{ItemServiceImpl, ItemServiceServer},
+    product_service::{ProductServiceImpl, ProductServiceServer},
     user_service::{UserServiceImpl, UserServiceServer},
+    FILE_DESCRIPTOR_SET,
 };
 use domain::repository::user_repository::UserRepositoryImpl;
 
@@ -52,18 +77,38 @@ pub struct AppContainer {
     #[allow(dead_code)]
     pub deletion_facade: Arc<DeletionFacade>,
 
+    // 冪等性キー（HTTP ミドルウェアと gRPC で共有）
+    pub idempotency_service: Arc<IdempotencyService>,
+
+    // ドメインイベントの outbox とプロセス内の配信先
+    pub outbox_repository: Arc<dyn OutboxRepository>,
+    #[allow(dead_code)]
+    pub event_channel: Arc<ChannelSink>,
+    // Webhook 購読（管理 API と outbox の配信先を兼ねる）
+    pub webhook_service: Arc<WebhookService>,
+
     // Handlers
     pub item_handler: web::Data<ItemHandler>,
     pub user_handler: web::Data<UserHandler>,
     pub category_handler: web::Data<CategoryHandler>,
     pub product_handler: web::Data<ProductHandler>,
+    pub change_handler: web::Data<ChangeHandler>,
+    pub webhook_handler: web::Data<WebhookHandler>,
+    pub slug_handler: web::Data<SlugHandler>,
+    pub translation_handler: web::Data<TranslationHandler>,
 
     // Auth
     pub keycloak_auth: web::Data<KeycloakAuth>,
 
+    // ルートごとのリクエストボディ上限
+    pub body_limits: BodyLimitConfig,
+
     // gRPC Services
     pub grpc_user_service: UserServiceImpl,
     pub grpc_item_service: ItemServiceImpl,
+    pub grpc_product_service: ProductServiceImpl,
+    pub grpc_category_service: CategoryServiceImpl,
+    pub grpc_change_feed_service: ChangeFeedServiceImpl,
 }
 
 impl AppContainer {
@@ -78,20 +123,69 @@ impl AppContainer {
             Arc::new(PostgresCategoryRepository::new(pool.clone()));
         let product_repository: Arc<dyn ProductRepository> =
             Arc::new(PostgresProductRepository::new(pool.clone()));
+        let outbox_repository: Arc<dyn OutboxRepository> =
+            Arc::new(PostgresOutboxRepository::new(pool.clone()));
+        let event_channel = Arc::new(ChannelSink::new(1024));
+        let webhook_repository: Arc<dyn WebhookRepository> =
+            Arc::new(PostgresWebhookRepository::new(pool.clone()));
+        let webhook_service = Arc::new(WebhookService::new(
+            webhook_repository,
+            WebhookClient::new(Duration::from_secs(config.webhook.timeout_seconds))
+                .allow_private_targets(config.webhook.allow_private_targets),
+            config.webhook.max_consecutive_failures,
+            config.webhook.max_backoff_seconds,
+        ));
+
+        // 変更フィード（各サービスの更新を REST/gRPC の購読者に通知する）
+        let change_feed = Arc::new(ChangeFeed::new(config.change_feed.history_size));
+        let heartbeat = Duration::from_secs(config.change_feed.heartbeat_seconds);
+        let locales = Locales::new(
+            &config.localization.default_locale,
+            &config.localization.supported_locales,
+        );
 
         // サービスの作成
-        let item_service = Arc::new(ItemService::new(item_repository.clone()));
+        let item_service = Arc::new(
+            ItemService::new(item_repository.clone()).with_change_feed(change_feed.clone()),
+        );
         let user_service = Arc::new(UserService::new(user_repository.clone()));
-        let category_service = Arc::new(CategoryService::new(category_repository.clone()));
-        let product_service = Arc::new(ProductService::new(product_repository.clone()));
-
-        // 削除ファサードの作成
-        let deletion_facade = Arc::new(DeletionFacade::new(
-            item_repository.clone(),
-            category_repository.clone(),
+        let category_service = Arc::new(
+            CategoryService::new(category_repository.clone())
+                .with_change_feed(change_feed.clone())
+                .with_locales(locales.clone()),
+        );
+        let product_service = Arc::new(
+            ProductService::new(product_repository.clone())
+                .with_change_feed(change_feed.clone())
+                .with_locales(locales),
+        );
+        let product_import_service = Arc::new(
+            ProductImportService::new(
+                product_repository.clone(),
+                config.import.chunk_size,
+                config.import.max_rows,
+            )
+            .with_change_feed(change_feed.clone()),
+        );
+        let product_export_service = Arc::new(ProductExportService::new(
             product_repository.clone(),
+            config.export.batch_size,
+        ));
+        let idempotency_repository: Arc<dyn IdempotencyRepository> =
+            Arc::new(PostgresIdempotencyRepository::new(pool.clone()));
+        let idempotency_service = Arc::new(IdempotencyService::new(
+            idempotency_repository,
+            config.idempotency.ttl_seconds,
         ));
 
+        // 削除ファサードの作成
+        let deletion_facade = Arc::new(
+            DeletionFacade::new(item_repository.clone())
+                .with_category_repository(category_repository.clone())
+                .with_product_repository(product_repository.clone())
+                .with_change_feed(change_feed.clone()),
+        );
+
         // Keycloak認証の設定
         let keycloak_config = KeycloakConfig::from_auth_config(&config.auth);
         let keycloak_auth = web::Data::new(KeycloakAuth::new(keycloak_config));
@@ -102,18 +196,43 @@ impl AppContainer {
             deletion_facade.clone(),
         ));
         let user_handler = web::Data::new(UserHandler::new(user_service.clone()));
-        let category_handler = web::Data::new(CategoryHandler::new(
-            category_service.clone(),
-            deletion_facade.clone(),
-        ));
+        let category_handler = web::Data::new(CategoryHandler::new(category_service.clone()));
         let product_handler = web::Data::new(ProductHandler::new(
             product_service.clone(),
-            deletion_facade.clone(),
+            product_import_service,
+            product_export_service,
+        ));
+        let change_handler = web::Data::new(ChangeHandler::new(change_feed.clone(), heartbeat));
+        let webhook_handler = web::Data::new(WebhookHandler::new(webhook_service.clone()));
+        let slug_handler = web::Data::new(SlugHandler::new(Arc::new(SlugService::new(
+            category_repository.clone(),
+            product_service.clone(),
+        ))));
+        let translation_handler = web::Data::new(TranslationHandler::new(
+            product_service.clone(),
+            category_service.clone(),
         ));
 
         // gRPCサービスの作成
-        let grpc_user_service = UserServiceImpl::new(user_service.clone());
-        let grpc_item_service = ItemServiceImpl::new(item_service.clone(), deletion_facade.clone());
+        let grpc_user_service =
+            UserServiceImpl::new(user_service.clone(), idempotency_service.clone());
+        let grpc_item_service = ItemServiceImpl::new(
+            item_service.clone(),
+            deletion_facade.clone(),
+            idempotency_service.clone(),
+        );
+        let grpc_auth = GrpcAuth::new(keycloak_auth.clone().into_inner());
+        let grpc_product_service = ProductServiceImpl::new(
+            product_service.clone(),
+            idempotency_service.clone(),
+            grpc_auth.clone(),
+        );
+        let grpc_category_service = CategoryServiceImpl::new(
+            category_service.clone(),
+            idempotency_service.clone(),
+            grpc_auth,
+        );
+        let grpc_change_feed_service = ChangeFeedServiceImpl::new(change_feed, heartbeat);
 
         Self {
             item_repository,
@@ -125,20 +244,66 @@ impl AppContainer {
             category_service,
             product_service,
             deletion_facade,
+            idempotency_service,
+            outbox_repository,
+            event_channel,
+            webhook_service,
             item_handler,
             user_handler,
             category_handler,
             product_handler,
+            change_handler,
+            webhook_handler,
+            slug_handler,
+            translation_handler,
             keycloak_auth,
+            body_limits: config.body_limits.clone(),
             grpc_user_service,
             grpc_item_service,
+            grpc_product_service,
+            grpc_category_service,
+            grpc_change_feed_service,
+        }
+    }
+
+    /// 商品ステータス変更予約の実行器を構築する
+    pub fn build_schedule_executor(&self, config: &SchedulerConfig) -> ProductScheduleExecutor {
+        ProductScheduleExecutor::new(self.product_service.clone(), config.clone())
+    }
+
+    /// outbox のディスパッチャーを構築する（登録済みの Webhook 購読に加え、設定に応じてログ・固定の Webhook にも配信する）
+    pub fn build_outbox_dispatcher(&self, config: &OutboxConfig) -> OutboxDispatcher {
+        let mut sinks: Vec<Arc<dyn EventSink>> =
+            vec![self.event_channel.clone(), self.webhook_service.clone()];
+        if config.log_events {
+            sinks.push(Arc::new(LogSink));
         }
+        if let Some(url) = &config.webhook_url {
+            sinks.push(Arc::new(WebhookSink::new(
+                url.clone(),
+                Duration::from_secs(config.webhook_timeout_seconds),
+            )));
+        }
+        OutboxDispatcher::new(self.outbox_repository.clone(), sinks, config.clone())
     }
 
-    /// gRPCサーバーを構築する
-    pub fn build_grpc_server(&self) -> tonic::transport::server::Router {
-        tonic::transport::Server::builder()
+    /// gRPCサーバーを構築する（grpcurl などから使えるようにリフレクションも登録する）
+    pub fn build_grpc_server(&self) -> Result<tonic::transport::server::Router, StartupError> {
+        let reflection = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .build_v1()
+            .map_err(|e| StartupError::GrpcServer(e.to_string()))?;
+
+        Ok(tonic::transport::Server::builder()
+            .add_service(reflection)
             .add_service(UserServiceServer::new(self.grpc_user_service.clone()))
             .add_service(ItemServiceServer::new(self.grpc_item_service.clone()))
+            .add_service(ProductServiceServer::new(self.grpc_product_service.clone()))
+            .add_service(CategoryServiceServer::new(
+                self.grpc_category_service.clone(),
+            ))
+            .add_service(ChangeFeedServiceServer::new(
+                self.grpc_change_feed_service.clone(),
+            )))
     }
 }
@@ -3,13 +3,17 @@ use actix_web::{middleware, web, App, HttpServer};
 use std::time::{Duration, Instant};
 use tracing_actix_web::TracingLogger;
 
+use crate::infrastructure::config::BodyLimitConfig;
 use crate::infrastructure::di::container::AppContainer;
 use crate::infrastructure::metrics::{
     metrics_handler, normalize_path_for_metrics, record_http_request, Metrics,
 };
 use crate::presentation::api::{
-    category_handler::configure_category_routes, item_handler::ItemHandler,
-    product_handler::configure_product_routes, user_handler::UserHandler,
+    body_limit::json_config, category_handler::configure_category_routes,
+    change_handler::ChangeHandler, idempotency::Idempotency, item_handler::ItemHandler,
+    product_handler::configure_product_routes, slug_handler::configure_slug_routes,
+    translation_handler::configure_translation_routes, user_handler::UserHandler,
+    webhook_handler::configure_webhook_routes,
 };
 
 /// HTTPサーバーを構築する
@@ -22,7 +26,13 @@ pub fn build_http_server(
         let user_handler = container.user_handler.clone();
         let category_handler = container.category_handler.clone();
         let product_handler = container.product_handler.clone();
+        let change_handler = container.change_handler.clone();
+        let webhook_handler = container.webhook_handler.clone();
+        let slug_handler = container.slug_handler.clone();
+        let translation_handler = container.translation_handler.clone();
         let keycloak_auth = container.keycloak_auth.clone();
+        let idempotency_service = container.idempotency_service.clone();
+        let body_limits = container.body_limits.clone();
 
         move || {
             App::new()
@@ -30,15 +40,19 @@ pub fn build_http_server(
                 .app_data(user_handler.clone())
                 .app_data(category_handler.clone())
                 .app_data(product_handler.clone())
+                .app_data(change_handler.clone())
+                .app_data(webhook_handler.clone())
+                .app_data(slug_handler.clone())
+                .app_data(translation_handler.clone())
                 .app_data(keycloak_auth.clone())
-                // Configure JSON handling with size limit
-                .app_data(
-                    web::JsonConfig::default()
-                        .limit(4096) // 4KB limit for JSON payloads
-                        .error_handler(|err, _req| {
-                            use crate::infrastructure::error::AppError;
-                            AppError::bad_request(err.to_string()).into()
-                        }),
+                // JSON ボディの既定の上限（一括操作・インポートはルート側で上書き）
+                .app_data(json_config(body_limits.default_bytes))
+                // Replay stored responses for retried requests (inside compression)
+                // 冪等性キー付きリクエストはルーティング前にボディを読むため、JSON の最大の上限で打ち切る
+                // （ストリームで受信するインポートは先読みしない）
+                .wrap(
+                    Idempotency::new(idempotency_service.clone(), body_limits.max_json_bytes())
+                        .streaming_path("/api/products/import"),
                 )
                 // Enable response compression
                 .wrap(middleware::Compress::default())
@@ -79,59 +93,7 @@ pub fn build_http_server(
                     }
                 })
                 .route("/", web::get().to(ItemHandler::index))
-                .service(
-                    web::scope("/api")
-                        // 認証不要のエンドポイント
-                        .route("/health", web::get().to(|| async { "OK" }))
-                        .route("/metrics", web::get().to(metrics_handler))
-                        // 認証必要のエンドポイント
-                        .route("/items", web::get().to(ItemHandler::get_items))
-                        .route("/items", web::post().to(ItemHandler::create_item))
-                        .route("/items/{id}", web::get().to(ItemHandler::get_item))
-                        .route("/items/{id}", web::put().to(ItemHandler::update_item))
-                        .route("/items/{id}", web::delete().to(ItemHandler::delete_item))
-                        // New product deletion API routes
-                        .route(
-                            "/products/{id}",
-                            web::delete().to(ItemHandler::logical_delete_item),
-                        )
-                        .route(
-                            "/products/{id}/permanent",
-                            web::delete().to(ItemHandler::physical_delete_item),
-                        )
-                        .route(
-                            "/products/{id}/restore",
-                            web::post().to(ItemHandler::restore_item),
-                        )
-                        .route(
-                            "/products/{id}/deletion-check",
-                            web::get().to(ItemHandler::validate_item_deletion),
-                        )
-                        .route(
-                            "/products/batch",
-                            web::delete().to(ItemHandler::batch_delete_items),
-                        )
-                        .route(
-                            "/products/deleted",
-                            web::get().to(ItemHandler::get_deleted_items),
-                        )
-                        .route(
-                            "/products/{id}/deletion-log",
-                            web::get().to(ItemHandler::get_item_deletion_log),
-                        )
-                        .route(
-                            "/deletion-logs",
-                            web::get().to(ItemHandler::get_deletion_logs),
-                        )
-                        .route("/users", web::get().to(UserHandler::get_users))
-                        .route("/users", web::post().to(UserHandler::create_user))
-                        .route("/users/{id}", web::get().to(UserHandler::get_user))
-                        .route("/users/{id}", web::put().to(UserHandler::update_user))
-                        .route("/users/{id}", web::delete().to(UserHandler::delete_user))
-                        // Configure categories and products routes
-                        .configure(configure_category_routes)
-                        .configure(configure_product_routes),
-                )
+                .configure(|cfg| configure_api_routes(cfg, &body_limits))
         }
     })
     // Performance optimizations
@@ -144,3 +106,70 @@ pub fn build_http_server(
 
     Ok(server)
 }
+
+/// `/api` 以下のルートを登録する
+///
+/// 固定のパス（`/items/batch` など）は同じ位置のパスパラメーターより前に登録する。
+pub fn configure_api_routes(cfg: &mut web::ServiceConfig, body_limits: &BodyLimitConfig) {
+    cfg.service(
+        web::scope("/api")
+            // 認証不要のエンドポイント
+            .route("/health", web::get().to(|| async { "OK" }))
+            .route("/metrics", web::get().to(metrics_handler))
+            // 認証必要のエンドポイント
+            .route("/items", web::get().to(ItemHandler::get_items))
+            .route("/items", web::post().to(ItemHandler::create_item))
+            // アイテムの削除・復元（一括削除用の上限を適用する）
+            .service(
+                web::resource("/items/batch")
+                    .app_data(json_config(body_limits.batch_bytes))
+                    .route(web::delete().to(ItemHandler::batch_delete_items)),
+            )
+            .route(
+                "/items/deleted",
+                web::get().to(ItemHandler::get_deleted_items),
+            )
+            .route("/items/{id}", web::get().to(ItemHandler::get_item))
+            .route("/items/{id}", web::put().to(ItemHandler::update_item))
+            .route("/items/{id}", web::delete().to(ItemHandler::delete_item))
+            .route(
+                "/items/{id}/logical",
+                web::delete().to(ItemHandler::logical_delete_item),
+            )
+            .route(
+                "/items/{id}/permanent",
+                web::delete().to(ItemHandler::physical_delete_item),
+            )
+            .route(
+                "/items/{id}/restore",
+                web::post().to(ItemHandler::restore_item),
+            )
+            .route(
+                "/items/{id}/deletion-check",
+                web::get().to(ItemHandler::validate_item_deletion),
+            )
+            .route(
+                "/items/{id}/deletion-log",
+                web::get().to(ItemHandler::get_item_deletion_log),
+            )
+            .route(
+                "/deletion-logs",
+                web::get().to(ItemHandler::get_deletion_logs),
+            )
+            .route("/users", web::get().to(UserHandler::get_users))
+            .route("/users", web::post().to(UserHandler::create_user))
+            .route("/users/{id}", web::get().to(UserHandler::get_user))
+            .route("/users/{id}", web::put().to(UserHandler::update_user))
+            .route("/users/{id}", web::delete().to(UserHandler::delete_user))
+            // アイテム・商品・カテゴリの変更通知（Server-Sent Events）
+            .route("/changes", web::get().to(ChangeHandler::watch_changes))
+            // Configure categories and products routes
+            .configure(configure_category_routes)
+            .configure(|cfg| configure_product_routes(cfg, body_limits))
+            // スラッグのパスからカテゴリ・商品を解決する
+            .configure(configure_slug_routes)
+            // Webhook 購読の管理
+            .configure(configure_webhook_routes)
+            .configure(configure_translation_routes),
+    );
+}
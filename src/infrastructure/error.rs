@@ -50,108 +50,104 @@ pub enum AppError {
     #[error("Timeout error: {0}")]
     TimeoutError(String),
 
+    #[error("Payload too large: limit is {0} bytes")]
+    PayloadTooLarge(usize),
+
     #[error("Generic error: {0}")]
     Generic(#[from] anyhow::Error),
 }
 
+impl AppError {
+    /// REST レスポンスの `error.type`（gRPC の ErrorInfo reason にも使う）
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            AppError::DatabaseError(_) => "database_error",
+            AppError::NotFound(_) => "not_found",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::Conflict(_) => "conflict",
+            AppError::InternalServerError(_) => "internal_server_error",
+            AppError::ServiceUnavailable(_) => "service_unavailable",
+            AppError::ValidationError(_) => "validation_error",
+            AppError::AuthenticationError(_) => "authentication_error",
+            AppError::ExternalServiceError(_) => "external_service_error",
+            AppError::ConfigurationError(_) => "configuration_error",
+            AppError::SerializationError(_) => "serialization_error",
+            AppError::NetworkError(_) => "network_error",
+            AppError::TimeoutError(_) => "timeout_error",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::Generic(_) => "generic_error",
+        }
+    }
+}
+
 impl ResponseError for AppError {
     fn error_response(&self) -> HttpResponse {
-        let (status, error_type, message) = match self {
+        let (status, message) = match self {
             AppError::DatabaseError(e) => {
                 tracing::error!("Database error: {:?}", e);
                 (
                     actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    "database_error",
                     "データベースエラーが発生しました".to_string(),
                 )
             }
-            AppError::NotFound(msg) => (
-                actix_web::http::StatusCode::NOT_FOUND,
-                "not_found",
-                msg.clone(),
-            ),
-            AppError::BadRequest(msg) => (
-                actix_web::http::StatusCode::BAD_REQUEST,
-                "bad_request",
-                msg.clone(),
-            ),
-            AppError::Unauthorized(msg) => (
-                actix_web::http::StatusCode::UNAUTHORIZED,
-                "unauthorized",
-                msg.clone(),
-            ),
-            AppError::Forbidden(msg) => (
-                actix_web::http::StatusCode::FORBIDDEN,
-                "forbidden",
-                msg.clone(),
-            ),
-            AppError::Conflict(msg) => (
-                actix_web::http::StatusCode::CONFLICT,
-                "conflict",
-                msg.clone(),
-            ),
+            AppError::NotFound(msg) => (actix_web::http::StatusCode::NOT_FOUND, msg.clone()),
+            AppError::BadRequest(msg) => (actix_web::http::StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::Unauthorized(msg) => (actix_web::http::StatusCode::UNAUTHORIZED, msg.clone()),
+            AppError::Forbidden(msg) => (actix_web::http::StatusCode::FORBIDDEN, msg.clone()),
+            AppError::Conflict(msg) => (actix_web::http::StatusCode::CONFLICT, msg.clone()),
             AppError::InternalServerError(msg) => {
                 tracing::error!("Internal server error: {}", msg);
                 (
                     actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    "internal_server_error",
                     "サーバーエラーが発生しました".to_string(),
                 )
             }
             AppError::ServiceUnavailable(msg) => (
                 actix_web::http::StatusCode::SERVICE_UNAVAILABLE,
-                "service_unavailable",
-                msg.clone(),
-            ),
-            AppError::ValidationError(msg) => (
-                actix_web::http::StatusCode::BAD_REQUEST,
-                "validation_error",
-                msg.clone(),
-            ),
-            AppError::AuthenticationError(msg) => (
-                actix_web::http::StatusCode::UNAUTHORIZED,
-                "authentication_error",
                 msg.clone(),
             ),
+            AppError::ValidationError(msg) => {
+                (actix_web::http::StatusCode::BAD_REQUEST, msg.clone())
+            }
+            AppError::AuthenticationError(msg) => {
+                (actix_web::http::StatusCode::UNAUTHORIZED, msg.clone())
+            }
             AppError::ExternalServiceError(msg) => {
                 tracing::error!("External service error: {}", msg);
-                (
-                    actix_web::http::StatusCode::BAD_GATEWAY,
-                    "external_service_error",
-                    msg.clone(),
-                )
+                (actix_web::http::StatusCode::BAD_GATEWAY, msg.clone())
             }
             AppError::ConfigurationError(msg) => {
                 tracing::error!("Configuration error: {}", msg);
                 (
                     actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    "configuration_error",
                     "設定エラーが発生しました".to_string(),
                 )
             }
             AppError::SerializationError(msg) => (
                 actix_web::http::StatusCode::BAD_REQUEST,
-                "serialization_error",
                 format!("データの変換に失敗しました: {}", msg),
             ),
             AppError::NetworkError(msg) => {
                 tracing::error!("Network error: {}", msg);
                 (
                     actix_web::http::StatusCode::BAD_GATEWAY,
-                    "network_error",
                     "ネットワークエラーが発生しました".to_string(),
                 )
             }
             AppError::TimeoutError(msg) => (
                 actix_web::http::StatusCode::REQUEST_TIMEOUT,
-                "timeout_error",
                 format!("タイムアウトが発生しました: {}", msg),
             ),
+            AppError::PayloadTooLarge(limit) => (
+                actix_web::http::StatusCode::PAYLOAD_TOO_LARGE,
+                format!("リクエストボディが上限（{} バイト）を超えています", limit),
+            ),
             AppError::Generic(e) => {
                 tracing::error!("Generic error: {:?}", e);
                 (
                     actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    "generic_error",
                     "予期しないエラーが発生しました".to_string(),
                 )
             }
@@ -159,7 +155,7 @@ impl ResponseError for AppError {
 
         HttpResponse::build(status).json(serde_json::json!({
             "error": {
-                "type": error_type,
+                "type": self.error_type(),
                 "message": message,
                 "timestamp": chrono::Utc::now().to_rfc3339(),
             }
@@ -227,6 +223,11 @@ impl AppError {
         AppError::TimeoutError(msg.into())
     }
 
+    /// リクエストボディの上限超過エラーを生成
+    pub fn payload_too_large(limit: usize) -> Self {
+        AppError::PayloadTooLarge(limit)
+    }
+
     /// 汎用エラーを生成（anyhowから）
     pub fn from_anyhow(err: anyhow::Error) -> Self {
         AppError::Generic(err)
@@ -7,3 +7,4 @@ pub mod metrics;
 pub mod repository;
 pub mod startup_error;
 pub mod tracing;
+pub mod webhook;
@@ -1,15 +1,85 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{PgPool, Row};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use tracing::error;
-
-use crate::app_domain::model::category::{Category, CategoryError, CategoryPath, CategoryTree};
+use uuid::Uuid;
+
+use crate::app_domain::model::attribute_schema::{
+    AttributeDefinition, AttributeSchema, AttributeType,
+};
+use crate::app_domain::model::category::{
+    Category, CategoryError, CategoryPath, CategoryRedirect, CategoryTree, ProductCount,
+    ProductDisposition,
+};
+use crate::app_domain::model::domain_event::DomainEvent;
+use crate::app_domain::model::localization::CategoryTranslation;
+use crate::app_domain::model::product::{ChangeContext, FieldChange, ProductStatus};
+use crate::app_domain::model::slug;
 use crate::app_domain::repository::category_repository::CategoryRepository;
+use crate::infrastructure::repository::outbox_repository::append_event;
+#[cfg(test)]
+use crate::infrastructure::repository::outbox_repository::PostgresOutboxRepository;
+use crate::infrastructure::repository::postgres::converters::parse_product_status;
+use crate::infrastructure::repository::postgres::product_metadata::{
+    record_revision, record_revision_with_event,
+};
+
+/// カテゴリ階層の最大階層数
+const MAX_DEPTH: i64 = 5;
+/// 階層を変更するトランザクションが取得するアドバイザリロックのキー
+const HIERARCHY_LOCK_KEY: i64 = 0x6361_7465_676f_7279;
 
 pub struct PostgresCategoryRepository {
     pool: PgPool,
 }
 
+/// 祖先カテゴリから継承したものを含む商品属性の定義を閉包テーブルから読み込む
+///
+/// 商品の作成・更新時の検証にも使うため、商品リポジトリのトランザクションからも呼び出せる。
+pub async fn attribute_schema<'e, E>(
+    executor: E,
+    category_id: &str,
+) -> Result<AttributeSchema, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let rows = sqlx::query(
+        "SELECT d.category_id, d.name, d.attribute_type, d.allowed_values, d.required, d.unit
+         FROM category_closure cc
+         JOIN category_attribute_definitions d ON d.category_id = cc.ancestor_id
+         WHERE cc.descendant_id = $1
+         ORDER BY cc.depth DESC, d.sort_order",
+    )
+    .bind(category_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(AttributeSchema::inherit(rows.iter().map(|row| {
+        (row.get("category_id"), row_to_attribute_definition(row))
+    })))
+}
+
+fn row_to_attribute_definition(row: &PgRow) -> AttributeDefinition {
+    let attribute_type: String = row.get("attribute_type");
+    AttributeDefinition {
+        name: row.get("name"),
+        attribute_type: AttributeType::parse(&attribute_type).unwrap_or(AttributeType::String),
+        allowed_values: row.get("allowed_values"),
+        required: row.get("required"),
+        unit: row.get("unit"),
+    }
+}
+
+fn row_to_translation(row: &PgRow) -> CategoryTranslation {
+    CategoryTranslation {
+        locale: row.get("locale"),
+        name: row.get("name"),
+        description: row.get("description"),
+    }
+}
+
 impl PostgresCategoryRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
@@ -21,12 +91,14 @@ impl PostgresCategoryRepository {
             "CREATE TABLE IF NOT EXISTS categories (
                 id VARCHAR(255) PRIMARY KEY,
                 name VARCHAR(100) NOT NULL,
+                slug VARCHAR(100) NOT NULL,
                 description TEXT,
                 parent_id VARCHAR(255),
                 sort_order INTEGER NOT NULL DEFAULT 0,
                 is_active BOOLEAN NOT NULL DEFAULT true,
                 created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
                 updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                version BIGINT NOT NULL DEFAULT 1,
                 FOREIGN KEY (parent_id) REFERENCES categories(id) ON DELETE CASCADE,
                 CONSTRAINT check_sort_order_non_negative CHECK (sort_order >= 0),
                 CONSTRAINT check_name_not_empty CHECK (LENGTH(TRIM(name)) > 0),
@@ -34,72 +106,780 @@ impl PostgresCategoryRepository {
             )",
         )
         .execute(&self.pool)
-        .await
-        .map(|_| ())
+        .await?;
+
+        sqlx::query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_parent_slug
+             ON categories ((COALESCE(parent_id, '')), slug)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS category_slug_redirects (
+                parent_id VARCHAR(255),
+                slug VARCHAR(100) NOT NULL,
+                category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+                merged_from VARCHAR(255),
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_category_slug_redirects_parent_slug
+             ON category_slug_redirects ((COALESCE(parent_id, '')), slug)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS category_closure (
+                ancestor_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+                descendant_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+                depth INTEGER NOT NULL CHECK (depth >= 0),
+                PRIMARY KEY (ancestor_id, descendant_id)
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_category_closure_descendant
+             ON category_closure(descendant_id, depth)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS category_attribute_definitions (
+                category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+                name VARCHAR(100) NOT NULL,
+                attribute_type VARCHAR(20) NOT NULL,
+                allowed_values TEXT[] NOT NULL DEFAULT '{}',
+                required BOOLEAN NOT NULL DEFAULT false,
+                unit VARCHAR(50),
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (category_id, name)
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS category_translations (
+                category_id VARCHAR(255) NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+                locale VARCHAR(35) NOT NULL,
+                name VARCHAR(100),
+                description TEXT,
+                updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (category_id, locale)
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // 商品数の集計と削除時の商品の付け替えに使う列だけを持つ
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS products (
+                id VARCHAR(255) PRIMARY KEY,
+                status VARCHAR(20) NOT NULL DEFAULT 'Draft',
+                category_id VARCHAR(255) REFERENCES categories(id) ON DELETE SET NULL,
+                updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                version BIGINT NOT NULL DEFAULT 1
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS product_history (
+                id BIGSERIAL PRIMARY KEY,
+                product_id VARCHAR(255) NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+                field_name VARCHAR(100) NOT NULL,
+                old_value TEXT,
+                new_value TEXT,
+                changed_by VARCHAR(255),
+                reason TEXT,
+                changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        PostgresOutboxRepository::init_table(&self.pool).await
     }
 
     fn row_to_category(row: &sqlx::postgres::PgRow) -> Category {
         Category {
             id: row.get("id"),
             name: row.get("name"),
+            slug: row.get("slug"),
             description: row.get("description"),
             parent_id: row.get("parent_id"),
             sort_order: row.get("sort_order"),
             is_active: row.get("is_active"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            version: row.get("version"),
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn build_category_tree_recursive<'a>(
-        &'a self,
-        categories: &'a [Category],
-        parent_id: Option<&'a str>,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Vec<CategoryTree>> + Send + 'a>> {
-        Box::pin(async move {
-            let mut tree = Vec::new();
-
-            for category in categories {
-                if category.parent_id.as_deref() == parent_id {
-                    let children = self
-                        .build_category_tree_recursive(categories, Some(&category.id))
-                        .await;
-
-                    tree.push(CategoryTree {
-                        id: category.id.clone(),
-                        name: category.name.clone(),
-                        description: category.description.clone(),
-                        sort_order: category.sort_order,
-                        is_active: category.is_active,
-                        children,
-                    });
+    /// 親子関係から木構造を組み立てる（カテゴリは sort_order, name 順で渡す）
+    fn build_tree(
+        categories: &[Category],
+        product_counts: &HashMap<String, ProductCount>,
+    ) -> Vec<CategoryTree> {
+        let mut children: HashMap<Option<&str>, Vec<&Category>> = HashMap::new();
+        for category in categories {
+            children
+                .entry(category.parent_id.as_deref())
+                .or_default()
+                .push(category);
+        }
+
+        fn build(
+            parent_id: Option<&str>,
+            children: &HashMap<Option<&str>, Vec<&Category>>,
+            product_counts: &HashMap<String, ProductCount>,
+        ) -> Vec<CategoryTree> {
+            children
+                .get(&parent_id)
+                .map(|categories| {
+                    categories
+                        .iter()
+                        .map(|category| CategoryTree {
+                            id: category.id.clone(),
+                            name: category.name.clone(),
+                            slug: category.slug.clone(),
+                            description: category.description.clone(),
+                            sort_order: category.sort_order,
+                            is_active: category.is_active,
+                            product_count: product_counts
+                                .get(&category.id)
+                                .copied()
+                                .unwrap_or_default(),
+                            children: build(Some(&category.id), children, product_counts),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        build(None, &children, product_counts)
+    }
+
+    /// 閉包テーブルで子孫の商品を集計し、全カテゴリの商品数を1回のクエリで求める
+    async fn product_counts(&self) -> HashMap<String, ProductCount> {
+        let query = "SELECT cc.ancestor_id AS category_id,
+                            COUNT(p.id) FILTER (WHERE cc.depth = 0) AS direct,
+                            COUNT(p.id) AS total
+                     FROM category_closure cc
+                     JOIN products p ON p.category_id = cc.descendant_id
+                     GROUP BY cc.ancestor_id";
+
+        match sqlx::query(query).fetch_all(&self.pool).await {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| {
+                    (
+                        row.get("category_id"),
+                        ProductCount {
+                            direct: row.get("direct"),
+                            total: row.get("total"),
+                        },
+                    )
+                })
+                .collect(),
+            Err(e) => {
+                error!("Error counting products by category: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// 削除するカテゴリ直下の商品を指定どおりに扱う（指定がなければ商品があると失敗する）
+    ///
+    /// 変更理由の指定がなければ、カテゴリの削除によるものとして履歴に記録する。
+    async fn release_products(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        disposition: Option<&ProductDisposition>,
+        ctx: &ChangeContext,
+    ) -> Result<(), CategoryError> {
+        let ctx = ChangeContext::new(
+            ctx.changed_by.clone(),
+            ctx.reason
+                .clone()
+                .or_else(|| Some(format!("カテゴリ {} の削除", id))),
+        );
+
+        match disposition {
+            None => {
+                let row =
+                    sqlx::query("SELECT COUNT(*) AS count FROM products WHERE category_id = $1")
+                        .bind(id)
+                        .fetch_one(&mut **tx)
+                        .await
+                        .map_err(Self::database_error("削除"))?;
+                let count: i64 = row.get("count");
+                if count > 0 {
+                    return Err(CategoryError::HasProducts(format!(
+                        "商品が{}件紐づいているため削除できません",
+                        count
+                    )));
+                }
+            }
+            Some(ProductDisposition::Reassign(target_id)) => {
+                if target_id == id {
+                    return Err(CategoryError::CircularReference(
+                        "削除するカテゴリ自身を付け替え先にすることはできません".to_string(),
+                    ));
+                }
+                let target = sqlx::query("SELECT id FROM categories WHERE id = $1")
+                    .bind(target_id)
+                    .fetch_optional(&mut **tx)
+                    .await
+                    .map_err(Self::database_error("削除"))?;
+                if target.is_none() {
+                    return Err(CategoryError::NotFound(
+                        "付け替え先のカテゴリが見つかりません".to_string(),
+                    ));
+                }
+
+                let rows = sqlx::query(
+                    "UPDATE products SET category_id = $2, updated_at = NOW()
+                     WHERE category_id = $1
+                     RETURNING id",
+                )
+                .bind(id)
+                .bind(target_id)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Self::database_error("削除"))?;
+
+                let change = FieldChange {
+                    field_name: "category_id".to_string(),
+                    old_value: Some(id.to_string()),
+                    new_value: Some(target_id.clone()),
+                };
+                for row in rows {
+                    let product_id: String = row.get("id");
+                    record_revision(tx, &product_id, std::slice::from_ref(&change), &ctx)
+                        .await
+                        .map_err(|e| CategoryError::NotFound(e.to_string()))?;
+                }
+            }
+            Some(ProductDisposition::Deactivate) => {
+                let rows = sqlx::query(
+                    "WITH released AS (
+                         SELECT id, status FROM products WHERE category_id = $1 FOR UPDATE
+                     )
+                     UPDATE products p
+                     SET status = CASE WHEN p.status = 'Discontinued' THEN p.status ELSE 'Inactive' END,
+                         category_id = NULL,
+                         updated_at = NOW()
+                     FROM released r
+                     WHERE p.id = r.id
+                     RETURNING p.id, r.status AS previous_status, p.status",
+                )
+                .bind(id)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Self::database_error("削除"))?;
+
+                for row in rows {
+                    let product_id: String = row.get("id");
+                    let previous = parse_product_status(row.get("previous_status"));
+                    let current = parse_product_status(row.get("status"));
+
+                    let mut changes = vec![FieldChange {
+                        field_name: "category_id".to_string(),
+                        old_value: Some(id.to_string()),
+                        new_value: None,
+                    }];
+                    changes.extend(FieldChange::diff(
+                        "status",
+                        Some(previous.to_string()),
+                        Some(current.to_string()),
+                    ));
+                    let event = if current == ProductStatus::Inactive && previous != current {
+                        DomainEvent::product_status_changed(&product_id, &previous, &current)
+                    } else {
+                        DomainEvent::product_updated(&product_id, &changes)
+                    };
+                    record_revision_with_event(tx, &product_id, &changes, &ctx, event)
+                        .await
+                        .map_err(|e| CategoryError::NotFound(e.to_string()))?;
                 }
             }
+        }
+        Ok(())
+    }
+
+    /// カテゴリ行をロックし、子カテゴリがないことを確認してから商品を処理して削除する
+    ///
+    /// `expected_version` を指定した場合は、ロックしたカテゴリのバージョンが一致する場合だけ削除する。
+    async fn remove(
+        &self,
+        id: &str,
+        disposition: Option<&ProductDisposition>,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<bool, CategoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(Self::database_error("削除"))?;
+        // 子カテゴリの追加・移動と直列化し、確認から削除までの間に子が増えないようにする
+        Self::lock_hierarchy(&mut tx)
+            .await
+            .map_err(Self::database_error("削除"))?;
 
-            tree.sort_by_key(|t| t.sort_order);
-            tree
+        // 行ロックにより、削除が終わるまでこのカテゴリへの商品の追加を待たせる
+        let locked = sqlx::query(
+            "SELECT id FROM categories
+             WHERE id = $1 AND ($2::BIGINT IS NULL OR version = $2)
+             FOR UPDATE",
+        )
+        .bind(id)
+        .bind(expected_version)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::database_error("削除"))?;
+        if locked.is_none() {
+            let _ = tx.rollback().await;
+            return match expected_version {
+                Some(version) => Err(self.update_conflict(id, version).await),
+                None => Ok(false),
+            };
+        }
+
+        let children = sqlx::query("SELECT COUNT(*) AS count FROM categories WHERE parent_id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(Self::database_error("削除"))?;
+        let children_count: i64 = children.get("count");
+        if children_count > 0 {
+            let _ = tx.rollback().await;
+            return Err(CategoryError::HasChildren(
+                "子カテゴリが存在するため削除できません".to_string(),
+            ));
+        }
+
+        Self::release_products(&mut tx, id, disposition, ctx).await?;
+
+        sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::database_error("削除"))?;
+        append_event(&mut tx, &DomainEvent::category_deleted(id))
+            .await
+            .map_err(Self::database_error("削除"))?;
+        tx.commit().await.map_err(Self::database_error("削除"))?;
+        Ok(true)
+    }
+
+    fn database_error(operation: &'static str) -> impl Fn(sqlx::Error) -> CategoryError {
+        move |e| {
+            error!("Error on category {}: {}", operation, e);
+            CategoryError::NotFound(format!("カテゴリの{}に失敗しました: {}", operation, e))
+        }
+    }
+
+    /// 階層を変更するトランザクションを直列化し、閉包テーブルの検証と更新の間に他の移動が割り込まないようにする
+    async fn lock_hierarchy(tx: &mut Transaction<'_, Postgres>) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(HIERARCHY_LOCK_KEY)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// 親の存在・循環参照・移動後の階層数を閉包テーブルから1回のクエリで求める
+    async fn placement<'e, E>(
+        executor: E,
+        id: Option<&str>,
+        parent_id: &str,
+    ) -> Result<Placement, CategoryError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        let query = "SELECT EXISTS (SELECT 1 FROM categories WHERE id = $2) AS parent_exists,
+                            EXISTS (SELECT 1 FROM category_closure
+                                    WHERE ancestor_id = $1 AND descendant_id = $2) AS circular,
+                            (SELECT COUNT(*) FROM category_closure WHERE descendant_id = $2) AS parent_depth,
+                            (SELECT COALESCE(MAX(depth), 0) FROM category_closure
+                             WHERE ancestor_id = $1) AS subtree_height";
+
+        let row = sqlx::query(query)
+            .bind(id)
+            .bind(parent_id)
+            .fetch_one(executor)
+            .await
+            .map_err(Self::database_error("階層の検証"))?;
+        let subtree_height: i32 = row.get("subtree_height");
+        Ok(Placement {
+            is_self: id == Some(parent_id),
+            parent_exists: row.get("parent_exists"),
+            circular: row.get("circular"),
+            depth: row.get::<i64, _>("parent_depth") + 1 + i64::from(subtree_height),
         })
     }
 
-    async fn get_category_ancestors(
+    /// 新しいカテゴリについて、親の祖先すべてと自分自身への経路を閉包テーブルに追加する
+    async fn insert_closure(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO category_closure (ancestor_id, descendant_id, depth)
+             SELECT ancestor_id, $1, depth + 1 FROM category_closure WHERE descendant_id = $2
+             UNION ALL
+             SELECT $1, $1, 0",
+        )
+        .bind(id)
+        .bind(parent_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// サブツリーを旧親の祖先から切り離し、新しい親の祖先へ付け替える
+    async fn relink_closure(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        new_parent_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM category_closure
+             WHERE descendant_id IN (SELECT descendant_id FROM category_closure WHERE ancestor_id = $1)
+               AND ancestor_id NOT IN (SELECT descendant_id FROM category_closure WHERE ancestor_id = $1)",
+        )
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO category_closure (ancestor_id, descendant_id, depth)
+             SELECT a.ancestor_id, d.descendant_id, a.depth + d.depth + 1
+             FROM category_closure a
+             CROSS JOIN category_closure d
+             WHERE a.descendant_id = $2 AND d.ancestor_id = $1",
+        )
+        .bind(id)
+        .bind(new_parent_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// 更新できなかった理由（存在しない・バージョン不一致）を調べる
+    async fn update_conflict(&self, id: &str, version: i64) -> CategoryError {
+        match self.find_by_id(id).await {
+            Some(current) => current
+                .check_version(Some(version))
+                .err()
+                .unwrap_or_else(|| CategoryError::NotFound("カテゴリが見つかりません".to_string())),
+            None => CategoryError::NotFound("カテゴリが見つかりません".to_string()),
+        }
+    }
+
+    /// 楽観的排他制御付きで更新し、同じトランザクションでドメインイベントを outbox に書き込む
+    ///
+    /// 親カテゴリは閉包テーブルと一緒に変更する必要があるため、ここでは更新しない（move_category を使う）。
+    async fn save(
         &self,
+        category: Category,
+        event: impl FnOnce(&Category) -> DomainEvent,
+    ) -> Result<Category, CategoryError> {
+        let db_error = Self::database_error("更新");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+
+        // 読み込み時のバージョンと一致する場合のみ更新する（楽観的排他制御）
+        let previous =
+            sqlx::query("SELECT slug FROM categories WHERE id = $1 AND version = $2 FOR UPDATE")
+                .bind(&category.id)
+                .bind(category.version)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(&db_error)?;
+        let Some(previous) = previous else {
+            return Err(self.update_conflict(&category.id, category.version).await);
+        };
+        let previous_slug: String = previous.get("slug");
+        if previous_slug != category.slug {
+            Self::ensure_unique_slug(
+                &mut tx,
+                &category.slug,
+                category.parent_id.as_deref(),
+                Some(&category.id),
+            )
+            .await?;
+            Self::keep_old_slug(
+                &mut tx,
+                category.parent_id.as_deref(),
+                &previous_slug,
+                &category.id,
+                None,
+            )
+            .await
+            .map_err(&db_error)?;
+        }
+
+        let query = "UPDATE categories 
+                     SET name = $2, slug = $3, description = $4, sort_order = $5, is_active = $6, updated_at = $7,
+                         version = version + 1
+                     WHERE id = $1
+                     RETURNING id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version";
+        let row = sqlx::query(query)
+            .bind(&category.id)
+            .bind(&category.name)
+            .bind(&category.slug)
+            .bind(&category.description)
+            .bind(category.sort_order)
+            .bind(category.is_active)
+            .bind(Utc::now())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+        let updated = Self::row_to_category(&row);
+        append_event(&mut tx, &event(&updated))
+            .await
+            .map_err(&db_error)?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(updated)
+    }
+
+    /// 同じ親の下に同名のカテゴリがないことを確認する（ルート直下は UNIQUE 制約が効かないため明示的に調べる）
+    async fn ensure_unique_name(
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+        parent_id: Option<&str>,
+        exclude_id: Option<&str>,
+    ) -> Result<(), CategoryError> {
+        let row = sqlx::query(
+            "SELECT EXISTS (
+                 SELECT 1 FROM categories
+                 WHERE name = $1 AND parent_id IS NOT DISTINCT FROM $2 AND id IS DISTINCT FROM $3
+             ) AS taken",
+        )
+        .bind(name)
+        .bind(parent_id)
+        .bind(exclude_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Self::database_error("名前の検証"))?;
+        if row.get::<bool, _>("taken") {
+            return Err(CategoryError::NameDuplicate(
+                "同一階層内に同じ名前のカテゴリが既に存在します".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 同じ親の下に同じスラッグのカテゴリがないことを確認する
+    async fn ensure_unique_slug(
+        tx: &mut Transaction<'_, Postgres>,
+        slug: &str,
+        parent_id: Option<&str>,
+        exclude_id: Option<&str>,
+    ) -> Result<(), CategoryError> {
+        let row = sqlx::query(
+            "SELECT EXISTS (
+                 SELECT 1 FROM categories
+                 WHERE COALESCE(parent_id, '') = COALESCE($2, '') AND slug = $1
+                   AND id IS DISTINCT FROM $3
+             ) AS taken",
+        )
+        .bind(slug)
+        .bind(parent_id)
+        .bind(exclude_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Self::database_error("スラッグの検証"))?;
+        if row.get::<bool, _>("taken") {
+            return Err(CategoryError::SlugDuplicate(format!(
+                "同一階層内にスラッグ「{}」のカテゴリが既に存在します",
+                slug
+            )));
+        }
+        Ok(())
+    }
+
+    /// 同じ親の下で使われていないスラッグを探す（使われていれば `-2` などの連番を付ける）
+    async fn available_slug(
+        tx: &mut Transaction<'_, Postgres>,
+        base: &str,
+        parent_id: Option<&str>,
+    ) -> Result<String, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT slug FROM categories
+             WHERE COALESCE(parent_id, '') = COALESCE($2, '')
+               AND (slug = $1 OR slug LIKE $1 || '-%')",
+        )
+        .bind(base)
+        .bind(parent_id)
+        .fetch_all(&mut **tx)
+        .await?;
+        let taken: HashSet<String> = rows.iter().map(|row| row.get("slug")).collect();
+        Ok((1..)
+            .map(|n| slug::with_suffix(base, n))
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or_else(|| base.to_string()))
+    }
+
+    /// 変更前のスラッグを旧スラッグとして残し、以前の URL から転送できるようにする
+    ///
+    /// `merged_from` は統合で削除されたカテゴリのスラッグを統合先へ転送する場合の統合元 ID。
+    async fn keep_old_slug(
+        tx: &mut Transaction<'_, Postgres>,
+        parent_id: Option<&str>,
+        slug: &str,
         category_id: &str,
-    ) -> Result<Vec<String>, CategoryError> {
-        let mut ancestors = Vec::new();
-        let mut current_id = Some(category_id.to_string());
-
-        while let Some(id) = current_id {
-            if let Some(category) = self.find_by_id(&id).await {
-                ancestors.push(category.id);
-                current_id = category.parent_id;
-            } else {
-                break;
-            }
+        merged_from: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO category_slug_redirects (parent_id, slug, category_id, merged_from)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT ((COALESCE(parent_id, '')), slug)
+             DO UPDATE SET category_id = EXCLUDED.category_id, merged_from = EXCLUDED.merged_from,
+                           created_at = CURRENT_TIMESTAMP",
+        )
+        .bind(parent_id)
+        .bind(slug)
+        .bind(category_id)
+        .bind(merged_from)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// 親の下に置く位置を、指定された並び順と兄弟の数から決める
+    async fn sibling_position(
+        tx: &mut Transaction<'_, Postgres>,
+        parent_id: Option<&str>,
+        exclude_id: &str,
+        sort_order: i32,
+    ) -> Result<i32, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM categories
+             WHERE parent_id IS NOT DISTINCT FROM $1 AND id <> $2",
+        )
+        .bind(parent_id)
+        .bind(exclude_id)
+        .fetch_one(&mut **tx)
+        .await?;
+        let count: i64 = row.get("count");
+        Ok(i64::from(sort_order.max(0)).min(count) as i32)
+    }
+
+    /// 兄弟カテゴリの並び順を 0 からの連番に振り直し、`slot` の位置を空けておく
+    ///
+    /// 並び順が変わったカテゴリには更新イベントを書き込む。
+    async fn renumber_siblings(
+        tx: &mut Transaction<'_, Postgres>,
+        parent_id: Option<&str>,
+        exclude_id: &str,
+        slot: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query(
+            "WITH ordered AS (
+                 SELECT id, (ROW_NUMBER() OVER (ORDER BY sort_order, name, id) - 1)::int AS position
+                 FROM categories
+                 WHERE parent_id IS NOT DISTINCT FROM $1 AND id <> $2
+             ),
+             renumbered AS (
+                 SELECT id,
+                        CASE WHEN $3::int IS NOT NULL AND position >= $3 THEN position + 1
+                             ELSE position END AS sort_order
+                 FROM ordered
+             )
+             UPDATE categories c
+             SET sort_order = r.sort_order, updated_at = NOW(), version = c.version + 1
+             FROM renumbered r
+             WHERE c.id = r.id AND c.sort_order <> r.sort_order
+             RETURNING c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active,
+                       c.created_at, c.updated_at, c.version",
+        )
+        .bind(parent_id)
+        .bind(exclude_id)
+        .bind(slot)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for row in rows {
+            let sibling = Self::row_to_category(&row);
+            append_event(tx, &DomainEvent::category_updated(&sibling)).await?;
         }
+        Ok(())
+    }
 
-        ancestors.reverse();
-        Ok(ancestors)
+    /// カテゴリ行をロックして取得する
+    async fn lock_category(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> Result<Option<Category>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version
+             FROM categories
+             WHERE id = $1
+             FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(row.as_ref().map(Self::row_to_category))
+    }
+}
+
+/// 閉包テーブルから求めた、親カテゴリの下に置いたときの検証結果
+struct Placement {
+    is_self: bool,
+    parent_exists: bool,
+    circular: bool,
+    /// 移動するサブツリーの最も深いカテゴリが位置する階層
+    depth: i64,
+}
+
+impl Placement {
+    fn ensure_parent_exists(&self) -> Result<(), CategoryError> {
+        if !self.parent_exists {
+            return Err(CategoryError::NotFound(
+                "親カテゴリが見つかりません".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn ensure_acyclic(&self) -> Result<(), CategoryError> {
+        if self.is_self {
+            return Err(CategoryError::CircularReference(
+                "自分自身を親カテゴリに設定することはできません".to_string(),
+            ));
+        }
+        self.ensure_parent_exists()?;
+        if self.circular {
+            return Err(CategoryError::CircularReference(
+                "循環参照が発生するため、この操作は実行できません".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn ensure_depth(&self) -> Result<(), CategoryError> {
+        if self.depth > MAX_DEPTH {
+            return Err(CategoryError::MaxDepthExceeded(format!(
+                "最大階層数({}階層)を超過しています",
+                MAX_DEPTH
+            )));
+        }
+        Ok(())
     }
 }
 
@@ -107,11 +887,11 @@ impl PostgresCategoryRepository {
 impl CategoryRepository for PostgresCategoryRepository {
     async fn find_all(&self, include_inactive: bool) -> Vec<Category> {
         let query = if include_inactive {
-            "SELECT id, name, description, parent_id, sort_order, is_active, created_at, updated_at 
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version 
              FROM categories 
              ORDER BY sort_order, name"
         } else {
-            "SELECT id, name, description, parent_id, sort_order, is_active, created_at, updated_at 
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version 
              FROM categories 
              WHERE is_active = true 
              ORDER BY sort_order, name"
@@ -127,7 +907,7 @@ impl CategoryRepository for PostgresCategoryRepository {
     }
 
     async fn find_by_id(&self, id: &str) -> Option<Category> {
-        let query = "SELECT id, name, description, parent_id, sort_order, is_active, created_at, updated_at 
+        let query = "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version 
                      FROM categories 
                      WHERE id = $1";
 
@@ -147,12 +927,12 @@ impl CategoryRepository for PostgresCategoryRepository {
         include_inactive: bool,
     ) -> Vec<Category> {
         let query = if include_inactive {
-            "SELECT id, name, description, parent_id, sort_order, is_active, created_at, updated_at 
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version 
              FROM categories 
              WHERE ($1::varchar IS NULL AND parent_id IS NULL) OR parent_id = $1
              ORDER BY sort_order, name"
         } else {
-            "SELECT id, name, description, parent_id, sort_order, is_active, created_at, updated_at 
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version 
              FROM categories 
              WHERE (($1::varchar IS NULL AND parent_id IS NULL) OR parent_id = $1) AND is_active = true
              ORDER BY sort_order, name"
@@ -175,20 +955,109 @@ impl CategoryRepository for PostgresCategoryRepository {
         }
     }
 
+    async fn find_by_slug(&self, parent_id: Option<String>, slug: &str) -> Option<Category> {
+        let query = "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version
+                     FROM categories
+                     WHERE COALESCE(parent_id, '') = COALESCE($1, '') AND slug = $2";
+
+        match sqlx::query(query)
+            .bind(&parent_id)
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await
+        {
+            Ok(row) => row.as_ref().map(Self::row_to_category),
+            Err(e) => {
+                error!("Error finding category by slug {}: {}", slug, e);
+                None
+            }
+        }
+    }
+
+    async fn find_by_old_slug(
+        &self,
+        parent_id: Option<String>,
+        slug: &str,
+    ) -> Option<CategoryRedirect> {
+        let query = "SELECT c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active, c.created_at, c.updated_at, c.version,
+                            r.merged_from
+                     FROM category_slug_redirects r
+                     JOIN categories c ON c.id = r.category_id
+                     WHERE COALESCE(r.parent_id, '') = COALESCE($1, '') AND r.slug = $2";
+
+        match sqlx::query(query)
+            .bind(&parent_id)
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await
+        {
+            Ok(row) => row.as_ref().map(|row| CategoryRedirect {
+                category: Self::row_to_category(row),
+                merged_from: row.get("merged_from"),
+            }),
+            Err(e) => {
+                error!("Error finding category by old slug {}: {}", slug, e);
+                None
+            }
+        }
+    }
+
     // async fn find_children(&self, id: &str, include_inactive: bool) -> Vec<Category> {
     //     self.find_by_parent_id(Some(id.to_string()), include_inactive).await
     // }
 
     async fn find_path(&self, id: &str) -> Result<CategoryPath, CategoryError> {
-        match self.get_category_ancestors(id).await {
-            Ok(ancestors) => Ok(CategoryPath::new(ancestors)),
-            Err(e) => Err(e),
+        let ancestors = self.find_ancestors(id).await?;
+        Ok(CategoryPath::new(
+            ancestors.into_iter().map(|category| category.id).collect(),
+        ))
+    }
+
+    async fn find_ancestors(&self, id: &str) -> Result<Vec<Category>, CategoryError> {
+        let query = "SELECT c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active, c.created_at, c.updated_at, c.version
+                     FROM category_closure cc
+                     JOIN categories c ON c.id = cc.ancestor_id
+                     WHERE cc.descendant_id = $1
+                     ORDER BY cc.depth DESC";
+
+        sqlx::query(query)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map(|rows| rows.iter().map(Self::row_to_category).collect())
+            .map_err(Self::database_error("祖先の取得"))
+    }
+
+    async fn find_descendants(&self, id: &str, include_inactive: bool) -> Vec<Category> {
+        let query = "SELECT c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active, c.created_at, c.updated_at, c.version
+                     FROM category_closure cc
+                     JOIN categories c ON c.id = cc.descendant_id
+                     WHERE cc.ancestor_id = $1 AND cc.depth > 0 AND ($2 OR c.is_active)
+                     ORDER BY cc.depth, c.sort_order, c.name";
+
+        match sqlx::query(query)
+            .bind(id)
+            .bind(include_inactive)
+            .fetch_all(&self.pool)
+            .await
+        {
+            Ok(rows) => rows.iter().map(Self::row_to_category).collect(),
+            Err(e) => {
+                error!("Error finding descendants of category {}: {}", id, e);
+                vec![]
+            }
         }
     }
 
     async fn find_tree(&self, include_inactive: bool) -> Vec<CategoryTree> {
-        let categories = self.find_all(include_inactive).await;
-        self.build_category_tree_recursive(&categories, None).await
+        // 子孫を含む商品数は非表示のカテゴリも含めて集計する
+        let all = self.find_all(true).await;
+        let product_counts = self.product_counts().await;
+        let categories: Vec<Category> = all
+            .into_iter()
+            .filter(|c| include_inactive || c.is_active)
+            .collect();
+        Self::build_tree(&categories, &product_counts)
     }
 
     async fn exists_by_name_and_parent(
@@ -246,46 +1115,48 @@ impl CategoryRepository for PostgresCategoryRepository {
         // Validate category
         category.validate()?;
 
+        let db_error = Self::database_error("作成");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        Self::lock_hierarchy(&mut tx).await.map_err(&db_error)?;
+
         // Check for duplicate name in same parent
-        if self
-            .exists_by_name_and_parent(&category.name, category.parent_id.clone(), None)
-            .await
-        {
-            return Err(CategoryError::NameDuplicate(
-                "同一階層内に同じ名前のカテゴリが既に存在します".to_string(),
-            ));
-        }
+        Self::ensure_unique_name(&mut tx, &category.name, category.parent_id.as_deref(), None)
+            .await?;
+        Self::ensure_unique_slug(&mut tx, &category.slug, category.parent_id.as_deref(), None)
+            .await?;
 
-        // Validate depth if has parent
-        if category.parent_id.is_some() {
-            self.validate_depth(category.parent_id.clone()).await?;
+        if let Some(parent_id) = &category.parent_id {
+            let placement = Self::placement(&mut *tx, None, parent_id).await?;
+            placement.ensure_acyclic()?;
+            placement.ensure_depth()?;
         }
 
-        let query = "INSERT INTO categories (id, name, description, parent_id, sort_order, is_active, created_at, updated_at)
-                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                     RETURNING id, name, description, parent_id, sort_order, is_active, created_at, updated_at";
+        let query = "INSERT INTO categories (id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     RETURNING id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version";
 
-        match sqlx::query(query)
+        let row = sqlx::query(query)
             .bind(&category.id)
             .bind(&category.name)
+            .bind(&category.slug)
             .bind(&category.description)
             .bind(&category.parent_id)
             .bind(category.sort_order)
             .bind(category.is_active)
             .bind(category.created_at)
             .bind(category.updated_at)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await
-        {
-            Ok(row) => Ok(Self::row_to_category(&row)),
-            Err(e) => {
-                error!("Error creating category: {}", e);
-                Err(CategoryError::NotFound(format!(
-                    "カテゴリの作成に失敗しました: {}",
-                    e
-                )))
-            }
-        }
+            .map_err(&db_error)?;
+        let created = Self::row_to_category(&row);
+        Self::insert_closure(&mut tx, &created.id, created.parent_id.as_deref())
+            .await
+            .map_err(&db_error)?;
+        append_event(&mut tx, &DomainEvent::category_created(&created))
+            .await
+            .map_err(&db_error)?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(created)
     }
 
     async fn update(&self, category: Category) -> Result<Category, CategoryError> {
@@ -306,95 +1177,684 @@ impl CategoryRepository for PostgresCategoryRepository {
             ));
         }
 
-        let query = "UPDATE categories 
-                     SET name = $2, description = $3, parent_id = $4, sort_order = $5, is_active = $6, updated_at = $7
-                     WHERE id = $1
-                     RETURNING id, name, description, parent_id, sort_order, is_active, created_at, updated_at";
+        self.save(category, DomainEvent::category_updated).await
+    }
 
-        match sqlx::query(query)
+    async fn delete(
+        &self,
+        id: &str,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<bool, CategoryError> {
+        self.remove(id, None, expected_version, ctx).await
+    }
+
+    async fn delete_with_products(
+        &self,
+        id: &str,
+        disposition: ProductDisposition,
+        expected_version: Option<i64>,
+        ctx: &ChangeContext,
+    ) -> Result<bool, CategoryError> {
+        self.remove(id, Some(&disposition), expected_version, ctx)
+            .await
+    }
+
+    async fn move_category(
+        &self,
+        id: &str,
+        new_parent_id: Option<String>,
+        new_sort_order: i32,
+        expected_version: Option<i64>,
+    ) -> Result<Category, CategoryError> {
+        let db_error = Self::database_error("移動");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        Self::lock_hierarchy(&mut tx).await.map_err(&db_error)?;
+
+        let category = Self::lock_category(&mut tx, id)
+            .await
+            .map_err(&db_error)?
+            .ok_or_else(|| CategoryError::NotFound("カテゴリが見つかりません".to_string()))?;
+        // ロックした行で照合するため、照合から更新までの間に他の更新は入らない
+        category.check_version(expected_version)?;
+        let previous_parent_id = category.parent_id;
+
+        // 循環参照と、サブツリー全体が最大階層数に収まるかを閉包テーブルで検証する
+        if let Some(parent_id) = &new_parent_id {
+            let placement = Self::placement(&mut *tx, Some(id), parent_id).await?;
+            placement.ensure_acyclic()?;
+            placement.ensure_depth()?;
+        }
+        Self::ensure_unique_name(&mut tx, &category.name, new_parent_id.as_deref(), Some(id))
+            .await?;
+        Self::ensure_unique_slug(&mut tx, &category.slug, new_parent_id.as_deref(), Some(id))
+            .await?;
+
+        let position =
+            Self::sibling_position(&mut tx, new_parent_id.as_deref(), id, new_sort_order)
+                .await
+                .map_err(&db_error)?;
+        let row = sqlx::query(
+            "UPDATE categories
+             SET parent_id = $2, sort_order = $3, updated_at = $4, version = version + 1
+             WHERE id = $1
+             RETURNING id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version",
+        )
+        .bind(id)
+        .bind(&new_parent_id)
+        .bind(position)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        let moved = Self::row_to_category(&row);
+
+        // 移動先の兄弟は移動したカテゴリの位置を空けて、移動元の兄弟は詰めて振り直す
+        Self::renumber_siblings(&mut tx, moved.parent_id.as_deref(), id, Some(position))
+            .await
+            .map_err(&db_error)?;
+        if previous_parent_id != moved.parent_id {
+            Self::renumber_siblings(&mut tx, previous_parent_id.as_deref(), id, None)
+                .await
+                .map_err(&db_error)?;
+            Self::relink_closure(&mut tx, id, moved.parent_id.as_deref())
+                .await
+                .map_err(&db_error)?;
+            Self::keep_old_slug(
+                &mut tx,
+                previous_parent_id.as_deref(),
+                &moved.slug,
+                id,
+                None,
+            )
+            .await
+            .map_err(&db_error)?;
+        }
+        append_event(
+            &mut tx,
+            &DomainEvent::category_moved(&moved, previous_parent_id.as_deref()),
+        )
+        .await
+        .map_err(&db_error)?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(moved)
+    }
+
+    async fn copy_subtree(
+        &self,
+        id: &str,
+        new_parent_id: Option<String>,
+        new_name: Option<String>,
+        new_sort_order: i32,
+    ) -> Result<Category, CategoryError> {
+        let db_error = Self::database_error("複製");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        Self::lock_hierarchy(&mut tx).await.map_err(&db_error)?;
+
+        // 親から先に作成できるよう浅い順に読み込む
+        let rows = sqlx::query(
+            "SELECT c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active,
+                    c.created_at, c.updated_at, c.version
+             FROM category_closure cc
+             JOIN categories c ON c.id = cc.descendant_id
+             WHERE cc.ancestor_id = $1
+             ORDER BY cc.depth, c.sort_order, c.name",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        let subtree: Vec<Category> = rows.iter().map(Self::row_to_category).collect();
+        let Some(source) = subtree.first() else {
+            return Err(CategoryError::NotFound(
+                "カテゴリが見つかりません".to_string(),
+            ));
+        };
+
+        // 複製はもとのサブツリーと別物になるため、自分の子孫の下にも置ける（階層数だけを検証する）
+        if let Some(parent_id) = &new_parent_id {
+            let placement = Self::placement(&mut *tx, Some(id), parent_id).await?;
+            placement.ensure_parent_exists()?;
+            placement.ensure_depth()?;
+        }
+
+        let now = Utc::now();
+        let renamed = new_name.is_some();
+        let mut root = Category::new(
+            format!("cat_{}", Uuid::new_v4()),
+            new_name.unwrap_or_else(|| source.name.clone()),
+            source.description.clone(),
+            new_parent_id.clone(),
+            0,
+        );
+        // 新しい名前から生成できない場合（漢字を含むなど）も複製元のスラッグを引き継ぐ
+        if !renamed || root.slug.is_empty() {
+            root.slug = source.slug.clone();
+        }
+        root.validate()?;
+        Self::ensure_unique_name(&mut tx, &root.name, root.parent_id.as_deref(), None).await?;
+        // 複製先の兄弟とスラッグが重なる場合は連番を付ける（子孫は新しい親の下なので重ならない）
+        root.slug = Self::available_slug(&mut tx, &root.slug, root.parent_id.as_deref())
+            .await
+            .map_err(&db_error)?;
+        root.sort_order =
+            Self::sibling_position(&mut tx, root.parent_id.as_deref(), &root.id, new_sort_order)
+                .await
+                .map_err(&db_error)?;
+        root.is_active = source.is_active;
+
+        let mut new_ids: HashMap<&str, String> = HashMap::new();
+        new_ids.insert(source.id.as_str(), root.id.clone());
+        let mut copies = vec![root];
+        for category in &subtree[1..] {
+            let new_id = format!("cat_{}", Uuid::new_v4());
+            let parent_id = category
+                .parent_id
+                .as_deref()
+                .and_then(|parent_id| new_ids.get(parent_id))
+                .cloned();
+            new_ids.insert(category.id.as_str(), new_id.clone());
+            copies.push(Category {
+                id: new_id,
+                parent_id,
+                created_at: now,
+                updated_at: now,
+                version: 1,
+                ..category.clone()
+            });
+        }
+
+        let query = "INSERT INTO categories (id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     RETURNING id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version";
+        let mut created = Vec::with_capacity(copies.len());
+        for (original, copy) in subtree.iter().zip(&copies) {
+            let row = sqlx::query(query)
+                .bind(&copy.id)
+                .bind(&copy.name)
+                .bind(&copy.slug)
+                .bind(&copy.description)
+                .bind(&copy.parent_id)
+                .bind(copy.sort_order)
+                .bind(copy.is_active)
+                .bind(copy.created_at)
+                .bind(copy.updated_at)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(&db_error)?;
+            let category = Self::row_to_category(&row);
+            Self::insert_closure(&mut tx, &category.id, category.parent_id.as_deref())
+                .await
+                .map_err(&db_error)?;
+            sqlx::query(
+                "INSERT INTO category_attribute_definitions
+                     (category_id, name, attribute_type, allowed_values, required, unit, sort_order)
+                 SELECT $1, name, attribute_type, allowed_values, required, unit, sort_order
+                 FROM category_attribute_definitions
+                 WHERE category_id = $2",
+            )
             .bind(&category.id)
-            .bind(&category.name)
-            .bind(&category.description)
-            .bind(&category.parent_id)
-            .bind(category.sort_order)
-            .bind(category.is_active)
-            .bind(Utc::now())
-            .fetch_optional(&self.pool)
+            .bind(&original.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+            // 名前を変えて複製したルートには元の名前の翻訳を引き継がない
+            sqlx::query(
+                "INSERT INTO category_translations (category_id, locale, name, description)
+                 SELECT $1, locale, CASE WHEN $3 THEN NULL ELSE name END, description
+                 FROM category_translations
+                 WHERE category_id = $2 AND (NOT $3 OR description IS NOT NULL)",
+            )
+            .bind(&category.id)
+            .bind(&original.id)
+            .bind(renamed && original.id == source.id)
+            .execute(&mut *tx)
             .await
+            .map_err(&db_error)?;
+            append_event(&mut tx, &DomainEvent::category_created(&category))
+                .await
+                .map_err(&db_error)?;
+            created.push(category);
+        }
+
+        let root = created.swap_remove(0);
+        Self::renumber_siblings(
+            &mut tx,
+            root.parent_id.as_deref(),
+            &root.id,
+            Some(root.sort_order),
+        )
+        .await
+        .map_err(&db_error)?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(root)
+    }
+
+    async fn merge_category(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<Category, CategoryError> {
+        if source_id == target_id {
+            return Err(CategoryError::CircularReference(
+                "統合元と統合先に同じカテゴリは指定できません".to_string(),
+            ));
+        }
+
+        let db_error = Self::database_error("統合");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        Self::lock_hierarchy(&mut tx).await.map_err(&db_error)?;
+
+        let source = Self::lock_category(&mut tx, source_id)
+            .await
+            .map_err(&db_error)?
+            .ok_or_else(|| CategoryError::NotFound("カテゴリが見つかりません".to_string()))?;
+        Self::lock_category(&mut tx, target_id)
+            .await
+            .map_err(&db_error)?
+            .ok_or_else(|| {
+                CategoryError::NotFound("統合先のカテゴリが見つかりません".to_string())
+            })?;
+
+        // 統合元の子は統合先の子になるため、統合元のサブツリーより1階層浅く収まればよい
+        let placement = Self::placement(&mut *tx, Some(source_id), target_id).await?;
+        placement.ensure_acyclic()?;
+        Placement {
+            depth: placement.depth - 1,
+            ..placement
+        }
+        .ensure_depth()?;
+
+        let duplicate = sqlx::query(
+            "SELECT c.name FROM categories c
+             JOIN categories t ON t.name = c.name AND t.parent_id = $2
+             WHERE c.parent_id = $1
+             LIMIT 1",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        if let Some(row) = duplicate {
+            let name: String = row.get("name");
+            return Err(CategoryError::NameDuplicate(format!(
+                "統合先に同じ名前のカテゴリ「{}」が既に存在します",
+                name
+            )));
+        }
+
+        // 名前を変えてもスラッグは変わらないため、統合先の子と重なるスラッグは連番を付けて移す
+        let siblings = sqlx::query(
+            "SELECT id, slug, parent_id = $1 AS from_source FROM categories
+             WHERE parent_id IN ($1, $2)",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        let mut taken: HashSet<String> = siblings.iter().map(|row| row.get("slug")).collect();
+        let target_slugs: HashSet<String> = siblings
+            .iter()
+            .filter(|row| !row.get::<bool, _>("from_source"))
+            .map(|row| row.get("slug"))
+            .collect();
+        for row in siblings
+            .iter()
+            .filter(|row| row.get::<bool, _>("from_source"))
         {
-            Ok(Some(row)) => Ok(Self::row_to_category(&row)),
-            Ok(None) => Err(CategoryError::NotFound(
-                "カテゴリが見つかりません".to_string(),
-            )),
-            Err(e) => {
-                error!("Error updating category {}: {}", category.id, e);
-                Err(CategoryError::NotFound(format!(
-                    "カテゴリの更新に失敗しました: {}",
-                    e
-                )))
+            let current: String = row.get("slug");
+            if !target_slugs.contains(&current) {
+                continue;
             }
+            let child_id: String = row.get("id");
+            let renamed = (2..)
+                .map(|n| slug::with_suffix(&current, n))
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or_else(|| current.clone());
+            sqlx::query("UPDATE categories SET slug = $1 WHERE id = $2")
+                .bind(&renamed)
+                .bind(&child_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(&db_error)?;
+            // 統合元の下にあった旧スラッグから転送できるようにする。統合先の同じスラッグの子に
+            // 隠れないよう、解決時は統合元の旧スラッグをたどった場合に先にこちらを探す
+            Self::keep_old_slug(&mut tx, Some(source_id), &current, &child_id, None)
+                .await
+                .map_err(&db_error)?;
+            taken.insert(renamed);
         }
+
+        // 統合元の子は並び順を保ったまま統合先の子の後ろに並べる
+        let rows = sqlx::query(
+            "WITH ordered AS (
+                 SELECT id, (ROW_NUMBER() OVER (ORDER BY sort_order, name, id) - 1)::int AS position
+                 FROM categories
+                 WHERE parent_id = $1
+             )
+             UPDATE categories c
+             SET parent_id = $2,
+                 sort_order = o.position + (SELECT COUNT(*) FROM categories WHERE parent_id = $2)::int,
+                 updated_at = NOW(),
+                 version = c.version + 1
+             FROM ordered o
+             WHERE c.id = o.id
+             RETURNING c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active,
+                       c.created_at, c.updated_at, c.version",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        for row in rows {
+            let child = Self::row_to_category(&row);
+            Self::relink_closure(&mut tx, &child.id, Some(target_id))
+                .await
+                .map_err(&db_error)?;
+            append_event(
+                &mut tx,
+                &DomainEvent::category_moved(&child, Some(source_id)),
+            )
+            .await
+            .map_err(&db_error)?;
+        }
+
+        Self::release_products(
+            &mut tx,
+            source_id,
+            Some(&ProductDisposition::Reassign(target_id.to_string())),
+            ctx,
+        )
+        .await?;
+
+        // 統合元の URL（旧スラッグを含む）は統合先へ転送し、統合元の ID を残す
+        sqlx::query(
+            "UPDATE category_slug_redirects
+             SET category_id = $2, merged_from = COALESCE(merged_from, $1)
+             WHERE category_id = $1",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+        Self::keep_old_slug(
+            &mut tx,
+            source.parent_id.as_deref(),
+            &source.slug,
+            target_id,
+            Some(source_id),
+        )
+        .await
+        .map_err(&db_error)?;
+        append_event(&mut tx, &DomainEvent::category_deleted(source_id))
+            .await
+            .map_err(&db_error)?;
+
+        let target = Self::lock_category(&mut tx, target_id)
+            .await
+            .map_err(&db_error)?
+            .ok_or_else(|| {
+                CategoryError::NotFound("統合先のカテゴリが見つかりません".to_string())
+            })?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(target)
     }
 
-    async fn delete(&self, id: &str) -> Result<bool, CategoryError> {
-        // Check if category has children
-        let children_count = self.count_children(id).await;
-        if children_count > 0 {
-            return Err(CategoryError::HasChildren(
-                "子カテゴリが存在するため削除できません".to_string(),
+    async fn reorder_children(
+        &self,
+        parent_id: Option<String>,
+        child_ids: Vec<String>,
+    ) -> Result<Vec<Category>, CategoryError> {
+        let db_error = Self::database_error("並び替え");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        // 子カテゴリの追加・移動と並行して一覧が変わらないようにする
+        Self::lock_hierarchy(&mut tx).await.map_err(&db_error)?;
+
+        if let Some(parent_id) = &parent_id {
+            Self::lock_category(&mut tx, parent_id)
+                .await
+                .map_err(&db_error)?
+                .ok_or_else(|| CategoryError::NotFound("親カテゴリが見つかりません".to_string()))?;
+        }
+
+        let rows = sqlx::query("SELECT id FROM categories WHERE parent_id IS NOT DISTINCT FROM $1")
+            .bind(&parent_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+        let current: HashSet<String> = rows.iter().map(|row| row.get("id")).collect();
+        let requested: HashSet<&str> = child_ids.iter().map(String::as_str).collect();
+        if requested.len() != child_ids.len() {
+            return Err(CategoryError::InvalidSortOrder(
+                "同じカテゴリが複数回指定されています".to_string(),
             ));
         }
+        let mut missing: Vec<&str> = current
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !requested.contains(id))
+            .collect();
+        missing.sort_unstable();
+        let unknown: Vec<&str> = child_ids
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !current.contains(*id))
+            .collect();
+        if !missing.is_empty() || !unknown.is_empty() {
+            return Err(CategoryError::InvalidSortOrder(format!(
+                "子カテゴリの一覧が現在の子カテゴリと一致しません（不足: [{}], 対象外: [{}]）",
+                missing.join(", "),
+                unknown.join(", ")
+            )));
+        }
 
-        // Check if category has products (for now, we'll skip this check as we don't have products yet)
-        // let products_count = self.count_products(id).await;
-        // if products_count > 0 {
-        //     return Err(CategoryError::HasProducts(
-        //         "商品が紐づいているため削除できません".to_string(),
-        //     ));
-        // }
+        let rows = sqlx::query(
+            "UPDATE categories c
+             SET sort_order = (o.position - 1)::int, updated_at = NOW(), version = c.version + 1
+             FROM UNNEST($1::text[]) WITH ORDINALITY AS o(id, position)
+             WHERE c.id = o.id AND c.sort_order <> (o.position - 1)::int
+             RETURNING c.id, c.name, c.slug, c.description, c.parent_id, c.sort_order, c.is_active,
+                       c.created_at, c.updated_at, c.version",
+        )
+        .bind(&child_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        for row in rows {
+            let child = Self::row_to_category(&row);
+            append_event(&mut tx, &DomainEvent::category_updated(&child))
+                .await
+                .map_err(&db_error)?;
+        }
 
-        let query = "DELETE FROM categories WHERE id = $1";
+        let rows = sqlx::query(
+            "SELECT id, name, slug, description, parent_id, sort_order, is_active, created_at, updated_at, version
+             FROM categories
+             WHERE parent_id IS NOT DISTINCT FROM $1
+             ORDER BY sort_order, name",
+        )
+        .bind(&parent_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&db_error)?;
+        tx.commit().await.map_err(&db_error)?;
+        Ok(rows.iter().map(Self::row_to_category).collect())
+    }
 
-        match sqlx::query(query).bind(id).execute(&self.pool).await {
-            Ok(result) => Ok(result.rows_affected() > 0),
+    async fn find_attribute_definitions(
+        &self,
+        id: &str,
+    ) -> Result<Vec<AttributeDefinition>, CategoryError> {
+        sqlx::query(
+            "SELECT name, attribute_type, allowed_values, required, unit
+             FROM category_attribute_definitions
+             WHERE category_id = $1
+             ORDER BY sort_order",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.iter().map(row_to_attribute_definition).collect())
+        .map_err(Self::database_error("属性定義の取得"))
+    }
+
+    async fn find_attribute_schema(&self, id: &str) -> Result<AttributeSchema, CategoryError> {
+        attribute_schema(&self.pool, id)
+            .await
+            .map_err(Self::database_error("属性定義の取得"))
+    }
+
+    async fn replace_attribute_definitions(
+        &self,
+        id: &str,
+        definitions: Vec<AttributeDefinition>,
+    ) -> Result<Vec<AttributeDefinition>, CategoryError> {
+        let db_error = Self::database_error("属性定義の更新");
+        let mut tx = self.pool.begin().await.map_err(&db_error)?;
+        if Self::lock_category(&mut tx, id)
+            .await
+            .map_err(&db_error)?
+            .is_none()
+        {
+            return Err(CategoryError::NotFound(
+                "カテゴリが見つかりません".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM category_attribute_definitions WHERE category_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+        for (sort_order, definition) in definitions.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO category_attribute_definitions
+                     (category_id, name, attribute_type, allowed_values, required, unit, sort_order)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(id)
+            .bind(&definition.name)
+            .bind(definition.attribute_type.as_str())
+            .bind(&definition.allowed_values)
+            .bind(definition.required)
+            .bind(&definition.unit)
+            .bind(sort_order as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(&db_error)?;
+        }
+        tx.commit().await.map_err(&db_error)?;
+        Ok(definitions)
+    }
+
+    async fn find_translations(&self, id: &str) -> Result<Vec<CategoryTranslation>, CategoryError> {
+        sqlx::query(
+            "SELECT locale, name, description
+             FROM category_translations
+             WHERE category_id = $1
+             ORDER BY locale",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.iter().map(row_to_translation).collect())
+        .map_err(Self::database_error("翻訳の取得"))
+    }
+
+    async fn find_translations_by_locale(
+        &self,
+        locale: &str,
+    ) -> HashMap<String, CategoryTranslation> {
+        let query = "SELECT category_id, locale, name, description
+                     FROM category_translations
+                     WHERE locale = $1";
+
+        match sqlx::query(query).bind(locale).fetch_all(&self.pool).await {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| (row.get("category_id"), row_to_translation(row)))
+                .collect(),
             Err(e) => {
-                error!("Error deleting category {}: {}", id, e);
-                Err(CategoryError::NotFound(format!(
-                    "カテゴリの削除に失敗しました: {}",
-                    e
-                )))
+                error!("Error fetching category translations for {}: {}", locale, e);
+                HashMap::new()
             }
         }
     }
 
-    async fn move_category(
+    async fn find_translations_for(
         &self,
-        id: &str,
-        new_parent_id: Option<String>,
-        new_sort_order: i32,
-    ) -> Result<Category, CategoryError> {
-        // Validate circular reference
-        self.validate_circular_reference(id, new_parent_id.clone())
-            .await?;
+        ids: &[String],
+        locale: &str,
+    ) -> HashMap<String, CategoryTranslation> {
+        if ids.is_empty() {
+            return HashMap::new();
+        }
+        let query = "SELECT category_id, locale, name, description
+                     FROM category_translations
+                     WHERE category_id = ANY($1) AND locale = $2";
+
+        match sqlx::query(query)
+            .bind(ids)
+            .bind(locale)
+            .fetch_all(&self.pool)
+            .await
+        {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| (row.get("category_id"), row_to_translation(row)))
+                .collect(),
+            Err(e) => {
+                error!("Error fetching category translations for {}: {}", locale, e);
+                HashMap::new()
+            }
+        }
+    }
 
-        // Validate depth
-        if new_parent_id.is_some() {
-            self.validate_depth(new_parent_id.clone()).await?;
+    async fn upsert_translation(
+        &self,
+        id: &str,
+        translation: CategoryTranslation,
+    ) -> Result<(), CategoryError> {
+        let db_error = Self::database_error("翻訳の更新");
+        let result = sqlx::query(
+            "INSERT INTO category_translations (category_id, locale, name, description)
+             SELECT id, $2, $3, $4 FROM categories WHERE id = $1
+             ON CONFLICT (category_id, locale)
+             DO UPDATE SET name = $3, description = $4, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(id)
+        .bind(&translation.locale)
+        .bind(&translation.name)
+        .bind(&translation.description)
+        .execute(&self.pool)
+        .await
+        .map_err(&db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(CategoryError::NotFound(
+                "カテゴリが見つかりません".to_string(),
+            ));
         }
+        Ok(())
+    }
 
-        // Get current category
-        let mut category = self
-            .find_by_id(id)
+    async fn delete_translation(&self, id: &str, locale: &str) -> Result<bool, CategoryError> {
+        sqlx::query("DELETE FROM category_translations WHERE category_id = $1 AND locale = $2")
+            .bind(id)
+            .bind(locale)
+            .execute(&self.pool)
             .await
-            .ok_or_else(|| CategoryError::NotFound("カテゴリが見つかりません".to_string()))?;
-
-        // Update parent and sort order
-        category.parent_id = new_parent_id.map(|s| s.to_string());
-        category.sort_order = new_sort_order;
-        category.updated_at = Utc::now();
-
-        // Update in database
-        self.update(category).await
+            .map(|result| result.rows_affected() > 0)
+            .map_err(Self::database_error("翻訳の削除"))
     }
 
     async fn count_children(&self, id: &str) -> i64 {
@@ -409,28 +1869,29 @@ impl CategoryRepository for PostgresCategoryRepository {
         }
     }
 
-    // async fn count_products(&self, _id: &str) -> i64 {
-    //     // For now, return 0 as we don't have products table linked yet
-    //     // In the future, this would count products linked to this category
-    //     0
-    // }
+    async fn count_products(&self, id: &str) -> ProductCount {
+        let query = "SELECT COUNT(p.id) FILTER (WHERE cc.depth = 0) AS direct, COUNT(p.id) AS total
+                     FROM category_closure cc
+                     JOIN products p ON p.category_id = cc.descendant_id
+                     WHERE cc.ancestor_id = $1";
+
+        match sqlx::query(query).bind(id).fetch_one(&self.pool).await {
+            Ok(row) => ProductCount {
+                direct: row.get("direct"),
+                total: row.get("total"),
+            },
+            Err(e) => {
+                error!("Error counting products for category {}: {}", id, e);
+                ProductCount::default()
+            }
+        }
+    }
 
     async fn validate_depth(&self, parent_id: Option<String>) -> Result<(), CategoryError> {
         if let Some(parent_id) = parent_id {
-            match self.find_path(&parent_id).await {
-                Ok(path) => {
-                    if path.depth >= 5 {
-                        return Err(CategoryError::MaxDepthExceeded(
-                            "最大階層数(5階層)を超過しています".to_string(),
-                        ));
-                    }
-                }
-                Err(_) => {
-                    return Err(CategoryError::NotFound(
-                        "親カテゴリが見つかりません".to_string(),
-                    ));
-                }
-            }
+            let placement = Self::placement(&self.pool, None, &parent_id).await?;
+            placement.ensure_acyclic()?;
+            placement.ensure_depth()?;
         }
         Ok(())
     }
@@ -441,28 +1902,9 @@ impl CategoryRepository for PostgresCategoryRepository {
         new_parent_id: Option<String>,
     ) -> Result<(), CategoryError> {
         if let Some(new_parent_id) = new_parent_id {
-            // Cannot set self as parent
-            if id == new_parent_id {
-                return Err(CategoryError::CircularReference(
-                    "自分自身を親カテゴリに設定することはできません".to_string(),
-                ));
-            }
-
-            // Check if new_parent_id is a descendant of current category
-            match self.find_path(&new_parent_id).await {
-                Ok(path) => {
-                    if path.contains(id) {
-                        return Err(CategoryError::CircularReference(
-                            "循環参照が発生するため、この操作は実行できません".to_string(),
-                        ));
-                    }
-                }
-                Err(_) => {
-                    return Err(CategoryError::NotFound(
-                        "親カテゴリが見つかりません".to_string(),
-                    ));
-                }
-            }
+            Self::placement(&self.pool, Some(id), &new_parent_id)
+                .await?
+                .ensure_acyclic()?;
         }
         Ok(())
     }
@@ -604,14 +2046,14 @@ mod tests {
 
         // 8. Delete child first (cannot delete parent with children)
         let child_deleted = repo
-            .delete("cat_456")
+            .delete("cat_456", None, &ChangeContext::default())
             .await
             .expect("Failed to delete child");
         assert!(child_deleted);
 
         // 9. Delete parent
         let parent_deleted = repo
-            .delete("cat_123")
+            .delete("cat_123", None, &ChangeContext::default())
             .await
             .expect("Failed to delete parent");
         assert!(parent_deleted);
@@ -660,4 +2102,707 @@ mod tests {
             _ => panic!("Expected NameDuplicate error"),
         }
     }
+
+    #[tokio::test]
+    async fn test_postgres_category_delete_with_products() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, name, parent_id) in [
+            ("cat_a", "Books", None),
+            ("cat_b", "Music", None),
+            ("cat_c", "Vinyl", Some("cat_b")),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                name.to_string(),
+                None,
+                parent_id.map(str::to_string),
+                1,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        for (id, status, category_id) in [
+            ("p1", "Active", "cat_a"),
+            ("p2", "Discontinued", "cat_a"),
+            ("p3", "Active", "cat_c"),
+        ] {
+            sqlx::query("INSERT INTO products (id, status, category_id) VALUES ($1, $2, $3)")
+                .bind(id)
+                .bind(status)
+                .bind(category_id)
+                .execute(&pool)
+                .await
+                .expect("Failed to insert product");
+        }
+
+        assert_eq!(
+            repo.count_products("cat_b").await,
+            ProductCount {
+                direct: 0,
+                total: 1
+            }
+        );
+        let tree = repo.find_tree(false).await;
+        let music = tree.iter().find(|t| t.id == "cat_b").unwrap();
+        assert_eq!(music.product_count.total, 1);
+        assert_eq!(music.children[0].product_count.direct, 1);
+
+        // 商品が紐づいていれば削除しない
+        match repo.delete("cat_a", None, &ChangeContext::default()).await {
+            Err(CategoryError::HasProducts(_)) => (),
+            other => panic!("Expected HasProducts error, got {:?}", other),
+        }
+
+        // 呼び出し元が把握しているバージョンが古ければ削除しない
+        let version = repo.find_by_id("cat_a").await.unwrap().version;
+        match repo
+            .delete_with_products(
+                "cat_a",
+                ProductDisposition::Reassign("cat_c".to_string()),
+                Some(version + 1),
+                &ChangeContext::default(),
+            )
+            .await
+        {
+            Err(CategoryError::VersionMismatch(_)) => (),
+            other => panic!("Expected VersionMismatch error, got {:?}", other),
+        }
+
+        // 付け替えてから削除する
+        let deleted = repo
+            .delete_with_products(
+                "cat_a",
+                ProductDisposition::Reassign("cat_c".to_string()),
+                Some(version),
+                &ChangeContext::default(),
+            )
+            .await
+            .expect("Failed to delete category");
+        assert!(deleted);
+        assert_eq!(repo.count_products("cat_c").await.direct, 3);
+
+        // 非アクティブにしてから削除する（販売終了の商品はそのまま）
+        let ctx = ChangeContext::new(Some("admin".to_string()), None);
+        repo.delete_with_products("cat_c", ProductDisposition::Deactivate, None, &ctx)
+            .await
+            .expect("Failed to delete category");
+        let rows = sqlx::query("SELECT id, status, category_id FROM products ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let products: Vec<(String, String, Option<String>)> = rows
+            .iter()
+            .map(|row| (row.get("id"), row.get("status"), row.get("category_id")))
+            .collect();
+        assert_eq!(
+            products,
+            vec![
+                ("p1".to_string(), "Inactive".to_string(), None),
+                ("p2".to_string(), "Discontinued".to_string(), None),
+                ("p3".to_string(), "Inactive".to_string(), None),
+            ]
+        );
+
+        // 商品の変更履歴には呼び出し元の操作者が残る
+        let recorded: i64 = sqlx::query(
+            "SELECT COUNT(*) AS count FROM product_history
+             WHERE field_name = 'category_id' AND old_value = 'cat_c'
+               AND changed_by = 'admin' AND reason = 'カテゴリ cat_c の削除'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+        assert_eq!(recorded, 3);
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_closure_table() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, parent_id) in [
+            ("a", None),
+            ("b", Some("a")),
+            ("c", Some("b")),
+            ("d", None),
+            ("e1", None),
+            ("e2", Some("e1")),
+            ("e3", Some("e2")),
+            ("e4", Some("e3")),
+            ("e5", Some("e4")),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                format!("Category {}", id),
+                None,
+                parent_id.map(str::to_string),
+                1,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        let ids = |categories: Vec<Category>| -> Vec<String> {
+            categories.into_iter().map(|c| c.id).collect()
+        };
+
+        assert_eq!(
+            ids(repo.find_ancestors("c").await.unwrap()),
+            ["a", "b", "c"]
+        );
+        assert_eq!(ids(repo.find_descendants("a", true).await), ["b", "c"]);
+
+        // サブツリーごと付け替わる
+        repo.move_category("b", Some("d".to_string()), 0, None)
+            .await
+            .expect("Failed to move category");
+        assert_eq!(repo.find_path("c").await.unwrap().path, ["d", "b", "c"]);
+        assert!(repo.find_descendants("a", true).await.is_empty());
+        assert_eq!(ids(repo.find_descendants("d", true).await), ["b", "c"]);
+
+        // 子孫の下へは移動できない
+        match repo
+            .move_category("d", Some("c".to_string()), 0, None)
+            .await
+        {
+            Err(CategoryError::CircularReference(_)) => (),
+            other => panic!("Expected CircularReference error, got {:?}", other),
+        }
+
+        // 古いバージョンを指定した移動は拒否され、位置も変わらない
+        let stale = repo.find_by_id("b").await.unwrap().version - 1;
+        match repo
+            .move_category("b", Some("a".to_string()), 0, Some(stale))
+            .await
+        {
+            Err(CategoryError::VersionMismatch(_)) => (),
+            other => panic!("Expected VersionMismatch error, got {:?}", other),
+        }
+        assert_eq!(repo.find_path("c").await.unwrap().path, ["d", "b", "c"]);
+
+        // 6階層目は作れず、サブツリーの深さも含めて検証する
+        match repo
+            .create(Category::new(
+                "e6".to_string(),
+                "Category e6".to_string(),
+                None,
+                Some("e5".to_string()),
+                1,
+            ))
+            .await
+        {
+            Err(CategoryError::MaxDepthExceeded(_)) => (),
+            other => panic!("Expected MaxDepthExceeded error, got {:?}", other),
+        }
+        match repo
+            .move_category("b", Some("e4".to_string()), 0, None)
+            .await
+        {
+            Err(CategoryError::MaxDepthExceeded(_)) => (),
+            other => panic!("Expected MaxDepthExceeded error, got {:?}", other),
+        }
+        repo.move_category("b", Some("e3".to_string()), 0, None)
+            .await
+            .expect("Failed to move category");
+
+        // 削除したカテゴリの経路は残らない
+        repo.delete("c", None, &ChangeContext::default())
+            .await
+            .expect("Failed to delete category");
+        let remaining: i64 = sqlx::query(
+            "SELECT COUNT(*) AS count FROM category_closure
+             WHERE ancestor_id = 'c' OR descendant_id = 'c'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_subtree_operations() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, name, parent_id, sort_order) in [
+            ("a", "Books", None, 0),
+            ("a1", "Novels", Some("a"), 0),
+            ("a2", "Comics", Some("a"), 1),
+            ("a3", "Magazines", Some("a"), 2),
+            ("a2x", "Manga", Some("a2"), 0),
+            ("b", "Media", None, 1),
+            ("b1", "Comics", Some("b"), 0),
+            ("b2", "Movies", Some("b"), 1),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                name.to_string(),
+                None,
+                parent_id.map(str::to_string),
+                sort_order,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        let children = |parent_id: &str| {
+            let repo = &repo;
+            let parent_id = parent_id.to_string();
+            async move {
+                repo.find_by_parent_id(Some(parent_id), true)
+                    .await
+                    .into_iter()
+                    .map(|c| (c.id, c.sort_order))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // 同じ名前の兄弟がいる親へは移動できない
+        match repo
+            .move_category("a2", Some("b".to_string()), 0, None)
+            .await
+        {
+            Err(CategoryError::NameDuplicate(_)) => (),
+            other => panic!("Expected NameDuplicate error, got {:?}", other),
+        }
+
+        // 移動元は詰め、移動先は指定位置を空けて振り直す
+        repo.move_category("a1", Some("b".to_string()), 1, None)
+            .await
+            .expect("Failed to move category");
+        assert_eq!(
+            children("a").await,
+            [("a2".to_string(), 0), ("a3".to_string(), 1)]
+        );
+        assert_eq!(
+            children("b").await,
+            [
+                ("b1".to_string(), 0),
+                ("a1".to_string(), 1),
+                ("b2".to_string(), 2)
+            ]
+        );
+
+        // サブツリーを新しい ID で複製する
+        let copy = repo
+            .copy_subtree(
+                "a2",
+                Some("a2".to_string()),
+                Some("Comics copy".to_string()),
+                0,
+            )
+            .await
+            .expect("Failed to copy category");
+        assert_ne!(copy.id, "a2");
+        assert_eq!(copy.parent_id.as_deref(), Some("a2"));
+        let copied = repo.find_descendants(&copy.id, true).await;
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].name, "Manga");
+        assert_ne!(copied[0].id, "a2x");
+        assert_eq!(repo.find_path(&copied[0].id).await.unwrap().depth, 4);
+        match repo
+            .copy_subtree("a2", Some("b".to_string()), None, 0)
+            .await
+        {
+            Err(CategoryError::NameDuplicate(_)) => (),
+            other => panic!("Expected NameDuplicate error, got {:?}", other),
+        }
+
+        // 統合先に同じ名前の子があれば何も変更しない
+        sqlx::query("INSERT INTO products (id, category_id) VALUES ('p1', 'a'), ('p2', 'a3')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        match repo
+            .merge_category("a", "b", &ChangeContext::default())
+            .await
+        {
+            Err(CategoryError::NameDuplicate(_)) => (),
+            other => panic!("Expected NameDuplicate error, got {:?}", other),
+        }
+        assert!(repo.find_by_id("a").await.is_some());
+        let mut b1 = repo.find_by_id("b1").await.unwrap();
+        b1.update_name("Graphic Novels".to_string()).unwrap();
+        repo.update(b1).await.unwrap();
+
+        match repo
+            .merge_category("b", "a1", &ChangeContext::default())
+            .await
+        {
+            Err(CategoryError::CircularReference(_)) => (),
+            other => panic!("Expected CircularReference error, got {:?}", other),
+        }
+
+        // 子カテゴリと商品を付け替えてから統合元を削除する
+        repo.merge_category("a", "b", &ChangeContext::default())
+            .await
+            .expect("Failed to merge category");
+        assert!(repo.find_by_id("a").await.is_none());
+        assert_eq!(
+            children("b").await,
+            [
+                ("b1".to_string(), 0),
+                ("a1".to_string(), 1),
+                ("b2".to_string(), 2),
+                ("a2".to_string(), 3),
+                ("a3".to_string(), 4)
+            ]
+        );
+        assert_eq!(
+            repo.find_path("a2x").await.unwrap().path,
+            ["b", "a2", "a2x"]
+        );
+        // 統合先の子とスラッグが重なる子には連番を付ける
+        assert_eq!(repo.find_by_id("a2").await.unwrap().slug, "comics-2");
+        let renamed = repo
+            .find_by_old_slug(Some("a".to_string()), "comics")
+            .await
+            .unwrap();
+        assert_eq!(
+            (renamed.category.id.as_str(), renamed.merged_from),
+            ("a2", None)
+        );
+        let redirected = repo.find_by_old_slug(None, "books").await.unwrap();
+        assert_eq!(
+            (redirected.category.id.as_str(), redirected.merged_from),
+            ("b", Some("a".to_string()))
+        );
+        assert_eq!(
+            repo.count_products("b").await,
+            ProductCount {
+                direct: 1,
+                total: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_reorder_children() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, parent_id, sort_order) in [
+            ("r1", None, 0),
+            ("r2", None, 1),
+            ("c1", Some("r1"), 0),
+            ("c2", Some("r1"), 1),
+            ("c3", Some("r1"), 2),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                format!("Category {}", id),
+                None,
+                parent_id.map(str::to_string),
+                sort_order,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        // 現在の子カテゴリとちょうど一致しなければ何も変更しない
+        for child_ids in [
+            ids(&["c3", "c1"]),
+            ids(&["c3", "c1", "c1"]),
+            ids(&["c3", "c1", "r2"]),
+        ] {
+            match repo
+                .reorder_children(Some("r1".to_string()), child_ids)
+                .await
+            {
+                Err(CategoryError::InvalidSortOrder(_)) => (),
+                other => panic!("Expected InvalidSortOrder error, got {:?}", other),
+            }
+        }
+        match repo
+            .reorder_children(Some("missing".to_string()), vec![])
+            .await
+        {
+            Err(CategoryError::NotFound(_)) => (),
+            other => panic!("Expected NotFound error, got {:?}", other),
+        }
+
+        let children = repo
+            .reorder_children(Some("r1".to_string()), ids(&["c3", "c1", "c2"]))
+            .await
+            .expect("Failed to reorder children");
+        let order: Vec<(String, i32)> =
+            children.into_iter().map(|c| (c.id, c.sort_order)).collect();
+        assert_eq!(
+            order,
+            [
+                ("c3".to_string(), 0),
+                ("c1".to_string(), 1),
+                ("c2".to_string(), 2)
+            ]
+        );
+
+        // ルートカテゴリも並べ替えられる
+        let roots = repo
+            .reorder_children(None, ids(&["r2", "r1"]))
+            .await
+            .expect("Failed to reorder root categories");
+        assert_eq!(roots[0].id, "r2");
+        assert_eq!(roots[1].sort_order, 1);
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_slug_redirects() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, name, parent_id) in [
+            ("audio", "Audio", None),
+            ("video", "Video", None),
+            ("phones", "Headphones", Some("audio")),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                name.to_string(),
+                None,
+                parent_id.map(str::to_string),
+                0,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+
+        // 同じ親の下ではスラッグを重複させられない
+        let mut duplicate = Category::new("audio2".to_string(), "Sound".to_string(), None, None, 1);
+        duplicate.update_slug("audio".to_string()).unwrap();
+        match repo.create(duplicate).await {
+            Err(CategoryError::SlugDuplicate(_)) => (),
+            other => panic!("Expected SlugDuplicate error, got {:?}", other),
+        }
+
+        let mut phones = repo.find_by_id("phones").await.unwrap();
+        phones.update_slug("earphones".to_string()).unwrap();
+        repo.update(phones).await.expect("Failed to update slug");
+
+        let audio = Some("audio".to_string());
+        let found = repo.find_by_slug(audio.clone(), "earphones").await.unwrap();
+        assert_eq!(found.id, "phones");
+        assert!(repo
+            .find_by_slug(audio.clone(), "headphones")
+            .await
+            .is_none());
+        let redirected = repo.find_by_old_slug(audio.clone(), "headphones").await;
+        assert_eq!(redirected.map(|r| r.category.id).as_deref(), Some("phones"));
+
+        // 移動前の親の下のスラッグも転送元として残る
+        repo.move_category("phones", Some("video".to_string()), 0, None)
+            .await
+            .expect("Failed to move category");
+        let moved = repo
+            .find_by_slug(Some("video".to_string()), "earphones")
+            .await;
+        assert_eq!(moved.map(|c| c.id).as_deref(), Some("phones"));
+        let redirected = repo.find_by_old_slug(audio, "earphones").await;
+        assert_eq!(redirected.map(|r| r.category.id).as_deref(), Some("phones"));
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_attribute_definitions() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, parent_id) in [("electronics", None), ("displays", Some("electronics"))] {
+            repo.create(Category::new(
+                id.to_string(),
+                format!("Category {}", id),
+                None,
+                parent_id.map(str::to_string),
+                0,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        let definition =
+            |name: &str, attribute_type: AttributeType, unit: Option<&str>| AttributeDefinition {
+                name: name.to_string(),
+                attribute_type,
+                allowed_values: vec![],
+                required: false,
+                unit: unit.map(str::to_string),
+            };
+
+        match repo.replace_attribute_definitions("missing", vec![]).await {
+            Err(CategoryError::NotFound(_)) => (),
+            other => panic!("Expected NotFound error, got {:?}", other),
+        }
+
+        let mut color = definition("color", AttributeType::Enum, None);
+        color.allowed_values = vec!["black".to_string(), "white".to_string()];
+        repo.replace_attribute_definitions(
+            "electronics",
+            vec![definition("brand", AttributeType::String, None), color],
+        )
+        .await
+        .expect("Failed to replace attribute definitions");
+        let mut brand = definition("Brand", AttributeType::String, None);
+        brand.required = true;
+        let saved = repo
+            .replace_attribute_definitions(
+                "displays",
+                vec![
+                    definition("screen_size", AttributeType::Number, Some("inch")),
+                    brand,
+                ],
+            )
+            .await
+            .expect("Failed to replace attribute definitions");
+        assert_eq!(saved[0].unit.as_deref(), Some("inch"));
+        assert_eq!(
+            repo.find_attribute_definitions("displays").await.unwrap(),
+            saved
+        );
+
+        // 子カテゴリの定義は同名の祖先の定義を上書きし、位置は祖先の順序を保つ
+        let schema = repo.find_attribute_schema("displays").await.unwrap();
+        let effective: Vec<(&str, &str, bool)> = schema
+            .iter()
+            .map(|(category_id, d)| (category_id, d.name.as_str(), d.required))
+            .collect();
+        assert_eq!(
+            effective,
+            [
+                ("displays", "Brand", true),
+                ("electronics", "color", false),
+                ("displays", "screen_size", false),
+            ]
+        );
+
+        // サブツリーを複製すると属性定義も複製される
+        let copy = repo
+            .copy_subtree(
+                "electronics",
+                None,
+                Some("Electronics (copy)".to_string()),
+                1,
+            )
+            .await
+            .expect("Failed to copy subtree");
+        assert_eq!(
+            repo.find_attribute_definitions(&copy.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // 空の一覧で置き換えると継承した定義だけが残る
+        repo.replace_attribute_definitions("displays", vec![])
+            .await
+            .unwrap();
+        let schema = repo.find_attribute_schema("displays").await.unwrap();
+        assert_eq!(schema.iter().count(), 2);
+        assert!(!schema.find("brand").unwrap().required);
+    }
+
+    #[tokio::test]
+    async fn test_postgres_category_translations() {
+        let (pool, _container) = setup_postgres().await;
+        let repo = PostgresCategoryRepository::new(pool.clone());
+
+        repo.init_table()
+            .await
+            .expect("Failed to create categories table");
+
+        for (id, name, parent_id) in [
+            ("electronics", "家電", None),
+            ("tv", "テレビ", Some("electronics")),
+        ] {
+            repo.create(Category::new(
+                id.to_string(),
+                name.to_string(),
+                Some(format!("{}の説明", name)),
+                parent_id.map(str::to_string),
+                0,
+            ))
+            .await
+            .expect("Failed to create category");
+        }
+        let translation = |name: &str, description: Option<&str>| CategoryTranslation {
+            locale: "en".to_string(),
+            name: Some(name.to_string()),
+            description: description.map(str::to_string),
+        };
+
+        match repo
+            .upsert_translation("missing", translation("Missing", None))
+            .await
+        {
+            Err(CategoryError::NotFound(_)) => (),
+            other => panic!("Expected NotFound error, got {:?}", other),
+        }
+
+        repo.upsert_translation("electronics", translation("Appliances", None))
+            .await
+            .unwrap();
+        repo.upsert_translation("electronics", translation("Electronics", Some("Devices")))
+            .await
+            .unwrap();
+        repo.upsert_translation("tv", translation("TV", None))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.find_translations("electronics").await.unwrap(),
+            vec![translation("Electronics", Some("Devices"))]
+        );
+        let by_locale = repo.find_translations_by_locale("en").await;
+        assert_eq!(by_locale.len(), 2);
+        assert_eq!(by_locale["tv"].name.as_deref(), Some("TV"));
+        assert!(repo.find_translations_by_locale("fr").await.is_empty());
+        let selected = repo
+            .find_translations_for(&["tv".to_string(), "missing".to_string()], "en")
+            .await;
+        assert_eq!(selected.keys().collect::<Vec<_>>(), ["tv"]);
+
+        // 名前を変えて複製したルートは翻訳名を引き継がず、子孫の翻訳はそのまま複製される
+        let copy = repo
+            .copy_subtree("electronics", None, Some("家電（コピー）".to_string()), 1)
+            .await
+            .expect("Failed to copy subtree");
+        let copied = repo.find_translations_by_locale("en").await;
+        assert_eq!(copied.len(), 4);
+        assert_eq!(copied[&copy.id].name, None);
+        assert_eq!(copied[&copy.id].description.as_deref(), Some("Devices"));
+        assert_eq!(
+            copied
+                .values()
+                .filter(|t| t.name.as_deref() == Some("TV"))
+                .count(),
+            2
+        );
+
+        assert!(repo.delete_translation("tv", "en").await.unwrap());
+        assert!(!repo.delete_translation("tv", "en").await.unwrap());
+        assert!(repo.find_translations("tv").await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,234 @@
+use crate::app_domain::model::idempotency::{
+    IdempotencyKey, IdempotencyRecord, Reservation, StoredResponse,
+};
+use crate::app_domain::repository::idempotency_repository::IdempotencyRepository;
+use crate::infrastructure::error::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub struct InMemoryIdempotencyRepository {
+    records: Mutex<HashMap<IdempotencyKey, IdempotencyRecord>>,
+}
+
+impl InMemoryIdempotencyRepository {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemoryIdempotencyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for InMemoryIdempotencyRepository {
+    async fn reserve(
+        &self,
+        key: &IdempotencyKey,
+        fingerprint: &str,
+        request_path: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<Reservation> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        let now = Utc::now();
+
+        // 期限切れの記録は同じキーで上書きできる
+        if let Some(record) = records.get(key).filter(|record| !record.is_expired(now)) {
+            return Ok(Reservation::Existing(record.clone()));
+        }
+
+        records.insert(
+            key.clone(),
+            IdempotencyRecord {
+                key: key.clone(),
+                fingerprint: fingerprint.to_string(),
+                request_path: request_path.to_string(),
+                response: None,
+                created_at: now,
+                expires_at,
+            },
+        );
+        Ok(Reservation::Reserved)
+    }
+
+    async fn complete(&self, key: &IdempotencyKey, response: StoredResponse) -> AppResult<()> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        if let Some(record) = records.get_mut(key) {
+            record.response = Some(response);
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: &IdempotencyKey) -> AppResult<()> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        records.remove(key);
+        Ok(())
+    }
+
+    async fn purge_expired(&self, now: DateTime<Utc>) -> AppResult<u64> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        let before = records.len();
+        records.retain(|_, record| !record.is_expired(now));
+        Ok((before - records.len()) as u64)
+    }
+}
+
+pub struct PostgresIdempotencyRepository {
+    pool: PgPool,
+}
+
+impl PostgresIdempotencyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_record(row: &sqlx::postgres::PgRow) -> AppResult<IdempotencyRecord> {
+        let status_code: Option<i32> = row.get("status_code");
+        let response = match status_code {
+            Some(status_code) => {
+                let headers: Option<String> = row.get("response_headers");
+                let headers = match headers {
+                    Some(headers) => serde_json::from_str(&headers)
+                        .map_err(|e| AppError::SerializationError(e.to_string()))?,
+                    None => Vec::new(),
+                };
+                let body: Option<Vec<u8>> = row.get("response_body");
+                Some(StoredResponse {
+                    status_code: status_code as u16,
+                    headers,
+                    body: body.unwrap_or_default(),
+                })
+            }
+            None => None,
+        };
+
+        Ok(IdempotencyRecord {
+            key: IdempotencyKey::new(
+                row.get::<String, _>("scope"),
+                row.get::<String, _>("idempotency_key"),
+            ),
+            fingerprint: row.get("fingerprint"),
+            request_path: row.get("request_path"),
+            response,
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for PostgresIdempotencyRepository {
+    async fn reserve(
+        &self,
+        key: &IdempotencyKey,
+        fingerprint: &str,
+        request_path: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<Reservation> {
+        // 期限切れの記録が残っていれば、そのキーだけを上書きして確保する
+        // （期限切れの記録の一括削除は purge_expired で定期的に行う）
+        let inserted = sqlx::query(
+            "INSERT INTO idempotency_keys (scope, idempotency_key, fingerprint, request_path, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (scope, idempotency_key) DO UPDATE
+             SET fingerprint = EXCLUDED.fingerprint, request_path = EXCLUDED.request_path,
+                 status_code = NULL, response_headers = NULL, response_body = NULL,
+                 created_at = CURRENT_TIMESTAMP, completed_at = NULL,
+                 expires_at = EXCLUDED.expires_at
+             WHERE idempotency_keys.expires_at <= CURRENT_TIMESTAMP",
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .bind(fingerprint)
+        .bind(request_path)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        if inserted.rows_affected() > 0 {
+            return Ok(Reservation::Reserved);
+        }
+
+        let row = sqlx::query(
+            "SELECT scope, idempotency_key, fingerprint, request_path, status_code,
+                    response_headers, response_body, created_at, expires_at
+             FROM idempotency_keys
+             WHERE scope = $1 AND idempotency_key = $2",
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Reservation::Existing(Self::row_to_record(&row)?)),
+            // 確保に失敗した直後に別のリクエストが解放した場合
+            None => Err(AppError::Conflict(
+                "冪等性キーの確保に失敗しました。再試行してください".to_string(),
+            )),
+        }
+    }
+
+    async fn complete(&self, key: &IdempotencyKey, response: StoredResponse) -> AppResult<()> {
+        let headers = serde_json::to_string(&response.headers)
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        sqlx::query(
+            "UPDATE idempotency_keys
+             SET status_code = $3, response_headers = $4, response_body = $5,
+                 completed_at = CURRENT_TIMESTAMP
+             WHERE scope = $1 AND idempotency_key = $2",
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .bind(response.status_code as i32)
+        .bind(headers)
+        .bind(response.body)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &IdempotencyKey) -> AppResult<()> {
+        sqlx::query(
+            "DELETE FROM idempotency_keys
+             WHERE scope = $1 AND idempotency_key = $2 AND status_code IS NULL",
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self, now: DateTime<Utc>) -> AppResult<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,5 +1,9 @@
+use crate::app_domain::model::domain_event::DomainEvent;
 use crate::app_domain::repository::item_repository::ItemRepository;
 use crate::infrastructure::error::{AppError, AppResult};
+use crate::infrastructure::repository::outbox_repository::append_event;
+#[cfg(any(test, feature = "testing"))]
+use crate::infrastructure::repository::outbox_repository::PostgresOutboxRepository;
 use async_trait::async_trait;
 use chrono::Utc;
 use domain::model::item::{DeletionLog, DeletionType, DeletionValidation, Item, RelatedDataCount};
@@ -10,6 +14,7 @@ use tracing::error;
 
 pub struct InMemoryItemRepository {
     items: Mutex<HashMap<u64, Item>>,
+    deletion_logs: Mutex<Vec<DeletionLog>>,
 }
 
 impl InMemoryItemRepository {
@@ -17,8 +22,27 @@ impl InMemoryItemRepository {
     pub fn new() -> Self {
         Self {
             items: Mutex::new(HashMap::new()),
+            deletion_logs: Mutex::new(Vec::new()),
         }
     }
+
+    // PostgresItemRepository と同じく、削除・復元を "system" として記録する
+    fn log_deletion(&self, item: &Item, deletion_type: DeletionType) -> AppResult<()> {
+        let mut logs = self
+            .deletion_logs
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        let id = logs.len() as u64 + 1;
+        logs.push(DeletionLog {
+            id,
+            item_id: item.id,
+            item_name: item.name.clone(),
+            deletion_type,
+            deleted_at: Utc::now(),
+            deleted_by: "system".to_string(),
+        });
+        Ok(())
+    }
 }
 
 impl Default for InMemoryItemRepository {
@@ -84,8 +108,9 @@ impl ItemRepository for InMemoryItemRepository {
             if !item.deleted {
                 item.deleted = true;
                 item.deleted_at = Some(chrono::Utc::now());
-                items.insert(id, item);
-                return Ok(());
+                items.insert(id, item.clone());
+                drop(items);
+                return self.log_deletion(&item, DeletionType::Logical);
             }
         }
         Err(AppError::not_found("Item", id))
@@ -96,10 +121,12 @@ impl ItemRepository for InMemoryItemRepository {
             .items
             .lock()
             .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
-        if items.remove(&id).is_some() {
-            Ok(())
-        } else {
-            Err(AppError::not_found("Item", id))
+        match items.remove(&id) {
+            Some(item) => {
+                drop(items);
+                self.log_deletion(&item, DeletionType::Physical)
+            }
+            None => Err(AppError::not_found("Item", id)),
         }
     }
 
@@ -112,8 +139,9 @@ impl ItemRepository for InMemoryItemRepository {
             if item.deleted {
                 item.deleted = false;
                 item.deleted_at = None;
-                items.insert(id, item);
-                return Ok(());
+                items.insert(id, item.clone());
+                drop(items);
+                return self.log_deletion(&item, DeletionType::Restore);
             }
         }
         Err(AppError::not_found(
@@ -164,9 +192,18 @@ impl ItemRepository for InMemoryItemRepository {
         Ok(successful_ids)
     }
 
-    async fn get_deletion_logs(&self, _item_id: Option<u64>) -> AppResult<Vec<DeletionLog>> {
-        // In-memory implementation doesn't track deletion logs
-        Ok(Vec::new())
+    async fn get_deletion_logs(&self, item_id: Option<u64>) -> AppResult<Vec<DeletionLog>> {
+        let logs = self
+            .deletion_logs
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        // 新しい順（PostgresItemRepository の ORDER BY deleted_at DESC に合わせる）
+        Ok(logs
+            .iter()
+            .rev()
+            .filter(|log| item_id.is_none_or(|id| log.item_id == id))
+            .cloned()
+            .collect())
     }
 }
 
@@ -245,7 +282,9 @@ impl PostgresItemRepository {
         )
         .execute(&self.pool)
         .await
-        .map(|_| ())
+        .map(|_| ())?;
+
+        PostgresOutboxRepository::init_table(&self.pool).await
     }
 }
 
@@ -332,13 +371,18 @@ impl ItemRepository for PostgresItemRepository {
 
     async fn logical_delete(&self, id: u64) -> AppResult<()> {
         let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query(
             "UPDATE items SET deleted = TRUE, deleted_at = $2 WHERE id = $1 AND deleted = FALSE",
         )
         .bind(id as i64)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        if result.rows_affected() > 0 {
+            append_event(&mut tx, &DomainEvent::item_deleted(id, false)).await?;
+        }
+        tx.commit().await?;
 
         if result.rows_affected() > 0 {
             // Log the deletion
@@ -355,10 +399,15 @@ impl ItemRepository for PostgresItemRepository {
         // Get the item name before deletion for logging
         let item = self.find_by_id(id).await?;
 
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query("DELETE FROM items WHERE id = $1")
             .bind(id as i64)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
+        if result.rows_affected() > 0 {
+            append_event(&mut tx, &DomainEvent::item_deleted(id, true)).await?;
+        }
+        tx.commit().await?;
 
         if result.rows_affected() > 0 {
             if item.is_some() {
@@ -374,12 +423,17 @@ impl ItemRepository for PostgresItemRepository {
     }
 
     async fn restore(&self, id: u64) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query(
             "UPDATE items SET deleted = FALSE, deleted_at = NULL WHERE id = $1 AND deleted = TRUE",
         )
         .bind(id as i64)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        if result.rows_affected() > 0 {
+            append_event(&mut tx, &DomainEvent::item_restored(id)).await?;
+        }
+        tx.commit().await?;
 
         if result.rows_affected() > 0 {
             // Log the restoration
@@ -564,6 +618,9 @@ mod tests {
         .execute(&pool)
         .await
         .expect("Failed to create items table");
+        PostgresOutboxRepository::init_table(&pool)
+            .await
+            .expect("Failed to create outbox_events table");
 
         // テストデータ
         let item = Item {
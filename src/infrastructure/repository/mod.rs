@@ -1,5 +1,8 @@
 pub mod category_repository;
+pub mod idempotency_repository;
 pub mod item_repository;
+pub mod outbox_repository;
 pub mod postgres;
 pub mod product_repository;
 pub mod user_repository;
+pub mod webhook_repository;
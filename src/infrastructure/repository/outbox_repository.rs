@@ -0,0 +1,323 @@
+use crate::app_domain::model::domain_event::{DomainEvent, OutboxEvent};
+use crate::app_domain::repository::outbox_repository::OutboxRepository;
+use crate::infrastructure::error::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+
+/// イベントを呼び出し元のトランザクション内で outbox に書き込む
+///
+/// 集約の行を更新（ロック）した後に呼び出すことで、同じ集約のイベントの
+/// `id` がコミット順に並ぶ。
+pub async fn append_event(
+    tx: &mut Transaction<'_, Postgres>,
+    event: &DomainEvent,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO outbox_events (aggregate_type, aggregate_id, event_type, payload, occurred_at)
+         VALUES ($1, $2, $3, $4::jsonb, $5)",
+    )
+    .bind(event.aggregate_type.as_str())
+    .bind(&event.aggregate_id)
+    .bind(event.event_type.as_str())
+    .bind(event.payload.to_string())
+    .bind(event.occurred_at)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+}
+
+struct OutboxRecord {
+    event: OutboxEvent,
+    next_attempt_at: DateTime<Utc>,
+    claimed_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+}
+
+/// テスト用のインメモリ outbox（PostgresOutboxRepository と同じ取得規則）
+pub struct InMemoryOutboxRepository {
+    records: Mutex<Vec<OutboxRecord>>,
+    /// 配信済みのイベントを削除しても ID を再利用しないよう、採番は件数とは別に管理する
+    next_id: AtomicI64,
+}
+
+impl InMemoryOutboxRepository {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(Vec::new()),
+            next_id: AtomicI64::new(1),
+        }
+    }
+
+    /// イベントを追加し、採番した ID を返す
+    #[allow(dead_code)]
+    pub fn append(&self, event: DomainEvent) -> i64 {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        records.push(OutboxRecord {
+            event: OutboxEvent {
+                id,
+                event,
+                attempts: 0,
+            },
+            next_attempt_at: Utc::now(),
+            claimed_at: None,
+            last_error: None,
+            published_at: None,
+        });
+        id
+    }
+
+    /// 未配信のイベント ID
+    #[allow(dead_code)]
+    pub fn pending_ids(&self) -> Vec<i64> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records
+            .iter()
+            .filter(|record| record.published_at.is_none())
+            .map(|record| record.event.id)
+            .collect()
+    }
+
+    /// 最後の配信エラー
+    #[allow(dead_code)]
+    pub fn last_error(&self, id: i64) -> Option<String> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records
+            .iter()
+            .find(|record| record.event.id == id)
+            .and_then(|record| record.last_error.clone())
+    }
+
+    fn with_record(&self, id: i64, update: impl FnOnce(&mut OutboxRecord)) -> AppResult<()> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        match records.iter_mut().find(|record| record.event.id == id) {
+            Some(record) => {
+                update(record);
+                Ok(())
+            }
+            None => Err(AppError::not_found("OutboxEvent", id)),
+        }
+    }
+}
+
+impl Default for InMemoryOutboxRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for InMemoryOutboxRepository {
+    async fn claim_pending(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        claim_timeout_seconds: i64,
+    ) -> AppResult<Vec<OutboxEvent>> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        let stale_before = now - Duration::seconds(claim_timeout_seconds);
+
+        let mut seen = HashSet::new();
+        let mut claimed = Vec::new();
+        for record in records.iter_mut() {
+            if record.published_at.is_some() {
+                continue;
+            }
+            // 集約ごとの先頭のみが対象
+            let aggregate = (
+                record.event.event.aggregate_type,
+                record.event.event.aggregate_id.clone(),
+            );
+            if !seen.insert(aggregate) {
+                continue;
+            }
+            let available = record.next_attempt_at <= now
+                && record.claimed_at.is_none_or(|at| at < stale_before);
+            if available && (claimed.len() as i64) < limit {
+                record.claimed_at = Some(now);
+                record.event.attempts += 1;
+                claimed.push(record.event.clone());
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn mark_published(&self, id: i64) -> AppResult<()> {
+        self.with_record(id, |record| {
+            record.published_at = Some(Utc::now());
+            record.claimed_at = None;
+            record.last_error = None;
+        })
+    }
+
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: DateTime<Utc>) -> AppResult<()> {
+        self.with_record(id, |record| {
+            record.claimed_at = None;
+            record.last_error = Some(error.to_string());
+            record.next_attempt_at = retry_at;
+        })
+    }
+
+    async fn purge_published(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))?;
+        let count = records.len();
+        records.retain(|record| record.published_at.is_none_or(|at| at >= before));
+        Ok((count - records.len()) as u64)
+    }
+}
+
+pub struct PostgresOutboxRepository {
+    pool: PgPool,
+}
+
+impl PostgresOutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // テーブルを初期化するメソッド（テスト用）
+    #[cfg(any(test, feature = "testing"))]
+    #[allow(dead_code)]
+    pub async fn init_table(pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS outbox_events (
+                id BIGSERIAL PRIMARY KEY,
+                aggregate_type VARCHAR(50) NOT NULL,
+                aggregate_id VARCHAR(255) NOT NULL,
+                event_type VARCHAR(100) NOT NULL,
+                payload JSONB NOT NULL,
+                occurred_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                claimed_at TIMESTAMP WITH TIME ZONE,
+                last_error TEXT,
+                published_at TIMESTAMP WITH TIME ZONE
+            )",
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    fn row_to_event(row: &sqlx::postgres::PgRow) -> AppResult<OutboxEvent> {
+        let aggregate_type: String = row.get("aggregate_type");
+        let event_type: String = row.get("event_type");
+        let payload: String = row.get("payload");
+
+        Ok(OutboxEvent {
+            id: row.get("id"),
+            event: DomainEvent {
+                event_type: event_type.parse().map_err(AppError::SerializationError)?,
+                aggregate_type: aggregate_type
+                    .parse()
+                    .map_err(AppError::SerializationError)?,
+                aggregate_id: row.get("aggregate_id"),
+                payload: serde_json::from_str(&payload)
+                    .map_err(|e| AppError::SerializationError(e.to_string()))?,
+                occurred_at: row.get("occurred_at"),
+            },
+            attempts: row.get("attempts"),
+        })
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for PostgresOutboxRepository {
+    async fn claim_pending(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        claim_timeout_seconds: i64,
+    ) -> AppResult<Vec<OutboxEvent>> {
+        // 集約ごとの先頭イベントだけを確保するため、同じ集約のイベントが並行・逆順に
+        // 配信されることはない。確保済みかどうかは行ロック取得後に再評価されるので、
+        // 複数のディスパッチャーが同じイベントを同時に確保することもない。
+        let rows = sqlx::query(
+            "UPDATE outbox_events
+             SET claimed_at = $1, attempts = attempts + 1
+             WHERE id IN (
+                 SELECT id FROM (
+                     SELECT DISTINCT ON (aggregate_type, aggregate_id)
+                            id, next_attempt_at, claimed_at
+                     FROM outbox_events
+                     WHERE published_at IS NULL
+                     ORDER BY aggregate_type, aggregate_id, id
+                 ) heads
+                 WHERE next_attempt_at <= $1
+                   AND (claimed_at IS NULL OR claimed_at < $1 - make_interval(secs => $3))
+                 ORDER BY id
+                 LIMIT $2
+             )
+               AND published_at IS NULL
+               AND (claimed_at IS NULL OR claimed_at < $1 - make_interval(secs => $3))
+             RETURNING id, aggregate_type, aggregate_id, event_type, payload::text AS payload,
+                       occurred_at, attempts",
+        )
+        .bind(now)
+        .bind(limit)
+        .bind(claim_timeout_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut events = rows
+            .iter()
+            .map(Self::row_to_event)
+            .collect::<AppResult<Vec<_>>>()?;
+        events.sort_by_key(|event| event.id);
+        Ok(events)
+    }
+
+    async fn mark_published(&self, id: i64) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE outbox_events
+             SET published_at = CURRENT_TIMESTAMP, claimed_at = NULL, last_error = NULL
+             WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: DateTime<Utc>) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE outbox_events
+             SET claimed_at = NULL, last_error = $2, next_attempt_at = $3
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn purge_published(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        let result = sqlx::query(
+            "DELETE FROM outbox_events WHERE published_at IS NOT NULL AND published_at < $1",
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
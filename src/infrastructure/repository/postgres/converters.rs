@@ -1,9 +1,10 @@
 use rust_decimal::Decimal;
 use sqlx::Row;
+use std::collections::BTreeMap;
 
 use crate::app_domain::model::product::{
     Dimensions, Inventory, Price, Product, ProductHistory, ProductImage, ProductStatus,
-    ShippingInfo,
+    ProductStatusSchedule, ProductVariant, ScheduleState, ShippingInfo,
 };
 
 /// SQLクエリ結果をProductエンティティに変換
@@ -31,17 +32,12 @@ pub fn row_to_product(row: &sqlx::postgres::PgRow) -> Product {
     };
 
     let status_str: String = row.get("status");
-    let status = match status_str.as_str() {
-        "Active" => ProductStatus::Active,
-        "Inactive" => ProductStatus::Inactive,
-        "Draft" => ProductStatus::Draft,
-        "Discontinued" => ProductStatus::Discontinued,
-        _ => ProductStatus::Draft,
-    };
+    let status = parse_product_status(&status_str);
 
     Product {
         id: row.get("id"),
         name: row.get("name"),
+        slug: row.get("slug"),
         description: row.get("description"),
         sku: row.get("sku"),
         brand: row.get("brand"),
@@ -52,6 +48,18 @@ pub fn row_to_product(row: &sqlx::postgres::PgRow) -> Product {
         shipping_info,
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
+        version: row.try_get("version").unwrap_or(1),
+    }
+}
+
+/// ステータス文字列をProductStatusに変換（不明な値はDraft扱い）
+pub fn parse_product_status(status: &str) -> ProductStatus {
+    match status {
+        "Active" => ProductStatus::Active,
+        "Inactive" => ProductStatus::Inactive,
+        "Draft" => ProductStatus::Draft,
+        "Discontinued" => ProductStatus::Discontinued,
+        _ => ProductStatus::Draft,
     }
 }
 
@@ -105,3 +113,42 @@ pub fn row_to_product_history(row: &sqlx::postgres::PgRow) -> ProductHistory {
         changed_at: row.get("changed_at"),
     }
 }
+
+/// SQLクエリ結果をProductVariantエンティティに変換（オプション値は別途設定）
+pub fn row_to_product_variant(row: &sqlx::postgres::PgRow) -> ProductVariant {
+    ProductVariant {
+        id: row.get("id"),
+        product_id: row.get("product_id"),
+        sku: row.get("sku"),
+        option_values: BTreeMap::new(),
+        price: row_to_price(row),
+        inventory: row_to_inventory(row),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// SQLクエリ結果をProductStatusScheduleエンティティに変換
+pub fn row_to_status_schedule(row: &sqlx::postgres::PgRow) -> ProductStatusSchedule {
+    let target_status: String = row.get("target_status");
+    let state: String = row.get("state");
+
+    ProductStatusSchedule {
+        id: row.get("id"),
+        product_id: row.get("product_id"),
+        target_status: parse_product_status(&target_status),
+        scheduled_at: row.get("scheduled_at"),
+        state: match state.as_str() {
+            "processing" => ScheduleState::Processing,
+            "applied" => ScheduleState::Applied,
+            "cancelled" => ScheduleState::Cancelled,
+            "failed" => ScheduleState::Failed,
+            _ => ScheduleState::Pending,
+        },
+        reason: row.try_get("reason").unwrap_or(None),
+        created_by: row.try_get("created_by").unwrap_or(None),
+        executed_at: row.try_get("executed_at").unwrap_or(None),
+        failure_reason: row.try_get("failure_reason").unwrap_or(None),
+        created_at: row.get("created_at"),
+    }
+}
@@ -1,6 +1,12 @@
 pub mod converters;
+pub mod product_bundles;
+pub mod product_export;
 pub mod product_extensions;
+pub mod product_import;
 pub mod product_metadata;
 pub mod product_repository;
+pub mod product_schedules;
+pub mod product_translations;
+pub mod product_variants;
 
 pub use product_repository::PostgresProductRepository;
@@ -0,0 +1,164 @@
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Row};
+use tracing::error;
+
+use crate::app_domain::model::product::{
+    BundleComponent, BundlePricing, ProductBundle, ProductError,
+};
+
+/// Product repository extensions for bundle (kit) definitions
+pub struct ProductBundles<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductBundles<'_> {
+    pub async fn get_bundle(&self, product_id: &str) -> Option<ProductBundle> {
+        let query = "SELECT pricing_type, discount_rate FROM product_bundles WHERE product_id = $1";
+
+        let row = match sqlx::query(query)
+            .bind(product_id)
+            .fetch_optional(self.pool)
+            .await
+        {
+            Ok(Some(row)) => row,
+            Ok(None) => return None,
+            Err(e) => {
+                error!("Error fetching bundle {}: {}", product_id, e);
+                return None;
+            }
+        };
+
+        let pricing_type: String = row.get("pricing_type");
+        let pricing = match pricing_type.as_str() {
+            "discounted_sum" => BundlePricing::DiscountedSum {
+                discount_rate: row
+                    .try_get::<Option<Decimal>, _>("discount_rate")
+                    .unwrap_or(None)
+                    .unwrap_or(Decimal::ZERO),
+            },
+            _ => BundlePricing::Fixed,
+        };
+
+        let components_query = "SELECT component_id, quantity
+                                FROM product_bundle_components
+                                WHERE bundle_id = $1
+                                ORDER BY id";
+
+        match sqlx::query(components_query)
+            .bind(product_id)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => Some(ProductBundle {
+                product_id: product_id.to_string(),
+                components: rows
+                    .iter()
+                    .map(|row| BundleComponent {
+                        product_id: row.get("component_id"),
+                        quantity: row.get("quantity"),
+                    })
+                    .collect(),
+                pricing,
+            }),
+            Err(e) => {
+                error!("Error fetching components of bundle {}: {}", product_id, e);
+                None
+            }
+        }
+    }
+
+    pub async fn set_bundle(&self, bundle: ProductBundle) -> Result<(), ProductError> {
+        bundle.validate()?;
+
+        let (pricing_type, discount_rate) = match bundle.pricing {
+            BundlePricing::Fixed => ("fixed", None),
+            BundlePricing::DiscountedSum { discount_rate } => {
+                ("discounted_sum", Some(discount_rate))
+            }
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let upsert_query = "INSERT INTO product_bundles (product_id, pricing_type, discount_rate)
+                            VALUES ($1, $2, $3)
+                            ON CONFLICT (product_id)
+                            DO UPDATE SET pricing_type = $2, discount_rate = $3";
+
+        if let Err(e) = sqlx::query(upsert_query)
+            .bind(&bundle.product_id)
+            .bind(pricing_type)
+            .bind(discount_rate)
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        let delete_query = "DELETE FROM product_bundle_components WHERE bundle_id = $1";
+        if let Err(e) = sqlx::query(delete_query)
+            .bind(&bundle.product_id)
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        for component in &bundle.components {
+            let insert_query =
+                "INSERT INTO product_bundle_components (bundle_id, component_id, quantity)
+                                VALUES ($1, $2, $3)";
+
+            if let Err(e) = sqlx::query(insert_query)
+                .bind(&bundle.product_id)
+                .bind(&component.product_id)
+                .bind(component.quantity)
+                .execute(&mut *tx)
+                .await
+            {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_bundle(&self, product_id: &str) -> Result<(), ProductError> {
+        let query = "DELETE FROM product_bundles WHERE product_id = $1";
+
+        match sqlx::query(query).bind(product_id).execute(self.pool).await {
+            Ok(result) if result.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(ProductError::BundleNotFound),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn find_bundles_by_component(&self, component_id: &str) -> Vec<String> {
+        let query = "SELECT bundle_id
+                     FROM product_bundle_components
+                     WHERE component_id = $1
+                     ORDER BY bundle_id";
+
+        match sqlx::query(query)
+            .bind(component_id)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows.iter().map(|row| row.get("bundle_id")).collect(),
+            Err(e) => {
+                error!("Error finding bundles containing {}: {}", component_id, e);
+                vec![]
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap};
+
+use super::converters::{row_to_inventory, row_to_price};
+use super::product_repository::find_by_filter;
+use crate::app_domain::model::product::{Inventory, Price, ProductError};
+use crate::app_domain::model::product_export::ProductExportRow;
+use crate::app_domain::model::product_filter::ProductFilter;
+
+/// Product repository extensions for catalog exports
+pub struct ProductExports<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductExports<'_> {
+    /// 商品 ID 順に `after_id` より後の商品を最大 `limit` 件取得する
+    ///
+    /// 価格・在庫・タグ・属性・メイン画像はページ単位でまとめて取得する。
+    pub async fn export_products(
+        &self,
+        filter: &ProductFilter,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ProductExportRow>, ProductError> {
+        let products = find_by_filter(self.pool, filter, after_id, limit).await?;
+        if products.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let ids: Vec<String> = products.iter().map(|p| p.id.clone()).collect();
+        let mut prices = self.prices(&ids).await?;
+        let mut inventories = self.inventories(&ids).await?;
+        let mut tags = self.tags(&ids).await?;
+        let mut attributes = self.attributes(&ids).await?;
+        let mut main_images = self.main_image_urls(&ids).await?;
+
+        Ok(products
+            .into_iter()
+            .map(|product| ProductExportRow {
+                price: prices.remove(&product.id),
+                inventory: inventories.remove(&product.id),
+                tags: tags.remove(&product.id).unwrap_or_default(),
+                attributes: attributes.remove(&product.id).unwrap_or_default(),
+                main_image_url: main_images.remove(&product.id),
+                product,
+            })
+            .collect())
+    }
+
+    async fn prices(&self, ids: &[String]) -> Result<HashMap<String, Price>, ProductError> {
+        let query =
+            "SELECT DISTINCT ON (product_id) product_id, selling_price, list_price, discount_price,
+                            currency, tax_included, effective_from, effective_until
+                     FROM product_prices
+                     WHERE product_id = ANY($1)
+                       AND (effective_from IS NULL OR effective_from <= NOW())
+                       AND (effective_until IS NULL OR effective_until >= NOW())
+                     ORDER BY product_id, created_at DESC";
+
+        sqlx::query(query)
+            .bind(ids)
+            .fetch_all(self.pool)
+            .await
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| (row.get("product_id"), row_to_price(row)))
+                    .collect()
+            })
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
+    }
+
+    async fn inventories(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, Inventory>, ProductError> {
+        let query = "SELECT product_id, quantity, reserved_quantity, alert_threshold,
+                            track_inventory, allow_backorder
+                     FROM product_inventory
+                     WHERE product_id = ANY($1)";
+
+        sqlx::query(query)
+            .bind(ids)
+            .fetch_all(self.pool)
+            .await
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| (row.get("product_id"), row_to_inventory(row)))
+                    .collect()
+            })
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
+    }
+
+    async fn tags(&self, ids: &[String]) -> Result<HashMap<String, Vec<String>>, ProductError> {
+        let query = "SELECT product_id, tag FROM product_tags
+                     WHERE product_id = ANY($1)
+                     ORDER BY product_id, tag";
+
+        let rows = sqlx::query(query)
+            .bind(ids)
+            .fetch_all(self.pool)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            tags.entry(row.get("product_id"))
+                .or_default()
+                .push(row.get("tag"));
+        }
+        Ok(tags)
+    }
+
+    async fn attributes(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, BTreeMap<String, String>>, ProductError> {
+        let query = "SELECT product_id, attribute_name, attribute_value FROM product_attributes
+                     WHERE product_id = ANY($1)";
+
+        let rows = sqlx::query(query)
+            .bind(ids)
+            .fetch_all(self.pool)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let mut attributes: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+        for row in rows {
+            attributes
+                .entry(row.get("product_id"))
+                .or_default()
+                .insert(row.get("attribute_name"), row.get("attribute_value"));
+        }
+        Ok(attributes)
+    }
+
+    /// メイン画像の URL（未設定の場合は表示順で先頭の画像）
+    async fn main_image_urls(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, String>, ProductError> {
+        let query = "SELECT DISTINCT ON (product_id) product_id, url
+                     FROM product_images
+                     WHERE product_id = ANY($1)
+                     ORDER BY product_id, is_main DESC, sort_order, created_at";
+
+        sqlx::query(query)
+            .bind(ids)
+            .fetch_all(self.pool)
+            .await
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| (row.get("product_id"), row.get("url")))
+                    .collect()
+            })
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
+    }
+}
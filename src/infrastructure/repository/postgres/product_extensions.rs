@@ -1,8 +1,136 @@
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use tracing::error;
 
 use super::converters::{row_to_inventory, row_to_price, row_to_product_image};
-use crate::app_domain::model::product::{Inventory, Price, ProductError, ProductImage};
+use super::product_metadata::{insert_event, record_revision, record_revision_with_event};
+use crate::app_domain::model::domain_event::{DomainEvent, StockLevel};
+use crate::app_domain::model::product::{
+    ChangeContext, FieldChange, Inventory, Price, ProductError, ProductImage,
+};
+
+/// 現在有効な価格を呼び出し元のトランザクション内で取得する
+pub async fn price_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<Option<Price>, ProductError> {
+    let query = "SELECT selling_price, list_price, discount_price, currency, tax_included,
+                        effective_from, effective_until
+                 FROM product_prices
+                 WHERE product_id = $1
+                   AND (effective_from IS NULL OR effective_from <= NOW())
+                   AND (effective_until IS NULL OR effective_until >= NOW())
+                 ORDER BY created_at DESC
+                 LIMIT 1";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map(|row| row.as_ref().map(row_to_price))
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+/// 在庫をロックして取得する
+pub async fn inventory_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<Option<Inventory>, ProductError> {
+    let query =
+        "SELECT quantity, reserved_quantity, alert_threshold, track_inventory, allow_backorder
+         FROM product_inventory
+         WHERE product_id = $1
+         FOR UPDATE";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map(|row| row.as_ref().map(row_to_inventory))
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+/// 商品の行をロックし、画像の追加と並び替えのように子の行をまとめて扱う操作を直列化する
+pub async fn lock_product(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<(), ProductError> {
+    sqlx::query("SELECT id FROM products WHERE id = $1 FOR UPDATE")
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?
+        .map(|_| ())
+        .ok_or(ProductError::ProductNotFound)
+}
+
+/// 新しい価格を登録し、差分を呼び出し元のトランザクション内で記録する
+pub async fn write_price(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    price: &Price,
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let previous = price_in_tx(tx, product_id).await?;
+
+    let query =
+        "INSERT INTO product_prices (product_id, selling_price, list_price, discount_price, 
+                                           currency, tax_included, effective_from, effective_until)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .bind(price.selling_price)
+        .bind(price.list_price)
+        .bind(price.discount_price)
+        .bind(&price.currency)
+        .bind(price.tax_included)
+        .bind(price.effective_from)
+        .bind(price.effective_until)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    let changes = Price::field_changes(previous.as_ref(), price);
+    let event = DomainEvent::price_changed(product_id, previous.as_ref(), price);
+    record_revision_with_event(tx, product_id, &changes, ctx, event).await
+}
+
+/// 在庫を更新し、差分を呼び出し元のトランザクション内で記録する
+pub async fn write_inventory(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    inventory: &Inventory,
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let previous = inventory_for_update(tx, product_id)
+        .await?
+        .ok_or(ProductError::ProductNotFound)?;
+
+    let query = "UPDATE product_inventory 
+                 SET quantity = $2, reserved_quantity = $3, alert_threshold = $4, 
+                     track_inventory = $5, allow_backorder = $6, updated_at = NOW()
+                 WHERE product_id = $1";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .bind(inventory.quantity)
+        .bind(inventory.reserved_quantity)
+        .bind(inventory.alert_threshold)
+        .bind(inventory.track_inventory)
+        .bind(inventory.allow_backorder)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    let changes = Inventory::field_changes(Some(&previous), inventory);
+    let event = DomainEvent::inventory_adjusted(
+        product_id,
+        StockLevel::from(&previous),
+        StockLevel::from(inventory),
+    );
+    record_revision_with_event(tx, product_id, &changes, ctx, event).await
+}
 
 /// Product repository extensions for price, inventory, and image management
 pub struct ProductExtensions<'a> {
@@ -41,29 +169,27 @@ impl ProductExtensions<'_> {
         &self,
         product_id: &str,
         price: Price,
+        ctx: &ChangeContext,
     ) -> Result<Price, ProductError> {
         // Validate price before updating
         price.validate()?;
 
-        let query = "INSERT INTO product_prices (product_id, selling_price, list_price, discount_price, 
-                                               currency, tax_included, effective_from, effective_until)
-                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)";
-
-        match sqlx::query(query)
-            .bind(product_id)
-            .bind(price.selling_price)
-            .bind(price.list_price)
-            .bind(price.discount_price)
-            .bind(&price.currency)
-            .bind(price.tax_included)
-            .bind(price.effective_from)
-            .bind(price.effective_until)
-            .execute(self.pool)
+        let mut tx = self
+            .pool
+            .begin()
             .await
-        {
-            Ok(_) => Ok(price),
-            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        if let Err(e) = write_price(&mut tx, product_id, &price, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(price)
     }
 
     pub async fn get_inventory(&self, product_id: &str) -> Option<Inventory> {
@@ -90,28 +216,174 @@ impl ProductExtensions<'_> {
         &self,
         product_id: &str,
         inventory: Inventory,
+        ctx: &ChangeContext,
     ) -> Result<Inventory, ProductError> {
         inventory.validate()?;
 
-        let query = "UPDATE product_inventory 
-                     SET quantity = $2, reserved_quantity = $3, alert_threshold = $4, 
-                         track_inventory = $5, allow_backorder = $6, updated_at = NOW()
-                     WHERE product_id = $1";
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
-        match sqlx::query(query)
-            .bind(product_id)
-            .bind(inventory.quantity)
-            .bind(inventory.reserved_quantity)
-            .bind(inventory.alert_threshold)
-            .bind(inventory.track_inventory)
-            .bind(inventory.allow_backorder)
-            .execute(self.pool)
+        if let Err(e) = write_inventory(&mut tx, product_id, &inventory, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
             .await
-        {
-            Ok(result) if result.rows_affected() > 0 => Ok(inventory),
-            Ok(_) => Err(ProductError::ProductNotFound),
-            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(inventory)
+    }
+
+    pub async fn reserve_inventory(
+        &self,
+        mut reservations: Vec<(String, i32)>,
+    ) -> Result<(), ProductError> {
+        // Lock rows in a stable order to avoid deadlocks between concurrent reservations
+        reservations.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        for (product_id, quantity) in reservations {
+            if quantity <= 0 {
+                let _ = tx.rollback().await;
+                return Err(ProductError::InvalidInventoryQuantity);
+            }
+
+            let inventory = match inventory_for_update(&mut tx, &product_id).await {
+                Ok(Some(inventory)) => inventory,
+                Ok(None) => {
+                    let _ = tx.rollback().await;
+                    return Err(ProductError::ProductNotFound);
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            };
+
+            // 販売可能数の算出と同じ規則で、在庫に制約されない商品は引き当てない
+            if !inventory.limits_availability() {
+                continue;
+            }
+
+            let stock = inventory.quantity;
+            let reserved = inventory.reserved_quantity;
+            if stock - reserved < quantity {
+                let _ = tx.rollback().await;
+                return Err(ProductError::InsufficientInventory);
+            }
+
+            let update_query = "UPDATE product_inventory
+                                SET reserved_quantity = reserved_quantity + $2, updated_at = NOW()
+                                WHERE product_id = $1";
+
+            if let Err(e) = sqlx::query(update_query)
+                .bind(&product_id)
+                .bind(quantity)
+                .execute(&mut *tx)
+                .await
+            {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+
+            let event = DomainEvent::inventory_adjusted(
+                &product_id,
+                StockLevel {
+                    quantity: stock,
+                    reserved_quantity: reserved,
+                },
+                StockLevel {
+                    quantity: stock,
+                    reserved_quantity: reserved + quantity,
+                },
+            );
+            if let Err(e) = insert_event(&mut tx, &event).await {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn release_inventory(
+        &self,
+        mut reservations: Vec<(String, i32)>,
+    ) -> Result<(), ProductError> {
+        reservations.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        for (product_id, quantity) in reservations {
+            if quantity <= 0 {
+                let _ = tx.rollback().await;
+                return Err(ProductError::InvalidInventoryQuantity);
+            }
+
+            let previous = match inventory_for_update(&mut tx, &product_id).await {
+                // 引当時に引き当てなかった商品は解放もしない
+                Ok(Some(inventory)) if !inventory.limits_availability() => continue,
+                Ok(Some(inventory)) => StockLevel::from(&inventory),
+                Ok(None) => {
+                    let _ = tx.rollback().await;
+                    return Err(ProductError::ProductNotFound);
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            };
+
+            let query = "UPDATE product_inventory
+                         SET reserved_quantity = GREATEST(0, reserved_quantity - $2), updated_at = NOW()
+                         WHERE product_id = $1";
+
+            if let Err(e) = sqlx::query(query)
+                .bind(&product_id)
+                .bind(quantity)
+                .execute(&mut *tx)
+                .await
+            {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+
+            let event = DomainEvent::inventory_adjusted(
+                &product_id,
+                previous,
+                StockLevel {
+                    quantity: previous.quantity,
+                    reserved_quantity: (previous.reserved_quantity - quantity).max(0),
+                },
+            );
+            if let Err(e) = insert_event(&mut tx, &event).await {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
     }
 
     pub async fn get_images(&self, product_id: &str) -> Vec<ProductImage> {
@@ -137,31 +409,54 @@ impl ProductExtensions<'_> {
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError> {
+        image.validate()?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        // 並び替えと並行して画像が増えないよう、商品の行ロックを取ってから数える
+        if let Err(e) = lock_product(&mut tx, product_id).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
         // Check image count limit
         let count_query = "SELECT COUNT(*) as count FROM product_images WHERE product_id = $1";
-        let count_result = sqlx::query(count_query)
+        if let Ok(row) = sqlx::query(count_query)
             .bind(product_id)
-            .fetch_one(self.pool)
-            .await;
-
-        if let Ok(row) = count_result {
+            .fetch_one(&mut *tx)
+            .await
+        {
             let count: i64 = row.get("count");
             if count >= 10 {
+                let _ = tx.rollback().await;
                 return Err(ProductError::TooManyImages);
             }
         }
 
-        image.validate()?;
-
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+        let mut changes = Vec::new();
 
         // If setting as main image, unset others
         if image.is_main {
+            match main_image_id(&mut tx, product_id).await {
+                Ok(previous_main) => {
+                    changes.extend(FieldChange::diff(
+                        "images.main",
+                        previous_main,
+                        Some(image.id.clone()),
+                    ));
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            }
+
             let unset_query = "UPDATE product_images SET is_main = false WHERE product_id = $1";
             if let Err(e) = sqlx::query(unset_query)
                 .bind(product_id)
@@ -177,7 +472,7 @@ impl ProductExtensions<'_> {
             "INSERT INTO product_images (id, product_id, url, alt_text, sort_order, is_main)
                      VALUES ($1, $2, $3, $4, $5, $6)";
 
-        let result = sqlx::query(query)
+        if let Err(e) = sqlx::query(query)
             .bind(&image.id)
             .bind(product_id)
             .bind(&image.url)
@@ -185,26 +480,30 @@ impl ProductExtensions<'_> {
             .bind(image.sort_order)
             .bind(image.is_main)
             .execute(&mut *tx)
-            .await;
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
 
-        match result {
-            Ok(_) => {
-                tx.commit()
-                    .await
-                    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
-                Ok(image)
-            }
-            Err(e) => {
-                let _ = tx.rollback().await;
-                Err(ProductError::DatabaseError(e.to_string()))
-            }
+        changes.extend(ProductImage::field_changes(None, Some(&image)));
+        if let Err(e) = record_revision(&mut tx, product_id, &changes, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(image)
     }
 
     pub async fn update_image(
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError> {
         image.validate()?;
 
@@ -214,8 +513,32 @@ impl ProductExtensions<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
+        let previous = match image_for_update(&mut tx, product_id, &image.id).await {
+            Ok(previous) => previous,
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        };
+
+        let mut changes = Vec::new();
+
         // If setting as main image, unset others
         if image.is_main {
+            match main_image_id(&mut tx, product_id).await {
+                Ok(previous_main) => {
+                    changes.extend(FieldChange::diff(
+                        "images.main",
+                        previous_main,
+                        Some(image.id.clone()),
+                    ));
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            }
+
             let unset_query =
                 "UPDATE product_images SET is_main = false WHERE product_id = $1 AND id != $2";
             if let Err(e) = sqlx::query(unset_query)
@@ -233,7 +556,7 @@ impl ProductExtensions<'_> {
                      SET url = $3, alt_text = $4, sort_order = $5, is_main = $6, updated_at = NOW()
                      WHERE product_id = $1 AND id = $2";
 
-        let result = sqlx::query(query)
+        if let Err(e) = sqlx::query(query)
             .bind(product_id)
             .bind(&image.id)
             .bind(&image.url)
@@ -241,45 +564,74 @@ impl ProductExtensions<'_> {
             .bind(image.sort_order)
             .bind(image.is_main)
             .execute(&mut *tx)
-            .await;
-
-        match result {
-            Ok(result) if result.rows_affected() > 0 => {
-                tx.commit()
-                    .await
-                    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
-                Ok(image)
-            }
-            Ok(_) => {
-                let _ = tx.rollback().await;
-                Err(ProductError::ImageNotFound)
-            }
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        changes.extend(ProductImage::field_changes(Some(&previous), Some(&image)));
+        if let Err(e) = record_revision(&mut tx, product_id, &changes, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(image)
+    }
+
+    pub async fn delete_image(
+        &self,
+        product_id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let previous = match image_for_update(&mut tx, product_id, image_id).await {
+            Ok(previous) => previous,
             Err(e) => {
                 let _ = tx.rollback().await;
-                Err(ProductError::DatabaseError(e.to_string()))
+                return Err(e);
             }
-        }
-    }
+        };
 
-    pub async fn delete_image(&self, product_id: &str, image_id: &str) -> Result<(), ProductError> {
         let query = "DELETE FROM product_images WHERE product_id = $1 AND id = $2";
-
-        match sqlx::query(query)
+        if let Err(e) = sqlx::query(query)
             .bind(product_id)
             .bind(image_id)
-            .execute(self.pool)
+            .execute(&mut *tx)
             .await
         {
-            Ok(result) if result.rows_affected() > 0 => Ok(()),
-            Ok(_) => Err(ProductError::ImageNotFound),
-            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        let changes = ProductImage::field_changes(Some(&previous), None);
+        if let Err(e) = record_revision(&mut tx, product_id, &changes, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
     }
 
     pub async fn reorder_images(
         &self,
         product_id: &str,
         image_orders: Vec<(String, i32)>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let mut tx = self
             .pool
@@ -287,12 +639,24 @@ impl ProductExtensions<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
+        let mut changes = Vec::new();
+
         for (image_id, sort_order) in image_orders {
             if sort_order < 0 {
                 let _ = tx.rollback().await;
                 return Err(ProductError::InvalidImageOrder);
             }
 
+            let previous = match image_for_update(&mut tx, product_id, &image_id).await {
+                Ok(previous) => previous,
+                // Images that do not belong to the product are ignored
+                Err(ProductError::ImageNotFound) => continue,
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            };
+
             let query =
                 "UPDATE product_images SET sort_order = $3 WHERE product_id = $1 AND id = $2";
 
@@ -306,6 +670,17 @@ impl ProductExtensions<'_> {
                 let _ = tx.rollback().await;
                 return Err(ProductError::DatabaseError(e.to_string()));
             }
+
+            changes.extend(FieldChange::diff(
+                format!("images.{}.sort_order", image_id),
+                Some(previous.sort_order.to_string()),
+                Some(sort_order.to_string()),
+            ));
+        }
+
+        if let Err(e) = record_revision(&mut tx, product_id, &changes, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
 
         tx.commit()
@@ -315,10 +690,85 @@ impl ProductExtensions<'_> {
         Ok(())
     }
 
+    /// 画像 ID の一覧の順に並び順を振り直す（一覧は商品の画像すべてとちょうど一致しなければならない）
+    pub async fn set_image_order(
+        &self,
+        product_id: &str,
+        image_ids: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        // 画像の行ロックでは追加を防げないため、画像の追加と同じく商品の行ロックを取る
+        if let Err(e) = lock_product(&mut tx, product_id).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        let rows = sqlx::query(
+            "SELECT id, sort_order FROM product_images WHERE product_id = $1 FOR UPDATE",
+        )
+        .bind(product_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+        let current: HashMap<String, i32> = rows
+            .iter()
+            .map(|row| (row.get("id"), row.get("sort_order")))
+            .collect();
+
+        let requested: HashSet<&str> = image_ids.iter().map(String::as_str).collect();
+        if requested.len() != image_ids.len()
+            || requested.len() != current.len()
+            || !image_ids.iter().all(|id| current.contains_key(id))
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::InvalidImageOrder);
+        }
+
+        let mut changes = Vec::new();
+        for (position, image_id) in image_ids.iter().enumerate() {
+            let sort_order = position as i32;
+            let previous = current[image_id];
+            if previous == sort_order {
+                continue;
+            }
+
+            sqlx::query(
+                "UPDATE product_images SET sort_order = $3 WHERE product_id = $1 AND id = $2",
+            )
+            .bind(product_id)
+            .bind(image_id)
+            .bind(sort_order)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+            changes.extend(FieldChange::diff(
+                format!("images.{}.sort_order", image_id),
+                Some(previous.to_string()),
+                Some(sort_order.to_string()),
+            ));
+        }
+
+        record_revision(&mut tx, product_id, &changes, ctx).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub async fn set_main_image(
         &self,
         product_id: &str,
         image_id: &str,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let mut tx = self
             .pool
@@ -326,6 +776,14 @@ impl ProductExtensions<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
+        let previous_main = match main_image_id(&mut tx, product_id).await {
+            Ok(previous_main) => previous_main,
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        };
+
         // Unset all main images for this product
         let unset_query = "UPDATE product_images SET is_main = false WHERE product_id = $1";
         if let Err(e) = sqlx::query(unset_query)
@@ -340,27 +798,72 @@ impl ProductExtensions<'_> {
         // Set the specified image as main
         let set_query =
             "UPDATE product_images SET is_main = true WHERE product_id = $1 AND id = $2";
-        let result = sqlx::query(set_query)
+        match sqlx::query(set_query)
             .bind(product_id)
             .bind(image_id)
             .execute(&mut *tx)
-            .await;
-
-        match result {
-            Ok(result) if result.rows_affected() > 0 => {
-                tx.commit()
-                    .await
-                    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
-                Ok(())
-            }
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => {}
             Ok(_) => {
                 let _ = tx.rollback().await;
-                Err(ProductError::ImageNotFound)
+                return Err(ProductError::ImageNotFound);
             }
             Err(e) => {
                 let _ = tx.rollback().await;
-                Err(ProductError::DatabaseError(e.to_string()))
+                return Err(ProductError::DatabaseError(e.to_string()));
             }
         }
+
+        let changes: Vec<FieldChange> =
+            FieldChange::diff("images.main", previous_main, Some(image_id.to_string()))
+                .into_iter()
+                .collect();
+        if let Err(e) = record_revision(&mut tx, product_id, &changes, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+async fn image_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    image_id: &str,
+) -> Result<ProductImage, ProductError> {
+    let query = "SELECT id, url, alt_text, sort_order, is_main
+                 FROM product_images
+                 WHERE product_id = $1 AND id = $2
+                 FOR UPDATE";
+
+    match sqlx::query(query)
+        .bind(product_id)
+        .bind(image_id)
+        .fetch_optional(&mut **tx)
+        .await
+    {
+        Ok(Some(row)) => Ok(row_to_product_image(&row)),
+        Ok(None) => Err(ProductError::ImageNotFound),
+        Err(e) => Err(ProductError::DatabaseError(e.to_string())),
     }
 }
+
+async fn main_image_id(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<Option<String>, ProductError> {
+    let query = "SELECT id FROM product_images WHERE product_id = $1 AND is_main = true LIMIT 1";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map(|row| row.map(|row| row.get("id")))
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
@@ -0,0 +1,178 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::converters::row_to_product;
+use super::product_extensions::{inventory_for_update, price_in_tx, write_inventory, write_price};
+use super::product_metadata::{attributes_in_tx, write_attributes, write_tags};
+use super::product_repository::{available_slug, insert_product, update_product};
+use crate::app_domain::model::product::{ChangeContext, ProductError};
+use crate::app_domain::model::product_import::{
+    ImportRowError, ImportRowOutcome, ProductImportRecord, ProductSnapshot,
+};
+use crate::infrastructure::repository::category_repository::attribute_schema;
+
+/// Product repository extensions for bulk imports
+pub struct ProductImports<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductImports<'_> {
+    pub async fn import_products(
+        &self,
+        records: Vec<ProductImportRecord>,
+        ctx: &ChangeContext,
+    ) -> Result<Vec<ImportRowOutcome>, ImportRowError> {
+        let first_line = records.first().map(|r| r.line).unwrap_or_default();
+        let database_error =
+            |e: sqlx::Error| ImportRowError::new(first_line, None, None, e.to_string());
+
+        let mut tx = self.pool.begin().await.map_err(database_error)?;
+
+        let mut outcomes = Vec::with_capacity(records.len());
+        for record in &records {
+            match import_record(&mut tx, record, ctx).await {
+                Ok(outcome) => outcomes.push(outcome),
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+            }
+        }
+
+        tx.commit().await.map_err(database_error)?;
+
+        Ok(outcomes)
+    }
+}
+
+/// SKU の商品をロックし、価格・在庫・属性とあわせて取得する
+async fn snapshot_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    sku: &str,
+) -> Result<Option<ProductSnapshot>, ProductError> {
+    let query = "SELECT id, name, slug, description, sku, brand, status, category_id,
+                        width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
+                        created_at, updated_at, version
+                 FROM products
+                 WHERE sku = $1
+                 FOR UPDATE";
+
+    let product = match sqlx::query(query)
+        .bind(sku)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?
+    {
+        Some(row) => row_to_product(&row),
+        None => return Ok(None),
+    };
+
+    let price = price_in_tx(tx, &product.id).await?;
+    let inventory = inventory_for_update(tx, &product.id).await?;
+    let attributes = attributes_in_tx(tx, &product.id).await?;
+
+    Ok(Some(ProductSnapshot {
+        product,
+        price,
+        inventory,
+        attributes,
+    }))
+}
+
+async fn import_record(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &ProductImportRecord,
+    ctx: &ChangeContext,
+) -> Result<ImportRowOutcome, ImportRowError> {
+    let row_error = |e: ProductError| {
+        ImportRowError::new(record.line, Some(record.sku.clone()), None, e.to_string())
+    };
+
+    let current = snapshot_for_update(tx, &record.sku)
+        .await
+        .map_err(row_error)?;
+    let mut resolved = record.resolve(current.as_ref(), || Uuid::new_v4().to_string())?;
+    if resolved.is_new {
+        // 名前から生成したスラッグが既に使われていれば連番を付け、指定されたスラッグは重複をエラーにする
+        let slug = available_slug(tx, &resolved.product.slug)
+            .await
+            .map_err(row_error)?;
+        if record.slug.is_some() && slug != resolved.product.slug {
+            return Err(ImportRowError::new(
+                record.line,
+                Some(record.sku.clone()),
+                Some("slug".to_string()),
+                ProductError::SlugAlreadyExists.to_string(),
+            ));
+        }
+        resolved.product.slug = slug;
+    }
+    let product_id = resolved.product.id.clone();
+
+    // 属性はカテゴリ（祖先を含む）の属性定義に合わせる。カテゴリが変わる場合は既存の属性も検証する
+    let category_changed = current
+        .as_ref()
+        .is_none_or(|s| s.product.category_id != resolved.product.category_id);
+    if let Some(category_id) = &resolved.product.category_id {
+        if resolved.attributes.is_some() || category_changed {
+            let schema = attribute_schema(&mut **tx, category_id)
+                .await
+                .map_err(|e| row_error(ProductError::DatabaseError(e.to_string())))?;
+            let attributes = match &resolved.attributes {
+                Some(attributes) => attributes.clone(),
+                None => current
+                    .as_ref()
+                    .map(|s| s.attributes.clone())
+                    .unwrap_or_default(),
+            };
+            let normalized = schema.validate(attributes).map_err(|violations| {
+                ImportRowError::new(
+                    record.line,
+                    Some(record.sku.clone()),
+                    Some("attributes".to_string()),
+                    ProductError::InvalidAttributes(violations).to_string(),
+                )
+            })?;
+            if resolved.attributes.is_some() {
+                resolved.attributes = Some(normalized);
+            }
+        }
+    }
+
+    if resolved.is_new {
+        insert_product(tx, &resolved.product, ctx)
+            .await
+            .map_err(row_error)?;
+    } else if resolved.product_changed {
+        update_product(tx, resolved.product, ctx)
+            .await
+            .map_err(row_error)?;
+    }
+
+    if let Some(price) = &resolved.price {
+        write_price(tx, &product_id, price, ctx)
+            .await
+            .map_err(row_error)?;
+    }
+    if let Some(inventory) = &resolved.inventory {
+        write_inventory(tx, &product_id, inventory, ctx)
+            .await
+            .map_err(row_error)?;
+    }
+    if let Some(tags) = &resolved.tags {
+        write_tags(tx, &product_id, tags, ctx)
+            .await
+            .map_err(row_error)?;
+    }
+    if let Some(attributes) = &resolved.attributes {
+        write_attributes(tx, &product_id, attributes, ctx)
+            .await
+            .map_err(row_error)?;
+    }
+
+    Ok(if resolved.is_new {
+        ImportRowOutcome::Created
+    } else {
+        ImportRowOutcome::Updated
+    })
+}
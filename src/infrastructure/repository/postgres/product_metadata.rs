@@ -1,9 +1,184 @@
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, Row, Transaction};
 use std::collections::HashMap;
 use tracing::error;
 
 use super::converters::row_to_product_history;
-use crate::app_domain::model::product::{ProductError, ProductHistory};
+use crate::app_domain::model::domain_event::DomainEvent;
+use crate::app_domain::model::product::{
+    ChangeContext, FieldChange, ProductError, ProductHistory, ProductStatus,
+};
+use crate::infrastructure::repository::outbox_repository::append_event;
+
+/// 変更差分を呼び出し元のトランザクション内で product_history に書き込む
+pub async fn insert_history(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    changes: &[FieldChange],
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let query = "INSERT INTO product_history (product_id, field_name, old_value, new_value, changed_by, reason)
+                 VALUES ($1, $2, $3, $4, $5, $6)";
+
+    for change in changes {
+        sqlx::query(query)
+            .bind(product_id)
+            .bind(&change.field_name)
+            .bind(&change.old_value)
+            .bind(&change.new_value)
+            .bind(&ctx.changed_by)
+            .bind(&ctx.reason)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// ドメインイベントを呼び出し元のトランザクション内で outbox に書き込む
+pub async fn insert_event(
+    tx: &mut Transaction<'_, Postgres>,
+    event: &DomainEvent,
+) -> Result<(), ProductError> {
+    append_event(tx, event)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+/// 変更履歴を記録し、変更があれば商品のバージョンを進めて ProductUpdated を発行する
+pub async fn record_revision(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    changes: &[FieldChange],
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let event = DomainEvent::product_updated(product_id, changes);
+    record_revision_with_event(tx, product_id, changes, ctx, event).await
+}
+
+/// `record_revision` と同じだが、ProductUpdated の代わりに指定したイベントを発行する
+pub async fn record_revision_with_event(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    changes: &[FieldChange],
+    ctx: &ChangeContext,
+    event: DomainEvent,
+) -> Result<(), ProductError> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    insert_history(tx, product_id, changes, ctx).await?;
+
+    sqlx::query("UPDATE products SET version = version + 1 WHERE id = $1")
+        .bind(product_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    insert_event(tx, &event).await
+}
+
+async fn tags_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<Vec<String>, ProductError> {
+    let query = "SELECT tag FROM product_tags WHERE product_id = $1 ORDER BY tag";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map(|rows| rows.iter().map(|row| row.get("tag")).collect())
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+pub async fn attributes_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+) -> Result<HashMap<String, String>, ProductError> {
+    let query =
+        "SELECT attribute_name, attribute_value FROM product_attributes WHERE product_id = $1";
+
+    sqlx::query(query)
+        .bind(product_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map(|rows| {
+            rows.iter()
+                .map(|row| (row.get("attribute_name"), row.get("attribute_value")))
+                .collect()
+        })
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+/// タグを置き換え、差分を呼び出し元のトランザクション内で記録する
+pub async fn write_tags(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    tags: &[String],
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let previous = tags_in_tx(tx, product_id).await?;
+    let change = FieldChange::tags(&previous, tags);
+
+    // Delete all existing tags
+    sqlx::query("DELETE FROM product_tags WHERE product_id = $1")
+        .bind(product_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    // Insert new tags
+    for tag in tags {
+        sqlx::query("INSERT INTO product_tags (product_id, tag) VALUES ($1, $2)")
+            .bind(product_id)
+            .bind(tag)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    }
+
+    if let Some(change) = change {
+        record_revision(tx, product_id, &[change], ctx).await?;
+    }
+
+    Ok(())
+}
+
+/// 属性を置き換え、差分を呼び出し元のトランザクション内で記録する
+pub async fn write_attributes(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    attributes: &HashMap<String, String>,
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let previous = attributes_in_tx(tx, product_id).await?;
+    let changes = FieldChange::attributes(&previous, attributes);
+
+    // Delete all existing attributes
+    sqlx::query("DELETE FROM product_attributes WHERE product_id = $1")
+        .bind(product_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    // Insert new attributes
+    for (name, value) in attributes {
+        sqlx::query(
+            "INSERT INTO product_attributes (product_id, attribute_name, attribute_value)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(product_id)
+        .bind(name)
+        .bind(value)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    }
+
+    record_revision(tx, product_id, &changes, ctx).await
+}
 
 /// Product repository extensions for tags, attributes, and history management
 pub struct ProductMetadata<'a> {
@@ -27,7 +202,12 @@ impl ProductMetadata<'_> {
         }
     }
 
-    pub async fn add_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError> {
+    pub async fn add_tags(
+        &self,
+        product_id: &str,
+        tags: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
         if tags.is_empty() {
             return Ok(());
         }
@@ -38,6 +218,16 @@ impl ProductMetadata<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
+        let previous = match tags_in_tx(&mut tx, product_id).await {
+            Ok(previous) => previous,
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        };
+        let mut current = previous.clone();
+        current.extend(tags.iter().cloned());
+
         for tag in tags {
             let query =
                 "INSERT INTO product_tags (product_id, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING";
@@ -53,6 +243,13 @@ impl ProductMetadata<'_> {
             }
         }
 
+        if let Some(change) = FieldChange::tags(&previous, &current) {
+            if let Err(e) = record_revision(&mut tx, product_id, &[change], ctx).await {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        }
+
         tx.commit()
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
@@ -64,6 +261,7 @@ impl ProductMetadata<'_> {
         &self,
         product_id: &str,
         tags: Vec<String>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let mut tx = self
             .pool
@@ -71,30 +269,9 @@ impl ProductMetadata<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
-        // Delete all existing tags
-        let delete_query = "DELETE FROM product_tags WHERE product_id = $1";
-        if let Err(e) = sqlx::query(delete_query)
-            .bind(product_id)
-            .execute(&mut *tx)
-            .await
-        {
+        if let Err(e) = write_tags(&mut tx, product_id, &tags, ctx).await {
             let _ = tx.rollback().await;
-            return Err(ProductError::DatabaseError(e.to_string()));
-        }
-
-        // Insert new tags
-        for tag in tags {
-            let insert_query = "INSERT INTO product_tags (product_id, tag) VALUES ($1, $2)";
-
-            if let Err(e) = sqlx::query(insert_query)
-                .bind(product_id)
-                .bind(&tag)
-                .execute(&mut *tx)
-                .await
-            {
-                let _ = tx.rollback().await;
-                return Err(ProductError::DatabaseError(e.to_string()));
-            }
+            return Err(e);
         }
 
         tx.commit()
@@ -134,6 +311,7 @@ impl ProductMetadata<'_> {
         &self,
         product_id: &str,
         attributes: HashMap<String, String>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let mut tx = self
             .pool
@@ -141,33 +319,9 @@ impl ProductMetadata<'_> {
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
-        // Delete all existing attributes
-        let delete_query = "DELETE FROM product_attributes WHERE product_id = $1";
-        if let Err(e) = sqlx::query(delete_query)
-            .bind(product_id)
-            .execute(&mut *tx)
-            .await
-        {
+        if let Err(e) = write_attributes(&mut tx, product_id, &attributes, ctx).await {
             let _ = tx.rollback().await;
-            return Err(ProductError::DatabaseError(e.to_string()));
-        }
-
-        // Insert new attributes
-        for (name, value) in attributes {
-            let insert_query =
-                "INSERT INTO product_attributes (product_id, attribute_name, attribute_value)
-                               VALUES ($1, $2, $3)";
-
-            if let Err(e) = sqlx::query(insert_query)
-                .bind(product_id)
-                .bind(&name)
-                .bind(&value)
-                .execute(&mut *tx)
-                .await
-            {
-                let _ = tx.rollback().await;
-                return Err(ProductError::DatabaseError(e.to_string()));
-            }
+            return Err(e);
         }
 
         tx.commit()
@@ -226,4 +380,69 @@ impl ProductMetadata<'_> {
             }
         }
     }
+
+    pub async fn change_status(
+        &self,
+        product_id: &str,
+        from: ProductStatus,
+        to: ProductStatus,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        // 現在のステータスをロックして確認し、並行した遷移と競合しないようにする
+        let lock_query = "SELECT status FROM products WHERE id = $1 FOR UPDATE";
+        let current: String = match sqlx::query(lock_query)
+            .bind(product_id)
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(row)) => row.get("status"),
+            Ok(None) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::ProductNotFound);
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+        };
+
+        if current != from.to_string() {
+            let _ = tx.rollback().await;
+            return Err(ProductError::InvalidStatusTransition { from, to });
+        }
+
+        let update_query = "UPDATE products SET status = $2, updated_at = NOW() WHERE id = $1";
+        if let Err(e) = sqlx::query(update_query)
+            .bind(product_id)
+            .bind(to.to_string())
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        let changes: Vec<FieldChange> =
+            FieldChange::diff("status", Some(from.to_string()), Some(to.to_string()))
+                .into_iter()
+                .collect();
+        let event = DomainEvent::product_status_changed(product_id, &from, &to);
+        if let Err(e) = record_revision_with_event(&mut tx, product_id, &changes, ctx, event).await
+        {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
 }
@@ -1,17 +1,39 @@
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::{PgPool, Row};
-use std::collections::HashMap;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use tracing::error;
 
 use super::converters::{row_to_inventory, row_to_product};
-use super::product_extensions::ProductExtensions;
-use super::product_metadata::ProductMetadata;
+use super::product_bundles::ProductBundles;
+use super::product_export::ProductExports;
+use super::product_extensions::{write_price, ProductExtensions};
+use super::product_import::ProductImports;
+use super::product_metadata::{
+    insert_event, insert_history, write_attributes, write_tags, ProductMetadata,
+};
+use super::product_schedules::ProductSchedules;
+use super::product_translations::ProductTranslations;
+use super::product_variants::ProductVariants;
+use crate::app_domain::model::attribute_schema::AttributeSchema;
+use crate::app_domain::model::domain_event::DomainEvent;
+use crate::app_domain::model::localization::{MissingTranslation, ProductTranslation};
 use crate::app_domain::model::product::{
-    Inventory, Price, Product, ProductError, ProductHistory, ProductImage,
+    ChangeContext, Inventory, Price, Product, ProductBundle, ProductError, ProductHistory,
+    ProductImage, ProductOption, ProductRevision, ProductStatus, ProductStatusSchedule,
+    ProductVariant, ScheduleState,
+};
+use crate::app_domain::model::product_export::ProductExportRow;
+use crate::app_domain::model::product_filter::{
+    AttributeCondition, AttributeFilter, ProductFilter,
+};
+use crate::app_domain::model::product_import::{
+    ImportRowError, ImportRowOutcome, ProductImportRecord,
 };
+use crate::app_domain::model::slug;
 use crate::app_domain::repository::product_repository::ProductRepository;
+use crate::infrastructure::repository::category_repository::attribute_schema;
 
 pub struct PostgresProductRepository {
     pool: PgPool,
@@ -23,12 +45,500 @@ impl PostgresProductRepository {
     }
 }
 
+fn map_sku_error(e: sqlx::Error) -> ProductError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some("products_sku_key") => {
+            ProductError::SkuAlreadyExists
+        }
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some("products_slug_key") => {
+            ProductError::SlugAlreadyExists
+        }
+        e => ProductError::DatabaseError(e.to_string()),
+    }
+}
+
+/// `base` が使用中（現在または旧スラッグ）なら連番を付けた空きスラッグを返す（呼び出し元のトランザクション内）
+pub async fn available_slug(
+    tx: &mut Transaction<'_, Postgres>,
+    base: &str,
+) -> Result<String, ProductError> {
+    let rows = sqlx::query(
+        "SELECT slug FROM products WHERE slug = $1 OR slug LIKE $2
+         UNION
+         SELECT slug FROM product_slug_redirects WHERE slug = $1 OR slug LIKE $2",
+    )
+    .bind(base)
+    .bind(format!("{}-%", base))
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    let taken: HashSet<String> = rows.iter().map(|row| row.get("slug")).collect();
+
+    Ok((1..)
+        .map(|n| slug::with_suffix(base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| base.to_string()))
+}
+
+/// SKU が他の商品・バリエーションで使われていないことを確認する（呼び出し元のトランザクション内）
+///
+/// 商品とバリエーションの SKU は同じ名前空間を共有するが、一意制約はテーブルごとにしか
+/// 張れないため、SKU ごとのアドバイザリロックで確認から登録までを直列化する。
+/// ロックはトランザクションの終了時に解放される。`variant_id` の行自身は重複として扱わない。
+pub async fn reserve_sku(
+    tx: &mut Transaction<'_, Postgres>,
+    sku: &str,
+    variant_id: Option<&str>,
+) -> Result<(), ProductError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(sku)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    let taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM products WHERE sku = $1)
+             OR EXISTS (SELECT 1 FROM product_variants WHERE sku = $1 AND id IS DISTINCT FROM $2)",
+    )
+    .bind(sku)
+    .bind(variant_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    if taken {
+        return Err(ProductError::SkuAlreadyExists);
+    }
+    Ok(())
+}
+
+/// 商品と初期在庫を登録し、作成時の値を履歴に記録する（呼び出し元のトランザクション内）
+pub async fn insert_product(
+    tx: &mut Transaction<'_, Postgres>,
+    product: &Product,
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    reserve_sku(tx, &product.sku, None).await?;
+
+    // Insert main product record
+    let query = "INSERT INTO products (id, name, slug, description, sku, brand, status, category_id, 
+                                     width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
+                                     created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)";
+
+    let width = product.dimensions.as_ref().map(|d| d.width);
+    let height = product.dimensions.as_ref().map(|d| d.height);
+    let depth = product.dimensions.as_ref().map(|d| d.depth);
+
+    sqlx::query(query)
+        .bind(&product.id)
+        .bind(&product.name)
+        .bind(&product.slug)
+        .bind(&product.description)
+        .bind(&product.sku)
+        .bind(&product.brand)
+        .bind(product.status.to_string())
+        .bind(&product.category_id)
+        .bind(width)
+        .bind(height)
+        .bind(depth)
+        .bind(product.weight)
+        .bind(&product.shipping_info.shipping_class)
+        .bind(product.shipping_info.free_shipping)
+        .bind(product.shipping_info.shipping_fee)
+        .bind(product.created_at)
+        .bind(product.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_sku_error)?;
+
+    // Create initial inventory record
+    let inventory_query = "INSERT INTO product_inventory (product_id, quantity, reserved_quantity, track_inventory, allow_backorder)
+                         VALUES ($1, 0, 0, true, false)";
+
+    sqlx::query(inventory_query)
+        .bind(&product.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+    let changes = Product::field_changes(None, product);
+    insert_history(tx, &product.id, &changes, ctx).await?;
+    insert_event(tx, &DomainEvent::product_created(product)).await
+}
+
+/// 商品をロックしてバージョンを照合し、更新と差分の記録を行う（呼び出し元のトランザクション内）
+pub async fn update_product(
+    tx: &mut Transaction<'_, Postgres>,
+    product: Product,
+    ctx: &ChangeContext,
+) -> Result<Product, ProductError> {
+    // 変更前の状態をロックして取得し、差分を履歴に残す
+    let lock_query = "SELECT id, name, slug, description, sku, brand, status, category_id,
+                             width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
+                             created_at, updated_at, version
+                      FROM products
+                      WHERE id = $1
+                      FOR UPDATE";
+
+    let previous = sqlx::query(lock_query)
+        .bind(&product.id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?
+        .map(|row| row_to_product(&row))
+        .ok_or(ProductError::ProductNotFound)?;
+
+    // 読み込み後に他の更新が入っていれば上書きせずに失敗させる
+    previous.check_version(Some(product.version))?;
+    if previous.sku != product.sku {
+        reserve_sku(tx, &product.sku, None).await?;
+    }
+
+    let query = "UPDATE products 
+                 SET name = $2, description = $3, sku = $4, brand = $5, status = $6, category_id = $7,
+                     width = $8, height = $9, depth = $10, weight = $11, 
+                     shipping_class = $12, free_shipping = $13, shipping_fee = $14, updated_at = $15,
+                     slug = $16, version = version + 1
+                 WHERE id = $1";
+
+    let width = product.dimensions.as_ref().map(|d| d.width);
+    let height = product.dimensions.as_ref().map(|d| d.height);
+    let depth = product.dimensions.as_ref().map(|d| d.depth);
+
+    sqlx::query(query)
+        .bind(&product.id)
+        .bind(&product.name)
+        .bind(&product.description)
+        .bind(&product.sku)
+        .bind(&product.brand)
+        .bind(product.status.to_string())
+        .bind(&product.category_id)
+        .bind(width)
+        .bind(height)
+        .bind(depth)
+        .bind(product.weight)
+        .bind(&product.shipping_info.shipping_class)
+        .bind(product.shipping_info.free_shipping)
+        .bind(product.shipping_info.shipping_fee)
+        .bind(Utc::now())
+        .bind(&product.slug)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_sku_error)?;
+
+    // 旧スラッグの URL から転送できるように残す（元のスラッグに戻した場合は転送を消す）
+    if previous.slug != product.slug {
+        sqlx::query("DELETE FROM product_slug_redirects WHERE slug = $1")
+            .bind(&product.slug)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+        sqlx::query(
+            "INSERT INTO product_slug_redirects (slug, product_id, created_at)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (slug) DO UPDATE SET product_id = EXCLUDED.product_id, created_at = NOW()",
+        )
+        .bind(&previous.slug)
+        .bind(&product.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+    }
+
+    let changes = Product::field_changes(Some(&previous), &product);
+    insert_history(tx, &product.id, &changes, ctx).await?;
+    if !changes.is_empty() {
+        insert_event(tx, &DomainEvent::product_updated(&product.id, &changes)).await?;
+    }
+
+    let mut product = product;
+    product.version = previous.version + 1;
+    Ok(product)
+}
+
+/// 商品行をロックしてバージョンを照合し、商品本体・価格・タグ・属性の変更をまとめて書き込む
+/// （呼び出し元のトランザクション内）
+pub async fn write_revision(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: &str,
+    expected_version: i64,
+    revision: ProductRevision,
+    ctx: &ChangeContext,
+) -> Result<(), ProductError> {
+    let row = sqlx::query("SELECT version FROM products WHERE id = $1 FOR UPDATE")
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))?
+        .ok_or(ProductError::ProductNotFound)?;
+
+    let version: i64 = row.get("version");
+    if version != expected_version {
+        return Err(ProductError::VersionMismatch {
+            expected: expected_version,
+            actual: version,
+        });
+    }
+
+    if let Some(mut product) = revision.product {
+        product.version = version;
+        update_product(tx, product, ctx).await?;
+    }
+
+    if let Some(price) = revision.price {
+        price.validate()?;
+        write_price(tx, product_id, &price, ctx).await?;
+    }
+
+    if let Some(tags) = revision.tags {
+        write_tags(tx, product_id, &tags, ctx).await?;
+    }
+
+    if let Some(attributes) = revision.attributes {
+        write_attributes(tx, product_id, &attributes, ctx).await?;
+    }
+
+    Ok(())
+}
+
+/// 商品検索の結合・条件・パラメータ（プレースホルダーは `$1` から採番）
+pub struct SearchConditions {
+    pub joins: Vec<String>,
+    pub conditions: Vec<String>,
+    pub params: Vec<String>,
+}
+
+/// 検索・エクスポート・一括更新で共通の絞り込み条件を組み立てる
+pub fn search_conditions(filter: &ProductFilter) -> SearchConditions {
+    let mut joins = Vec::new();
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+    let mut param_index = 1;
+
+    // Text search (variant SKUs roll up to their parent product)
+    if !filter.query.is_empty() {
+        conditions.push(format!(
+            "(p.name ILIKE ${} OR p.description ILIKE ${} OR p.sku ILIKE ${}
+              OR EXISTS (SELECT 1 FROM product_variants pv WHERE pv.product_id = p.id AND pv.sku ILIKE ${}))",
+            param_index,
+            param_index + 1,
+            param_index + 2,
+            param_index + 3
+        ));
+        let search_pattern = format!("%{}%", filter.query);
+        params.push(search_pattern.clone());
+        params.push(search_pattern.clone());
+        params.push(search_pattern.clone());
+        params.push(search_pattern);
+        param_index += 4;
+    }
+
+    // Category filter
+    if let Some(cat_id) = &filter.category_id {
+        conditions.push(format!("p.category_id = ${}", param_index));
+        params.push(cat_id.clone());
+        param_index += 1;
+    }
+
+    // Brand filter
+    if let Some(brand) = &filter.brand {
+        conditions.push(format!("p.brand = ${}", param_index));
+        params.push(brand.clone());
+        param_index += 1;
+    }
+
+    // Status filter
+    if let Some(status) = &filter.status {
+        conditions.push(format!("p.status = ${}", param_index));
+        params.push(status.to_string());
+        param_index += 1;
+    }
+
+    // Tags filter
+    if !filter.tags.is_empty() {
+        joins.push("JOIN product_tags pt ON p.id = pt.product_id".to_string());
+        let placeholders: Vec<String> = (0..filter.tags.len())
+            .map(|i| format!("${}", param_index + i))
+            .collect();
+        conditions.push(format!("pt.tag IN ({})", placeholders.join(", ")));
+        for tag in &filter.tags {
+            params.push(tag.clone());
+            param_index += 1;
+        }
+    }
+
+    // Attribute filters (names and values are matched case-insensitively)
+    for attribute in &filter.attributes {
+        let name_index = param_index;
+        params.push(attribute.name.clone());
+        param_index += 1;
+
+        let mut value_conditions = Vec::new();
+        match &attribute.condition {
+            AttributeCondition::OneOf(values) => {
+                let mut placeholders = Vec::new();
+                for value in values {
+                    placeholders.push(format!("LOWER(${})", param_index));
+                    params.push(value.clone());
+                    param_index += 1;
+                }
+                value_conditions.push(format!(
+                    "LOWER(pa.attribute_value) IN ({})",
+                    placeholders.join(", ")
+                ));
+            }
+            AttributeCondition::Range { min, max } => {
+                for (bound, operator) in [(min, ">="), (max, "<=")] {
+                    if let Some(bound) = bound {
+                        // 数値でない値は範囲の比較から外す
+                        value_conditions.push(format!(
+                            "(CASE WHEN pa.attribute_value ~ '^-?[0-9]+(\\.[0-9]+)?$'
+                                   THEN pa.attribute_value::numeric END) {} ${}::numeric",
+                            operator, param_index
+                        ));
+                        params.push(bound.to_string());
+                        param_index += 1;
+                    }
+                }
+            }
+        }
+
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM product_attributes pa WHERE pa.product_id = p.id
+                     AND LOWER(pa.attribute_name) = LOWER(${}) AND {})",
+            name_index,
+            value_conditions.join(" AND ")
+        ));
+    }
+
+    // Price range filter (matches the product's own price or any of its variants)
+    if filter.min_price.is_some() || filter.max_price.is_some() {
+        let mut price_conditions = Vec::new();
+
+        if let Some(min_p) = filter.min_price {
+            price_conditions.push(format!("{{price}} >= ${}::numeric", param_index));
+            params.push(min_p.to_string());
+            param_index += 1;
+        }
+
+        if let Some(max_p) = filter.max_price {
+            price_conditions.push(format!("{{price}} <= ${}::numeric", param_index));
+            params.push(max_p.to_string());
+            // param_index += 1; // Not needed since we're not using it after this
+        }
+
+        let price_condition = price_conditions.join(" AND ");
+        conditions.push(format!(
+            "(EXISTS (SELECT 1 FROM product_prices pp WHERE pp.product_id = p.id
+                      AND (pp.effective_from IS NULL OR pp.effective_from <= NOW())
+                      AND (pp.effective_until IS NULL OR pp.effective_until >= NOW())
+                      AND {})
+              OR EXISTS (SELECT 1 FROM product_variants pv WHERE pv.product_id = p.id AND {}))",
+            price_condition.replace("{price}", "pp.selling_price"),
+            price_condition.replace("{price}", "pv.selling_price")
+        ));
+    }
+
+    // Stock filter
+    if filter.in_stock_only {
+        joins.push("JOIN product_inventory pi ON p.id = pi.product_id".to_string());
+        conditions.push(
+            "(pi.quantity > pi.reserved_quantity
+              OR EXISTS (SELECT 1 FROM product_variants pv WHERE pv.product_id = p.id
+                         AND pv.quantity > pv.reserved_quantity))"
+                .to_string(),
+        );
+    }
+
+    SearchConditions {
+        joins,
+        conditions,
+        params,
+    }
+}
+
+/// 絞り込み条件に一致する商品を ID 順に `after_id` より後から最大 `limit` 件取得する
+///
+/// キーセットで読み進めるため、処理中に条件から外れた商品があっても取りこぼさない。
+pub async fn find_by_filter(
+    pool: &PgPool,
+    filter: &ProductFilter,
+    after_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Product>, ProductError> {
+    let SearchConditions {
+        joins,
+        mut conditions,
+        mut params,
+    } = search_conditions(filter);
+
+    if let Some(after_id) = after_id {
+        conditions.push(format!("p.id > ${}", params.len() + 1));
+        params.push(after_id.to_string());
+    }
+
+    let mut sql_query = "SELECT DISTINCT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id,
+                                p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
+                                p.created_at, p.updated_at, p.version
+                        FROM products p".to_string();
+    if !joins.is_empty() {
+        sql_query.push(' ');
+        sql_query.push_str(&joins.join(" "));
+    }
+    if !conditions.is_empty() {
+        sql_query.push_str(" WHERE ");
+        sql_query.push_str(&conditions.join(" AND "));
+    }
+    sql_query.push_str(&format!(" ORDER BY p.id LIMIT {}", limit));
+
+    let mut sqlx_query = sqlx::query(&sql_query);
+    for param in params {
+        sqlx_query = sqlx_query.bind(param);
+    }
+
+    sqlx_query
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.iter().map(row_to_product).collect())
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
+/// 絞り込み条件に一致する商品数
+pub async fn count_by_filter(pool: &PgPool, filter: &ProductFilter) -> Result<i64, ProductError> {
+    let SearchConditions {
+        joins,
+        conditions,
+        params,
+    } = search_conditions(filter);
+
+    let mut sql_query = "SELECT COUNT(DISTINCT p.id) AS count FROM products p".to_string();
+    if !joins.is_empty() {
+        sql_query.push(' ');
+        sql_query.push_str(&joins.join(" "));
+    }
+    if !conditions.is_empty() {
+        sql_query.push_str(" WHERE ");
+        sql_query.push_str(&conditions.join(" AND "));
+    }
+
+    let mut sqlx_query = sqlx::query(&sql_query);
+    for param in params {
+        sqlx_query = sqlx_query.bind(param);
+    }
+
+    sqlx_query
+        .fetch_one(pool)
+        .await
+        .map(|row| row.get("count"))
+        .map_err(|e| ProductError::DatabaseError(e.to_string()))
+}
+
 #[async_trait]
 impl ProductRepository for PostgresProductRepository {
     async fn find_by_id(&self, id: &str) -> Option<Product> {
-        let query = "SELECT id, name, description, sku, brand, status, category_id, 
+        let query = "SELECT id, name, slug, description, sku, brand, status, category_id, 
                            width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
-                           created_at, updated_at 
+                           created_at, updated_at, version 
                      FROM products 
                      WHERE id = $1";
 
@@ -43,9 +553,9 @@ impl ProductRepository for PostgresProductRepository {
     }
 
     async fn find_by_sku(&self, sku: &str) -> Option<Product> {
-        let query = "SELECT id, name, description, sku, brand, status, category_id, 
+        let query = "SELECT id, name, slug, description, sku, brand, status, category_id, 
                            width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
-                           created_at, updated_at 
+                           created_at, updated_at, version 
                      FROM products 
                      WHERE sku = $1";
 
@@ -63,13 +573,56 @@ impl ProductRepository for PostgresProductRepository {
         }
     }
 
+    async fn find_by_slug(&self, slug: &str) -> Option<Product> {
+        let query = "SELECT id, name, slug, description, sku, brand, status, category_id, 
+                           width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
+                           created_at, updated_at, version 
+                     FROM products 
+                     WHERE slug = $1";
+
+        match sqlx::query(query)
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await
+        {
+            Ok(Some(row)) => Some(row_to_product(&row)),
+            Ok(None) => None,
+            Err(e) => {
+                error!("Error finding product by slug {}: {}", slug, e);
+                None
+            }
+        }
+    }
+
+    async fn find_by_old_slug(&self, slug: &str) -> Option<Product> {
+        let query = "SELECT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id, 
+                           p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
+                           p.created_at, p.updated_at, p.version 
+                     FROM product_slug_redirects r
+                     JOIN products p ON p.id = r.product_id
+                     WHERE r.slug = $1";
+
+        match sqlx::query(query)
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await
+        {
+            Ok(Some(row)) => Some(row_to_product(&row)),
+            Ok(None) => None,
+            Err(e) => {
+                error!("Error finding product by old slug {}: {}", slug, e);
+                None
+            }
+        }
+    }
+
     // async fn find_all(&self,
     //     category_id: Option<&str>,
     //     status: Option<&str>,
     //     limit: Option<i64>,
     //     offset: Option<i64>,
     // ) -> Vec<Product> {
-    //     let mut query = "SELECT id, name, description, sku, brand, status, category_id,
+    //     let mut query = "SELECT id, name, slug, description, sku, brand, status, category_id,
     //                            width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
     //                            created_at, updated_at
     //                      FROM products WHERE 1=1".to_string();
@@ -120,138 +673,146 @@ impl ProductRepository for PostgresProductRepository {
     //     }
     // }
 
-    async fn create(&self, product: Product) -> Result<Product, ProductError> {
+    async fn create(&self, product: Product, ctx: &ChangeContext) -> Result<Product, ProductError> {
         let mut tx = self
             .pool
             .begin()
             .await
             .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
-        // Insert main product record
-        let query = "INSERT INTO products (id, name, description, sku, brand, status, category_id, 
-                                         width, height, depth, weight, shipping_class, free_shipping, shipping_fee,
-                                         created_at, updated_at)
-                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)";
-
-        let width = product.dimensions.as_ref().map(|d| d.width);
-        let height = product.dimensions.as_ref().map(|d| d.height);
-        let depth = product.dimensions.as_ref().map(|d| d.depth);
-
-        let result = sqlx::query(query)
-            .bind(&product.id)
-            .bind(&product.name)
-            .bind(&product.description)
-            .bind(&product.sku)
-            .bind(&product.brand)
-            .bind(product.status.to_string())
-            .bind(&product.category_id)
-            .bind(width)
-            .bind(height)
-            .bind(depth)
-            .bind(product.weight)
-            .bind(&product.shipping_info.shipping_class)
-            .bind(product.shipping_info.free_shipping)
-            .bind(product.shipping_info.shipping_fee)
-            .bind(product.created_at)
-            .bind(product.updated_at)
-            .execute(&mut *tx)
-            .await;
+        if let Err(e) = insert_product(&mut tx, &product, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
 
-        match result {
-            Ok(_) => {
-                // Create initial inventory record
-                let inventory_query = "INSERT INTO product_inventory (product_id, quantity, reserved_quantity, track_inventory, allow_backorder)
-                                     VALUES ($1, 0, 0, true, false)";
-
-                if let Err(e) = sqlx::query(inventory_query)
-                    .bind(&product.id)
-                    .execute(&mut *tx)
-                    .await
-                {
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(product)
+    }
+
+    async fn update(&self, product: Product, ctx: &ChangeContext) -> Result<Product, ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let product = match update_product(&mut tx, product, ctx).await {
+            Ok(product) => product,
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        };
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(product)
+    }
+
+    async fn delete(&self, id: &str, expected_version: Option<i64>) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        // バージョンの照合と削除の間に更新が割り込まないよう、商品行をロックしてから照合する
+        let version: i64 =
+            match sqlx::query_scalar("SELECT version FROM products WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+            {
+                Ok(Some(version)) => version,
+                Ok(None) => {
+                    let _ = tx.rollback().await;
+                    return Err(ProductError::ProductNotFound);
+                }
+                Err(e) => {
                     let _ = tx.rollback().await;
                     return Err(ProductError::DatabaseError(e.to_string()));
                 }
+            };
+        if let Some(expected) = expected_version.filter(|expected| *expected != version) {
+            let _ = tx.rollback().await;
+            return Err(ProductError::VersionMismatch {
+                expected,
+                actual: version,
+            });
+        }
 
-                tx.commit()
-                    .await
-                    .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
-
-                Ok(product)
+        match sqlx::query("DELETE FROM products WHERE id = $1 AND version = $2")
+            .bind(id)
+            .bind(version)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => {}
+            Ok(_) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::ProductNotFound);
             }
-            Err(sqlx::Error::Database(db_err)) => {
+            Err(sqlx::Error::Database(db_err))
+                if db_err.constraint() == Some("product_bundle_components_component_id_fkey") =>
+            {
                 let _ = tx.rollback().await;
-                if db_err.constraint() == Some("products_sku_key") {
-                    Err(ProductError::SkuAlreadyExists)
-                } else {
-                    Err(ProductError::DatabaseError(db_err.to_string()))
-                }
+                let bundles = ProductBundles { pool: &self.pool };
+                return Err(ProductError::UsedInBundle(
+                    bundles.find_bundles_by_component(id).await,
+                ));
             }
             Err(e) => {
                 let _ = tx.rollback().await;
-                Err(ProductError::DatabaseError(e.to_string()))
+                return Err(ProductError::DatabaseError(e.to_string()));
             }
         }
-    }
 
-    async fn update(&self, product: Product) -> Result<Product, ProductError> {
-        let query = "UPDATE products 
-                     SET name = $2, description = $3, sku = $4, brand = $5, status = $6, category_id = $7,
-                         width = $8, height = $9, depth = $10, weight = $11, 
-                         shipping_class = $12, free_shipping = $13, shipping_fee = $14, updated_at = $15
-                     WHERE id = $1";
-
-        let width = product.dimensions.as_ref().map(|d| d.width);
-        let height = product.dimensions.as_ref().map(|d| d.height);
-        let depth = product.dimensions.as_ref().map(|d| d.depth);
-
-        let result = sqlx::query(query)
-            .bind(&product.id)
-            .bind(&product.name)
-            .bind(&product.description)
-            .bind(&product.sku)
-            .bind(&product.brand)
-            .bind(product.status.to_string())
-            .bind(&product.category_id)
-            .bind(width)
-            .bind(height)
-            .bind(depth)
-            .bind(product.weight)
-            .bind(&product.shipping_info.shipping_class)
-            .bind(product.shipping_info.free_shipping)
-            .bind(product.shipping_info.shipping_fee)
-            .bind(Utc::now())
-            .execute(&self.pool)
-            .await;
-
-        match result {
-            Ok(result) if result.rows_affected() > 0 => Ok(product),
-            Ok(_) => Err(ProductError::ProductNotFound),
-            Err(sqlx::Error::Database(db_err)) => {
-                if db_err.constraint() == Some("products_sku_key") {
-                    Err(ProductError::SkuAlreadyExists)
-                } else {
-                    Err(ProductError::DatabaseError(db_err.to_string()))
-                }
-            }
-            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        if let Err(e) = insert_event(&mut tx, &DomainEvent::product_deleted(id)).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
     }
 
-    async fn delete(&self, id: &str) -> Result<(), ProductError> {
-        let query = "DELETE FROM products WHERE id = $1";
+    async fn apply_revision(
+        &self,
+        product_id: &str,
+        expected_version: i64,
+        revision: ProductRevision,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
 
-        match sqlx::query(query).bind(id).execute(&self.pool).await {
-            Ok(result) if result.rows_affected() > 0 => Ok(()),
-            Ok(_) => Err(ProductError::ProductNotFound),
-            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        if let Err(e) = write_revision(&mut tx, product_id, expected_version, revision, ctx).await {
+            let _ = tx.rollback().await;
+            return Err(e);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
     }
 
     async fn exists_by_sku(&self, sku: &str, exclude_id: Option<&str>) -> bool {
+        // Variant SKUs share the namespace with product SKUs
         let query = if exclude_id.is_some() {
-            "SELECT COUNT(*) as count FROM products WHERE sku = $1 AND id != $2"
+            "SELECT (SELECT COUNT(*) FROM products WHERE sku = $1 AND id != $2)
+                  + (SELECT COUNT(*) FROM product_variants WHERE sku = $1 AND id != $2) as count"
         } else {
-            "SELECT COUNT(*) as count FROM products WHERE sku = $1"
+            "SELECT (SELECT COUNT(*) FROM products WHERE sku = $1)
+                  + (SELECT COUNT(*) FROM product_variants WHERE sku = $1) as count"
         };
 
         let mut sqlx_query = sqlx::query(query).bind(sku);
@@ -271,6 +832,30 @@ impl ProductRepository for PostgresProductRepository {
         }
     }
 
+    async fn exists_by_slug(&self, slug: &str, exclude_id: Option<&str>) -> bool {
+        // 他の商品の旧スラッグも転送先が変わらないように使用中として扱う
+        let query =
+            "SELECT (SELECT COUNT(*) FROM products WHERE slug = $1 AND id IS DISTINCT FROM $2)
+                          + (SELECT COUNT(*) FROM product_slug_redirects
+                             WHERE slug = $1 AND product_id IS DISTINCT FROM $2) as count";
+
+        match sqlx::query(query)
+            .bind(slug)
+            .bind(exclude_id)
+            .fetch_one(&self.pool)
+            .await
+        {
+            Ok(row) => {
+                let count: i64 = row.get("count");
+                count > 0
+            }
+            Err(e) => {
+                error!("Error checking slug existence: {}", e);
+                false
+            }
+        }
+    }
+
     async fn get_current_price(&self, product_id: &str) -> Option<Price> {
         let extensions = ProductExtensions { pool: &self.pool };
         extensions.get_current_price(product_id).await
@@ -300,9 +885,14 @@ impl ProductRepository for PostgresProductRepository {
     //     }
     // }
 
-    async fn update_price(&self, product_id: &str, price: Price) -> Result<Price, ProductError> {
+    async fn update_price(
+        &self,
+        product_id: &str,
+        price: Price,
+        ctx: &ChangeContext,
+    ) -> Result<Price, ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.update_price(product_id, price).await
+        extensions.update_price(product_id, price, ctx).await
     }
 
     async fn get_inventory(&self, product_id: &str) -> Option<Inventory> {
@@ -314,52 +904,29 @@ impl ProductRepository for PostgresProductRepository {
         &self,
         product_id: &str,
         inventory: Inventory,
+        ctx: &ChangeContext,
     ) -> Result<Inventory, ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.update_inventory(product_id, inventory).await
+        extensions
+            .update_inventory(product_id, inventory, ctx)
+            .await
     }
 
-    // async fn reserve_inventory(&self, product_id: &str, quantity: i32) -> Result<(), ProductError> {
-    //     if quantity <= 0 {
-    //         return Err(ProductError::InvalidInventoryQuantity);
-    //     }
-
-    //     let query = "UPDATE product_inventory
-    //                  SET reserved_quantity = reserved_quantity + $2
-    //                  WHERE product_id = $1 AND quantity >= reserved_quantity + $2";
-
-    //     match sqlx::query(query)
-    //         .bind(product_id)
-    //         .bind(quantity)
-    //         .execute(&self.pool)
-    //         .await
-    //     {
-    //         Ok(result) if result.rows_affected() > 0 => Ok(()),
-    //         Ok(_) => Err(ProductError::InvalidInventoryQuantity),
-    //         Err(e) => Err(ProductError::DatabaseError(e.to_string())),
-    //     }
-    // }
-
-    // async fn release_inventory(&self, product_id: &str, quantity: i32) -> Result<(), ProductError> {
-    //     if quantity <= 0 {
-    //         return Err(ProductError::InvalidInventoryQuantity);
-    //     }
-
-    //     let query = "UPDATE product_inventory
-    //                  SET reserved_quantity = GREATEST(0, reserved_quantity - $2)
-    //                  WHERE product_id = $1";
+    async fn reserve_inventory(
+        &self,
+        reservations: Vec<(String, i32)>,
+    ) -> Result<(), ProductError> {
+        let extensions = ProductExtensions { pool: &self.pool };
+        extensions.reserve_inventory(reservations).await
+    }
 
-    //     match sqlx::query(query)
-    //         .bind(product_id)
-    //         .bind(quantity)
-    //         .execute(&self.pool)
-    //         .await
-    //     {
-    //         Ok(result) if result.rows_affected() > 0 => Ok(()),
-    //         Ok(_) => Err(ProductError::ProductNotFound),
-    //         Err(e) => Err(ProductError::DatabaseError(e.to_string())),
-    //     }
-    // }
+    async fn release_inventory(
+        &self,
+        reservations: Vec<(String, i32)>,
+    ) -> Result<(), ProductError> {
+        let extensions = ProductExtensions { pool: &self.pool };
+        extensions.release_inventory(reservations).await
+    }
 
     async fn get_images(&self, product_id: &str) -> Vec<ProductImage> {
         let extensions = ProductExtensions { pool: &self.pool };
@@ -370,37 +937,62 @@ impl ProductRepository for PostgresProductRepository {
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.add_image(product_id, image).await
+        extensions.add_image(product_id, image, ctx).await
     }
 
     async fn update_image(
         &self,
         product_id: &str,
         image: ProductImage,
+        ctx: &ChangeContext,
     ) -> Result<ProductImage, ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.update_image(product_id, image).await
+        extensions.update_image(product_id, image, ctx).await
     }
 
-    async fn delete_image(&self, product_id: &str, image_id: &str) -> Result<(), ProductError> {
+    async fn delete_image(
+        &self,
+        product_id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.delete_image(product_id, image_id).await
+        extensions.delete_image(product_id, image_id, ctx).await
     }
 
     async fn reorder_images(
         &self,
         product_id: &str,
         image_orders: Vec<(String, i32)>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let extensions = ProductExtensions { pool: &self.pool };
+        extensions
+            .reorder_images(product_id, image_orders, ctx)
+            .await
+    }
+
+    async fn set_image_order(
+        &self,
+        product_id: &str,
+        image_ids: Vec<String>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.reorder_images(product_id, image_orders).await
+        extensions.set_image_order(product_id, image_ids, ctx).await
     }
 
-    async fn set_main_image(&self, product_id: &str, image_id: &str) -> Result<(), ProductError> {
+    async fn set_main_image(
+        &self,
+        product_id: &str,
+        image_id: &str,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
         let extensions = ProductExtensions { pool: &self.pool };
-        extensions.set_main_image(product_id, image_id).await
+        extensions.set_main_image(product_id, image_id, ctx).await
     }
 
     async fn get_tags(&self, product_id: &str) -> Vec<String> {
@@ -408,9 +1000,14 @@ impl ProductRepository for PostgresProductRepository {
         metadata.get_tags_for_product(product_id).await
     }
 
-    async fn add_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError> {
+    async fn add_tags(
+        &self,
+        product_id: &str,
+        tags: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
         let metadata = ProductMetadata { pool: &self.pool };
-        metadata.add_tags(product_id, tags).await
+        metadata.add_tags(product_id, tags, ctx).await
     }
 
     // async fn remove_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError> {
@@ -433,9 +1030,14 @@ impl ProductRepository for PostgresProductRepository {
     //     }
     // }
 
-    async fn replace_tags(&self, product_id: &str, tags: Vec<String>) -> Result<(), ProductError> {
+    async fn replace_tags(
+        &self,
+        product_id: &str,
+        tags: Vec<String>,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
         let metadata = ProductMetadata { pool: &self.pool };
-        metadata.replace_tags(product_id, tags).await
+        metadata.replace_tags(product_id, tags, ctx).await
     }
 
     async fn get_attributes(&self, product_id: &str) -> HashMap<String, String> {
@@ -447,9 +1049,20 @@ impl ProductRepository for PostgresProductRepository {
         &self,
         product_id: &str,
         attributes: HashMap<String, String>,
+        ctx: &ChangeContext,
     ) -> Result<(), ProductError> {
         let metadata = ProductMetadata { pool: &self.pool };
-        metadata.set_attributes(product_id, attributes).await
+        metadata.set_attributes(product_id, attributes, ctx).await
+    }
+
+    async fn find_attribute_schema(
+        &self,
+        category_id: &str,
+    ) -> Result<AttributeSchema, ProductError> {
+        // 読み込めないまま検証を省くと必須属性の欠けた商品を保存してしまうため、エラーを返す
+        attribute_schema(&self.pool, category_id)
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))
     }
 
     // async fn set_attribute(&self, product_id: &str, name: &str, value: &str) -> Result<(), ProductError> {
@@ -484,6 +1097,114 @@ impl ProductRepository for PostgresProductRepository {
     //     }
     // }
 
+    async fn get_options(&self, product_id: &str) -> Vec<ProductOption> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.get_options(product_id).await
+    }
+
+    async fn set_options(
+        &self,
+        product_id: &str,
+        options: Vec<ProductOption>,
+    ) -> Result<(), ProductError> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.set_options(product_id, options).await
+    }
+
+    async fn get_variants(&self, product_id: &str) -> Vec<ProductVariant> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.get_variants(product_id).await
+    }
+
+    async fn get_variants_for(
+        &self,
+        product_ids: &[String],
+    ) -> HashMap<String, Vec<ProductVariant>> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.get_variants_for(product_ids).await
+    }
+
+    async fn create_variant(
+        &self,
+        variant: ProductVariant,
+    ) -> Result<ProductVariant, ProductError> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.create_variant(variant).await
+    }
+
+    async fn update_variant(
+        &self,
+        variant: ProductVariant,
+    ) -> Result<ProductVariant, ProductError> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.update_variant(variant).await
+    }
+
+    async fn delete_variant(&self, product_id: &str, variant_id: &str) -> Result<(), ProductError> {
+        let variants = ProductVariants { pool: &self.pool };
+        variants.delete_variant(product_id, variant_id).await
+    }
+
+    async fn get_bundle(&self, product_id: &str) -> Option<ProductBundle> {
+        let bundles = ProductBundles { pool: &self.pool };
+        bundles.get_bundle(product_id).await
+    }
+
+    async fn set_bundle(&self, bundle: ProductBundle) -> Result<(), ProductError> {
+        let bundles = ProductBundles { pool: &self.pool };
+        bundles.set_bundle(bundle).await
+    }
+
+    async fn delete_bundle(&self, product_id: &str) -> Result<(), ProductError> {
+        let bundles = ProductBundles { pool: &self.pool };
+        bundles.delete_bundle(product_id).await
+    }
+
+    async fn find_bundles_by_component(&self, component_id: &str) -> Vec<String> {
+        let bundles = ProductBundles { pool: &self.pool };
+        bundles.find_bundles_by_component(component_id).await
+    }
+
+    async fn get_translations(&self, product_id: &str) -> Vec<ProductTranslation> {
+        let translations = ProductTranslations { pool: &self.pool };
+        translations.get_translations(product_id).await
+    }
+
+    async fn get_translations_for(
+        &self,
+        product_ids: &[String],
+        locale: &str,
+    ) -> HashMap<String, ProductTranslation> {
+        let translations = ProductTranslations { pool: &self.pool };
+        translations.get_translations_for(product_ids, locale).await
+    }
+
+    async fn set_translation(
+        &self,
+        product_id: &str,
+        translation: ProductTranslation,
+    ) -> Result<(), ProductError> {
+        let translations = ProductTranslations { pool: &self.pool };
+        translations.set_translation(product_id, translation).await
+    }
+
+    async fn delete_translation(&self, product_id: &str, locale: &str) -> Result<(), ProductError> {
+        let translations = ProductTranslations { pool: &self.pool };
+        translations.delete_translation(product_id, locale).await
+    }
+
+    async fn find_missing_translations(
+        &self,
+        locale: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Vec<MissingTranslation> {
+        let translations = ProductTranslations { pool: &self.pool };
+        translations
+            .find_missing_translations(locale, limit, offset)
+            .await
+    }
+
     async fn get_history(
         &self,
         product_id: &str,
@@ -497,6 +1218,72 @@ impl ProductRepository for PostgresProductRepository {
             .await
     }
 
+    async fn change_status(
+        &self,
+        product_id: &str,
+        from: ProductStatus,
+        to: ProductStatus,
+        ctx: &ChangeContext,
+    ) -> Result<(), ProductError> {
+        let metadata = ProductMetadata { pool: &self.pool };
+        metadata.change_status(product_id, from, to, ctx).await
+    }
+
+    async fn get_schedules(&self, product_id: &str) -> Vec<ProductStatusSchedule> {
+        let schedules = ProductSchedules { pool: &self.pool };
+        schedules.get_schedules(product_id).await
+    }
+
+    async fn create_schedule(
+        &self,
+        schedule: ProductStatusSchedule,
+    ) -> Result<ProductStatusSchedule, ProductError> {
+        let schedules = ProductSchedules { pool: &self.pool };
+        schedules.create_schedule(schedule).await
+    }
+
+    async fn cancel_schedule(
+        &self,
+        product_id: &str,
+        schedule_id: &str,
+    ) -> Result<(), ProductError> {
+        let schedules = ProductSchedules { pool: &self.pool };
+        schedules.cancel_schedule(product_id, schedule_id).await
+    }
+
+    async fn claim_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<ProductStatusSchedule>, ProductError> {
+        let schedules = ProductSchedules { pool: &self.pool };
+        schedules
+            .claim_due_schedules(now, limit, lease_seconds)
+            .await
+    }
+
+    async fn complete_schedule(
+        &self,
+        schedule_id: &str,
+        state: ScheduleState,
+        failure_reason: Option<String>,
+    ) -> Result<(), ProductError> {
+        let schedules = ProductSchedules { pool: &self.pool };
+        schedules
+            .complete_schedule(schedule_id, state, failure_reason)
+            .await
+    }
+
+    async fn import_products(
+        &self,
+        records: Vec<ProductImportRecord>,
+        ctx: &ChangeContext,
+    ) -> Result<Vec<ImportRowOutcome>, ImportRowError> {
+        let imports = ProductImports { pool: &self.pool };
+        imports.import_products(records, ctx).await
+    }
+
     // async fn add_history_entry(&self,
     //     product_id: &str,
     //     field_name: &str,
@@ -658,82 +1445,33 @@ impl ProductRepository for PostgresProductRepository {
         min_price: Option<Decimal>,
         max_price: Option<Decimal>,
         in_stock_only: bool,
+        attributes: &[AttributeFilter],
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Vec<Product> {
-        let mut sql_query = "SELECT DISTINCT p.id, p.name, p.description, p.sku, p.brand, p.status, p.category_id, 
+        let mut sql_query = "SELECT DISTINCT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id, 
                                     p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
-                                    p.created_at, p.updated_at 
+                                    p.created_at, p.updated_at, p.version 
                             FROM products p".to_string();
 
-        let mut joins = Vec::new();
-        let mut conditions = Vec::new();
-        let mut params = Vec::new();
-        let mut param_index = 1;
-
-        // Text search
-        if !query.is_empty() {
-            conditions.push(format!(
-                "(p.name ILIKE ${} OR p.description ILIKE ${} OR p.sku ILIKE ${})",
-                param_index,
-                param_index + 1,
-                param_index + 2
-            ));
-            let search_pattern = format!("%{}%", query);
-            params.push(search_pattern.clone());
-            params.push(search_pattern.clone());
-            params.push(search_pattern);
-            param_index += 3;
-        }
-
-        // Category filter
-        if let Some(cat_id) = category_id {
-            conditions.push(format!("p.category_id = ${}", param_index));
-            params.push(cat_id.to_string());
-            param_index += 1;
-        }
-
-        // Tags filter
-        if let Some(tag_list) = tags {
-            if !tag_list.is_empty() {
-                joins.push("JOIN product_tags pt ON p.id = pt.product_id".to_string());
-                let placeholders: Vec<String> = (0..tag_list.len())
-                    .map(|i| format!("${}", param_index + i))
-                    .collect();
-                conditions.push(format!("pt.tag IN ({})", placeholders.join(", ")));
-                for tag in tag_list {
-                    params.push(tag.to_string());
-                    param_index += 1;
-                }
-            }
-        }
-
-        // Price range filter
-        if min_price.is_some() || max_price.is_some() {
-            joins.push("JOIN product_prices pp ON p.id = pp.product_id".to_string());
-            joins.push("AND (pp.effective_from IS NULL OR pp.effective_from <= NOW())".to_string());
-            joins.push(
-                "AND (pp.effective_until IS NULL OR pp.effective_until >= NOW())".to_string(),
-            );
-
-            if let Some(min_p) = min_price {
-                conditions.push(format!("pp.selling_price >= ${}", param_index));
-                params.push(min_p.to_string());
-                param_index += 1;
-            }
-
-            if let Some(max_p) = max_price {
-                conditions.push(format!("pp.selling_price <= ${}", param_index));
-                params.push(max_p.to_string());
-                // param_index += 1; // Not needed since we're not using it after this
-            }
-        }
-
-        // Stock filter
-        if in_stock_only {
-            joins.push("JOIN product_inventory pi ON p.id = pi.product_id".to_string());
-            conditions.push("pi.quantity > pi.reserved_quantity".to_string());
-        }
+        let SearchConditions {
+            joins,
+            conditions,
+            params,
+        } = search_conditions(&ProductFilter {
+            query: query.to_string(),
+            category_id: category_id.map(str::to_string),
+            tags: tags
+                .unwrap_or_default()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            min_price,
+            max_price,
+            in_stock_only,
+            attributes: attributes.to_vec(),
+            ..Default::default()
+        });
 
         // Build the complete query
         if !joins.is_empty() {
@@ -770,10 +1508,34 @@ impl ProductRepository for PostgresProductRepository {
         }
     }
 
+    async fn find_by_filter(
+        &self,
+        filter: &ProductFilter,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductError> {
+        find_by_filter(&self.pool, filter, after_id, limit).await
+    }
+
+    async fn count_by_filter(&self, filter: &ProductFilter) -> Result<i64, ProductError> {
+        count_by_filter(&self.pool, filter).await
+    }
+
+    async fn export_products(
+        &self,
+        filter: &ProductFilter,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ProductExportRow>, ProductError> {
+        ProductExports { pool: &self.pool }
+            .export_products(filter, after_id, limit)
+            .await
+    }
+
     // async fn find_by_category_recursive(&self, category_id: &str) -> Vec<Product> {
     //     // This would require a recursive CTE to find all subcategories
     //     // For simplicity, just finding direct children for now
-    //     let query = "SELECT p.id, p.name, p.description, p.sku, p.brand, p.status, p.category_id,
+    //     let query = "SELECT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id,
     //                        p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
     //                        p.created_at, p.updated_at
     //                  FROM products p
@@ -799,9 +1561,9 @@ impl ProductRepository for PostgresProductRepository {
     async fn find_low_stock_products(&self, threshold: Option<i32>) -> Vec<(Product, Inventory)> {
         let default_threshold = threshold.unwrap_or(10);
 
-        let query = "SELECT p.id, p.name, p.description, p.sku, p.brand, p.status, p.category_id, 
+        let query = "SELECT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id, 
                            p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
-                           p.created_at, p.updated_at,
+                           p.created_at, p.updated_at, p.version,
                            i.quantity, i.reserved_quantity, i.alert_threshold, i.track_inventory, i.allow_backorder
                      FROM products p
                      JOIN product_inventory i ON p.id = i.product_id
@@ -830,9 +1592,9 @@ impl ProductRepository for PostgresProductRepository {
     }
 
     async fn find_out_of_stock_products(&self) -> Vec<Product> {
-        let query = "SELECT p.id, p.name, p.description, p.sku, p.brand, p.status, p.category_id, 
+        let query = "SELECT p.id, p.name, p.slug, p.description, p.sku, p.brand, p.status, p.category_id, 
                            p.width, p.height, p.depth, p.weight, p.shipping_class, p.free_shipping, p.shipping_fee,
-                           p.created_at, p.updated_at
+                           p.created_at, p.updated_at, p.version
                      FROM products p
                      JOIN product_inventory i ON p.id = i.product_id
                      WHERE i.track_inventory = true 
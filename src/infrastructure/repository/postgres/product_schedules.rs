@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::error;
+
+use super::converters::row_to_status_schedule;
+use crate::app_domain::model::product::{ProductError, ProductStatusSchedule, ScheduleState};
+
+const SCHEDULE_COLUMNS: &str = "id, product_id, target_status, scheduled_at, state, reason,
+                                created_by, executed_at, failure_reason, created_at";
+
+/// Product repository extensions for scheduled status changes
+pub struct ProductSchedules<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductSchedules<'_> {
+    pub async fn get_schedules(&self, product_id: &str) -> Vec<ProductStatusSchedule> {
+        let query = format!(
+            "SELECT {} FROM product_status_schedules
+             WHERE product_id = $1
+             ORDER BY scheduled_at, created_at",
+            SCHEDULE_COLUMNS
+        );
+
+        match sqlx::query(&query)
+            .bind(product_id)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows.iter().map(row_to_status_schedule).collect(),
+            Err(e) => {
+                error!("Error fetching schedules for product {}: {}", product_id, e);
+                vec![]
+            }
+        }
+    }
+
+    pub async fn create_schedule(
+        &self,
+        schedule: ProductStatusSchedule,
+    ) -> Result<ProductStatusSchedule, ProductError> {
+        let query = format!(
+            "INSERT INTO product_status_schedules
+                 (id, product_id, target_status, scheduled_at, state, reason, created_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            SCHEDULE_COLUMNS
+        );
+
+        match sqlx::query(&query)
+            .bind(&schedule.id)
+            .bind(&schedule.product_id)
+            .bind(schedule.target_status.to_string())
+            .bind(schedule.scheduled_at)
+            .bind(schedule.state.to_string())
+            .bind(&schedule.reason)
+            .bind(&schedule.created_by)
+            .fetch_one(self.pool)
+            .await
+        {
+            Ok(row) => Ok(row_to_status_schedule(&row)),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn cancel_schedule(
+        &self,
+        product_id: &str,
+        schedule_id: &str,
+    ) -> Result<(), ProductError> {
+        // 実行前（pending）の予約のみ取り消せる
+        let query = "UPDATE product_status_schedules
+                     SET state = 'cancelled'
+                     WHERE id = $1 AND product_id = $2 AND state = 'pending'";
+
+        match sqlx::query(query)
+            .bind(schedule_id)
+            .bind(product_id)
+            .execute(self.pool)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(ProductError::ScheduleNotFound),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn claim_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<ProductStatusSchedule>, ProductError> {
+        // SKIP LOCKED により複数レプリカが同じ予約を同時に取得しないようにする。
+        // claim 後に停止したレプリカの予約は lease 経過後に再取得される。
+        let query = format!(
+            "UPDATE product_status_schedules
+             SET state = 'processing', claimed_at = $1
+             WHERE id IN (
+                 SELECT id FROM product_status_schedules
+                 WHERE scheduled_at <= $1
+                   AND (state = 'pending'
+                        OR (state = 'processing'
+                            AND claimed_at < $1 - make_interval(secs => $3)))
+                 ORDER BY scheduled_at
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING {}",
+            SCHEDULE_COLUMNS
+        );
+
+        match sqlx::query(&query)
+            .bind(now)
+            .bind(limit)
+            .bind(lease_seconds as f64)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => {
+                let mut schedules: Vec<ProductStatusSchedule> =
+                    rows.iter().map(row_to_status_schedule).collect();
+                schedules.sort_by_key(|schedule| schedule.scheduled_at);
+                Ok(schedules)
+            }
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn complete_schedule(
+        &self,
+        schedule_id: &str,
+        state: ScheduleState,
+        failure_reason: Option<String>,
+    ) -> Result<(), ProductError> {
+        let query = "UPDATE product_status_schedules
+                     SET state = $2, failure_reason = $3, executed_at = NOW()
+                     WHERE id = $1 AND state = 'processing'";
+
+        match sqlx::query(query)
+            .bind(schedule_id)
+            .bind(state.to_string())
+            .bind(failure_reason)
+            .execute(self.pool)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use tracing::error;
+
+use crate::app_domain::model::localization::{MissingTranslation, ProductTranslation};
+use crate::app_domain::model::product::ProductError;
+
+/// Product repository extensions for per-locale translations
+pub struct ProductTranslations<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductTranslations<'_> {
+    pub async fn get_translations(&self, product_id: &str) -> Vec<ProductTranslation> {
+        self.fetch_translations(&[product_id.to_string()], None)
+            .await
+            .into_iter()
+            .map(|(_, translation)| translation)
+            .collect()
+    }
+
+    /// 一覧の表示用に、複数の商品のロケールの翻訳を1回のクエリで取得する
+    pub async fn get_translations_for(
+        &self,
+        product_ids: &[String],
+        locale: &str,
+    ) -> HashMap<String, ProductTranslation> {
+        if product_ids.is_empty() {
+            return HashMap::new();
+        }
+        self.fetch_translations(product_ids, Some(locale))
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn fetch_translations(
+        &self,
+        product_ids: &[String],
+        locale: Option<&str>,
+    ) -> Vec<(String, ProductTranslation)> {
+        let query = "SELECT t.product_id, t.locale, t.name, t.description,
+                            COALESCE(ARRAY_AGG(i.image_id::TEXT ORDER BY i.image_id)
+                                FILTER (WHERE i.image_id IS NOT NULL), '{}'::TEXT[]) AS image_ids,
+                            COALESCE(ARRAY_AGG(i.alt_text ORDER BY i.image_id)
+                                FILTER (WHERE i.image_id IS NOT NULL), '{}'::TEXT[]) AS alt_texts
+                     FROM product_translations t
+                     LEFT JOIN product_image_translations i
+                         ON i.product_id = t.product_id AND i.locale = t.locale
+                     WHERE t.product_id = ANY($1) AND ($2::TEXT IS NULL OR t.locale = $2)
+                     GROUP BY t.product_id, t.locale, t.name, t.description
+                     ORDER BY t.product_id, t.locale";
+
+        match sqlx::query(query)
+            .bind(product_ids)
+            .bind(locale)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| {
+                    let image_ids: Vec<String> = row.get("image_ids");
+                    let alt_texts: Vec<String> = row.get("alt_texts");
+                    let translation = ProductTranslation {
+                        locale: row.get("locale"),
+                        name: row.get("name"),
+                        description: row.get("description"),
+                        image_alt_texts: image_ids.into_iter().zip(alt_texts).collect(),
+                    };
+                    (row.get("product_id"), translation)
+                })
+                .collect(),
+            Err(e) => {
+                error!(
+                    "Error fetching translations of {}: {}",
+                    product_ids.join(", "),
+                    e
+                );
+                vec![]
+            }
+        }
+    }
+
+    pub async fn set_translation(
+        &self,
+        product_id: &str,
+        translation: ProductTranslation,
+    ) -> Result<(), ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let upsert_query =
+            "INSERT INTO product_translations (product_id, locale, name, description)
+             SELECT id, $2, $3, $4 FROM products WHERE id = $1
+             ON CONFLICT (product_id, locale)
+             DO UPDATE SET name = $3, description = $4, updated_at = CURRENT_TIMESTAMP";
+
+        match sqlx::query(upsert_query)
+            .bind(product_id)
+            .bind(&translation.locale)
+            .bind(&translation.name)
+            .bind(&translation.description)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => {}
+            Ok(_) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::ProductNotFound);
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+        }
+
+        let delete_query =
+            "DELETE FROM product_image_translations WHERE product_id = $1 AND locale = $2";
+        if let Err(e) = sqlx::query(delete_query)
+            .bind(product_id)
+            .bind(&translation.locale)
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        if !translation.image_alt_texts.is_empty() {
+            let (image_ids, alt_texts): (Vec<String>, Vec<String>) =
+                translation.image_alt_texts.into_iter().unzip();
+
+            // 他の商品の画像 ID は JOIN で落ちるため、件数の不一致を ImageNotFound とする
+            let insert_query =
+                "INSERT INTO product_image_translations (image_id, product_id, locale, alt_text)
+                 SELECT i.id, i.product_id, $2, v.alt_text
+                 FROM UNNEST($3::TEXT[], $4::TEXT[]) AS v(image_id, alt_text)
+                 JOIN product_images i ON i.id = v.image_id AND i.product_id = $1";
+
+            match sqlx::query(insert_query)
+                .bind(product_id)
+                .bind(&translation.locale)
+                .bind(&image_ids)
+                .bind(&alt_texts)
+                .execute(&mut *tx)
+                .await
+            {
+                Ok(result) if result.rows_affected() == image_ids.len() as u64 => {}
+                Ok(_) => {
+                    let _ = tx.rollback().await;
+                    return Err(ProductError::ImageNotFound);
+                }
+                Err(e) => {
+                    let _ = tx.rollback().await;
+                    return Err(ProductError::DatabaseError(e.to_string()));
+                }
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_translation(
+        &self,
+        product_id: &str,
+        locale: &str,
+    ) -> Result<(), ProductError> {
+        let query = "DELETE FROM product_translations WHERE product_id = $1 AND locale = $2";
+
+        match sqlx::query(query)
+            .bind(product_id)
+            .bind(locale)
+            .execute(self.pool)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(ProductError::TranslationNotFound),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn find_missing_translations(
+        &self,
+        locale: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Vec<MissingTranslation> {
+        // 既定ロケールに内容がある項目だけを不足として数える（ProductTranslation::missing_fields と同じ規則）
+        let query = "SELECT id, name, missing
+                     FROM (
+                         SELECT p.id, p.name,
+                                ARRAY_REMOVE(ARRAY[
+                                    CASE WHEN t.name IS NULL THEN 'name' END,
+                                    CASE WHEN LENGTH(TRIM(COALESCE(p.description, ''))) > 0
+                                              AND t.description IS NULL
+                                         THEN 'description' END
+                                ], NULL)
+                                || COALESCE((
+                                    SELECT ARRAY_AGG('images.' || i.id || '.alt_text'
+                                                     ORDER BY i.sort_order, i.id)
+                                    FROM product_images i
+                                    WHERE i.product_id = p.id
+                                      AND LENGTH(TRIM(COALESCE(i.alt_text, ''))) > 0
+                                      AND NOT EXISTS (
+                                          SELECT 1 FROM product_image_translations it
+                                          WHERE it.image_id = i.id AND it.locale = $1
+                                      )
+                                ), '{}'::TEXT[]) AS missing
+                         FROM products p
+                         LEFT JOIN product_translations t
+                             ON t.product_id = p.id AND t.locale = $1
+                     ) m
+                     WHERE CARDINALITY(missing) > 0
+                     ORDER BY id
+                     LIMIT $2 OFFSET $3";
+
+        match sqlx::query(query)
+            .bind(locale)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| MissingTranslation {
+                    id: row.get("id"),
+                    name: row.get("name"),
+                    locale: locale.to_string(),
+                    fields: row.get("missing"),
+                })
+                .collect(),
+            Err(e) => {
+                error!(
+                    "Error finding products missing {} translations: {}",
+                    locale, e
+                );
+                vec![]
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::{BTreeMap, HashMap};
+use tracing::error;
+
+use super::converters::row_to_product_variant;
+use super::product_repository::reserve_sku;
+use crate::app_domain::model::product::{ProductError, ProductOption, ProductVariant};
+
+/// Product repository extensions for option definitions and variants
+pub struct ProductVariants<'a> {
+    pub pool: &'a PgPool,
+}
+
+impl ProductVariants<'_> {
+    pub async fn get_options(&self, product_id: &str) -> Vec<ProductOption> {
+        let query = "SELECT option_name, option_values
+                     FROM product_options
+                     WHERE product_id = $1
+                     ORDER BY sort_order, id";
+
+        match sqlx::query(query)
+            .bind(product_id)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| ProductOption {
+                    name: row.get("option_name"),
+                    values: row.get("option_values"),
+                })
+                .collect(),
+            Err(e) => {
+                error!("Error fetching options for product {}: {}", product_id, e);
+                vec![]
+            }
+        }
+    }
+
+    pub async fn set_options(
+        &self,
+        product_id: &str,
+        options: Vec<ProductOption>,
+    ) -> Result<(), ProductError> {
+        ProductOption::validate_all(&options)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        let delete_query = "DELETE FROM product_options WHERE product_id = $1";
+        if let Err(e) = sqlx::query(delete_query)
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        for (index, option) in options.iter().enumerate() {
+            let insert_query =
+                "INSERT INTO product_options (product_id, option_name, option_values, sort_order)
+                                VALUES ($1, $2, $3, $4)";
+
+            if let Err(e) = sqlx::query(insert_query)
+                .bind(product_id)
+                .bind(&option.name)
+                .bind(&option.values)
+                .bind(index as i32)
+                .execute(&mut *tx)
+                .await
+            {
+                let _ = tx.rollback().await;
+                return Err(ProductError::DatabaseError(e.to_string()));
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn get_variants(&self, product_id: &str) -> Vec<ProductVariant> {
+        self.get_variants_for(&[product_id.to_string()])
+            .await
+            .remove(product_id)
+            .unwrap_or_default()
+    }
+
+    /// 複数の商品のバリエーションを商品 ID ごとにまとめて取得する
+    pub async fn get_variants_for(
+        &self,
+        product_ids: &[String],
+    ) -> HashMap<String, Vec<ProductVariant>> {
+        let query = "SELECT id, product_id, sku, selling_price, list_price, discount_price, currency,
+                           tax_included, effective_from, effective_until, quantity, reserved_quantity,
+                           alert_threshold, track_inventory, allow_backorder, created_at, updated_at
+                     FROM product_variants
+                     WHERE product_id = ANY($1)
+                     ORDER BY created_at, id";
+
+        let rows = match sqlx::query(query)
+            .bind(product_ids)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(
+                    "Error fetching variants for products {:?}: {}",
+                    product_ids, e
+                );
+                return HashMap::new();
+            }
+        };
+
+        let options_query = "SELECT o.variant_id, o.option_name, o.option_value
+                             FROM product_variant_options o
+                             JOIN product_variants v ON v.id = o.variant_id
+                             WHERE v.product_id = ANY($1)";
+
+        let mut option_values: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+        match sqlx::query(options_query)
+            .bind(product_ids)
+            .fetch_all(self.pool)
+            .await
+        {
+            Ok(option_rows) => {
+                for row in option_rows {
+                    option_values
+                        .entry(row.get("variant_id"))
+                        .or_default()
+                        .insert(row.get("option_name"), row.get("option_value"));
+                }
+            }
+            Err(e) => {
+                error!(
+                    "Error fetching variant options for products {:?}: {}",
+                    product_ids, e
+                );
+                return HashMap::new();
+            }
+        }
+
+        let mut variants: HashMap<String, Vec<ProductVariant>> = HashMap::new();
+        for row in &rows {
+            let mut variant = row_to_product_variant(row);
+            variant.option_values = option_values.remove(&variant.id).unwrap_or_default();
+            variants
+                .entry(variant.product_id.clone())
+                .or_default()
+                .push(variant);
+        }
+        variants
+    }
+
+    pub async fn create_variant(
+        &self,
+        variant: ProductVariant,
+    ) -> Result<ProductVariant, ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        if let Err(e) = reserve_sku(&mut tx, &variant.sku, None).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        let query = "INSERT INTO product_variants (id, product_id, sku, combination_key, selling_price, list_price,
+                                                 discount_price, currency, tax_included, effective_from,
+                                                 effective_until, quantity, reserved_quantity, alert_threshold,
+                                                 track_inventory, allow_backorder, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)";
+
+        let result = sqlx::query(query)
+            .bind(&variant.id)
+            .bind(&variant.product_id)
+            .bind(&variant.sku)
+            .bind(variant.combination_key())
+            .bind(variant.price.selling_price)
+            .bind(variant.price.list_price)
+            .bind(variant.price.discount_price)
+            .bind(&variant.price.currency)
+            .bind(variant.price.tax_included)
+            .bind(variant.price.effective_from)
+            .bind(variant.price.effective_until)
+            .bind(variant.inventory.quantity)
+            .bind(variant.inventory.reserved_quantity)
+            .bind(variant.inventory.alert_threshold)
+            .bind(variant.inventory.track_inventory)
+            .bind(variant.inventory.allow_backorder)
+            .bind(variant.created_at)
+            .bind(variant.updated_at)
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            let _ = tx.rollback().await;
+            return Err(map_variant_error(e));
+        }
+
+        if let Err(e) = Self::insert_option_values(&mut tx, &variant).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(variant)
+    }
+
+    pub async fn update_variant(
+        &self,
+        variant: ProductVariant,
+    ) -> Result<ProductVariant, ProductError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        if let Err(e) = reserve_sku(&mut tx, &variant.sku, Some(&variant.id)).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        let query = "UPDATE product_variants
+                     SET sku = $3, combination_key = $4, selling_price = $5, list_price = $6,
+                         discount_price = $7, currency = $8, tax_included = $9, effective_from = $10,
+                         effective_until = $11, quantity = $12, reserved_quantity = $13,
+                         alert_threshold = $14, track_inventory = $15, allow_backorder = $16
+                     WHERE product_id = $1 AND id = $2";
+
+        let result = sqlx::query(query)
+            .bind(&variant.product_id)
+            .bind(&variant.id)
+            .bind(&variant.sku)
+            .bind(variant.combination_key())
+            .bind(variant.price.selling_price)
+            .bind(variant.price.list_price)
+            .bind(variant.price.discount_price)
+            .bind(&variant.price.currency)
+            .bind(variant.price.tax_included)
+            .bind(variant.price.effective_from)
+            .bind(variant.price.effective_until)
+            .bind(variant.inventory.quantity)
+            .bind(variant.inventory.reserved_quantity)
+            .bind(variant.inventory.alert_threshold)
+            .bind(variant.inventory.track_inventory)
+            .bind(variant.inventory.allow_backorder)
+            .execute(&mut *tx)
+            .await;
+
+        match result {
+            Ok(result) if result.rows_affected() > 0 => {}
+            Ok(_) => {
+                let _ = tx.rollback().await;
+                return Err(ProductError::VariantNotFound);
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(map_variant_error(e));
+            }
+        }
+
+        let delete_query = "DELETE FROM product_variant_options WHERE variant_id = $1";
+        if let Err(e) = sqlx::query(delete_query)
+            .bind(&variant.id)
+            .execute(&mut *tx)
+            .await
+        {
+            let _ = tx.rollback().await;
+            return Err(ProductError::DatabaseError(e.to_string()));
+        }
+
+        if let Err(e) = Self::insert_option_values(&mut tx, &variant).await {
+            let _ = tx.rollback().await;
+            return Err(e);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+
+        Ok(variant)
+    }
+
+    pub async fn delete_variant(
+        &self,
+        product_id: &str,
+        variant_id: &str,
+    ) -> Result<(), ProductError> {
+        let query = "DELETE FROM product_variants WHERE product_id = $1 AND id = $2";
+
+        match sqlx::query(query)
+            .bind(product_id)
+            .bind(variant_id)
+            .execute(self.pool)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(ProductError::VariantNotFound),
+            Err(e) => Err(ProductError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn insert_option_values(
+        tx: &mut Transaction<'_, Postgres>,
+        variant: &ProductVariant,
+    ) -> Result<(), ProductError> {
+        for (name, value) in &variant.option_values {
+            let query =
+                "INSERT INTO product_variant_options (variant_id, option_name, option_value)
+                         VALUES ($1, $2, $3)";
+
+            sqlx::query(query)
+                .bind(&variant.id)
+                .bind(name)
+                .bind(value)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| ProductError::DatabaseError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn map_variant_error(e: sqlx::Error) -> ProductError {
+    match e {
+        sqlx::Error::Database(db_err) => match db_err.constraint() {
+            Some("product_variants_sku_key") => ProductError::SkuAlreadyExists,
+            Some("product_variants_combination_key") => ProductError::VariantCombinationDuplicate,
+            _ => ProductError::DatabaseError(db_err.to_string()),
+        },
+        e => ProductError::DatabaseError(e.to_string()),
+    }
+}
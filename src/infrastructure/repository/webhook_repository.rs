@@ -0,0 +1,765 @@
+use crate::app_domain::model::domain_event::DomainEventType;
+use crate::app_domain::model::webhook::{DeliveryAttempt, WebhookDelivery, WebhookSubscription};
+use crate::app_domain::repository::webhook_repository::WebhookRepository;
+use crate::infrastructure::error::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct WebhookStore {
+    subscriptions: Vec<WebhookSubscription>,
+    deliveries: Vec<WebhookDelivery>,
+    /// 古い配信記録を削除しても ID を再利用しないよう、最後に採番した ID を保持する
+    last_delivery_id: i64,
+}
+
+/// テスト用のインメモリ実装
+pub struct InMemoryWebhookRepository {
+    store: Mutex<WebhookStore>,
+}
+
+impl InMemoryWebhookRepository {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            store: Mutex::new(WebhookStore::default()),
+        }
+    }
+
+    fn lock(&self) -> AppResult<std::sync::MutexGuard<'_, WebhookStore>> {
+        self.store
+            .lock()
+            .map_err(|_| AppError::InternalServerError("Failed to acquire lock".to_string()))
+    }
+}
+
+impl Default for InMemoryWebhookRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for InMemoryWebhookRepository {
+    async fn create(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription> {
+        self.lock()?.subscriptions.push(subscription.clone());
+        Ok(subscription)
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<WebhookSubscription>> {
+        Ok(self.lock()?.subscriptions.clone())
+    }
+
+    async fn find_by_id(&self, id: &str) -> AppResult<Option<WebhookSubscription>> {
+        Ok(self
+            .lock()?
+            .subscriptions
+            .iter()
+            .find(|subscription| subscription.id == id)
+            .cloned())
+    }
+
+    async fn find_active(&self) -> AppResult<Vec<WebhookSubscription>> {
+        Ok(self
+            .lock()?
+            .subscriptions
+            .iter()
+            .filter(|subscription| subscription.is_active)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription> {
+        let mut store = self.lock()?;
+        match store
+            .subscriptions
+            .iter_mut()
+            .find(|current| current.id == subscription.id)
+        {
+            Some(current) => {
+                *current = WebhookSubscription {
+                    updated_at: Utc::now(),
+                    ..subscription
+                };
+                Ok(current.clone())
+            }
+            None => Err(AppError::not_found("WebhookSubscription", &subscription.id)),
+        }
+    }
+
+    async fn delete(&self, id: &str) -> AppResult<bool> {
+        let mut store = self.lock()?;
+        let count = store.subscriptions.len();
+        store
+            .subscriptions
+            .retain(|subscription| subscription.id != id);
+        store
+            .deliveries
+            .retain(|delivery| delivery.subscription_id != id);
+        Ok(store.subscriptions.len() < count)
+    }
+
+    async fn find_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+    ) -> AppResult<Option<WebhookDelivery>> {
+        Ok(self
+            .lock()?
+            .deliveries
+            .iter()
+            .find(|delivery| {
+                delivery.subscription_id == subscription_id && delivery.event_id == event_id
+            })
+            .cloned())
+    }
+
+    async fn find_delivery_by_id(&self, id: i64) -> AppResult<Option<WebhookDelivery>> {
+        Ok(self
+            .lock()?
+            .deliveries
+            .iter()
+            .find(|delivery| delivery.id == id)
+            .cloned())
+    }
+
+    async fn find_deliveries(
+        &self,
+        subscription_id: &str,
+        limit: i64,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        Ok(self
+            .lock()?
+            .deliveries
+            .iter()
+            .rev()
+            .filter(|delivery| delivery.subscription_id == subscription_id)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn has_pending_delivery(
+        &self,
+        subscription_id: &str,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        before_event_id: i64,
+    ) -> AppResult<bool> {
+        Ok(self.lock()?.deliveries.iter().any(|delivery| {
+            delivery.subscription_id == subscription_id
+                && !delivery.succeeded
+                && delivery.event_id < before_event_id
+                && delivery.aggregate() == (aggregate_type, aggregate_id)
+        }))
+    }
+
+    async fn queue_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+    ) -> AppResult<WebhookDelivery> {
+        let mut store = self.lock()?;
+        if let Some(delivery) = store.deliveries.iter().find(|delivery| {
+            delivery.subscription_id == subscription_id && delivery.event_id == event_id
+        }) {
+            return Ok(delivery.clone());
+        }
+
+        let now = Utc::now();
+        store.last_delivery_id += 1;
+        let delivery = WebhookDelivery {
+            id: store.last_delivery_id,
+            subscription_id: subscription_id.to_string(),
+            event_id,
+            event_type,
+            payload: payload.clone(),
+            attempts: 0,
+            status_code: None,
+            latency_ms: None,
+            last_error: None,
+            succeeded: false,
+            next_attempt_at: Some(now),
+            created_at: now,
+            last_attempt_at: now,
+        };
+        store.deliveries.push(delivery.clone());
+        Ok(delivery)
+    }
+
+    async fn record_attempt(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+        attempt: &DeliveryAttempt,
+        max_failures: i32,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> AppResult<WebhookDelivery> {
+        let mut store = self.lock()?;
+        let now = Utc::now();
+
+        let subscription = store
+            .subscriptions
+            .iter_mut()
+            .find(|subscription| subscription.id == subscription_id)
+            .ok_or_else(|| AppError::not_found("WebhookSubscription", subscription_id))?;
+        if attempt.succeeded() {
+            subscription.consecutive_failures = 0;
+        } else {
+            subscription.consecutive_failures += 1;
+            if subscription.is_active && subscription.consecutive_failures >= max_failures {
+                subscription.is_active = false;
+                subscription.disabled_at = Some(now);
+            }
+        }
+
+        let index = match store.deliveries.iter().position(|delivery| {
+            delivery.subscription_id == subscription_id && delivery.event_id == event_id
+        }) {
+            Some(index) => index,
+            None => {
+                store.last_delivery_id += 1;
+                let id = store.last_delivery_id;
+                store.deliveries.push(WebhookDelivery {
+                    id,
+                    subscription_id: subscription_id.to_string(),
+                    event_id,
+                    event_type,
+                    payload: payload.clone(),
+                    attempts: 0,
+                    status_code: None,
+                    latency_ms: None,
+                    last_error: None,
+                    succeeded: false,
+                    next_attempt_at: None,
+                    created_at: now,
+                    last_attempt_at: now,
+                });
+                store.deliveries.len() - 1
+            }
+        };
+
+        let delivery = &mut store.deliveries[index];
+        delivery.attempts += 1;
+        delivery.status_code = attempt.status_code;
+        delivery.latency_ms = Some(attempt.latency_ms);
+        delivery.last_error = attempt.error.clone();
+        delivery.succeeded = attempt.succeeded();
+        delivery.next_attempt_at = retry_at.filter(|_| !attempt.succeeded());
+        delivery.last_attempt_at = now;
+        Ok(delivery.clone())
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        let mut store = self.lock()?;
+        let WebhookStore {
+            subscriptions,
+            deliveries,
+            ..
+        } = &mut *store;
+
+        // 同じ購読・同じ集約で最も前の未配信の記録だけを対象にする
+        let heads: Vec<i64> = deliveries
+            .iter()
+            .filter(|delivery| {
+                !delivery.succeeded
+                    && !deliveries.iter().any(|earlier| {
+                        earlier.subscription_id == delivery.subscription_id
+                            && !earlier.succeeded
+                            && earlier.event_id < delivery.event_id
+                            && earlier.aggregate() == delivery.aggregate()
+                    })
+            })
+            .map(|delivery| delivery.id)
+            .collect();
+
+        let mut claimed = Vec::new();
+        for delivery in deliveries.iter_mut() {
+            let active = subscriptions.iter().any(|subscription| {
+                subscription.id == delivery.subscription_id && subscription.is_active
+            });
+            let due = heads.contains(&delivery.id)
+                && delivery.next_attempt_at.is_some_and(|at| at <= now);
+            if active && due && (claimed.len() as i64) < limit {
+                delivery.next_attempt_at = Some(now + Duration::seconds(lease_seconds));
+                claimed.push(delivery.clone());
+            }
+        }
+        Ok(claimed)
+    }
+}
+
+pub struct PostgresWebhookRepository {
+    pool: PgPool,
+}
+
+const SUBSCRIPTION_COLUMNS: &str = "id, url, event_types, secret, is_active, consecutive_failures, disabled_at, created_at, updated_at";
+const DELIVERY_COLUMNS: &str = "id, subscription_id, event_id, event_type, payload::text AS payload, attempts, status_code, latency_ms, last_error, succeeded, next_attempt_at, created_at, last_attempt_at";
+
+impl PostgresWebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_subscription(row: &sqlx::postgres::PgRow) -> AppResult<WebhookSubscription> {
+        let event_types: Vec<String> = row.get("event_types");
+        Ok(WebhookSubscription {
+            id: row.get("id"),
+            url: row.get("url"),
+            event_types: event_types
+                .iter()
+                .map(|event_type| event_type.parse())
+                .collect::<Result<_, _>>()
+                .map_err(AppError::SerializationError)?,
+            secret: row.get("secret"),
+            is_active: row.get("is_active"),
+            consecutive_failures: row.get("consecutive_failures"),
+            disabled_at: row.get("disabled_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    fn row_to_delivery(row: &sqlx::postgres::PgRow) -> AppResult<WebhookDelivery> {
+        let event_type: String = row.get("event_type");
+        let payload: String = row.get("payload");
+        Ok(WebhookDelivery {
+            id: row.get("id"),
+            subscription_id: row.get("subscription_id"),
+            event_id: row.get("event_id"),
+            event_type: event_type.parse().map_err(AppError::SerializationError)?,
+            payload: serde_json::from_str(&payload)
+                .map_err(|e| AppError::SerializationError(e.to_string()))?,
+            attempts: row.get("attempts"),
+            status_code: row.get("status_code"),
+            latency_ms: row.get("latency_ms"),
+            last_error: row.get("last_error"),
+            succeeded: row.get("succeeded"),
+            next_attempt_at: row.get("next_attempt_at"),
+            created_at: row.get("created_at"),
+            last_attempt_at: row.get("last_attempt_at"),
+        })
+    }
+
+    fn event_type_names(subscription: &WebhookSubscription) -> Vec<String> {
+        subscription
+            .event_types
+            .iter()
+            .map(|event_type| event_type.as_str().to_string())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresWebhookRepository {
+    async fn create(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO webhook_subscriptions (id, url, event_types, secret, is_active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(&subscription.id)
+        .bind(&subscription.url)
+        .bind(Self::event_type_names(&subscription))
+        .bind(&subscription.secret)
+        .bind(subscription.is_active)
+        .bind(subscription.created_at)
+        .bind(subscription.updated_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::row_to_subscription(&row)
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<WebhookSubscription>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM webhook_subscriptions ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_subscription).collect()
+    }
+
+    async fn find_by_id(&self, id: &str) -> AppResult<Option<WebhookSubscription>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE id = $1",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_subscription).transpose()
+    }
+
+    async fn find_active(&self) -> AppResult<Vec<WebhookSubscription>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE is_active = TRUE ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_subscription).collect()
+    }
+
+    async fn update(&self, subscription: WebhookSubscription) -> AppResult<WebhookSubscription> {
+        let row = sqlx::query(&format!(
+            "UPDATE webhook_subscriptions
+             SET url = $2, event_types = $3, secret = $4, is_active = $5,
+                 consecutive_failures = $6, disabled_at = $7, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1
+             RETURNING {}",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(&subscription.id)
+        .bind(&subscription.url)
+        .bind(Self::event_type_names(&subscription))
+        .bind(&subscription.secret)
+        .bind(subscription.is_active)
+        .bind(subscription.consecutive_failures)
+        .bind(subscription.disabled_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Self::row_to_subscription(&row),
+            None => Err(AppError::not_found("WebhookSubscription", &subscription.id)),
+        }
+    }
+
+    async fn delete(&self, id: &str) -> AppResult<bool> {
+        // 配信記録は ON DELETE CASCADE で削除される
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+    ) -> AppResult<Option<WebhookDelivery>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE subscription_id = $1 AND event_id = $2",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_delivery).transpose()
+    }
+
+    async fn find_delivery_by_id(&self, id: i64) -> AppResult<Option<WebhookDelivery>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE id = $1",
+            DELIVERY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_delivery).transpose()
+    }
+
+    async fn find_deliveries(
+        &self,
+        subscription_id: &str,
+        limit: i64,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM webhook_deliveries
+             WHERE subscription_id = $1
+             ORDER BY last_attempt_at DESC, id DESC
+             LIMIT $2",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_delivery).collect()
+    }
+
+    async fn has_pending_delivery(
+        &self,
+        subscription_id: &str,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        before_event_id: i64,
+    ) -> AppResult<bool> {
+        let pending: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM webhook_deliveries
+                 WHERE subscription_id = $1 AND NOT succeeded AND event_id < $4
+                   AND payload->>'aggregate_type' = $2 AND payload->>'aggregate_id' = $3
+             )",
+        )
+        .bind(subscription_id)
+        .bind(aggregate_type)
+        .bind(aggregate_id)
+        .bind(before_event_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(pending)
+    }
+
+    async fn queue_delivery(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+    ) -> AppResult<WebhookDelivery> {
+        // 既に記録がある場合はそのまま返す（RETURNING で行を返すため DO NOTHING は使わない）
+        let row = sqlx::query(&format!(
+            "INSERT INTO webhook_deliveries
+                 (subscription_id, event_id, event_type, payload, attempts, next_attempt_at)
+             VALUES ($1, $2, $3, $4::jsonb, 0, CURRENT_TIMESTAMP)
+             ON CONFLICT (subscription_id, event_id) DO UPDATE
+             SET subscription_id = webhook_deliveries.subscription_id
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(payload.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::row_to_delivery(&row)
+    }
+
+    async fn record_attempt(
+        &self,
+        subscription_id: &str,
+        event_id: i64,
+        event_type: DomainEventType,
+        payload: &Value,
+        attempt: &DeliveryAttempt,
+        max_failures: i32,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> AppResult<WebhookDelivery> {
+        let mut tx = self.pool.begin().await?;
+
+        // 連続失敗回数の更新と自動無効化を配信記録と同じトランザクションで行う
+        let updated = sqlx::query(
+            "UPDATE webhook_subscriptions
+             SET consecutive_failures = CASE WHEN $2 THEN 0 ELSE consecutive_failures + 1 END,
+                 is_active = is_active AND ($2 OR consecutive_failures + 1 < $3),
+                 disabled_at = CASE
+                     WHEN is_active AND NOT $2 AND consecutive_failures + 1 >= $3
+                         THEN CURRENT_TIMESTAMP
+                     ELSE disabled_at
+                 END
+             WHERE id = $1",
+        )
+        .bind(subscription_id)
+        .bind(attempt.succeeded())
+        .bind(max_failures)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(AppError::not_found("WebhookSubscription", subscription_id));
+        }
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO webhook_deliveries
+                 (subscription_id, event_id, event_type, payload, attempts, status_code,
+                  latency_ms, last_error, succeeded, next_attempt_at)
+             VALUES ($1, $2, $3, $4::jsonb, 1, $5, $6, $7, $8, CASE WHEN $8 THEN NULL ELSE $9 END)
+             ON CONFLICT (subscription_id, event_id) DO UPDATE
+             SET attempts = webhook_deliveries.attempts + 1,
+                 status_code = EXCLUDED.status_code,
+                 latency_ms = EXCLUDED.latency_ms,
+                 last_error = EXCLUDED.last_error,
+                 succeeded = EXCLUDED.succeeded,
+                 next_attempt_at = EXCLUDED.next_attempt_at,
+                 last_attempt_at = CURRENT_TIMESTAMP
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(payload.to_string())
+        .bind(attempt.status_code)
+        .bind(attempt.latency_ms)
+        .bind(&attempt.error)
+        .bind(attempt.succeeded())
+        .bind(retry_at)
+        .fetch_one(&mut *tx)
+        .await?;
+        let delivery = Self::row_to_delivery(&row)?;
+
+        tx.commit().await?;
+        Ok(delivery)
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        // 確保と同時に次の再試行日時を先に延ばすため、複数のプロセスが同じ記録を再送することはない。
+        // 確保中の記録も未配信のままなので、同じ集約の後続の記録は確保されない
+        let rows = sqlx::query(&format!(
+            "UPDATE webhook_deliveries
+             SET next_attempt_at = $1 + make_interval(secs => $3)
+             WHERE id IN (
+                 SELECT d.id FROM webhook_deliveries d
+                 JOIN webhook_subscriptions s ON s.id = d.subscription_id
+                 WHERE NOT d.succeeded AND d.next_attempt_at <= $1 AND s.is_active
+                   AND NOT EXISTS (
+                       SELECT 1 FROM webhook_deliveries e
+                       WHERE e.subscription_id = d.subscription_id
+                         AND NOT e.succeeded
+                         AND e.event_id < d.event_id
+                         AND e.payload->>'aggregate_type' = d.payload->>'aggregate_type'
+                         AND e.payload->>'aggregate_id' = d.payload->>'aggregate_id'
+                   )
+                 ORDER BY d.next_attempt_at, d.id
+                 LIMIT $2
+                 FOR UPDATE OF d SKIP LOCKED
+             )
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(now)
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_delivery).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure() -> DeliveryAttempt {
+        DeliveryAttempt {
+            status_code: Some(500),
+            latency_ms: 12,
+            error: Some("unexpected status 500".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_attempt_disables_after_consecutive_failures() {
+        let repo = InMemoryWebhookRepository::new();
+        let subscription = repo
+            .create(WebhookSubscription::new(
+                "http://127.0.0.1:9/hooks".to_string(),
+                Vec::new(),
+                "secret".to_string(),
+            ))
+            .await
+            .unwrap();
+        let payload = serde_json::json!({ "id": 1 });
+
+        for event_id in 1..=2 {
+            repo.record_attempt(
+                &subscription.id,
+                event_id,
+                DomainEventType::ItemDeleted,
+                &payload,
+                &failure(),
+                3,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        let current = repo.find_by_id(&subscription.id).await.unwrap().unwrap();
+        assert!(current.is_active);
+        assert_eq!(current.consecutive_failures, 2);
+
+        // 再試行は同じ配信記録の試行回数を増やす
+        let delivery = repo
+            .record_attempt(
+                &subscription.id,
+                2,
+                DomainEventType::ItemDeleted,
+                &payload,
+                &failure(),
+                3,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(delivery.attempts, 2);
+        assert_eq!(delivery.status_code, Some(500));
+
+        let current = repo.find_by_id(&subscription.id).await.unwrap().unwrap();
+        assert!(!current.is_active);
+        assert!(current.disabled_at.is_some());
+        assert!(repo.find_active().await.unwrap().is_empty());
+        assert_eq!(
+            repo.find_deliveries(&subscription.id, 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_ids_are_not_reused_after_subscription_delete() {
+        let repo = InMemoryWebhookRepository::new();
+        let payload = serde_json::json!({ "id": 1 });
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let subscription = repo
+                .create(WebhookSubscription::new(
+                    "https://example.com/hooks".to_string(),
+                    Vec::new(),
+                    "secret".to_string(),
+                ))
+                .await
+                .unwrap();
+            let delivery = repo
+                .record_attempt(
+                    &subscription.id,
+                    1,
+                    DomainEventType::ItemDeleted,
+                    &payload,
+                    &failure(),
+                    3,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(delivery.id);
+            // 購読を削除すると配信記録も削除される
+            assert!(repo.delete(&subscription.id).await.unwrap());
+        }
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
@@ -0,0 +1,443 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::app_domain::model::domain_event::OutboxEvent;
+use crate::app_domain::model::webhook::DeliveryAttempt;
+use crate::application::service::outbox_dispatcher::EventSink;
+
+/// イベント種別を示すヘッダー
+pub const EVENT_TYPE_HEADER: &str = "X-Event-Type";
+/// 受信側で重複を除外するためのイベント ID ヘッダー
+pub const EVENT_ID_HEADER: &str = "X-Event-Id";
+/// ボディの HMAC-SHA256 署名（`sha256=<hex>`）
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Webhook の 1 回分の送信内容
+pub struct WebhookRequest<'a> {
+    pub url: &'a str,
+    pub secret: Option<&'a str>,
+    pub event_type: &'a str,
+    pub event_id: i64,
+    pub body: &'a serde_json::Value,
+}
+
+/// Webhook を送信し、ステータスコードと所要時間を返すクライアント
+///
+/// 既定ではループバック・リンクローカル・プライベートアドレスへの送信を拒否する（SSRF 対策）。
+/// 確認した後に名前解決の結果を差し替えられないよう、送信時は確認したアドレスに接続する。
+/// リダイレクトで宛先を差し替えられないよう、リダイレクトには従わない。
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: Client,
+    timeout: Duration,
+    allow_private_targets: bool,
+}
+
+impl WebhookClient {
+    pub fn new(timeout: Duration) -> Self {
+        let client = Self::builder(timeout)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            client,
+            timeout,
+            allow_private_targets: false,
+        }
+    }
+
+    fn builder(timeout: Duration) -> reqwest::ClientBuilder {
+        Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+    }
+
+    /// 内部ネットワークへの送信を許可する（開発環境や運用者が設定した宛先向け）
+    pub fn allow_private_targets(mut self, allow: bool) -> Self {
+        self.allow_private_targets = allow;
+        self
+    }
+
+    /// 宛先のホストを名前解決し、内部ネットワークのアドレスが含まれていればエラーを返す
+    pub async fn check_target(&self, url: &str) -> Result<(), String> {
+        self.resolve_target(url).await.map(|_| ())
+    }
+
+    /// 宛先を確認し、ホスト名の場合は確認したアドレスを返す（IP アドレスの場合は `None`）
+    async fn resolve_target(&self, url: &str) -> Result<Option<(String, Vec<SocketAddr>)>, String> {
+        if self.allow_private_targets {
+            return Ok(None);
+        }
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("URL が不正です: {}", e))?;
+        let host = parsed
+            .host_str()
+            .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| "URL にホスト名がありません".to_string())?;
+        let port = parsed.port_or_known_default().unwrap_or(443);
+
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            if !is_public(&ip) {
+                return Err(private_target(&ip));
+            }
+            return Ok(None);
+        }
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("ホスト名を解決できません: {}", e))?
+            .collect();
+        if addrs.is_empty() {
+            return Err("ホスト名を解決できません".to_string());
+        }
+        if let Some(addr) = addrs.iter().find(|addr| !is_public(&addr.ip())) {
+            return Err(private_target(&addr.ip()));
+        }
+        Ok(Some((host.to_string(), addrs)))
+    }
+
+    /// 名前解決を確認済みのアドレスに固定したクライアント
+    fn pinned(&self, host: &str, addrs: &[SocketAddr]) -> Result<Client, String> {
+        Self::builder(self.timeout)
+            .resolve_to_addrs(host, addrs)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+
+    /// 2xx 以外の応答や接続エラー・タイムアウト、宛先が許可されない場合は失敗として返す
+    pub async fn send(&self, request: WebhookRequest<'_>) -> DeliveryAttempt {
+        // 登録後に名前解決の結果が変わる場合もあるため、送信のたびに確認し、
+        // 接続時に別のアドレスへ解決されないよう確認したアドレスに固定する
+        let client = match self.resolve_target(request.url).await {
+            Ok(None) => Ok(self.client.clone()),
+            Ok(Some((host, addrs))) => self.pinned(&host, &addrs),
+            Err(error) => Err(error),
+        };
+        let client = match client {
+            Ok(client) => client,
+            Err(error) => {
+                return DeliveryAttempt {
+                    status_code: None,
+                    latency_ms: 0,
+                    error: Some(error),
+                }
+            }
+        };
+
+        let body = request.body.to_string();
+        let mut builder = client
+            .post(request.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_TYPE_HEADER, request.event_type)
+            .header(EVENT_ID_HEADER, request.event_id.to_string());
+        if let Some(secret) = request.secret {
+            builder = builder.header(SIGNATURE_HEADER, signature(secret, body.as_bytes()));
+        }
+
+        let started = Instant::now();
+        let result = builder.body(body).send().await;
+        let latency_ms = started.elapsed().as_millis() as i64;
+
+        match result {
+            Ok(response) if response.status().is_success() => DeliveryAttempt {
+                status_code: Some(response.status().as_u16() as i32),
+                latency_ms,
+                error: None,
+            },
+            Ok(response) => DeliveryAttempt {
+                status_code: Some(response.status().as_u16() as i32),
+                latency_ms,
+                error: Some(format!("unexpected status {}", response.status())),
+            },
+            Err(e) => DeliveryAttempt {
+                status_code: None,
+                latency_ms,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+fn private_target(ip: &IpAddr) -> String {
+    format!("内部ネットワークのアドレス（{}）には送信できません", ip)
+}
+
+/// インターネット上の宛先として扱えるアドレスか
+fn is_public(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(&v4),
+            None => {
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || v6.is_unique_local()
+                    || v6.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+fn is_public_v4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 はキャリアグレード NAT の共有アドレス
+    let shared = a == 100 && (b & 0xc0) == 64;
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared)
+}
+
+/// `sha256=<hex>` 形式の HMAC-SHA256 署名
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut key = [0u8; BLOCK_SIZE];
+    if secret.len() > BLOCK_SIZE {
+        key[..32].copy_from_slice(&Sha256::digest(secret.as_bytes()));
+    } else {
+        key[..secret.len()].copy_from_slice(secret.as_bytes());
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(key.map(|b| b ^ 0x36));
+    inner.update(body);
+    let mut outer = Sha256::new();
+    outer.update(key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+
+    let hex: String = outer
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+/// イベントを JSON で設定済みの URL に POST するシンク
+///
+/// 2xx 以外の応答やタイムアウトは失敗として扱い、ディスパッチャーが再試行する。
+pub struct WebhookSink {
+    client: WebhookClient,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            // 宛先は運用者が設定するため、内部ネットワークも許可する
+            client: WebhookClient::new(timeout).allow_private_targets(true),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        let body = serde_json::to_value(event).map_err(|e| e.to_string())?;
+        let attempt = self
+            .client
+            .send(WebhookRequest {
+                url: &self.url,
+                secret: None,
+                event_type: event.event.event_type.as_str(),
+                event_id: event.id,
+                body: &body,
+            })
+            .await;
+        match attempt.error {
+            None => Ok(()),
+            Some(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::domain_event::DomainEvent;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 1 件だけ受け付けて指定したステータスを返す HTTP サーバー
+    async fn stand_in(status: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // ヘッダーと Content-Length 分のボディを読み切る
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\n\r\n", status);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    fn event() -> OutboxEvent {
+        OutboxEvent {
+            id: 42,
+            event: DomainEvent::item_deleted(7, false),
+            attempts: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_posts_event_with_headers() {
+        let (url, server) = stand_in("204 No Content").await;
+        let sink = WebhookSink::new(url, Duration::from_secs(5));
+
+        sink.publish(&event()).await.unwrap();
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("post /hooks "));
+        assert!(request.contains("x-event-type: itemdeleted"));
+        assert!(request.contains("x-event-id: 42"));
+        assert!(request.contains("\"aggregate_id\":\"7\""));
+    }
+
+    #[tokio::test]
+    async fn test_non_success_status_is_failure() {
+        let (url, server) = stand_in("503 Service Unavailable").await;
+        let sink = WebhookSink::new(url, Duration::from_secs(5));
+
+        let error = sink.publish(&event()).await.unwrap_err();
+        assert!(error.contains("503"), "{}", error);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_signs_body_and_reports_status() {
+        let (url, server) = stand_in("202 Accepted").await;
+        let client = WebhookClient::new(Duration::from_secs(5)).allow_private_targets(true);
+        let body = serde_json::json!({ "id": 1 });
+
+        let attempt = client
+            .send(WebhookRequest {
+                url: &url,
+                secret: Some("key"),
+                event_type: "ItemDeleted",
+                event_id: 1,
+                body: &body,
+            })
+            .await;
+        assert!(attempt.succeeded());
+        assert_eq!(attempt.status_code, Some(202));
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        let expected = signature("key", body.to_string().as_bytes());
+        assert!(request.contains(&format!("x-webhook-signature: {}", expected)));
+    }
+
+    #[tokio::test]
+    async fn test_private_targets_are_rejected_unless_allowed() {
+        let client = WebhookClient::new(Duration::from_secs(1));
+        for url in [
+            "http://127.0.0.1:8080/hooks",
+            "http://10.0.0.5/hooks",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/hooks",
+            "http://0.0.0.0/hooks",
+            "http://[::1]/hooks",
+            "http://[fe80::1]/hooks",
+            "http://[fd00::1]/hooks",
+            "http://[::ffff:192.168.0.1]/hooks",
+        ] {
+            assert!(client.check_target(url).await.is_err(), "{}", url);
+        }
+        assert!(client
+            .check_target("https://93.184.216.34/hooks")
+            .await
+            .is_ok());
+
+        let attempt = client
+            .send(WebhookRequest {
+                url: "http://127.0.0.1:1/hooks",
+                secret: None,
+                event_type: "ItemDeleted",
+                event_id: 1,
+                body: &serde_json::json!({}),
+            })
+            .await;
+        assert!(attempt.error.unwrap().contains("127.0.0.1"));
+
+        let client = client.allow_private_targets(true);
+        assert!(client
+            .check_target("http://127.0.0.1:8080/hooks")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_client_connects_to_checked_address() {
+        let (url, server) = stand_in("204 No Content").await;
+        let addr: SocketAddr = url
+            .trim_start_matches("http://")
+            .trim_end_matches("/hooks")
+            .parse()
+            .unwrap();
+        let client = WebhookClient::new(Duration::from_secs(5));
+
+        // 名前解決できないホスト名でも、確認済みのアドレスへ接続する
+        let response = client
+            .pinned("webhook.invalid", &[addr])
+            .unwrap()
+            .post(format!("http://webhook.invalid:{}/hooks", addr.port()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+        assert!(server
+            .await
+            .unwrap()
+            .to_ascii_lowercase()
+            .contains(&format!("host: webhook.invalid:{}", addr.port())));
+
+        // IP アドレスの宛先は名前解決しないため固定しない
+        assert_eq!(
+            client.resolve_target("https://93.184.216.34/hooks").await,
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_signature_matches_hmac_sha256() {
+        // RFC 4231 テストケース 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
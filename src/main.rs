@@ -56,6 +56,29 @@ async fn main() -> StartupResult<()> {
     // 依存性注入コンテナの作成
     let container = AppContainer::new(pool, &config);
 
+    // 商品ステータス変更予約の実行器を起動
+    if config.scheduler.enabled {
+        container.build_schedule_executor(&config.scheduler).spawn();
+        info!("Product schedule executor enabled");
+    }
+
+    // 期限切れの冪等性キーを定期的に削除する
+    container
+        .idempotency_service
+        .clone()
+        .spawn_purge(config.idempotency.purge_interval_seconds);
+
+    // ドメインイベントの outbox ディスパッチャーを起動
+    if config.outbox.enabled {
+        container.build_outbox_dispatcher(&config.outbox).spawn();
+        // 配信に失敗した Webhook 購読は outbox とは別に購読ごとに再試行する
+        container
+            .webhook_service
+            .clone()
+            .spawn_retry(config.webhook.retry_interval_seconds);
+        info!("Outbox dispatcher enabled");
+    }
+
     // サーバーアドレスの準備
     let http_addr = format!("{}:{}", config.server.http_host, config.server.http_port);
     let grpc_addr = format!("{}:{}", config.server.grpc_host, config.server.grpc_port)
@@ -69,7 +92,7 @@ async fn main() -> StartupResult<()> {
 
     // HTTPサーバーとgRPCサーバーの構築
     let http_server = build_http_server(&container, &http_addr)?;
-    let grpc_server = container.build_grpc_server().serve(grpc_addr);
+    let grpc_server = container.build_grpc_server()?.serve(grpc_addr);
 
     // 両方のサーバーを並行して実行
     tokio::select! {
@@ -0,0 +1,122 @@
+use actix_web::error::JsonPayloadError;
+use actix_web::http::header;
+use actix_web::{web, HttpRequest};
+
+use crate::infrastructure::error::AppError;
+
+/// 上限を指定した JSON ボディの設定
+///
+/// 上限を超えた場合は上限値を含む 413、それ以外の不正なボディは 400 を返す。
+pub fn json_config(limit: usize) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(limit)
+        .error_handler(|err, _req| match err {
+            JsonPayloadError::Overflow { limit }
+            | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+                AppError::payload_too_large(limit).into()
+            }
+            err => AppError::bad_request(err.to_string()).into(),
+        })
+}
+
+/// ストリーミングで受信するボディの上限
+///
+/// `web::Payload` には `JsonConfig` の上限が効かないため、
+/// リソースの `app_data` として登録し、ハンドラー側で受信量を確認する。
+#[derive(Debug, Clone, Copy)]
+pub struct StreamLimit(pub usize);
+
+impl StreamLimit {
+    /// リクエストに登録された上限（未登録の場合は無制限）
+    pub fn of(req: &HttpRequest) -> Self {
+        req.app_data::<StreamLimit>()
+            .copied()
+            .unwrap_or(StreamLimit(usize::MAX))
+    }
+
+    /// Content-Length が上限を超えている場合は受信前にエラーを返す
+    pub fn check_declared(&self, req: &HttpRequest) -> Result<(), AppError> {
+        let declared = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+
+        match declared {
+            Some(length) => self.check_received(length),
+            None => Ok(()),
+        }
+    }
+
+    /// 受信済みのバイト数が上限を超えている場合はエラーを返す
+    pub fn check_received(&self, received: usize) -> Result<(), AppError> {
+        if received > self.0 {
+            return Err(AppError::payload_too_large(self.0));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App, HttpResponse};
+
+    async fn echo(body: web::Json<serde_json::Value>) -> HttpResponse {
+        HttpResponse::Ok().json(body.into_inner())
+    }
+
+    #[actix_web::test]
+    async fn test_json_over_limit_is_413_with_limit() {
+        let app = test::init_service(
+            App::new()
+                .app_data(json_config(16))
+                .route("/", web::post().to(echo)),
+        )
+        .await;
+
+        let small = test::TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "a": 1 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, small).await.status(), 200);
+
+        let large = test::TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": "x".repeat(64) }))
+            .to_request();
+        let res = test::call_service(&app, large).await;
+        assert_eq!(res.status(), 413);
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(body["error"]["type"], "payload_too_large");
+        assert!(body["error"]["message"].as_str().unwrap().contains("16"));
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_json_is_400() {
+        let app = test::init_service(
+            App::new()
+                .app_data(json_config(1024))
+                .route("/", web::post().to(echo)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload("{not json")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_stream_limit_checks_received_bytes() {
+        let limit = StreamLimit(10);
+        assert!(limit.check_received(10).is_ok());
+        assert!(matches!(
+            limit.check_received(11),
+            Err(AppError::PayloadTooLarge(10))
+        ));
+    }
+}
@@ -0,0 +1,266 @@
+use actix_web::http::header::{self, ContentEncoding};
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
+use futures::stream;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::app_domain::model::change_event::{ChangeCursor, EntityType};
+use crate::application::service::change_feed::{ChangeFeed, ChangeFeedError, ChangeFeedMessage};
+use crate::infrastructure::error::AppError;
+
+/// 再接続時に EventSource が送る最後のイベント ID
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+pub struct ChangeHandler {
+    feed: Arc<ChangeFeed>,
+    heartbeat: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeFeedQuery {
+    /// このカーソル（`<epoch>-<sequence>`）より後のイベントから再開する（`Last-Event-ID` より優先）
+    pub after: Option<String>,
+    /// 受け取るエンティティ種別（カンマ区切り、例: `products,categories`）
+    pub entity_types: Option<String>,
+}
+
+impl ChangeHandler {
+    pub fn new(feed: Arc<ChangeFeed>, heartbeat: Duration) -> Self {
+        Self { feed, heartbeat }
+    }
+
+    // GET /api/changes
+    pub async fn watch_changes(
+        data: web::Data<ChangeHandler>,
+        req: HttpRequest,
+        query: web::Query<ChangeFeedQuery>,
+    ) -> ActixResult<HttpResponse> {
+        let query = query.into_inner();
+        let after = match query.after {
+            Some(after) => Some(
+                after
+                    .parse::<ChangeCursor>()
+                    .map_err(AppError::BadRequest)?,
+            ),
+            None => last_event_id(&req)?,
+        };
+        let entity_types = query
+            .entity_types
+            .as_deref()
+            .map(parse_entity_types)
+            .transpose()
+            .map_err(AppError::BadRequest)?
+            .unwrap_or_default();
+        info!(
+            "Change feed subscription: after={:?}, entity_types={:?}",
+            after, entity_types
+        );
+
+        let subscription = match data.feed.subscribe(after, entity_types) {
+            Ok(subscription) => subscription,
+            Err(error) => return Ok(error_response(error)),
+        };
+        let heartbeat = data.heartbeat;
+
+        // 途中のエラーはステータスを変更できないため、error イベントを送って終了する
+        let body = stream::unfold(Some(subscription), move |subscription| async move {
+            let mut subscription = subscription?;
+            let (frame, subscription) = match subscription.next_or_heartbeat(heartbeat).await {
+                Ok(message) => (message_frame(&message), Some(subscription)),
+                Err(error) => {
+                    warn!(
+                        "Change feed stream ended at {}: {}",
+                        subscription.cursor(),
+                        error
+                    );
+                    (error_frame(&error), None)
+                }
+            };
+            Some((
+                Ok::<_, std::io::Error>(web::Bytes::from(frame)),
+                subscription,
+            ))
+        });
+
+        Ok(HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            // 圧縮やプロキシのバッファリングでイベントが遅れないようにする
+            .insert_header(ContentEncoding::Identity)
+            .insert_header(("X-Accel-Buffering", "no"))
+            .streaming(body))
+    }
+}
+
+fn last_event_id(req: &HttpRequest) -> Result<Option<ChangeCursor>, AppError> {
+    req.headers()
+        .get(LAST_EVENT_ID)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or_else(|| {
+                    AppError::BadRequest(
+                        "Last-Event-ID は <epoch>-<sequence> の形式で指定してください".into(),
+                    )
+                })
+        })
+        .transpose()
+}
+
+fn parse_entity_types(value: &str) -> Result<Vec<EntityType>, String> {
+    value
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+fn error_response(error: ChangeFeedError) -> HttpResponse {
+    let (mut builder, error_type, oldest) = match &error {
+        // 410 の場合、クライアントは全件を取り直してからカーソルなしで購読し直す
+        ChangeFeedError::CursorExpired { oldest, .. } => {
+            (HttpResponse::Gone(), "cursor_expired", Some(*oldest))
+        }
+        ChangeFeedError::Closed => (
+            HttpResponse::ServiceUnavailable(),
+            "service_unavailable",
+            None,
+        ),
+    };
+    builder.json(serde_json::json!({
+        "error": {
+            "type": error_type,
+            "message": error.to_string(),
+            "oldest_cursor": oldest.map(|oldest| oldest.to_string()),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }
+    }))
+}
+
+fn message_frame(message: &ChangeFeedMessage) -> String {
+    match message {
+        ChangeFeedMessage::Event(event) => format!(
+            "id: {}\nevent: change\ndata: {}\n\n",
+            event.cursor(),
+            serde_json::to_string(event).unwrap_or_default()
+        ),
+        ChangeFeedMessage::Heartbeat { cursor, sent_at } => format!(
+            "id: {}\nevent: heartbeat\ndata: {}\n\n",
+            cursor,
+            serde_json::json!({ "cursor": cursor.to_string(), "sent_at": sent_at })
+        ),
+    }
+}
+
+fn error_frame(error: &ChangeFeedError) -> String {
+    let error_type = match error {
+        ChangeFeedError::CursorExpired { .. } => "cursor_expired",
+        ChangeFeedError::Closed => "service_unavailable",
+    };
+    format!(
+        "event: error\ndata: {}\n\n",
+        serde_json::json!({ "type": error_type, "message": error.to_string() })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::change_event::ChangeKind;
+    use actix_web::body::MessageBody;
+    use actix_web::{test, App};
+    use serde_json::Value;
+
+    fn app_data(feed: Arc<ChangeFeed>) -> web::Data<ChangeHandler> {
+        web::Data::new(ChangeHandler::new(feed, Duration::from_millis(50)))
+    }
+
+    async fn next_frame(body: &mut actix_web::body::BoxBody) -> String {
+        let chunk = futures::future::poll_fn(|cx| std::pin::Pin::new(&mut *body).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        String::from_utf8(chunk.to_vec()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_stream_resumes_from_last_event_id_with_filter() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        feed.publish_id(EntityType::Item, 1, ChangeKind::Deleted);
+        feed.publish_id(EntityType::Product, "p1", ChangeKind::Deleted);
+        feed.publish_id(EntityType::Item, 2, ChangeKind::Restored);
+        let epoch = feed.epoch();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(app_data(feed))
+                .route("/api/changes", web::get().to(ChangeHandler::watch_changes)),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/api/changes?entity_types=items")
+            .insert_header((LAST_EVENT_ID, format!("{}-1", epoch)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let mut body = resp.into_body();
+        let frame = next_frame(&mut body).await;
+        assert!(frame.starts_with(&format!("id: {}-3\nevent: change\ndata: ", epoch)));
+        let data: Value = serde_json::from_str(
+            frame
+                .lines()
+                .find_map(|line| line.strip_prefix("data: "))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(data["entity_type"], "item");
+        assert_eq!(data["entity_id"], "2");
+        assert_eq!(data["kind"], "restored");
+
+        // 何も起きなければハートビートで処理済みの位置を知らせる
+        let frame = next_frame(&mut body).await;
+        assert!(frame.starts_with(&format!("id: {}-3\nevent: heartbeat\n", epoch)));
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_requests_are_rejected() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        let epoch = feed.epoch();
+        let app = test::init_service(
+            App::new()
+                .app_data(app_data(feed))
+                .route("/api/changes", web::get().to(ChangeHandler::watch_changes)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/changes?entity_types=orders")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // エポックを含まない旧形式のカーソル
+        let req = test::TestRequest::get()
+            .uri("/api/changes?after=42")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // 別のプロセスで発行されたカーソル
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/changes?after={}-0", epoch + 1))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 410);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"]["type"], "cursor_expired");
+        assert_eq!(body["error"]["oldest_cursor"], format!("{}-1", epoch));
+    }
+}
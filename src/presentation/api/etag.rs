@@ -0,0 +1,120 @@
+use actix_web::http::header::{ETag, EntityTag, Header, IfMatch};
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use std::fmt;
+use std::future::Future;
+
+/// リソースのバージョンを ETag ヘッダーとして表現する
+pub fn etag(version: i64) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+/// If-Match ヘッダーがどのバージョンとも一致しない場合のエラー（412 を返す）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreconditionFailed;
+
+impl fmt::Display for PreconditionFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "If-Match does not match the current version")
+    }
+}
+
+impl ResponseError for PreconditionFailed {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::PreconditionFailed().json(serde_json::json!({
+            "code": "PRECONDITION_FAILED",
+            "message": "If-Match が現在のバージョンと一致しません",
+            "details": null
+        }))
+    }
+}
+
+/// If-Match ヘッダーから呼び出し元が期待するバージョンを取り出す。
+///
+/// ヘッダーが無い場合と `*` の場合は照合しないため `None` を返す。
+/// If-Match は強い比較で照合するため、弱いタグ（`W/"4"`）と数値として解釈できないタグは
+/// どのバージョンとも一致しない。一致しうるタグが無ければ `PreconditionFailed` を返す。
+///
+/// タグが複数ある場合は `current_version` で現在のバージョンを調べ、いずれかと一致すれば
+/// そのバージョンを返す。返したバージョンは更新時にも照合されるため、調べてから更新するまでの
+/// 間に他の更新が入った場合も検出される。
+pub async fn if_match_version<F, Fut>(
+    req: &HttpRequest,
+    current_version: F,
+) -> Result<Option<i64>, PreconditionFailed>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Option<i64>>,
+{
+    if !req.headers().contains_key(IfMatch::name()) {
+        return Ok(None);
+    }
+
+    let versions: Vec<i64> = match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => return Ok(None),
+        Ok(IfMatch::Items(tags)) => tags
+            .iter()
+            .filter(|tag| !tag.weak)
+            .filter_map(|tag| tag.tag().parse().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    match versions.as_slice() {
+        [] => Err(PreconditionFailed),
+        [version] => Ok(Some(*version)),
+        _ => match current_version().await {
+            Some(current) if versions.contains(&current) => Ok(Some(current)),
+            Some(_) => Err(PreconditionFailed),
+            // 見つからない場合は更新側で NotFound を返す
+            None => Ok(Some(versions[0])),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    async fn version(
+        header: Option<&str>,
+        current: Option<i64>,
+    ) -> Result<Option<i64>, PreconditionFailed> {
+        let mut req = TestRequest::default();
+        if let Some(header) = header {
+            req = req.insert_header(("If-Match", header));
+        }
+        if_match_version(&req.to_http_request(), || async move { current }).await
+    }
+
+    #[actix_web::test]
+    async fn test_if_match_version() {
+        assert_eq!(version(None, Some(3)).await, Ok(None));
+        assert_eq!(version(Some("*"), Some(3)).await, Ok(None));
+        assert_eq!(version(Some("\"3\""), Some(3)).await, Ok(Some(3)));
+        assert_eq!(
+            version(Some("\"abc\""), Some(3)).await,
+            Err(PreconditionFailed)
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_weak_tags_never_match() {
+        assert_eq!(
+            version(Some("W/\"4\""), Some(4)).await,
+            Err(PreconditionFailed)
+        );
+        assert_eq!(version(Some("W/\"4\", \"5\""), Some(4)).await, Ok(Some(5)));
+    }
+
+    #[actix_web::test]
+    async fn test_any_listed_tag_matches() {
+        assert_eq!(version(Some("\"2\", \"4\""), Some(4)).await, Ok(Some(4)));
+        assert_eq!(version(Some("\"2\", \"4\""), Some(2)).await, Ok(Some(2)));
+        assert_eq!(
+            version(Some("\"2\", \"4\""), Some(3)).await,
+            Err(PreconditionFailed)
+        );
+        assert_eq!(version(Some("\"2\", \"4\""), None).await, Ok(Some(2)));
+    }
+}
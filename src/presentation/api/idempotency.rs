@@ -0,0 +1,358 @@
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::web::{Bytes, BytesMut};
+use actix_web::{Error, HttpMessage, HttpResponse, ResponseError};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use futures::StreamExt;
+use std::rc::Rc;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::app_domain::model::idempotency::{IdempotencyKey, IdempotencyOutcome, StoredResponse};
+use crate::application::service::idempotency_service::IdempotencyService;
+use crate::infrastructure::auth::middleware::KeycloakUser;
+use crate::infrastructure::error::AppError;
+
+/// 再送を識別するリクエストヘッダー
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// 保存済みの結果を返したことを示すレスポンスヘッダー
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// 再生時にも返すレスポンスヘッダー
+const STORED_HEADERS: [header::HeaderName; 3] =
+    [header::CONTENT_TYPE, header::ETAG, header::LOCATION];
+
+/// POST / PUT / PATCH に `Idempotency-Key` が付いている場合、同じキーの再送には
+/// 最初の処理結果を返すミドルウェア。
+///
+/// キーは認証済みユーザーごとに区切り、メソッド・パス・クエリ・ボディの
+/// フィンガープリントを保存する。同じキーで内容の異なるリクエストは 422、
+/// 処理中の再送は 409 を返す。認証できないリクエストのキーは扱わない（ハンドラーが 401 を返す）。
+/// 5xx になった処理は結果を保存せず、キーを解放して再試行できるようにする。
+///
+/// ボディはルーティング前に `body_limit` バイトまで読み込み、超えた場合は 413 を返す。
+/// ボディをストリームで処理するパスは先読みせず、冪等性キーも扱わない。
+pub struct Idempotency {
+    service: Arc<IdempotencyService>,
+    body_limit: usize,
+    streaming_paths: Arc<Vec<String>>,
+}
+
+impl Idempotency {
+    pub fn new(service: Arc<IdempotencyService>, body_limit: usize) -> Self {
+        Self {
+            service,
+            body_limit,
+            streaming_paths: Arc::new(Vec::new()),
+        }
+    }
+
+    /// ボディをストリームで処理するパスを登録する
+    pub fn streaming_path(mut self, path: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.streaming_paths).push(path.into());
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = IdempotencyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+            idempotency: self.service.clone(),
+            body_limit: self.body_limit,
+            streaming_paths: self.streaming_paths.clone(),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+    idempotency: Arc<IdempotencyService>,
+    body_limit: usize,
+    streaming_paths: Arc<Vec<String>>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let idempotency = self.idempotency.clone();
+        let body_limit = self.body_limit;
+        let streaming = self.streaming_paths.iter().any(|path| path == req.path());
+
+        Box::pin(async move {
+            let key = match idempotency_key(&req).filter(|_| !streaming) {
+                Some(key) => key,
+                None => return service.call(req).await.map(|res| res.map_into_boxed_body()),
+            };
+            let key = match req.extract::<KeycloakUser>().await {
+                Ok(user) => IdempotencyKey::new(format!("sub:{}", user.claims.sub), key),
+                Err(_) => return service.call(req).await.map(|res| res.map_into_boxed_body()),
+            };
+
+            // ボディを読み取ってフィンガープリントを計算し、ハンドラー用に戻す
+            let body = match read_body(&mut req, body_limit).await {
+                Ok(body) => body,
+                Err(error) => return Ok(req.into_response(error.error_response())),
+            };
+            let request_path = format!("{} {}", req.method(), req.path());
+            let fingerprint = IdempotencyService::fingerprint(&[
+                request_path.as_bytes(),
+                req.query_string().as_bytes(),
+                &body,
+            ]);
+            req.set_payload(Payload::from(body));
+
+            match idempotency.begin(&key, &fingerprint, &request_path).await? {
+                IdempotencyOutcome::Proceed => {}
+                IdempotencyOutcome::Replay(stored) => {
+                    return Ok(req.into_response(replay_response(stored)));
+                }
+                IdempotencyOutcome::InProgress => {
+                    return Ok(req.into_response(error_response(
+                        StatusCode::CONFLICT,
+                        "IDEMPOTENCY_KEY_IN_USE",
+                        "同じ冪等性キーのリクエストを処理中です",
+                    )));
+                }
+                IdempotencyOutcome::FingerprintMismatch => {
+                    return Ok(req.into_response(error_response(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "IDEMPOTENCY_KEY_REUSED",
+                        "冪等性キーが異なる内容のリクエストで再利用されました",
+                    )));
+                }
+            }
+
+            let res = match service.call(req).await {
+                Ok(res) => res,
+                Err(e) => {
+                    release(&idempotency, &key).await;
+                    return Err(e);
+                }
+            };
+
+            if res.status().is_server_error() {
+                release(&idempotency, &key).await;
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = match to_bytes(body).await {
+                Ok(body) => body,
+                Err(_) => {
+                    release(&idempotency, &key).await;
+                    return Err(actix_web::error::ErrorInternalServerError(
+                        "failed to read response body",
+                    ));
+                }
+            };
+
+            let stored = StoredResponse {
+                status_code: res.status().as_u16(),
+                headers: stored_headers(&res),
+                body: body.to_vec(),
+            };
+            if let Err(e) = idempotency.complete(&key, stored).await {
+                error!("Failed to store idempotent response: {}", e);
+            }
+
+            Ok(ServiceResponse::new(req, res.set_body(BoxBody::new(body))))
+        })
+    }
+}
+
+/// 上限までボディを読み込む（超えた場合は残りを読まずに打ち切る）
+async fn read_body(req: &mut ServiceRequest, limit: usize) -> Result<Bytes, AppError> {
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared.is_some_and(|length| length > limit) {
+        return Err(AppError::payload_too_large(limit));
+    }
+
+    let mut payload = req.take_payload();
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| AppError::bad_request(e.to_string()))?;
+        if body.len() + chunk.len() > limit {
+            return Err(AppError::payload_too_large(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
+}
+
+/// 冪等性キーの対象となるリクエストであればキーを返す
+fn idempotency_key(req: &ServiceRequest) -> Option<String> {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH) {
+        return None;
+    }
+
+    req.headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).trim().to_string())
+}
+
+fn stored_headers(res: &HttpResponse<()>) -> Vec<(String, String)> {
+    STORED_HEADERS
+        .iter()
+        .filter_map(|name| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn replay_response(stored: StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in stored.headers {
+        builder.insert_header((name, value));
+    }
+    builder
+        .insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"))
+        .body(stored.body)
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "error": {
+            "code": code,
+            "message": message
+        }
+    }))
+}
+
+async fn release(idempotency: &IdempotencyService, key: &IdempotencyKey) {
+    if let Err(e) = idempotency.release(key).await {
+        error!("Failed to release idempotency key: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repository::idempotency_repository::InMemoryIdempotencyRepository;
+    use actix_web::{test, web, App};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn create(counter: web::Data<AtomicUsize>, body: web::Bytes) -> HttpResponse {
+        let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        HttpResponse::Created()
+            .insert_header((header::LOCATION, format!("/things/{}", n)))
+            .json(serde_json::json!({ "n": n, "len": body.len() }))
+    }
+
+    #[actix_web::test]
+    async fn test_retry_replays_stored_response() {
+        let counter = web::Data::new(AtomicUsize::new(0));
+        let service = Arc::new(IdempotencyService::new(
+            Arc::new(InMemoryIdempotencyRepository::new()),
+            60,
+        ));
+        let app = test::init_service(
+            App::new()
+                .app_data(counter.clone())
+                .wrap(Idempotency::new(service, 1024))
+                .route("/things", web::post().to(create)),
+        )
+        .await;
+
+        let request = |body: &'static str| {
+            test::TestRequest::post()
+                .uri("/things")
+                .insert_header((IDEMPOTENCY_KEY_HEADER, "retry-1"))
+                .set_payload(body)
+                .to_request()
+        };
+
+        let first = test::call_service(&app, request("{\"a\":1}")).await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first_body = test::read_body(first).await;
+
+        let retry = test::call_service(&app, request("{\"a\":1}")).await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(
+            retry.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+            "true"
+        );
+        assert_eq!(retry.headers().get(header::LOCATION).unwrap(), "/things/1");
+        assert_eq!(test::read_body(retry).await, first_body);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        let reused = test::call_service(&app, request("{\"a\":2}")).await;
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        // キーが無いリクエストは毎回処理される
+        let without_key = test::TestRequest::post()
+            .uri("/things")
+            .set_payload("{\"a\":1}")
+            .to_request();
+        let res = test::call_service(&app, without_key).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_body_over_limit_is_rejected_with_413() {
+        let counter = web::Data::new(AtomicUsize::new(0));
+        let service = Arc::new(IdempotencyService::new(
+            Arc::new(InMemoryIdempotencyRepository::new()),
+            60,
+        ));
+        let app = test::init_service(
+            App::new()
+                .app_data(counter.clone())
+                .wrap(Idempotency::new(service, 8).streaming_path("/import"))
+                .route("/things", web::post().to(create))
+                .route("/import", web::post().to(create)),
+        )
+        .await;
+
+        let request = |uri: &str| {
+            test::TestRequest::post()
+                .uri(uri)
+                .insert_header((IDEMPOTENCY_KEY_HEADER, "large-1"))
+                .set_payload("0123456789")
+                .to_request()
+        };
+
+        let res = test::call_service(&app, request("/things")).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        // ストリームで処理するパスは先読みせずそのままハンドラーに渡す
+        let res = test::call_service(&app, request("/import")).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let res = test::call_service(&app, request("/import")).await;
+        assert!(res.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+}
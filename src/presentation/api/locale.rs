@@ -0,0 +1,59 @@
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponseBuilder};
+
+use crate::app_domain::model::localization::Locales;
+use crate::application::dto::translation_dto::LocaleQuery;
+
+/// 表示するロケールを `locale` クエリ、Accept-Language ヘッダーの順に決める。
+///
+/// どちらもサポートしていないロケールの場合は既定ロケールを返す。
+pub fn request_locale(req: &HttpRequest, locales: &Locales) -> String {
+    let requested = web::Query::<LocaleQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().locale);
+    let accept_language = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+
+    locales.negotiate(requested.as_deref(), accept_language)
+}
+
+/// 表示したロケールを Content-Language で返す（Accept-Language で内容が変わるため Vary も付ける）
+pub fn content_language<'a>(
+    builder: &'a mut HttpResponseBuilder,
+    locale: &str,
+) -> &'a mut HttpResponseBuilder {
+    builder
+        .insert_header((header::CONTENT_LANGUAGE, locale.to_string()))
+        .insert_header((header::VARY, "Accept-Language"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_request_locale() {
+        let locales = Locales::default();
+
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(request_locale(&req, &locales), "ja");
+
+        let req = TestRequest::default()
+            .insert_header(("Accept-Language", "en-US,en;q=0.9"))
+            .to_http_request();
+        assert_eq!(request_locale(&req, &locales), "en");
+
+        let req = TestRequest::with_uri("/api/products?locale=ja&limit=10")
+            .insert_header(("Accept-Language", "en"))
+            .to_http_request();
+        assert_eq!(request_locale(&req, &locales), "ja");
+
+        let req = TestRequest::with_uri("/api/products?locale=fr")
+            .insert_header(("Accept-Language", "en"))
+            .to_http_request();
+        assert_eq!(request_locale(&req, &locales), "en");
+    }
+}
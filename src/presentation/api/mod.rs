@@ -1,4 +1,12 @@
+pub mod body_limit;
 pub mod category_handler;
+pub mod change_handler;
+pub mod etag;
+pub mod idempotency;
 pub mod item_handler;
+pub mod locale;
 pub mod product_handler;
+pub mod slug_handler;
+pub mod translation_handler;
 pub mod user_handler;
+pub mod webhook_handler;
@@ -1,44 +1,71 @@
-use actix_web::{web, HttpResponse, Responder, Result as ActixResult};
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Result as ActixResult};
+use futures::StreamExt;
 use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::app_domain::service::deletion_service::DeleteKind;
+use crate::app_domain::model::product::{ChangeContext, ProductError};
+use crate::app_domain::model::product_import::ImportFormat;
 use crate::application::dto::product_dto::{
-    BatchUpdateRequest, CreateProductRequest, ImageReorderRequest, InventoryRequest,
-    PatchProductRequest, PriceRequest, ProductErrorResponse, ProductHistoryQuery,
-    ProductImageRequest, ProductSearchQuery, UpdateProductRequest,
+    BatchUpdateRequest, BulkUpdateQuery, BulkUpdateRequest, CreateProductRequest,
+    CreateStatusScheduleRequest, CreateVariantRequest, DeleteProductQuery, ImageOrderRequest,
+    ImageReorderRequest, InventoryRequest, InventoryReservationRequest, PatchProductRequest,
+    PriceRequest, ProductDeletionResponse, ProductErrorResponse, ProductExportQuery,
+    ProductHistoryQuery, ProductImageRequest, ProductImportQuery, ProductSearchQuery,
+    RollbackRequest, SetBundleRequest, SetProductOptionsRequest, StatusTransitionRequest,
+    UpdateProductRequest, UpdateVariantRequest,
 };
-use crate::application::service::deletion_facade::DeletionFacade;
+use crate::application::service::product_export_service::ProductExportService;
+use crate::application::service::product_import_service::ProductImportService;
 use crate::application::service::product_service::ProductService;
 use crate::infrastructure::auth::middleware::KeycloakUser;
-use crate::infrastructure::error::AppError;
+use crate::infrastructure::config::BodyLimitConfig;
+use crate::presentation::api::body_limit::{json_config, StreamLimit};
+use crate::presentation::api::etag::{etag, if_match_version, PreconditionFailed};
+use crate::presentation::api::locale::{content_language, request_locale};
+
+/// 変更理由を受け取るリクエストヘッダー（履歴に記録される）
+const CHANGE_REASON_HEADER: &str = "X-Change-Reason";
 
 pub struct ProductHandler {
     service: Arc<ProductService>,
-    deletion_facade: Arc<DeletionFacade>,
+    import_service: Arc<ProductImportService>,
+    export_service: Arc<ProductExportService>,
 }
 
 impl ProductHandler {
-    pub fn new(service: Arc<ProductService>, deletion_facade: Arc<DeletionFacade>) -> Self {
+    pub fn new(
+        service: Arc<ProductService>,
+        import_service: Arc<ProductImportService>,
+        export_service: Arc<ProductExportService>,
+    ) -> Self {
         Self {
             service,
-            deletion_facade,
+            import_service,
+            export_service,
         }
     }
 
     // GET /api/products/{id}
     pub async fn get_product(
         data: web::Data<ProductHandler>,
+        req: HttpRequest,
         path: web::Path<String>,
     ) -> ActixResult<impl Responder> {
         let product_id = path.into_inner();
+        let locale = request_locale(&req, data.service.locales());
 
         info!("Fetching product {}", product_id);
 
         match data.service.find_by_id(&product_id).await {
-            Ok(product) => {
+            Ok(mut product) => {
                 info!("Successfully fetched product {}", product_id);
-                Ok(HttpResponse::Ok().json(product))
+                data.service
+                    .localize(std::slice::from_mut(&mut product), &locale)
+                    .await;
+                Ok(content_language(&mut HttpResponse::Ok(), &locale)
+                    .insert_header(etag(product.version))
+                    .json(product))
             }
             Err(error) => {
                 error!("Failed to fetch product {}: {}", product_id, error);
@@ -54,16 +81,21 @@ impl ProductHandler {
     // GET /api/products/sku/{sku}
     pub async fn get_product_by_sku(
         data: web::Data<ProductHandler>,
+        req: HttpRequest,
         path: web::Path<String>,
     ) -> ActixResult<impl Responder> {
         let sku = path.into_inner();
+        let locale = request_locale(&req, data.service.locales());
 
         info!("Fetching product by SKU {}", sku);
 
         match data.service.find_by_sku(&sku).await {
-            Ok(product) => {
+            Ok(mut product) => {
                 info!("Successfully fetched product by SKU {}", sku);
-                Ok(HttpResponse::Ok().json(product))
+                data.service
+                    .localize(std::slice::from_mut(&mut product), &locale)
+                    .await;
+                Ok(content_language(&mut HttpResponse::Ok(), &locale).json(product))
             }
             Err(error) => {
                 error!("Failed to fetch product by SKU {}: {}", sku, error);
@@ -79,41 +111,69 @@ impl ProductHandler {
     // GET /api/products (with search/filter capabilities)
     pub async fn search_products(
         data: web::Data<ProductHandler>,
+        req: HttpRequest,
         query: web::Query<ProductSearchQuery>,
     ) -> ActixResult<impl Responder> {
+        let locale = request_locale(&req, data.service.locales());
         info!("Searching products with query: {:?}", query.q);
 
-        let response = data.service.search(query.into_inner()).await;
-
-        info!("Found {} products", response.total);
-        Ok(HttpResponse::Ok().json(response))
+        match data.service.search(query.into_inner()).await {
+            Ok(mut response) => {
+                info!("Found {} products", response.total);
+                data.service.localize(&mut response.products, &locale).await;
+                Ok(content_language(&mut HttpResponse::Ok(), &locale).json(response))
+            }
+            Err(error) => {
+                error!("Failed to search products: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "INVALID_PRODUCT_FILTER" => Ok(HttpResponse::BadRequest().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
     }
 
     // POST /api/products
     pub async fn create_product(
         data: web::Data<ProductHandler>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<CreateProductRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         info!("Creating new product with SKU {}", request.sku);
 
-        match data.service.create(request.into_inner()).await {
+        match data.service.create(request.into_inner(), &ctx).await {
             Ok(product) => {
                 info!("Successfully created product {}", product.id);
-                Ok(HttpResponse::Created().json(product))
+                Ok(HttpResponse::Created()
+                    .insert_header(etag(product.version))
+                    .json(product))
             }
             Err(error) => {
                 error!("Failed to create product: {}", error);
                 let error_response: ProductErrorResponse = error.into();
                 match error_response.code.as_str() {
-                    "PRODUCT_SKU_DUPLICATE" => Ok(HttpResponse::Conflict().json(error_response)),
+                    "PRODUCT_SKU_DUPLICATE" | "PRODUCT_SLUG_DUPLICATE" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
                     "PRODUCT_INVALID_NAME"
                     | "PRODUCT_INVALID_SKU"
+                    | "PRODUCT_INVALID_SLUG"
+                    | "PRODUCT_SLUG_REQUIRED"
+                    | "PRODUCT_INVALID_ATTRIBUTES"
                     | "INVALID_PRICE_RANGE"
                     | "INVALID_INVENTORY_QUANTITY" => {
                         Ok(HttpResponse::BadRequest().json(error_response))
                     }
                     "CATEGORY_NOT_FOUND" => Ok(HttpResponse::BadRequest().json(error_response)),
+                    "ACTIVATION_REQUIREMENTS_NOT_MET" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    "INVALID_STATUS_TRANSITION" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
                     _ => Ok(HttpResponse::InternalServerError().json(error_response)),
                 }
             }
@@ -124,28 +184,48 @@ impl ProductHandler {
     pub async fn update_product(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<UpdateProductRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Updating product {}", product_id);
+        let expected_version = data.expected_version(&req, &product_id).await?;
 
-        match data.service.update(&product_id, request.into_inner()).await {
+        match data
+            .service
+            .update(&product_id, request.into_inner(), expected_version, &ctx)
+            .await
+        {
             Ok(product) => {
                 info!("Successfully updated product {}", product_id);
-                Ok(HttpResponse::Ok().json(product))
+                Ok(HttpResponse::Ok()
+                    .insert_header(etag(product.version))
+                    .json(product))
             }
             Err(error) => {
                 error!("Failed to update product {}: {}", product_id, error);
                 let error_response: ProductErrorResponse = error.into();
                 match error_response.code.as_str() {
                     "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
-                    "PRODUCT_SKU_DUPLICATE" => Ok(HttpResponse::Conflict().json(error_response)),
+                    "VERSION_MISMATCH" => {
+                        Ok(HttpResponse::PreconditionFailed().json(error_response))
+                    }
+                    "PRODUCT_SKU_DUPLICATE"
+                    | "PRODUCT_SLUG_DUPLICATE"
+                    | "INVALID_STATUS_TRANSITION" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
                     "PRODUCT_INVALID_NAME"
                     | "PRODUCT_INVALID_SKU"
+                    | "PRODUCT_INVALID_SLUG"
+                    | "PRODUCT_SLUG_REQUIRED"
+                    | "PRODUCT_INVALID_ATTRIBUTES"
                     | "INVALID_PRICE_RANGE"
-                    | "INVALID_INVENTORY_QUANTITY" => {
+                    | "INVALID_INVENTORY_QUANTITY"
+                    | "ACTIVATION_REQUIREMENTS_NOT_MET" => {
                         Ok(HttpResponse::BadRequest().json(error_response))
                     }
                     "CATEGORY_NOT_FOUND" => Ok(HttpResponse::BadRequest().json(error_response)),
@@ -159,24 +239,41 @@ impl ProductHandler {
     pub async fn patch_product(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<PatchProductRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Patching product {}", product_id);
+        let expected_version = data.expected_version(&req, &product_id).await?;
 
-        match data.service.patch(&product_id, request.into_inner()).await {
+        match data
+            .service
+            .patch(&product_id, request.into_inner(), expected_version, &ctx)
+            .await
+        {
             Ok(product) => {
                 info!("Successfully patched product {}", product_id);
-                Ok(HttpResponse::Ok().json(product))
+                Ok(HttpResponse::Ok()
+                    .insert_header(etag(product.version))
+                    .json(product))
             }
             Err(error) => {
                 error!("Failed to patch product {}: {}", product_id, error);
                 let error_response: ProductErrorResponse = error.into();
                 match error_response.code.as_str() {
                     "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
-                    "INVALID_PRICE_RANGE" | "INVALID_INVENTORY_QUANTITY" => {
+                    "VERSION_MISMATCH" => {
+                        Ok(HttpResponse::PreconditionFailed().json(error_response))
+                    }
+                    "INVALID_STATUS_TRANSITION" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
+                    "INVALID_PRICE_RANGE"
+                    | "INVALID_INVENTORY_QUANTITY"
+                    | "ACTIVATION_REQUIREMENTS_NOT_MET" => {
                         Ok(HttpResponse::BadRequest().json(error_response))
                     }
                     _ => Ok(HttpResponse::InternalServerError().json(error_response)),
@@ -185,40 +282,253 @@ impl ProductHandler {
         }
     }
 
+    // POST /api/products/{id}/publish
+    pub async fn publish_product(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        request: Option<web::Json<StatusTransitionRequest>>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+        let request = request.map(|r| r.into_inner()).unwrap_or_default();
+        let mut ctx = Self::change_context(&req, &user);
+        if request.reason.is_some() {
+            ctx.reason = request.reason;
+        }
+
+        info!("Publishing product {}", product_id);
+
+        match data.service.publish(&product_id, &ctx).await {
+            Ok(product) => {
+                info!("Successfully published product {}", product_id);
+                Ok(HttpResponse::Ok().json(product))
+            }
+            Err(error) => {
+                error!("Failed to publish product {}: {}", product_id, error);
+                Ok(Self::status_transition_error(error))
+            }
+        }
+    }
+
+    // POST /api/products/{id}/discontinue
+    pub async fn discontinue_product(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        request: Option<web::Json<StatusTransitionRequest>>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+        let request = request.map(|r| r.into_inner()).unwrap_or_default();
+        let mut ctx = Self::change_context(&req, &user);
+        if request.reason.is_some() {
+            ctx.reason = request.reason;
+        }
+
+        info!("Discontinuing product {}", product_id);
+
+        match data.service.discontinue(&product_id, &ctx).await {
+            Ok(product) => {
+                info!("Successfully discontinued product {}", product_id);
+                Ok(HttpResponse::Ok().json(product))
+            }
+            Err(error) => {
+                error!("Failed to discontinue product {}: {}", product_id, error);
+                Ok(Self::status_transition_error(error))
+            }
+        }
+    }
+
+    // GET /api/products/{id}/schedules
+    pub async fn list_product_schedules(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        match data.service.list_schedules(&product_id).await {
+            Ok(schedules) => Ok(HttpResponse::Ok().json(schedules)),
+            Err(error) => {
+                error!(
+                    "Failed to list schedules for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/{id}/schedules
+    pub async fn create_product_schedule(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        user: KeycloakUser,
+        request: web::Json<CreateStatusScheduleRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Creating status schedule for product {}", product_id);
+
+        match data
+            .service
+            .create_schedule(
+                &product_id,
+                request.into_inner(),
+                Some(user.claims.preferred_username.as_str()),
+            )
+            .await
+        {
+            Ok(schedule) => {
+                info!(
+                    "Successfully created schedule {} for product {}",
+                    schedule.id, product_id
+                );
+                Ok(HttpResponse::Created().json(schedule))
+            }
+            Err(error) => {
+                error!(
+                    "Failed to create schedule for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_SCHEDULE" => Ok(HttpResponse::BadRequest().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // DELETE /api/products/{id}/schedules/{schedule_id}
+    pub async fn cancel_product_schedule(
+        data: web::Data<ProductHandler>,
+        path: web::Path<(String, String)>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let (product_id, schedule_id) = path.into_inner();
+
+        info!(
+            "Cancelling schedule {} for product {}",
+            schedule_id, product_id
+        );
+
+        match data
+            .service
+            .cancel_schedule(&product_id, &schedule_id)
+            .await
+        {
+            Ok(_) => Ok(HttpResponse::NoContent().finish()),
+            Err(error) => {
+                error!(
+                    "Failed to cancel schedule {} for product {}: {}",
+                    schedule_id, product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "SCHEDULE_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // 認証ユーザーと X-Change-Reason ヘッダーから履歴用のコンテキストを組み立てる
+    /// If-Match ヘッダーから照合するバージョンを決める（タグが複数ある場合は現在のバージョンを調べる）
+    async fn expected_version(
+        &self,
+        req: &HttpRequest,
+        product_id: &str,
+    ) -> Result<Option<i64>, PreconditionFailed> {
+        if_match_version(req, || async {
+            self.service
+                .find_by_id(product_id)
+                .await
+                .ok()
+                .map(|product| product.version)
+        })
+        .await
+    }
+
+    pub(crate) fn change_context(req: &HttpRequest, user: &KeycloakUser) -> ChangeContext {
+        let reason = req
+            .headers()
+            .get(CHANGE_REASON_HEADER)
+            .and_then(|value| std::str::from_utf8(value.as_bytes()).ok())
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string);
+
+        ChangeContext::new(Some(user.claims.preferred_username.clone()), reason)
+    }
+
+    fn status_transition_error(error: ProductError) -> HttpResponse {
+        let error_response: ProductErrorResponse = error.into();
+        match error_response.code.as_str() {
+            "PRODUCT_NOT_FOUND" => HttpResponse::NotFound().json(error_response),
+            "INVALID_STATUS_TRANSITION" => HttpResponse::Conflict().json(error_response),
+            "ACTIVATION_REQUIREMENTS_NOT_MET" => HttpResponse::BadRequest().json(error_response),
+            _ => HttpResponse::InternalServerError().json(error_response),
+        }
+    }
+
     // DELETE /api/products/{id}
     pub async fn delete_product(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        query: web::Query<DeleteProductQuery>,
+        req: HttpRequest,
+        user: KeycloakUser,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
+        let logical = query.logical.unwrap_or(false);
 
         info!("Deleting product {}", product_id);
+        let expected_version = data.expected_version(&req, &product_id).await?;
 
         match data
-            .deletion_facade
-            .delete_product(product_id.clone(), DeleteKind::Physical)
+            .service
+            .delete(&product_id, logical, expected_version, &ctx)
             .await
         {
+            Ok(used_in_bundles) if logical => {
+                info!("Successfully discontinued product {}", product_id);
+                // セット商品の構成品は論理削除できるが、呼び出し元に警告を返す
+                let warnings = if used_in_bundles.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![format!(
+                        "商品はセット商品の構成品として使用されています: {}",
+                        used_in_bundles.join(", ")
+                    )]
+                };
+                Ok(HttpResponse::Ok().json(ProductDeletionResponse {
+                    id: product_id,
+                    used_in_bundles,
+                    warnings,
+                }))
+            }
             Ok(_) => {
                 info!("Successfully deleted product {}", product_id);
                 Ok(HttpResponse::NoContent().finish())
             }
             Err(error) => {
                 error!("Failed to delete product {}: {}", product_id, error);
-                match error {
-                    AppError::NotFound(_) => Ok(HttpResponse::NotFound().json(serde_json::json!({
-                        "error": {
-                            "code": "PRODUCT_NOT_FOUND",
-                            "message": "商品が見つかりません"
-                        }
-                    }))),
-                    _ => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                        "error": {
-                            "code": "INTERNAL_SERVER_ERROR",
-                            "message": "削除処理中にエラーが発生しました"
-                        }
-                    }))),
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "VERSION_MISMATCH" => {
+                        Ok(HttpResponse::PreconditionFailed().json(error_response))
+                    }
+                    "PRODUCT_USED_IN_BUNDLE" => Ok(HttpResponse::Conflict().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
                 }
             }
         }
@@ -228,16 +538,18 @@ impl ProductHandler {
     pub async fn update_product_price(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<PriceRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Updating price for product {}", product_id);
 
         match data
             .service
-            .update_price(&product_id, request.into_inner())
+            .update_price(&product_id, request.into_inner(), &ctx)
             .await
         {
             Ok(price) => {
@@ -263,16 +575,18 @@ impl ProductHandler {
     pub async fn update_product_inventory(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<InventoryRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Updating inventory for product {}", product_id);
 
         match data
             .service
-            .update_inventory(&product_id, request.into_inner())
+            .update_inventory(&product_id, request.into_inner(), &ctx)
             .await
         {
             Ok(inventory) => {
@@ -300,16 +614,18 @@ impl ProductHandler {
     pub async fn add_product_image(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<ProductImageRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Adding image to product {}", product_id);
 
         match data
             .service
-            .add_image(&product_id, request.into_inner())
+            .add_image(&product_id, request.into_inner(), &ctx)
             .await
         {
             Ok(image) => {
@@ -335,16 +651,18 @@ impl ProductHandler {
     pub async fn update_product_image(
         data: web::Data<ProductHandler>,
         path: web::Path<(String, String)>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<ProductImageRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let (product_id, image_id) = path.into_inner();
 
         info!("Updating image {} for product {}", image_id, product_id);
 
         match data
             .service
-            .update_image(&product_id, &image_id, request.into_inner())
+            .update_image(&product_id, &image_id, request.into_inner(), &ctx)
             .await
         {
             Ok(image) => {
@@ -373,13 +691,19 @@ impl ProductHandler {
     pub async fn delete_product_image(
         data: web::Data<ProductHandler>,
         path: web::Path<(String, String)>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let (product_id, image_id) = path.into_inner();
 
         info!("Deleting image {} from product {}", image_id, product_id);
 
-        match data.service.delete_image(&product_id, &image_id).await {
+        match data
+            .service
+            .delete_image(&product_id, &image_id, &ctx)
+            .await
+        {
             Ok(_) => {
                 info!(
                     "Successfully deleted image {} from product {}",
@@ -406,16 +730,18 @@ impl ProductHandler {
     pub async fn reorder_product_images(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
         request: web::Json<ImageReorderRequest>,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let product_id = path.into_inner();
 
         info!("Reordering images for product {}", product_id);
 
         match data
             .service
-            .reorder_images(&product_id, request.into_inner())
+            .reorder_images(&product_id, request.into_inner(), &ctx)
             .await
         {
             Ok(_) => {
@@ -438,17 +764,61 @@ impl ProductHandler {
         }
     }
 
+    // PUT /api/products/{id}/images/order
+    pub async fn set_product_image_order(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        request: web::Json<ImageOrderRequest>,
+    ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
+        let product_id = path.into_inner();
+
+        info!("Setting image order for product {}", product_id);
+
+        match data
+            .service
+            .set_image_order(&product_id, request.into_inner(), &ctx)
+            .await
+        {
+            Ok(_) => {
+                info!("Successfully set image order for product {}", product_id);
+                Ok(HttpResponse::Ok()
+                    .json(serde_json::json!({"message": "Image order updated successfully"})))
+            }
+            Err(error) => {
+                error!(
+                    "Failed to set image order for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_IMAGE_ORDER" => Ok(HttpResponse::BadRequest().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
     // PUT /api/products/{id}/images/{image_id}/main
     pub async fn set_main_product_image(
         data: web::Data<ProductHandler>,
         path: web::Path<(String, String)>,
-        _user: KeycloakUser,
+        req: HttpRequest,
+        user: KeycloakUser,
     ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
         let (product_id, image_id) = path.into_inner();
 
         info!("Setting main image {} for product {}", image_id, product_id);
 
-        match data.service.set_main_image(&product_id, &image_id).await {
+        match data
+            .service
+            .set_main_image(&product_id, &image_id, &ctx)
+            .await
+        {
             Ok(_) => {
                 info!(
                     "Successfully set main image {} for product {}",
@@ -472,92 +842,697 @@ impl ProductHandler {
         }
     }
 
-    // PUT /api/products/batch
-    pub async fn batch_update_products(
+    // GET /api/products/{id}/options
+    pub async fn get_product_options(
         data: web::Data<ProductHandler>,
-        _user: KeycloakUser,
-        request: web::Json<BatchUpdateRequest>,
+        path: web::Path<String>,
     ) -> ActixResult<impl Responder> {
-        let update_count = request.updates.len();
-        info!("Batch updating {} products", update_count);
+        let product_id = path.into_inner();
 
-        match data.service.batch_update(request.into_inner()).await {
-            Ok(response) => {
-                info!(
-                    "Batch update completed: {} success, {} errors",
-                    response.success_count, response.error_count
-                );
-                Ok(HttpResponse::Ok().json(response))
-            }
+        info!("Fetching options for product {}", product_id);
+
+        match data.service.get_options(&product_id).await {
+            Ok(options) => Ok(HttpResponse::Ok().json(serde_json::json!({ "options": options }))),
             Err(error) => {
-                error!("Failed to perform batch update: {}", error);
+                error!(
+                    "Failed to fetch options for product {}: {}",
+                    product_id, error
+                );
                 let error_response: ProductErrorResponse = error.into();
-                Ok(HttpResponse::InternalServerError().json(error_response))
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
             }
         }
     }
 
-    // GET /api/products/{id}/history
-    pub async fn get_product_history(
+    // PUT /api/products/{id}/options
+    pub async fn set_product_options(
         data: web::Data<ProductHandler>,
         path: web::Path<String>,
-        query: web::Query<ProductHistoryQuery>,
         _user: KeycloakUser,
+        request: web::Json<SetProductOptionsRequest>,
     ) -> ActixResult<impl Responder> {
         let product_id = path.into_inner();
 
-        info!("Fetching history for product {}", product_id);
+        info!("Setting options for product {}", product_id);
 
         match data
             .service
-            .get_history(&product_id, query.into_inner())
+            .set_options(&product_id, request.into_inner())
             .await
         {
-            Ok(history) => {
-                info!(
-                    "Successfully fetched {} history items for product {}",
-                    history.total, product_id
-                );
-                Ok(HttpResponse::Ok().json(history))
+            Ok(options) => {
+                info!("Successfully set options for product {}", product_id);
+                Ok(HttpResponse::Ok().json(serde_json::json!({ "options": options })))
             }
             Err(error) => {
                 error!(
-                    "Failed to fetch history for product {}: {}",
+                    "Failed to set options for product {}: {}",
                     product_id, error
                 );
                 let error_response: ProductErrorResponse = error.into();
                 match error_response.code.as_str() {
                     "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_VARIANT_OPTIONS" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
                     _ => Ok(HttpResponse::InternalServerError().json(error_response)),
                 }
             }
         }
     }
 
-    // GET /api/products/reports/low-stock
-    pub async fn get_low_stock_products(
+    // GET /api/products/{id}/variants
+    pub async fn list_product_variants(
         data: web::Data<ProductHandler>,
-        query: web::Query<LowStockQuery>,
-        _user: KeycloakUser,
+        path: web::Path<String>,
     ) -> ActixResult<impl Responder> {
-        let threshold = query.threshold;
-
-        info!(
-            "Fetching low stock products with threshold: {:?}",
-            threshold
-        );
-
-        let products = data.service.find_low_stock_products(threshold).await;
+        let product_id = path.into_inner();
 
-        info!("Found {} low stock products", products.len());
-        Ok(HttpResponse::Ok().json(serde_json::json!({
-            "products": products,
-            "total": products.len()
-        })))
-    }
+        info!("Fetching variants for product {}", product_id);
 
-    // GET /api/products/reports/out-of-stock
-    pub async fn get_out_of_stock_products(
+        match data.service.list_variants(&product_id).await {
+            Ok(variants) => Ok(HttpResponse::Ok().json(variants)),
+            Err(error) => {
+                error!(
+                    "Failed to fetch variants for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/{id}/variants
+    pub async fn create_product_variant(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+        request: web::Json<CreateVariantRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!(
+            "Creating variant {} for product {}",
+            request.sku, product_id
+        );
+
+        match data
+            .service
+            .create_variant(&product_id, request.into_inner())
+            .await
+        {
+            Ok(variant) => {
+                info!(
+                    "Successfully created variant {} for product {}",
+                    variant.id, product_id
+                );
+                Ok(HttpResponse::Created().json(variant))
+            }
+            Err(error) => {
+                error!(
+                    "Failed to create variant for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "PRODUCT_SKU_DUPLICATE" | "VARIANT_COMBINATION_DUPLICATE" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
+                    "PRODUCT_INVALID_SKU"
+                    | "INVALID_PRICE_RANGE"
+                    | "INVALID_INVENTORY_QUANTITY"
+                    | "INVALID_VARIANT_OPTIONS" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // PUT /api/products/{id}/variants/{variant_id}
+    pub async fn update_product_variant(
+        data: web::Data<ProductHandler>,
+        path: web::Path<(String, String)>,
+        _user: KeycloakUser,
+        request: web::Json<UpdateVariantRequest>,
+    ) -> ActixResult<impl Responder> {
+        let (product_id, variant_id) = path.into_inner();
+
+        info!("Updating variant {} for product {}", variant_id, product_id);
+
+        match data
+            .service
+            .update_variant(&product_id, &variant_id, request.into_inner())
+            .await
+        {
+            Ok(variant) => {
+                info!(
+                    "Successfully updated variant {} for product {}",
+                    variant_id, product_id
+                );
+                Ok(HttpResponse::Ok().json(variant))
+            }
+            Err(error) => {
+                error!(
+                    "Failed to update variant {} for product {}: {}",
+                    variant_id, product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" | "VARIANT_NOT_FOUND" => {
+                        Ok(HttpResponse::NotFound().json(error_response))
+                    }
+                    "PRODUCT_SKU_DUPLICATE" | "VARIANT_COMBINATION_DUPLICATE" => {
+                        Ok(HttpResponse::Conflict().json(error_response))
+                    }
+                    "PRODUCT_INVALID_SKU"
+                    | "INVALID_PRICE_RANGE"
+                    | "INVALID_INVENTORY_QUANTITY"
+                    | "INVALID_VARIANT_OPTIONS" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // DELETE /api/products/{id}/variants/{variant_id}
+    pub async fn delete_product_variant(
+        data: web::Data<ProductHandler>,
+        path: web::Path<(String, String)>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let (product_id, variant_id) = path.into_inner();
+
+        info!(
+            "Deleting variant {} from product {}",
+            variant_id, product_id
+        );
+
+        match data.service.delete_variant(&product_id, &variant_id).await {
+            Ok(_) => {
+                info!(
+                    "Successfully deleted variant {} from product {}",
+                    variant_id, product_id
+                );
+                Ok(HttpResponse::NoContent().finish())
+            }
+            Err(error) => {
+                error!(
+                    "Failed to delete variant {} from product {}: {}",
+                    variant_id, product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "VARIANT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // GET /api/products/{id}/bundle
+    pub async fn get_product_bundle(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Getting bundle for product {}", product_id);
+
+        match data.service.get_bundle(&product_id).await {
+            Ok(bundle) => Ok(HttpResponse::Ok().json(bundle)),
+            Err(error) => {
+                error!("Failed to get bundle for product {}: {}", product_id, error);
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" | "BUNDLE_NOT_FOUND" => {
+                        Ok(HttpResponse::NotFound().json(error_response))
+                    }
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // PUT /api/products/{id}/bundle
+    pub async fn set_product_bundle(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+        request: web::Json<SetBundleRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Setting bundle for product {}", product_id);
+
+        match data
+            .service
+            .set_bundle(&product_id, request.into_inner())
+            .await
+        {
+            Ok(bundle) => {
+                info!("Successfully set bundle for product {}", product_id);
+                Ok(HttpResponse::Ok().json(bundle))
+            }
+            Err(error) => {
+                error!("Failed to set bundle for product {}: {}", product_id, error);
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_BUNDLE" => Ok(HttpResponse::BadRequest().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // DELETE /api/products/{id}/bundle
+    pub async fn delete_product_bundle(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Removing bundle from product {}", product_id);
+
+        match data.service.remove_bundle(&product_id).await {
+            Ok(_) => {
+                info!("Successfully removed bundle from product {}", product_id);
+                Ok(HttpResponse::NoContent().finish())
+            }
+            Err(error) => {
+                error!(
+                    "Failed to remove bundle from product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "BUNDLE_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/{id}/inventory/reserve
+    pub async fn reserve_product_inventory(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+        request: web::Json<InventoryReservationRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+        let quantity = request.into_inner().quantity;
+
+        info!("Reserving {} units of product {}", quantity, product_id);
+
+        match data.service.reserve_inventory(&product_id, quantity).await {
+            Ok(reservation) => Ok(HttpResponse::Ok().json(reservation)),
+            Err(error) => {
+                error!(
+                    "Failed to reserve inventory for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_INVENTORY_QUANTITY" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    "INSUFFICIENT_INVENTORY" => Ok(HttpResponse::Conflict().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/{id}/inventory/release
+    pub async fn release_product_inventory(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+        request: web::Json<InventoryReservationRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+        let quantity = request.into_inner().quantity;
+
+        info!("Releasing {} units of product {}", quantity, product_id);
+
+        match data.service.release_inventory(&product_id, quantity).await {
+            Ok(reservation) => Ok(HttpResponse::Ok().json(reservation)),
+            Err(error) => {
+                error!(
+                    "Failed to release inventory for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    "INVALID_INVENTORY_QUANTITY" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // PUT /api/products/batch
+    pub async fn batch_update_products(
+        data: web::Data<ProductHandler>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        request: web::Json<BatchUpdateRequest>,
+    ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
+        let update_count = request.updates.len();
+        info!("Batch updating {} products", update_count);
+
+        match data.service.batch_update(request.into_inner(), &ctx).await {
+            Ok(response) => {
+                info!(
+                    "Batch update completed: {} success, {} errors",
+                    response.success_count, response.error_count
+                );
+                Ok(HttpResponse::Ok().json(response))
+            }
+            Err(error) => {
+                error!("Failed to perform batch update: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                Ok(HttpResponse::InternalServerError().json(error_response))
+            }
+        }
+    }
+
+    // POST /api/products/bulk-update
+    pub async fn bulk_update_products(
+        data: web::Data<ProductHandler>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        query: web::Query<BulkUpdateQuery>,
+        request: web::Json<BulkUpdateRequest>,
+    ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
+        let query = query.into_inner();
+
+        let result = if query.preview {
+            info!("Previewing bulk product update");
+            data.service
+                .preview_bulk_update(request.into_inner(), query.sample_size)
+                .await
+                .map(|preview| HttpResponse::Ok().json(preview))
+        } else {
+            info!("Running bulk product update");
+            data.service
+                .bulk_update(request.into_inner(), &ctx)
+                .await
+                .map(|response| HttpResponse::Ok().json(response))
+        };
+
+        match result {
+            Ok(response) => Ok(response),
+            Err(error) => {
+                error!("Failed to perform bulk update: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "INVALID_BULK_UPDATE" | "INVALID_PRODUCT_FILTER" => {
+                        Ok(HttpResponse::BadRequest().json(error_response))
+                    }
+                    "BULK_UPDATE_COUNT_MISMATCH" => {
+                        Ok(HttpResponse::PreconditionFailed().json(error_response))
+                    }
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/import
+    pub async fn import_products(
+        data: web::Data<ProductHandler>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        query: web::Query<ProductImportQuery>,
+        mut payload: web::Payload,
+    ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
+        let query = query.into_inner();
+        let format = query.format.unwrap_or_else(|| Self::import_format(&req));
+        let limit = StreamLimit::of(&req);
+        limit.check_declared(&req)?;
+
+        // ボディ全体を保持せず、受信したチャンクごとに解析する
+        let mut parser = data.import_service.parser(format);
+        let mut received = 0;
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk?;
+            received += chunk.len();
+            limit.check_received(received)?;
+            if let Err(error) = parser.feed(&chunk) {
+                error!("Rejected product import file: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                return Ok(HttpResponse::BadRequest().json(error_response));
+            }
+        }
+        let parsed = match parser.finish() {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                error!("Rejected product import file: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                return Ok(HttpResponse::BadRequest().json(error_response));
+            }
+        };
+
+        if query.dry_run {
+            info!("Validating {} import rows (dry run)", parsed.total_rows);
+            let report = data.import_service.dry_run(parsed).await;
+            return Ok(HttpResponse::Ok().json(report));
+        }
+
+        info!("Starting import of {} rows", parsed.total_rows);
+        let job = data.import_service.start(parsed, ctx);
+        Ok(HttpResponse::Accepted()
+            .insert_header((
+                header::LOCATION,
+                format!("/api/products/import/jobs/{}", job.id),
+            ))
+            .json(job))
+    }
+
+    // GET /api/products/import/jobs/{job_id}
+    pub async fn get_import_job(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let job_id = path.into_inner();
+
+        match data.import_service.get_job(&job_id) {
+            Ok(job) => Ok(HttpResponse::Ok().json(job)),
+            Err(error) => {
+                let error_response: ProductErrorResponse = error.into();
+                Ok(HttpResponse::NotFound().json(error_response))
+            }
+        }
+    }
+
+    // GET /api/products/export
+    pub async fn export_products(
+        data: web::Data<ProductHandler>,
+        _user: KeycloakUser,
+        query: web::Query<ProductExportQuery>,
+    ) -> ActixResult<impl Responder> {
+        let export = match data.export_service.export(query.into_inner()) {
+            Ok(export) => export,
+            Err(error) => {
+                error!("Rejected product export: {}", error);
+                let error_response: ProductErrorResponse = error.into();
+                return Ok(HttpResponse::BadRequest().json(error_response));
+            }
+        };
+
+        // 書き出し途中のエラーはステータスを変更できないため、接続を切って通知する
+        let body = export.body.map(|chunk| {
+            chunk
+                .map(web::Bytes::from)
+                .map_err(|e| std::io::Error::other(e.to_string()))
+        });
+
+        Ok(HttpResponse::Ok()
+            .content_type(export.format.content_type())
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"products-{}.{}\"",
+                    chrono::Utc::now().format("%Y%m%d%H%M%S"),
+                    export.format.extension()
+                ),
+            ))
+            .streaming(body))
+    }
+
+    fn import_format(req: &HttpRequest) -> ImportFormat {
+        let is_tsv = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/tab-separated-values"));
+
+        if is_tsv {
+            ImportFormat::Tsv
+        } else {
+            ImportFormat::Csv
+        }
+    }
+
+    // GET /api/products/{id}/history
+    pub async fn get_product_history(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        query: web::Query<ProductHistoryQuery>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Fetching history for product {}", product_id);
+
+        match data
+            .service
+            .get_history(&product_id, query.into_inner())
+            .await
+        {
+            Ok(history) => {
+                info!(
+                    "Successfully fetched {} history items for product {}",
+                    history.total, product_id
+                );
+                Ok(HttpResponse::Ok().json(history))
+            }
+            Err(error) => {
+                error!(
+                    "Failed to fetch history for product {}: {}",
+                    product_id, error
+                );
+                let error_response: ProductErrorResponse = error.into();
+                match error_response.code.as_str() {
+                    "PRODUCT_NOT_FOUND" => Ok(HttpResponse::NotFound().json(error_response)),
+                    _ => Ok(HttpResponse::InternalServerError().json(error_response)),
+                }
+            }
+        }
+    }
+
+    // POST /api/products/{id}/rollback/preview
+    pub async fn preview_product_rollback(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        _user: KeycloakUser,
+        request: web::Json<RollbackRequest>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        info!("Previewing rollback for product {}", product_id);
+
+        match data
+            .service
+            .preview_rollback(&product_id, request.into_inner())
+            .await
+        {
+            Ok(preview) => Ok(HttpResponse::Ok().json(preview)),
+            Err(error) => {
+                error!(
+                    "Failed to preview rollback for product {}: {}",
+                    product_id, error
+                );
+                Ok(Self::rollback_error(error))
+            }
+        }
+    }
+
+    // POST /api/products/{id}/rollback
+    pub async fn rollback_product(
+        data: web::Data<ProductHandler>,
+        path: web::Path<String>,
+        req: HttpRequest,
+        user: KeycloakUser,
+        request: web::Json<RollbackRequest>,
+    ) -> ActixResult<impl Responder> {
+        let ctx = Self::change_context(&req, &user);
+        let product_id = path.into_inner();
+
+        info!("Rolling back product {}", product_id);
+
+        match data
+            .service
+            .rollback(&product_id, request.into_inner(), &ctx)
+            .await
+        {
+            Ok(product) => {
+                info!("Successfully rolled back product {}", product_id);
+                Ok(HttpResponse::Ok().json(product))
+            }
+            Err(error) => {
+                error!("Failed to roll back product {}: {}", product_id, error);
+                Ok(Self::rollback_error(error))
+            }
+        }
+    }
+
+    fn rollback_error(error: ProductError) -> HttpResponse {
+        let error_response: ProductErrorResponse = error.into();
+        match error_response.code.as_str() {
+            "PRODUCT_NOT_FOUND" | "HISTORY_NOT_FOUND" => {
+                HttpResponse::NotFound().json(error_response)
+            }
+            "PRODUCT_SKU_DUPLICATE" | "PRODUCT_SLUG_DUPLICATE" => {
+                HttpResponse::Conflict().json(error_response)
+            }
+            "INVALID_ROLLBACK_TARGET"
+            | "PRODUCT_INVALID_NAME"
+            | "PRODUCT_INVALID_SKU"
+            | "PRODUCT_INVALID_SLUG"
+            | "PRODUCT_SLUG_REQUIRED"
+            | "INVALID_PRICE_RANGE" => HttpResponse::BadRequest().json(error_response),
+            _ => HttpResponse::InternalServerError().json(error_response),
+        }
+    }
+
+    // GET /api/products/reports/low-stock
+    pub async fn get_low_stock_products(
+        data: web::Data<ProductHandler>,
+        query: web::Query<LowStockQuery>,
+        _user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        let threshold = query.threshold;
+
+        info!(
+            "Fetching low stock products with threshold: {:?}",
+            threshold
+        );
+
+        let products = data.service.find_low_stock_products(threshold).await;
+
+        info!("Found {} low stock products", products.len());
+        Ok(HttpResponse::Ok().json(serde_json::json!({
+            "products": products,
+            "total": products.len()
+        })))
+    }
+
+    // GET /api/products/reports/out-of-stock
+    pub async fn get_out_of_stock_products(
         data: web::Data<ProductHandler>,
         _user: KeycloakUser,
     ) -> ActixResult<impl Responder> {
@@ -580,11 +1555,28 @@ pub struct LowStockQuery {
 }
 
 // Product configuration function to register all routes
-pub fn configure_product_routes(cfg: &mut web::ServiceConfig) {
+pub fn configure_product_routes(cfg: &mut web::ServiceConfig, limits: &BodyLimitConfig) {
     cfg.service(
         web::scope("/products")
             .route("", web::get().to(ProductHandler::search_products))
             .route("", web::post().to(ProductHandler::create_product))
+            .route("/export", web::get().to(ProductHandler::export_products))
+            // Batch operations (larger body limits, registered before "/{id}")
+            .service(
+                web::resource("/batch")
+                    .app_data(json_config(limits.batch_bytes))
+                    .route(web::put().to(ProductHandler::batch_update_products)),
+            )
+            .service(
+                web::resource("/bulk-update")
+                    .app_data(json_config(limits.batch_bytes))
+                    .route(web::post().to(ProductHandler::bulk_update_products)),
+            )
+            .service(
+                web::resource("/import")
+                    .app_data(StreamLimit(limits.import_bytes))
+                    .route(web::post().to(ProductHandler::import_products)),
+            )
             .route("/{id}", web::get().to(ProductHandler::get_product))
             .route("/{id}", web::put().to(ProductHandler::update_product))
             .route("/{id}", web::patch().to(ProductHandler::patch_product))
@@ -609,6 +1601,15 @@ pub fn configure_product_routes(cfg: &mut web::ServiceConfig) {
                 "/{id}/images",
                 web::post().to(ProductHandler::add_product_image),
             )
+            // Fixed paths must come before /{id}/images/{image_id} or they are matched as an image id
+            .route(
+                "/{id}/images/reorder",
+                web::put().to(ProductHandler::reorder_product_images),
+            )
+            .route(
+                "/{id}/images/order",
+                web::put().to(ProductHandler::set_product_image_order),
+            )
             .route(
                 "/{id}/images/{image_id}",
                 web::put().to(ProductHandler::update_product_image),
@@ -617,24 +1618,94 @@ pub fn configure_product_routes(cfg: &mut web::ServiceConfig) {
                 "/{id}/images/{image_id}",
                 web::delete().to(ProductHandler::delete_product_image),
             )
-            .route(
-                "/{id}/images/reorder",
-                web::put().to(ProductHandler::reorder_product_images),
-            )
             .route(
                 "/{id}/images/{image_id}/main",
                 web::put().to(ProductHandler::set_main_product_image),
             )
-            // Batch operations
+            // Lifecycle operations
+            .route(
+                "/{id}/publish",
+                web::post().to(ProductHandler::publish_product),
+            )
+            .route(
+                "/{id}/discontinue",
+                web::post().to(ProductHandler::discontinue_product),
+            )
+            .route(
+                "/{id}/schedules",
+                web::get().to(ProductHandler::list_product_schedules),
+            )
+            .route(
+                "/{id}/schedules",
+                web::post().to(ProductHandler::create_product_schedule),
+            )
+            .route(
+                "/{id}/schedules/{schedule_id}",
+                web::delete().to(ProductHandler::cancel_product_schedule),
+            )
+            // Variant operations
+            .route(
+                "/{id}/options",
+                web::get().to(ProductHandler::get_product_options),
+            )
+            .route(
+                "/{id}/options",
+                web::put().to(ProductHandler::set_product_options),
+            )
+            .route(
+                "/{id}/variants",
+                web::get().to(ProductHandler::list_product_variants),
+            )
             .route(
-                "/batch",
-                web::put().to(ProductHandler::batch_update_products),
+                "/{id}/variants",
+                web::post().to(ProductHandler::create_product_variant),
+            )
+            .route(
+                "/{id}/variants/{variant_id}",
+                web::put().to(ProductHandler::update_product_variant),
+            )
+            .route(
+                "/{id}/variants/{variant_id}",
+                web::delete().to(ProductHandler::delete_product_variant),
+            )
+            // Bundle operations
+            .route(
+                "/{id}/bundle",
+                web::get().to(ProductHandler::get_product_bundle),
+            )
+            .route(
+                "/{id}/bundle",
+                web::put().to(ProductHandler::set_product_bundle),
+            )
+            .route(
+                "/{id}/bundle",
+                web::delete().to(ProductHandler::delete_product_bundle),
+            )
+            .route(
+                "/{id}/inventory/reserve",
+                web::post().to(ProductHandler::reserve_product_inventory),
+            )
+            .route(
+                "/{id}/inventory/release",
+                web::post().to(ProductHandler::release_product_inventory),
+            )
+            .route(
+                "/import/jobs/{job_id}",
+                web::get().to(ProductHandler::get_import_job),
             )
             // History
             .route(
                 "/{id}/history",
                 web::get().to(ProductHandler::get_product_history),
             )
+            .route(
+                "/{id}/rollback/preview",
+                web::post().to(ProductHandler::preview_product_rollback),
+            )
+            .route(
+                "/{id}/rollback",
+                web::post().to(ProductHandler::rollback_product),
+            )
             // Reports
             .route(
                 "/reports/low-stock",
@@ -0,0 +1,37 @@
+use actix_web::http::header;
+use actix_web::{web, HttpResponse, Responder, Result as ActixResult};
+use std::sync::Arc;
+
+use crate::application::dto::slug_dto::ResolveSlugQuery;
+use crate::application::service::slug_service::SlugService;
+
+pub struct SlugHandler {
+    service: Arc<SlugService>,
+}
+
+impl SlugHandler {
+    pub fn new(service: Arc<SlugService>) -> Self {
+        Self { service }
+    }
+
+    // GET /api/slugs/resolve?path=...
+    //
+    // 旧スラッグなど正規でないパスは 301 で正規のパスの解決先へ転送する（本文には解決結果を含める）
+    pub async fn resolve(
+        data: web::Data<SlugHandler>,
+        query: web::Query<ResolveSlugQuery>,
+    ) -> ActixResult<impl Responder> {
+        let resolution = data.service.resolve(&query.path).await?;
+        if resolution.redirect {
+            let location = format!("/api/slugs/resolve?path={}", resolution.canonical_path);
+            return Ok(HttpResponse::MovedPermanently()
+                .insert_header((header::LOCATION, location))
+                .json(resolution));
+        }
+        Ok(HttpResponse::Ok().json(resolution))
+    }
+}
+
+pub fn configure_slug_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/slugs/resolve", web::get().to(SlugHandler::resolve));
+}
@@ -0,0 +1,284 @@
+use actix_web::{web, HttpResponse, Responder, Result as ActixResult};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::app_domain::model::category::CategoryError;
+use crate::app_domain::model::product::ProductError;
+use crate::application::dto::category_dto::CategoryErrorResponse;
+use crate::application::dto::product_dto::ProductErrorResponse;
+use crate::application::dto::translation_dto::{
+    CategoryTranslationRequest, LocaleMissingTranslations, MissingTranslationsQuery,
+    MissingTranslationsResponse, ProductTranslationRequest, TranslatableEntity,
+};
+use crate::application::service::category_service::CategoryService;
+use crate::application::service::product_service::ProductService;
+use crate::infrastructure::auth::middleware::KeycloakUser;
+
+/// 翻訳の不足レポートで1回に返す件数の既定値と上限（ロケール・種類ごと）
+const DEFAULT_MISSING_LIMIT: i64 = 50;
+const MAX_MISSING_LIMIT: i64 = 500;
+
+pub struct TranslationHandler {
+    product_service: Arc<ProductService>,
+    category_service: Arc<CategoryService>,
+}
+
+impl TranslationHandler {
+    pub fn new(
+        product_service: Arc<ProductService>,
+        category_service: Arc<CategoryService>,
+    ) -> Self {
+        Self {
+            product_service,
+            category_service,
+        }
+    }
+
+    fn product_error(error: ProductError) -> HttpResponse {
+        let error_response: ProductErrorResponse = error.into();
+        match error_response.code.as_str() {
+            "PRODUCT_NOT_FOUND" | "PRODUCT_TRANSLATION_NOT_FOUND" => {
+                HttpResponse::NotFound().json(error_response)
+            }
+            "PRODUCT_INVALID_TRANSLATION" => HttpResponse::BadRequest().json(error_response),
+            _ => HttpResponse::InternalServerError().json(error_response),
+        }
+    }
+
+    fn category_error(error: CategoryError) -> HttpResponse {
+        let error_response: CategoryErrorResponse = error.into();
+        match error_response.code.as_str() {
+            "CATEGORY_NOT_FOUND" => HttpResponse::NotFound().json(error_response),
+            "CATEGORY_INVALID_TRANSLATION" => HttpResponse::BadRequest().json(error_response),
+            _ => HttpResponse::InternalServerError().json(error_response),
+        }
+    }
+
+    // GET /api/admin/translations/products/{id}
+    pub async fn get_product_translations(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<String>,
+    ) -> ActixResult<impl Responder> {
+        let product_id = path.into_inner();
+
+        match data.product_service.get_translations(&product_id).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(error) => {
+                error!(
+                    "Failed to fetch translations of product {}: {}",
+                    product_id, error
+                );
+                Ok(Self::product_error(error))
+            }
+        }
+    }
+
+    // PUT /api/admin/translations/products/{id}/{locale}
+    pub async fn set_product_translation(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<(String, String)>,
+        request: web::Json<ProductTranslationRequest>,
+    ) -> ActixResult<impl Responder> {
+        let (product_id, locale) = path.into_inner();
+
+        match data
+            .product_service
+            .set_translation(&product_id, &locale, request.into_inner())
+            .await
+        {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(error) => {
+                error!(
+                    "Failed to set {} translation of product {}: {}",
+                    locale, product_id, error
+                );
+                Ok(Self::product_error(error))
+            }
+        }
+    }
+
+    // DELETE /api/admin/translations/products/{id}/{locale}
+    pub async fn delete_product_translation(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<(String, String)>,
+    ) -> ActixResult<impl Responder> {
+        let (product_id, locale) = path.into_inner();
+
+        match data
+            .product_service
+            .delete_translation(&product_id, &locale)
+            .await
+        {
+            Ok(()) => Ok(HttpResponse::NoContent().finish()),
+            Err(error) => {
+                error!(
+                    "Failed to delete {} translation of product {}: {}",
+                    locale, product_id, error
+                );
+                Ok(Self::product_error(error))
+            }
+        }
+    }
+
+    // GET /api/admin/translations/categories/{id}
+    pub async fn get_category_translations(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<String>,
+    ) -> ActixResult<impl Responder> {
+        let category_id = path.into_inner();
+
+        match data.category_service.find_translations(&category_id).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(error) => {
+                error!(
+                    "Failed to fetch translations of category {}: {}",
+                    category_id, error
+                );
+                Ok(Self::category_error(error))
+            }
+        }
+    }
+
+    // PUT /api/admin/translations/categories/{id}/{locale}
+    pub async fn set_category_translation(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<(String, String)>,
+        request: web::Json<CategoryTranslationRequest>,
+    ) -> ActixResult<impl Responder> {
+        let (category_id, locale) = path.into_inner();
+
+        match data
+            .category_service
+            .set_translation(&category_id, &locale, request.into_inner())
+            .await
+        {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(error) => {
+                error!(
+                    "Failed to set {} translation of category {}: {}",
+                    locale, category_id, error
+                );
+                Ok(Self::category_error(error))
+            }
+        }
+    }
+
+    // DELETE /api/admin/translations/categories/{id}/{locale}
+    pub async fn delete_category_translation(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        path: web::Path<(String, String)>,
+    ) -> ActixResult<impl Responder> {
+        let (category_id, locale) = path.into_inner();
+
+        match data
+            .category_service
+            .delete_translation(&category_id, &locale)
+            .await
+        {
+            Ok(()) => Ok(HttpResponse::NoContent().finish()),
+            Err(error) => {
+                error!(
+                    "Failed to delete {} translation of category {}: {}",
+                    locale, category_id, error
+                );
+                Ok(Self::category_error(error))
+            }
+        }
+    }
+
+    // GET /api/admin/translations/missing
+    pub async fn get_missing_translations(
+        data: web::Data<TranslationHandler>,
+        _user: KeycloakUser,
+        query: web::Query<MissingTranslationsQuery>,
+    ) -> ActixResult<impl Responder> {
+        let query = query.into_inner();
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_MISSING_LIMIT)
+            .clamp(1, MAX_MISSING_LIMIT);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let locales = data.product_service.locales();
+        let targets: Vec<String> = match &query.locale {
+            Some(locale) => vec![locale.clone()],
+            None => locales.translatable().map(str::to_string).collect(),
+        };
+
+        let mut report = Vec::new();
+        for locale in targets {
+            let products = match query.entity_type {
+                Some(TranslatableEntity::Category) => None,
+                _ => match data
+                    .product_service
+                    .find_missing_translations(&locale, limit, offset)
+                    .await
+                {
+                    Ok(products) => Some(products),
+                    Err(error) => return Ok(Self::product_error(error)),
+                },
+            };
+            let categories = match query.entity_type {
+                Some(TranslatableEntity::Product) => None,
+                _ => match data
+                    .category_service
+                    .find_missing_translations(&locale, limit, offset)
+                    .await
+                {
+                    Ok(categories) => Some(categories),
+                    Err(error) => return Ok(Self::category_error(error)),
+                },
+            };
+            report.push(LocaleMissingTranslations {
+                locale,
+                products,
+                categories,
+            });
+        }
+
+        info!("Reported missing translations for {} locales", report.len());
+        Ok(HttpResponse::Ok().json(MissingTranslationsResponse {
+            default_locale: locales.default_locale().to_string(),
+            locales: report,
+        }))
+    }
+}
+
+pub fn configure_translation_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin/translations")
+            .route(
+                "/missing",
+                web::get().to(TranslationHandler::get_missing_translations),
+            )
+            .route(
+                "/products/{id}",
+                web::get().to(TranslationHandler::get_product_translations),
+            )
+            .route(
+                "/products/{id}/{locale}",
+                web::put().to(TranslationHandler::set_product_translation),
+            )
+            .route(
+                "/products/{id}/{locale}",
+                web::delete().to(TranslationHandler::delete_product_translation),
+            )
+            .route(
+                "/categories/{id}",
+                web::get().to(TranslationHandler::get_category_translations),
+            )
+            .route(
+                "/categories/{id}/{locale}",
+                web::put().to(TranslationHandler::set_category_translation),
+            )
+            .route(
+                "/categories/{id}/{locale}",
+                web::delete().to(TranslationHandler::delete_category_translation),
+            ),
+    );
+}
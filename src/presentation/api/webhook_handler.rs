@@ -0,0 +1,124 @@
+use actix_web::{web, HttpResponse, Responder, Result as ActixResult};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::application::dto::webhook_dto::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveryQuery,
+};
+use crate::application::service::webhook_service::WebhookService;
+use crate::infrastructure::auth::middleware::{KeycloakUser, ADMIN_ROLE};
+
+pub struct WebhookHandler {
+    service: Arc<WebhookService>,
+}
+
+impl WebhookHandler {
+    pub fn new(service: Arc<WebhookService>) -> Self {
+        Self { service }
+    }
+
+    // GET /api/admin/webhooks
+    pub async fn get_webhooks(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let subscriptions = data.service.find_all().await?;
+        Ok(HttpResponse::Ok().json(subscriptions))
+    }
+
+    // GET /api/admin/webhooks/{id}
+    pub async fn get_webhook(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        path: web::Path<String>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let subscription = data.service.find_by_id(&path.into_inner()).await?;
+        Ok(HttpResponse::Ok().json(subscription))
+    }
+
+    // POST /api/admin/webhooks
+    pub async fn create_webhook(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        req: web::Json<CreateWebhookRequest>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let subscription = data.service.create(req.into_inner()).await?;
+        info!("Created webhook subscription {}", subscription.id);
+        Ok(HttpResponse::Created().json(subscription))
+    }
+
+    // PUT /api/admin/webhooks/{id}
+    pub async fn update_webhook(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        path: web::Path<String>,
+        req: web::Json<UpdateWebhookRequest>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let subscription = data
+            .service
+            .update(&path.into_inner(), req.into_inner())
+            .await?;
+        Ok(HttpResponse::Ok().json(subscription))
+    }
+
+    // DELETE /api/admin/webhooks/{id}
+    pub async fn delete_webhook(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        path: web::Path<String>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        data.service.delete(&path.into_inner()).await?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    // GET /api/admin/webhooks/{id}/deliveries
+    pub async fn get_deliveries(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        path: web::Path<String>,
+        query: web::Query<WebhookDeliveryQuery>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let deliveries = data
+            .service
+            .find_deliveries(&path.into_inner(), query.limit)
+            .await?;
+        Ok(HttpResponse::Ok().json(deliveries))
+    }
+
+    // POST /api/admin/webhooks/{id}/deliveries/{delivery_id}/redeliver
+    pub async fn redeliver(
+        data: web::Data<WebhookHandler>,
+        user: KeycloakUser,
+        path: web::Path<(String, i64)>,
+    ) -> ActixResult<impl Responder> {
+        user.require_role(ADMIN_ROLE)?;
+        let (id, delivery_id) = path.into_inner();
+        let delivery = data.service.redeliver(&id, delivery_id).await?;
+        Ok(HttpResponse::Ok().json(delivery))
+    }
+}
+
+pub fn configure_webhook_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin/webhooks")
+            .route("", web::get().to(WebhookHandler::get_webhooks))
+            .route("", web::post().to(WebhookHandler::create_webhook))
+            .route("/{id}", web::get().to(WebhookHandler::get_webhook))
+            .route("/{id}", web::put().to(WebhookHandler::update_webhook))
+            .route("/{id}", web::delete().to(WebhookHandler::delete_webhook))
+            .route(
+                "/{id}/deliveries",
+                web::get().to(WebhookHandler::get_deliveries),
+            )
+            .route(
+                "/{id}/deliveries/{delivery_id}/redeliver",
+                web::post().to(WebhookHandler::redeliver),
+            ),
+    );
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tonic::{Code, Request, Status};
+use tracing::error;
+
+use crate::infrastructure::auth::keycloak::{KeycloakAuth, KeycloakClaims, KeycloakError};
+use crate::presentation::grpc::error_details::ErrorDetails;
+
+/// 呼び出し元の資格情報を含むメタデータキー
+pub const AUTHORIZATION_METADATA: &str = "authorization";
+
+/// ベアラートークンを検証してクレームを返す
+#[async_trait]
+pub trait TokenVerifier: Send + Sync {
+    async fn verify(&self, token: &str) -> Result<KeycloakClaims, KeycloakError>;
+}
+
+#[async_trait]
+impl TokenVerifier for KeycloakAuth {
+    async fn verify(&self, token: &str) -> Result<KeycloakClaims, KeycloakError> {
+        Ok(self.verify_token(token).await?.claims)
+    }
+}
+
+/// 書き込み RPC の呼び出し元を REST と同じ Keycloak のベアラートークンで認証する
+///
+/// tonic のインターセプターは同期的に呼ばれ、JWKS を取得するトークンの検証を行えないため、
+/// 各書き込み RPC の先頭（冪等性キーの照合より前）で `authenticate` を呼び出す。
+#[derive(Clone)]
+pub struct GrpcAuth {
+    verifier: Arc<dyn TokenVerifier>,
+}
+
+impl GrpcAuth {
+    pub fn new(verifier: Arc<dyn TokenVerifier>) -> Self {
+        Self { verifier }
+    }
+
+    /// `authorization: Bearer <token>` メタデータを検証し、トークンのクレームを返す
+    pub async fn authenticate<T>(&self, request: &Request<T>) -> Result<KeycloakClaims, Status> {
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| unauthenticated("認証トークンが必要です"))?;
+
+        self.verifier.verify(token).await.map_err(|e| {
+            error!("gRPC: 認証エラー: {}", e);
+            match e {
+                KeycloakError::TokenExpired => unauthenticated("トークンの有効期限が切れています"),
+                _ => unauthenticated("認証に失敗しました"),
+            }
+        })
+    }
+}
+
+fn unauthenticated(message: &str) -> Status {
+    ErrorDetails::new(Code::Unauthenticated, "UNAUTHORIZED", message).into_status()
+}
+
+/// 固定のトークンだけを受け付ける検証（テスト用）
+#[cfg(test)]
+pub struct StaticTokenVerifier {
+    pub token: &'static str,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl TokenVerifier for StaticTokenVerifier {
+    async fn verify(&self, token: &str) -> Result<KeycloakClaims, KeycloakError> {
+        if token == self.token {
+            Ok(KeycloakClaims::dummy())
+        } else {
+            Err(KeycloakError::Other("unknown token".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::MetadataValue;
+
+    fn request(authorization: Option<&'static str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(value) = authorization {
+            request
+                .metadata_mut()
+                .insert(AUTHORIZATION_METADATA, MetadataValue::from_static(value));
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_requires_valid_bearer_token() {
+        let auth = GrpcAuth::new(Arc::new(StaticTokenVerifier { token: "good" }));
+
+        let claims = auth
+            .authenticate(&request(Some("Bearer good")))
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "dummy-sub");
+
+        for authorization in [None, Some("good"), Some("Bearer "), Some("Bearer bad")] {
+            let status = auth
+                .authenticate(&request(authorization))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), Code::Unauthenticated, "{:?}", authorization);
+        }
+    }
+}
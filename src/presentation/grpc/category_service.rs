@@ -0,0 +1,491 @@
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+use crate::app_domain::model::category::CategoryError;
+use crate::application::dto::category_dto::{
+    self, CategoriesResponse, CategoryErrorResponse, CategoryListResponse, CategoryTreeResponse,
+};
+use crate::application::service::category_service::CategoryService;
+use crate::application::service::idempotency_service::IdempotencyService;
+use crate::presentation::grpc::auth::GrpcAuth;
+use crate::presentation::grpc::convert::timestamp;
+use crate::presentation::grpc::error_details::ErrorDetails;
+use crate::presentation::grpc::idempotency::idempotent;
+
+// Include the generated proto code
+tonic::include_proto!("category");
+
+pub use category_service_server::{CategoryService as CategoryServiceTrait, CategoryServiceServer};
+
+#[derive(Clone)]
+pub struct CategoryServiceImpl {
+    service: Arc<CategoryService>,
+    idempotency: Arc<IdempotencyService>,
+    auth: GrpcAuth,
+}
+
+impl CategoryServiceImpl {
+    pub fn new(
+        service: Arc<CategoryService>,
+        idempotency: Arc<IdempotencyService>,
+        auth: GrpcAuth,
+    ) -> Self {
+        Self {
+            service,
+            idempotency,
+            auth,
+        }
+    }
+}
+
+/// カテゴリのドメインエラーを gRPC のステータスに変換する
+fn to_status(error: CategoryError) -> Status {
+    let code = match &error {
+        CategoryError::NotFound(_) => tonic::Code::NotFound,
+        CategoryError::InvalidName(_)
+        | CategoryError::InvalidSortOrder(_)
+        | CategoryError::InvalidSlug(_)
+        | CategoryError::InvalidAttributeDefinition(_)
+        | CategoryError::InvalidTranslation(_) => tonic::Code::InvalidArgument,
+        CategoryError::NameDuplicate(_) | CategoryError::SlugDuplicate(_) => {
+            tonic::Code::AlreadyExists
+        }
+        CategoryError::CircularReference(_)
+        | CategoryError::MaxDepthExceeded(_)
+        | CategoryError::HasChildren(_)
+        | CategoryError::HasProducts(_) => tonic::Code::FailedPrecondition,
+        CategoryError::VersionMismatch(_) => tonic::Code::Aborted,
+    };
+    // REST と同じエラーコードを reason に、不正な項目を BadRequest に載せる
+    let response: CategoryErrorResponse = error.into();
+    let field = response
+        .details
+        .as_ref()
+        .filter(|_| code == tonic::Code::InvalidArgument)
+        .and_then(|details| details["field"].as_str())
+        .map(str::to_string);
+    let details = ErrorDetails::new(code, response.code, response.message.clone());
+    match field {
+        Some(field) => details.field_violation(field, response.message),
+        None => details,
+    }
+    .into_status()
+}
+
+impl From<category_dto::CategoryResponse> for Category {
+    fn from(category: category_dto::CategoryResponse) -> Self {
+        Self {
+            id: category.id,
+            name: category.name,
+            slug: category.slug,
+            description: category.description,
+            parent_id: category.parent_id,
+            sort_order: category.sort_order,
+            is_active: category.is_active,
+            created_at: Some(timestamp(category.created_at)),
+            updated_at: Some(timestamp(category.updated_at)),
+            version: category.version,
+            product_count: category.product_count,
+            total_product_count: category.total_product_count,
+        }
+    }
+}
+
+impl From<CategoryListResponse> for CategorySummary {
+    fn from(category: CategoryListResponse) -> Self {
+        Self {
+            id: category.id,
+            name: category.name,
+            slug: category.slug,
+            description: category.description,
+            parent_id: category.parent_id,
+            sort_order: category.sort_order,
+            is_active: category.is_active,
+            children_count: category.children_count,
+            product_count: category.product_count,
+            total_product_count: category.total_product_count,
+            created_at: Some(timestamp(category.created_at)),
+            updated_at: Some(timestamp(category.updated_at)),
+        }
+    }
+}
+
+impl From<CategoryTreeResponse> for CategoryTreeNode {
+    fn from(tree: CategoryTreeResponse) -> Self {
+        Self {
+            id: tree.id,
+            name: tree.name,
+            slug: tree.slug,
+            description: tree.description,
+            sort_order: tree.sort_order,
+            is_active: tree.is_active,
+            product_count: tree.product_count,
+            total_product_count: tree.total_product_count,
+            children: tree.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CategoriesResponse> for ListCategoriesResponse {
+    fn from(response: CategoriesResponse) -> Self {
+        Self {
+            categories: response.categories.into_iter().map(Into::into).collect(),
+            total: response.total as u64,
+        }
+    }
+}
+
+fn category_response(category: category_dto::CategoryResponse) -> Response<CategoryResponse> {
+    Response::new(CategoryResponse {
+        category: Some(category.into()),
+    })
+}
+
+#[tonic::async_trait]
+impl CategoryServiceTrait for CategoryServiceImpl {
+    async fn get_category(
+        &self,
+        request: Request<GetCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.service.find_by_id(&req.id).await {
+            Ok(category) => {
+                info!("gRPC: Fetched category {}", req.id);
+                Ok(category_response(category))
+            }
+            Err(e) => {
+                info!("gRPC: Category {} not found or error: {}", req.id, e);
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn list_categories(
+        &self,
+        request: Request<ListCategoriesRequest>,
+    ) -> Result<Response<ListCategoriesResponse>, Status> {
+        let req = request.into_inner();
+
+        let result = match req.parent_id {
+            Some(parent_id) => {
+                self.service
+                    .find_by_parent_id(Some(parent_id), req.include_inactive)
+                    .await
+            }
+            None => self.service.find_all(req.include_inactive).await,
+        };
+
+        match result {
+            Ok(response) => {
+                info!("gRPC: Fetched {} categories", response.total);
+                Ok(Response::new(response.into()))
+            }
+            Err(e) => {
+                error!("gRPC: Failed to fetch categories: {}", e);
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn get_category_tree(
+        &self,
+        request: Request<GetCategoryTreeRequest>,
+    ) -> Result<Response<GetCategoryTreeResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.service.find_tree(req.include_inactive).await {
+            Ok(response) => Ok(Response::new(GetCategoryTreeResponse {
+                tree: response.tree.into_iter().map(Into::into).collect(),
+            })),
+            Err(e) => {
+                error!("gRPC: Failed to fetch category tree: {}", e);
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn get_category_path(
+        &self,
+        request: Request<GetCategoryPathRequest>,
+    ) -> Result<Response<GetCategoryPathResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.service.find_path(&req.id).await {
+            Ok(response) => Ok(Response::new(GetCategoryPathResponse {
+                path: response
+                    .path
+                    .into_iter()
+                    .map(|item| CategoryPathItem {
+                        id: item.id,
+                        name: item.name,
+                        slug: item.slug,
+                    })
+                    .collect(),
+                depth: response.depth as u64,
+            })),
+            Err(e) => {
+                info!("gRPC: Failed to fetch path for category {}: {}", req.id, e);
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn create_category(
+        &self,
+        request: Request<CreateCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        self.auth.authenticate(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/category.CategoryService/CreateCategory",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Creating category {}", req.name);
+
+                let create_request = category_dto::CreateCategoryRequest {
+                    name: req.name,
+                    slug: req.slug,
+                    description: req.description,
+                    parent_id: req.parent_id,
+                    sort_order: req.sort_order,
+                };
+
+                match self.service.create(create_request).await {
+                    Ok(category) => {
+                        info!("gRPC: Created category {}", category.id);
+                        Ok(category_response(category))
+                    }
+                    Err(e) => {
+                        error!("gRPC: Failed to create category: {}", e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn update_category(
+        &self,
+        request: Request<UpdateCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        self.auth.authenticate(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/category.CategoryService/UpdateCategory",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Updating category {}", req.id);
+
+                let update_request = category_dto::UpdateCategoryRequest {
+                    name: req.name,
+                    slug: req.slug,
+                    description: req.description,
+                    sort_order: req.sort_order,
+                    is_active: req.is_active,
+                };
+
+                match self
+                    .service
+                    .update(&req.id, update_request, req.expected_version)
+                    .await
+                {
+                    Ok(category) => Ok(category_response(category)),
+                    Err(e) => {
+                        error!("gRPC: Failed to update category {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn move_category(
+        &self,
+        request: Request<MoveCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        self.auth.authenticate(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/category.CategoryService/MoveCategory",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Moving category {}", req.id);
+
+                let move_request = category_dto::MoveCategoryRequest {
+                    new_parent_id: req.new_parent_id,
+                    new_sort_order: req.new_sort_order,
+                };
+
+                match self
+                    .service
+                    .move_category(&req.id, move_request, req.expected_version)
+                    .await
+                {
+                    Ok(category) => Ok(category_response(category)),
+                    Err(e) => {
+                        error!("gRPC: Failed to move category {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::category::{Category as DomainCategory, ProductCount};
+    use crate::app_domain::repository::category_repository::MockCategoryRepository;
+    use crate::infrastructure::repository::idempotency_repository::InMemoryIdempotencyRepository;
+    use crate::presentation::grpc::auth::{StaticTokenVerifier, AUTHORIZATION_METADATA};
+    use crate::presentation::grpc::error_details::{decode, find, rpc};
+    use chrono::Utc;
+    use mockall::predicate::*;
+
+    fn grpc_service(repository: MockCategoryRepository) -> CategoryServiceImpl {
+        CategoryServiceImpl::new(
+            Arc::new(CategoryService::new(Arc::new(repository))),
+            Arc::new(IdempotencyService::new(
+                Arc::new(InMemoryIdempotencyRepository::new()),
+                60,
+            )),
+            GrpcAuth::new(Arc::new(StaticTokenVerifier { token: "token" })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_category_delegates_to_service() {
+        let mut repository = MockCategoryRepository::new();
+        let category = DomainCategory {
+            id: "cat_1".to_string(),
+            name: "Electronics".to_string(),
+            slug: "electronics".to_string(),
+            description: None,
+            parent_id: None,
+            sort_order: 1,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 3,
+        };
+        repository
+            .expect_find_by_id()
+            .with(eq("cat_1"))
+            .return_once(move |_| Some(category));
+        repository
+            .expect_count_products()
+            .with(eq("cat_1"))
+            .return_once(|_| ProductCount {
+                direct: 1,
+                total: 4,
+            });
+
+        let response = grpc_service(repository)
+            .get_category(Request::new(GetCategoryRequest {
+                id: "cat_1".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let category = response.category.unwrap();
+        assert_eq!(category.name, "Electronics");
+        assert_eq!(category.version, 3);
+        assert_eq!(category.product_count, Some(1));
+        assert_eq!(category.total_product_count, Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_missing_category_is_not_found() {
+        let mut repository = MockCategoryRepository::new();
+        repository.expect_find_by_id().return_once(|_| None);
+
+        let status = grpc_service(repository)
+            .get_category(Request::new(GetCategoryRequest {
+                id: "missing".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_write_rpcs_require_bearer_token() {
+        let move_request = || MoveCategoryRequest {
+            id: "cat_1".to_string(),
+            new_parent_id: None,
+            new_sort_order: Some(0),
+            expected_version: None,
+        };
+
+        // 認証に失敗した呼び出しはリポジトリまで届かない
+        let status = grpc_service(MockCategoryRepository::new())
+            .move_category(Request::new(move_request()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let mut repository = MockCategoryRepository::new();
+        repository
+            .expect_move_category()
+            .return_once(|id, _, _, _| Err(CategoryError::NotFound(id.to_string())));
+        let mut request = Request::new(move_request());
+        request.metadata_mut().insert(
+            AUTHORIZATION_METADATA,
+            tonic::metadata::MetadataValue::from_static("Bearer token"),
+        );
+        let status = grpc_service(repository)
+            .move_category(request)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[test]
+    fn test_domain_errors_map_to_status_codes() {
+        let cases = [
+            (
+                CategoryError::NameDuplicate("x".to_string()),
+                tonic::Code::AlreadyExists,
+            ),
+            (
+                CategoryError::CircularReference("x".to_string()),
+                tonic::Code::FailedPrecondition,
+            ),
+            (
+                CategoryError::HasProducts("x".to_string()),
+                tonic::Code::FailedPrecondition,
+            ),
+            (
+                CategoryError::VersionMismatch("x".to_string()),
+                tonic::Code::Aborted,
+            ),
+            (
+                CategoryError::InvalidName("x".to_string()),
+                tonic::Code::InvalidArgument,
+            ),
+        ];
+        for (error, code) in cases {
+            assert_eq!(to_status(error).code(), code);
+        }
+    }
+
+    #[test]
+    fn test_invalid_name_reports_field_violation() {
+        let details = decode(&to_status(CategoryError::InvalidName("x".to_string()))).unwrap();
+        let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+        assert_eq!(info.reason, "CATEGORY_INVALID_NAME");
+        let bad_request: rpc::BadRequest = find(&details, "BadRequest").unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "name");
+    }
+}
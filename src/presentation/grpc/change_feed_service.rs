@@ -0,0 +1,268 @@
+// 生成コードの WatchChangesResponse はイベントとハートビートの oneof でサイズ差が大きいが、
+// 1 件ずつ送るだけなので Box 化はしない
+#![allow(clippy::large_enum_variant)]
+
+use futures::stream::{self, Stream};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::app_domain::model::change_event::{self as domain, ChangeCursor, ChangePayload};
+use crate::application::service::change_feed::{ChangeFeed, ChangeFeedError, ChangeFeedMessage};
+use crate::presentation::grpc::convert::{invalid_field, timestamp, RETRY_DELAY};
+use crate::presentation::grpc::error_details::ErrorDetails;
+
+// Include the generated proto code
+tonic::include_proto!("changefeed");
+
+pub use change_feed_service_server::{
+    ChangeFeedService as ChangeFeedServiceTrait, ChangeFeedServiceServer,
+};
+
+#[derive(Clone)]
+pub struct ChangeFeedServiceImpl {
+    feed: Arc<ChangeFeed>,
+    heartbeat: Duration,
+}
+
+impl ChangeFeedServiceImpl {
+    pub fn new(feed: Arc<ChangeFeed>, heartbeat: Duration) -> Self {
+        Self { feed, heartbeat }
+    }
+}
+
+/// 変更フィードのエラーを gRPC のステータスに変換する
+fn to_status(error: ChangeFeedError) -> Status {
+    match error {
+        // 再開できないカーソルは全件を取り直してから購読し直してもらう
+        ChangeFeedError::CursorExpired { cursor, oldest } => {
+            ErrorDetails::new(tonic::Code::OutOfRange, "CURSOR_EXPIRED", error.to_string())
+                .metadata("cursor", cursor)
+                .metadata("oldest_epoch", oldest.epoch)
+                .metadata("oldest_sequence", oldest.sequence)
+                .into_status()
+        }
+        ChangeFeedError::Closed => ErrorDetails::new(
+            tonic::Code::Unavailable,
+            "SERVICE_UNAVAILABLE",
+            error.to_string(),
+        )
+        .retry_after(RETRY_DELAY)
+        .into_status(),
+    }
+}
+
+impl From<domain::EntityType> for EntityType {
+    fn from(entity_type: domain::EntityType) -> Self {
+        match entity_type {
+            domain::EntityType::Item => EntityType::Item,
+            domain::EntityType::Product => EntityType::Product,
+            domain::EntityType::Category => EntityType::Category,
+        }
+    }
+}
+
+impl From<domain::ChangeKind> for ChangeKind {
+    fn from(kind: domain::ChangeKind) -> Self {
+        match kind {
+            domain::ChangeKind::Created => ChangeKind::Created,
+            domain::ChangeKind::Updated => ChangeKind::Updated,
+            domain::ChangeKind::Deleted => ChangeKind::Deleted,
+            domain::ChangeKind::Restored => ChangeKind::Restored,
+        }
+    }
+}
+
+impl From<ChangePayload> for change_event::Payload {
+    fn from(payload: ChangePayload) -> Self {
+        match payload {
+            ChangePayload::Item(item) => change_event::Payload::Item(ItemSnapshot {
+                id: item.id,
+                name: item.name,
+                description: item.description,
+                deleted: item.deleted,
+            }),
+            ChangePayload::Product(product) => change_event::Payload::Product(ProductSnapshot {
+                id: product.id,
+                sku: product.sku,
+                name: product.name,
+                status: product.status.to_string(),
+                category_id: product.category_id,
+                version: product.version,
+                updated_at: Some(timestamp(product.updated_at)),
+            }),
+            ChangePayload::Category(category) => {
+                change_event::Payload::Category(CategorySnapshot {
+                    id: category.id,
+                    name: category.name,
+                    parent_id: category.parent_id,
+                    sort_order: category.sort_order,
+                    is_active: category.is_active,
+                    version: category.version,
+                })
+            }
+        }
+    }
+}
+
+impl From<domain::ChangeEvent> for ChangeEvent {
+    fn from(event: domain::ChangeEvent) -> Self {
+        Self {
+            epoch: event.epoch,
+            sequence: event.sequence,
+            entity_type: EntityType::from(event.entity_type) as i32,
+            entity_id: event.entity_id,
+            kind: ChangeKind::from(event.kind) as i32,
+            changed_fields: event.changed_fields,
+            occurred_at: Some(timestamp(event.occurred_at)),
+            payload: event.payload.map(Into::into),
+        }
+    }
+}
+
+impl From<ChangeFeedMessage> for WatchChangesResponse {
+    fn from(message: ChangeFeedMessage) -> Self {
+        let message = match message {
+            ChangeFeedMessage::Event(event) => watch_changes_response::Message::Event(event.into()),
+            ChangeFeedMessage::Heartbeat { cursor, sent_at } => {
+                watch_changes_response::Message::Heartbeat(Heartbeat {
+                    epoch: cursor.epoch,
+                    cursor: cursor.sequence,
+                    sent_at: Some(timestamp(sent_at)),
+                })
+            }
+        };
+        Self {
+            message: Some(message),
+        }
+    }
+}
+
+fn entity_types(values: &[i32]) -> Result<Vec<domain::EntityType>, Status> {
+    values
+        .iter()
+        .map(|value| match EntityType::try_from(*value) {
+            Ok(EntityType::Item) => Ok(domain::EntityType::Item),
+            Ok(EntityType::Product) => Ok(domain::EntityType::Product),
+            Ok(EntityType::Category) => Ok(domain::EntityType::Category),
+            _ => Err(invalid_field(
+                "entity_types",
+                format!("不明なエンティティ種別です: {}", value),
+            )),
+        })
+        .collect()
+}
+
+type WatchChangesStream = Pin<Box<dyn Stream<Item = Result<WatchChangesResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl ChangeFeedServiceTrait for ChangeFeedServiceImpl {
+    type WatchChangesStream = WatchChangesStream;
+
+    async fn watch_changes(
+        &self,
+        request: Request<WatchChangesRequest>,
+    ) -> Result<Response<Self::WatchChangesStream>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC WatchChanges request: after={:?}/{:?}, entity_types={:?}",
+            req.after_epoch, req.after_sequence, req.entity_types
+        );
+
+        let entity_types = entity_types(&req.entity_types)?;
+        // エポックのないカーソルはどのプロセスのものか分からないため期限切れとして扱う
+        let after = req.after_sequence.map(|sequence| ChangeCursor {
+            epoch: req.after_epoch.unwrap_or_default(),
+            sequence,
+        });
+        let subscription = self
+            .feed
+            .subscribe(after, entity_types)
+            .map_err(to_status)?;
+        let heartbeat = self.heartbeat;
+
+        // エラーを 1 件送ったらストリームを終える
+        let stream = stream::unfold(Some(subscription), move |subscription| async move {
+            let mut subscription = subscription?;
+            match subscription.next_or_heartbeat(heartbeat).await {
+                Ok(message) => Some((Ok(message.into()), Some(subscription))),
+                Err(e) => Some((Err(to_status(e)), None)),
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_domain::model::change_event::ChangeKind as DomainChangeKind;
+    use crate::presentation::grpc::error_details::{decode, find, rpc};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_watch_changes_filters_and_resumes() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        feed.publish_id(domain::EntityType::Item, 1, DomainChangeKind::Deleted);
+        feed.publish_id(
+            domain::EntityType::Category,
+            "c1",
+            DomainChangeKind::Restored,
+        );
+        let epoch = feed.epoch();
+        let service = ChangeFeedServiceImpl::new(feed, Duration::from_secs(60));
+
+        let mut stream = service
+            .watch_changes(Request::new(WatchChangesRequest {
+                after_epoch: Some(epoch),
+                after_sequence: Some(0),
+                entity_types: vec![EntityType::Category as i32],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let response = stream.next().await.unwrap().unwrap();
+        match response.message {
+            Some(watch_changes_response::Message::Event(event)) => {
+                assert_eq!(event.epoch, epoch);
+                assert_eq!(event.sequence, 2);
+                assert_eq!(event.entity_id, "c1");
+                assert_eq!(event.kind, ChangeKind::Restored as i32);
+                assert!(event.payload.is_none());
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_cursor_returns_out_of_range() {
+        let feed = Arc::new(ChangeFeed::new(10));
+        let epoch = feed.epoch();
+        let service = ChangeFeedServiceImpl::new(feed, Duration::from_secs(60));
+
+        // 未発行のシーケンス、別プロセスのエポック、エポックなしのカーソル
+        for after_epoch in [Some(epoch), Some(epoch + 1), None] {
+            let after_sequence = if after_epoch == Some(epoch) { 5 } else { 0 };
+            let status = service
+                .watch_changes(Request::new(WatchChangesRequest {
+                    after_epoch,
+                    after_sequence: Some(after_sequence),
+                    entity_types: Vec::new(),
+                }))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(status.code(), tonic::Code::OutOfRange);
+
+            let details = decode(&status).unwrap();
+            let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+            assert_eq!(info.reason, "CURSOR_EXPIRED");
+            assert_eq!(info.metadata["oldest_epoch"], epoch.to_string());
+            assert_eq!(info.metadata["oldest_sequence"], "1");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::time::Duration;
+use tonic::{Code, Status};
+
+use crate::infrastructure::error::AppError;
+use crate::presentation::grpc::error_details::ErrorDetails;
+
+/// 一時的なエラーで RetryInfo に設定する再試行までの待ち時間
+pub const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// `DateTime<Utc>` を protobuf の Timestamp に変換する
+pub fn timestamp(dt: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: dt.timestamp(),
+        nanos: dt.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// protobuf の Timestamp を `DateTime<Utc>` に変換する
+pub fn datetime(field: &str, ts: prost_types::Timestamp) -> Result<DateTime<Utc>, Status> {
+    DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+        .ok_or_else(|| invalid_field(field, format!("{} が不正な日時です", field)))
+}
+
+/// 文字列で受け取った金額・寸法などを `Decimal` に変換する
+pub fn decimal(field: &str, value: &str) -> Result<Decimal, Status> {
+    Decimal::from_str(value.trim())
+        .map_err(|_| invalid_field(field, format!("{} が不正な数値です: {}", field, value)))
+}
+
+/// 任意項目の数値を変換する
+pub fn optional_decimal(field: &str, value: Option<String>) -> Result<Option<Decimal>, Status> {
+    value.map(|value| decimal(field, &value)).transpose()
+}
+
+/// リクエストの項目が不正な場合のステータス（BadRequest のフィールド違反付き）
+pub fn invalid_field(field: &str, message: impl Into<String>) -> Status {
+    let message = message.into();
+    ErrorDetails::new(Code::InvalidArgument, "BAD_REQUEST", message.clone())
+        .field_violation(field, message)
+        .into_status()
+}
+
+/// アプリケーションエラーを gRPC のステータスに変換する（REST の HTTP ステータスに対応）
+///
+/// ErrorInfo の reason は REST の `error.type` を大文字にしたもの
+pub fn app_error_status(error: AppError) -> Status {
+    let reason = error.error_type().to_uppercase();
+    let (code, message) = match error {
+        AppError::NotFound(message) => (Code::NotFound, message),
+        AppError::BadRequest(message) | AppError::ValidationError(message) => {
+            (Code::InvalidArgument, message)
+        }
+        AppError::Conflict(message) => (Code::Aborted, message),
+        AppError::Unauthorized(message) | AppError::AuthenticationError(message) => {
+            (Code::Unauthenticated, message)
+        }
+        AppError::Forbidden(message) => (Code::PermissionDenied, message),
+        AppError::ServiceUnavailable(message) => (Code::Unavailable, message),
+        AppError::TimeoutError(message) => (Code::DeadlineExceeded, message),
+        other => (Code::Internal, other.to_string()),
+    };
+
+    let details = ErrorDetails::new(code, reason, message);
+    match code {
+        Code::Unavailable | Code::DeadlineExceeded => details.retry_after(RETRY_DELAY),
+        _ => details,
+    }
+    .into_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::grpc::error_details::{decode, find, rpc};
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        let now = Utc::now();
+        assert_eq!(datetime("at", timestamp(now)).unwrap(), now);
+    }
+
+    #[test]
+    fn test_decimal_rejects_invalid_value() {
+        assert_eq!(decimal("price", " 12.50 ").unwrap().to_string(), "12.50");
+        assert_eq!(
+            decimal("price", "abc").unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_app_error_status_carries_rest_error_type() {
+        let status = app_error_status(AppError::not_found("Item", 9));
+        assert_eq!(status.code(), Code::NotFound);
+        let details = decode(&status).unwrap();
+        let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+        assert_eq!(info.reason, "NOT_FOUND");
+        assert!(find::<rpc::RetryInfo>(&details, "RetryInfo").is_none());
+
+        let status = app_error_status(AppError::ServiceUnavailable("down".to_string()));
+        let details = decode(&status).unwrap();
+        let retry: rpc::RetryInfo = find(&details, "RetryInfo").unwrap();
+        assert_eq!(retry.retry_delay.unwrap().seconds, 1);
+    }
+
+    #[test]
+    fn test_invalid_decimal_reports_field_violation() {
+        let status = decimal("price.selling_price", "abc").unwrap_err();
+        let details = decode(&status).unwrap();
+        let bad_request: rpc::BadRequest = find(&details, "BadRequest").unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "price.selling_price");
+    }
+}
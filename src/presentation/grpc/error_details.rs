@@ -0,0 +1,172 @@
+use prost::Message;
+use std::collections::HashMap;
+use std::time::Duration;
+use tonic::{Code, Status};
+
+pub mod rpc {
+    // google.rpc.Status とエラー詳細メッセージ
+    tonic::include_proto!("google.rpc");
+}
+
+/// ErrorInfo の domain に設定するサービス名
+pub const ERROR_DOMAIN: &str = "rust-webapi";
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/google.rpc.";
+
+/// `google.rpc.Status` の詳細（ErrorInfo / BadRequest / RetryInfo）を付けた
+/// `tonic::Status` を組み立てる
+pub struct ErrorDetails {
+    code: Code,
+    message: String,
+    reason: String,
+    metadata: HashMap<String, String>,
+    field_violations: Vec<rpc::bad_request::FieldViolation>,
+    retry_delay: Option<Duration>,
+}
+
+impl ErrorDetails {
+    /// `reason` には REST のエラーコードと同じ値を指定する
+    pub fn new(code: Code, reason: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            reason: reason.into(),
+            metadata: HashMap::new(),
+            field_violations: Vec::new(),
+            retry_delay: None,
+        }
+    }
+
+    /// ErrorInfo にメタデータを追加する
+    pub fn metadata(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.metadata.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// BadRequest にフィールド単位の違反を追加する
+    pub fn field_violation(
+        mut self,
+        field: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.field_violations
+            .push(rpc::bad_request::FieldViolation {
+                field: field.into(),
+                description: description.into(),
+                reason: self.reason.clone(),
+            });
+        self
+    }
+
+    /// 再試行までの待ち時間を RetryInfo として付ける
+    pub fn retry_after(mut self, delay: Duration) -> Self {
+        self.retry_delay = Some(delay);
+        self
+    }
+
+    pub fn into_status(self) -> Status {
+        let mut details = vec![any(
+            "ErrorInfo",
+            &rpc::ErrorInfo {
+                reason: self.reason,
+                domain: ERROR_DOMAIN.to_string(),
+                metadata: self.metadata,
+            },
+        )];
+        if !self.field_violations.is_empty() {
+            details.push(any(
+                "BadRequest",
+                &rpc::BadRequest {
+                    field_violations: self.field_violations,
+                },
+            ));
+        }
+        if let Some(delay) = self.retry_delay {
+            details.push(any(
+                "RetryInfo",
+                &rpc::RetryInfo {
+                    retry_delay: Some(prost_types::Duration {
+                        seconds: delay.as_secs() as i64,
+                        nanos: delay.subsec_nanos() as i32,
+                    }),
+                },
+            ));
+        }
+
+        let status = rpc::Status {
+            code: self.code as i32,
+            message: self.message.clone(),
+            details,
+        };
+        Status::with_details(self.code, self.message, status.encode_to_vec().into())
+    }
+}
+
+impl From<ErrorDetails> for Status {
+    fn from(details: ErrorDetails) -> Self {
+        details.into_status()
+    }
+}
+
+fn any(name: &str, message: &impl Message) -> prost_types::Any {
+    prost_types::Any {
+        type_url: format!("{}{}", TYPE_URL_PREFIX, name),
+        value: message.encode_to_vec(),
+    }
+}
+
+/// `tonic::Status` に含まれる `google.rpc.Status` を取り出す（クライアント・テスト用）
+#[allow(dead_code)]
+pub fn decode(status: &Status) -> Option<rpc::Status> {
+    rpc::Status::decode(status.details()).ok()
+}
+
+/// `google.rpc.Status` の詳細から指定した型のメッセージを取り出す
+#[allow(dead_code)]
+pub fn find<M: Message + Default>(status: &rpc::Status, name: &str) -> Option<M> {
+    let type_url = format!("{}{}", TYPE_URL_PREFIX, name);
+    status
+        .details
+        .iter()
+        .find(|detail| detail.type_url == type_url)
+        .and_then(|detail| M::decode(detail.value.as_slice()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_details_round_trip() {
+        let status = ErrorDetails::new(Code::InvalidArgument, "PRODUCT_INVALID_NAME", "invalid")
+            .metadata("id", 42)
+            .field_violation("name", "required")
+            .retry_after(Duration::from_millis(1500))
+            .into_status();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let details = decode(&status).unwrap();
+        assert_eq!(details.code, Code::InvalidArgument as i32);
+        assert_eq!(details.message, "invalid");
+
+        let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+        assert_eq!(info.reason, "PRODUCT_INVALID_NAME");
+        assert_eq!(info.domain, ERROR_DOMAIN);
+        assert_eq!(info.metadata["id"], "42");
+
+        let bad_request: rpc::BadRequest = find(&details, "BadRequest").unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "name");
+
+        let retry: rpc::RetryInfo = find(&details, "RetryInfo").unwrap();
+        let delay = retry.retry_delay.unwrap();
+        assert_eq!((delay.seconds, delay.nanos), (1, 500_000_000));
+    }
+
+    #[test]
+    fn test_optional_details_are_omitted() {
+        let status = ErrorDetails::new(Code::NotFound, "NOT_FOUND", "missing").into_status();
+        let details = decode(&status).unwrap();
+        assert_eq!(details.details.len(), 1);
+        assert!(find::<rpc::BadRequest>(&details, "BadRequest").is_none());
+    }
+}
@@ -0,0 +1,189 @@
+use prost::Message;
+use std::future::Future;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Request, Response, Status};
+use tracing::error;
+
+use crate::app_domain::model::idempotency::{IdempotencyKey, IdempotencyOutcome, StoredResponse};
+use crate::application::service::idempotency_service::IdempotencyService;
+use crate::presentation::grpc::auth::AUTHORIZATION_METADATA;
+use crate::presentation::grpc::convert::{app_error_status, RETRY_DELAY};
+use crate::presentation::grpc::error_details::ErrorDetails;
+
+/// 再送を識別するメタデータキー（HTTP の `Idempotency-Key` に相当）
+pub const IDEMPOTENCY_KEY_METADATA: &str = "idempotency-key";
+/// 保存済みの結果を返したことを示すレスポンスメタデータ
+pub const IDEMPOTENT_REPLAYED_METADATA: &str = "idempotent-replayed";
+
+/// `idempotency-key` メタデータが付いた呼び出しについて、同じキーの再送には
+/// 最初の成功レスポンスを返す。エラーになった呼び出しはキーを解放する。
+///
+/// 認証しない RPC もあるため、キーは `authorization` メタデータの資格情報ごとに区切る
+/// （資格情報を持たない呼び出しは同じ匿名の範囲を共有する）。認証する RPC では、
+/// ここに来る前に `GrpcAuth` でトークンを検証しておく。
+pub async fn idempotent<Req, Res, F, Fut>(
+    idempotency: &IdempotencyService,
+    method: &str,
+    request: Request<Req>,
+    handler: F,
+) -> Result<Response<Res>, Status>
+where
+    Req: Message,
+    Res: Message + Default,
+    F: FnOnce(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Res>, Status>>,
+{
+    let key = match request
+        .metadata()
+        .get(IDEMPOTENCY_KEY_METADATA)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(key) => IdempotencyKey::new(caller_scope(&request), key.trim()),
+        None => return handler(request).await,
+    };
+
+    let fingerprint =
+        IdempotencyService::fingerprint(&[method.as_bytes(), &request.get_ref().encode_to_vec()]);
+
+    match idempotency
+        .begin(&key, &fingerprint, method)
+        .await
+        .map_err(app_error_status)?
+    {
+        IdempotencyOutcome::Proceed => {}
+        IdempotencyOutcome::Replay(stored) => {
+            let message = Res::decode(stored.body.as_slice()).map_err(|e| {
+                ErrorDetails::new(
+                    Code::Internal,
+                    "INTERNAL_SERVER_ERROR",
+                    format!("保存済みレスポンスの復元に失敗しました: {}", e),
+                )
+                .into_status()
+            })?;
+            let mut response = Response::new(message);
+            response.metadata_mut().insert(
+                IDEMPOTENT_REPLAYED_METADATA,
+                MetadataValue::from_static("true"),
+            );
+            return Ok(response);
+        }
+        IdempotencyOutcome::InProgress => {
+            // 先行リクエストの完了後に再送すれば保存済みの結果が返る
+            return Err(ErrorDetails::new(
+                Code::Aborted,
+                "IDEMPOTENCY_KEY_IN_USE",
+                "同じ冪等性キーのリクエストを処理中です",
+            )
+            .retry_after(RETRY_DELAY)
+            .into_status());
+        }
+        IdempotencyOutcome::FingerprintMismatch => {
+            return Err(ErrorDetails::new(
+                Code::InvalidArgument,
+                "IDEMPOTENCY_KEY_REUSED",
+                "冪等性キーが異なる内容のリクエストで再利用されました",
+            )
+            .field_violation(
+                IDEMPOTENCY_KEY_METADATA,
+                "冪等性キーが異なる内容のリクエストで再利用されました",
+            )
+            .into_status());
+        }
+    }
+
+    match handler(request).await {
+        Ok(response) => {
+            let stored = StoredResponse {
+                status_code: 0, // gRPC の OK
+                headers: Vec::new(),
+                body: response.get_ref().encode_to_vec(),
+            };
+            if let Err(e) = idempotency.complete(&key, stored).await {
+                error!("Failed to store idempotent response: {}", e);
+            }
+            Ok(response)
+        }
+        Err(status) => {
+            if let Err(e) = idempotency.release(&key).await {
+                error!("Failed to release idempotency key: {}", e);
+            }
+            Err(status)
+        }
+    }
+}
+
+/// 資格情報そのものは保存せず、ハッシュをキーの範囲として使う
+fn caller_scope<T>(request: &Request<T>) -> String {
+    match request.metadata().get(AUTHORIZATION_METADATA) {
+        Some(credential) => format!(
+            "credential:{}",
+            IdempotencyService::fingerprint(&[credential.as_bytes()])
+        ),
+        None => "anonymous".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repository::idempotency_repository::InMemoryIdempotencyRepository;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Echo {
+        #[prost(string, tag = "1")]
+        value: String,
+    }
+
+    fn request(value: &str) -> Request<Echo> {
+        let mut request = Request::new(Echo {
+            value: value.to_string(),
+        });
+        request.metadata_mut().insert(
+            IDEMPOTENCY_KEY_METADATA,
+            MetadataValue::from_static("grpc-1"),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_first_response() {
+        let service = IdempotencyService::new(Arc::new(InMemoryIdempotencyRepository::new()), 60);
+        let calls = AtomicUsize::new(0);
+        let handler = |request: Request<Echo>| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(Response::new(Echo {
+                    value: format!("{}-{}", request.into_inner().value, n),
+                }))
+            }
+        };
+
+        let first = idempotent(&service, "/test.Echo/Call", request("a"), handler)
+            .await
+            .unwrap();
+        let retry = idempotent(&service, "/test.Echo/Call", request("a"), handler)
+            .await
+            .unwrap();
+
+        assert_eq!(first.get_ref(), retry.get_ref());
+        assert!(retry.metadata().get(IDEMPOTENT_REPLAYED_METADATA).is_some());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let reused = idempotent(&service, "/test.Echo/Call", request("b"), handler).await;
+        assert_eq!(reused.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        // 別の資格情報を持つ呼び出し元は同じキーでも別の記録になる
+        let mut other = request("b");
+        other.metadata_mut().insert(
+            AUTHORIZATION_METADATA,
+            MetadataValue::from_static("Bearer other-client"),
+        );
+        let other = idempotent(&service, "/test.Echo/Call", other, handler)
+            .await
+            .unwrap();
+        assert!(other.metadata().get(IDEMPOTENT_REPLAYED_METADATA).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
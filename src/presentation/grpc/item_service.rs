@@ -3,8 +3,12 @@ use tonic::{Request, Response, Status};
 use tracing::info;
 
 use crate::app_domain::service::deletion_service::DeleteKind;
+use crate::application::dto::item_dto::{self, DeletionLogResponse, ItemResponse};
 use crate::application::service::deletion_facade::DeletionFacade;
+use crate::application::service::idempotency_service::IdempotencyService;
 use crate::application::service::item_service::ItemService;
+use crate::presentation::grpc::convert::{app_error_status, timestamp};
+use crate::presentation::grpc::idempotency::idempotent;
 
 // Include the generated proto code
 tonic::include_proto!("item");
@@ -15,13 +19,62 @@ pub use item_service_server::{ItemService as ItemServiceTrait, ItemServiceServer
 pub struct ItemServiceImpl {
     service: Arc<ItemService>,
     deletion_facade: Arc<DeletionFacade>,
+    idempotency: Arc<IdempotencyService>,
 }
 
 impl ItemServiceImpl {
-    pub fn new(service: Arc<ItemService>, deletion_facade: Arc<DeletionFacade>) -> Self {
+    pub fn new(
+        service: Arc<ItemService>,
+        deletion_facade: Arc<DeletionFacade>,
+        idempotency: Arc<IdempotencyService>,
+    ) -> Self {
         Self {
             service,
             deletion_facade,
+            idempotency,
+        }
+    }
+
+    /// 削除ファサード経由で削除・復元する（REST の削除系エンドポイントと同じ経路）
+    async fn delete(&self, id: u64, kind: DeleteKind) -> Result<(), Status> {
+        self.deletion_facade
+            .delete_item(id, kind)
+            .await
+            .map_err(|e| {
+                info!("gRPC: Error in {:?} delete of item {}: {}", kind, id, e);
+                app_error_status(e)
+            })
+    }
+}
+
+impl From<ItemResponse> for Item {
+    fn from(item: ItemResponse) -> Self {
+        Self {
+            id: item.id,
+            name: item.name,
+            description: item.description,
+            deleted: item.deleted,
+            deleted_at: item.deleted_at.map(timestamp),
+        }
+    }
+}
+
+impl From<DeletionLogResponse> for DeletionLog {
+    fn from(log: DeletionLogResponse) -> Self {
+        let deletion_type = match log.deletion_type.as_str() {
+            "Logical" => DeletionType::Logical,
+            "Physical" => DeletionType::Physical,
+            "Restore" => DeletionType::Restore,
+            _ => DeletionType::Unspecified,
+        };
+
+        Self {
+            id: log.id,
+            item_id: log.item_id,
+            item_name: log.item_name,
+            deletion_type: deletion_type as i32,
+            deleted_at: Some(timestamp(log.deleted_at)),
+            deleted_by: log.deleted_by,
         }
     }
 }
@@ -36,30 +89,15 @@ impl ItemServiceTrait for ItemServiceImpl {
             Ok(items) => {
                 info!("gRPC: Fetched {} items", items.len());
 
-                let grpc_items = items
-                    .into_iter()
-                    .map(|item| Item {
-                        id: item.id,
-                        name: item.name,
-                        description: item.description,
-                        deleted: item.deleted,
-                        deleted_at: item.deleted_at.map(|dt| prost_types::Timestamp {
-                            seconds: dt.timestamp(),
-                            nanos: dt.timestamp_subsec_nanos() as i32,
-                        }),
-                    })
-                    .collect();
-
-                let response = GetItemsResponse { items: grpc_items };
+                let response = GetItemsResponse {
+                    items: items.into_iter().map(Into::into).collect(),
+                };
 
                 Ok(Response::new(response))
             }
             Err(e) => {
                 info!("gRPC: Error fetching items: {}", e);
-                Err(Status::internal(format!(
-                    "アイテムの取得に失敗しました: {}",
-                    e
-                )))
+                Err(app_error_status(e))
             }
         }
     }
@@ -73,29 +111,16 @@ impl ItemServiceTrait for ItemServiceImpl {
         match self.service.find_by_id(req.id).await {
             Ok(item) => {
                 info!("gRPC: Fetched item {}", req.id);
-                let grpc_item = Item {
-                    id: item.id,
-                    name: item.name,
-                    description: item.description,
-                    deleted: item.deleted,
-                    deleted_at: item.deleted_at.map(|dt| prost_types::Timestamp {
-                        seconds: dt.timestamp(),
-                        nanos: dt.timestamp_subsec_nanos() as i32,
-                    }),
-                };
 
                 let response = GetItemResponse {
-                    item: Some(grpc_item),
+                    item: Some(item.into()),
                 };
 
                 Ok(Response::new(response))
             }
             Err(e) => {
                 info!("gRPC: Item {} not found or error: {}", req.id, e);
-                Err(Status::not_found(format!(
-                    "アイテムが見つかりません: {}",
-                    e
-                )))
+                Err(app_error_status(e))
             }
         }
     }
@@ -104,83 +129,72 @@ impl ItemServiceTrait for ItemServiceImpl {
         &self,
         request: Request<CreateItemRequest>,
     ) -> Result<Response<CreateItemResponse>, Status> {
-        let req = request.into_inner();
-
-        let create_request = crate::application::dto::item_dto::CreateItemRequest {
-            name: req.name,
-            description: req.description,
-        };
-
-        match self.service.create(create_request).await {
-            Ok(new_item) => {
-                info!("gRPC: Created item with id {}", new_item.id);
-
-                let grpc_item = Item {
-                    id: new_item.id,
-                    name: new_item.name,
-                    description: new_item.description,
-                    deleted: new_item.deleted,
-                    deleted_at: new_item.deleted_at.map(|dt| prost_types::Timestamp {
-                        seconds: dt.timestamp(),
-                        nanos: dt.timestamp_subsec_nanos() as i32,
-                    }),
+        idempotent(
+            &self.idempotency,
+            "/item.ItemService/CreateItem",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+
+                let create_request = item_dto::CreateItemRequest {
+                    name: req.name,
+                    description: req.description,
                 };
 
-                let response = CreateItemResponse {
-                    item: Some(grpc_item),
-                };
-
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error creating item: {}", e);
-                Err(Status::internal(format!(
-                    "アイテムの作成に失敗しました: {}",
-                    e
-                )))
-            }
-        }
+                match self.service.create(create_request).await {
+                    Ok(new_item) => {
+                        info!("gRPC: Created item with id {}", new_item.id);
+
+                        let response = CreateItemResponse {
+                            item: Some(new_item.into()),
+                        };
+
+                        Ok(Response::new(response))
+                    }
+                    Err(e) => {
+                        info!("gRPC: Error creating item: {}", e);
+                        Err(app_error_status(e))
+                    }
+                }
+            },
+        )
+        .await
     }
 
     async fn update_item(
         &self,
         request: Request<UpdateItemRequest>,
     ) -> Result<Response<UpdateItemResponse>, Status> {
-        let req = request.into_inner();
-
-        let update_request = crate::application::dto::item_dto::UpdateItemRequest {
-            name: req.name,
-            description: req.description,
-        };
-
-        match self.service.update(req.id, update_request).await {
-            Ok(updated_item) => {
-                info!("gRPC: Updated item {}", req.id);
-                let grpc_item = Item {
-                    id: updated_item.id,
-                    name: updated_item.name,
-                    description: updated_item.description,
-                    deleted: updated_item.deleted,
-                    deleted_at: updated_item.deleted_at.map(|dt| prost_types::Timestamp {
-                        seconds: dt.timestamp(),
-                        nanos: dt.timestamp_subsec_nanos() as i32,
-                    }),
-                };
-
-                let response = UpdateItemResponse {
-                    item: Some(grpc_item),
+        idempotent(
+            &self.idempotency,
+            "/item.ItemService/UpdateItem",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+
+                let update_request = item_dto::UpdateItemRequest {
+                    name: req.name,
+                    description: req.description,
                 };
 
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error updating item {}: {}", req.id, e);
-                Err(Status::internal(format!(
-                    "アイテムの更新に失敗しました: {}",
-                    e
-                )))
-            }
-        }
+                match self.service.update(req.id, update_request).await {
+                    Ok(updated_item) => {
+                        info!("gRPC: Updated item {}", req.id);
+
+                        let response = UpdateItemResponse {
+                            item: Some(updated_item.into()),
+                        };
+
+                        Ok(Response::new(response))
+                    }
+                    Err(e) => {
+                        info!("gRPC: Error updating item {}: {}", req.id, e);
+                        Err(app_error_status(e))
+                    }
+                }
+            },
+        )
+        .await
     }
 
     async fn delete_item(
@@ -189,48 +203,21 @@ impl ItemServiceTrait for ItemServiceImpl {
     ) -> Result<Response<DeleteItemResponse>, Status> {
         let req = request.into_inner();
 
-        match self
-            .deletion_facade
-            .delete_item(req.id, DeleteKind::Logical)
-            .await
-        {
-            Ok(_) => {
-                info!("gRPC: Deleted item {}", req.id);
-                let response = DeleteItemResponse { success: true };
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error deleting item {}: {}", req.id, e);
-                Err(Status::internal(format!(
-                    "アイテムの削除に失敗しました: {}",
-                    e
-                )))
-            }
-        }
+        // デフォルトで論理削除を使用
+        self.delete(req.id, DeleteKind::Logical).await?;
+        info!("gRPC: Deleted item {}", req.id);
+        Ok(Response::new(DeleteItemResponse { success: true }))
     }
 
-    // Simplified implementations for the complex deletion methods
     async fn logical_delete_item(
         &self,
         request: Request<LogicalDeleteItemRequest>,
     ) -> Result<Response<LogicalDeleteItemResponse>, Status> {
         let req = request.into_inner();
 
-        match self
-            .deletion_facade
-            .delete_item(req.id, DeleteKind::Logical)
-            .await
-        {
-            Ok(_) => {
-                info!("gRPC: Logical delete item {}", req.id);
-                let response = LogicalDeleteItemResponse { success: true };
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error logical deleting item {}: {}", req.id, e);
-                Err(Status::internal(format!("論理削除に失敗しました: {}", e)))
-            }
-        }
+        self.delete(req.id, DeleteKind::Logical).await?;
+        info!("gRPC: Logical delete item {}", req.id);
+        Ok(Response::new(LogicalDeleteItemResponse { success: true }))
     }
 
     async fn physical_delete_item(
@@ -239,21 +226,9 @@ impl ItemServiceTrait for ItemServiceImpl {
     ) -> Result<Response<PhysicalDeleteItemResponse>, Status> {
         let req = request.into_inner();
 
-        match self
-            .deletion_facade
-            .delete_item(req.id, DeleteKind::Physical)
-            .await
-        {
-            Ok(_) => {
-                info!("gRPC: Physical delete item {}", req.id);
-                let response = PhysicalDeleteItemResponse { success: true };
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error physical deleting item {}: {}", req.id, e);
-                Err(Status::internal(format!("物理削除に失敗しました: {}", e)))
-            }
-        }
+        self.delete(req.id, DeleteKind::Physical).await?;
+        info!("gRPC: Physical delete item {}", req.id);
+        Ok(Response::new(PhysicalDeleteItemResponse { success: true }))
     }
 
     async fn restore_item(
@@ -262,60 +237,114 @@ impl ItemServiceTrait for ItemServiceImpl {
     ) -> Result<Response<RestoreItemResponse>, Status> {
         let req = request.into_inner();
 
-        match self
-            .deletion_facade
-            .delete_item(req.id, DeleteKind::Restore)
-            .await
-        {
-            Ok(_) => {
-                info!("gRPC: Restored item {}", req.id);
-                let response = RestoreItemResponse { success: true };
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                info!("gRPC: Error restoring item {}: {}", req.id, e);
-                Err(Status::internal(format!("復元に失敗しました: {}", e)))
-            }
-        }
+        self.delete(req.id, DeleteKind::Restore).await?;
+        info!("gRPC: Restored item {}", req.id);
+        Ok(Response::new(RestoreItemResponse { success: true }))
     }
 
     async fn validate_item_deletion(
         &self,
-        _request: Request<ValidateItemDeletionRequest>,
+        request: Request<ValidateItemDeletionRequest>,
     ) -> Result<Response<ValidateItemDeletionResponse>, Status> {
-        // Validation not implemented yet
-        Err(Status::unimplemented("削除検証は未実装です"))
+        let req = request.into_inner();
+
+        let validation = self
+            .service
+            .validate_deletion(req.id)
+            .await
+            .map_err(app_error_status)?;
+        info!("gRPC: Validated deletion for item {}", req.id);
+
+        Ok(Response::new(ValidateItemDeletionResponse {
+            validation: Some(DeletionValidation {
+                can_delete: validation.can_delete,
+                related_data: Some(RelatedDataCount {
+                    related_orders: validation.related_orders,
+                    related_reviews: validation.related_reviews,
+                    related_categories: validation.related_categories,
+                }),
+            }),
+        }))
     }
 
     async fn batch_delete_items(
         &self,
-        _request: Request<BatchDeleteItemsRequest>,
+        request: Request<BatchDeleteItemsRequest>,
     ) -> Result<Response<BatchDeleteItemsResponse>, Status> {
-        // Batch delete not implemented yet
-        Err(Status::unimplemented("バッチ削除は未実装です"))
+        let req = request.into_inner();
+        let ids = req.ids.clone();
+
+        let result = self
+            .service
+            .batch_delete(item_dto::BatchDeleteRequest {
+                ids: req.ids,
+                is_physical: req.is_physical,
+            })
+            .await
+            .map_err(app_error_status)?;
+        info!("gRPC: Batch deleted {} items", result.successful_ids.len());
+
+        Ok(Response::new(BatchDeleteItemsResponse {
+            results: ids
+                .iter()
+                .map(|id| result.successful_ids.contains(id))
+                .collect(),
+            successful_ids: result.successful_ids,
+            failed_ids: result.failed_ids,
+        }))
     }
 
     async fn get_deleted_items(
         &self,
         _request: Request<GetDeletedItemsRequest>,
     ) -> Result<Response<GetDeletedItemsResponse>, Status> {
-        // Get deleted items not implemented yet
-        Err(Status::unimplemented("削除済みアイテム取得は未実装です"))
+        let items = self
+            .service
+            .find_deleted()
+            .await
+            .map_err(app_error_status)?;
+        info!("gRPC: Fetched {} deleted items", items.len());
+
+        Ok(Response::new(GetDeletedItemsResponse {
+            items: items.into_iter().map(Into::into).collect(),
+        }))
     }
 
     async fn get_item_deletion_log(
         &self,
-        _request: Request<GetItemDeletionLogRequest>,
+        request: Request<GetItemDeletionLogRequest>,
     ) -> Result<Response<GetItemDeletionLogResponse>, Status> {
-        // Deletion log not implemented yet
-        Err(Status::unimplemented("削除ログは未実装です"))
+        let req = request.into_inner();
+
+        let logs = self
+            .service
+            .get_deletion_logs(Some(req.id))
+            .await
+            .map_err(app_error_status)?;
+        info!(
+            "gRPC: Fetched {} deletion logs for item {}",
+            logs.len(),
+            req.id
+        );
+
+        Ok(Response::new(GetItemDeletionLogResponse {
+            logs: logs.into_iter().map(Into::into).collect(),
+        }))
     }
 
     async fn get_deletion_logs(
         &self,
         _request: Request<GetDeletionLogsRequest>,
     ) -> Result<Response<GetDeletionLogsResponse>, Status> {
-        // Deletion logs not implemented yet
-        Err(Status::unimplemented("削除ログ一覧は未実装です"))
+        let logs = self
+            .service
+            .get_deletion_logs(None)
+            .await
+            .map_err(app_error_status)?;
+        info!("gRPC: Fetched {} deletion logs", logs.len());
+
+        Ok(Response::new(GetDeletionLogsResponse {
+            logs: logs.into_iter().map(Into::into).collect(),
+        }))
     }
 }
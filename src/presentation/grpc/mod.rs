@@ -1,2 +1,46 @@
+// 変換処理はそのまま tonic::Status を返すため、gRPC 層ではエラー型のサイズを許容する
+#![allow(clippy::result_large_err)]
+
+pub mod auth;
+pub mod category_service;
+pub mod change_feed_service;
+pub mod convert;
+pub mod error_details;
+pub mod idempotency;
 pub mod item_service;
+pub mod product_service;
 pub mod user_service;
+
+/// サーバーリフレクション用にビルド時に生成した全サービスのディスクリプタ
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    tonic::include_file_descriptor_set!("rust_webapi_descriptor");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_descriptor_set_contains_all_services() {
+        let set = prost_types::FileDescriptorSet::decode(FILE_DESCRIPTOR_SET).unwrap();
+        let services: Vec<String> = set
+            .file
+            .iter()
+            .flat_map(|file| {
+                file.service
+                    .iter()
+                    .map(move |service| format!("{}.{}", file.package(), service.name()))
+            })
+            .collect();
+
+        for name in [
+            "user.UserService",
+            "item.ItemService",
+            "product.ProductService",
+            "category.CategoryService",
+            "changefeed.ChangeFeedService",
+        ] {
+            assert!(services.iter().any(|service| service == name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,882 @@
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+use crate::app_domain::model::product::{
+    ChangeContext, ProductError, ProductStatus as DomainProductStatus,
+};
+use crate::application::dto::product_dto::{self, ProductErrorResponse};
+use crate::application::service::idempotency_service::IdempotencyService;
+use crate::application::service::product_service::ProductService;
+use crate::presentation::grpc::auth::GrpcAuth;
+use crate::presentation::grpc::convert::{
+    datetime, decimal, invalid_field, optional_decimal, timestamp,
+};
+use crate::presentation::grpc::error_details::ErrorDetails;
+use crate::presentation::grpc::idempotency::idempotent;
+
+// Include the generated proto code
+tonic::include_proto!("product");
+
+pub use product_service_server::{ProductService as ProductServiceTrait, ProductServiceServer};
+
+/// 変更理由を受け取るメタデータ（HTTP の `X-Change-Reason` に相当）
+pub const CHANGE_REASON_METADATA: &str = "x-change-reason";
+
+#[derive(Clone)]
+pub struct ProductServiceImpl {
+    service: Arc<ProductService>,
+    idempotency: Arc<IdempotencyService>,
+    auth: GrpcAuth,
+}
+
+impl ProductServiceImpl {
+    pub fn new(
+        service: Arc<ProductService>,
+        idempotency: Arc<IdempotencyService>,
+        auth: GrpcAuth,
+    ) -> Self {
+        Self {
+            service,
+            idempotency,
+            auth,
+        }
+    }
+
+    /// 呼び出し元を認証し、メタデータから履歴用のコンテキストを組み立てる
+    ///
+    /// changed_by にはトークンの `sub` を記録する。
+    async fn change_context<T>(&self, request: &Request<T>) -> Result<ChangeContext, Status> {
+        let claims = self.auth.authenticate(request).await?;
+        let reason = request
+            .metadata()
+            .get(CHANGE_REASON_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+
+        Ok(ChangeContext::new(Some(claims.sub), reason))
+    }
+}
+
+/// 商品のドメインエラーを gRPC のステータスに変換する
+fn to_status(error: ProductError) -> Status {
+    let code = match &error {
+        ProductError::ProductNotFound
+        | ProductError::ImageNotFound
+        | ProductError::VariantNotFound
+        | ProductError::BundleNotFound
+        | ProductError::ScheduleNotFound
+        | ProductError::HistoryNotFound
+        | ProductError::ImportJobNotFound
+        | ProductError::TranslationNotFound => tonic::Code::NotFound,
+        ProductError::SkuAlreadyExists
+        | ProductError::SlugAlreadyExists
+        | ProductError::VariantCombinationDuplicate => tonic::Code::AlreadyExists,
+        ProductError::VersionMismatch { .. } => tonic::Code::Aborted,
+        ProductError::InvalidStatusTransition { .. }
+        | ProductError::ActivationRequirementsNotMet(_)
+        | ProductError::InsufficientInventory
+        | ProductError::UsedInBundle(_)
+        | ProductError::BulkCountMismatch { .. } => tonic::Code::FailedPrecondition,
+        ProductError::DatabaseError(_) => tonic::Code::Internal,
+        ProductError::InvalidName
+        | ProductError::InvalidSku
+        | ProductError::InvalidSlug
+        | ProductError::SlugRequired
+        | ProductError::InvalidPrice
+        | ProductError::InvalidPriceRelationship
+        | ProductError::InvalidInventoryQuantity
+        | ProductError::InvalidDimensions
+        | ProductError::InvalidWeight
+        | ProductError::InvalidShippingFee
+        | ProductError::InvalidAttributes(_)
+        | ProductError::InvalidTranslation(_)
+        | ProductError::TooManyImages
+        | ProductError::InvalidImageOrder
+        | ProductError::InvalidVariantOptions
+        | ProductError::InvalidBundle
+        | ProductError::InvalidSchedule
+        | ProductError::InvalidRollbackTarget(_)
+        | ProductError::InvalidImport(_)
+        | ProductError::InvalidExport(_)
+        | ProductError::InvalidFilter(_)
+        | ProductError::InvalidBulkUpdate(_) => tonic::Code::InvalidArgument,
+    };
+    let versions = match &error {
+        ProductError::VersionMismatch { expected, actual } => Some((*expected, *actual)),
+        _ => None,
+    };
+
+    // REST と同じエラーコードを reason に、項目の制約を BadRequest に載せる
+    let response: ProductErrorResponse = error.into();
+    let mut details = ErrorDetails::new(code, response.code, response.message.clone());
+    if let Some((expected, actual)) = versions {
+        details = details
+            .metadata("expected_version", expected)
+            .metadata("current_version", actual);
+    }
+    let violation = response
+        .details
+        .filter(|_| code == tonic::Code::InvalidArgument)
+        .and_then(|d| d.field.map(|field| (field, d.constraint)));
+    if let Some((field, constraint)) = violation {
+        details = details.field_violation(field, constraint.unwrap_or(response.message));
+    }
+    details.into_status()
+}
+
+/// 未指定（UNSPECIFIED）は `None` として扱う
+fn domain_status(value: i32) -> Result<Option<DomainProductStatus>, Status> {
+    match ProductStatus::try_from(value) {
+        Ok(ProductStatus::Unspecified) => Ok(None),
+        Ok(ProductStatus::Active) => Ok(Some(DomainProductStatus::Active)),
+        Ok(ProductStatus::Inactive) => Ok(Some(DomainProductStatus::Inactive)),
+        Ok(ProductStatus::Draft) => Ok(Some(DomainProductStatus::Draft)),
+        Ok(ProductStatus::Discontinued) => Ok(Some(DomainProductStatus::Discontinued)),
+        Err(_) => Err(invalid_field(
+            "status",
+            format!("不明な商品ステータスです: {}", value),
+        )),
+    }
+}
+
+impl From<DomainProductStatus> for ProductStatus {
+    fn from(status: DomainProductStatus) -> Self {
+        match status {
+            DomainProductStatus::Active => ProductStatus::Active,
+            DomainProductStatus::Inactive => ProductStatus::Inactive,
+            DomainProductStatus::Draft => ProductStatus::Draft,
+            DomainProductStatus::Discontinued => ProductStatus::Discontinued,
+        }
+    }
+}
+
+fn price_request(price: Price) -> Result<product_dto::PriceRequest, Status> {
+    Ok(product_dto::PriceRequest {
+        selling_price: decimal("selling_price", &price.selling_price)?,
+        list_price: optional_decimal("list_price", price.list_price)?,
+        discount_price: optional_decimal("discount_price", price.discount_price)?,
+        currency: price.currency,
+        tax_included: price.tax_included,
+        effective_from: price
+            .effective_from
+            .map(|ts| datetime("effective_from", ts))
+            .transpose()?,
+        effective_until: price
+            .effective_until
+            .map(|ts| datetime("effective_until", ts))
+            .transpose()?,
+    })
+}
+
+impl From<InventoryInput> for product_dto::InventoryRequest {
+    fn from(inventory: InventoryInput) -> Self {
+        Self {
+            quantity: inventory.quantity,
+            reserved_quantity: inventory.reserved_quantity,
+            alert_threshold: inventory.alert_threshold,
+            track_inventory: inventory.track_inventory,
+            allow_backorder: inventory.allow_backorder,
+        }
+    }
+}
+
+fn dimensions_request(dimensions: Dimensions) -> Result<product_dto::DimensionsRequest, Status> {
+    Ok(product_dto::DimensionsRequest {
+        width: decimal("width", &dimensions.width)?,
+        height: decimal("height", &dimensions.height)?,
+        depth: decimal("depth", &dimensions.depth)?,
+    })
+}
+
+fn shipping_info_request(
+    shipping_info: ShippingInfo,
+) -> Result<product_dto::ShippingInfoRequest, Status> {
+    Ok(product_dto::ShippingInfoRequest {
+        shipping_class: shipping_info.shipping_class,
+        free_shipping: shipping_info.free_shipping,
+        shipping_fee: decimal("shipping_fee", &shipping_info.shipping_fee)?,
+    })
+}
+
+impl From<product_dto::PriceResponse> for Price {
+    fn from(price: product_dto::PriceResponse) -> Self {
+        Self {
+            selling_price: price.selling_price.to_string(),
+            list_price: price.list_price.map(|p| p.to_string()),
+            discount_price: price.discount_price.map(|p| p.to_string()),
+            currency: price.currency,
+            tax_included: price.tax_included,
+            effective_from: price.effective_from.map(timestamp),
+            effective_until: price.effective_until.map(timestamp),
+        }
+    }
+}
+
+impl From<product_dto::InventoryResponse> for Inventory {
+    fn from(inventory: product_dto::InventoryResponse) -> Self {
+        Self {
+            quantity: inventory.quantity,
+            reserved_quantity: inventory.reserved_quantity,
+            alert_threshold: inventory.alert_threshold,
+            track_inventory: inventory.track_inventory,
+            allow_backorder: inventory.allow_backorder,
+        }
+    }
+}
+
+impl From<product_dto::ProductImageResponse> for ProductImage {
+    fn from(image: product_dto::ProductImageResponse) -> Self {
+        Self {
+            id: image.id,
+            url: image.url,
+            alt_text: image.alt_text,
+            sort_order: image.sort_order,
+            is_main: image.is_main,
+        }
+    }
+}
+
+impl From<product_dto::ProductResponse> for Product {
+    fn from(product: product_dto::ProductResponse) -> Self {
+        Self {
+            id: product.id,
+            name: product.name,
+            slug: product.slug,
+            description: product.description,
+            sku: product.sku,
+            brand: product.brand,
+            status: ProductStatus::from(product.status) as i32,
+            price: product.price.map(Into::into),
+            inventory: product.inventory.map(Into::into),
+            category_id: product.category_id,
+            tags: product.tags,
+            attributes: product.attributes,
+            images: product.images.into_iter().map(Into::into).collect(),
+            dimensions: product.dimensions.map(|d| Dimensions {
+                width: d.width.to_string(),
+                height: d.height.to_string(),
+                depth: d.depth.to_string(),
+            }),
+            weight: product.weight.map(|w| w.to_string()),
+            shipping_info: Some(ShippingInfo {
+                shipping_class: product.shipping_info.shipping_class,
+                free_shipping: product.shipping_info.free_shipping,
+                shipping_fee: product.shipping_info.shipping_fee.to_string(),
+            }),
+            created_at: Some(timestamp(product.created_at)),
+            updated_at: Some(timestamp(product.updated_at)),
+            version: product.version,
+        }
+    }
+}
+
+impl From<product_dto::ProductHistoryItem> for ProductHistoryEntry {
+    fn from(item: product_dto::ProductHistoryItem) -> Self {
+        Self {
+            id: item.id,
+            product_id: item.product_id,
+            field: item.field,
+            old_value: item.old_value,
+            new_value: item.new_value,
+            changed_by: item.changed_by,
+            changed_at: Some(timestamp(item.changed_at)),
+            reason: item.reason,
+        }
+    }
+}
+
+fn product_response(product: product_dto::ProductResponse) -> Response<ProductResponse> {
+    Response::new(ProductResponse {
+        product: Some(product.into()),
+    })
+}
+
+fn required<T>(field: &str, value: Option<T>) -> Result<T, Status> {
+    value.ok_or_else(|| invalid_field(field, format!("{} は必須です", field)))
+}
+
+#[tonic::async_trait]
+impl ProductServiceTrait for ProductServiceImpl {
+    async fn get_product(
+        &self,
+        request: Request<GetProductRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.service.find_by_id(&req.id).await {
+            Ok(product) => {
+                info!("gRPC: Fetched product {}", req.id);
+                Ok(product_response(product))
+            }
+            Err(e) => {
+                info!("gRPC: Product {} not found or error: {}", req.id, e);
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn get_product_by_sku(
+        &self,
+        request: Request<GetProductBySkuRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.service.find_by_sku(&req.sku).await {
+            Ok(product) => Ok(product_response(product)),
+            Err(e) => {
+                info!(
+                    "gRPC: Product with SKU {} not found or error: {}",
+                    req.sku, e
+                );
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn search_products(
+        &self,
+        request: Request<SearchProductsRequest>,
+    ) -> Result<Response<SearchProductsResponse>, Status> {
+        let req = request.into_inner();
+
+        let query = product_dto::ProductSearchQuery {
+            q: req.q,
+            category_id: req.category_id,
+            status: req.status,
+            tags: (!req.tags.is_empty()).then(|| req.tags.join(",")),
+            min_price: optional_decimal("min_price", req.min_price)?,
+            max_price: optional_decimal("max_price", req.max_price)?,
+            in_stock_only: Some(req.in_stock_only),
+            attributes: req.attributes,
+            limit: req.limit,
+            offset: req.offset,
+        };
+
+        let response = self.service.search(query).await.map_err(to_status)?;
+        info!("gRPC: Found {} products", response.products.len());
+
+        Ok(Response::new(SearchProductsResponse {
+            products: response.products.into_iter().map(Into::into).collect(),
+            total: response.total,
+            has_more: response.has_more,
+        }))
+    }
+
+    async fn create_product(
+        &self,
+        request: Request<CreateProductRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/CreateProduct",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Creating product with SKU {}", req.sku);
+
+                let create_request = product_dto::CreateProductRequest {
+                    name: req.name,
+                    description: req.description,
+                    sku: req.sku,
+                    brand: req.brand,
+                    status: required("status", domain_status(req.status)?)?,
+                    price: price_request(required("price", req.price)?)?,
+                    inventory: required("inventory", req.inventory)?.into(),
+                    category_id: req.category_id,
+                    tags: Some(req.tags),
+                    attributes: Some(req.attributes),
+                    dimensions: req.dimensions.map(dimensions_request).transpose()?,
+                    weight: optional_decimal("weight", req.weight)?,
+                    shipping_info: req.shipping_info.map(shipping_info_request).transpose()?,
+                    slug: req.slug,
+                };
+
+                match self.service.create(create_request, &ctx).await {
+                    Ok(product) => {
+                        info!("gRPC: Created product {}", product.id);
+                        Ok(product_response(product))
+                    }
+                    Err(e) => {
+                        error!("gRPC: Failed to create product: {}", e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn update_product(
+        &self,
+        request: Request<UpdateProductRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/UpdateProduct",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Updating product {}", req.id);
+
+                let update_request = product_dto::UpdateProductRequest {
+                    name: req.name,
+                    description: req.description,
+                    sku: req.sku,
+                    brand: req.brand,
+                    status: domain_status(req.status)?,
+                    price: req.price.map(price_request).transpose()?,
+                    inventory: req.inventory.map(Into::into),
+                    category_id: req.category_id,
+                    tags: None,
+                    attributes: None,
+                    dimensions: req.dimensions.map(dimensions_request).transpose()?,
+                    weight: optional_decimal("weight", req.weight)?,
+                    shipping_info: req.shipping_info.map(shipping_info_request).transpose()?,
+                    slug: req.slug,
+                };
+
+                match self
+                    .service
+                    .update(&req.id, update_request, req.expected_version, &ctx)
+                    .await
+                {
+                    Ok(product) => Ok(product_response(product)),
+                    Err(e) => {
+                        error!("gRPC: Failed to update product {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn patch_product(
+        &self,
+        request: Request<PatchProductRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/PatchProduct",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Patching product {}", req.id);
+
+                let price = product_dto::PricePatchRequest {
+                    selling_price: optional_decimal("selling_price", req.selling_price)?,
+                    list_price: optional_decimal("list_price", req.list_price)?,
+                    discount_price: optional_decimal("discount_price", req.discount_price)?,
+                };
+                let has_price = price.selling_price.is_some()
+                    || price.list_price.is_some()
+                    || price.discount_price.is_some();
+
+                let patch_request = product_dto::PatchProductRequest {
+                    name: req.name,
+                    description: req.description,
+                    price: has_price.then_some(price),
+                    inventory: req
+                        .quantity
+                        .map(|quantity| product_dto::InventoryPatchRequest {
+                            quantity: Some(quantity),
+                            reserved_quantity: None,
+                            alert_threshold: None,
+                            track_inventory: None,
+                            allow_backorder: None,
+                        }),
+                    status: domain_status(req.status)?,
+                    category_id: req.category_id,
+                };
+
+                match self
+                    .service
+                    .patch(&req.id, patch_request, req.expected_version, &ctx)
+                    .await
+                {
+                    Ok(product) => Ok(product_response(product)),
+                    Err(e) => {
+                        error!("gRPC: Failed to patch product {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn update_price(
+        &self,
+        request: Request<UpdatePriceRequest>,
+    ) -> Result<Response<PriceResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/UpdatePrice",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                let price = price_request(required("price", req.price)?)?;
+
+                match self.service.update_price(&req.id, price, &ctx).await {
+                    Ok(price) => Ok(Response::new(PriceResponse {
+                        price: Some(price.into()),
+                    })),
+                    Err(e) => {
+                        error!("gRPC: Failed to update price for product {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn update_inventory(
+        &self,
+        request: Request<UpdateInventoryRequest>,
+    ) -> Result<Response<InventoryResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/UpdateInventory",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                let inventory = required("inventory", req.inventory)?.into();
+
+                match self
+                    .service
+                    .update_inventory(&req.id, inventory, &ctx)
+                    .await
+                {
+                    Ok(inventory) => Ok(Response::new(InventoryResponse {
+                        inventory: Some(inventory.into()),
+                    })),
+                    Err(e) => {
+                        error!(
+                            "gRPC: Failed to update inventory for product {}: {}",
+                            req.id, e
+                        );
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn add_image(
+        &self,
+        request: Request<AddImageRequest>,
+    ) -> Result<Response<ImageResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/AddImage",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+
+                let image_request = product_dto::ProductImageRequest {
+                    url: req.url,
+                    alt_text: req.alt_text,
+                    sort_order: req.sort_order,
+                    is_main: req.is_main,
+                };
+
+                match self.service.add_image(&req.id, image_request, &ctx).await {
+                    Ok(image) => Ok(Response::new(ImageResponse {
+                        image: Some(image.into()),
+                    })),
+                    Err(e) => {
+                        error!("gRPC: Failed to add image to product {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn update_image(
+        &self,
+        request: Request<UpdateImageRequest>,
+    ) -> Result<Response<ImageResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/UpdateImage",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+
+                let image_request = product_dto::ProductImageRequest {
+                    url: req.url,
+                    alt_text: req.alt_text,
+                    sort_order: req.sort_order,
+                    is_main: req.is_main,
+                };
+
+                match self
+                    .service
+                    .update_image(&req.id, &req.image_id, image_request, &ctx)
+                    .await
+                {
+                    Ok(image) => Ok(Response::new(ImageResponse {
+                        image: Some(image.into()),
+                    })),
+                    Err(e) => {
+                        error!(
+                            "gRPC: Failed to update image {} of product {}: {}",
+                            req.image_id, req.id, e
+                        );
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn delete_image(
+        &self,
+        request: Request<DeleteImageRequest>,
+    ) -> Result<Response<EmptyResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        let req = request.into_inner();
+
+        match self
+            .service
+            .delete_image(&req.id, &req.image_id, &ctx)
+            .await
+        {
+            Ok(()) => Ok(Response::new(EmptyResponse {})),
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to delete image {} of product {}: {}",
+                    req.image_id, req.id, e
+                );
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn reorder_images(
+        &self,
+        request: Request<ReorderImagesRequest>,
+    ) -> Result<Response<EmptyResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        let req = request.into_inner();
+
+        let reorder_request = product_dto::ImageReorderRequest {
+            image_orders: req
+                .image_orders
+                .into_iter()
+                .map(|order| product_dto::ImageOrderItem {
+                    image_id: order.image_id,
+                    sort_order: order.sort_order,
+                })
+                .collect(),
+        };
+
+        match self
+            .service
+            .reorder_images(&req.id, reorder_request, &ctx)
+            .await
+        {
+            Ok(()) => Ok(Response::new(EmptyResponse {})),
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to reorder images of product {}: {}",
+                    req.id, e
+                );
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn set_main_image(
+        &self,
+        request: Request<SetMainImageRequest>,
+    ) -> Result<Response<EmptyResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        let req = request.into_inner();
+
+        match self
+            .service
+            .set_main_image(&req.id, &req.image_id, &ctx)
+            .await
+        {
+            Ok(()) => Ok(Response::new(EmptyResponse {})),
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to set main image {} of product {}: {}",
+                    req.image_id, req.id, e
+                );
+                Err(to_status(e))
+            }
+        }
+    }
+
+    async fn set_tags(
+        &self,
+        request: Request<SetTagsRequest>,
+    ) -> Result<Response<ProductResponse>, Status> {
+        let ctx = self.change_context(&request).await?;
+        idempotent(
+            &self.idempotency,
+            "/product.ProductService/SetTags",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Replacing tags of product {}", req.id);
+
+                let update_request = product_dto::UpdateProductRequest {
+                    name: None,
+                    description: None,
+                    sku: None,
+                    brand: None,
+                    status: None,
+                    price: None,
+                    inventory: None,
+                    category_id: None,
+                    tags: Some(req.tags),
+                    attributes: None,
+                    dimensions: None,
+                    weight: None,
+                    shipping_info: None,
+                    slug: None,
+                };
+
+                match self
+                    .service
+                    .update(&req.id, update_request, req.expected_version, &ctx)
+                    .await
+                {
+                    Ok(product) => Ok(product_response(product)),
+                    Err(e) => {
+                        error!("gRPC: Failed to set tags of product {}: {}", req.id, e);
+                        Err(to_status(e))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn get_history(
+        &self,
+        request: Request<GetHistoryRequest>,
+    ) -> Result<Response<GetHistoryResponse>, Status> {
+        let req = request.into_inner();
+
+        let query = product_dto::ProductHistoryQuery {
+            field: req.field,
+            limit: req.limit,
+            offset: req.offset,
+        };
+
+        match self.service.get_history(&req.id, query).await {
+            Ok(response) => Ok(Response::new(GetHistoryResponse {
+                history: response.history.into_iter().map(Into::into).collect(),
+                total: response.total,
+                has_more: response.has_more,
+            })),
+            Err(e) => {
+                info!("gRPC: Failed to fetch history of product {}: {}", req.id, e);
+                Err(to_status(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::grpc::error_details::{decode, find, rpc};
+
+    #[test]
+    fn test_domain_errors_map_to_status_codes() {
+        let cases = [
+            (ProductError::ProductNotFound, tonic::Code::NotFound),
+            (ProductError::SkuAlreadyExists, tonic::Code::AlreadyExists),
+            (
+                ProductError::VersionMismatch {
+                    expected: 1,
+                    actual: 2,
+                },
+                tonic::Code::Aborted,
+            ),
+            (
+                ProductError::InsufficientInventory,
+                tonic::Code::FailedPrecondition,
+            ),
+            (ProductError::InvalidPrice, tonic::Code::InvalidArgument),
+            (
+                ProductError::DatabaseError("down".to_string()),
+                tonic::Code::Internal,
+            ),
+        ];
+        for (error, code) in cases {
+            assert_eq!(to_status(error).code(), code);
+        }
+    }
+
+    #[test]
+    fn test_error_details_use_rest_error_codes() {
+        let details = decode(&to_status(ProductError::InvalidPrice)).unwrap();
+        let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+        assert_eq!(info.reason, "INVALID_PRICE_RANGE");
+        let bad_request: rpc::BadRequest = find(&details, "BadRequest").unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "price");
+
+        let details = decode(&to_status(ProductError::VersionMismatch {
+            expected: 1,
+            actual: 2,
+        }))
+        .unwrap();
+        let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+        assert_eq!(info.metadata["expected_version"], "1");
+        assert_eq!(info.metadata["current_version"], "2");
+        assert!(find::<rpc::BadRequest>(&details, "BadRequest").is_none());
+    }
+
+    #[test]
+    fn test_unspecified_status_means_unchanged() {
+        assert_eq!(
+            domain_status(ProductStatus::Unspecified as i32).unwrap(),
+            None
+        );
+        assert_eq!(
+            domain_status(ProductStatus::Draft as i32).unwrap(),
+            Some(DomainProductStatus::Draft)
+        );
+        assert_eq!(
+            domain_status(42).unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_price_request_parses_decimals() {
+        let price = price_request(Price {
+            selling_price: "1200".to_string(),
+            list_price: Some("1500".to_string()),
+            discount_price: None,
+            currency: "JPY".to_string(),
+            tax_included: true,
+            effective_from: None,
+            effective_until: None,
+        })
+        .unwrap();
+        assert_eq!(price.selling_price.to_string(), "1200");
+        assert_eq!(price.list_price.unwrap().to_string(), "1500");
+    }
+}
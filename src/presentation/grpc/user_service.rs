@@ -1,8 +1,11 @@
 use std::sync::Arc;
-use tonic::{Request, Response, Status};
+use tonic::{Code, Request, Response, Status};
 use tracing::{error, info};
 
+use crate::application::service::idempotency_service::IdempotencyService;
 use crate::application::service::user_service::UserService;
+use crate::presentation::grpc::error_details::ErrorDetails;
+use crate::presentation::grpc::idempotency::idempotent;
 
 // Include the generated proto code
 tonic::include_proto!("user");
@@ -12,14 +15,26 @@ pub use user_service_server::{UserService as UserServiceTrait, UserServiceServer
 #[derive(Clone)]
 pub struct UserServiceImpl {
     service: Arc<UserService>,
+    idempotency: Arc<IdempotencyService>,
 }
 
 impl UserServiceImpl {
-    pub fn new(service: Arc<UserService>) -> Self {
-        Self { service }
+    pub fn new(service: Arc<UserService>, idempotency: Arc<IdempotencyService>) -> Self {
+        Self {
+            service,
+            idempotency,
+        }
     }
 }
 
+fn not_found() -> Status {
+    ErrorDetails::new(Code::NotFound, "NOT_FOUND", "User not found").into_status()
+}
+
+fn internal(message: &str) -> Status {
+    ErrorDetails::new(Code::Internal, "INTERNAL_SERVER_ERROR", message).into_status()
+}
+
 #[tonic::async_trait]
 impl UserServiceTrait for UserServiceImpl {
     async fn get_users(
@@ -46,7 +61,7 @@ impl UserServiceTrait for UserServiceImpl {
             }
             Err(err) => {
                 error!("gRPC: Failed to fetch users: {}", err);
-                Err(Status::internal("Failed to fetch users"))
+                Err(internal("Failed to fetch users"))
             }
         }
     }
@@ -74,11 +89,11 @@ impl UserServiceTrait for UserServiceImpl {
             Err(err) => match err {
                 crate::infrastructure::error::AppError::NotFound(_) => {
                     info!("gRPC: User {} not found", req.id);
-                    Err(Status::not_found("User not found"))
+                    Err(not_found())
                 }
                 _ => {
                     error!("gRPC: Failed to get user {}: {}", req.id, err);
-                    Err(Status::internal("Failed to get user"))
+                    Err(internal("Failed to get user"))
                 }
             },
         }
@@ -88,71 +103,87 @@ impl UserServiceTrait for UserServiceImpl {
         &self,
         request: Request<CreateUserRequest>,
     ) -> Result<Response<CreateUserResponse>, Status> {
-        let req = request.into_inner();
-        info!("gRPC: Creating user with username {}", req.username);
-
-        let create_request = crate::application::dto::user_dto::CreateUserRequest {
-            username: req.username,
-            email: req.email,
-        };
-
-        match self.service.create(create_request).await {
-            Ok(new_user) => {
-                info!("gRPC: Created user with id {}", new_user.id);
-
-                let user_proto = Some(User {
-                    id: new_user.id,
-                    username: new_user.username,
-                    email: new_user.email,
-                });
-
-                let response = CreateUserResponse { user: user_proto };
-                Ok(Response::new(response))
-            }
-            Err(err) => {
-                error!("gRPC: Failed to create user: {}", err);
-                Err(Status::internal("Failed to create user"))
-            }
-        }
+        idempotent(
+            &self.idempotency,
+            "/user.UserService/CreateUser",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Creating user with username {}", req.username);
+
+                let create_request = crate::application::dto::user_dto::CreateUserRequest {
+                    username: req.username,
+                    email: req.email,
+                };
+
+                match self.service.create(create_request).await {
+                    Ok(new_user) => {
+                        info!("gRPC: Created user with id {}", new_user.id);
+
+                        let user_proto = Some(User {
+                            id: new_user.id,
+                            username: new_user.username,
+                            email: new_user.email,
+                        });
+
+                        let response = CreateUserResponse { user: user_proto };
+                        Ok(Response::new(response))
+                    }
+                    Err(err) => {
+                        error!("gRPC: Failed to create user: {}", err);
+                        Err(internal("Failed to create user"))
+                    }
+                }
+            },
+        )
+        .await
     }
 
     async fn update_user(
         &self,
         request: Request<UpdateUserRequest>,
     ) -> Result<Response<UpdateUserResponse>, Status> {
-        let req = request.into_inner();
-        info!("gRPC: Updating user {}", req.id);
-
-        // gRPCのUpdateUserRequestはOption<String>フィールドを持つ
-        let update_request = crate::application::dto::user_dto::UpdateUserRequest {
-            username: req.username.filter(|s| !s.is_empty()),
-            email: req.email.filter(|s| !s.is_empty()),
-        };
-
-        match self.service.update(req.id, update_request).await {
-            Ok(updated_user) => {
-                info!("gRPC: Updated user {}", req.id);
-
-                let user_proto = Some(User {
-                    id: updated_user.id,
-                    username: updated_user.username,
-                    email: updated_user.email,
-                });
-
-                let response = UpdateUserResponse { user: user_proto };
-                Ok(Response::new(response))
-            }
-            Err(err) => match err {
-                crate::infrastructure::error::AppError::NotFound(_) => {
-                    info!("gRPC: User {} not found for update", req.id);
-                    Err(Status::not_found("User not found"))
-                }
-                _ => {
-                    error!("gRPC: Failed to update user {}: {}", req.id, err);
-                    Err(Status::internal("Failed to update user"))
+        idempotent(
+            &self.idempotency,
+            "/user.UserService/UpdateUser",
+            request,
+            |request| async move {
+                let req = request.into_inner();
+                info!("gRPC: Updating user {}", req.id);
+
+                // gRPCのUpdateUserRequestはOption<String>フィールドを持つ
+                let update_request = crate::application::dto::user_dto::UpdateUserRequest {
+                    username: req.username.filter(|s| !s.is_empty()),
+                    email: req.email.filter(|s| !s.is_empty()),
+                };
+
+                match self.service.update(req.id, update_request).await {
+                    Ok(updated_user) => {
+                        info!("gRPC: Updated user {}", req.id);
+
+                        let user_proto = Some(User {
+                            id: updated_user.id,
+                            username: updated_user.username,
+                            email: updated_user.email,
+                        });
+
+                        let response = UpdateUserResponse { user: user_proto };
+                        Ok(Response::new(response))
+                    }
+                    Err(err) => match err {
+                        crate::infrastructure::error::AppError::NotFound(_) => {
+                            info!("gRPC: User {} not found for update", req.id);
+                            Err(not_found())
+                        }
+                        _ => {
+                            error!("gRPC: Failed to update user {}: {}", req.id, err);
+                            Err(internal("Failed to update user"))
+                        }
+                    },
                 }
             },
-        }
+        )
+        .await
     }
 
     async fn delete_user(
@@ -171,11 +202,11 @@ impl UserServiceTrait for UserServiceImpl {
             Err(err) => match err {
                 crate::infrastructure::error::AppError::NotFound(_) => {
                     info!("gRPC: User {} not found for deletion", req.id);
-                    Err(Status::not_found("User not found"))
+                    Err(not_found())
                 }
                 _ => {
                     error!("gRPC: Failed to delete user {}: {}", req.id, err);
-                    Err(Status::internal("Failed to delete user"))
+                    Err(internal("Failed to delete user"))
                 }
             },
         }
@@ -0,0 +1,189 @@
+//! 変更フィードを gRPC (WatchChanges) と SSE (/api/changes) の両方で購読するテスト
+//!
+//! 同じフィードに接続したアイテムサービスで作成・削除・復元を行い、
+//! 両方のトランスポートに同じ順序・同じカーソルで届くことを検証する。
+
+use actix_web::body::MessageBody;
+use actix_web::{test, web, App};
+use futures::StreamExt;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+
+use rust_webapi::app_domain::service::deletion_service::DeleteKind;
+use rust_webapi::application::dto::item_dto::{CreateItemRequest, UpdateItemRequest};
+use rust_webapi::application::service::change_feed::ChangeFeed;
+use rust_webapi::application::service::deletion_facade::DeletionFacade;
+use rust_webapi::application::service::item_service::ItemService;
+use rust_webapi::infrastructure::repository::item_repository::InMemoryItemRepository;
+use rust_webapi::presentation::api::change_handler::ChangeHandler;
+use rust_webapi::presentation::grpc::change_feed_service::{
+    change_event, change_feed_service_client::ChangeFeedServiceClient, watch_changes_response,
+    ChangeFeedServiceImpl, ChangeFeedServiceServer, ChangeKind, EntityType, WatchChangesRequest,
+};
+
+const HEARTBEAT: Duration = Duration::from_secs(60);
+
+struct Fixture {
+    feed: Arc<ChangeFeed>,
+    items: Arc<ItemService>,
+    deletion_facade: DeletionFacade,
+    grpc: ChangeFeedServiceClient<tonic::transport::Channel>,
+}
+
+async fn fixture() -> Fixture {
+    let feed = Arc::new(ChangeFeed::new(100));
+    let repository = Arc::new(InMemoryItemRepository::new());
+    let items = Arc::new(ItemService::new(repository.clone()).with_change_feed(feed.clone()));
+
+    let deletion_facade = DeletionFacade::new(repository).with_change_feed(feed.clone());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(ChangeFeedServiceServer::new(ChangeFeedServiceImpl::new(
+                feed.clone(),
+                HEARTBEAT,
+            )))
+            .serve_with_incoming(incoming),
+    );
+    let grpc = ChangeFeedServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+
+    Fixture {
+        feed,
+        items,
+        deletion_facade,
+        grpc,
+    }
+}
+
+/// SSE の 1 フレームから `id` と `data` を取り出す
+async fn next_sse_event(body: &mut actix_web::body::BoxBody) -> (String, Value) {
+    let chunk = futures::future::poll_fn(|cx| std::pin::Pin::new(&mut *body).poll_next(cx))
+        .await
+        .unwrap()
+        .unwrap();
+    let frame = String::from_utf8(chunk.to_vec()).unwrap();
+    assert!(frame.contains("\nevent: change\n"), "{}", frame);
+
+    let field = |name: &str| {
+        frame
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap()
+            .to_string()
+    };
+    (
+        field("id: "),
+        serde_json::from_str(&field("data: ")).unwrap(),
+    )
+}
+
+#[actix_web::test]
+async fn test_item_changes_reach_grpc_and_sse_subscribers() {
+    let mut f = fixture().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(ChangeHandler::new(
+                f.feed.clone(),
+                HEARTBEAT,
+            )))
+            .route("/api/changes", web::get().to(ChangeHandler::watch_changes)),
+    )
+    .await;
+
+    let item = f
+        .items
+        .create(CreateItemRequest {
+            name: "Desk".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    f.items
+        .update(
+            item.id,
+            UpdateItemRequest {
+                name: Some("Standing desk".to_string()),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    f.deletion_facade
+        .delete_item(item.id, DeleteKind::Logical)
+        .await
+        .unwrap();
+    f.deletion_facade
+        .delete_item(item.id, DeleteKind::Restore)
+        .await
+        .unwrap();
+
+    // どちらも先頭から再開して同じ 4 件を受け取る
+    let epoch = f.feed.epoch();
+    let mut grpc = f
+        .grpc
+        .watch_changes(WatchChangesRequest {
+            after_epoch: Some(epoch),
+            after_sequence: Some(0),
+            entity_types: vec![EntityType::Item as i32],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/changes?after={}-0&entity_types=item", epoch))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let mut sse = resp.into_body();
+
+    let expected = [
+        (ChangeKind::Created, "created"),
+        (ChangeKind::Updated, "updated"),
+        (ChangeKind::Deleted, "deleted"),
+        (ChangeKind::Restored, "restored"),
+    ];
+    for (sequence, (grpc_kind, sse_kind)) in (1..).zip(expected) {
+        let event = match grpc.next().await.unwrap().unwrap().message {
+            Some(watch_changes_response::Message::Event(event)) => event,
+            other => panic!("unexpected message: {:?}", other),
+        };
+        let (id, data) = next_sse_event(&mut sse).await;
+
+        assert_eq!((event.epoch, event.sequence), (epoch, sequence));
+        assert_eq!(id, format!("{}-{}", epoch, sequence));
+        assert_eq!(event.kind, grpc_kind as i32);
+        assert_eq!(data["kind"], sse_kind);
+        assert_eq!(event.entity_id, item.id.to_string());
+        assert_eq!(data["entity_id"], item.id.to_string());
+    }
+
+    // 作成・更新イベントには変更後の状態が付く
+    let mut grpc = f
+        .grpc
+        .watch_changes(WatchChangesRequest {
+            after_epoch: Some(epoch),
+            after_sequence: Some(1),
+            entity_types: Vec::new(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    match grpc.next().await.unwrap().unwrap().message {
+        Some(watch_changes_response::Message::Event(event)) => match event.payload {
+            Some(change_event::Payload::Item(snapshot)) => {
+                assert_eq!(snapshot.name, "Standing desk")
+            }
+            other => panic!("unexpected payload: {:?}", other),
+        },
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
@@ -1,9 +1,12 @@
 mod helpers;
 
 use domain::model::item::Item;
+use mockall::predicate::eq;
+use rust_webapi::app_domain::model::category::{Category, CategoryError};
+use rust_webapi::app_domain::repository::category_repository::MockCategoryRepository;
 use rust_webapi::app_domain::repository::item_repository::ItemRepository;
 use rust_webapi::app_domain::service::deletion_service::{
-    DeleteKind, DeletionError, DeletionStrategy, ItemDeletionStrategy,
+    CategoryDeletionStrategy, DeleteKind, DeletionError, DeletionStrategy, ItemDeletionStrategy,
 };
 use rust_webapi::infrastructure::repository::item_repository::InMemoryItemRepository;
 use std::sync::Arc;
@@ -265,3 +268,54 @@ async fn test_deletion_strategy_boundary_conditions() {
     let result = strategy.delete(u64::MAX, DeleteKind::Logical).await;
     assert!(result.is_err(), "MAX ID should fail");
 }
+
+/// Category DeletionStrategy: 期待するバージョンの照合
+#[tokio::test]
+async fn test_category_deletion_strategy_checks_expected_version() {
+    let category = Category::new("cat_1".to_string(), "Books".to_string(), None, None, 0);
+    let version = category.version;
+
+    let mut repository = MockCategoryRepository::new();
+    repository
+        .expect_find_by_id()
+        .with(eq("cat_1"))
+        .returning(move |_| Some(category.clone()));
+    repository.expect_update().times(1).returning(Ok);
+    repository
+        .expect_delete()
+        .withf(move |id, expected, _| id == "cat_1" && *expected == Some(version + 1))
+        .returning(|_, expected, _| {
+            Err(CategoryError::VersionMismatch(format!("{:?}", expected)))
+        });
+    let strategy = CategoryDeletionStrategy::new(Arc::new(repository));
+
+    // 古いバージョンでは論理削除・物理削除・復元のいずれも行わない
+    for kind in [DeleteKind::Logical, DeleteKind::Physical, DeleteKind::Restore] {
+        let result = strategy
+            .delete_with_version("cat_1".to_string(), kind, Some(version + 1))
+            .await;
+        assert!(
+            matches!(result, Err(DeletionError::VersionMismatch(_))),
+            "{:?}",
+            kind
+        );
+    }
+
+    // 現在のバージョンなら論理削除する
+    let result = strategy
+        .delete_with_version("cat_1".to_string(), DeleteKind::Logical, Some(version))
+        .await;
+    assert!(result.is_ok());
+}
+
+/// バージョンを持たない Item はバージョンの指定を受け付けない
+#[tokio::test]
+async fn test_item_deletion_strategy_rejects_expected_version() {
+    let repository = Arc::new(InMemoryItemRepository::new());
+    let strategy = ItemDeletionStrategy::new(repository);
+
+    let result = strategy
+        .delete_with_version(1, DeleteKind::Logical, Some(1))
+        .await;
+    assert!(matches!(result, Err(DeletionError::Validation(_))));
+}
@@ -2,6 +2,7 @@ use chrono::Utc;
 use domain::model::item::Item;
 use mockall::predicate::*;
 use rust_webapi::app_domain::model::category::{Category, CategoryError};
+use rust_webapi::app_domain::model::slug::slugify;
 use rust_webapi::app_domain::repository::category_repository::MockCategoryRepository;
 use rust_webapi::app_domain::repository::item_repository::MockItemRepository;
 use rust_webapi::infrastructure::error::{AppError, AppResult};
@@ -254,12 +255,14 @@ impl TestDataFactory {
         Category {
             id: id.to_string(),
             name: name.to_string(),
+            slug: slugify(name).unwrap_or_default(),
             description: Some(format!("Description for {}", name)),
             parent_id: None,
             sort_order: 1,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         }
     }
 
@@ -0,0 +1,303 @@
+//! REST と gRPC のアイテム API が同じ結果を返すことを確認するテスト
+//!
+//! 両方のトランスポートを同じインメモリリポジトリに接続し、
+//! 一方で行った削除・復元がもう一方から同じように見えることを検証する。
+
+use actix_web::{test, web, App};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Channel, Server};
+
+use rust_webapi::application::dto::item_dto::CreateItemRequest;
+use rust_webapi::application::service::deletion_facade::DeletionFacade;
+use rust_webapi::application::service::idempotency_service::IdempotencyService;
+use rust_webapi::application::service::item_service::ItemService;
+use rust_webapi::infrastructure::config::BodyLimitConfig;
+use rust_webapi::infrastructure::di::server::configure_api_routes;
+use rust_webapi::infrastructure::repository::idempotency_repository::InMemoryIdempotencyRepository;
+use rust_webapi::infrastructure::repository::item_repository::InMemoryItemRepository;
+use rust_webapi::presentation::api::item_handler::ItemHandler;
+use rust_webapi::presentation::grpc::error_details::{decode, find, rpc};
+use rust_webapi::presentation::grpc::item_service::{
+    item_service_client::ItemServiceClient, BatchDeleteItemsRequest, DeletionType,
+    GetDeletedItemsRequest, GetDeletionLogsRequest, GetItemDeletionLogRequest, GetItemRequest,
+    ItemServiceImpl, ItemServiceServer, LogicalDeleteItemRequest, RestoreItemRequest,
+    ValidateItemDeletionRequest,
+};
+
+struct Fixture {
+    service: Arc<ItemService>,
+    handler: web::Data<ItemHandler>,
+    grpc: ItemServiceClient<Channel>,
+}
+
+/// 同じリポジトリを使う REST ハンドラーと gRPC サーバー（ローカルポート）を用意する
+async fn fixture() -> Fixture {
+    let repository = Arc::new(InMemoryItemRepository::new());
+    let service = Arc::new(ItemService::new(repository.clone()));
+
+    let deletion_facade = Arc::new(DeletionFacade::new(repository));
+    let idempotency = Arc::new(IdempotencyService::new(
+        Arc::new(InMemoryIdempotencyRepository::new()),
+        60,
+    ));
+
+    let handler = web::Data::new(ItemHandler::new(service.clone(), deletion_facade.clone()));
+    let grpc_service = ItemServiceImpl::new(service.clone(), deletion_facade, idempotency);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(ItemServiceServer::new(grpc_service))
+            .serve_with_incoming(incoming),
+    );
+    let grpc = ItemServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+
+    Fixture {
+        service,
+        handler,
+        grpc,
+    }
+}
+
+/// server.rs と同じルート表を登録する
+fn routes(cfg: &mut web::ServiceConfig) {
+    configure_api_routes(
+        cfg,
+        &BodyLimitConfig {
+            default_bytes: 256 * 1024,
+            batch_bytes: 1024 * 1024,
+            import_bytes: 10 * 1024 * 1024,
+        },
+    );
+}
+
+async fn create_items(service: &ItemService, names: &[&str]) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for name in names {
+        let item = service
+            .create(CreateItemRequest {
+                name: name.to_string(),
+                description: None,
+            })
+            .await
+            .unwrap();
+        ids.push(item.id);
+    }
+    ids
+}
+
+fn sorted(mut ids: Vec<u64>) -> Vec<u64> {
+    ids.sort();
+    ids
+}
+
+fn json_ids(value: &Value, field: &str) -> Vec<u64> {
+    sorted(
+        value[field]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|id| id.as_u64().unwrap())
+            .collect(),
+    )
+}
+
+#[actix_web::test]
+async fn test_validate_deletion_matches_rest() {
+    let mut f = fixture().await;
+    let app = test::init_service(App::new().app_data(f.handler.clone()).configure(routes)).await;
+    let ids = create_items(&f.service, &["Desk"]).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{}/deletion-check", ids[0]))
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+
+    let grpc = f
+        .grpc
+        .validate_item_deletion(ValidateItemDeletionRequest { id: ids[0] })
+        .await
+        .unwrap()
+        .into_inner()
+        .validation
+        .unwrap();
+    let related = grpc.related_data.unwrap();
+
+    assert_eq!(rest["can_delete"], grpc.can_delete);
+    assert_eq!(rest["related_orders"], related.related_orders);
+    assert_eq!(rest["related_reviews"], related.related_reviews);
+    assert_eq!(rest["related_categories"], related.related_categories);
+
+    // 存在しないアイテムは REST では 404、gRPC では NOT_FOUND
+    let req = test::TestRequest::get()
+        .uri("/api/items/999/deletion-check")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    let status = f
+        .grpc
+        .validate_item_deletion(ValidateItemDeletionRequest { id: 999 })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    // エラー詳細の reason は REST の error.type と対応する
+    let details = decode(&status).unwrap();
+    let info: rpc::ErrorInfo = find(&details, "ErrorInfo").unwrap();
+    assert_eq!(info.reason, "NOT_FOUND");
+}
+
+#[actix_web::test]
+async fn test_batch_delete_over_both_transports() {
+    let mut f = fixture().await;
+    let app = test::init_service(App::new().app_data(f.handler.clone()).configure(routes)).await;
+    let ids = create_items(&f.service, &["A", "B", "C", "D"]).await;
+
+    // REST で 2 件（うち 1 件は存在しない）、gRPC で 2 件（うち 1 件は REST で削除済み）
+    let req = test::TestRequest::delete()
+        .uri("/api/items/batch")
+        .set_json(serde_json::json!({ "ids": [ids[0], ids[1], 999] }))
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(json_ids(&rest, "successful_ids"), vec![ids[0], ids[1]]);
+    assert_eq!(json_ids(&rest, "failed_ids"), vec![999]);
+
+    let grpc = f
+        .grpc
+        .batch_delete_items(BatchDeleteItemsRequest {
+            ids: vec![ids[2], ids[1]],
+            is_physical: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(grpc.results, vec![true, false]);
+    assert_eq!(grpc.successful_ids, vec![ids[2]]);
+    assert_eq!(grpc.failed_ids, vec![ids[1]]);
+
+    // 削除済みアイテムはどちらから見ても同じ
+    let req = test::TestRequest::get()
+        .uri("/api/items/deleted")
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+    let rest_deleted = sorted(
+        rest.as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_u64().unwrap())
+            .collect(),
+    );
+    let grpc_deleted = f
+        .grpc
+        .get_deleted_items(GetDeletedItemsRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .items;
+    assert!(grpc_deleted.iter().all(|item| item.deleted));
+    assert_eq!(
+        sorted(grpc_deleted.iter().map(|item| item.id).collect()),
+        rest_deleted
+    );
+    assert_eq!(rest_deleted, vec![ids[0], ids[1], ids[2]]);
+}
+
+#[actix_web::test]
+async fn test_deletion_logs_match_rest() {
+    let mut f = fixture().await;
+    let app = test::init_service(App::new().app_data(f.handler.clone()).configure(routes)).await;
+    let ids = create_items(&f.service, &["Lamp", "Chair"]).await;
+
+    // gRPC で論理削除し、REST で復元する
+    f.grpc
+        .logical_delete_item(LogicalDeleteItemRequest { id: ids[0] })
+        .await
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/items/{}/restore", ids[0]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{}/logical", ids[1]))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    f.grpc
+        .restore_item(RestoreItemRequest { id: ids[1] })
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/api/deletion-logs")
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+    let grpc = f
+        .grpc
+        .get_deletion_logs(GetDeletionLogsRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .logs;
+
+    let rest = rest.as_array().unwrap();
+    assert_eq!(rest.len(), 4);
+    assert_eq!(grpc.len(), rest.len());
+    for (rest_log, grpc_log) in rest.iter().zip(&grpc) {
+        assert_eq!(rest_log["id"], grpc_log.id);
+        assert_eq!(rest_log["item_id"], grpc_log.item_id);
+        assert_eq!(rest_log["item_name"], grpc_log.item_name.as_str());
+        assert_eq!(rest_log["deleted_by"], grpc_log.deleted_by.as_str());
+        let deletion_type = DeletionType::try_from(grpc_log.deletion_type).unwrap();
+        let expected = match rest_log["deletion_type"].as_str().unwrap() {
+            "Logical" => DeletionType::Logical,
+            "Physical" => DeletionType::Physical,
+            _ => DeletionType::Restore,
+        };
+        assert_eq!(deletion_type, expected);
+    }
+
+    // アイテム単位のログ（新しい順）
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{}/deletion-log", ids[0]))
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+    let grpc = f
+        .grpc
+        .get_item_deletion_log(GetItemDeletionLogRequest { id: ids[0] })
+        .await
+        .unwrap()
+        .into_inner()
+        .logs;
+    let rest_types: Vec<&str> = rest
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|log| log["deletion_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(rest_types, vec!["Restore", "Logical"]);
+    assert_eq!(
+        grpc.iter().map(|log| log.deletion_type).collect::<Vec<_>>(),
+        vec![DeletionType::Restore as i32, DeletionType::Logical as i32]
+    );
+
+    // 復元後のアイテムはどちらからも取得できる
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{}", ids[0]))
+        .to_request();
+    let rest: Value = test::call_and_read_body_json(&app, req).await;
+    let grpc = f
+        .grpc
+        .get_item(GetItemRequest { id: ids[0] })
+        .await
+        .unwrap()
+        .into_inner()
+        .item
+        .unwrap();
+    assert_eq!(rest["name"], grpc.name.as_str());
+    assert_eq!(rest["deleted"], grpc.deleted);
+}
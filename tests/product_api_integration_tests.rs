@@ -147,6 +147,7 @@ async fn test_create_and_get_product() {
         dimensions: None,
         weight: Some(Decimal::new(100, 0)),
         shipping_info: None,
+        slug: None,
     };
     
     let req_json = TestRequest::post()
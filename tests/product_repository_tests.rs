@@ -3,10 +3,12 @@ mod helpers;
 use helpers::postgres::PostgresContainer;
 use rust_webapi::infrastructure::repository::postgres::product_repository::PostgresProductRepository;
 use rust_webapi::app_domain::repository::product_repository::ProductRepository;
-use rust_webapi::app_domain::model::product::{Product, ProductStatus, ProductError, Price, Inventory, Dimensions, ShippingInfo};
+use rust_webapi::app_domain::model::product_filter::{AttributeCondition, AttributeFilter};
+use rust_webapi::app_domain::model::product::{Product, ProductStatus, ProductError, Price, Inventory, Dimensions, ShippingInfo, ChangeContext, ProductImage, ProductVariant};
 use rust_decimal::Decimal;
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use rust_webapi::app_domain::model::localization::{MissingTranslation, ProductTranslation};
 
 #[tokio::test]
 async fn test_postgres_product_repository_basic_crud() {
@@ -40,7 +42,7 @@ async fn test_postgres_product_repository_basic_crud() {
     };
 
     // 1. Test product creation
-    let created_product = repo.create(product.clone()).await.unwrap();
+    let created_product = repo.create(product.clone(), &ChangeContext::default()).await.unwrap();
     assert_eq!(created_product.id, product.id);
     assert_eq!(created_product.name, product.name);
     assert_eq!(created_product.sku, product.sku);
@@ -73,20 +75,28 @@ async fn test_postgres_product_repository_basic_crud() {
     updated_product.description = Some("Updated Description".to_string());
     updated_product.updated_at = Utc::now();
 
-    let update_result = repo.update(updated_product.clone()).await;
+    let ctx = ChangeContext::new(Some("editor".to_string()), Some("typo fix".to_string()));
+    let update_result = repo.update(updated_product.clone(), &ctx).await;
     assert!(update_result.is_ok());
 
     let updated = repo.find_by_id("test-product-1").await.unwrap();
     assert_eq!(updated.name, "Updated Product");
     assert_eq!(updated.description, Some("Updated Description".to_string()));
 
+    // The update is recorded field by field together with the actor and reason
+    let history = repo.get_history("test-product-1", Some("name"), None, None).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].new_value.as_deref(), Some("Updated Product"));
+    assert_eq!(history[0].changed_by.as_deref(), Some("editor"));
+    assert_eq!(history[0].reason.as_deref(), Some("typo fix"));
+
     // 6. Test SKU existence check
     assert!(repo.exists_by_sku("SKU-001", None).await);
     assert!(!repo.exists_by_sku("SKU-999", None).await);
     assert!(!repo.exists_by_sku("SKU-001", Some("test-product-1")).await); // Exclude self
 
     // 7. Test deleting a product
-    let delete_result = repo.delete("test-product-1").await;
+    let delete_result = repo.delete("test-product-1", None).await;
     assert!(delete_result.is_ok());
 
     // Verify deletion
@@ -109,7 +119,7 @@ async fn test_postgres_product_repository_price_operations() {
         ProductStatus::Active,
     ).unwrap();
 
-    repo.create(product).await.unwrap();
+    repo.create(product, &ChangeContext::default()).await.unwrap();
 
     // Test price operations
     let price = Price {
@@ -123,7 +133,7 @@ async fn test_postgres_product_repository_price_operations() {
     };
 
     // Update price
-    let updated_price = repo.update_price("test-product-2", price.clone()).await;
+    let updated_price = repo.update_price("test-product-2", price.clone(), &ChangeContext::default()).await;
     assert!(updated_price.is_ok());
     let updated_price = updated_price.unwrap();
     assert_eq!(updated_price.selling_price, price.selling_price);
@@ -152,7 +162,7 @@ async fn test_postgres_product_repository_inventory_operations() {
         ProductStatus::Active,
     ).unwrap();
 
-    repo.create(product).await.unwrap();
+    repo.create(product, &ChangeContext::default()).await.unwrap();
 
     // Test inventory operations
     let inventory = Inventory {
@@ -164,7 +174,7 @@ async fn test_postgres_product_repository_inventory_operations() {
     };
 
     // Update inventory
-    let updated_inventory = repo.update_inventory("test-product-3", inventory.clone()).await;
+    let updated_inventory = repo.update_inventory("test-product-3", inventory.clone(), &ChangeContext::default()).await;
     assert!(updated_inventory.is_ok());
     let updated_inventory = updated_inventory.unwrap();
     assert_eq!(updated_inventory.quantity, 100);
@@ -194,11 +204,11 @@ async fn test_postgres_product_repository_tags_and_attributes() {
         ProductStatus::Active,
     ).unwrap();
 
-    repo.create(product).await.unwrap();
+    repo.create(product, &ChangeContext::default()).await.unwrap();
 
     // Test tags operations
     let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()];
-    let add_result = repo.add_tags("test-product-4", tags.clone()).await;
+    let add_result = repo.add_tags("test-product-4", tags.clone(), &ChangeContext::default()).await;
     assert!(add_result.is_ok());
 
     let retrieved_tags = repo.get_tags("test-product-4").await;
@@ -209,7 +219,7 @@ async fn test_postgres_product_repository_tags_and_attributes() {
 
     // Test replace tags
     let new_tags = vec!["newtag1".to_string(), "newtag2".to_string()];
-    let replace_result = repo.replace_tags("test-product-4", new_tags.clone()).await;
+    let replace_result = repo.replace_tags("test-product-4", new_tags.clone(), &ChangeContext::default()).await;
     assert!(replace_result.is_ok());
 
     let replaced_tags = repo.get_tags("test-product-4").await;
@@ -223,7 +233,7 @@ async fn test_postgres_product_repository_tags_and_attributes() {
     attributes.insert("size".to_string(), "large".to_string());
     attributes.insert("material".to_string(), "cotton".to_string());
 
-    let set_result = repo.set_attributes("test-product-4", attributes.clone()).await;
+    let set_result = repo.set_attributes("test-product-4", attributes.clone(), &ChangeContext::default()).await;
     assert!(set_result.is_ok());
 
     let retrieved_attributes = repo.get_attributes("test-product-4").await;
@@ -233,6 +243,186 @@ async fn test_postgres_product_repository_tags_and_attributes() {
     assert_eq!(retrieved_attributes.get("material"), Some(&"cotton".to_string()));
 }
 
+#[tokio::test]
+async fn test_postgres_product_repository_image_order() {
+    let postgres = PostgresContainer::new();
+    let pool = postgres.create_pool().await;
+    let repo = PostgresProductRepository::new(pool.clone());
+    postgres.run_migrations(&pool).await;
+
+    let product = Product::new(
+        "test-product-images".to_string(),
+        "Image Order Product".to_string(),
+        "SKU-IMG".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    repo.create(product, &ChangeContext::default()).await.unwrap();
+
+    for (index, id) in ["img-a", "img-b", "img-c"].iter().enumerate() {
+        let image = ProductImage {
+            id: id.to_string(),
+            url: format!("https://example.com/{}.jpg", id),
+            alt_text: None,
+            sort_order: index as i32,
+            is_main: index == 0,
+        };
+        repo.add_image("test-product-images", image, &ChangeContext::default()).await.unwrap();
+    }
+
+    // The list must name every image exactly once
+    for image_ids in [
+        vec!["img-c", "img-a"],
+        vec!["img-c", "img-a", "img-a"],
+        vec!["img-c", "img-a", "img-x"],
+    ] {
+        let result = repo
+            .set_image_order(
+                "test-product-images",
+                image_ids.into_iter().map(String::from).collect(),
+                &ChangeContext::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(ProductError::InvalidImageOrder)));
+    }
+
+    repo.set_image_order(
+        "test-product-images",
+        vec!["img-c".to_string(), "img-a".to_string(), "img-b".to_string()],
+        &ChangeContext::default(),
+    )
+    .await
+    .unwrap();
+
+    let ordered: Vec<(String, i32)> = repo
+        .get_images("test-product-images")
+        .await
+        .into_iter()
+        .map(|image| (image.id, image.sort_order))
+        .collect();
+    assert_eq!(
+        ordered,
+        vec![
+            ("img-c".to_string(), 0),
+            ("img-a".to_string(), 1),
+            ("img-b".to_string(), 2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_product_repository_slug_redirects() {
+    let postgres = PostgresContainer::new();
+    let pool = postgres.create_pool().await;
+    let repo = PostgresProductRepository::new(pool.clone());
+    postgres.run_migrations(&pool).await;
+
+    let product = Product::new(
+        "test-product-slug".to_string(),
+        "Slug Product".to_string(),
+        "SKU-SLUG".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    assert_eq!(product.slug, "slug-product");
+    let mut product = repo.create(product, &ChangeContext::default()).await.unwrap();
+
+    product.update_slug("renamed-product".to_string()).unwrap();
+    repo.update(product, &ChangeContext::default()).await.unwrap();
+
+    let found = repo.find_by_slug("renamed-product").await.unwrap();
+    assert_eq!(found.id, "test-product-slug");
+    assert!(repo.find_by_slug("slug-product").await.is_none());
+
+    // The previous slug still leads to the product and stays reserved for it
+    let redirected = repo.find_by_old_slug("slug-product").await.unwrap();
+    assert_eq!(redirected.id, "test-product-slug");
+    assert!(repo.exists_by_slug("slug-product", None).await);
+    assert!(!repo.exists_by_slug("slug-product", Some("test-product-slug")).await);
+
+    let mut other = Product::new(
+        "test-product-slug-2".to_string(),
+        "Other Product".to_string(),
+        "SKU-SLUG-2".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    other.update_slug("renamed-product".to_string()).unwrap();
+    let result = repo.create(other, &ChangeContext::default()).await;
+    assert!(matches!(result, Err(ProductError::SlugAlreadyExists)));
+}
+
+#[tokio::test]
+async fn test_postgres_product_repository_translations() {
+    let postgres = PostgresContainer::new();
+    let pool = postgres.create_pool().await;
+    let repo = PostgresProductRepository::new(pool.clone());
+    postgres.run_migrations(&pool).await;
+
+    let mut product = Product::new(
+        "test-product-i18n".to_string(),
+        "ノート".to_string(),
+        "SKU-I18N".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    product.description = Some("方眼ノート".to_string());
+    repo.create(product, &ChangeContext::default()).await.unwrap();
+    for (index, alt_text) in [Some("表紙"), None].into_iter().enumerate() {
+        let image = ProductImage {
+            id: format!("img-i18n-{}", index),
+            url: format!("https://example.com/i18n-{}.jpg", index),
+            alt_text: alt_text.map(String::from),
+            sort_order: index as i32,
+            is_main: index == 0,
+        };
+        repo.add_image("test-product-i18n", image, &ChangeContext::default()).await.unwrap();
+    }
+
+    let missing = |fields: Vec<MissingTranslation>| {
+        fields
+            .into_iter()
+            .find(|m| m.id == "test-product-i18n")
+            .map(|m| m.fields)
+            .unwrap_or_default()
+    };
+    assert_eq!(
+        missing(repo.find_missing_translations("en", 100, 0).await),
+        vec!["name", "description", "images.img-i18n-0.alt_text"]
+    );
+
+    let translation = ProductTranslation {
+        locale: "en".to_string(),
+        name: Some("Notebook".to_string()),
+        description: None,
+        image_alt_texts: BTreeMap::from([("img-i18n-0".to_string(), "Cover".to_string())]),
+    };
+    repo.set_translation("test-product-i18n", translation.clone()).await.unwrap();
+    assert_eq!(repo.get_translations("test-product-i18n").await, vec![translation.clone()]);
+    assert!(repo.get_translations_for(&["test-product-i18n".to_string()], "fr").await.is_empty());
+    let batch = repo.get_translations_for(&["test-product-i18n".to_string(), "no-such-product".to_string()], "en").await;
+    assert_eq!(batch.len(), 1);
+    assert_eq!(batch.get("test-product-i18n"), Some(&translation));
+    assert_eq!(
+        missing(repo.find_missing_translations("en", 100, 0).await),
+        vec!["description"]
+    );
+
+    // Images of other products and unknown products are rejected without partial writes
+    let foreign = ProductTranslation {
+        locale: "en".to_string(),
+        name: Some("Notebook".to_string()),
+        description: None,
+        image_alt_texts: BTreeMap::from([("img-x".to_string(), "Cover".to_string())]),
+    };
+    let result = repo.set_translation("test-product-i18n", foreign.clone()).await;
+    assert!(matches!(result, Err(ProductError::ImageNotFound)));
+    assert_eq!(repo.get_translations("test-product-i18n").await[0].image_alt_texts.len(), 1);
+    let result = repo.set_translation("no-such-product", foreign).await;
+    assert!(matches!(result, Err(ProductError::ProductNotFound)));
+
+    repo.delete_translation("test-product-i18n", "en").await.unwrap();
+    assert!(repo.get_translations("test-product-i18n").await.is_empty());
+    let result = repo.delete_translation("test-product-i18n", "en").await;
+    assert!(matches!(result, Err(ProductError::TranslationNotFound)));
+}
+
 #[tokio::test]
 async fn test_postgres_product_repository_search() {
     let postgres = PostgresContainer::new();
@@ -255,7 +445,7 @@ async fn test_postgres_product_repository_search() {
             sku.to_string(),
             ProductStatus::Active,
         ).unwrap();
-        repo.create(product).await.unwrap();
+        repo.create(product, &ChangeContext::default()).await.unwrap();
     }
 
     // Test text search
@@ -266,6 +456,7 @@ async fn test_postgres_product_repository_search() {
         None,
         None,
         false,
+        &[],
         None,
         None,
     ).await;
@@ -279,6 +470,7 @@ async fn test_postgres_product_repository_search() {
         None,
         None,
         false,
+        &[],
         None,
         None,
     ).await;
@@ -292,6 +484,7 @@ async fn test_postgres_product_repository_search() {
         None,
         None,
         false,
+        &[],
         Some(2),
         None,
     ).await;
@@ -305,10 +498,54 @@ async fn test_postgres_product_repository_search() {
         None,
         None,
         false,
+        &[],
         Some(2),
         Some(1),
     ).await;
     assert_eq!(offset_results.len(), 2);
+
+    // Test attribute filters
+    for (id, color, size) in [
+        ("test-product-5", "Black", "13"),
+        ("test-product-6", "White", "27"),
+        ("test-product-7", "black", "not-a-number"),
+    ] {
+        let attributes = HashMap::from([
+            ("color".to_string(), color.to_string()),
+            ("screen_size".to_string(), size.to_string()),
+        ]);
+        repo.set_attributes(id, attributes, &ChangeContext::default()).await.unwrap();
+    }
+    let search_by = |filters: Vec<AttributeFilter>| {
+        let repo = &repo;
+        async move {
+            let mut ids: Vec<String> = repo
+                .search("", None, None, None, None, false, &filters, None, None)
+                .await
+                .into_iter()
+                .map(|p| p.id)
+                .collect();
+            ids.sort();
+            ids
+        }
+    };
+    let color_filter = AttributeFilter {
+        name: "Color".to_string(),
+        condition: AttributeCondition::OneOf(vec!["BLACK".to_string()]),
+    };
+    assert_eq!(
+        search_by(vec![color_filter.clone()]).await,
+        ["test-product-5", "test-product-7"]
+    );
+    let size_filter = AttributeFilter {
+        name: "screen_size".to_string(),
+        condition: AttributeCondition::Range {
+            min: Some(Decimal::new(10, 0)),
+            max: Some(Decimal::new(20, 0)),
+        },
+    };
+    assert_eq!(search_by(vec![size_filter.clone()]).await, ["test-product-5"]);
+    assert_eq!(search_by(vec![color_filter, size_filter]).await, ["test-product-5"]);
 }
 
 #[tokio::test]
@@ -333,7 +570,7 @@ async fn test_postgres_product_repository_stock_queries() {
             sku.to_string(),
             ProductStatus::Active,
         ).unwrap();
-        repo.create(product).await.unwrap();
+        repo.create(product, &ChangeContext::default()).await.unwrap();
 
         let inventory = Inventory {
             quantity,
@@ -342,7 +579,7 @@ async fn test_postgres_product_repository_stock_queries() {
             track_inventory: true,
             allow_backorder: false,
         };
-        repo.update_inventory(id, inventory).await.unwrap();
+        repo.update_inventory(id, inventory, &ChangeContext::default()).await.unwrap();
     }
 
     // Test low stock products
@@ -377,11 +614,11 @@ async fn test_postgres_product_repository_error_handling() {
     ).unwrap();
 
     // First creation should succeed
-    let first_result = repo.create(product1).await;
+    let first_result = repo.create(product1, &ChangeContext::default()).await;
     assert!(first_result.is_ok());
 
     // Second creation with same SKU should fail
-    let second_result = repo.create(product2).await;
+    let second_result = repo.create(product2, &ChangeContext::default()).await;
     assert!(matches!(second_result, Err(ProductError::SkuAlreadyExists)));
 
     // Test updating non-existent product
@@ -392,11 +629,11 @@ async fn test_postgres_product_repository_error_handling() {
         ProductStatus::Active,
     ).unwrap();
 
-    let update_result = repo.update(non_existent_product).await;
+    let update_result = repo.update(non_existent_product, &ChangeContext::default()).await;
     assert!(matches!(update_result, Err(ProductError::ProductNotFound)));
 
     // Test deleting non-existent product
-    let delete_result = repo.delete("non-existent-id").await;
+    let delete_result = repo.delete("non-existent-id", None).await;
     assert!(matches!(delete_result, Err(ProductError::ProductNotFound)));
 
     // Test price update for non-existent product
@@ -410,7 +647,7 @@ async fn test_postgres_product_repository_error_handling() {
         effective_until: None,
     };
 
-    let price_update_result = repo.update_price("non-existent-id", price).await;
+    let price_update_result = repo.update_price("non-existent-id", price, &ChangeContext::default()).await;
     assert!(price_update_result.is_err(), "Expected error for non-existent product price update, got: {:?}", price_update_result);
 
     // Test inventory update for non-existent product
@@ -422,10 +659,59 @@ async fn test_postgres_product_repository_error_handling() {
         allow_backorder: false,
     };
 
-    let inventory_update_result = repo.update_inventory("non-existent-id", inventory).await;
+    let inventory_update_result = repo.update_inventory("non-existent-id", inventory, &ChangeContext::default()).await;
     assert!(inventory_update_result.is_err(), "Expected error for non-existent product inventory update, got: {:?}", inventory_update_result);
 }
 
+#[tokio::test]
+async fn test_postgres_product_repository_variant_sku_namespace() {
+    let postgres = PostgresContainer::new();
+    let pool = postgres.create_pool().await;
+    let repo = PostgresProductRepository::new(pool.clone());
+    postgres.run_migrations(&pool).await;
+
+    let price = Price {
+        selling_price: Decimal::new(2800, 0),
+        list_price: None,
+        discount_price: None,
+        currency: "JPY".to_string(),
+        tax_included: true,
+        effective_from: None,
+        effective_until: None,
+    };
+    let inventory = Inventory {
+        quantity: 5,
+        reserved_quantity: 0,
+        alert_threshold: None,
+        track_inventory: true,
+        allow_backorder: false,
+    };
+    let variant = |id: &str, product_id: &str, sku: &str| {
+        ProductVariant::new(id.to_string(), product_id.to_string(), sku.to_string(), BTreeMap::new(), price.clone(), inventory.clone()).unwrap()
+    };
+
+    let tee = repo.create(Product::new("tee".to_string(), "Tee".to_string(), "TEE".to_string(), ProductStatus::Active).unwrap(), &ChangeContext::default()).await.unwrap();
+    let cap = repo.create(Product::new("cap".to_string(), "Cap".to_string(), "CAP".to_string(), ProductStatus::Active).unwrap(), &ChangeContext::default()).await.unwrap();
+    repo.create_variant(variant("tee-s", "tee", "TEE-S")).await.unwrap();
+    repo.create_variant(variant("tee-m", "tee", "TEE-M")).await.unwrap();
+
+    // 商品とバリエーションの SKU は同じ名前空間を共有する
+    assert!(matches!(repo.create_variant(variant("cap-1", "cap", "CAP")).await, Err(ProductError::SkuAlreadyExists)));
+    assert!(matches!(repo.create_variant(variant("cap-1", "cap", "TEE-S")).await, Err(ProductError::SkuAlreadyExists)));
+    assert!(matches!(repo.update_variant(variant("tee-m", "tee", "TEE-S")).await, Err(ProductError::SkuAlreadyExists)));
+    let new_sku = Product::new("new".to_string(), "New".to_string(), "TEE-M".to_string(), ProductStatus::Active).unwrap();
+    assert!(matches!(repo.create(new_sku, &ChangeContext::default()).await, Err(ProductError::SkuAlreadyExists)));
+    let mut renamed = cap.clone();
+    renamed.sku = "TEE-M".to_string();
+    assert!(matches!(repo.update(renamed, &ChangeContext::default()).await, Err(ProductError::SkuAlreadyExists)));
+    // 自分自身の SKU のままの更新は重複にならない
+    assert!(repo.update_variant(variant("tee-m", "tee", "TEE-M")).await.is_ok());
+
+    let variants = repo.get_variants_for(&[tee.id.clone(), cap.id.clone()]).await;
+    assert_eq!(variants[&tee.id].len(), 2);
+    assert!(!variants.contains_key(&cap.id));
+}
+
 #[tokio::test]
 async fn test_postgres_product_repository_concurrent_operations() {
     use std::sync::Arc;
@@ -448,7 +734,7 @@ async fn test_postgres_product_repository_concurrent_operations() {
                 format!("CONCURRENT-SKU-{:03}", i),
                 ProductStatus::Active,
             ).unwrap();
-            repo_clone.create(product).await
+            repo_clone.create(product, &ChangeContext::default()).await
         });
         create_handles.push(handle);
     }
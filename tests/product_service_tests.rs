@@ -1,52 +1,172 @@
 use std::sync::Arc;
 use async_trait::async_trait;
 use rust_webapi::application::service::product_service::ProductService;
+use rust_webapi::application::service::change_feed::ChangeFeed;
+use rust_webapi::application::service::slug_service::SlugService;
+use rust_webapi::application::dto::slug_dto::SlugResourceType;
+use rust_webapi::app_domain::model::category::{Category, CategoryRedirect};
+use rust_webapi::app_domain::repository::category_repository::MockCategoryRepository;
+use rust_webapi::infrastructure::error::AppError;
+use rust_webapi::infrastructure::config::BodyLimitConfig;
+use rust_webapi::application::service::deletion_facade::DeletionFacade;
+use rust_webapi::app_domain::service::deletion_service::DeleteKind;
+use rust_webapi::infrastructure::repository::item_repository::InMemoryItemRepository;
+use rust_webapi::infrastructure::di::server::configure_api_routes;
+use rust_webapi::presentation::api::product_handler::ProductHandler;
+use rust_webapi::application::service::product_import_service::ProductImportService;
+use rust_webapi::application::service::product_export_service::ProductExportService;
+use rust_webapi::app_domain::model::product_export::{ExportFormat, ProductExportRow};
+use rust_webapi::app_domain::model::product_filter::{AttributeFilter, ProductFilter};
+use rust_webapi::app_domain::model::attribute_schema::{AttributeDefinition, AttributeSchema, AttributeType};
+use rust_webapi::app_domain::model::product_bulk::ProductBulkPatch;
+use rust_webapi::app_domain::model::localization::{Locales, MissingTranslation, ProductTranslation};
+use rust_webapi::application::dto::translation_dto::{ImageAltTextRequest, ProductTranslationRequest};
+use rust_webapi::app_domain::model::product_import::{ImportFormat, ImportJobState, ImportRowError, ImportRowOutcome, ProductImportRecord};
 use rust_webapi::app_domain::repository::product_repository::ProductRepository;
-use rust_webapi::app_domain::model::product::{Product, ProductStatus, ProductError, Price, Inventory};
-use rust_webapi::application::dto::product_dto::{CreateProductRequest, PriceRequest, InventoryRequest, DimensionsRequest, ShippingInfoRequest};
+use rust_webapi::app_domain::model::product::{Product, ProductStatus, ProductError, Price, Inventory, ProductOption, ProductVariant, ProductBundle, BundleComponent, BundlePricing, ProductStatusSchedule, ScheduleState, ChangeContext, ProductHistory, ProductRevision};
+use rust_webapi::application::dto::product_dto::{PatchProductRequest, RollbackRequest, CreateProductRequest, CreateVariantRequest, ProductExportQuery, BulkUpdateRequest, ProductFilterRequest, ProductSearchQuery, PriceRequest, InventoryRequest, DimensionsRequest, ShippingInfoRequest};
 use rust_decimal::Decimal;
 
 struct MockProductRepository {
     exists: bool,
     created: Option<Product>,
+    options: Vec<ProductOption>,
+    variants: Vec<ProductVariant>,
+    bundle: Option<ProductBundle>,
+    schedules: std::sync::Mutex<Vec<ProductStatusSchedule>>,
+    contexts: std::sync::Mutex<Vec<ChangeContext>>,
+    history: Vec<ProductHistory>,
+    prices: std::collections::HashMap<String, Price>,
 }
 
 #[async_trait]
 impl ProductRepository for MockProductRepository {
-    async fn find_by_id(&self, _id: &str) -> Option<Product> {
-        self.created.clone()
+    // EXP-* ids resolve to the catalog unless `created` is the same product
+    async fn find_by_id(&self, id: &str) -> Option<Product> {
+        match &self.created {
+            Some(created) if created.id == id || !id.starts_with("EXP-") => Some(created.clone()),
+            _ => catalog(None, 100).into_iter().find(|p| p.id == id),
+        }
     }
     async fn find_by_sku(&self, _sku: &str) -> Option<Product> { None }
-    async fn create(&self, product: Product) -> Result<Product, ProductError> {
+    async fn find_by_slug(&self, slug: &str) -> Option<Product> { self.created.clone().filter(|p| p.slug == slug) }
+    async fn find_by_old_slug(&self, _slug: &str) -> Option<Product> { None }
+    async fn create(&self, product: Product, ctx: &ChangeContext) -> Result<Product, ProductError> {
+        self.contexts.lock().unwrap().push(ctx.clone());
         Ok(product.clone())
     }
-    async fn update(&self, _product: Product) -> Result<Product, ProductError> { Ok(_product) }
-    async fn delete(&self, _id: &str) -> Result<(), ProductError> { Ok(()) }
+    async fn update(&self, _product: Product, ctx: &ChangeContext) -> Result<Product, ProductError> { self.contexts.lock().unwrap().push(ctx.clone()); Ok(_product) }
+    async fn delete(&self, _id: &str, expected_version: Option<i64>) -> Result<(), ProductError> {
+        let actual = self.created.as_ref().map_or(0, |p| p.version);
+        match expected_version {
+            Some(expected) if expected != actual => Err(ProductError::VersionMismatch { expected, actual }),
+            _ => Ok(()),
+        }
+    }
+    async fn apply_revision(&self, product_id: &str, expected_version: i64, _revision: ProductRevision, ctx: &ChangeContext) -> Result<(), ProductError> {
+        let actual = self.find_by_id(product_id).await.map_or(0, |p| p.version);
+        if expected_version != actual {
+            return Err(ProductError::VersionMismatch { expected: expected_version, actual });
+        }
+        self.contexts.lock().unwrap().push(ctx.clone());
+        Ok(())
+    }
     async fn exists_by_sku(&self, _sku: &str, _exclude_id: Option<&str>) -> bool { self.exists }
-    async fn get_current_price(&self, _product_id: &str) -> Option<Price> { None }
-    async fn update_price(&self, _product_id: &str, price: Price) -> Result<Price, ProductError> { Ok(price) }
+    async fn exists_by_slug(&self, _slug: &str, _exclude_id: Option<&str>) -> bool { false }
+    async fn get_current_price(&self, product_id: &str) -> Option<Price> { self.prices.get(product_id).cloned() }
+    async fn update_price(&self, _product_id: &str, price: Price, ctx: &ChangeContext) -> Result<Price, ProductError> { self.contexts.lock().unwrap().push(ctx.clone()); Ok(price) }
     async fn get_inventory(&self, _product_id: &str) -> Option<Inventory> { None }
-    async fn update_inventory(&self, _product_id: &str, inventory: Inventory) -> Result<Inventory, ProductError> { Ok(inventory) }
+    async fn update_inventory(&self, _product_id: &str, inventory: Inventory, ctx: &ChangeContext) -> Result<Inventory, ProductError> { self.contexts.lock().unwrap().push(ctx.clone()); Ok(inventory) }
     async fn get_images(&self, _product_id: &str) -> Vec<rust_webapi::app_domain::model::product::ProductImage> { vec![] }
-    async fn add_image(&self, _product_id: &str, _image: rust_webapi::app_domain::model::product::ProductImage) -> Result<rust_webapi::app_domain::model::product::ProductImage, ProductError> { Ok(_image) }
-    async fn update_image(&self, _product_id: &str, _image: rust_webapi::app_domain::model::product::ProductImage) -> Result<rust_webapi::app_domain::model::product::ProductImage, ProductError> { Ok(_image) }
-    async fn delete_image(&self, _product_id: &str, _image_id: &str) -> Result<(), ProductError> { Ok(()) }
-    async fn reorder_images(&self, _product_id: &str, _image_orders: Vec<(String, i32)>) -> Result<(), ProductError> { Ok(()) }
-    async fn set_main_image(&self, _product_id: &str, _image_id: &str) -> Result<(), ProductError> { Ok(()) }
+    async fn add_image(&self, _product_id: &str, _image: rust_webapi::app_domain::model::product::ProductImage, _ctx: &ChangeContext) -> Result<rust_webapi::app_domain::model::product::ProductImage, ProductError> { Ok(_image) }
+    async fn update_image(&self, _product_id: &str, _image: rust_webapi::app_domain::model::product::ProductImage, _ctx: &ChangeContext) -> Result<rust_webapi::app_domain::model::product::ProductImage, ProductError> { Ok(_image) }
+    async fn delete_image(&self, _product_id: &str, _image_id: &str, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
+    async fn reorder_images(&self, _product_id: &str, _image_orders: Vec<(String, i32)>, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
+    async fn set_image_order(&self, _product_id: &str, _image_ids: Vec<String>, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
+    async fn set_main_image(&self, _product_id: &str, _image_id: &str, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
     async fn get_tags(&self, _product_id: &str) -> Vec<String> { vec![] }
-    async fn add_tags(&self, _product_id: &str, _tags: Vec<String>) -> Result<(), ProductError> { Ok(()) }
-    async fn replace_tags(&self, _product_id: &str, _tags: Vec<String>) -> Result<(), ProductError> { Ok(()) }
+    async fn add_tags(&self, _product_id: &str, _tags: Vec<String>, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
+    async fn replace_tags(&self, _product_id: &str, _tags: Vec<String>, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
     async fn get_attributes(&self, _product_id: &str) -> std::collections::HashMap<String, String> { std::collections::HashMap::new() }
-    async fn set_attributes(&self, _product_id: &str, _attributes: std::collections::HashMap<String, String>) -> Result<(), ProductError> { Ok(()) }
-    async fn get_history(&self, _product_id: &str, _field_name: Option<&str>, _limit: Option<i64>, _offset: Option<i64>) -> Vec<rust_webapi::app_domain::model::product::ProductHistory> { vec![] }
-    async fn search(&self, _query: &str, _category_id: Option<&str>, _tags: Option<Vec<&str>>, _min_price: Option<Decimal>, _max_price: Option<Decimal>, _in_stock_only: bool, _limit: Option<i64>, _offset: Option<i64>) -> Vec<Product> { vec![] }
+    async fn set_attributes(&self, _product_id: &str, _attributes: std::collections::HashMap<String, String>, _ctx: &ChangeContext) -> Result<(), ProductError> { Ok(()) }
+    async fn find_attribute_schema(&self, category_id: &str) -> Result<AttributeSchema, ProductError> { if category_id == "cat_unavailable" { Err(ProductError::DatabaseError("connection refused".to_string())) } else { Ok(display_schema(category_id)) } }
+    async fn get_options(&self, _product_id: &str) -> Vec<ProductOption> { self.options.clone() }
+    async fn set_options(&self, _product_id: &str, _options: Vec<ProductOption>) -> Result<(), ProductError> { Ok(()) }
+    async fn get_variants(&self, _product_id: &str) -> Vec<ProductVariant> { self.variants.clone() }
+    async fn get_variants_for(&self, product_ids: &[String]) -> std::collections::HashMap<String, Vec<ProductVariant>> { product_ids.iter().map(|id| (id.clone(), self.variants.clone())).collect() }
+    async fn create_variant(&self, variant: ProductVariant) -> Result<ProductVariant, ProductError> { Ok(variant) }
+    async fn update_variant(&self, variant: ProductVariant) -> Result<ProductVariant, ProductError> { Ok(variant) }
+    async fn delete_variant(&self, _product_id: &str, _variant_id: &str) -> Result<(), ProductError> { Ok(()) }
+    async fn reserve_inventory(&self, _reservations: Vec<(String, i32)>) -> Result<(), ProductError> { Ok(()) }
+    async fn release_inventory(&self, _reservations: Vec<(String, i32)>) -> Result<(), ProductError> { Ok(()) }
+    async fn get_bundle(&self, product_id: &str) -> Option<ProductBundle> { self.bundle.clone().filter(|b| b.product_id == product_id) }
+    async fn set_bundle(&self, _bundle: ProductBundle) -> Result<(), ProductError> { Ok(()) }
+    async fn delete_bundle(&self, _product_id: &str) -> Result<(), ProductError> { Ok(()) }
+    async fn find_bundles_by_component(&self, component_id: &str) -> Vec<String> { self.bundle.iter().filter(|b| b.components.iter().any(|c| c.product_id == component_id)).map(|b| b.product_id.clone()).collect() }
+    async fn get_translations(&self, _product_id: &str) -> Vec<ProductTranslation> { vec![] }
+    async fn get_translations_for(&self, _product_ids: &[String], _locale: &str) -> std::collections::HashMap<String, ProductTranslation> { std::collections::HashMap::new() }
+    async fn set_translation(&self, _product_id: &str, _translation: ProductTranslation) -> Result<(), ProductError> { Ok(()) }
+    async fn delete_translation(&self, _product_id: &str, _locale: &str) -> Result<(), ProductError> { Err(ProductError::TranslationNotFound) }
+    async fn find_missing_translations(&self, _locale: &str, _limit: i64, _offset: i64) -> Vec<MissingTranslation> { vec![] }
+    async fn change_status(&self, _product_id: &str, _from: ProductStatus, _to: ProductStatus, ctx: &ChangeContext) -> Result<(), ProductError> { self.contexts.lock().unwrap().push(ctx.clone()); Ok(()) }
+    async fn get_schedules(&self, _product_id: &str) -> Vec<ProductStatusSchedule> { self.schedules.lock().unwrap().clone() }
+    async fn create_schedule(&self, schedule: ProductStatusSchedule) -> Result<ProductStatusSchedule, ProductError> { Ok(schedule) }
+    async fn cancel_schedule(&self, _product_id: &str, _schedule_id: &str) -> Result<(), ProductError> { Ok(()) }
+    async fn claim_due_schedules(&self, now: chrono::DateTime<chrono::Utc>, _limit: i64, _lease_seconds: i64) -> Result<Vec<ProductStatusSchedule>, ProductError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let due: Vec<ProductStatusSchedule> = schedules.iter().filter(|s| s.state == ScheduleState::Pending && s.scheduled_at <= now).cloned().collect();
+        for schedule in schedules.iter_mut().filter(|s| s.state == ScheduleState::Pending && s.scheduled_at <= now) {
+            schedule.state = ScheduleState::Processing;
+        }
+        Ok(due)
+    }
+    async fn complete_schedule(&self, schedule_id: &str, state: ScheduleState, failure_reason: Option<String>) -> Result<(), ProductError> {
+        if schedule_id.starts_with("broken") {
+            return Err(ProductError::DatabaseError("connection reset".to_string()));
+        }
+        for schedule in self.schedules.lock().unwrap().iter_mut().filter(|s| s.id == schedule_id) {
+            schedule.state = state.clone();
+            schedule.failure_reason = failure_reason.clone();
+        }
+        Ok(())
+    }
+    async fn get_history(&self, _product_id: &str, _field_name: Option<&str>, _limit: Option<i64>, _offset: Option<i64>) -> Vec<ProductHistory> { self.history.clone() }
+    async fn search(&self, _query: &str, _category_id: Option<&str>, _tags: Option<Vec<&str>>, _min_price: Option<Decimal>, _max_price: Option<Decimal>, _in_stock_only: bool, _attributes: &[AttributeFilter], _limit: Option<i64>, _offset: Option<i64>) -> Vec<Product> { vec![] }
     async fn find_low_stock_products(&self, _threshold: Option<i32>) -> Vec<(Product, Inventory)> { vec![] }
     async fn find_out_of_stock_products(&self) -> Vec<Product> { vec![] }
+    async fn import_products(&self, records: Vec<ProductImportRecord>, _ctx: &ChangeContext) -> Result<Vec<ImportRowOutcome>, ImportRowError> {
+        match records.iter().find(|r| r.sku.starts_with("FAIL")) {
+            Some(r) => Err(ImportRowError::new(r.line, Some(r.sku.clone()), None, "Database error: rejected".to_string())),
+            None => Ok(records.iter().map(|_| ImportRowOutcome::Created).collect()),
+        }
+    }
+    async fn find_by_filter(&self, _filter: &ProductFilter, after_id: Option<&str>, limit: i64) -> Result<Vec<Product>, ProductError> { Ok(catalog(after_id, limit)) }
+    async fn count_by_filter(&self, _filter: &ProductFilter) -> Result<i64, ProductError> { Ok(catalog(None, 100).len() as i64) }
+    async fn export_products(&self, _filter: &ProductFilter, after_id: Option<&str>, limit: i64) -> Result<Vec<ProductExportRow>, ProductError> {
+        Ok(catalog(after_id, limit).into_iter().map(|product| ProductExportRow { product, price: None, inventory: None, tags: vec![], attributes: Default::default(), main_image_url: None }).collect())
+    }
+}
+
+/// EXP-1..EXP-5 (EXP-3 is discontinued, EXP-5 inactive), ordered by id
+/// "cat_displays" には親カテゴリの color（必須の enum）と自身の screen_size（number）が定義されている
+fn display_schema(category_id: &str) -> AttributeSchema {
+    if category_id != "cat_displays" {
+        return AttributeSchema::default();
+    }
+    let color = AttributeDefinition { name: "color".to_string(), attribute_type: AttributeType::Enum, allowed_values: vec!["Black".to_string(), "White".to_string()], required: true, unit: None };
+    let screen_size = AttributeDefinition { name: "screen_size".to_string(), attribute_type: AttributeType::Number, allowed_values: vec![], required: false, unit: Some("inch".to_string()) };
+    AttributeSchema::inherit([("cat_electronics".to_string(), color), ("cat_displays".to_string(), screen_size)])
+}
+
+fn catalog(after_id: Option<&str>, limit: i64) -> Vec<Product> {
+    let status = |i: i32| match i { 3 => ProductStatus::Discontinued, 5 => ProductStatus::Inactive, _ => ProductStatus::Active };
+    (1..=5).map(|i| (format!("EXP-{}", i), status(i))).filter(|(id, _)| after_id.is_none_or(|after| id.as_str() > after)).take(limit as usize)
+        .map(|(id, status)| Product::new(id.clone(), format!("Product {}", id), id, status).unwrap()).collect()
 }
 
 #[tokio::test]
 async fn test_create_product_duplicate_sku() {
-    let repo = Arc::new(MockProductRepository { exists: true, created: None });
+    let repo = Arc::new(MockProductRepository { exists: true, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
     let service = ProductService::new(repo);
     let req = CreateProductRequest {
         name: "Test Product".to_string(),
@@ -76,8 +196,9 @@ async fn test_create_product_duplicate_sku() {
         dimensions: None,
         weight: None,
         shipping_info: None,
+        slug: None,
     };
-    let result = service.create(req).await;
+    let result = service.create(req, &ChangeContext::default()).await;
     assert!(matches!(result, Err(ProductError::SkuAlreadyExists)));
 }
 
@@ -89,14 +210,14 @@ async fn test_create_product_success() {
         "SKU-001".to_string(),
         ProductStatus::Active,
     ).unwrap();
-    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product) });
-    let service = ProductService::new(repo);
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
     let req = CreateProductRequest {
         name: "Test Product".to_string(),
         description: Some("desc".to_string()),
         sku: "SKU-001".to_string(),
         brand: Some("BrandX".to_string()),
-        status: ProductStatus::Active,
+        status: ProductStatus::Draft,
         price: PriceRequest {
             selling_price: Decimal::new(1000, 2),
             list_price: Some(Decimal::new(1200, 2)),
@@ -119,10 +240,718 @@ async fn test_create_product_success() {
         dimensions: Some(DimensionsRequest { width: Decimal::new(10, 0), height: Decimal::new(20, 0), depth: Decimal::new(5, 0) }),
         weight: Some(Decimal::new(100, 0)),
         shipping_info: Some(ShippingInfoRequest { shipping_class: "standard".to_string(), free_shipping: false, shipping_fee: Decimal::new(500, 2) }),
+        slug: None,
     };
-    let result = service.create(req).await;
+    let ctx = ChangeContext::new(Some("admin".to_string()), Some("initial import".to_string()));
+    let result = service.create(req, &ctx).await;
     assert!(result.is_ok());
     let product = result.unwrap();
     assert_eq!(product.name, "Test Product");
     assert_eq!(product.sku, "SKU-001");
-} 
\ No newline at end of file
+    // Product, price and inventory writes all carry the caller's context into history
+    let contexts = repo.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 3);
+    assert!(contexts.iter().all(|c| c == &ctx));
+}
+
+#[tokio::test]
+async fn test_create_product_rejects_activation_without_requirements() {
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let request = |status: ProductStatus| CreateProductRequest {
+        name: "Launch Product".to_string(),
+        description: None,
+        sku: "SKU-LAUNCH".to_string(),
+        brand: None,
+        status,
+        price: PriceRequest { selling_price: Decimal::new(1000, 0), list_price: None, discount_price: None, currency: "JPY".to_string(), tax_included: true, effective_from: None, effective_until: None },
+        inventory: InventoryRequest { quantity: 1, reserved_quantity: None, alert_threshold: None, track_inventory: None, allow_backorder: None },
+        category_id: None,
+        tags: None,
+        attributes: None,
+        dimensions: None,
+        weight: None,
+        shipping_info: None,
+        slug: None,
+    };
+    let ctx = ChangeContext::default();
+
+    // A new product cannot go live without images and a category
+    match service.create(request(ProductStatus::Active), &ctx).await {
+        Err(ProductError::ActivationRequirementsNotMet(missing)) => assert_eq!(missing, vec!["images", "category_id"]),
+        other => panic!("expected ActivationRequirementsNotMet, got {:?}", other.map(|p| p.id)),
+    }
+    // Creation starts from Draft, so Inactive is not reachable directly
+    assert!(matches!(
+        service.create(request(ProductStatus::Inactive), &ctx).await,
+        Err(ProductError::InvalidStatusTransition { from: ProductStatus::Draft, to: ProductStatus::Inactive })
+    ));
+    // Nothing was written for the rejected requests
+    assert!(repo.contexts.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_create_product_requires_slug_when_name_has_no_reading() {
+    let product = Product::new("dummy_id".to_string(), "Watch".to_string(), "SKU-WATCH".to_string(), ProductStatus::Draft).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let request = |slug: Option<&str>| CreateProductRequest {
+        name: "腕時計".to_string(),
+        description: None,
+        sku: "SKU-WATCH".to_string(),
+        brand: None,
+        status: ProductStatus::Draft,
+        price: PriceRequest { selling_price: Decimal::new(1000, 0), list_price: None, discount_price: None, currency: "JPY".to_string(), tax_included: true, effective_from: None, effective_until: None },
+        inventory: InventoryRequest { quantity: 1, reserved_quantity: None, alert_threshold: None, track_inventory: None, allow_backorder: None },
+        category_id: None,
+        tags: None,
+        attributes: None,
+        dimensions: None,
+        weight: None,
+        shipping_info: None,
+        slug: slug.map(str::to_string),
+    };
+    let ctx = ChangeContext::default();
+
+    // Kanji have no reading here, so the slug is not derived from the SKU or the id
+    assert!(matches!(service.create(request(None), &ctx).await, Err(ProductError::SlugRequired)));
+    assert!(repo.contexts.lock().unwrap().is_empty());
+
+    assert!(service.create(request(Some("udedokei")), &ctx).await.is_ok());
+}
+
+fn variant_request(sku: &str, size: &str) -> CreateVariantRequest {
+    CreateVariantRequest {
+        sku: sku.to_string(),
+        option_values: std::collections::BTreeMap::from([("size".to_string(), size.to_string())]),
+        price: PriceRequest {
+            selling_price: Decimal::new(3000, 0),
+            list_price: None,
+            discount_price: None,
+            currency: "JPY".to_string(),
+            tax_included: true,
+            effective_from: None,
+            effective_until: None,
+        },
+        inventory: InventoryRequest {
+            quantity: 5,
+            reserved_quantity: None,
+            alert_threshold: None,
+            track_inventory: None,
+            allow_backorder: None,
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_create_variant_rejects_duplicate_combination() {
+    let product = Product::new(
+        "parent".to_string(),
+        "Tee".to_string(),
+        "TEE".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    let options = vec![ProductOption { name: "size".to_string(), values: vec!["S".to_string(), "M".to_string()] }];
+    let existing = ProductVariant::new(
+        "var-1".to_string(),
+        "parent".to_string(),
+        "TEE-M".to_string(),
+        std::collections::BTreeMap::from([("size".to_string(), "M".to_string())]),
+        Price::from(variant_request("TEE-M", "M").price),
+        Inventory::from(variant_request("TEE-M", "M").inventory),
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options, variants: vec![existing], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo);
+
+    let duplicate = service.create_variant("parent", variant_request("TEE-M-2", "M")).await;
+    assert!(matches!(duplicate, Err(ProductError::VariantCombinationDuplicate)));
+
+    let unknown_value = service.create_variant("parent", variant_request("TEE-XL", "XL")).await;
+    assert!(matches!(unknown_value, Err(ProductError::InvalidVariantOptions)));
+
+    let created = service.create_variant("parent", variant_request("TEE-S", "S")).await.unwrap();
+    assert_eq!(created.sku, "TEE-S");
+    assert_eq!(created.option_values.get("size").map(String::as_str), Some("S"));
+}
+
+#[tokio::test]
+async fn test_reserve_bundle_reserves_components() {
+    let product = Product::new(
+        "kit".to_string(),
+        "Starter Kit".to_string(),
+        "KIT-1".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    let bundle = ProductBundle::new(
+        "kit".to_string(),
+        vec![
+            BundleComponent { product_id: "pen".to_string(), quantity: 2 },
+            BundleComponent { product_id: "notebook".to_string(), quantity: 1 },
+        ],
+        BundlePricing::Fixed,
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: Some(bundle), schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo);
+
+    let reservation = service.reserve_inventory("kit", 3).await.unwrap();
+    let reserved: Vec<(String, i32)> = reservation.reservations.into_iter().map(|r| (r.product_id, r.quantity)).collect();
+    assert_eq!(reserved, vec![("pen".to_string(), 6), ("notebook".to_string(), 3)]);
+
+    let invalid = service.reserve_inventory("kit", 0).await;
+    assert!(matches!(invalid, Err(ProductError::InvalidInventoryQuantity)));
+}
+
+#[tokio::test]
+async fn test_bundle_reservation_notifies_each_component() {
+    let product = Product::new("kit".to_string(), "Starter Kit".to_string(), "KIT-1".to_string(), ProductStatus::Active).unwrap();
+    let bundle = ProductBundle::new(
+        "kit".to_string(),
+        vec![
+            BundleComponent { product_id: "pen".to_string(), quantity: 2 },
+            BundleComponent { product_id: "notebook".to_string(), quantity: 1 },
+        ],
+        BundlePricing::Fixed,
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: Some(bundle), schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let feed = Arc::new(ChangeFeed::new(10));
+    let service = ProductService::new(repo).with_change_feed(feed.clone());
+    let mut subscription = feed.subscribe(None, vec![]).unwrap();
+
+    service.reserve_inventory("kit", 1).await.unwrap();
+
+    // セット商品自身と、在庫が変わった構成品ごとに 1 件ずつ通知される
+    for _ in 0..3 {
+        let event = subscription.next().await.unwrap();
+        assert_eq!(event.changed_fields, vec!["inventory".to_string()]);
+    }
+    assert!(tokio::time::timeout(std::time::Duration::from_millis(50), subscription.next()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_discounted_bundle_without_component_prices_has_no_price() {
+    let product = Product::new("kit".to_string(), "Starter Kit".to_string(), "KIT-1".to_string(), ProductStatus::Active).unwrap();
+    let bundle = ProductBundle::new(
+        "kit".to_string(),
+        vec![
+            BundleComponent { product_id: "pen".to_string(), quantity: 2 },
+            BundleComponent { product_id: "notebook".to_string(), quantity: 1 },
+        ],
+        BundlePricing::DiscountedSum { discount_rate: Decimal::new(1, 1) },
+    ).unwrap();
+    let price = |amount: i64| Price { selling_price: Decimal::from(amount), list_price: None, discount_price: None, currency: "JPY".to_string(), tax_included: true, effective_from: None, effective_until: None };
+    // notebook の価格が無い
+    let prices = [("kit".to_string(), price(5000)), ("pen".to_string(), price(100))].into_iter().collect();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: Some(bundle), schedules: Default::default(), contexts: Default::default(), prices, history: vec![] });
+    let service = ProductService::new(repo);
+
+    // セット商品自身の価格で代用しない
+    let response = service.get_bundle("kit").await.unwrap();
+    assert!(response.price.is_none());
+}
+
+#[tokio::test]
+async fn test_set_translation_validates_locale_and_images() {
+    let product = Product::new(
+        "p1".to_string(),
+        "ノート".to_string(),
+        "NB-1".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo).with_locales(Locales::new("ja", ["en", "fr"]));
+    let request = |images: Vec<ImageAltTextRequest>| ProductTranslationRequest {
+        name: Some(" Notebook ".to_string()),
+        description: None,
+        images,
+    };
+
+    let default_locale = service.set_translation("p1", "ja", request(vec![])).await;
+    assert!(matches!(default_locale, Err(ProductError::InvalidTranslation(_))));
+    let unsupported = service.set_translation("p1", "de", request(vec![])).await;
+    assert!(matches!(unsupported, Err(ProductError::InvalidTranslation(_))));
+    let unknown_image = service
+        .set_translation("p1", "en", request(vec![ImageAltTextRequest { image_id: "img_x".to_string(), alt_text: "Cover".to_string() }]))
+        .await;
+    assert!(matches!(unknown_image, Err(ProductError::InvalidTranslation(_))));
+
+    let translation = service.set_translation("p1", "EN", request(vec![])).await.unwrap();
+    assert_eq!(translation.locale, "en");
+    assert_eq!(translation.name.as_deref(), Some("Notebook"));
+    assert!(translation.missing_fields.is_empty());
+
+    let translations = service.get_translations("p1").await.unwrap();
+    assert_eq!(translations.default_locale, "ja");
+    let locales: Vec<&str> = translations.translations.iter().map(|t| t.locale.as_str()).collect();
+    assert_eq!(locales, vec!["en", "fr"]);
+    assert_eq!(translations.translations[1].missing_fields, vec!["name"]);
+
+    let deleted = service.delete_translation("p1", "en").await;
+    assert!(matches!(deleted, Err(ProductError::TranslationNotFound)));
+}
+
+#[tokio::test]
+async fn test_publish_enforces_lifecycle_rules() {
+    let draft = Product::new(
+        "p1".to_string(),
+        "Draft Product".to_string(),
+        "DRAFT-1".to_string(),
+        ProductStatus::Draft,
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(draft), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo);
+
+    let result = service.publish("p1", &ChangeContext::new(Some("tester".to_string()), None)).await;
+    match result {
+        Err(ProductError::ActivationRequirementsNotMet(missing)) => {
+            assert_eq!(missing, vec!["price", "images", "category_id"]);
+        }
+        other => panic!("unexpected result: {:?}", other.map(|p| p.id)),
+    }
+
+    let discontinued = Product::new(
+        "p2".to_string(),
+        "Old Product".to_string(),
+        "OLD-1".to_string(),
+        ProductStatus::Discontinued,
+    ).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(discontinued), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo);
+
+    let result = service.publish("p2", &ChangeContext::new(Some("tester".to_string()), None)).await;
+    assert!(matches!(
+        result,
+        Err(ProductError::InvalidStatusTransition { from: ProductStatus::Discontinued, to: ProductStatus::Active })
+    ));
+}
+
+#[tokio::test]
+async fn test_delete_passes_expected_version_to_repository() {
+    let product = Product::new(
+        "p1".to_string(),
+        "Product".to_string(),
+        "DEL-1".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    let version = product.version;
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let ctx = ChangeContext::new(Some("tester".to_string()), None);
+
+    for logical in [false, true] {
+        let result = service.delete("p1", logical, Some(version + 1), &ctx).await;
+        assert!(matches!(result, Err(ProductError::VersionMismatch { .. })));
+    }
+    assert!(repo.contexts.lock().unwrap().is_empty());
+
+    assert!(service.delete("p1", false, Some(version), &ctx).await.is_ok());
+    assert!(service.delete("p1", true, None, &ctx).await.is_ok());
+    assert_eq!(repo.contexts.lock().unwrap()[0].changed_by.as_deref(), Some("tester"));
+}
+
+#[tokio::test]
+async fn test_deletion_facade_passes_expected_version_to_product_strategy() {
+    let mut product = Product::new("p1".to_string(), "Product".to_string(), "DEL-3".to_string(), ProductStatus::Active).unwrap();
+    product.update_status(ProductStatus::Discontinued).unwrap();
+    let version = product.version;
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let facade = DeletionFacade::new(Arc::new(InMemoryItemRepository::new())).with_product_repository(repo.clone());
+
+    for kind in [DeleteKind::Logical, DeleteKind::Physical, DeleteKind::Restore] {
+        let result = facade.delete_product("p1".to_string(), kind, Some(version + 1)).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", kind);
+    }
+    assert!(repo.contexts.lock().unwrap().is_empty());
+
+    facade.delete_product("p1".to_string(), DeleteKind::Restore, Some(version)).await.unwrap();
+    assert_eq!(repo.contexts.lock().unwrap()[0].reason.as_deref(), Some("restore"));
+}
+
+fn product_handler(repo: Arc<MockProductRepository>) -> actix_web::web::Data<ProductHandler> {
+    actix_web::web::Data::new(ProductHandler::new(Arc::new(ProductService::new(repo.clone())), Arc::new(ProductImportService::new(repo.clone(), 100, 1000)), Arc::new(ProductExportService::new(repo, 100))))
+}
+
+/// server.rs と同じルート表を登録する
+fn api_routes(cfg: &mut actix_web::web::ServiceConfig) {
+    configure_api_routes(cfg, &BodyLimitConfig { default_bytes: 256 * 1024, batch_bytes: 1024 * 1024, import_bytes: 10 * 1024 * 1024 });
+}
+
+#[actix_web::test]
+async fn test_delete_route_rejects_stale_if_match() {
+    let product = Product::new("prod_001".to_string(), "Product".to_string(), "DEL-2".to_string(), ProductStatus::Active).unwrap();
+    let version = product.version;
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let app = actix_web::test::init_service(actix_web::App::new().app_data(product_handler(repo)).configure(api_routes)).await;
+
+    let stale = actix_web::test::TestRequest::delete().uri("/api/products/prod_001").insert_header(("If-Match", format!("\"{}\"", version + 1))).to_request();
+    let resp = actix_web::test::call_service(&app, stale).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::PRECONDITION_FAILED);
+
+    let current = actix_web::test::TestRequest::delete().uri("/api/products/prod_001").insert_header(("If-Match", format!("\"{}\"", version))).to_request();
+    let resp = actix_web::test::call_service(&app, current).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::NO_CONTENT);
+}
+
+#[actix_web::test]
+async fn test_logical_delete_route_warns_about_bundles() {
+    let product = Product::new("pen".to_string(), "Pen".to_string(), "PEN-1".to_string(), ProductStatus::Active).unwrap();
+    let bundle = ProductBundle::new("kit".to_string(), vec![BundleComponent { product_id: "pen".to_string(), quantity: 2 }], BundlePricing::Fixed).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: Some(bundle), schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let app = actix_web::test::init_service(actix_web::App::new().app_data(product_handler(repo)).configure(api_routes)).await;
+
+    let req = actix_web::test::TestRequest::delete().uri("/api/products/pen?logical=true").to_request();
+    let body: serde_json::Value = actix_web::test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["used_in_bundles"], serde_json::json!(["kit"]));
+    assert_eq!(body["warnings"].as_array().unwrap().len(), 1);
+
+    let req = actix_web::test::TestRequest::delete().uri("/api/products/pen").to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_run_due_schedules_applies_once() {
+    let inactive = Product::new(
+        "p3".to_string(),
+        "Seasonal Product".to_string(),
+        "SEASON-1".to_string(),
+        ProductStatus::Active,
+    ).unwrap();
+    let mut schedule = ProductStatusSchedule::new(
+        "s1".to_string(),
+        "p3".to_string(),
+        ProductStatus::Inactive,
+        chrono::Utc::now() + chrono::Duration::minutes(5),
+        Some("campaign end".to_string()),
+        Some("planner".to_string()),
+    ).unwrap();
+    schedule.scheduled_at = chrono::Utc::now() - chrono::Duration::minutes(1);
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(inactive), options: vec![], variants: vec![], bundle: None, schedules: std::sync::Mutex::new(vec![schedule]), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+
+    let processed = service.run_due_schedules(chrono::Utc::now(), 10, 300).await.unwrap();
+    assert_eq!(processed, 1);
+    assert_eq!(repo.schedules.lock().unwrap()[0].state, ScheduleState::Applied);
+    {
+        let contexts = repo.contexts.lock().unwrap();
+        assert_eq!(contexts[0].changed_by.as_deref(), Some("planner"));
+        assert_eq!(contexts[0].reason.as_deref(), Some("schedule s1: campaign end"));
+    }
+
+    // A second run finds nothing left to apply
+    let processed = service.run_due_schedules(chrono::Utc::now(), 10, 300).await.unwrap();
+    assert_eq!(processed, 0);
+}
+
+#[tokio::test]
+async fn test_run_due_schedules_continues_after_record_failure() {
+    let product = Product::new("p3".to_string(), "Seasonal Product".to_string(), "SEASON-1".to_string(), ProductStatus::Active).unwrap();
+    let due = |id: &str| {
+        let mut schedule = ProductStatusSchedule::new(id.to_string(), "p3".to_string(), ProductStatus::Inactive, chrono::Utc::now() + chrono::Duration::minutes(5), None, None).unwrap();
+        schedule.scheduled_at = chrono::Utc::now() - chrono::Duration::minutes(1);
+        schedule
+    };
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: std::sync::Mutex::new(vec![due("broken-1"), due("s2")]), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+
+    // The first schedule's result cannot be recorded, but the second is still applied
+    let processed = service.run_due_schedules(chrono::Utc::now(), 10, 300).await.unwrap();
+    assert_eq!(processed, 2);
+    let schedules = repo.schedules.lock().unwrap();
+    assert_eq!(schedules[0].state, ScheduleState::Processing);
+    assert_eq!(schedules[1].state, ScheduleState::Applied);
+}
+
+fn history_entry(id: i64, field: &str, old_value: Option<&str>, new_value: Option<&str>) -> ProductHistory {
+    ProductHistory {
+        id,
+        product_id: "p4".to_string(),
+        field_name: field.to_string(),
+        old_value: old_value.map(str::to_string),
+        new_value: new_value.map(str::to_string),
+        changed_by: Some("editor".to_string()),
+        reason: None,
+        changed_at: chrono::Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn test_rollback_restores_fields_and_respects_sku_uniqueness() {
+    let current = Product::new("p4".to_string(), "New Name".to_string(), "SKU-NEW".to_string(), ProductStatus::Draft).unwrap();
+    let history = vec![
+        history_entry(3, "sku", Some("SKU-OLD"), Some("SKU-NEW")),
+        history_entry(2, "name", Some("Old Name"), Some("New Name")),
+        history_entry(1, "name", None, Some("Old Name")),
+    ];
+    let target = || RollbackRequest { history_id: Some(1), at: None };
+
+    // The old SKU has been reused by another product since
+    let repo = Arc::new(MockProductRepository { exists: true, created: Some(current.clone()), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: history.clone() });
+    let service = ProductService::new(repo.clone());
+    let preview = service.preview_rollback("p4", target()).await.unwrap();
+    assert_eq!(preview.changes.len(), 2);
+    assert!(preview.changes.iter().any(|c| c.field == "name" && c.restored_value.as_deref() == Some("Old Name")));
+    assert_eq!(preview.conflicts, vec!["sku".to_string()]);
+    let result = service.rollback("p4", target(), &ChangeContext::default()).await;
+    assert!(matches!(result, Err(ProductError::SkuAlreadyExists)));
+    assert!(repo.contexts.lock().unwrap().is_empty());
+
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(current), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history });
+    let service = ProductService::new(repo.clone());
+    service.rollback("p4", target(), &ChangeContext::new(Some("admin".to_string()), None)).await.unwrap();
+    let invalid = service.preview_rollback("p4", RollbackRequest { history_id: None, at: None }).await;
+    assert!(matches!(invalid, Err(ProductError::InvalidRollbackTarget(_))));
+
+    let contexts = repo.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(contexts[0].reason.as_deref(), Some("rollback to history 1"));
+}
+
+#[tokio::test]
+async fn test_patch_rejects_stale_version() {
+    let mut product = Product::new("p1".to_string(), "Widget".to_string(), "SKU-1".to_string(), ProductStatus::Draft).unwrap();
+    product.version = 5;
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let patch = || PatchProductRequest { name: Some("Renamed".to_string()), description: None, status: None, category_id: None, price: None, inventory: None };
+    let err = service.patch("p1", patch(), Some(4), &ChangeContext::default()).await.unwrap_err();
+    assert_eq!(err, ProductError::VersionMismatch { expected: 4, actual: 5 });
+    // The stale write never reaches the repository
+    assert!(repo.contexts.lock().unwrap().is_empty());
+    let patched = service.patch("p1", patch(), Some(5), &ChangeContext::default()).await.unwrap();
+    assert_eq!(patched.version, 5);
+    assert_eq!(repo.contexts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_import_dry_run_reports_row_errors() {
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductImportService::new(repo.clone(), 2, 100);
+    let mut parser = service.parser(ImportFormat::Csv);
+    parser.feed(b"sku,name,selling_price,list_price,quantity,tags\nNB-1,Notebook,500,600,10,paper|a4\n").unwrap();
+    parser.feed(b"NB-2,Pen,900,600,1,\nNB-1,Dup,1,,,\nNB-3,,100,,,\nbad sku,X,1,,x,\n").unwrap();
+    let report = service.dry_run(parser.finish().unwrap()).await;
+    assert_eq!(report.total_rows, 5);
+    assert_eq!(report.create_count, 1);
+    assert_eq!(report.update_count, 0);
+    let lines: Vec<usize> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![3, 4, 5, 6, 6]);
+    assert_eq!(report.errors[0].field.as_deref(), Some("price"));
+    assert_eq!(report.errors[2].field.as_deref(), Some("name"));
+    // Nothing is written during a dry run
+    assert!(repo.contexts.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_import_job_rolls_back_failed_chunk() {
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductImportService::new(repo, 2, 100);
+    let mut parser = service.parser(ImportFormat::Tsv);
+    parser.feed(b"sku\tname\tselling_price\nA-1\tOne\t100\nA-2\tTwo\t100\nFAIL-3\tThree\t100\nA-4\tFour\t100\nA-5\tFive\t100\n").unwrap();
+    let job = service.start(parser.finish().unwrap(), ChangeContext::new(Some("importer".to_string()), None));
+    assert_eq!(job.total_rows, 5);
+    assert_eq!(job.created_by.as_deref(), Some("importer"));
+
+    let mut job = service.get_job(&job.id).unwrap();
+    for _ in 0..100 {
+        if job.state == ImportJobState::Completed { break; }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        job = service.get_job(&job.id).unwrap();
+    }
+    assert_eq!(job.state, ImportJobState::Completed);
+    assert_eq!(job.processed_rows, 5);
+    assert_eq!(job.created_count, 3);
+    // The second chunk (FAIL-3, A-4) is rolled back as a whole
+    assert_eq!(job.failed_count, 2);
+    let lines: Vec<usize> = job.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![4, 5]);
+    assert!(job.errors[1].message.contains("line 4"));
+    assert!(matches!(service.get_job("missing"), Err(ProductError::ImportJobNotFound)));
+}
+
+#[tokio::test]
+async fn test_export_streams_catalog_in_pages() {
+    use futures::StreamExt;
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductExportService::new(repo, 2);
+
+    let export = service.export(ProductExportQuery { columns: Some("name,sku".to_string()), ..Default::default() }).unwrap();
+    assert_eq!(export.format, ExportFormat::Csv);
+    let chunks: Vec<String> = export.body.map(|chunk| chunk.unwrap()).collect().await;
+
+    // header + 3 pages (2, 2, 1 rows)
+    assert_eq!(chunks.len(), 4);
+    assert_eq!(chunks[0], "sku,name\n");
+    assert_eq!(chunks[3], "EXP-5,Product EXP-5\n");
+    assert_eq!(chunks.concat().lines().count(), 6);
+
+    let invalid = service.export(ProductExportQuery { columns: Some("sku,cost".to_string()), ..Default::default() });
+    assert!(matches!(invalid, Err(ProductError::InvalidExport(_))));
+}
+
+#[tokio::test]
+async fn test_bulk_update_preview_and_run() {
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let request = |expected_count| BulkUpdateRequest { filter: ProductFilterRequest { brand: Some("Acme".to_string()), ..Default::default() }, patch: ProductBulkPatch { status: Some(ProductStatus::Inactive), ..Default::default() }, expected_count };
+
+    let preview = service.preview_bulk_update(request(None), Some(3)).await.unwrap();
+    assert_eq!(preview.matched_count, 5);
+    assert_eq!(preview.sample.len(), 3);
+    assert_eq!(preview.sample[0].changes[0].field, "status");
+    assert_eq!(preview.sample[0].changes[0].new_value.as_deref(), Some("Inactive"));
+    assert!(preview.sample[2].error.is_some());
+    assert!(repo.contexts.lock().unwrap().is_empty());
+
+    let mismatch = service.bulk_update(request(Some(4)), &ChangeContext::default()).await;
+    assert!(matches!(mismatch, Err(ProductError::BulkCountMismatch { expected: 4, actual: 5 })));
+
+    let ctx = ChangeContext { changed_by: Some("merch".to_string()), reason: None };
+    let result = service.bulk_update(request(Some(5)), &ctx).await.unwrap();
+    assert_eq!((result.matched_count, result.updated_count, result.unchanged_count, result.failed_count), (5, 3, 1, 1));
+    assert_eq!(result.failures[0].id, "EXP-3");
+
+    let contexts = repo.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 3);
+    assert_eq!(contexts[0].reason, Some(format!("bulk update {}", result.bulk_id)));
+    assert_eq!(contexts[0].changed_by.as_deref(), Some("merch"));
+}
+
+#[tokio::test]
+async fn test_bulk_update_reports_products_changed_after_matching_as_conflicts() {
+    // EXP-2 was updated by someone else after the bulk update fixed its targets
+    let mut changed = catalog(None, 100).remove(1);
+    changed.version += 1;
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(changed), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo.clone());
+    let request = BulkUpdateRequest { filter: ProductFilterRequest::default(), patch: ProductBulkPatch { brand: Some("Acme".to_string()), ..Default::default() }, expected_count: Some(5) };
+
+    let result = service.bulk_update(request, &ChangeContext::default()).await.unwrap();
+    assert_eq!((result.matched_count, result.updated_count, result.conflicted_count), (5, 4, 1));
+    assert_eq!(result.conflicts[0].id, "EXP-2");
+    assert!(result.conflicts[0].error.starts_with("Version mismatch"));
+    assert_eq!(repo.contexts.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_slug_resolver_redirects_old_paths_to_canonical_path() {
+    let audio = Category::new("cat-audio".to_string(), "Audio".to_string(), None, None, 0);
+    let mut product = Product::new("p-1".to_string(), "Wireless Headphones".to_string(), "WH-001".to_string(), ProductStatus::Active).unwrap();
+    product.category_id = Some(audio.id.clone());
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+
+    let mut categories = MockCategoryRepository::new();
+    let current = audio.clone();
+    categories.expect_find_by_slug().returning(move |parent, slug| (parent.is_none() && slug == "audio").then(|| current.clone()));
+    // "sound" was the category's slug before it was renamed
+    let renamed = audio.clone();
+    categories.expect_find_by_old_slug().returning(move |parent, slug| (parent.is_none() && slug == "sound").then(|| CategoryRedirect { category: renamed.clone(), merged_from: None }));
+    let ancestors = vec![audio.clone()];
+    categories.expect_find_ancestors().returning(move |_| Ok(ancestors.clone()));
+
+    let service = SlugService::new(Arc::new(categories), Arc::new(ProductService::new(repo)));
+
+    let category = service.resolve("audio").await.unwrap();
+    assert_eq!(category.resource_type, SlugResourceType::Category);
+    assert_eq!(category.canonical_path, "/audio");
+    assert!(!category.redirect);
+
+    let product = service.resolve("/audio/wireless-headphones").await.unwrap();
+    assert_eq!(product.resource_type, SlugResourceType::Product);
+    assert_eq!(product.canonical_path, "/audio/wireless-headphones");
+    assert!(!product.redirect);
+    assert_eq!(product.breadcrumb.len(), 1);
+    assert_eq!(product.product.unwrap().id, "p-1");
+
+    let old = service.resolve("/sound/wireless-headphones").await.unwrap();
+    assert_eq!(old.canonical_path, "/audio/wireless-headphones");
+    assert!(old.redirect);
+
+    assert!(matches!(service.resolve("/audio/missing").await, Err(AppError::NotFound(_))));
+    assert!(matches!(service.resolve("/missing/wireless-headphones").await, Err(AppError::NotFound(_))));
+    assert!(matches!(service.resolve("/").await, Err(AppError::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn test_slug_resolver_reaches_children_renamed_by_a_merge() {
+    // "books" was merged into "manga"; its "comics" child clashed with manga's own and became "comics-2"
+    let manga = Category::new("cat-manga".to_string(), "Manga".to_string(), None, None, 0);
+    let comics = Category::new("cat-comics".to_string(), "Comics".to_string(), None, Some(manga.id.clone()), 0);
+    let mut merged = Category::new("cat-books-comics".to_string(), "Comic Strips".to_string(), None, Some(manga.id.clone()), 1);
+    merged.update_slug("comics-2".to_string()).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: None, options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+
+    let mut categories = MockCategoryRepository::new();
+    let live = [manga.clone(), comics.clone(), merged.clone()];
+    categories.expect_find_by_slug().returning(move |parent, slug| live.iter().find(|c| c.parent_id == parent && c.slug == slug).cloned());
+    let (target, renamed) = (manga.clone(), merged.clone());
+    categories.expect_find_by_old_slug().returning(move |parent, slug| match (parent.as_deref(), slug) {
+        (None, "books") => Some(CategoryRedirect { category: target.clone(), merged_from: Some("cat-books".to_string()) }),
+        (Some("cat-books"), "comics") => Some(CategoryRedirect { category: renamed.clone(), merged_from: None }),
+        _ => None,
+    });
+    let (root, children) = (manga.clone(), vec![comics.clone(), merged.clone()]);
+    categories.expect_find_ancestors().returning(move |id| Ok(std::iter::once(root.clone()).chain(children.iter().filter(|c| c.id == id).cloned()).collect()));
+
+    let service = SlugService::new(Arc::new(categories), Arc::new(ProductService::new(repo)));
+
+    let old = service.resolve("/books/comics").await.unwrap();
+    assert_eq!(old.canonical_path, "/manga/comics-2");
+    assert!(old.redirect);
+    assert_eq!(old.category.unwrap().id, "cat-books-comics");
+
+    // The target's own child keeps its path
+    let own = service.resolve("/manga/comics").await.unwrap();
+    assert_eq!(own.canonical_path, "/manga/comics");
+    assert!(!own.redirect);
+    assert_eq!(own.category.unwrap().id, "cat-comics");
+
+    let target = service.resolve("/books").await.unwrap();
+    assert_eq!(target.canonical_path, "/manga");
+    assert!(target.redirect);
+}
+
+#[tokio::test]
+async fn test_create_product_validates_category_attributes() {
+    let product = Product::new("dummy_id".to_string(), "Monitor".to_string(), "MON-001".to_string(), ProductStatus::Draft).unwrap();
+    let repo = Arc::new(MockProductRepository { exists: false, created: Some(product), options: vec![], variants: vec![], bundle: None, schedules: Default::default(), contexts: Default::default(), prices: Default::default(), history: vec![] });
+    let service = ProductService::new(repo);
+    let request = |attributes: &[(&str, &str)]| CreateProductRequest {
+        name: "Monitor".to_string(),
+        description: None,
+        sku: "MON-001".to_string(),
+        brand: None,
+        status: ProductStatus::Draft,
+        price: PriceRequest { selling_price: Decimal::new(30000, 0), list_price: None, discount_price: None, currency: "JPY".to_string(), tax_included: true, effective_from: None, effective_until: None },
+        inventory: InventoryRequest { quantity: 1, reserved_quantity: None, alert_threshold: None, track_inventory: None, allow_backorder: None },
+        category_id: Some("cat_displays".to_string()),
+        tags: None,
+        attributes: Some(attributes.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()),
+        dimensions: None,
+        weight: None,
+        shipping_info: None,
+        slug: None,
+    };
+    let ctx = ChangeContext::default();
+
+    // Inherited and own definitions both apply; names and enum values are case-insensitive
+    assert!(service.create(request(&[("Color", "black"), ("screen_size", "27 inch")]), &ctx).await.is_ok());
+
+    match service.create(request(&[("colour", "black"), ("screen_size", "large")]), &ctx).await {
+        Err(ProductError::InvalidAttributes(violations)) => {
+            let names: Vec<&str> = violations.iter().map(|v| v.attribute.as_str()).collect();
+            assert_eq!(names, vec!["colour", "screen_size", "color"]);
+        }
+        other => panic!("expected InvalidAttributes, got {:?}", other.map(|p| p.id)),
+    }
+
+    // A schema that cannot be loaded fails the request instead of skipping validation
+    let mut unavailable = request(&[("anything", "goes")]);
+    unavailable.category_id = Some("cat_unavailable".to_string());
+    assert!(matches!(service.create(unavailable, &ctx).await, Err(ProductError::DatabaseError(_))));
+
+    // Typed search filters are checked against the category schema
+    let search = |attributes: &str| ProductSearchQuery { q: None, category_id: Some("cat_displays".to_string()), status: None, tags: None, min_price: None, max_price: None, in_stock_only: None, attributes: Some(attributes.to_string()), limit: None, offset: None };
+    assert!(service.search(search("COLOR:white,screen_size>=24")).await.is_ok());
+    assert!(matches!(service.search(search("color>=1")).await, Err(ProductError::InvalidFilter(_))));
+    assert!(matches!(service.search(search("screen_size:big")).await, Err(ProductError::InvalidFilter(_))));
+}
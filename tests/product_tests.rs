@@ -124,6 +124,7 @@ mod product_tests {
                 free_shipping: false,
                 shipping_fee: Decimal::new(500, 2),
             }),
+            slug: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let de: CreateProductRequest = serde_json::from_str(&json).unwrap();
@@ -138,6 +139,7 @@ mod product_tests {
         let domain_product = Product {
             id: "prod01".to_string(),
             name: "Test Product".to_string(),
+            slug: "test-product".to_string(),
             description: Some("desc".to_string()),
             sku: "SKU-001".to_string(),
             brand: Some("BrandX".to_string()),
@@ -152,12 +154,14 @@ mod product_tests {
             },
             created_at: now,
             updated_at: now,
+            version: 4,
         };
         let dto: ProductResponse = domain_product.into();
         assert_eq!(dto.id, "prod01");
         assert_eq!(dto.name, "Test Product");
         assert_eq!(dto.sku, "SKU-001");
         assert_eq!(dto.status, ProductStatus::Active);
+        assert_eq!(dto.version, 4);
         assert_eq!(dto.shipping_info.shipping_class, "standard");
     }
 
@@ -0,0 +1,348 @@
+//! Webhook 購読の管理 API と配信をローカルの HTTP スタンドインに対して検証するテスト
+//!
+//! outbox ディスパッチャーから購読ごとに署名付きで配信され、配信記録・手動再送・
+//! 購読ごとの自動再試行（同じ集約のイベントは順序どおり）・連続失敗による自動無効化が
+//! 管理 API から確認できることを検証する。
+
+use actix_web::{test, web, App};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use rust_webapi::app_domain::model::domain_event::DomainEvent;
+use rust_webapi::application::dto::webhook_dto::CreateWebhookRequest;
+use rust_webapi::application::service::outbox_dispatcher::{EventSink, OutboxDispatcher};
+use rust_webapi::application::service::webhook_service::WebhookService;
+use rust_webapi::infrastructure::config::OutboxConfig;
+use rust_webapi::infrastructure::repository::outbox_repository::InMemoryOutboxRepository;
+use rust_webapi::infrastructure::repository::webhook_repository::InMemoryWebhookRepository;
+use rust_webapi::infrastructure::webhook::{signature, WebhookClient};
+use rust_webapi::presentation::api::webhook_handler::{configure_webhook_routes, WebhookHandler};
+
+const MAX_FAILURES: i32 = 3;
+
+/// 受け取ったリクエストのヘッダー行とボディ
+type Recorded = Arc<Mutex<Vec<(Vec<String>, String)>>>;
+
+/// 受け取ったリクエストを記録し、設定したステータスで応答する HTTP サーバー
+struct StandIn {
+    url: String,
+    status: Arc<AtomicU16>,
+    requests: Recorded,
+}
+
+impl StandIn {
+    async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let status = Arc::new(AtomicU16::new(200));
+        let requests: Recorded = Arc::new(Mutex::new(Vec::new()));
+
+        let (status_ref, requests_ref) = (status.clone(), requests.clone());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut data = Vec::new();
+                let mut buf = [0u8; 4096];
+                let (head, body) = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    data.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&data).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length || n == 0 {
+                            break (head.to_string(), body.to_string());
+                        }
+                    }
+                };
+                let headers = head.lines().map(|line| line.to_ascii_lowercase()).collect();
+                requests_ref.lock().unwrap().push((headers, body));
+
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status_ref.load(Ordering::SeqCst)
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        Self {
+            url,
+            status,
+            requests,
+        }
+    }
+
+    fn respond_with(&self, status: u16) {
+        self.status.store(status, Ordering::SeqCst);
+    }
+
+    fn header(&self, index: usize, name: &str) -> Option<String> {
+        let requests = self.requests.lock().unwrap();
+        requests[index]
+            .0
+            .iter()
+            .find_map(|line| line.strip_prefix(&format!("{}: ", name)))
+            .map(str::to_string)
+    }
+
+    fn count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+fn outbox_config() -> OutboxConfig {
+    OutboxConfig {
+        enabled: true,
+        interval_seconds: 1,
+        batch_size: 10,
+        claim_timeout_seconds: 60,
+        max_backoff_seconds: 1,
+        retention_hours: 1,
+        log_events: false,
+        webhook_url: None,
+        webhook_timeout_seconds: 1,
+    }
+}
+
+#[actix_web::test]
+async fn test_subscription_lifecycle_against_stand_in() {
+    let stand_in = StandIn::start().await;
+    let webhooks = Arc::new(WebhookService::new(
+        Arc::new(InMemoryWebhookRepository::new()),
+        WebhookClient::new(Duration::from_secs(5)).allow_private_targets(true),
+        MAX_FAILURES,
+        1,
+    ));
+    let outbox = Arc::new(InMemoryOutboxRepository::new());
+    let sinks: Vec<Arc<dyn EventSink>> = vec![webhooks.clone()];
+    let dispatcher = OutboxDispatcher::new(outbox.clone(), sinks, outbox_config());
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(WebhookHandler::new(webhooks.clone())))
+            .service(web::scope("/api").configure(configure_webhook_routes)),
+    )
+    .await;
+
+    // 登録（ItemDeleted のみ購読）
+    let req = test::TestRequest::post()
+        .uri("/api/admin/webhooks")
+        .set_json(json!({
+            "url": stand_in.url,
+            "event_types": ["ItemDeleted"],
+            "secret": "s3cret",
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: Value = test::read_body_json(resp).await;
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["secret"], "s3cret");
+
+    // 購読していない種別は送らない
+    outbox.append(DomainEvent::product_deleted("p1"));
+    let event_id = outbox.append(DomainEvent::item_deleted(1, false));
+    assert_eq!(dispatcher.run_once().await, 2);
+    assert_eq!(stand_in.count(), 1);
+    assert_eq!(stand_in.header(0, "x-event-id"), Some(event_id.to_string()));
+    let body = stand_in.requests.lock().unwrap()[0].1.clone();
+    assert_eq!(
+        stand_in.header(0, "x-webhook-signature"),
+        Some(signature("s3cret", body.as_bytes()))
+    );
+
+    // 失敗した配信は再試行の日時とともに記録され、手動で再送できる
+    stand_in.respond_with(500);
+    outbox.append(DomainEvent::item_deleted(2, true));
+    assert_eq!(dispatcher.run_once().await, 1);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/admin/webhooks/{}/deliveries", id))
+        .to_request();
+    let deliveries: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(deliveries.as_array().unwrap().len(), 2);
+    let failed = &deliveries[0];
+    assert_eq!(failed["succeeded"], false);
+    assert_eq!(failed["status_code"], 500);
+    assert_eq!(failed["attempts"], 1);
+    assert!(failed["latency_ms"].is_number());
+    assert!(failed["last_error"].as_str().unwrap().contains("500"));
+    assert!(failed["next_attempt_at"].is_string());
+    assert_eq!(deliveries[1]["succeeded"], true);
+    assert!(deliveries[1]["next_attempt_at"].is_null());
+
+    stand_in.respond_with(200);
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/admin/webhooks/{}/deliveries/{}/redeliver",
+            id, failed["id"]
+        ))
+        .to_request();
+    let redelivered: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(redelivered["succeeded"], true);
+    assert_eq!(redelivered["attempts"], 2);
+    assert_eq!(redelivered["status_code"], 200);
+    assert!(redelivered["next_attempt_at"].is_null());
+
+    // 連続して失敗すると自動的に無効化される
+    stand_in.respond_with(503);
+    for item_id in 10..10 + MAX_FAILURES as u64 {
+        outbox.append(DomainEvent::item_deleted(item_id, false));
+    }
+    for _ in 0..MAX_FAILURES {
+        dispatcher.run_once().await;
+    }
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/admin/webhooks/{}", id))
+        .to_request();
+    let subscription: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(subscription["is_active"], false);
+    assert_eq!(subscription["consecutive_failures"], MAX_FAILURES);
+    assert!(subscription["secret"].is_null());
+
+    // 無効化後は失敗した配信を再試行しない
+    let sent = stand_in.count();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(webhooks.retry_due().await, 0);
+    assert_eq!(stand_in.count(), sent);
+    assert!(outbox.pending_ids().is_empty());
+
+    // 再び有効化すると連続失敗回数がリセットされる
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/webhooks/{}", id))
+        .set_json(json!({ "is_active": true }))
+        .to_request();
+    let subscription: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(subscription["is_active"], true);
+    assert_eq!(subscription["consecutive_failures"], 0);
+    assert!(subscription["disabled_at"].is_null());
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/admin/webhooks/{}", id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/admin/webhooks/{}", id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_failing_subscription_is_retried_in_order_without_blocking_others() {
+    let failing = StandIn::start().await;
+    let healthy = StandIn::start().await;
+    let webhooks = Arc::new(WebhookService::new(
+        Arc::new(InMemoryWebhookRepository::new()),
+        WebhookClient::new(Duration::from_secs(5)).allow_private_targets(true),
+        MAX_FAILURES,
+        1,
+    ));
+    let outbox = Arc::new(InMemoryOutboxRepository::new());
+    let sinks: Vec<Arc<dyn EventSink>> = vec![webhooks.clone()];
+    let dispatcher = OutboxDispatcher::new(outbox.clone(), sinks, outbox_config());
+
+    for url in [&failing.url, &healthy.url] {
+        webhooks
+            .create(CreateWebhookRequest {
+                url: url.clone(),
+                event_types: None,
+                secret: Some("s3cret".to_string()),
+                is_active: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    // 失敗する購読があっても outbox の配信は進む。失敗した購読への同じ集約の後続イベントは
+    // 先に失敗したイベントを追い越さないよう送らずに待たせる
+    failing.respond_with(500);
+    outbox.append(DomainEvent::item_deleted(1, false));
+    outbox.append(DomainEvent::item_deleted(1, true));
+    while dispatcher.run_once().await > 0 {}
+    assert!(outbox.pending_ids().is_empty());
+    assert_eq!(healthy.count(), 2);
+    assert_eq!(failing.count(), 1);
+
+    // 再試行の時刻までは送らない
+    assert_eq!(webhooks.retry_due().await, 0);
+    assert_eq!(failing.count(), 1);
+
+    // 失敗した購読にだけ、先のイベントから順に再送される
+    failing.respond_with(200);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(webhooks.retry_due().await, 1);
+    assert_eq!(webhooks.retry_due().await, 1);
+    assert_eq!(webhooks.retry_due().await, 0);
+    assert_eq!(healthy.count(), 2);
+    let event_ids: Vec<_> = (0..failing.count())
+        .map(|index| failing.header(index, "x-event-id").unwrap())
+        .collect();
+    assert_eq!(event_ids, ["1", "1", "2"]);
+}
+
+#[actix_web::test]
+async fn test_invalid_subscription_is_rejected() {
+    let webhooks = Arc::new(WebhookService::new(
+        Arc::new(InMemoryWebhookRepository::new()),
+        WebhookClient::new(Duration::from_secs(1)),
+        MAX_FAILURES,
+        1,
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(WebhookHandler::new(webhooks)))
+            .service(web::scope("/api").configure(configure_webhook_routes)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/admin/webhooks")
+        .set_json(json!({ "url": "not-a-url" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri("/api/admin/webhooks")
+        .set_json(json!({ "url": "https://example.com", "event_types": ["OrderPlaced"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_private_target_is_rejected_unless_allowed() {
+    let webhooks = Arc::new(WebhookService::new(
+        Arc::new(InMemoryWebhookRepository::new()),
+        WebhookClient::new(Duration::from_secs(1)),
+        MAX_FAILURES,
+        1,
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(WebhookHandler::new(webhooks)))
+            .service(web::scope("/api").configure(configure_webhook_routes)),
+    )
+    .await;
+
+    for url in [
+        "http://127.0.0.1:8080/hooks",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[::1]/hooks",
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/admin/webhooks")
+            .set_json(json!({ "url": url }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400, "{}", url);
+    }
+}